@@ -3,16 +3,28 @@ use std::{
     io::{Stdout, Write, stdout},
 };
 
+use base64::{Engine, engine::general_purpose::STANDARD};
 use crossterm::{
     Command,
-    cursor::{DisableBlinking, EnableBlinking, SetCursorStyle},
-    event::{DisableMouseCapture, EnableMouseCapture},
+    cursor::{DisableBlinking, EnableBlinking, RestorePosition, SavePosition, SetCursorStyle},
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture,
+    },
     execute, queue,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use tui::{Terminal, backend::CrosstermBackend};
+use log::warn;
+use tui::{
+    Terminal,
+    backend::{Backend, CrosstermBackend},
+    buffer::Buffer,
+};
 
-use crate::handlers::config::{CursorType, FrontendConfig};
+use crate::{
+    handlers::config::{CursorType, FrontendConfig},
+    utils::links::DrawnLink,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ResetCursorShape;
@@ -30,10 +42,99 @@ impl Command for ResetCursorShape {
     }
 }
 
+/// OSC 52 escape sequence to put text on the clipboard, which works over SSH and in most terminals
+/// <https://invisible-island.net/xterm/ctlseqs/ctlseqs.html#h3-Operating-System-Commands/>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyToClipboard(pub String);
+
+impl Command for CopyToClipboard {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1B]52;c;{}\x07", STANDARD.encode(&self.0))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+/// OSC 8 escape sequence that makes the text printed after it a link, or ends the link without a URL
+/// <https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda/>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetHyperlink<'a>(pub Option<&'a str>);
+
+impl Command for SetHyperlink<'_> {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1B]8;;{}\x1B\\", self.0.unwrap_or_default())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+/// Draw the cells of links again as hyperlinks, for the ones that still show the text of their link.
+/// The terminal keeps them as hyperlinks until the cells are drawn over.
+pub fn draw_hyperlinks(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    buffer: &Buffer,
+    links: &[DrawnLink],
+) {
+    let shown = links
+        .iter()
+        .filter(|link| {
+            link.area.intersection(buffer.area) == link.area
+                && link
+                    .area
+                    .positions()
+                    .map(|p| buffer[p].symbol())
+                    .collect::<String>()
+                    == link.text
+        })
+        .collect::<Vec<&DrawnLink>>();
+
+    if shown.is_empty() {
+        return;
+    }
+
+    let backend = terminal.backend_mut();
+
+    let result = (|| -> std::io::Result<()> {
+        queue!(backend, SavePosition)?;
+
+        for link in shown {
+            queue!(backend, SetHyperlink(Some(&link.url)))?;
+            backend.draw(link.area.positions().map(|p| (p.x, p.y, &buffer[p])))?;
+            queue!(backend, SetHyperlink(None))?;
+        }
+
+        queue!(backend, RestorePosition)?;
+
+        Backend::flush(backend)
+    })();
+
+    if let Err(err) = result {
+        warn!("Unable to draw links as hyperlinks. {err}");
+    }
+}
+
+pub fn copy_to_clipboard(text: &str) {
+    if let Err(err) = execute!(stdout(), CopyToClipboard(text.to_string())) {
+        warn!("Unable to copy text to the clipboard. {err}");
+    }
+}
+
 pub fn reset_terminal() {
     disable_raw_mode().unwrap();
 
-    execute!(stdout(), LeaveAlternateScreen, ResetCursorShape).unwrap();
+    execute!(
+        stdout(),
+        LeaveAlternateScreen,
+        DisableBracketedPaste,
+        ResetCursorShape
+    )
+    .unwrap();
 }
 
 pub fn init_terminal(frontend_config: &FrontendConfig) -> Terminal<CrosstermBackend<Stdout>> {
@@ -51,11 +152,19 @@ pub fn init_terminal(frontend_config: &FrontendConfig) -> Terminal<CrosstermBack
     queue!(
         stdout,
         EnterAlternateScreen,
-        EnableMouseCapture,
-        cursor_style,
+        EnableBracketedPaste,
+        cursor_style
     )
     .unwrap();
 
+    if frontend_config.mouse {
+        queue!(stdout, EnableMouseCapture).unwrap();
+    }
+
+    if frontend_config.unread_marker {
+        queue!(stdout, EnableFocusChange).unwrap();
+    }
+
     if frontend_config.blinking_cursor {
         queue!(stdout, EnableBlinking).unwrap();
     } else {
@@ -76,6 +185,8 @@ pub fn quit_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) {
         terminal.backend_mut(),
         LeaveAlternateScreen,
         DisableMouseCapture,
+        DisableFocusChange,
+        DisableBracketedPaste,
     )
     .unwrap();
 
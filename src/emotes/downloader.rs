@@ -1,15 +1,27 @@
-use std::{borrow::BorrowMut, collections::HashMap, path::Path};
+use std::{
+    borrow::BorrowMut,
+    collections::{BTreeSet, HashMap},
+    path::Path,
+};
 
 use color_eyre::Result;
 use futures::StreamExt;
+use log::warn;
 use reqwest::{Client, Response};
 use tokio::io::AsyncWriteExt;
 
 use crate::{
-    emotes::DownloadedEmotes,
-    handlers::config::{CompleteConfig, FrontendConfig},
-    twitch::oauth::{get_channel_id, get_twitch_client, get_twitch_client_id},
-    utils::pathing::cache_path,
+    emotes::{DownloadedEmotes, EmoteSets},
+    handlers::config::{BadgeStyle, CompleteConfig, EmoteProvider},
+    twitch::{
+        budget::Priority,
+        helix::{ensure_requestable, track_request},
+        oauth::{get_channel_id, get_twitch_client, get_twitch_client_id},
+    },
+    utils::{
+        pathing::cache_path,
+        traffic::{record_download, send_tracked},
+    },
 };
 
 // HashMap of emote name, emote filename, emote url, and if the emote is an overlay
@@ -21,7 +33,13 @@ mod twitch {
     use reqwest::Client;
     use serde::Deserialize;
 
-    use crate::emotes::downloader::EmoteMap;
+    use crate::{
+        emotes::downloader::EmoteMap,
+        twitch::{
+            budget::{Priority, send_helix},
+            cache::{CacheKind, cached},
+        },
+    };
 
     #[derive(Deserialize, Debug)]
     struct Emote {
@@ -32,6 +50,23 @@ mod twitch {
         theme_mode: Vec<String>,
     }
 
+    #[derive(Deserialize, Debug, Clone)]
+    struct BadgeVersion {
+        id: String,
+        image_url_1x: String,
+    }
+
+    #[derive(Deserialize, Debug, Clone)]
+    struct BadgeSet {
+        set_id: String,
+        versions: Vec<BadgeVersion>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct BadgeList {
+        data: Vec<BadgeSet>,
+    }
+
     #[derive(Deserialize, Debug)]
     struct Cursor {
         cursor: Option<String>,
@@ -86,25 +121,30 @@ mod twitch {
 
     // Twitch will not send all the emotes in one response, we use the cursor they return to query further emotes.
     pub async fn get_user_emotes(client: &Client, user_id: &str) -> Result<EmoteMap> {
-        let mut user_emotes = client
-            .get(format!(
+        let mut user_emotes = send_helix(
+            "helix/chat/emotes/user",
+            Priority::Background,
+            client.get(format!(
                 "https://api.twitch.tv/helix/chat/emotes/user?user_id={user_id}",
-            ))
-            .send()
-            .await?
-            .error_for_status().inspect_err(|_| { warn!("Unable to get user emotes, please verify that the access token includes the user:read:emotes scope.");})?
-            .json::<EmoteList>()
-            .await?;
+            )),
+        )
+        .await?
+        .error_for_status().inspect_err(|_| { warn!("Unable to get user emotes, please verify that the access token includes the user:read:emotes scope.");})?
+        .json::<EmoteList>()
+        .await?;
 
         while let Some(c) = user_emotes.pagination.cursor {
-            let emotes =   client
-            .get(format!(
-                "https://api.twitch.tv/helix/chat/emotes/user?user_id={user_id}&after={c}",
-            ))
-            .send()
+            let emotes = send_helix(
+                "helix/chat/emotes/user",
+                Priority::Background,
+                client.get(format!(
+                    "https://api.twitch.tv/helix/chat/emotes/user?user_id={user_id}&after={c}",
+                )),
+            )
             .await?
             .error_for_status().inspect_err(|_| { warn!("Unable to get user emotes, please verify that the access token includes the user:read:emotes scope.");})?
-            .json::<EmoteList>().await?;
+            .json::<EmoteList>()
+            .await?;
 
             user_emotes.pagination = emotes.pagination;
             user_emotes.data.extend(emotes.data);
@@ -112,14 +152,76 @@ mod twitch {
 
         Ok(parse_emote_list(user_emotes))
     }
+
+    async fn get_badge_sets(
+        client: &Client,
+        endpoint: &'static str,
+        url: &str,
+    ) -> Result<Vec<BadgeSet>> {
+        let request = client.get(url);
+
+        cached(CacheKind::Badges, url, async move {
+            Ok(send_helix(endpoint, Priority::Background, request)
+                .await?
+                .error_for_status()?
+                .json::<BadgeList>()
+                .await?
+                .data)
+        })
+        .await
+    }
+
+    /// Badge images, keyed by their name and version as they appear in the `badges` tag.
+    pub async fn get_badges(client: &Client, channel_id: i32) -> Result<EmoteMap> {
+        let channel_url =
+            format!("https://api.twitch.tv/helix/chat/badges?broadcaster_id={channel_id}");
+
+        let (global_badges, channel_badges) = futures::join!(
+            get_badge_sets(
+                client,
+                "helix/chat/badges/global",
+                "https://api.twitch.tv/helix/chat/badges/global"
+            ),
+            get_badge_sets(client, "helix/chat/badges", &channel_url)
+        );
+
+        let channel_badges = channel_badges
+            .inspect_err(|err| warn!("Unable to get channel badges: {err}"))
+            .unwrap_or_default();
+
+        // Channel badges come last, so that custom subscriber badges replace the default ones
+        Ok(global_badges?
+            .into_iter()
+            .chain(channel_badges)
+            .flat_map(|BadgeSet { set_id, versions }| {
+                versions.into_iter().map(move |version| {
+                    // Badge image URLs end with `/{image id}/1`, which is unique across channels.
+                    let image_id = version
+                        .image_url_1x
+                        .rsplit('/')
+                        .nth(1)
+                        .unwrap_or(&version.id)
+                        .to_string();
+
+                    (
+                        format!("{set_id}/{}", version.id),
+                        (format!("badge_{image_id}"), version.image_url_1x, false),
+                    )
+                })
+            })
+            .collect())
+    }
 }
 
 mod betterttv {
+    use std::collections::BTreeSet;
+
     use color_eyre::Result;
-    use reqwest::Client;
+    use log::warn;
+    use reqwest::{Client, StatusCode};
     use serde::Deserialize;
 
-    use crate::emotes::downloader::EmoteMap;
+    use crate::{emotes::downloader::EmoteMap, utils::traffic::send_tracked};
 
     #[derive(Deserialize)]
     #[serde(rename_all = "camelCase")]
@@ -136,34 +238,62 @@ mod betterttv {
         shared_emotes: Vec<Emote>,
     }
 
-    pub async fn get_emotes(channel_id: i32) -> Result<EmoteMap> {
-        let client = Client::new();
+    /// Channels without a `BetterTTV` account have no channel emotes.
+    async fn get_channel_emotes(client: &Client, channel_id: i32) -> Result<Vec<Emote>> {
+        let response = send_tracked(
+            "betterttv/users",
+            client.get(format!(
+                "https://api.betterttv.net/3/cached/users/twitch/{channel_id}",
+            )),
+        )
+        .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(vec![]);
+        }
 
         let EmoteList {
             channel_emotes,
             shared_emotes,
-        } = client
-            .get(format!(
-                "https://api.betterttv.net/3/cached/users/twitch/{channel_id}",
-            ))
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<EmoteList>()
-            .await?;
+        } = response.error_for_status()?.json::<EmoteList>().await?;
 
-        let global_emotes = client
-            .get("https://api.betterttv.net/3/cached/emotes/global")
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<Vec<Emote>>()
-            .await?;
+        Ok(shared_emotes.into_iter().chain(channel_emotes).collect())
+    }
+
+    async fn get_global_emotes(client: &Client) -> Result<Vec<Emote>> {
+        Ok(send_tracked(
+            "betterttv/global",
+            client.get("https://api.betterttv.net/3/cached/emotes/global"),
+        )
+        .await?
+        .error_for_status()?
+        .json::<Vec<Emote>>()
+        .await?)
+    }
+
+    /// Returns the emotes, along with the names of the channel and shared emotes,
+    /// which take priority over Twitch emotes with the same name.
+    pub async fn get_emotes(channel_id: i32) -> Result<(EmoteMap, BTreeSet<String>)> {
+        let client = Client::new();
+
+        let (channel_emotes, global_emotes) = futures::join!(
+            get_channel_emotes(&client, channel_id),
+            get_global_emotes(&client)
+        );
+
+        let channel_emotes = channel_emotes
+            .inspect_err(|err| warn!("Unable to get BetterTTV channel emotes: {err}"))
+            .unwrap_or_default();
+        let global_emotes = global_emotes
+            .inspect_err(|err| warn!("Unable to get BetterTTV global emotes: {err}"))
+            .unwrap_or_default();
 
-        Ok(channel_emotes
+        let channel_names = channel_emotes.iter().map(|e| e.code.clone()).collect();
+
+        // Channel emotes come last, so that they replace global emotes with the same name
+        let emotes = global_emotes
             .into_iter()
-            .chain(shared_emotes)
-            .chain(global_emotes)
+            .chain(channel_emotes)
             .map(
                 |Emote {
                      code,
@@ -180,16 +310,23 @@ mod betterttv {
                     )
                 },
             )
-            .collect())
+            .collect();
+
+        Ok((emotes, channel_names))
     }
 }
 
 mod seventv {
     use color_eyre::Result;
-    use reqwest::Client;
+    use log::warn;
+    use reqwest::{Client, StatusCode};
     use serde::Deserialize;
 
-    use crate::emotes::downloader::EmoteMap;
+    use crate::{emotes::downloader::EmoteMap, utils::traffic::send_tracked};
+
+    /// Flag of an emote within an emote set, for emotes that are to be overlaid on the previous emote.
+    /// <https://github.com/SevenTV/API/blob/main/data/model/emote-set.model.go>
+    const ZERO_WIDTH_FLAG: u64 = 1 << 0;
 
     #[derive(Deserialize)]
     struct Emote {
@@ -205,41 +342,70 @@ mod seventv {
 
     #[derive(Deserialize)]
     struct EmoteSet {
-        emote_set: EmoteList,
+        /// Users that have a 7TV account do not always have an active emote set.
+        emote_set: Option<EmoteList>,
     }
 
-    pub async fn get_emotes(channel_id: i32) -> Result<EmoteMap> {
-        let client = Client::new();
+    /// Channels without a 7TV account, or without an active emote set, have no channel emotes.
+    async fn get_channel_emotes(client: &Client, channel_id: i32) -> Result<Vec<Emote>> {
+        let response = send_tracked(
+            "7tv/users",
+            client.get(format!("https://7tv.io/v3/users/twitch/{channel_id}",)),
+        )
+        .await?;
 
-        let channel_emotes = client
-            .get(format!("https://7tv.io/v3/users/twitch/{channel_id}",))
-            .send()
-            .await?
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(vec![]);
+        }
+
+        Ok(response
             .error_for_status()?
             .json::<EmoteSet>()
             .await?
             .emote_set
-            .emotes;
+            .map(|set| set.emotes)
+            .unwrap_or_default())
+    }
 
-        let global_emotes = client
-            .get("https://7tv.io/v3/emote-sets/global")
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<EmoteList>()
-            .await?
-            .emotes;
+    async fn get_global_emotes(client: &Client) -> Result<Vec<Emote>> {
+        Ok(send_tracked(
+            "7tv/global",
+            client.get("https://7tv.io/v3/emote-sets/global"),
+        )
+        .await?
+        .error_for_status()?
+        .json::<EmoteList>()
+        .await?
+        .emotes)
+    }
 
-        Ok(channel_emotes
+    pub async fn get_emotes(channel_id: i32) -> Result<EmoteMap> {
+        let client = Client::new();
+
+        // Global emotes should still be shown if the channel emotes could not be retrieved, and the other way around.
+        let (channel_emotes, global_emotes) = futures::join!(
+            get_channel_emotes(&client, channel_id),
+            get_global_emotes(&client)
+        );
+
+        let channel_emotes = channel_emotes
+            .inspect_err(|err| warn!("Unable to get 7TV channel emotes: {err}"))
+            .unwrap_or_default();
+        let global_emotes = global_emotes
+            .inspect_err(|err| warn!("Unable to get 7TV global emotes: {err}"))
+            .unwrap_or_default();
+
+        // Channel emotes come last, so that they replace global emotes with the same name
+        Ok(global_emotes
             .into_iter()
-            .chain(global_emotes)
+            .chain(channel_emotes)
             .map(|Emote { name, id, flags }| {
                 (
                     name,
                     (
                         format!("{id}.webp"),
                         format!("https://cdn.7tv.app/emote/{id}/1x.webp"),
-                        flags == 1,
+                        flags & ZERO_WIDTH_FLAG != 0,
                     ),
                 )
             })
@@ -250,10 +416,11 @@ mod seventv {
 mod frankerfacez {
     use color_eyre::Result;
     use futures::StreamExt;
-    use reqwest::Client;
+    use log::warn;
+    use reqwest::{Client, StatusCode};
     use serde::Deserialize;
 
-    use crate::emotes::downloader::EmoteMap;
+    use crate::{emotes::downloader::EmoteMap, utils::traffic::send_tracked};
 
     #[derive(Deserialize)]
     struct Emote {
@@ -287,42 +454,55 @@ mod frankerfacez {
         room: SetId,
     }
 
+    async fn get_room_set(client: &Client, channel_id: i32) -> Result<Option<u64>> {
+        let response = send_tracked(
+            "ffz/room",
+            client.get(format!(
+                "https://api.frankerfacez.com/v1/_room/id/{channel_id}",
+            )),
+        )
+        .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            response.error_for_status()?.json::<Room>().await?.room.set,
+        ))
+    }
+
     pub async fn get_emotes(channel_id: i32) -> Result<EmoteMap> {
         let client = &Client::new();
 
-        let mut sets = client
-            .get("https://api.frankerfacez.com/v1/_set/global")
-            .send()
+        let mut sets = send_tracked(
+            "ffz/global",
+            client.get("https://api.frankerfacez.com/v1/_set/global"),
+        )
+        .await?
+        .error_for_status()?
+        .json::<GlobalSets>()
+        .await?
+        .default_sets;
+
+        // Channels without a FrankerFaceZ room still get the global emotes
+        match get_room_set(client, channel_id).await {
+            Ok(Some(set)) => sets.push(set),
+            Ok(None) => {}
+            Err(err) => warn!("Unable to get FrankerFaceZ channel emotes: {err}"),
+        }
+
+        let emotes = futures::stream::iter(sets.into_iter().map(|set| async move {
+            Ok(send_tracked(
+                "ffz/set",
+                client.get(format!("https://api.frankerfacez.com/v1/_set/{set}",)),
+            )
             .await?
             .error_for_status()?
-            .json::<GlobalSets>()
+            .json::<EmoteSet>()
             .await?
-            .default_sets;
-
-        sets.push(
-            client
-                .get(format!(
-                    "https://api.frankerfacez.com/v1/_room/id/{channel_id}",
-                ))
-                .send()
-                .await?
-                .error_for_status()?
-                .json::<Room>()
-                .await?
-                .room
-                .set,
-        );
-
-        let emotes = futures::stream::iter(sets.into_iter().map(|set| async move {
-            Ok(client
-                .get(format!("https://api.frankerfacez.com/v1/_set/{set}",))
-                .send()
-                .await?
-                .error_for_status()?
-                .json::<EmoteSet>()
-                .await?
-                .set
-                .emoticons)
+            .set
+            .emoticons)
         }))
         .buffer_unordered(10)
         .collect::<Vec<Result<_>>>()
@@ -356,6 +536,7 @@ async fn save_emote(path: &Path, mut res: Response) -> Result<()> {
     let mut file = tokio::fs::File::create(&path).await?;
 
     while let Some(mut item) = res.chunk().await? {
+        record_download(item.len());
         file.write_all_buf(item.borrow_mut()).await?;
     }
 
@@ -378,7 +559,9 @@ async fn download_emotes(emotes: EmoteMap) -> DownloadedEmotes {
                     return Ok((x, (filename, o)));
                 }
 
-                let res = client.get(&url).send().await?.error_for_status()?;
+                let res = send_tracked("emote image", client.get(&url))
+                    .await?
+                    .error_for_status()?;
 
                 save_emote(path, res).await?;
 
@@ -393,46 +576,44 @@ async fn download_emotes(emotes: EmoteMap) -> DownloadedEmotes {
     .collect()
 }
 
-#[derive(Eq, PartialEq)]
-enum EmoteProvider {
-    Twitch,
-    BetterTTV,
-    SevenTV,
-    FrankerFaceZ,
-}
+/// The enabled third-party emote providers, from the highest priority to the lowest.
+fn get_enabled_emote_providers(config: &CompleteConfig) -> Vec<EmoteProvider> {
+    let enabled = |provider: &EmoteProvider| match provider {
+        EmoteProvider::BetterTTV => config.frontend.betterttv_emotes,
+        EmoteProvider::SevenTV => config.frontend.seventv_emotes,
+        EmoteProvider::FrankerFaceZ => config.frontend.frankerfacez_emotes,
+    };
 
-fn get_enabled_emote_providers(config: &FrontendConfig) -> Vec<EmoteProvider> {
-    let mut providers = Vec::with_capacity(4);
+    let mut providers = Vec::with_capacity(3);
 
-    if config.twitch_emotes {
-        providers.push(EmoteProvider::Twitch);
-    }
-    if config.betterttv_emotes {
-        providers.push(EmoteProvider::BetterTTV);
-    }
-    if config.seventv_emotes {
-        providers.push(EmoteProvider::SevenTV);
-    }
-    if config.frankerfacez_emotes {
-        providers.push(EmoteProvider::FrankerFaceZ);
+    // Providers left out of the priority list come last
+    for provider in config.emotes.priority.iter().chain(&[
+        EmoteProvider::BetterTTV,
+        EmoteProvider::SevenTV,
+        EmoteProvider::FrankerFaceZ,
+    ]) {
+        if !providers.contains(provider) {
+            providers.push(*provider);
+        }
     }
 
+    providers.retain(enabled);
+
     providers
 }
 
-pub async fn get_emotes(
-    config: &CompleteConfig,
-    channel: &str,
-) -> Result<(DownloadedEmotes, DownloadedEmotes)> {
+pub async fn get_emotes(config: &CompleteConfig, channel: &str) -> Result<EmoteSets> {
     // Reuse the same client and headers for twitch requests
     let twitch_client = get_twitch_client(config.twitch.token.as_deref()).await?;
     let user_id = &get_twitch_client_id(None).await?.user_id;
 
-    let channel_id = get_channel_id(&twitch_client, channel).await?;
+    ensure_requestable()?;
+    let channel_id =
+        track_request(get_channel_id(&twitch_client, channel, Priority::Background).await)?;
 
-    let enabled_emotes = get_enabled_emote_providers(&config.frontend);
+    let enabled_emotes = get_enabled_emote_providers(config);
 
-    let user_emotes = if enabled_emotes.contains(&EmoteProvider::Twitch) {
+    let mut user_emotes = if config.frontend.twitch_emotes {
         twitch::get_user_emotes(&twitch_client, user_id)
             .await
             .unwrap_or_default()
@@ -441,26 +622,48 @@ pub async fn get_emotes(
     };
 
     // Concurrently get the list of emotes for each provider
-    let global_emotes =
+    let provider_emotes =
         futures::stream::iter(enabled_emotes.into_iter().map(|emote_provider| async move {
             match emote_provider {
-                EmoteProvider::Twitch => Ok(HashMap::new()),
                 EmoteProvider::BetterTTV => betterttv::get_emotes(channel_id).await,
-                EmoteProvider::SevenTV => seventv::get_emotes(channel_id).await,
-                EmoteProvider::FrankerFaceZ => frankerfacez::get_emotes(channel_id).await,
+                EmoteProvider::SevenTV => seventv::get_emotes(channel_id)
+                    .await
+                    .map(|emotes| (emotes, BTreeSet::new())),
+                EmoteProvider::FrankerFaceZ => frankerfacez::get_emotes(channel_id)
+                    .await
+                    .map(|emotes| (emotes, BTreeSet::new())),
             }
         }))
-        .buffer_unordered(4)
-        .collect::<Vec<Result<EmoteMap>>>()
-        .await
-        .into_iter()
-        .flatten()
-        .flatten()
-        .collect::<EmoteMap>();
+        .buffered(3)
+        .collect::<Vec<Result<(EmoteMap, BTreeSet<String>)>>>()
+        .await;
+
+    let mut global_emotes = EmoteMap::new();
+    let mut overriding_emotes = BTreeSet::new();
+
+    // Lowest priority first, so that higher priority emotes replace them
+    for (emotes, overriding) in provider_emotes.into_iter().rev().flatten() {
+        global_emotes.extend(emotes);
+        overriding_emotes.extend(overriding);
+    }
+
+    // BetterTTV channel emotes are what the channel expects to be shown, even if a Twitch emote has the same name.
+    user_emotes.retain(|name, _| !overriding_emotes.contains(name));
+
+    let badges = if config.frontend.badges == BadgeStyle::Images {
+        twitch::get_badges(&twitch_client, channel_id)
+            .await
+            .inspect_err(|err| warn!("Unable to get badges: {err}"))
+            .unwrap_or_default()
+    } else {
+        HashMap::default()
+    };
 
     Ok((
         download_emotes(user_emotes).await,
         download_emotes(global_emotes).await,
+        overriding_emotes,
+        download_emotes(badges).await,
     ))
 }
 
@@ -476,17 +679,45 @@ pub async fn get_twitch_emote(name: &str) -> Result<()> {
     // Download it if it is not in the cache, try the animated version first.
     let url = format!("https://static-cdn.jtvnw.net/emoticons/v2/{name}/animated/light/1.0");
     let client = Client::new();
-    let res = client.get(&url).send().await?.error_for_status();
+    let res = send_tracked("twitch emote image", client.get(&url))
+        .await?
+        .error_for_status();
 
     let res = if res.is_err() {
-        client
-            .get(url.replace("animated", "static"))
-            .send()
-            .await?
-            .error_for_status()
+        send_tracked(
+            "twitch emote image",
+            client.get(url.replace("animated", "static")),
+        )
+        .await?
+        .error_for_status()
     } else {
         res
     }?;
 
     save_emote(path, res).await
 }
+
+/// Download the image of a cheer, such as `Cheer100`, returning the name it is cached under.
+/// Only the cheers available in every channel can be found this way, not the ones of partnered channels.
+pub async fn get_cheermote(prefix: &str, tier: u64) -> Result<String> {
+    let prefix = prefix.to_lowercase();
+    let name = format!("cheer-{prefix}-{tier}");
+
+    let path = cache_path(&name);
+    let path = Path::new(&path);
+
+    if tokio::fs::metadata(&path).await.is_ok() {
+        return Ok(name);
+    }
+
+    let url = format!(
+        "https://d3aqoihi2n8ty8.cloudfront.net/actions/{prefix}/dark/animated/{tier}/1.gif"
+    );
+    let res = send_tracked("cheermote image", Client::new().get(&url))
+        .await?
+        .error_for_status()?;
+
+    save_emote(path, res).await?;
+
+    Ok(name)
+}
@@ -1,4 +1,4 @@
-use std::{env, fmt, io::Write, path::PathBuf};
+use std::{env, fmt, io::Write, path::PathBuf, sync::OnceLock};
 
 use base64::{Engine, engine::general_purpose::STANDARD};
 use color_eyre::{
@@ -30,6 +30,23 @@ macro_rules! gp {
 /// string to be deleted by the terminal.
 const GP_PREFIX: &str = "twt.tty-graphics-protocol.";
 
+/// Frames with a delay this short are shown for [`DEFAULT_FRAME_DELAY`] instead, like browsers do,
+/// as a lot of GIFs do not set a delay and would otherwise play way too fast.
+const MINIMUM_FRAME_DELAY: u32 = 20;
+const DEFAULT_FRAME_DELAY: u32 = 100;
+
+/// If the frames of animated emotes should be sent to the terminal, rather than only the first one.
+/// Animations are played by the terminal itself, but decoding and storing every frame can be slow.
+pub static ANIMATE_EMOTES: OnceLock<bool> = OnceLock::new();
+
+const fn frame_delay(delay: u32) -> u32 {
+    if delay < MINIMUM_FRAME_DELAY {
+        DEFAULT_FRAME_DELAY
+    } else {
+        delay
+    }
+}
+
 struct StaticDecoder(DynamicImage);
 struct AnimatedDecoder<T: for<'a> AnimationDecoder<'a> + Send>(T);
 
@@ -48,9 +65,12 @@ impl<'a, T: for<'b> AnimationDecoder<'b> + Send> IntoFrames<'a> for AnimatedDeco
         Box::new(self.0.into_frames().map(|f| {
             let frame = f?;
 
-            let delay = frame.delay().numer_denom_ms().0;
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
 
-            Ok((frame.into_buffer(), delay))
+            Ok((
+                frame.into_buffer(),
+                frame_delay(numerator / denominator.max(1)),
+            ))
         }))
     }
 }
@@ -227,7 +247,13 @@ impl<'a> Image<'a> {
     }
 
     pub fn decode(self) -> Result<DecodedEmote> {
-        let frames = self.decoder.frames().map(|f| {
+        let frame_count = if *ANIMATE_EMOTES.get().unwrap_or(&true) {
+            usize::MAX
+        } else {
+            1
+        };
+
+        let frames = self.decoder.frames().take(frame_count).map(|f| {
             let (image, delay) = f?;
             let image = if self.overlay {
                 let (w, h) = image.dimensions();
@@ -420,21 +446,34 @@ fn query_terminal(command: &[u8]) -> Result<String> {
 
 const SUPPORTED_TERMINALS: [&str; 2] = ["xterm-kitty", "xterm-ghostty"];
 
+/// If the terminal supports the graphics protocol, once it has been checked.
+static GRAPHICS_PROTOCOL: OnceLock<bool> = OnceLock::new();
+
 /// First check if the terminal is `kitty`, this is the only terminal that supports the graphics protocol using unicode placeholders as of 2023-07-13.
 /// Then check that it supports the graphics protocol using temporary files, by sending a graphics protocol request followed by a request for terminal attributes.
 /// If we receive the terminal attributes without receiving the response for the graphics protocol, it does not support it.
+/// The terminal is only asked once, with the answer reused after.
 pub fn support_graphics_protocol() -> Result<bool> {
-    Ok(
-        env::var("TERM").is_ok_and(|term| SUPPORTED_TERMINALS.contains(&term.as_str()))
-            && query_terminal(
-                format!(
-                    concat!(gp!("i=31,s=1,v=1,a=q,t=d,f=24;{}"), csi!("c")),
-                    STANDARD.encode("AAAA"),
-                )
-                .as_bytes(),
-            )?
-            .contains("OK"),
-    )
+    if let Some(supported) = GRAPHICS_PROTOCOL.get() {
+        return Ok(*supported);
+    }
+
+    let supported = env::var("TERM").is_ok_and(|term| SUPPORTED_TERMINALS.contains(&term.as_str()))
+        && query_terminal(
+            format!(
+                concat!(gp!("i=31,s=1,v=1,a=q,t=d,f=24;{}"), csi!("c")),
+                STANDARD.encode("AAAA"),
+            )
+            .as_bytes(),
+        )?
+        .contains("OK");
+
+    Ok(*GRAPHICS_PROTOCOL.get_or_init(|| supported))
+}
+
+/// If the terminal supports the graphics protocol, if it was checked.
+pub fn graphics_protocol_detected() -> Option<bool> {
+    GRAPHICS_PROTOCOL.get().copied()
 }
 
 #[cfg(test)]
@@ -522,6 +561,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn short_frame_delays() {
+        assert_eq!(frame_delay(0), DEFAULT_FRAME_DELAY);
+        assert_eq!(frame_delay(10), DEFAULT_FRAME_DELAY);
+        assert_eq!(frame_delay(MINIMUM_FRAME_DELAY), MINIMUM_FRAME_DELAY);
+        assert_eq!(frame_delay(70), 70);
+    }
+
     #[test]
     fn clear_image() {
         let mut s = String::new();
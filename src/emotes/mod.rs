@@ -1,6 +1,6 @@
 use std::{
     cell::{OnceCell, RefCell},
-    collections::{BTreeMap, HashMap, hash_map::DefaultHasher},
+    collections::{BTreeMap, BTreeSet, HashMap, hash_map::DefaultHasher},
     hash::{Hash, Hasher},
     rc::Rc,
     sync::OnceLock,
@@ -25,12 +25,23 @@ use crate::{
 mod downloader;
 mod graphics_protocol;
 
-pub use downloader::get_twitch_emote;
-pub use graphics_protocol::{ApplyCommand, DecodedEmote, support_graphics_protocol};
+pub use downloader::{get_cheermote, get_twitch_emote};
+pub use graphics_protocol::{
+    ANIMATE_EMOTES, ApplyCommand, DecodedEmote, graphics_protocol_detected,
+    support_graphics_protocol,
+};
 
 // HashMap of emote name, emote filename, and if the emote is an overlay
 pub type DownloadedEmotes = BTreeMap<String, (String, bool)>;
 
+// User emotes, global emotes, the names of emotes that take priority over Twitch emotes, and badge images
+pub type EmoteSets = (
+    DownloadedEmotes,
+    DownloadedEmotes,
+    BTreeSet<String>,
+    DownloadedEmotes,
+);
+
 #[derive(Copy, Clone, Debug)]
 pub struct EmoteData {
     pub width: u32,
@@ -58,6 +69,10 @@ pub struct Emotes {
     /// `global_emotes` is used everywhere.
     pub user_emotes: RefCell<DownloadedEmotes>,
     pub global_emotes: RefCell<DownloadedEmotes>,
+    /// Names of `BetterTTV` channel emotes, which are shown instead of Twitch emotes with the same name.
+    pub overriding_emotes: RefCell<BTreeSet<String>>,
+    /// Map of badge, as `name/version`, and the filename of its image.
+    pub badges: RefCell<DownloadedEmotes>,
     /// Info about loaded emotes
     pub info: RefCell<HashMap<String, LoadedEmote>>,
     /// Terminal cell size in pixels: (width, height)
@@ -84,6 +99,8 @@ impl Emotes {
             });
         self.user_emotes.borrow_mut().clear();
         self.global_emotes.borrow_mut().clear();
+        self.overriding_emotes.borrow_mut().clear();
+        self.badges.borrow_mut().clear();
         self.info.borrow_mut().clear();
     }
 }
@@ -98,10 +115,7 @@ impl From<LoadedEmote> for EmoteData {
     }
 }
 
-pub fn query_emotes(
-    config: &CompleteConfig,
-    channel: String,
-) -> OSReceiver<(DownloadedEmotes, DownloadedEmotes)> {
+pub fn query_emotes(config: &CompleteConfig, channel: String) -> OSReceiver<EmoteSets> {
     let (tx, mut rx) = tokio::sync::oneshot::channel();
 
     if emotes_enabled(&config.frontend) {
@@ -114,11 +128,7 @@ pub fn query_emotes(
     rx
 }
 
-pub async fn send_emotes(
-    config: &CompleteConfig,
-    tx: OSSender<(DownloadedEmotes, DownloadedEmotes)>,
-    channel: String,
-) {
+pub async fn send_emotes(config: &CompleteConfig, tx: OSSender<EmoteSets>, channel: String) {
     info!("Starting emotes download.");
     match get_emotes(config, &channel).await {
         Ok(emotes) => {
@@ -1,30 +1,76 @@
-use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+    time::Duration,
+};
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, TimeDelta};
+use color_eyre::Result;
 use rustyline::line_buffer::LineBuffer;
 use tui::{
     Frame,
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
 };
 
 use crate::{
-    emotes::SharedEmotes,
+    emotes::{DownloadedEmotes, SharedEmotes},
     handlers::{
-        config::{CompleteConfig, SharedCompleteConfig, Theme},
-        data::MessageData,
+        chat_log::ChatLog,
+        completion::{RecentChatters, SharedRecentChatters},
+        config::{CompleteConfig, SharedCompleteConfig},
+        data::{MessageData, RawMessageData},
         filters::{Filters, SharedFilters},
+        highlights::{Highlights, SharedHighlights},
+        ignored::{IgnoredUsers, SharedIgnoredUsers},
+        joins::{JoinHistory, SharedJoinHistory},
+        mentions::Mention,
+        nicknames::{Nicknames, SharedNicknames},
+        pacing::{PART_SPACING, Paced},
         state::State,
         storage::{SharedStorage, Storage},
+        tabs::{ChannelBuffer, ChannelTabs},
+        themes::{Theme, next_theme, persist_theme, set_theme},
         user_input::events::{Event, Key},
     },
     terminal::TerminalAction,
+    twitch::{
+        TwitchAction,
+        alerts::{Alert, AlertRules, AlertSeverity},
+        automod::HeldMessage,
+        gifts::{GiftBurst, GiftSub},
+        membership::Membership,
+        mod_actions::ModAction,
+        notices::Notice,
+        polls::Poll,
+        raids::Raid,
+        redemptions::Redemption,
+        streams::LiveStream,
+        subscriptions::Subscription,
+        whispers::Whisper,
+    },
     ui::{
         components::{Component, Components},
         statics::LINE_BUFFER_CAPACITY,
     },
+    utils::{
+        links::DrawnLink,
+        text::capitalize_first_char,
+        window_title::{TitleState, format_window_title, set_window_title},
+    },
 };
 
 pub type SharedMessages = Rc<RefCell<VecDeque<MessageData>>>;
+pub type SharedNotices = Rc<RefCell<VecDeque<Notice>>>;
+pub type SharedWhispers = Rc<RefCell<VecDeque<Whisper>>>;
+pub type SharedActivity = Rc<RefCell<VecDeque<Membership>>>;
+pub type SharedRaids = Rc<RefCell<VecDeque<Raid>>>;
+pub type SharedMentions = Rc<RefCell<VecDeque<Mention>>>;
+pub type SharedAutoMod = Rc<RefCell<VecDeque<HeldMessage>>>;
+pub type SharedModActions = Rc<RefCell<VecDeque<ModAction>>>;
+pub type SharedPolls = Rc<RefCell<VecDeque<Poll>>>;
+pub type SharedChannelTabs = Rc<RefCell<ChannelTabs>>;
 
 #[allow(dead_code)]
 pub struct App {
@@ -32,12 +78,58 @@ pub struct App {
     pub components: Components,
     /// A config for the app and components to share.
     pub config: SharedCompleteConfig,
-    /// History of recorded messages (time, username, message, etc).
+    /// History of recorded messages (time, username, message, etc) of the active channel.
     pub messages: SharedMessages,
+    /// Joined channels, along with the messages of the channels that are not active.
+    pub tabs: SharedChannelTabs,
+    /// If no channel has been joined yet, since the first one is still to be picked from the dashboard.
+    waiting_for_channel: bool,
+    /// Notices sent by the Twitch server during this session.
+    pub notices: SharedNotices,
+    /// Whispers sent and received during this session.
+    pub whispers: SharedWhispers,
+    /// Users joining and leaving the joined channels.
+    pub activity: SharedActivity,
+    /// Raids into and out of joined channels and raids started by the user, including earlier sessions if stored.
+    pub raids: SharedRaids,
+    /// Messages that mentioned the user during this session.
+    pub mentions: SharedMentions,
+    /// Mentions received since the list of mentions was last opened.
+    unread_mentions: usize,
+    /// If channels are live, for the ones that have been checked.
+    live: HashMap<String, bool>,
+    /// The last title given to the terminal window.
+    window_title: String,
+    /// Messages held by AutoMod in the joined channels, waiting to be approved or denied.
+    pub automod: SharedAutoMod,
+    /// Actions taken by moderators in the joined channels.
+    pub mod_actions: SharedModActions,
+    /// Decides when moderation events are worth an alert.
+    pub alert_rules: AlertRules,
+    /// Polls and predictions in the user's channel, the most recent last.
+    pub polls: SharedPolls,
+    /// Gift subs combined into a single line each, the most recent last.
+    gift_bursts: VecDeque<GiftBurst>,
+    /// If the users who received gift subs are listed.
+    gift_recipients_shown: bool,
     /// Data loaded in from a JSON file.
     pub storage: SharedStorage,
     /// Messages to be filtered out.
     pub filters: SharedFilters,
+    /// Keywords that messages are highlighted for.
+    pub highlights: SharedHighlights,
+    /// Users whose messages are left out of chat.
+    pub ignored: SharedIgnoredUsers,
+    /// Names shown in place of the names of users.
+    pub nicknames: SharedNicknames,
+    /// The users who chatted in each channel, to complete mentions of them.
+    chatters: SharedRecentChatters,
+    /// When each channel was joined, to quickly switch back to them.
+    joins: SharedJoinHistory,
+    /// Where the messages of channels are written to, if they are logged.
+    chat_log: ChatLog,
+    /// Messages waiting to be sent to the current channel, along with how they are shown once sent.
+    pub outgoing: Paced<(MessageData, TwitchAction)>,
     /// Which window the terminal is currently focused on.
     state: State,
     /// The previous state, if any.
@@ -46,8 +138,8 @@ pub struct App {
     pub input_buffer: LineBuffer,
     /// The current suggestion, if any.
     pub buffer_suggestion: Option<String>,
-    /// The theme selected by the user.
-    pub theme: Theme,
+    /// The name of the theme selected by the user.
+    pub theme: String,
     /// Emotes
     pub emotes: SharedEmotes,
 }
@@ -73,61 +165,233 @@ impl App {
             storage.borrow_mut().add("channels", config.twitch.channel);
         }
 
-        let filters = shared!(Filters::new("filters.txt", &shared_config_borrow.filters));
+        let filters = shared!(Filters::new(&shared_config_borrow.filters));
 
         let messages = shared!(VecDeque::with_capacity(
             shared_config_borrow.terminal.maximum_messages,
         ));
 
+        let notices = shared!(VecDeque::new());
+
+        let whispers = shared!(VecDeque::new());
+
+        let activity = shared!(VecDeque::new());
+
+        let raids = shared!(
+            storage
+                .borrow()
+                .get("raids")
+                .iter()
+                .filter_map(|raid| Raid::from_storage(raid))
+                .collect::<VecDeque<Raid>>()
+        );
+
+        let mentions = shared!(VecDeque::new());
+
+        let automod = shared!(VecDeque::new());
+
+        let mod_actions = shared!(VecDeque::new());
+
+        let polls = shared!(VecDeque::new());
+
+        let tabs = shared!(ChannelTabs::new(&shared_config_borrow.twitch.channel));
+
         let emotes = SharedEmotes::default();
 
+        let highlights = shared!(Highlights::new(&shared_config_borrow.highlights));
+
+        let ignored = shared!(IgnoredUsers::from_storage(&storage.borrow().get("ignored")));
+
+        let nicknames = shared!(Nicknames::from_storage(&storage.borrow().get("nicknames")));
+
+        let chatters = shared!(RecentChatters::default());
+
+        let joins = shared!(JoinHistory::from_storage(&storage.borrow().get("joins")));
+
+        // The first channel is joined right away, unless it is picked from the dashboard
+        if shared_config_borrow.terminal.first_state != State::Dashboard {
+            joins
+                .borrow_mut()
+                .record(&shared_config_borrow.twitch.channel, Local::now());
+
+            storage
+                .borrow_mut()
+                .set("joins", joins.borrow().to_storage());
+        }
+
         let components = Components::new(
             &shared_config,
             storage.clone(),
             filters.clone(),
+            highlights.clone(),
+            ignored.clone(),
             messages.clone(),
+            notices.clone(),
+            whispers.clone(),
+            activity.clone(),
+            raids.clone(),
+            mentions.clone(),
+            automod.clone(),
+            mod_actions.clone(),
+            polls.clone(),
+            tabs.clone(),
             &emotes,
+            nicknames.clone(),
+            chatters.clone(),
+            joins.clone(),
             startup_time,
         );
 
-        Self {
+        let warnings = [
+            storage.borrow_mut().take_warning(),
+            filters.borrow_mut().take_warning(),
+        ];
+
+        let app = Self {
             components,
             config: shared_config.clone(),
             messages,
+            tabs,
+            waiting_for_channel: shared_config_borrow.terminal.first_state == State::Dashboard,
+            notices,
+            whispers,
+            activity,
+            raids,
+            mentions,
+            unread_mentions: 0,
+            live: HashMap::new(),
+            window_title: String::new(),
+            automod,
+            mod_actions,
+            alert_rules: AlertRules::new(shared_config_borrow.alerts.clone()),
+            polls,
+            gift_bursts: VecDeque::new(),
+            gift_recipients_shown: false,
             storage,
             filters,
+            highlights,
+            ignored,
+            nicknames,
+            chatters,
+            joins,
+            chat_log: ChatLog::new(&shared_config_borrow.logs),
+            outgoing: Paced::new(PART_SPACING),
             state: shared_config_borrow.terminal.first_state.clone(),
             previous_state: None,
             input_buffer: LineBuffer::with_capacity(LINE_BUFFER_CAPACITY),
             buffer_suggestion: None,
             theme: shared_config_borrow.frontend.theme.clone(),
             emotes,
+        };
+
+        for warning in warnings.into_iter().flatten() {
+            app.add_system_message(warning);
+        }
+
+        app
+    }
+
+    fn is_too_small(&self, area: Rect) -> bool {
+        (area.height < 10 || area.width < 60)
+            && self.config.borrow().frontend.show_unsupported_screen_size
+    }
+
+    /// Draw only the messages in chat on top of the previous frame, for when nothing but new messages changed.
+    /// Returns if that was possible, as everything has to be drawn again otherwise.
+    pub fn draw_chat_viewport(&self, f: &mut Frame, previous: &Buffer) -> bool {
+        let mut size = f.area();
+
+        size.height = size.height.saturating_sub(self.bottom_rows());
+
+        if self.state != State::Normal
+            || self.components.debug.is_focused()
+            || self.components.about.is_focused()
+            || previous.area != f.area()
+            || self.is_too_small(size)
+        {
+            return false;
+        }
+
+        let Some(viewport) = self.components.chat.viewport() else {
+            return false;
+        };
+
+        f.buffer_mut().clone_from(previous);
+
+        self.components.chat.draw_viewport(f, viewport);
+
+        true
+    }
+
+    /// The links drawn in chat that can be made clickable, which are only shown in normal mode.
+    pub fn drawn_links(&self) -> Vec<DrawnLink> {
+        if self.state == State::Normal {
+            self.components.chat.drawn_links()
+        } else {
+            vec![]
+        }
+    }
+
+    /// The rows along the bottom of the terminal taken by the state tabs and the status bar.
+    /// Nothing but chat is shown while projecting.
+    fn bottom_rows(&self) -> u16 {
+        if self.state == State::Projector {
+            return 0;
         }
+
+        let frontend = &self.config.borrow().frontend;
+
+        u16::from(frontend.state_tabs) + u16::from(frontend.status_bar)
     }
 
     pub fn draw(&mut self, f: &mut Frame) {
         let mut size = f.area();
 
-        if self.config.borrow().frontend.state_tabs {
+        self.read_mentions();
+
+        let (state_tabs, status_bar) = {
+            let frontend = &self.config.borrow().frontend;
+
+            (frontend.state_tabs, frontend.status_bar)
+        };
+
+        if self.bottom_rows() > 0 {
             let layout = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Length(size.height - 1), Constraint::Length(1)])
+                .constraints([
+                    Constraint::Min(0),
+                    Constraint::Length(u16::from(state_tabs)),
+                    Constraint::Length(u16::from(status_bar)),
+                ])
                 .split(f.area());
 
             size = layout[0];
 
-            self.components.tabs.draw(f, Some(layout[1]), &self.state);
+            if state_tabs {
+                self.components.tabs.draw(f, Some(layout[1]), &self.state);
+            }
+
+            if status_bar {
+                let mode = if self.state == State::Normal {
+                    self.components.chat.mode().to_string()
+                } else {
+                    capitalize_first_char(&self.state.to_string())
+                };
+
+                self.components
+                    .status_bar
+                    .draw(f, layout[2], &mode, self.unread_mentions);
+            }
         }
 
-        if (size.height < 10 || size.width < 60)
-            && self.config.borrow().frontend.show_unsupported_screen_size
-        {
+        if self.is_too_small(size) {
             self.components.window_size_error.draw(f, Some(f.area()));
         } else {
             match self.state {
-                State::Dashboard => self.components.dashboard.draw(f, None),
-                State::Normal => self.components.chat.draw(f, None),
-                State::Help => self.components.help.draw(f, None),
+                State::Dashboard => self.components.dashboard.draw(f, Some(size)),
+                State::Normal => self.components.chat.draw(f, Some(size)),
+                State::Help => self.components.help.draw(f, Some(size)),
+                State::Projector => self.components.projector.draw(f, Some(size)),
             }
         }
 
@@ -141,17 +405,54 @@ impl App {
 
             self.components.debug.draw(f, Some(rect));
         }
+
+        if self.components.about.is_focused() {
+            self.components.about.draw(f, None);
+        }
     }
 
     pub async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Focus(focused) = event {
+            self.components.chat.focus_changed(*focused);
+
+            return None;
+        }
+
+        // Only chat and the lists opened over it can be clicked on
+        if let Event::Mouse(..) = event {
+            if self.components.debug.is_focused() || self.components.about.is_focused() {
+                return None;
+            }
+
+            return match self.state {
+                State::Dashboard => self.components.dashboard.event(event).await,
+                State::Normal => self.components.chat.event(event).await,
+                State::Help | State::Projector => None,
+            };
+        }
+
+        // Pasted text only goes to what is being typed in
+        if let Event::Paste(_) = event {
+            return match self.state {
+                State::Dashboard => self.components.dashboard.event(event).await,
+                State::Normal => self.components.chat.event(event).await,
+                State::Help | State::Projector => None,
+            };
+        }
+
         if let Event::Input(key) = event {
             if self.components.debug.is_focused() {
                 return self.components.debug.event(event).await;
+            } else if self.components.about.is_focused() {
+                return self.components.about.event(event).await;
             }
 
+            // Motions take over keys while messages are being selected
+            let navigating = self.state == State::Normal && self.components.chat.is_selecting();
+
             match key {
                 // Global keybinds
-                Key::Ctrl('d') => {
+                Key::Ctrl('d') if !navigating => {
                     self.components.debug.toggle_focus();
                 }
                 _ => {
@@ -159,6 +460,7 @@ impl App {
                         State::Dashboard => self.components.dashboard.event(event).await,
                         State::Normal => self.components.chat.event(event).await,
                         State::Help => self.components.help.event(event).await,
+                        State::Projector => self.components.projector.event(event).await,
                     };
                 }
             }
@@ -172,41 +474,494 @@ impl App {
         self.emotes.unload();
     }
 
-    pub fn clear_messages(&mut self) {
+    pub fn clear_messages(&self) {
         self.messages.borrow_mut().clear();
 
         self.components.chat.scroll_offset.jump_to(0);
+        self.components.chat.clear_unread_marker();
     }
 
-    pub fn purge_user_messages(&self, user_id: &str) {
-        let messages = self
-            .messages
+    /// Add a message to the channel it was sent in, returning if that channel is the one being shown.
+    pub fn add_message(&self, channel: Option<&str>, message: MessageData) -> bool {
+        let maximum_messages = self.config.borrow().terminal.maximum_messages;
+
+        // Messages from the application itself are not in any channel, and are left out
+        if let Some(channel) = channel {
+            self.chat_log.log(channel, &message);
+
+            if !message.system {
+                self.chatters.borrow_mut().seen(channel, &message.author);
+            }
+        }
+
+        let message = self
+            .tabs
             .borrow_mut()
-            .iter()
-            .filter(|&m| m.user_id.clone().is_none_or(|user| user != user_id))
-            .cloned()
-            .collect::<VecDeque<MessageData>>();
+            .push_message(channel, message, maximum_messages);
+
+        message.is_some_and(|m| {
+            self.components.chat.message_added(&m);
+            self.messages.borrow_mut().push_front(m);
 
-        self.messages.replace(messages);
+            true
+        })
     }
 
-    pub fn remove_message_with(&self, message_id: &str) {
-        let index = self
-            .messages
+    /// Give a user a nickname, or remove it, keeping it for later sessions if nicknames are stored.
+    pub fn set_nickname(&self, user: &str, nickname: Option<String>) {
+        let mut nicknames = self.nicknames.borrow_mut();
+
+        nicknames.set(user, nickname);
+
+        self.storage
             .borrow_mut()
-            .iter()
-            .position(|f| f.message_id.clone().is_some_and(|id| id == message_id));
+            .set("nicknames", nicknames.to_storage());
+    }
+
+    /// Ignore a user or stop ignoring them, keeping the list for later sessions if it is stored.
+    pub fn set_ignored(&self, user: &str, ignored: bool) {
+        let mut ignored_users = self.ignored.borrow_mut();
 
-        if let Some(i) = index {
-            self.messages.borrow_mut().remove(i).unwrap();
+        ignored_users.set(user, ignored);
+
+        self.storage
+            .borrow_mut()
+            .set("ignored", ignored_users.to_storage());
+    }
+
+    /// If a message in a channel is left out of chat, by coming from an ignored user or matching a filter
+    /// that hides it, so that it does not notify or count as a mention either.
+    pub fn hides_message(&self, channel: &str, message: &MessageData) -> bool {
+        self.ignored.borrow().contains(&message.author)
+            || self.filters.borrow().hides_message(channel, message)
+    }
+
+    /// Show a message from the application itself in the active channel.
+    pub fn add_system_message(&self, payload: String) {
+        self.add_message(
+            None,
+            MessageData::new_user_message(
+                "System".to_string(),
+                None,
+                true,
+                payload,
+                None,
+                false,
+                &self.emotes,
+                false,
+            ),
+        );
+    }
+
+    /// Run a function over the messages of a channel, which are the shown messages
+    /// if the channel is active or not known.
+    fn with_channel_messages<T>(
+        &self,
+        channel: Option<&str>,
+        f: impl FnOnce(&mut VecDeque<MessageData>) -> T,
+    ) -> T {
+        let mut tabs = self.tabs.borrow_mut();
+
+        match channel.and_then(|c| tabs.messages_mut(c)) {
+            Some(messages) => f(messages),
+            None => f(&mut self.messages.borrow_mut()),
+        }
+    }
+
+    pub fn clear_channel_messages(&self, channel: Option<&str>) {
+        let inactive = channel.is_some_and(|c| self.tabs.borrow_mut().messages_mut(c).is_some());
+
+        if inactive {
+            self.with_channel_messages(channel, VecDeque::clear);
+        } else {
+            self.clear_messages();
         }
     }
 
+    /// Show the messages of another channel, opening a new tab for it if needed.
+    /// The messages, scroll position, and chat input of the current channel are kept for when it is switched back to.
+    pub fn switch_channel(&mut self, channel: &str) {
+        // Twitch names channels in lowercase, so messages are put in the tab they belong to
+        let channel = &channel.to_lowercase();
+
+        // What is still waiting to be sent was meant for the channel being left
+        let dropped = if *channel == self.config.borrow().twitch.channel {
+            0
+        } else {
+            self.outgoing.clear()
+        };
+
+        if self.waiting_for_channel {
+            self.waiting_for_channel = false;
+            self.tabs.replace(ChannelTabs::new(channel));
+        }
+
+        let current = ChannelBuffer::new(
+            self.messages.take(),
+            self.components.chat.scroll_offset.get_offset(),
+            self.components.chat.take_draft(),
+        );
+
+        let buffer = self.tabs.borrow_mut().switch_to(channel, current);
+
+        self.restore_buffer(buffer);
+
+        self.config.borrow_mut().twitch.channel = channel.to_string();
+
+        self.record_join(channel);
+
+        if dropped > 0 {
+            self.add_system_message(format!(
+                "{dropped} part(s) of a split message were not sent, as the channel was switched."
+            ));
+        }
+    }
+
+    /// Remember when a channel was switched to, keeping it in storage if channels are stored.
+    fn record_join(&self, channel: &str) {
+        let mut joins = self.joins.borrow_mut();
+
+        joins.record(channel, Local::now());
+
+        self.storage.borrow_mut().set("joins", joins.to_storage());
+    }
+
+    /// Close the tab of the active channel, returning the closed channel and the channel that is now shown.
+    pub fn close_channel(&mut self) -> Option<(String, String)> {
+        let (closed, next, buffer) = self.tabs.borrow_mut().close_active()?;
+
+        self.restore_buffer(buffer);
+
+        self.config.borrow_mut().twitch.channel.clone_from(&next);
+
+        Some((closed, next))
+    }
+
+    fn restore_buffer(&mut self, buffer: ChannelBuffer) {
+        self.messages.replace(buffer.messages);
+        self.components
+            .chat
+            .scroll_offset
+            .jump_to(buffer.scroll_offset);
+        self.components.chat.restore_draft(buffer.draft);
+        self.components.chat.clear_unread_marker();
+    }
+
+    pub fn add_notice(&self, notice: Notice) {
+        let mut notices = self.notices.borrow_mut();
+
+        if notices.len() >= self.config.borrow().terminal.maximum_messages {
+            notices.pop_front();
+        }
+
+        notices.push_back(notice);
+    }
+
+    pub fn add_whisper(&self, whisper: Whisper) {
+        let mut whispers = self.whispers.borrow_mut();
+
+        if whispers.len() >= self.config.borrow().terminal.maximum_messages {
+            whispers.pop_front();
+        }
+
+        whispers.push_back(whisper);
+    }
+
+    pub fn add_mention(&mut self, mention: Mention) {
+        self.unread_mentions += 1;
+
+        let mut mentions = self.mentions.borrow_mut();
+
+        if mentions.len() >= self.config.borrow().terminal.maximum_messages {
+            mentions.pop_front();
+        }
+
+        mentions.push_back(mention);
+    }
+
+    pub fn set_live(&mut self, channel: String, stream: Option<LiveStream>) {
+        self.live.insert(channel.clone(), stream.is_some());
+        self.components.chat.set_stream(channel, stream);
+    }
+
+    /// Mentions are read once the list of them is opened.
+    const fn read_mentions(&mut self) {
+        if self.components.chat.is_mentions_focused() {
+            self.unread_mentions = 0;
+        }
+    }
+
+    /// Update the title of the terminal window if it is enabled and anything in it changed.
+    pub fn update_window_title(&mut self) {
+        self.read_mentions();
+
+        let config = self.config.borrow();
+
+        let Some(format) = &config.frontend.window_title else {
+            return;
+        };
+
+        let title = format_window_title(
+            format,
+            &TitleState {
+                channel: &config.twitch.channel,
+                unread_mentions: self.unread_mentions,
+                live: self.live.get(&config.twitch.channel).copied(),
+            },
+        );
+
+        if title != self.window_title {
+            set_window_title(&title, config.frontend.tmux_window_name);
+            self.window_title = title;
+        }
+    }
+
+    pub fn add_held_message(&self, held: HeldMessage) {
+        let mut automod = self.automod.borrow_mut();
+
+        if automod.len() >= self.config.borrow().terminal.maximum_messages {
+            automod.pop_front();
+        }
+
+        automod.push_back(held);
+    }
+
+    pub fn remove_held_message(&self, message_id: &str) {
+        self.automod
+            .borrow_mut()
+            .retain(|held| held.message_id != message_id);
+    }
+
+    pub fn add_mod_action(&self, mod_action: ModAction) {
+        let mut mod_actions = self.mod_actions.borrow_mut();
+
+        if mod_actions.len() >= self.config.borrow().terminal.maximum_messages {
+            mod_actions.pop_front();
+        }
+
+        mod_actions.push_back(mod_action);
+    }
+
+    /// Replace a poll with its latest progress, or add it if it just started.
+    pub fn update_poll(&self, poll: Poll) {
+        let mut polls = self.polls.borrow_mut();
+
+        polls.retain(|p| p.id != poll.id);
+
+        if polls.len() >= self.config.borrow().terminal.maximum_messages {
+            polls.pop_front();
+        }
+
+        polls.push_back(poll);
+    }
+
+    pub fn add_membership(&self, membership: Membership) {
+        let mut activity = self.activity.borrow_mut();
+
+        if activity.len() >= self.config.borrow().terminal.maximum_messages {
+            activity.pop_front();
+        }
+
+        activity.push_back(membership);
+    }
+
+    pub fn add_raid(&self, raid: Raid) {
+        let mut raids = self.raids.borrow_mut();
+        let mut storage = self.storage.borrow_mut();
+
+        // A raid started from the chat input shows up again once it goes through, and again if the
+        // raided channel is joined, this time with the size of the party.
+        if let Some(last) = raids.back_mut().filter(|last| {
+            raid.outgoing
+                && last.outgoing
+                && (last.viewers.is_none() || last.viewers == raid.viewers)
+                && last.channel.eq_ignore_ascii_case(&raid.channel)
+        }) {
+            let stored = last.to_storage();
+
+            if storage.contains("raids", &stored) {
+                storage.remove_inner_with("raids", &stored);
+            }
+
+            last.viewers = raid.viewers;
+            last.time_received = raid.time_received;
+            storage.add("raids", last.to_storage());
+
+            return;
+        }
+
+        storage.add("raids", raid.to_storage());
+        raids.push_back(raid);
+    }
+
+    /// Combine a gift sub with the others from the same user, returning if it is the first of them.
+    pub fn add_gift_sub(&mut self, gift: GiftSub) -> bool {
+        let now = Local::now();
+        let window = TimeDelta::from_std(Duration::from_secs(
+            self.config.borrow().frontend.gift_sub_window,
+        ))
+        .unwrap_or(TimeDelta::MAX);
+
+        if let Some(burst) = self
+            .gift_bursts
+            .iter_mut()
+            .rev()
+            .find(|burst| burst.accepts(&gift, now, window))
+        {
+            burst.add(gift, now);
+
+            let burst = burst.clone();
+            self.update_gift_burst(&burst);
+
+            return false;
+        }
+
+        let burst = GiftBurst::new(gift, now);
+
+        let mut message = MessageData::from_twitch_message(
+            RawMessageData::new(
+                "Gift".to_string(),
+                None,
+                true,
+                burst.summary(self.gift_recipients_shown),
+                DownloadedEmotes::default(),
+                Some(burst.message_id.clone()),
+                false,
+                burst.channel.clone(),
+            ),
+            &self.emotes,
+            false,
+        );
+        message.color = Some(self.config.borrow().frontend.gift_sub_color);
+
+        self.add_message(burst.channel.as_deref(), message);
+
+        if self.gift_bursts.len() >= self.config.borrow().terminal.maximum_messages {
+            self.gift_bursts.pop_front();
+        }
+
+        self.gift_bursts.push_back(burst);
+
+        true
+    }
+
+    /// Show a subscription in chat, in its own color.
+    pub fn add_subscription(&self, subscription: &Subscription) {
+        let mut message = MessageData::from_twitch_message(
+            RawMessageData::new(
+                "Sub".to_string(),
+                None,
+                true,
+                subscription.summary(),
+                DownloadedEmotes::default(),
+                None,
+                false,
+                subscription.channel.clone(),
+            ),
+            &self.emotes,
+            false,
+        );
+        message.color = Some(self.config.borrow().frontend.subscription_color);
+
+        self.add_message(subscription.channel.as_deref(), message);
+    }
+
+    /// Show an alert in chat in the color of its severity, keeping critical alerts on screen until acknowledged.
+    pub fn add_alert(&mut self, alert: Alert) {
+        let mut message = MessageData::from_twitch_message(
+            RawMessageData::new(
+                alert.severity.to_string(),
+                None,
+                true,
+                alert.message.clone(),
+                DownloadedEmotes::default(),
+                None,
+                false,
+                Some(alert.channel.clone()),
+            ),
+            &self.emotes,
+            false,
+        );
+        message.color = Some(alert.severity.color());
+
+        self.add_message(Some(&alert.channel), message);
+
+        if alert.severity == AlertSeverity::Critical {
+            self.components.chat.push_alert(alert);
+        }
+    }
+
+    /// Show a redeemed reward in chat, styled apart from the messages of users.
+    pub fn add_redemption(&self, redemption: &Redemption) {
+        self.add_message(
+            Some(&redemption.channel),
+            MessageData::from_twitch_message(
+                RawMessageData::new(
+                    "Reward".to_string(),
+                    None,
+                    true,
+                    redemption.summary(),
+                    DownloadedEmotes::default(),
+                    None,
+                    true,
+                    Some(redemption.channel.clone()),
+                ),
+                &self.emotes,
+                false,
+            ),
+        );
+    }
+
+    pub fn toggle_gift_recipients(&mut self) {
+        self.gift_recipients_shown = !self.gift_recipients_shown;
+
+        for burst in &self.gift_bursts {
+            self.update_gift_burst(burst);
+        }
+    }
+
+    /// Rewrite the line showing gift subs, if it has not been removed from chat.
+    fn update_gift_burst(&self, burst: &GiftBurst) {
+        let summary = burst.summary(self.gift_recipients_shown);
+
+        self.with_channel_messages(burst.channel.as_deref(), |messages| {
+            if let Some(message) = messages
+                .iter_mut()
+                .find(|m| m.message_id.as_ref() == Some(&burst.message_id))
+            {
+                message.payload = summary;
+            }
+        });
+    }
+
+    pub fn purge_user_messages(&self, channel: Option<&str>, user_id: &str) {
+        let show_deleted = self.config.borrow().frontend.show_deleted_messages;
+
+        self.with_channel_messages(channel, |messages| {
+            messages
+                .iter_mut()
+                .filter(|m| m.user_id.as_ref().is_some_and(|user| user == user_id))
+                .for_each(|m| m.delete(show_deleted));
+        });
+    }
+
+    pub fn delete_message_with(&self, channel: Option<&str>, message_id: &str) {
+        let show_deleted = self.config.borrow().frontend.show_deleted_messages;
+
+        self.with_channel_messages(channel, |messages| {
+            if let Some(message) = messages
+                .iter_mut()
+                .find(|m| m.message_id.as_ref().is_some_and(|id| id == message_id))
+            {
+                message.delete(show_deleted);
+            }
+        });
+    }
+
     pub fn get_previous_state(&self) -> Option<State> {
         self.previous_state.clone()
     }
 
-    #[allow(dead_code)]
     pub fn get_state(&self) -> State {
         self.state.clone()
     }
@@ -216,8 +971,58 @@ impl App {
         self.state = other;
     }
 
-    #[allow(dead_code)]
-    pub fn rotate_theme(&self) {
-        todo!("Rotate through different themes")
+    /// Apply a config reloaded from the file while running, along with the theme, highlights, and filters made from it.
+    /// Filters that were toggled while running stay that way, unless they were changed in the file too.
+    pub fn reload_config(&mut self, reloaded: CompleteConfig) -> Result<()> {
+        let theme = Theme::load(&reloaded.frontend.theme)?;
+
+        let previous = self.config.borrow().filters.clone();
+
+        self.config.borrow_mut().merge_reloaded(reloaded);
+
+        let config = self.config.borrow();
+
+        set_theme(theme);
+        self.theme.clone_from(&config.frontend.theme);
+
+        *self.highlights.borrow_mut() = Highlights::new(&config.highlights);
+
+        let mut filters = self.filters.borrow_mut();
+
+        if config.filters.enabled != previous.enabled && config.filters.enabled != filters.enabled()
+        {
+            filters.toggle();
+        }
+
+        if config.filters.reversed != previous.reversed
+            && config.filters.reversed != filters.reversed()
+        {
+            filters.reverse();
+        }
+
+        Ok(())
+    }
+
+    /// Switch to the next theme, which is seen the next time the terminal is drawn,
+    /// and save it to the config file so that it is used from then on.
+    pub fn rotate_theme(&mut self) {
+        let name = next_theme(&self.theme);
+
+        match Theme::load(&name) {
+            Ok(theme) => {
+                set_theme(theme);
+
+                self.config.borrow_mut().frontend.theme.clone_from(&name);
+
+                if let Err(err) = persist_theme(&name) {
+                    self.add_system_message(format!("Switched to the {name} theme, but {err}."));
+                } else {
+                    self.add_system_message(format!("Switched to the {name} theme."));
+                }
+
+                self.theme = name;
+            }
+            Err(err) => self.add_system_message(format!("{err:#}")),
+        }
     }
 }
@@ -1,7 +1,8 @@
 use clap::{Parser, ValueEnum, builder::PossibleValue};
 
 use crate::handlers::{
-    config::{CompleteConfig, Palette, Theme},
+    config::{BadgeStyle, CompleteConfig, Palette},
+    import::ImportSource,
     state::State,
 };
 
@@ -20,22 +21,9 @@ impl ValueEnum for Palette {
     }
 }
 
-impl ValueEnum for Theme {
-    fn value_variants<'a>() -> &'a [Self] {
-        &[Self::Dark, Self::Light]
-    }
-
-    fn to_possible_value<'a>(&self) -> Option<PossibleValue> {
-        Some(PossibleValue::new(match self {
-            Self::Light => "light",
-            _ => "dark",
-        }))
-    }
-}
-
 impl ValueEnum for State {
     fn value_variants<'a>() -> &'a [Self] {
-        &[Self::Dashboard, Self::Normal, Self::Help]
+        &[Self::Dashboard, Self::Normal, Self::Help, Self::Projector]
     }
 
     fn to_possible_value(&self) -> Option<PossibleValue> {
@@ -43,15 +31,19 @@ impl ValueEnum for State {
             Self::Dashboard => "start",
             Self::Normal => "normal",
             Self::Help => "help",
+            Self::Projector => "projector",
         }))
     }
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[clap(rename_all = "kebab-case")]
-#[clap(author, version, about)]
+#[clap(author, version, about, disable_version_flag = true)]
 /// Twitch chat in the terminal
 pub struct Cli {
+    /// Print the version, along with build and terminal details if verbose
+    #[arg(short = 'V', long)]
+    pub version: bool,
     /// The streamer's name
     #[arg(short, long)]
     pub channel: Option<String>,
@@ -73,18 +65,56 @@ pub struct Cli {
     /// Username color palette
     #[arg(short, long)]
     pub palette: Option<Palette>,
-    /// Twitch badges support
+    /// Show twitch badges as unicode symbols
     #[arg(short, long)]
     pub badges: bool,
-    /// The theme of the terminal
+    /// The theme of the terminal, being dark, light, or the name of a theme file
     #[arg(short, long)]
-    pub theme: Option<Theme>,
+    pub theme: Option<String>,
     /// The starting state of the terminal
-    #[arg(short, long)]
+    #[arg(short, long, visible_alias = "start-state")]
     pub first_state: Option<State>,
     /// Show a warning if the screen size is too small
     #[arg(short, long)]
     pub unsupported_screen_size: bool,
+    /// Only connect to Twitch chat and Twitch's API, without emotes, statistics, or log files
+    #[arg(long)]
+    pub strict_privacy: bool,
+    /// Only show what moderation commands would do, without running them
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Print the default config, with every setting explained, then exit
+    #[arg(long)]
+    pub print_default_config: bool,
+    /// Set any value of the config, such as frontend.theme=light, over the config file and environment variables
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_override)]
+    pub overrides: Vec<(String, String)>,
+    /// Import highlights, ignores, and channels from Chatterino's settings.json, then exit
+    #[arg(long, value_name = "SETTINGS_JSON", conflicts_with = "import_weechat")]
+    pub import_chatterino: Option<String>,
+    /// Import the Twitch server and highlights from a WeeChat config directory, then exit
+    #[arg(long, value_name = "WEECHAT_DIR")]
+    pub import_weechat: Option<String>,
+}
+
+/// Split a `--set` argument into the path of a config value and the value.
+fn parse_override(argument: &str) -> Result<(String, String), String> {
+    argument
+        .split_once('=')
+        .map(|(path, value)| (path.trim().to_string(), value.to_string()))
+        .ok_or_else(|| {
+            format!("{argument} is not in the form KEY=VALUE, such as frontend.theme=light")
+        })
+}
+
+impl Cli {
+    /// The client to import settings from, if one was asked for.
+    pub fn import_source(&self) -> Option<ImportSource> {
+        self.import_chatterino
+            .clone()
+            .map(ImportSource::Chatterino)
+            .or_else(|| self.import_weechat.clone().map(ImportSource::WeeChat))
+    }
 }
 
 pub fn merge_args_into_config(config: &mut CompleteConfig, args: Cli) {
@@ -94,6 +124,7 @@ pub fn merge_args_into_config(config: &mut CompleteConfig, args: Cli) {
     }
 
     config.terminal.verbose = config.terminal.verbose || args.verbose;
+    config.terminal.strict_privacy = config.terminal.strict_privacy || args.strict_privacy;
 
     if let Some(delay) = args.delay {
         config.terminal.delay = delay;
@@ -110,6 +141,8 @@ pub fn merge_args_into_config(config: &mut CompleteConfig, args: Cli) {
         config.twitch.channel = channel;
     }
 
+    config.twitch.dry_run = config.twitch.dry_run || args.dry_run;
+
     // Frontend arguments
     config.frontend.show_datetimes = config.frontend.show_datetimes || args.show_datetimes;
 
@@ -117,7 +150,9 @@ pub fn merge_args_into_config(config: &mut CompleteConfig, args: Cli) {
         config.frontend.palette = palette;
     }
 
-    config.frontend.badges = config.frontend.badges || args.badges;
+    if args.badges && config.frontend.badges == BadgeStyle::Hidden {
+        config.frontend.badges = BadgeStyle::Unicode;
+    }
 
     if let Some(theme) = args.theme {
         config.frontend.theme = theme;
@@ -0,0 +1,175 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions, create_dir_all},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::mpsc::{Receiver, Sender, channel},
+    thread,
+};
+
+use chrono::{DateTime, Local, NaiveDate};
+use log::warn;
+
+use crate::{
+    handlers::{config::LogsConfig, data::MessageData},
+    utils::pathing::config_path,
+};
+
+/// A line to be written to the log of a channel.
+struct Entry {
+    channel: String,
+    time: DateTime<Local>,
+    line: String,
+}
+
+/// Writes the messages of channels to `logs/<channel>/<date>.log` in the config directory,
+/// starting a new file each day. Lines are written on a thread of their own, so that
+/// writing to disk never holds up drawing the terminal.
+pub struct ChatLog {
+    config: LogsConfig,
+    sender: Option<Sender<Entry>>,
+}
+
+impl ChatLog {
+    pub fn new(config: &LogsConfig) -> Self {
+        let sender = config.enabled.then(|| {
+            let (sender, receiver) = channel();
+
+            thread::spawn(move || write_entries(&receiver));
+
+            sender
+        });
+
+        Self {
+            config: config.clone(),
+            sender,
+        }
+    }
+
+    pub fn log(&self, channel: &str, message: &MessageData) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        let line = format_line(
+            &self.config,
+            message.time_sent,
+            &message.author,
+            &message.plain_payload(),
+        );
+
+        // The writing thread only stops if it could not write at all, which was already logged
+        let _ = sender.send(Entry {
+            channel: channel.to_lowercase(),
+            time: message.time_sent,
+            line,
+        });
+    }
+}
+
+/// A message as a line of a log, in the format of the config.
+fn format_line(config: &LogsConfig, sent: DateTime<Local>, name: &str, payload: &str) -> String {
+    config
+        .format
+        .replace("{time}", &sent.format(&config.datetime_format).to_string())
+        .replace("{author}", name)
+        .replace("{message}", payload)
+}
+
+fn log_path(channel: &str, date: NaiveDate) -> String {
+    config_path(&format!("logs/{channel}/{}.log", date.format("%Y-%m-%d")))
+}
+
+fn open_log(channel: &str, time: DateTime<Local>) -> std::io::Result<BufWriter<File>> {
+    let path = log_path(channel, time.date_naive());
+
+    if let Some(directory) = Path::new(&path).parent() {
+        create_dir_all(directory)?;
+    }
+
+    let mut file = BufWriter::new(OpenOptions::new().create(true).append(true).open(&path)?);
+
+    // Like Chatterino, each session is marked where it starts in the file
+    writeln!(
+        file,
+        "# Start logging at {}",
+        time.format("%Y-%m-%d %H:%M:%S %Z")
+    )?;
+
+    Ok(file)
+}
+
+fn write_entries(receiver: &Receiver<Entry>) {
+    let mut files: HashMap<String, (NaiveDate, BufWriter<File>)> = HashMap::new();
+
+    while let Ok(first) = receiver.recv() {
+        // Everything that arrived in the meantime is written before flushing once
+        for entry in std::iter::once(first).chain(receiver.try_iter()) {
+            let date = entry.time.date_naive();
+
+            let is_open = files
+                .get(&entry.channel)
+                .is_some_and(|(opened, _)| *opened == date);
+
+            if !is_open {
+                match open_log(&entry.channel, entry.time) {
+                    Ok(file) => {
+                        files.insert(entry.channel.clone(), (date, file));
+                    }
+                    Err(err) => {
+                        warn!("Chat log of {} could not be opened: {err}", entry.channel);
+                        continue;
+                    }
+                }
+            }
+
+            if let Some((_, file)) = files.get_mut(&entry.channel) {
+                if let Err(err) = writeln!(file, "{}", entry.line) {
+                    warn!("Chat log of {} could not be written: {err}", entry.channel);
+                }
+            }
+        }
+
+        for (_, file) in files.values_mut() {
+            if let Err(err) = file.flush() {
+                warn!("Chat log could not be written: {err}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_format_line() {
+        let config = LogsConfig::default();
+        let sent = Local.with_ymd_and_hms(2024, 5, 1, 9, 3, 7).unwrap();
+
+        assert_eq!(
+            format_line(&config, sent, "human", "hello {author}"),
+            "[09:03:07] human: hello {author}"
+        );
+
+        let config = LogsConfig {
+            format: "{time} <{author}> {message}".to_string(),
+            datetime_format: "%Y-%m-%d %H:%M".to_string(),
+            ..LogsConfig::default()
+        };
+
+        assert_eq!(
+            format_line(&config, sent, "human", "hi"),
+            "2024-05-01 09:03 <human> hi"
+        );
+    }
+
+    #[test]
+    fn test_log_path() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+
+        assert!(log_path("xithrius", date).ends_with("logs/xithrius/2024-05-01.log"));
+    }
+}
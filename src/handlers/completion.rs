@@ -0,0 +1,423 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+
+use chrono::{DateTime, Local};
+
+pub type SharedRecentChatters = Rc<RefCell<RecentChatters>>;
+
+/// How many of the users who chatted are remembered in each channel.
+const RECENT_CHATTERS: usize = 500;
+
+/// How many days it takes for a use of a word to count half as much.
+const HALF_LIFE_DAYS: f64 = 14.0;
+/// Words whose score decayed below this are forgotten.
+const MINIMUM_SCORE: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Usage {
+    score: f64,
+    last_used: DateTime<Local>,
+}
+
+impl Usage {
+    fn decayed(&self, now: &DateTime<Local>) -> f64 {
+        let days = (*now - self.last_used).num_seconds().max(0) as f64 / 86_400.0;
+
+        self.score * 0.5_f64.powf(days / HALF_LIFE_DAYS)
+    }
+}
+
+/// How often emotes and usernames have been sent in each channel,
+/// so that the ones used the most recently and often are suggested first.
+#[derive(Debug, Default)]
+pub struct CompletionRanking {
+    channels: HashMap<String, HashMap<String, Usage>>,
+}
+
+impl CompletionRanking {
+    /// Load the ranking from storage, where each word is stored as `channel\tword\tscore\ttime`.
+    pub fn from_storage(items: &[String]) -> Self {
+        let mut ranking = Self::default();
+
+        for item in items {
+            let mut parts = item.split('\t');
+
+            let (Some(channel), Some(word), Some(score), Some(last_used)) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+
+            let (Ok(score), Ok(last_used)) = (
+                score.parse::<f64>(),
+                DateTime::parse_from_rfc3339(last_used),
+            ) else {
+                continue;
+            };
+
+            ranking
+                .channels
+                .entry(channel.to_string())
+                .or_default()
+                .insert(
+                    word.to_string(),
+                    Usage {
+                        score,
+                        last_used: last_used.with_timezone(&Local),
+                    },
+                );
+        }
+
+        ranking
+    }
+
+    /// The words worth remembering, in the format read by [`Self::from_storage`].
+    pub fn to_storage(&self, now: &DateTime<Local>) -> Vec<String> {
+        let mut items = self
+            .channels
+            .iter()
+            .flat_map(|(channel, words)| {
+                words
+                    .iter()
+                    .filter(|(_, usage)| usage.decayed(now) >= MINIMUM_SCORE)
+                    .map(move |(word, usage)| {
+                        format!(
+                            "{channel}\t{word}\t{}\t{}",
+                            usage.score,
+                            usage.last_used.to_rfc3339()
+                        )
+                    })
+            })
+            .collect::<Vec<String>>();
+
+        items.sort();
+
+        items
+    }
+
+    /// Count a use of a word in a channel, on top of what is left of the earlier uses.
+    pub fn record(&mut self, channel: &str, word: &str, now: DateTime<Local>) {
+        let usage = self
+            .channels
+            .entry(channel.to_string())
+            .or_default()
+            .entry(word.to_string())
+            .or_insert(Usage {
+                score: 0.0,
+                last_used: now,
+            });
+
+        usage.score = usage.decayed(&now) + 1.0;
+        usage.last_used = now;
+    }
+
+    pub fn score(&self, channel: &str, word: &str, now: &DateTime<Local>) -> f64 {
+        self.channels
+            .get(channel)
+            .and_then(|words| words.get(word))
+            .map_or(0.0, |usage| usage.decayed(now))
+    }
+
+    /// Words that have been used in a channel, which can be suggested even if they are not known otherwise.
+    pub fn words(&self, channel: &str) -> impl Iterator<Item = &String> {
+        self.channels
+            .get(channel)
+            .into_iter()
+            .flat_map(HashMap::keys)
+    }
+
+    /// The candidate that starts with the search and has the highest score,
+    /// with the earliest candidate winning ties.
+    pub fn best<'a>(
+        &self,
+        channel: &str,
+        candidates: impl Iterator<Item = &'a String>,
+        search: &str,
+        now: &DateTime<Local>,
+    ) -> Option<&'a String> {
+        if search.is_empty() {
+            return None;
+        }
+
+        candidates
+            .filter(|c| c.starts_with(search) && c.len() > search.len())
+            .fold(None, |best: Option<(&String, f64)>, candidate| {
+                let score = self.score(channel, candidate, now);
+
+                match best {
+                    Some((_, best_score)) if best_score >= score => best,
+                    _ => Some((candidate, score)),
+                }
+            })
+            .map(|(candidate, _)| candidate)
+    }
+
+    /// Every candidate that starts with the search, the highest scores first and alphabetically otherwise.
+    pub fn ranked<'a>(
+        &self,
+        channel: &str,
+        candidates: impl Iterator<Item = &'a String>,
+        search: &str,
+        now: &DateTime<Local>,
+    ) -> Vec<String> {
+        if search.is_empty() {
+            return vec![];
+        }
+
+        let mut matches = candidates
+            .filter(|c| c.starts_with(search) && c.len() > search.len())
+            .map(|c| (self.score(channel, c, now), c))
+            .collect::<Vec<(f64, &String)>>();
+
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            b_score.total_cmp(a_score).then_with(|| a.cmp(b))
+        });
+        matches.dedup_by(|(_, a), (_, b)| a == b);
+
+        matches.into_iter().map(|(_, c)| c.clone()).collect()
+    }
+}
+
+/// The users who chatted in each channel, the most recent first, to complete mentions of them.
+#[derive(Debug, Default)]
+pub struct RecentChatters {
+    channels: HashMap<String, VecDeque<String>>,
+}
+
+impl RecentChatters {
+    /// Move a user to the front of the chatters of a channel, forgetting the one who chatted the longest ago if there are too many.
+    pub fn seen(&mut self, channel: &str, user: &str) {
+        let chatters = self.channels.entry(channel.to_lowercase()).or_default();
+
+        if let Some(position) = chatters.iter().position(|c| c.eq_ignore_ascii_case(user)) {
+            chatters.remove(position);
+        }
+
+        chatters.push_front(user.to_string());
+        chatters.truncate(RECENT_CHATTERS);
+    }
+
+    /// The users who chatted in a channel, the most recent first.
+    pub fn recent(&self, channel: &str) -> impl Iterator<Item = &String> {
+        self.channels
+            .get(&channel.to_lowercase())
+            .into_iter()
+            .flatten()
+    }
+
+    /// The users in a channel with names starting with the search, ignoring case, the most recent first.
+    pub fn matching(&self, channel: &str, search: &str) -> Vec<String> {
+        let search = search.to_lowercase();
+
+        self.recent(channel)
+            .filter(|user| user.to_lowercase().starts_with(&search))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Words that the last word of the input is being completed to, going through them each time Tab is pressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionCycle {
+    /// What the input was before completing, to go back to it.
+    original: String,
+    /// Everything in the input before the word being completed.
+    before: String,
+    candidates: Vec<String>,
+    selected: usize,
+}
+
+impl CompletionCycle {
+    /// Start completing the last word of an input, if there is anything to complete it to.
+    pub fn new(input: &str, candidates: Vec<String>) -> Option<Self> {
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let before = input
+            .rsplit_once(' ')
+            .map_or_else(String::new, |(before, _)| format!("{before} "));
+
+        Some(Self {
+            original: input.to_string(),
+            before,
+            candidates,
+            selected: 0,
+        })
+    }
+
+    /// The input with the last word completed to the selected candidate.
+    pub fn input(&self) -> String {
+        format!("{}{}", self.before, self.candidates[self.selected])
+    }
+
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    pub fn candidates(&self) -> &[String] {
+        &self.candidates
+    }
+
+    pub const fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % self.candidates.len();
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self
+            .selected
+            .checked_sub(1)
+            .unwrap_or(self.candidates.len() - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    #[test]
+    fn test_ranking_prefers_used_words() {
+        let now = Local::now();
+        let mut ranking = CompletionRanking::default();
+        let candidates = ["KEKW".to_string(), "KEKL".to_string(), "Kappa".to_string()];
+
+        assert_eq!(
+            ranking.best("channel", candidates.iter(), "KE", &now),
+            Some(&candidates[0])
+        );
+
+        ranking.record("channel", "KEKL", now);
+
+        assert_eq!(
+            ranking.best("channel", candidates.iter(), "KE", &now),
+            Some(&candidates[1])
+        );
+        // Usage is counted separately for each channel
+        assert_eq!(
+            ranking.best("other", candidates.iter(), "KE", &now),
+            Some(&candidates[0])
+        );
+        assert_eq!(ranking.best("channel", candidates.iter(), "", &now), None);
+    }
+
+    #[test]
+    fn test_ranked() {
+        let now = Local::now();
+        let mut ranking = CompletionRanking::default();
+        let candidates = [
+            "KEKW".to_string(),
+            "KEKL".to_string(),
+            "Kappa".to_string(),
+            "KEKW".to_string(),
+        ];
+
+        ranking.record("channel", "KEKW", now);
+
+        assert_eq!(
+            ranking.ranked("channel", candidates.iter(), "KE", &now),
+            vec!["KEKW", "KEKL"]
+        );
+        assert_eq!(
+            ranking.ranked("other", candidates.iter(), "K", &now),
+            vec!["KEKL", "KEKW", "Kappa"]
+        );
+        assert!(
+            ranking
+                .ranked("channel", candidates.iter(), "", &now)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_recent_chatters() {
+        let mut chatters = RecentChatters::default();
+
+        chatters.seen("Channel", "Xithrius");
+        chatters.seen("channel", "xander");
+        chatters.seen("channel", "human");
+        chatters.seen("other", "xavier");
+        chatters.seen("channel", "xithrius");
+
+        assert_eq!(
+            chatters.matching("channel", "X"),
+            vec!["xithrius", "xander"]
+        );
+        assert_eq!(chatters.matching("other", "x"), vec!["xavier"]);
+        assert!(chatters.matching("unknown", "x").is_empty());
+
+        for i in 0..=RECENT_CHATTERS {
+            chatters.seen("channel", &format!("user{i}"));
+        }
+
+        assert_eq!(chatters.recent("channel").count(), RECENT_CHATTERS);
+        assert!(chatters.matching("channel", "xi").is_empty());
+    }
+
+    #[test]
+    fn test_completion_cycle() {
+        assert!(CompletionCycle::new("hi KE", vec![]).is_none());
+
+        let mut cycle =
+            CompletionCycle::new("hi KE", vec!["KEKW".to_string(), "KEKL".to_string()]).unwrap();
+
+        assert_eq!(cycle.input(), "hi KEKW");
+
+        cycle.next();
+        assert_eq!(cycle.input(), "hi KEKL");
+
+        cycle.next();
+        cycle.previous();
+        cycle.previous();
+        assert_eq!(cycle.input(), "hi KEKW");
+        assert_eq!(cycle.original(), "hi KE");
+
+        let cycle = CompletionCycle::new("KE", vec!["KEKW".to_string()]).unwrap();
+
+        assert_eq!(cycle.input(), "KEKW");
+    }
+
+    #[test]
+    fn test_ranking_decays() {
+        let now = Local::now();
+        let mut ranking = CompletionRanking::default();
+
+        ranking.record("channel", "old", now - TimeDelta::days(28));
+        ranking.record("channel", "old", now - TimeDelta::days(28));
+        ranking.record("channel", "new", now);
+
+        // Two uses four weeks ago are worth half of a use today
+        assert!((ranking.score("channel", "old", &now) - 0.5).abs() < 0.01);
+        assert!(ranking.score("channel", "new", &now) > ranking.score("channel", "old", &now));
+
+        let stored = ranking.to_storage(&(now + TimeDelta::days(365)));
+
+        assert!(stored.is_empty());
+    }
+
+    #[test]
+    fn test_ranking_storage() {
+        let now = Local::now();
+        let mut ranking = CompletionRanking::default();
+
+        ranking.record("channel", "@xithrius", now);
+
+        let loaded = CompletionRanking::from_storage(&ranking.to_storage(&now));
+
+        assert!((loaded.score("channel", "@xithrius", &now) - 1.0).abs() < 0.01);
+        assert!(
+            CompletionRanking::from_storage(&["invalid".to_string()])
+                .words("invalid")
+                .next()
+                .is_none()
+        );
+    }
+}
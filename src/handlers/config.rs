@@ -1,29 +1,45 @@
 use std::{
     cell::RefCell,
+    collections::BTreeMap,
     env,
-    fs::{File, create_dir_all, read_to_string},
+    fmt::Display,
+    fs::{File, create_dir_all, metadata, read_to_string, write},
     io::Write,
     path::Path,
     rc::Rc,
     str::FromStr,
+    time::{Duration, Instant, SystemTime},
 };
 
-use color_eyre::eyre::{Error, Result, bail};
-use serde::{Deserialize, Serialize};
+use color_eyre::eyre::{Error, Result, WrapErr, bail, eyre};
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, Visitor},
+};
 use serde_with::DeserializeFromStr;
-use tokio::{runtime::Handle, task};
-use tui::widgets::BorderType;
+use tui::{
+    layout::Alignment,
+    style::Color,
+    widgets::{Block, BorderType, Borders, Padding},
+};
 
 use crate::{
     emotes::support_graphics_protocol,
     handlers::{
         args::{Cli, merge_args_into_config},
-        interactive::interactive_config,
+        interactive::{interactive_config, twitch_settings},
+        schema::{DEFAULT_CONFIG, unknown_keys},
         state::State,
+        themes::{Theme, set_theme},
+        user_input::{events::Key, keybinds::Action},
     },
+    twitch::moderation::LadderStep,
     utils::{
         emotes::emotes_enabled,
         pathing::{cache_path, config_path},
+        sounds::Sound,
+        styles::border_style,
+        time::{datetime_pattern, parse_duration},
     },
 };
 
@@ -42,6 +58,26 @@ pub struct CompleteConfig {
     pub filters: FiltersConfig,
     /// How everything looks to the user.
     pub frontend: FrontendConfig,
+    /// Where emotes come from.
+    pub emotes: EmotesConfig,
+    /// When moderation events are worth an alert.
+    pub alerts: AlertsConfig,
+    /// Desktop notifications about what happens while the terminal is not being looked at.
+    pub notifications: NotificationsConfig,
+    /// Quick actions on the authors of messages, for moderators.
+    pub moderation: ModerationConfig,
+    /// Sounds played when something happens.
+    pub sounds: SoundsConfig,
+    /// How long answers from the Twitch API are reused for.
+    pub cache: CacheConfig,
+    /// Writing the messages of channels to files.
+    pub logs: LogsConfig,
+    /// Styling messages that match keywords.
+    pub highlights: HighlightsConfig,
+    /// Keys that were rebound from their defaults.
+    pub keybinds: KeybindsConfig,
+    /// How individual widgets look, overriding the frontend options for every widget.
+    pub widgets: WidgetsConfig,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -53,8 +89,21 @@ pub struct TwitchConfig {
     pub channel: String,
     /// The IRC channel to connect to.
     pub server: String,
+    /// How to connect to the IRC server.
+    pub transport: Transport,
+    /// If users joining and leaving channels should be received.
+    pub membership: bool,
+    /// Stop showing users joining and leaving a channel once it has more chatters than this, 0 for no limit.
+    pub membership_chatter_limit: usize,
+    /// Only request what is needed to chat, for huge channels or slow connections.
+    /// Badges, Twitch emotes, and moderation of specific messages will be unavailable.
+    pub low_bandwidth: bool,
+    /// Only show what moderation commands would do, rather than running them.
+    pub dry_run: bool,
     /// The authentication token for the IRC.
     pub token: Option<String>,
+    /// The client ID of an application registered with Twitch, to log in through the browser when there is no token.
+    pub client_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -70,15 +119,34 @@ pub struct TerminalConfig {
     pub verbose: bool,
     /// What state the application should start in.
     pub first_state: State,
+    /// If only the IRC connection and Twitch's own API should be used,
+    /// without emotes, image downloads, traffic statistics, or logging to a file.
+    pub strict_privacy: bool,
+    /// If changes to the config file should be applied while running.
+    pub watch_config: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(default)]
 pub struct StorageConfig {
     /// If previous channels switched to should be tracked.
     pub channels: bool,
     /// If previous username mentions should be tracked.
     pub mentions: bool,
+    /// If raids should be kept between sessions.
+    pub raids: bool,
+    /// If the emotes and usernames sent in each channel should be counted, to rank completions.
+    pub completions: bool,
+    /// If messages and whispers that have not been sent yet should be kept, to send them after a restart.
+    pub outbox: bool,
+    /// If the nicknames given to users should be kept between sessions.
+    pub nicknames: bool,
+    /// If the users ignored with `/ignore` should be kept between sessions.
+    pub ignored: bool,
+    /// If the messages sent in each channel should be kept between sessions, to recall them with Up and Down.
+    pub history: bool,
+    /// If the storage file should be encrypted.
+    pub encrypted: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -88,6 +156,248 @@ pub struct FiltersConfig {
     pub enabled: bool,
     /// If the regex filters should be reversed.
     pub reversed: bool,
+    /// If channel point redemptions should be left out of chat.
+    pub hide_redemptions: bool,
+    /// If users blocked on Twitch should be ignored, and users ignored with `/ignore` blocked on Twitch.
+    pub twitch_blocks: bool,
+    /// Dimming or hiding messages that are not in the expected languages.
+    pub language: LanguageFilterConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LanguageFilterConfig {
+    /// The languages messages are expected to be in, as two letter codes such as `en`.
+    pub languages: Vec<String>,
+    /// The channels the language filter is used in.
+    pub channels: Vec<String>,
+    /// How sure the detected language has to be, from 0 to 1, for a message to be filtered.
+    pub confidence: f32,
+    /// If messages in other languages are hidden, rather than dimmed.
+    pub hide: bool,
+}
+
+impl Default for LanguageFilterConfig {
+    fn default() -> Self {
+        Self {
+            languages: vec![],
+            channels: vec![],
+            confidence: 0.75,
+            hide: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct EmotesConfig {
+    /// Which provider's emote is shown when several providers have an emote with the same name,
+    /// from the highest priority to the lowest.
+    pub priority: Vec<EmoteProvider>,
+}
+
+impl Default for EmotesConfig {
+    fn default() -> Self {
+        Self {
+            priority: vec![
+                EmoteProvider::SevenTV,
+                EmoteProvider::BetterTTV,
+                EmoteProvider::FrankerFaceZ,
+            ],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AlertsConfig {
+    /// If alerts should be raised at all.
+    pub enabled: bool,
+    /// The amount of messages held by AutoMod within the window that raises a warning.
+    pub automod_spike: usize,
+    /// The amount of seconds in which held messages are counted.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    pub automod_spike_window: u64,
+    /// The amount of bans and timeouts within the window that raises a critical alert.
+    pub mass_timeout: usize,
+    /// The amount of seconds in which bans and timeouts are counted.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    pub mass_timeout_window: u64,
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            automod_spike: 5,
+            automod_spike_window: 60,
+            mass_timeout: 5,
+            mass_timeout_window: 30,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// If being mentioned shows a desktop notification.
+    pub mentions: bool,
+    /// If receiving a whisper shows a desktop notification.
+    pub whispers: bool,
+    /// If a followed channel going live shows a desktop notification.
+    pub live: bool,
+    /// The amount of seconds between checking if followed channels went live.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    pub live_interval: u64,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            mentions: false,
+            whispers: false,
+            live: false,
+            live_interval: 120,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SoundsConfig {
+    /// The command playing sound files, using `afplay` on macOS and `paplay` elsewhere if it is not set.
+    pub player: Option<String>,
+    /// The sound played when being mentioned.
+    pub mention: Sound,
+    /// The sound played when receiving a whisper.
+    pub whisper: Sound,
+    /// The sound played when someone subscribes or gifts subs.
+    pub subscription: Sound,
+    /// The sound played when a joined channel is raided.
+    pub raid: Sound,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ModerationConfig {
+    /// The actions taken on the author of a selected message with `b` and then a digit from 1, from the lightest to the heaviest.
+    pub ladder: Vec<LadderStep>,
+    /// The reason given for the actions, with `{user}`, `{action}`, and `{message}` filled in. Empty to give none.
+    pub reason: String,
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self {
+            ladder: vec![
+                LadderStep::Timeout(60),
+                LadderStep::Timeout(10 * 60),
+                LadderStep::Timeout(60 * 60),
+                LadderStep::Timeout(24 * 60 * 60),
+                LadderStep::Ban,
+            ],
+            reason: String::new(),
+        }
+    }
+}
+
+/// The keys taking each action that was rebound, with the defaults used for the rest.
+pub type KeybindsConfig = BTreeMap<Action, Vec<Key>>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// The amount of seconds the IDs of users are reused for, 0 to always look them up again.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    pub users: u64,
+    /// The amount of seconds the streams of channels are reused for.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    pub streams: u64,
+    /// The amount of seconds the categories found by name are reused for.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    pub games: u64,
+    /// The amount of seconds the badges of channels are reused for.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    pub badges: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            users: 24 * 60 * 60,
+            streams: 30,
+            games: 24 * 60 * 60,
+            badges: 60 * 60,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LogsConfig {
+    /// If every message of the joined channels should be written to `logs/<channel>/<date>.log`.
+    pub enabled: bool,
+    /// Each line of the logs, with {time}, {author}, and {message} filled in.
+    pub format: String,
+    /// How {time} is written, in the format of `strftime`.
+    pub datetime_format: String,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: "[{time}] {author}: {message}".to_string(),
+            datetime_format: "%H:%M:%S".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct HighlightsConfig {
+    #[serde(rename = "rule")]
+    pub rules: Vec<HighlightRule>,
+}
+
+/// Messages with text matching a pattern are given a background, separately from filters.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct HighlightRule {
+    /// A regex, matched against the text of messages.
+    pub pattern: String,
+    /// What the rule is called in notifications, and in the `highlights` table of themes.
+    pub name: Option<String>,
+    /// The background of matching messages, rather than the one from the theme.
+    pub color: Option<Color>,
+    /// If matching messages show a desktop notification.
+    pub notify: bool,
+    /// If the pattern only matches text with the same case.
+    pub case_sensitive: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct WidgetStyle {
+    /// The border of the widget, rather than the `border_type` of the frontend.
+    pub border_type: Option<Border>,
+    /// Where the title is placed along the top border.
+    pub title_alignment: TitleAlignment,
+    /// The amount of columns between the left and right borders and the contents.
+    pub padding: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct WidgetsConfig {
+    /// The chat window, along with the sidebars next to it.
+    pub chat: WidgetStyle,
+    /// The boxes that can be typed in, such as the chat input and filters.
+    pub input: WidgetStyle,
+    /// The list of followed channels.
+    pub following: WidgetStyle,
+    /// Windows opened on top of chat, such as whispers and mentions.
+    pub popups: WidgetStyle,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -96,31 +406,66 @@ pub struct FrontendConfig {
     /// If the time and date is to be shown.
     pub show_datetimes: bool,
     /// The format of string that will show up in the terminal.
-    pub datetime_format: String,
+    /// If not set, the format is built from the clock, seconds, and date order options.
+    #[serde(alias = "timestamp_format")]
+    pub datetime_format: Option<String>,
+    /// If times should be shown with a 12 hour clock, rather than a 24 hour one.
+    pub twelve_hour_clock: bool,
+    /// If seconds are shown in times.
+    pub show_seconds: bool,
+    /// The order of the day, month, and year in dates.
+    pub date_order: DateOrder,
     /// If the username should be shown.
     pub username_shown: bool,
     /// The color palette.
     pub palette: Palette,
+    /// If users are shown in the color they picked on Twitch, with the palette for those that did not pick one.
+    pub twitch_colors: bool,
+    /// The contrast ratio that username colors are made to have at least against the background of the theme.
+    pub username_contrast: f64,
     /// Show Title with time and channel.
     pub title_shown: bool,
     /// The amount of space between the chat window and the terminal border.
     pub margin: u16,
-    /// Show twitch badges next to usernames.
-    pub badges: bool,
-    /// Theme, being either light or dark.
-    pub theme: Theme,
+    /// If borders, padding, and margins should be left out to fit as many messages as possible.
+    pub compact: bool,
+    /// How twitch badges are shown next to usernames.
+    pub badges: BadgeStyle,
+    /// The name of the theme, being dark, light, or one in the themes directory next to the config.
+    pub theme: String,
     /// If the username should be highlighted when it appears in chat.
     pub username_highlight: bool,
+    /// How messages that mention the user are highlighted.
+    pub mention_style: MentionStyle,
+    /// How the title, category, uptime, and viewers of the current stream are shown above chat.
+    pub stream_info: StreamInfoStyle,
+    /// If deleted messages should be crossed out, rather than replaced with a placeholder.
+    pub show_deleted_messages: bool,
+    /// The amount of lines long messages are collapsed to until they are expanded, 0 to never collapse them.
+    pub collapse_lines: usize,
     /// If there should be state tabs shown on the bottom of the terminal.
     pub state_tabs: bool,
+    /// If a line is shown along the bottom with the connection, channel, mode, mentions, and channel modes.
+    pub status_bar: bool,
     /// The shape of the cursor in insert boxes.
     pub cursor_shape: CursorType,
     /// If the cursor should be blinking.
     pub blinking_cursor: bool,
     /// If the scrolling should be inverted.
     pub inverted_scrolling: bool,
+    /// If the mouse can scroll, select messages, and click on lists.
+    /// Without it, text is selected by the terminal itself.
+    pub mouse: bool,
+    /// If links in chat can be clicked on, in terminals that are known to support it.
+    pub hyperlinks: bool,
+    /// If `:shortcode:` is replaced with its emoji while typing in the chat input.
+    pub emoji_shortcodes: bool,
     /// If scroll offset integer should be shown.
     pub show_scroll_offset: bool,
+    /// If a scrollbar is shown on the border of chat while it is scrolled up.
+    pub scrollbar: bool,
+    /// If a line is drawn in chat above the messages that arrived while the terminal was not focused.
+    pub unread_marker: bool,
     /// If twitch emotes should be displayed (requires kitty terminal).
     pub twitch_emotes: bool,
     /// If betterttv emotes should be displayed (requires kitty terminal).
@@ -129,10 +474,25 @@ pub struct FrontendConfig {
     pub seventv_emotes: bool,
     /// If frankerfacez emotes should be displayed (requires kitty terminal).
     pub frankerfacez_emotes: bool,
+    /// If animated emotes should play, rather than only showing their first frame.
+    pub animated_emotes: bool,
+    /// If cheers such as `Cheer100` should be shown as their images (requires twitch emotes).
+    pub cheermotes: bool,
+    /// The amount of seconds in which gift subs from the same user are combined into one line, 0 to show each one.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    pub gift_sub_window: u64,
+    /// The color of subscriptions and resubscriptions in chat.
+    pub subscription_color: Color,
+    /// The color of gift subs in chat.
+    pub gift_sub_color: Color,
     /// Channels to always be displayed in the start screen.
     pub favorite_channels: Vec<String>,
     /// The amount of recent channels that should be shown on the start screen.
     pub recent_channel_count: u16,
+    /// How the rows of searchable lists, such as the followed channels, are matched by what is typed.
+    pub matching: Matching,
+    /// The order of the followed channels.
+    pub following_sort: FollowingSort,
     /// A border wrapper around [`BorderType`].
     pub border_type: Border,
     /// If chat border should be hidden
@@ -141,6 +501,41 @@ pub struct FrontendConfig {
     pub right_align_usernames: bool,
     /// Do not display the window size warning.
     pub show_unsupported_screen_size: bool,
+    /// A command whose output is shown in the chat title, such as a now-playing script.
+    pub status_command: Option<String>,
+    /// The amount of seconds between runs of the status command.
+    #[serde(deserialize_with = "deserialize_seconds")]
+    pub status_command_interval: u64,
+    /// The format of the terminal window title, with `{channel}`, `{mentions}`, and `{live}` filled in.
+    /// The title is left alone if not set.
+    pub window_title: Option<String>,
+    /// If the window title should also be used as the name of the tmux window.
+    pub tmux_window_name: bool,
+}
+
+impl TwitchConfig {
+    /// Join and part events are dropped in low bandwidth mode, even if they were asked for.
+    pub const fn membership_enabled(&self) -> bool {
+        self.membership && !self.low_bandwidth
+    }
+}
+
+impl FrontendConfig {
+    /// The amount of space between the chat window and the terminal border.
+    pub const fn margin(&self) -> u16 {
+        if self.compact { 0 } else { self.margin }
+    }
+
+    /// The amount of rows around the messages in chat taken up by its border and title.
+    pub const fn chat_frame_height(&self) -> u16 {
+        if self.compact {
+            if self.title_shown { 1 } else { 0 }
+        } else if self.hide_chat_border {
+            0
+        } else {
+            2
+        }
+    }
 }
 
 impl Default for TwitchConfig {
@@ -149,7 +544,30 @@ impl Default for TwitchConfig {
             username: String::new(),
             channel: String::new(),
             server: "irc.chat.twitch.tv".to_string(),
+            transport: Transport::default(),
+            membership: false,
+            membership_chatter_limit: 200,
+            low_bandwidth: false,
+            dry_run: false,
             token: None,
+            client_id: None,
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            channels: false,
+            mentions: false,
+            raids: false,
+            completions: false,
+            outbox: false,
+            // Nicknames are given on purpose, unlike the history of channels and mentions
+            nicknames: true,
+            ignored: true,
+            history: false,
+            encrypted: false,
         }
     }
 }
@@ -162,6 +580,8 @@ impl Default for TerminalConfig {
             log_file: None,
             verbose: false,
             first_state: State::default(),
+            strict_privacy: false,
+            watch_config: true,
         }
     }
 }
@@ -170,29 +590,56 @@ impl Default for FrontendConfig {
     fn default() -> Self {
         Self {
             show_datetimes: true,
-            datetime_format: "%a %b %e %T %Y".to_string(),
+            datetime_format: None,
+            twelve_hour_clock: false,
+            show_seconds: true,
+            date_order: DateOrder::default(),
             username_shown: true,
             palette: Palette::default(),
+            twitch_colors: true,
+            username_contrast: 3.,
             title_shown: true,
             margin: 0,
-            badges: false,
-            theme: Theme::default(),
+            compact: false,
+            badges: BadgeStyle::default(),
+            theme: "dark".to_string(),
             username_highlight: true,
+            mention_style: MentionStyle::default(),
+            stream_info: StreamInfoStyle::default(),
+            show_deleted_messages: false,
+            collapse_lines: 6,
             state_tabs: false,
+            status_bar: true,
             cursor_shape: CursorType::default(),
             blinking_cursor: false,
             inverted_scrolling: false,
+            mouse: true,
+            hyperlinks: true,
+            emoji_shortcodes: true,
             show_scroll_offset: false,
+            scrollbar: true,
+            unread_marker: true,
             twitch_emotes: false,
             betterttv_emotes: false,
             seventv_emotes: false,
             frankerfacez_emotes: false,
+            animated_emotes: true,
+            cheermotes: false,
+            gift_sub_window: 10,
+            subscription_color: Color::LightMagenta,
+            gift_sub_color: Color::LightCyan,
             favorite_channels: vec![],
             recent_channel_count: 5,
+            matching: Matching::default(),
+            following_sort: FollowingSort::default(),
             border_type: Border::default(),
             hide_chat_border: false,
             right_align_usernames: false,
             show_unsupported_screen_size: true,
+            status_command: None,
+            status_command_interval: 5,
+            window_title: None,
+            tmux_window_name: false,
         }
     }
 }
@@ -226,30 +673,402 @@ impl FromStr for Palette {
     }
 }
 
+#[derive(Serialize, DeserializeFromStr, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    /// IRC over TLS, on port 6697.
+    Tcp,
+    /// IRC over a secure WebSocket, on port 443, for networks that only allow web traffic.
+    WebSocket,
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self::Tcp
+    }
+}
+
+impl FromStr for Transport {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "tcp" => Ok(Self::Tcp),
+            "websocket" | "ws" => Ok(Self::WebSocket),
+            _ => bail!("Transport '{}' cannot be deserialized", s),
+        }
+    }
+}
+
+impl Display for Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp => write!(f, "tcp"),
+            Self::WebSocket => write!(f, "websocket"),
+        }
+    }
+}
+
+#[derive(Serialize, DeserializeFromStr, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MentionStyle {
+    /// Mentions look like any other message.
+    None,
+    Bold,
+    /// Mentions are shown with a different background color.
+    Background,
+    /// The foreground and background colors of mentions are swapped.
+    Reverse,
+}
+
+impl Default for MentionStyle {
+    fn default() -> Self {
+        Self::Background
+    }
+}
+
+impl FromStr for MentionStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "bold" => Ok(Self::Bold),
+            "background" => Ok(Self::Background),
+            "reverse" => Ok(Self::Reverse),
+            _ => bail!("Mention style '{}' cannot be deserialized", s),
+        }
+    }
+}
+
+impl Display for MentionStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Bold => write!(f, "bold"),
+            Self::Background => write!(f, "background"),
+            Self::Reverse => write!(f, "reverse"),
+        }
+    }
+}
+
+#[derive(Serialize, DeserializeFromStr, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamInfoStyle {
+    Hidden,
+    /// Everything on a single line.
+    Line,
+    /// The title on its own line, with the rest below it.
+    Full,
+}
+
+impl StreamInfoStyle {
+    /// The style after this one, to go through them with a key.
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Hidden => Self::Full,
+            Self::Full => Self::Line,
+            Self::Line => Self::Hidden,
+        }
+    }
+}
+
+impl Default for StreamInfoStyle {
+    fn default() -> Self {
+        Self::Hidden
+    }
+}
+
+impl FromStr for StreamInfoStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hidden" | "none" => Ok(Self::Hidden),
+            "line" => Ok(Self::Line),
+            "full" => Ok(Self::Full),
+            _ => bail!("Stream info style '{}' cannot be deserialized", s),
+        }
+    }
+}
+
+impl Display for StreamInfoStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hidden => write!(f, "hidden"),
+            Self::Line => write!(f, "line"),
+            Self::Full => write!(f, "full"),
+        }
+    }
+}
+
+#[derive(Serialize, DeserializeFromStr, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Matching {
+    /// The typed characters appear in order, with anything between them.
+    Fuzzy,
+    /// The typed text appears anywhere.
+    Substring,
+    /// The typed text appears at the start.
+    Prefix,
+    /// The typed text is a regular expression.
+    Regex,
+}
+
+impl Matching {
+    /// The way of matching after this one, to go through them with a key.
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Fuzzy => Self::Substring,
+            Self::Substring => Self::Prefix,
+            Self::Prefix => Self::Regex,
+            Self::Regex => Self::Fuzzy,
+        }
+    }
+}
+
+impl Default for Matching {
+    fn default() -> Self {
+        Self::Fuzzy
+    }
+}
+
+impl FromStr for Matching {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fuzzy" => Ok(Self::Fuzzy),
+            "substring" => Ok(Self::Substring),
+            "prefix" => Ok(Self::Prefix),
+            "regex" => Ok(Self::Regex),
+            _ => bail!("Matching '{}' cannot be deserialized", s),
+        }
+    }
+}
+
+impl Display for Matching {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fuzzy => write!(f, "fuzzy"),
+            Self::Substring => write!(f, "substring"),
+            Self::Prefix => write!(f, "prefix"),
+            Self::Regex => write!(f, "regex"),
+        }
+    }
+}
+
+#[derive(Serialize, DeserializeFromStr, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FollowingSort {
+    /// The order Twitch gives, the most recently followed first.
+    Followed,
+    /// Live channels first, the ones with the most viewers first.
+    Viewers,
+    /// By name.
+    Alphabetical,
+    /// The channels switched to most recently first, which are only known if `channels` is stored.
+    Watched,
+}
+
+impl FollowingSort {
+    /// The order after this one, to go through them with a key.
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Followed => Self::Viewers,
+            Self::Viewers => Self::Alphabetical,
+            Self::Alphabetical => Self::Watched,
+            Self::Watched => Self::Followed,
+        }
+    }
+}
+
+impl Default for FollowingSort {
+    fn default() -> Self {
+        Self::Followed
+    }
+}
+
+impl FromStr for FollowingSort {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "followed" => Ok(Self::Followed),
+            "viewers" => Ok(Self::Viewers),
+            "alphabetical" => Ok(Self::Alphabetical),
+            "watched" => Ok(Self::Watched),
+            _ => bail!("Following sort '{}' cannot be deserialized", s),
+        }
+    }
+}
+
+impl Display for FollowingSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Followed => write!(f, "followed"),
+            Self::Viewers => write!(f, "viewers"),
+            Self::Alphabetical => write!(f, "alphabetical"),
+            Self::Watched => write!(f, "watched"),
+        }
+    }
+}
+
+#[derive(Serialize, DeserializeFromStr, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmoteProvider {
+    #[serde(rename = "betterttv")]
+    BetterTTV,
+    #[serde(rename = "seventv")]
+    SevenTV,
+    #[serde(rename = "frankerfacez")]
+    FrankerFaceZ,
+}
+
+impl FromStr for EmoteProvider {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "betterttv" | "bttv" => Ok(Self::BetterTTV),
+            "seventv" | "7tv" => Ok(Self::SevenTV),
+            "frankerfacez" | "ffz" => Ok(Self::FrankerFaceZ),
+            _ => bail!("Emote provider '{}' cannot be deserialized", s),
+        }
+    }
+}
+
+impl Display for EmoteProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BetterTTV => write!(f, "betterttv"),
+            Self::SevenTV => write!(f, "seventv"),
+            Self::FrankerFaceZ => write!(f, "frankerfacez"),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BadgeStyle {
+    Hidden,
+    /// A symbol for moderators, VIPs, subscribers, and Prime Gaming users.
+    Unicode,
+    /// The badge images from Twitch (requires kitty terminal).
+    Images,
+}
+
+impl Default for BadgeStyle {
+    fn default() -> Self {
+        Self::Hidden
+    }
+}
+
+impl FromStr for BadgeStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hidden" | "none" => Ok(Self::Hidden),
+            "unicode" => Ok(Self::Unicode),
+            "images" => Ok(Self::Images),
+            _ => bail!("Badge style '{}' cannot be deserialized", s),
+        }
+    }
+}
+
+impl Display for BadgeStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hidden => write!(f, "hidden"),
+            Self::Unicode => write!(f, "unicode"),
+            Self::Images => write!(f, "images"),
+        }
+    }
+}
+
+// Badges used to be either on or off, configs from back then should still work.
+impl<'de> Deserialize<'de> for BadgeStyle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BadgeStyleVisitor;
+
+        impl Visitor<'_> for BadgeStyleVisitor {
+            type Value = BadgeStyle;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "a badge style, or a boolean")
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(if v {
+                    BadgeStyle::Unicode
+                } else {
+                    BadgeStyle::Hidden
+                })
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(BadgeStyleVisitor)
+    }
+}
+
+/// Lengths of time can be given in seconds, or as a duration such as `1h30m`.
+fn deserialize_seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    struct SecondsVisitor;
+
+    impl Visitor<'_> for SecondsVisitor {
+        type Value = u64;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "a number of seconds, or a duration such as \"1h30m\"")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            u64::try_from(v).map_err(|_| E::custom("a duration cannot be negative"))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            parse_duration(v)
+                .map(|duration| duration.as_secs())
+                .map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_any(SecondsVisitor)
+}
+
 #[derive(Serialize, DeserializeFromStr, Debug, Clone)]
-#[serde(rename_all = "lowercase")]
-pub enum Theme {
-    Dark,
-    Light,
-
-    #[allow(dead_code)]
-    Custom,
+pub enum DateOrder {
+    #[serde(rename = "ymd")]
+    YearMonthDay,
+    #[serde(rename = "dmy")]
+    DayMonthYear,
+    #[serde(rename = "mdy")]
+    MonthDayYear,
 }
 
-impl Default for Theme {
+impl Default for DateOrder {
     fn default() -> Self {
-        Self::Dark
+        Self::YearMonthDay
     }
 }
 
-impl FromStr for Theme {
+impl FromStr for DateOrder {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
-            "dark" => Ok(Self::Dark),
-            "light" => Ok(Self::Light),
-            _ => bail!("Theme '{}' cannot be deserialized", s),
+            "ymd" | "iso" => Ok(Self::YearMonthDay),
+            "dmy" => Ok(Self::DayMonthYear),
+            "mdy" | "us" => Ok(Self::MonthDayYear),
+            _ => bail!("Date order '{}' cannot be deserialized", s),
         }
     }
 }
@@ -323,6 +1142,91 @@ impl From<Border> for BorderType {
     }
 }
 
+#[derive(Serialize, DeserializeFromStr, Debug, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum TitleAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl Default for TitleAlignment {
+    fn default() -> Self {
+        Self::Left
+    }
+}
+
+impl FromStr for TitleAlignment {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "left" => Ok(Self::Left),
+            "center" | "centre" => Ok(Self::Center),
+            "right" => Ok(Self::Right),
+            _ => bail!("Title alignment '{}' cannot be deserialized", s),
+        }
+    }
+}
+
+impl From<TitleAlignment> for Alignment {
+    fn from(val: TitleAlignment) -> Self {
+        match val {
+            TitleAlignment::Left => Self::Left,
+            TitleAlignment::Center => Self::Center,
+            TitleAlignment::Right => Self::Right,
+        }
+    }
+}
+
+/// The widgets that can be styled on their own in [`WidgetsConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Widget {
+    Chat,
+    Input,
+    Following,
+    Popup,
+}
+
+impl CompleteConfig {
+    const fn widget_style(&self, widget: Widget) -> &WidgetStyle {
+        match widget {
+            Widget::Chat => &self.widgets.chat,
+            Widget::Input => &self.widgets.input,
+            Widget::Following => &self.widgets.following,
+            Widget::Popup => &self.widgets.popups,
+        }
+    }
+
+    /// The border of a widget, falling back to the border of every widget.
+    pub fn border_type(&self, widget: Widget) -> BorderType {
+        self.widget_style(widget)
+            .border_type
+            .clone()
+            .unwrap_or_else(|| self.frontend.border_type.clone())
+            .into()
+    }
+
+    /// The amount of columns between the left and right borders of a widget and its contents.
+    pub const fn padding(&self, widget: Widget) -> u16 {
+        if self.frontend.compact {
+            0
+        } else {
+            self.widget_style(widget).padding
+        }
+    }
+
+    /// A bordered block for a widget, to which the title is added.
+    pub fn block<'a>(&self, widget: Widget) -> Block<'a> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(self.border_type(widget))
+            .border_style(border_style())
+            .title_alignment(self.widget_style(widget).title_alignment.clone().into())
+            .padding(Padding::horizontal(self.padding(widget)))
+    }
+}
+
 pub trait ToVec<T> {
     fn to_vec(&self) -> Vec<T>;
 }
@@ -333,6 +1237,18 @@ impl ToVec<(String, String)> for TwitchConfig {
             ("Username".to_string(), self.username.to_string()),
             ("Channel".to_string(), self.channel.to_string()),
             ("Server".to_string(), self.server.to_string()),
+            ("Transport".to_string(), self.transport.to_string()),
+            ("Membership".to_string(), self.membership.to_string()),
+            (
+                "Membership chatter limit".to_string(),
+                self.membership_chatter_limit.to_string(),
+            ),
+            ("Low bandwidth".to_string(), self.low_bandwidth.to_string()),
+            ("Dry run".to_string(), self.dry_run.to_string()),
+            (
+                "Client ID".to_string(),
+                self.client_id.clone().unwrap_or_default(),
+            ),
         ]
     }
 }
@@ -350,6 +1266,11 @@ impl ToVec<(String, String)> for TerminalConfig {
                 self.log_file.clone().map_or("None".to_string(), |f| f),
             ),
             ("First state".to_string(), self.first_state.to_string()),
+            (
+                "Strict privacy".to_string(),
+                self.strict_privacy.to_string(),
+            ),
+            ("Watch config".to_string(), self.watch_config.to_string()),
         ]
     }
 }
@@ -359,6 +1280,16 @@ impl ToVec<(String, String)> for StorageConfig {
         vec![
             ("Channels enabled".to_string(), self.channels.to_string()),
             ("Mentions enabled".to_string(), self.mentions.to_string()),
+            ("Raids enabled".to_string(), self.raids.to_string()),
+            (
+                "Completions enabled".to_string(),
+                self.completions.to_string(),
+            ),
+            ("Outbox enabled".to_string(), self.outbox.to_string()),
+            ("Nicknames enabled".to_string(), self.nicknames.to_string()),
+            ("Ignored enabled".to_string(), self.ignored.to_string()),
+            ("History enabled".to_string(), self.history.to_string()),
+            ("Encrypted".to_string(), self.encrypted.to_string()),
         ]
     }
 }
@@ -368,6 +1299,21 @@ impl ToVec<(String, String)> for FiltersConfig {
         vec![
             ("Enabled".to_string(), self.enabled.to_string()),
             ("Reversed".to_string(), self.reversed.to_string()),
+            (
+                "Hide redemptions".to_string(),
+                self.hide_redemptions.to_string(),
+            ),
+            ("Languages".to_string(), self.language.languages.join(", ")),
+            (
+                "Language channels".to_string(),
+                self.language.channels.join(", "),
+            ),
+            (
+                "Language confidence".to_string(),
+                self.language.confidence.to_string(),
+            ),
+            ("Hide languages".to_string(), self.language.hide.to_string()),
+            ("Twitch blocks".to_string(), self.twitch_blocks.to_string()),
         ]
     }
 }
@@ -381,22 +1327,44 @@ impl ToVec<(String, String)> for FrontendConfig {
             ),
             (
                 "Datetime format".to_string(),
-                self.datetime_format.to_string(),
+                datetime_pattern(self).to_string(),
             ),
+            (
+                "12 hour clock".to_string(),
+                self.twelve_hour_clock.to_string(),
+            ),
+            ("Show seconds".to_string(), self.show_seconds.to_string()),
             (
                 "Username shown".to_string(),
                 self.username_shown.to_string(),
             ),
             // ("".to_string(), self.palette.to_string()),
+            ("Twitch colors".to_string(), self.twitch_colors.to_string()),
+            (
+                "Username contrast".to_string(),
+                self.username_contrast.to_string(),
+            ),
             ("Title shown".to_string(), self.title_shown.to_string()),
             ("Margin".to_string(), self.margin.to_string()),
+            ("Compact".to_string(), self.compact.to_string()),
             ("Badges".to_string(), self.badges.to_string()),
-            // ("".to_string(), self.theme.to_string()),
+            ("Theme".to_string(), self.theme.clone()),
             (
                 "Username highlight".to_string(),
                 self.username_highlight.to_string(),
             ),
+            ("Mention style".to_string(), self.mention_style.to_string()),
+            ("Stream info".to_string(), self.stream_info.to_string()),
+            (
+                "Deleted messages shown".to_string(),
+                self.show_deleted_messages.to_string(),
+            ),
+            (
+                "Collapse lines".to_string(),
+                self.collapse_lines.to_string(),
+            ),
             ("State tabs".to_string(), self.state_tabs.to_string()),
+            ("Status bar".to_string(), self.status_bar.to_string()),
             // ("".to_string(), self.cursor_shape.to_string()),
             (
                 "Blinking cursor".to_string(),
@@ -406,10 +1374,18 @@ impl ToVec<(String, String)> for FrontendConfig {
                 "Inverted scrolling".to_string(),
                 self.inverted_scrolling.to_string(),
             ),
+            ("Mouse".to_string(), self.mouse.to_string()),
+            ("Hyperlinks".to_string(), self.hyperlinks.to_string()),
+            (
+                "Emoji shortcodes".to_string(),
+                self.emoji_shortcodes.to_string(),
+            ),
             (
                 "Scroll offset shown".to_string(),
                 self.show_scroll_offset.to_string(),
             ),
+            ("Scrollbar".to_string(), self.scrollbar.to_string()),
+            ("Unread marker".to_string(), self.unread_marker.to_string()),
             ("Twitch emotes".to_string(), self.twitch_emotes.to_string()),
             (
                 "BetterTTV emotes".to_string(),
@@ -423,21 +1399,178 @@ impl ToVec<(String, String)> for FrontendConfig {
                 "FrankerFacez emotes".to_string(),
                 self.frankerfacez_emotes.to_string(),
             ),
+            (
+                "Animated emotes".to_string(),
+                self.animated_emotes.to_string(),
+            ),
+            ("Cheermotes".to_string(), self.cheermotes.to_string()),
+            (
+                "Gift sub window".to_string(),
+                self.gift_sub_window.to_string(),
+            ),
+            (
+                "Subscription color".to_string(),
+                self.subscription_color.to_string(),
+            ),
+            (
+                "Gift sub color".to_string(),
+                self.gift_sub_color.to_string(),
+            ),
             // ("".to_string(), self.favorite_channels.to_string()),
             (
                 "Recent channel count".to_string(),
                 self.recent_channel_count.to_string(),
             ),
+            ("Matching".to_string(), self.matching.to_string()),
+            (
+                "Following sort".to_string(),
+                self.following_sort.to_string(),
+            ),
             // ("".to_string(), self.border_type.to_string()),
             (
                 "Right aligned usernames".to_string(),
                 self.right_align_usernames.to_string(),
             ),
+            (
+                "Window title".to_string(),
+                self.window_title
+                    .clone()
+                    .unwrap_or_else(|| "None".to_string()),
+            ),
+        ]
+    }
+}
+
+impl ToVec<(String, String)> for EmotesConfig {
+    fn to_vec(&self) -> Vec<(String, String)> {
+        vec![(
+            "Priority".to_string(),
+            self.priority
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(", "),
+        )]
+    }
+}
+
+impl ToVec<(String, String)> for AlertsConfig {
+    fn to_vec(&self) -> Vec<(String, String)> {
+        vec![
+            ("Enabled".to_string(), self.enabled.to_string()),
+            ("AutoMod spike".to_string(), self.automod_spike.to_string()),
+            (
+                "AutoMod spike window".to_string(),
+                self.automod_spike_window.to_string(),
+            ),
+            ("Mass timeout".to_string(), self.mass_timeout.to_string()),
+            (
+                "Mass timeout window".to_string(),
+                self.mass_timeout_window.to_string(),
+            ),
+        ]
+    }
+}
+
+impl ToVec<(String, String)> for NotificationsConfig {
+    fn to_vec(&self) -> Vec<(String, String)> {
+        vec![
+            ("Mentions".to_string(), self.mentions.to_string()),
+            ("Whispers".to_string(), self.whispers.to_string()),
+            ("Live".to_string(), self.live.to_string()),
+            ("Live interval".to_string(), self.live_interval.to_string()),
+        ]
+    }
+}
+
+impl ToVec<(String, String)> for SoundsConfig {
+    fn to_vec(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "Player".to_string(),
+                self.player.clone().unwrap_or_else(|| "default".to_string()),
+            ),
+            ("Mention".to_string(), self.mention.to_string()),
+            ("Whisper".to_string(), self.whisper.to_string()),
+            ("Subscription".to_string(), self.subscription.to_string()),
+            ("Raid".to_string(), self.raid.to_string()),
+        ]
+    }
+}
+
+impl ToVec<(String, String)> for ModerationConfig {
+    fn to_vec(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                "Ladder".to_string(),
+                self.ladder
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ),
+            ("Reason".to_string(), self.reason.clone()),
+        ]
+    }
+}
+
+impl ToVec<(String, String)> for KeybindsConfig {
+    fn to_vec(&self) -> Vec<(String, String)> {
+        self.iter()
+            .map(|(action, keys)| {
+                (
+                    action.to_string(),
+                    keys.iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                )
+            })
+            .collect()
+    }
+}
+
+impl ToVec<(String, String)> for CacheConfig {
+    fn to_vec(&self) -> Vec<(String, String)> {
+        vec![
+            ("Users".to_string(), self.users.to_string()),
+            ("Streams".to_string(), self.streams.to_string()),
+            ("Games".to_string(), self.games.to_string()),
+            ("Badges".to_string(), self.badges.to_string()),
+        ]
+    }
+}
+
+impl ToVec<(String, String)> for LogsConfig {
+    fn to_vec(&self) -> Vec<(String, String)> {
+        vec![
+            ("Enabled".to_string(), self.enabled.to_string()),
+            ("Format".to_string(), self.format.clone()),
+            ("Datetime format".to_string(), self.datetime_format.clone()),
         ]
     }
 }
 
-fn persist_config(path: &Path, config: &CompleteConfig) -> Result<()> {
+impl ToVec<(String, String)> for HighlightsConfig {
+    fn to_vec(&self) -> Vec<(String, String)> {
+        self.rules
+            .iter()
+            .map(|rule| {
+                (
+                    rule.name.clone().unwrap_or_else(|| rule.pattern.clone()),
+                    format!(
+                        "{}{}",
+                        rule.color
+                            .map_or_else(|| "theme".to_string(), |c| c.to_string()),
+                        if rule.notify { ", notifies" } else { "" }
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+pub(super) fn persist_config(path: &Path, config: &CompleteConfig) -> Result<()> {
     let toml_string = toml::to_string(&config)?;
     let mut file = File::create(path)?;
 
@@ -447,29 +1580,123 @@ fn persist_config(path: &Path, config: &CompleteConfig) -> Result<()> {
     Ok(())
 }
 
-const RAW_DEFAULT_CONFIG_URL: &str =
-    "https://raw.githubusercontent.com/Xithrius/twitch-tui/main/default-config.toml";
-
 fn persist_default_config(path: &Path) {
-    let default_config = task::block_in_place(move || {
-        Handle::current().block_on(async move {
-            reqwest::get(RAW_DEFAULT_CONFIG_URL)
-                .await
-                .unwrap()
-                .text()
-                .await
-                .unwrap()
-        })
-    });
-
     let mut file = File::create(path).unwrap();
 
-    file.write_all(default_config.as_bytes()).unwrap();
+    file.write_all(DEFAULT_CONFIG.as_bytes()).unwrap();
     drop(file);
 }
 
 impl CompleteConfig {
-    pub fn new(cli: Cli) -> Result<Self, Error> {
+    /// Turn off everything that reaches out to the network or disk beyond what chatting needs.
+    ///
+    /// Requests that get past this are refused by [`crate::utils::traffic::send_tracked`].
+    fn apply_strict_privacy(&mut self) {
+        self.frontend.twitch_emotes = false;
+        self.frontend.betterttv_emotes = false;
+        self.frontend.seventv_emotes = false;
+        self.frontend.frankerfacez_emotes = false;
+
+        if self.frontend.badges == BadgeStyle::Images {
+            self.frontend.badges = BadgeStyle::Unicode;
+        }
+
+        self.terminal.log_file = None;
+        self.logs.enabled = false;
+
+        for rule in &mut self.highlights.rules {
+            rule.notify = false;
+        }
+
+        self.notifications.mentions = false;
+        self.notifications.whispers = false;
+        self.notifications.live = false;
+    }
+
+    /// The config in the contents of a config file, with the values set by environment variables over it,
+    /// and the ones given as arguments over those. Values that are not set anywhere are the defaults.
+    fn layered(content: &str, cli: &Cli) -> Result<Self> {
+        // Errors in the file itself are shown along with where they are in it
+        let mut table: toml::Table = toml::from_str(content)
+            .map_err(|err| eyre!("Config could not be processed.\n{err}"))?;
+
+        toml::from_str::<Self>(content)
+            .map_err(|err| eyre!("Config could not be processed.\n{err}"))?;
+
+        let overrides = environment_overrides(env::vars()).into_iter().chain(
+            cli.overrides
+                .iter()
+                .map(|(path, value)| (path.clone(), value.clone())),
+        );
+
+        for (path, value) in overrides {
+            set_value(&mut table, &path, override_value(&value))?;
+        }
+
+        let unknown = unknown_keys(&table);
+
+        if !unknown.is_empty() {
+            bail!("Config could not be processed.\n{}", unknown.join("\n"));
+        }
+
+        let mut config: Self = table
+            .try_into()
+            .map_err(|err: toml::de::Error| eyre!("Config could not be processed.\n{err}"))?;
+
+        merge_args_into_config(&mut config, cli.clone());
+
+        Ok(config)
+    }
+
+    /// Take the settings of a reloaded config file that apply while running, in place.
+    /// The rest, such as the channel being chatted in or a dry run, stay as they are now.
+    pub fn merge_reloaded(&mut self, reloaded: Self) {
+        // Emotes and badge images are only loaded if they were shown from the start
+        self.frontend = FrontendConfig {
+            twitch_emotes: self.frontend.twitch_emotes,
+            betterttv_emotes: self.frontend.betterttv_emotes,
+            seventv_emotes: self.frontend.seventv_emotes,
+            frankerfacez_emotes: self.frontend.frankerfacez_emotes,
+            badges: self.frontend.badges,
+            ..reloaded.frontend
+        };
+
+        self.storage = reloaded.storage;
+        self.filters = reloaded.filters;
+        self.emotes = reloaded.emotes;
+        self.alerts = reloaded.alerts;
+        self.notifications = reloaded.notifications;
+        self.moderation = reloaded.moderation;
+        self.sounds = reloaded.sounds;
+        self.logs = reloaded.logs;
+        self.highlights = reloaded.highlights;
+        self.keybinds = reloaded.keybinds;
+        self.widgets = reloaded.widgets;
+
+        if self.terminal.strict_privacy {
+            self.apply_strict_privacy();
+        }
+    }
+
+    /// Change the settings for connecting to Twitch with the interactive wizard, and save them to the config file.
+    /// Only the Twitch section of the file is changed, so settings given as arguments are not saved along with it.
+    pub fn edit_twitch_settings(&mut self) -> Result<()> {
+        self.twitch = twitch_settings(&self.twitch);
+
+        let path_str = config_path("config.toml");
+        let path = Path::new(&path_str);
+
+        let mut saved: Self = read_to_string(path)
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default();
+
+        saved.twitch = self.twitch.clone();
+
+        persist_config(path, &saved)
+    }
+
+    pub fn new(cli: &Cli) -> Result<Self, Error> {
         let path_str = cache_path("");
 
         let p = Path::new(&path_str);
@@ -494,12 +1721,13 @@ impl CompleteConfig {
                 )
             }
         } else if let Ok(file_content) = read_to_string(p) {
-            let mut config: Self = match toml::from_str(&file_content) {
-                Ok(c) => c,
-                Err(err) => bail!("Config could not be processed. Error: {:?}", err.message()),
-            };
+            let mut config = Self::layered(&file_content, cli)?;
+
+            set_theme(Theme::load(&config.frontend.theme)?);
 
-            merge_args_into_config(&mut config, cli);
+            if config.terminal.strict_privacy {
+                config.apply_strict_privacy();
+            }
 
             let token = env::var("TWT_TOKEN").ok();
             if let Some(env_token) = token {
@@ -508,14 +1736,19 @@ impl CompleteConfig {
                 }
             }
 
+            // Without a token, one is gotten by logging in through the browser with the client ID
+            config.twitch.token = config.twitch.token.filter(|t| !t.is_empty());
+            config.twitch.client_id = config.twitch.client_id.filter(|c| !c.is_empty());
+
             {
                 let t = &config.twitch;
 
-                let check_token = t.token.as_ref().map_or("", |t| t);
-
-                if t.username.is_empty() || t.channel.is_empty() || check_token.is_empty() {
+                if t.username.is_empty()
+                    || t.channel.is_empty()
+                    || (t.token.is_none() && t.client_id.is_none())
+                {
                     bail!(
-                        "Twitch config section is missing one or more of the following: username, channel, token."
+                        "Twitch config section is missing one or more of the following: username, channel, token (or client_id to log in through the browser)."
                     );
                 }
 
@@ -543,3 +1776,268 @@ impl CompleteConfig {
         }
     }
 }
+
+/// Config file contents with a setting of the frontend section set,
+/// changing only that line so that the rest of the file is left as it was.
+fn with_frontend_setting(content: &str, key: &str, value: &toml::Value) -> String {
+    let setting_line = format!("{key} = {value}");
+
+    let mut lines = content
+        .lines()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>();
+    let mut section = None;
+    let mut frontend = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('[') {
+            section = Some(trimmed.to_string());
+
+            if trimmed == "[frontend]" {
+                frontend = Some(i);
+            }
+        } else if section.as_deref() == Some("[frontend]")
+            && trimmed.split('=').next().map(str::trim) == Some(key)
+        {
+            lines[i] = setting_line;
+
+            return lines.join("\n") + "\n";
+        }
+    }
+
+    if let Some(i) = frontend {
+        lines.insert(i + 1, setting_line);
+    } else {
+        if lines.last().is_some_and(|line| !line.is_empty()) {
+            lines.push(String::new());
+        }
+
+        lines.extend(["[frontend]".to_string(), setting_line]);
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Save a setting of the frontend section to the config file, such as one changed with a key, so that it is used the next time.
+pub fn persist_frontend_setting(key: &str, value: &toml::Value) -> Result<()> {
+    let path = config_path("config.toml");
+    let content = read_to_string(&path).unwrap_or_default();
+
+    write(&path, with_frontend_setting(&content, key, value))
+        .wrap_err_with(|| format!("{key} could not be saved to {path}"))
+}
+
+/// The start of environment variables that set config values.
+/// Sections and keys are separated by two underscores, such as `TWT_FRONTEND__THEME` for `theme` in `[frontend]`.
+const ENVIRONMENT_PREFIX: &str = "TWT_";
+
+/// The paths and values of the config values set by environment variables, such as `frontend.theme`.
+/// Variables without a section, such as `TWT_TOKEN`, are left out.
+fn environment_overrides(vars: impl Iterator<Item = (String, String)>) -> Vec<(String, String)> {
+    vars.filter_map(|(name, value)| {
+        let path = name.strip_prefix(ENVIRONMENT_PREFIX)?;
+
+        path.contains("__")
+            .then(|| (path.to_lowercase().replace("__", "."), value))
+    })
+    .collect()
+}
+
+/// A value given outside of the config file, read as TOML if it can be, such as `true`, `500`, or `["q"]`,
+/// and as a string otherwise, so that strings do not need quotes.
+fn override_value(value: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {value}"))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()))
+}
+
+/// Set a value in a config by its path, such as `frontend.theme`, adding the tables along the way that are missing.
+fn set_value(table: &mut toml::Table, path: &str, value: toml::Value) -> Result<()> {
+    let keys = path.split('.').collect::<Vec<&str>>();
+
+    let Some((last, sections)) = keys.split_last().filter(|_| !keys.contains(&"")) else {
+        bail!("{path} is not a path to a config value, such as frontend.theme");
+    };
+
+    let mut table = table;
+
+    for section in sections {
+        table = table
+            .entry(*section)
+            .or_insert_with(|| toml::Table::new().into())
+            .as_table_mut()
+            .ok_or_else(|| {
+                eyre!("{path} is not a path to a config value, as {section} is not a section")
+            })?;
+    }
+
+    table.insert((*last).to_string(), value);
+
+    Ok(())
+}
+
+/// How often the config file is checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Notices when the config file is changed while running, by when it was last modified, to apply the changes.
+pub struct ConfigWatcher {
+    /// The arguments given when starting, which are applied over the file again each time it is read.
+    cli: Cli,
+    path: String,
+    modified: Option<SystemTime>,
+    last_checked: Instant,
+}
+
+impl ConfigWatcher {
+    pub fn new(cli: Cli) -> Self {
+        let path = config_path("config.toml");
+        let modified = modified_time(&path);
+
+        Self {
+            cli,
+            path,
+            modified,
+            last_checked: Instant::now(),
+        }
+    }
+
+    /// The config in the file if it was changed since it was last checked, or the reason it could not be read.
+    pub fn reload(&mut self, current: &CompleteConfig) -> Option<Result<CompleteConfig>> {
+        if !current.terminal.watch_config || self.last_checked.elapsed() < WATCH_INTERVAL {
+            return None;
+        }
+
+        self.last_checked = Instant::now();
+
+        let modified = modified_time(&self.path);
+
+        if modified == self.modified {
+            return None;
+        }
+
+        self.modified = modified;
+
+        let reloaded = self.read();
+
+        // Settings saved by the terminal itself, such as the theme, are already applied
+        if let Ok(config) = &reloaded {
+            let mut merged = current.clone();
+            merged.merge_reloaded(config.clone());
+
+            if toml::to_string(&merged).ok() == toml::to_string(current).ok() {
+                return None;
+            }
+        }
+
+        Some(reloaded)
+    }
+
+    fn read(&self) -> Result<CompleteConfig> {
+        let content = read_to_string(&self.path)?;
+
+        CompleteConfig::layered(&content, &self.cli)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_frontend_setting() {
+        let content = "# Comments stay\n[terminal]\ntheme = \"other\"\n\n[frontend]\n# Theme\ntheme = \"dark\"\nmouse = true\n";
+
+        assert_eq!(
+            with_frontend_setting(content, "theme", &"light".into()),
+            content.replace("theme = \"dark\"", "theme = \"light\"")
+        );
+        assert_eq!(
+            with_frontend_setting("[frontend]\nmouse = true", "theme", &"light".into()),
+            "[frontend]\ntheme = \"light\"\nmouse = true\n"
+        );
+        assert_eq!(
+            with_frontend_setting("[terminal]\ndelay = 30\n", "theme", &"light".into()),
+            "[terminal]\ndelay = 30\n\n[frontend]\ntheme = \"light\"\n"
+        );
+    }
+
+    #[test]
+    fn test_environment_overrides() {
+        let vars = [
+            ("TWT_FRONTEND__THEME", "light"),
+            ("TWT_FILTERS__LANGUAGE__ENABLED", "true"),
+            ("TWT_TOKEN", "abc"),
+            ("HOME", "/home/twt"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        assert_eq!(
+            environment_overrides(vars.into_iter()),
+            vec![
+                ("frontend.theme".to_string(), "light".to_string()),
+                ("filters.language.enabled".to_string(), "true".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_value() {
+        let mut table: toml::Table =
+            toml::from_str("[terminal]\nmaximum_messages = 100\n[frontend]\ntheme = \"dark\"")
+                .unwrap();
+
+        set_value(
+            &mut table,
+            "terminal.maximum_messages",
+            override_value("250"),
+        )
+        .unwrap();
+        set_value(&mut table, "frontend.theme", override_value("light")).unwrap();
+        set_value(&mut table, "keybinds.quit", override_value(r#"["Q"]"#)).unwrap();
+
+        assert!(set_value(&mut table, "frontend.theme.name", override_value("a")).is_err());
+        assert!(set_value(&mut table, "frontend.", override_value("a")).is_err());
+
+        let config: CompleteConfig = table.try_into().unwrap();
+
+        assert_eq!(config.terminal.maximum_messages, 250);
+        assert_eq!(config.frontend.theme, "light");
+        assert_eq!(
+            config.keybinds.get(&Action::Quit),
+            Some(&vec![Key::Char('Q')])
+        );
+    }
+
+    #[test]
+    fn test_merge_reloaded() {
+        let mut config = CompleteConfig::default();
+
+        // Changed while running, after switching channels and starting a dry run
+        config.twitch.channel = "other".to_string();
+        config.twitch.dry_run = true;
+
+        let mut reloaded = CompleteConfig::default();
+        reloaded.twitch.channel = "startup".to_string();
+        reloaded.frontend.theme = "light".to_string();
+        reloaded.frontend.twitch_emotes = !config.frontend.twitch_emotes;
+        reloaded.filters.enabled = !config.filters.enabled;
+
+        config.merge_reloaded(reloaded.clone());
+
+        assert_eq!(config.twitch.channel, "other");
+        assert!(config.twitch.dry_run);
+        assert_eq!(config.frontend.theme, "light");
+        assert_ne!(
+            config.frontend.twitch_emotes,
+            reloaded.frontend.twitch_emotes
+        );
+        assert_eq!(config.filters.enabled, reloaded.filters.enabled);
+    }
+}
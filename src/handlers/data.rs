@@ -1,7 +1,6 @@
-use std::{borrow::Cow, mem::swap, string::ToString};
+use std::{borrow::Cow, collections::HashMap, mem::swap, ops::Range, string::ToString};
 
 use chrono::{DateTime, offset::Local};
-use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
 use log::{error, warn};
 use memchr::{memchr_iter, memmem};
 use once_cell::sync::Lazy;
@@ -13,28 +12,79 @@ use unicode_width::UnicodeWidthStr;
 
 use crate::{
     emotes::{DownloadedEmotes, EmoteData, SharedEmotes, display_emote, load_emote, overlay_emote},
-    handlers::config::{FrontendConfig, Palette, Theme},
+    handlers::{
+        config::{BadgeStyle, FrontendConfig, MentionStyle, Palette},
+        themes::theme,
+    },
+    twitch::{
+        alerts::Alert,
+        automod::HeldMessage,
+        cheers::{cheer_color, find_cheers},
+        gifts::GiftSub,
+        membership::Membership,
+        mod_actions::ModAction,
+        notices::Notice,
+        outbox::Outgoing,
+        polls::Poll,
+        raids::Raid,
+        redemptions::Redemption,
+        streams::LiveStream,
+        subscriptions::Subscription,
+        whispers::Whisper,
+    },
     ui::statics::NAME_MAX_CHARACTERS,
     utils::{
-        colors::{hsl_to_rgb, u32_to_color},
+        colors::{hsl_to_rgb, readable_color, u32_to_color},
         emotes::{
             PRIVATE_USE_UNICODE, UnicodePlaceholder, ZERO_WIDTH_SPACE, ZERO_WIDTH_SPACE_STR,
             get_emote_offset,
         },
+        search_query::SearchQuery,
         styles::{
-            DATETIME_DARK_STYLE, DATETIME_LIGHT_STYLE, HIGHLIGHT_NAME_DARK_STYLE,
-            HIGHLIGHT_NAME_LIGHT_STYLE, SEARCH_STYLE, SYSTEM_CHAT_STYLE,
+            BOLD_STYLE, NO_COLOR, datetime_style, highlight_name_style, mention_style,
+            search_style, system_chat_style,
         },
-        text::split_cow_in_place,
+        text::{split_cow_in_place, truncate_to_width},
+        time::format_datetime,
     },
 };
 
-static FUZZY_FINDER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
+const VIP_BADGE: char = '\u{1F48E}';
+const MODERATOR_BADGE: char = '\u{1F528}';
+const SUBSCRIBER_BADGE: char = '\u{2B50}';
+const PRIME_GAMING_BADGE: char = '\u{1F451}';
+
+const DELETED_MESSAGE: &str = "(message deleted)";
 
 pub enum TwitchToTerminalAction {
     Message(RawMessageData),
-    ClearChat(Option<String>),
-    DeleteMessage(String),
+    /// The channel the chat was cleared in, and the user whose messages were cleared, if any.
+    ClearChat(Option<String>, Option<String>),
+    /// The channel the message was deleted in, and the ID of the message.
+    DeleteMessage(Option<String>, String),
+    Notice(Notice),
+    Whisper(Whisper),
+    Membership(Membership),
+    Raid(Raid),
+    GiftSub(GiftSub),
+    /// A message that AutoMod held for review.
+    AutoModHeld(HeldMessage),
+    /// The ID of a held message that was approved, denied, or expired.
+    AutoModResolved(String),
+    ModAction(ModAction),
+    /// A poll or prediction that started, progressed, or ended.
+    Poll(Poll),
+    /// A channel point reward redeemed in the user's channel.
+    Redemption(Redemption),
+    Subscription(Subscription),
+    /// The stream of a channel if it is live, checked on an interval.
+    LiveStatus(String, Option<LiveStream>),
+    /// Messages and whispers left unsent by the previous session.
+    Recovered(Vec<Outgoing>),
+    /// Something moderators should know about right away, such as shield mode being turned on.
+    Alert(Alert),
+    /// The logins of the users blocked on Twitch, fetched when starting.
+    BlockedUsers(Vec<String>),
 }
 
 enum Word {
@@ -42,6 +92,99 @@ enum Word {
     Text(String),
 }
 
+/// A badge shown next to a username, such as `moderator/1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Badge {
+    pub name: String,
+    pub version: String,
+}
+
+impl Badge {
+    /// Parse the value of the `badges` IRC tag, such as `moderator/1,subscriber/12`.
+    pub fn parse_tag(value: &str) -> Vec<Self> {
+        value
+            .split(',')
+            .filter_map(|badge| {
+                let (name, version) = badge.split_once('/')?;
+
+                (!name.is_empty()).then(|| Self {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// The symbol shown when badges are shown as unicode, if this badge has one.
+    pub fn symbol(&self) -> Option<char> {
+        match self.name.as_str() {
+            "vip" => Some(VIP_BADGE),
+            "moderator" => Some(MODERATOR_BADGE),
+            "subscriber" => Some(SUBSCRIBER_BADGE),
+            "premium" => Some(PRIME_GAMING_BADGE),
+            _ => None,
+        }
+    }
+
+    /// The name of the badge image, as downloaded into [`crate::emotes::Emotes::badges`].
+    pub fn image_name(&self) -> String {
+        format!("{}/{}", self.name, self.version)
+    }
+}
+
+/// The message that a reply was sent to.
+/// <https://dev.twitch.tv/docs/irc/tags/#privmsg-tags>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub parent_id: String,
+    /// The first message of the thread, which is the parent unless replying to a reply.
+    pub thread_id: String,
+    pub parent_user: String,
+    pub parent_body: String,
+}
+
+impl Reply {
+    /// Read the `reply-parent-*` and `reply-thread-*` tags of a message, if it is a reply.
+    pub fn from_tags(tags: &HashMap<&str, &str>) -> Option<Self> {
+        let parent_id = (*tags.get("reply-parent-msg-id")?).to_string();
+
+        Some(Self {
+            thread_id: tags
+                .get("reply-thread-parent-msg-id")
+                .map_or_else(|| parent_id.clone(), ToString::to_string),
+            parent_user: tags
+                .get("reply-parent-display-name")
+                .or_else(|| tags.get("reply-parent-user-login"))
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            parent_body: tags
+                .get("reply-parent-msg-body")
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            parent_id,
+        })
+    }
+
+    /// Quote a message that is being replied to from this client.
+    pub fn to_message(parent: &MessageData) -> Option<Self> {
+        let parent_id = parent.message_id.clone()?;
+
+        Some(Self {
+            thread_id: parent
+                .reply
+                .as_ref()
+                .map_or_else(|| parent_id.clone(), |reply| reply.thread_id.clone()),
+            parent_user: parent.author.clone(),
+            // Emotes cannot be shown in the quote
+            parent_body: parent.plain_payload(),
+            parent_id,
+        })
+    }
+}
+
+/// The id and pid of a loaded badge image, encoded as colors, and its width in cells.
+pub type BadgeImage = (Color, Color, u16);
+
 #[derive(Debug, Clone)]
 pub struct MessageData {
     pub time_sent: DateTime<Local>,
@@ -52,6 +195,22 @@ pub struct MessageData {
     pub emotes: Vec<(Color, Color)>,
     pub message_id: Option<String>,
     pub highlight: bool,
+    /// Badges of the author, with their image if it has been loaded.
+    pub badges: Vec<(Badge, Option<BadgeImage>)>,
+    /// The message this one replied to, if it is a reply.
+    pub reply: Option<Reply>,
+    /// If the message mentions the current user.
+    pub mentioned: bool,
+    /// If the message was detected to be in a language other than the expected ones.
+    pub foreign_language: bool,
+    /// If the message was deleted by a moderator, or its author was timed out or banned.
+    pub deleted: bool,
+    /// The color of events such as subscriptions, which are shown in it entirely.
+    pub color: Option<Color>,
+    /// The bits cheered with the message, if it is a cheer.
+    pub bits: Option<u64>,
+    /// The color the author picked on Twitch, if they picked one.
+    pub user_color: Option<Color>,
 }
 
 #[derive(Debug, Clone)]
@@ -64,6 +223,14 @@ pub struct RawMessageData {
     pub emotes: DownloadedEmotes,
     pub message_id: Option<String>,
     pub highlight: bool,
+    /// The channel the message was sent in, messages without one are shown in the active channel.
+    pub channel: Option<String>,
+    pub badges: Vec<Badge>,
+    pub reply: Option<Reply>,
+    /// The bits cheered with the message, if it is a cheer.
+    pub bits: Option<u64>,
+    /// The color the author picked on Twitch, if they picked one.
+    pub user_color: Option<Color>,
 }
 
 impl RawMessageData {
@@ -75,6 +242,7 @@ impl RawMessageData {
         emotes: DownloadedEmotes,
         message_id: Option<String>,
         highlight: bool,
+        channel: Option<String>,
     ) -> Self {
         Self {
             time_sent: Local::now(),
@@ -85,11 +253,18 @@ impl RawMessageData {
             emotes,
             message_id,
             highlight,
+            channel,
+            badges: vec![],
+            reply: None,
+            bits: None,
+            user_color: None,
         }
     }
 }
 
 type Highlight<'a> = (&'a [usize], Style);
+/// Byte ranges of a message shown in their own style, such as cheers.
+type StyledRanges<'a> = &'a [(Range<usize>, Style)];
 
 impl MessageData {
     /// Used to create a message and parse its emotes using both global emotes and the current user emotes.
@@ -120,15 +295,39 @@ impl MessageData {
             emotes,
             message_id,
             highlight,
+            badges: vec![],
+            reply: None,
+            mentioned: false,
+            foreign_language: false,
+            deleted: false,
+            color: None,
+            bits: None,
+            user_color: None,
         }
     }
 
     /// Used to create a message and parse its emotes using global emotes, and twitch emotes provided through [`RawMessageData`]
     pub fn from_twitch_message(
-        msg: RawMessageData,
+        mut msg: RawMessageData,
         emotes: &SharedEmotes,
         is_emotes_enabled: bool,
     ) -> Self {
+        // Let the global emotes handle the names that have been taken over by channel emotes
+        msg.emotes
+            .retain(|name, _| !emotes.overriding_emotes.borrow().contains(name));
+
+        let badges = msg
+            .badges
+            .into_iter()
+            .map(|badge| {
+                let image = is_emotes_enabled
+                    .then(|| Self::load_badge(&badge, emotes))
+                    .flatten();
+
+                (badge, image)
+            })
+            .collect();
+
         let (payload, emotes) = Self::parse_emotes(
             msg.payload,
             emotes,
@@ -146,7 +345,61 @@ impl MessageData {
             emotes,
             message_id: msg.message_id,
             highlight: msg.highlight,
+            badges,
+            reply: msg.reply,
+            mentioned: false,
+            foreign_language: false,
+            deleted: false,
+            color: None,
+            bits: msg.bits,
+            user_color: msg.user_color,
+        }
+    }
+
+    /// Loads the image of a badge, if it has been downloaded, and tells the terminal to display it.
+    fn load_badge(badge: &Badge, emotes: &SharedEmotes) -> Option<BadgeImage> {
+        let cell_size = *emotes.cell_size.get()?;
+        let image_name = badge.image_name();
+        let (filename, _) = emotes.badges.borrow().get(&image_name)?.clone();
+
+        let loaded_badge = load_emote(
+            &format!("badge:{image_name}"),
+            &filename,
+            false,
+            &mut emotes.info.borrow_mut(),
+            cell_size,
+        )
+        .map_err(|e| warn!("Unable to load badge {image_name} ({filename}): {e}"))
+        .ok()?;
+
+        let cols = (loaded_badge.width as f32 / cell_size.0).ceil() as u16;
+
+        display_emote(loaded_badge.hash, loaded_badge.n, cols)
+            .map_err(|e| warn!("Unable to display badge: {e}"))
+            .ok()?;
+
+        Some((
+            u32_to_color(loaded_badge.hash),
+            u32_to_color(loaded_badge.n),
+            cols,
+        ))
+    }
+
+    fn badge_spans(&self, style: BadgeStyle) -> Vec<Span<'_>> {
+        if self.system || style == BadgeStyle::Hidden {
+            return vec![];
         }
+
+        self.badges
+            .iter()
+            .filter_map(|(badge, image)| match (style, image) {
+                (BadgeStyle::Images, Some((id, pid, cols))) => Some(Self::get_emote_span(
+                    UnicodePlaceholder::new(usize::from(*cols)).string(),
+                    &mut [(*id, *pid)].as_slice(),
+                )),
+                _ => badge.symbol().map(|symbol| Span::raw(symbol.to_string())),
+            })
+            .collect()
     }
 
     pub fn reparse_emotes(&mut self, emotes: &SharedEmotes, is_emotes_enabled: bool) {
@@ -299,14 +552,70 @@ impl MessageData {
         (payload, emotes)
     }
 
+    /// Mark the message as deleted, replacing its text unless deleted messages are shown.
+    pub fn delete(&mut self, show_deleted: bool) {
+        self.deleted = true;
+
+        if !show_deleted {
+            self.payload = DELETED_MESSAGE.to_string();
+            self.emotes.clear();
+        }
+    }
+
+    /// The text of the message, without the placeholders of its emotes.
+    pub fn plain_payload(&self) -> String {
+        self.payload
+            .chars()
+            .filter(|&c| c != PRIVATE_USE_UNICODE && c != ZERO_WIDTH_SPACE)
+            .collect()
+    }
+
+    /// If the message contains the username as a whole word, and was not sent by the user or the application.
+    pub fn mentions(&self, username: &str) -> bool {
+        if username.is_empty() || self.system || self.author.eq_ignore_ascii_case(username) {
+            return false;
+        }
+
+        let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let payload = self.payload.to_lowercase();
+        let username = username.to_lowercase();
+
+        payload.match_indices(&username).any(|(index, _)| {
+            let before = payload[..index].chars().next_back();
+            let after = payload[index + username.len()..].chars().next();
+
+            !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+        })
+    }
+
+    /// The color of the name of the author, being the one they picked on Twitch if they picked one and those are shown,
+    /// or one picked for them otherwise, made readable against the background of the theme.
+    fn author_color(&self, frontend_config: &FrontendConfig) -> Color {
+        let color = self
+            .user_color
+            .filter(|_| frontend_config.twitch_colors)
+            .unwrap_or_else(|| self.hash_username(&frontend_config.palette));
+
+        let background = theme().background;
+
+        readable_color(color, background, frontend_config.username_contrast)
+    }
+
     fn hash_username(&self, palette: &Palette) -> Color {
-        let hash = f64::from(
-            self.author
-                .as_bytes()
-                .iter()
-                .map(|&b| u32::from(b))
-                .sum::<u32>(),
-        );
+        let hash = self
+            .author
+            .as_bytes()
+            .iter()
+            .map(|&b| u32::from(b))
+            .sum::<u32>();
+
+        // Themes with their own colors for usernames take the place of the palette
+        let theme_color = theme().username_color(hash as usize);
+        if let Some(color) = theme_color {
+            return color;
+        }
+
+        let hash = f64::from(hash);
 
         let (hue, saturation, lightness) = match palette {
             Palette::Pastel => (hash % 360. + 1., 0.5, 0.75),
@@ -365,6 +674,7 @@ impl MessageData {
         default_style: Style,
         (search_highlight, search_theme): Highlight,
         (username_highlight, username_theme): Highlight,
+        styled_ranges: StyledRanges,
     ) -> Vec<Span<'s>> {
         const HAS_NO_HIGHLIGHTS: fn(&[usize], &usize, &usize) -> bool =
             |highlight: &[usize], start: &usize, end: &usize| {
@@ -378,6 +688,9 @@ impl MessageData {
 
         if HAS_NO_HIGHLIGHTS(search_highlight, &offset, start_index)
             && HAS_NO_HIGHLIGHTS(username_highlight, &offset, start_index)
+            && !styled_ranges
+                .iter()
+                .any(|(range, _)| range.start < *start_index && range.end > offset)
         {
             return vec![Span::styled(line, default_style)];
         }
@@ -389,6 +702,10 @@ impl MessageData {
                     Span::styled(c.to_string(), search_theme)
                 } else if username_highlight.binary_search(&i).is_ok() {
                     Span::styled(c.to_string(), username_theme)
+                } else if let Some((_, style)) =
+                    styled_ranges.iter().find(|(range, _)| range.contains(&i))
+                {
+                    Span::styled(c.to_string(), *style)
                 } else {
                     Span::styled(c.to_string(), default_style)
                 }
@@ -402,6 +719,7 @@ impl MessageData {
         default_style: Style,
         search_highlight: Highlight,
         username_highlight: Highlight,
+        styled_ranges: StyledRanges,
         emotes: &mut &[(Color, Color)],
     ) -> Vec<Span<'s>> {
         static EMOTE_FINDER: Lazy<memmem::Finder> =
@@ -418,6 +736,7 @@ impl MessageData {
                 default_style,
                 search_highlight,
                 username_highlight,
+                styled_ranges,
             )
         } else {
             let mut spans: Vec<Span<'s>> = vec![];
@@ -440,6 +759,7 @@ impl MessageData {
                         default_style,
                         search_highlight,
                         username_highlight,
+                        styled_ranges,
                     ));
                 }
                 *start_index += ZERO_WIDTH_SPACE_STR.len();
@@ -450,34 +770,43 @@ impl MessageData {
         }
     }
 
+    /// The lines of the message wrapped to a width, with the author shown by their nickname if they have one.
     pub fn to_vec(
         &self,
         frontend_config: &FrontendConfig,
         width: usize,
-        search_highlight: Option<&str>,
+        search: Option<&SearchQuery>,
         username_highlight: Option<&str>,
+        nickname: Option<&str>,
     ) -> Vec<Line> {
+        let author = nickname.unwrap_or(&self.author);
+
         // Theme styles
-        let fg = self.hash_username(&frontend_config.palette);
+        let fg = self.author_color(frontend_config);
 
-        let message_theme = if self.highlight {
+        let message_theme = if self.deleted {
+            // The text is either kept to be crossed out, or was replaced with a placeholder
+            Style::default().add_modifier(if frontend_config.show_deleted_messages {
+                Modifier::CROSSED_OUT | Modifier::DIM
+            } else {
+                Modifier::ITALIC | Modifier::DIM
+            })
+        } else if self.foreign_language {
+            Style::default().add_modifier(Modifier::DIM)
+        } else if let Some(color) = self.color.filter(|_| !*NO_COLOR) {
+            Style::default().fg(color)
+        } else if self.highlight {
             Style::default().fg(fg).add_modifier(Modifier::ITALIC)
         } else {
             Style::default()
         };
-        let username_theme = match frontend_config.theme {
-            Theme::Dark => *HIGHLIGHT_NAME_DARK_STYLE,
-            _ => *HIGHLIGHT_NAME_LIGHT_STYLE,
-        };
-        let author_theme = if self.system {
-            *SYSTEM_CHAT_STYLE
-        } else {
-            Style::default().fg(fg)
-        };
-        let datetime_theme = match frontend_config.theme {
-            Theme::Dark => *DATETIME_DARK_STYLE,
-            _ => *DATETIME_LIGHT_STYLE,
+        let username_theme = highlight_name_style();
+        let author_theme = match self.color.filter(|_| !*NO_COLOR) {
+            Some(color) => Style::default().fg(color).add_modifier(Modifier::BOLD),
+            None if self.system => system_chat_style(),
+            None => Style::default().fg(fg),
         };
+        let datetime_theme = datetime_style();
 
         // All indices to highlight like a user
         let username_highlight = username_highlight
@@ -490,28 +819,39 @@ impl MessageData {
             .unwrap_or_default();
 
         // All indices to highlight like a search result
-        let search_highlight = search_highlight
+        let search_highlight = search
             .and_then(|query| {
-                FUZZY_FINDER
-                    .fuzzy_indices(&self.payload, query)
-                    .map(|(_, indices)| {
-                        // `username_highlight` indices are byte indices, whereas `fuzzy_indices` returns char indices.
-                        // Convert those char indices to byte indices, which are easier to work with.
-                        Self::char_to_byte_indices(&self.payload, indices.into_iter())
-                    })
+                query.find(&self.author, &self.payload).map(|indices| {
+                    // `username_highlight` indices are byte indices, whereas searches find char indices.
+                    // Convert those char indices to byte indices, which are easier to work with.
+                    Self::char_to_byte_indices(&self.payload, indices.into_iter())
+                })
             })
             .unwrap_or_default();
 
-        let search = (&search_highlight as &[usize], *SEARCH_STYLE);
+        let search = (&search_highlight as &[usize], search_style());
         let username = (&username_highlight as &[usize], username_theme);
 
+        // Cheers are colored by their tier, like on Twitch
+        let cheers = if self.bits.is_some() && !*NO_COLOR && !self.deleted {
+            find_cheers(&self.payload)
+                .into_iter()
+                .map(|(range, amount)| {
+                    (
+                        range,
+                        Style::default()
+                            .fg(cheer_color(amount))
+                            .add_modifier(Modifier::BOLD),
+                    )
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
         // Message prefix
         let time_sent = if frontend_config.show_datetimes {
-            Some(
-                self.time_sent
-                    .format(&frontend_config.datetime_format)
-                    .to_string(),
-            )
+            Some(format_datetime(&self.time_sent, frontend_config))
         } else {
             None
         };
@@ -519,9 +859,27 @@ impl MessageData {
         // Add 1 for the space after the timestamp
         let time_sent_len = time_sent.as_ref().map_or(0, |t| t.len() + 1);
 
+        let mut badges = self.badge_spans(frontend_config.badges);
+
+        // The total of a cheer is shown in front of the author, in the color of its tier
+        if let Some(bits) = self.bits {
+            badges.push(Span::styled(
+                format!("{bits} bits "),
+                if *NO_COLOR {
+                    *BOLD_STYLE
+                } else {
+                    Style::default()
+                        .fg(cheer_color(bits))
+                        .add_modifier(Modifier::BOLD)
+                },
+            ));
+        }
+
+        let badges_width = badges.iter().map(Span::width).sum::<usize>();
+
         let prefix_len = if frontend_config.username_shown {
             // Add 2 for the ": "
-            time_sent_len + self.author.len() + 2
+            time_sent_len + badges_width + author.len() + 2
         } else {
             time_sent_len
         };
@@ -543,7 +901,7 @@ impl MessageData {
 
         let username_alignment = if frontend_config.username_shown {
             if frontend_config.right_align_usernames {
-                NAME_MAX_CHARACTERS.saturating_sub(self.author.width()) + 1
+                NAME_MAX_CHARACTERS.saturating_sub(badges_width + author.width()) + 1
             } else {
                 1
             }
@@ -561,8 +919,9 @@ impl MessageData {
         }
 
         if frontend_config.username_shown {
+            first_row.extend(badges);
             first_row.extend(vec![
-                Span::styled(&self.author, author_theme),
+                Span::styled(author.to_string(), author_theme),
                 Span::raw(": "),
             ]);
         }
@@ -585,12 +944,31 @@ impl MessageData {
             message_theme,
             search,
             username,
+            &cheers,
             &mut emotes,
         ));
 
         let mut space_idx = space_iter.next();
 
-        let mut rows = vec![Line::from(first_row)];
+        let mut rows = vec![];
+
+        // Replies are shown under a quote of the message they replied to
+        if let Some(reply) = &self.reply {
+            let quote = truncate_to_width(
+                &format!("Replying to @{}: {}", reply.parent_user, reply.parent_body),
+                wrap_limit.saturating_sub(time_sent_len),
+            );
+
+            rows.push(Line::from(vec![
+                Span::raw(" ".repeat(time_sent_len)),
+                Span::styled(
+                    quote,
+                    Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC),
+                ),
+            ]));
+        }
+
+        rows.push(Line::from(first_row));
 
         rows.extend(lines.map(|line| {
             while space_idx.is_some_and(|x| x < next_index) {
@@ -608,36 +986,75 @@ impl MessageData {
                 message_theme,
                 search,
                 username,
+                &cheers,
                 &mut emotes,
             ))
         }));
 
+        if self.mentioned {
+            let mention_theme = match frontend_config.mention_style {
+                MentionStyle::None => Style::default(),
+                MentionStyle::Bold => *BOLD_STYLE,
+                MentionStyle::Background => mention_style(),
+                MentionStyle::Reverse => Style::default().add_modifier(Modifier::REVERSED),
+            };
+
+            for row in &mut rows {
+                row.style = row.style.patch(mention_theme);
+            }
+        }
+
         rows
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone)]
 pub struct DataBuilder<'conf> {
-    #[allow(dead_code)]
-    pub datetime_format: &'conf str,
+    /// The channel that built messages belong to, if any.
+    pub channel: Option<&'conf str>,
 }
 
 impl<'conf> DataBuilder<'conf> {
-    pub const fn new(datetime_format: &'conf str) -> Self {
-        DataBuilder { datetime_format }
+    /// Build messages that belong to an IRC target, such as `#xithrius`.
+    /// Targets that are not channels, like `*`, result in messages without a channel.
+    pub fn in_channel<'a>(self, target: &'a str) -> DataBuilder<'a>
+    where
+        'conf: 'a,
+    {
+        DataBuilder {
+            channel: target.strip_prefix('#'),
+        }
     }
 
     pub fn user(
+        self,
         user: String,
         user_id: Option<String>,
         payload: String,
         emotes: DownloadedEmotes,
         message_id: Option<String>,
         highlight: bool,
+        badges: Vec<Badge>,
+        reply: Option<Reply>,
+        bits: Option<u64>,
+        user_color: Option<Color>,
     ) -> TwitchToTerminalAction {
-        TwitchToTerminalAction::Message(RawMessageData::new(
-            user, user_id, false, payload, emotes, message_id, highlight,
-        ))
+        let mut message = RawMessageData::new(
+            user,
+            user_id,
+            false,
+            payload,
+            emotes,
+            message_id,
+            highlight,
+            self.channel.map(ToString::to_string),
+        );
+        message.badges = badges;
+        message.reply = reply;
+        message.bits = bits;
+        message.user_color = user_color;
+
+        TwitchToTerminalAction::Message(message)
     }
 
     pub fn system(self, payload: String) -> TwitchToTerminalAction {
@@ -649,6 +1066,7 @@ impl<'conf> DataBuilder<'conf> {
             DownloadedEmotes::default(),
             None,
             false,
+            self.channel.map(ToString::to_string),
         ))
     }
 
@@ -661,6 +1079,7 @@ impl<'conf> DataBuilder<'conf> {
             DownloadedEmotes::default(),
             None,
             false,
+            self.channel.map(ToString::to_string),
         ))
     }
 }
@@ -670,6 +1089,8 @@ impl<'conf> DataBuilder<'conf> {
 mod tests {
     use std::collections::BTreeMap;
 
+    use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+
     use super::*;
 
     #[test]
@@ -684,12 +1105,74 @@ mod tests {
                 emotes: vec![],
                 message_id: None,
                 highlight: false,
+                badges: vec![],
+                reply: None,
+                mentioned: false,
+                foreign_language: false,
+                deleted: false,
+                color: None,
+                bits: None,
+                user_color: None,
             }
             .hash_username(&Palette::Pastel),
             Rgb(159, 223, 221)
         );
     }
 
+    #[test]
+    fn test_parse_badges_tag() {
+        assert_eq!(
+            Badge::parse_tag("moderator/1,subscriber/3012,glhf-pledge/1"),
+            vec![
+                Badge {
+                    name: "moderator".to_string(),
+                    version: "1".to_string(),
+                },
+                Badge {
+                    name: "subscriber".to_string(),
+                    version: "3012".to_string(),
+                },
+                Badge {
+                    name: "glhf-pledge".to_string(),
+                    version: "1".to_string(),
+                },
+            ]
+        );
+        assert!(Badge::parse_tag("").is_empty());
+    }
+
+    #[test]
+    fn test_badge_spans() {
+        let mut message = MessageData::new_user_message(
+            "human".to_string(),
+            None,
+            false,
+            "beep boop".to_string(),
+            None,
+            false,
+            &SharedEmotes::default(),
+            false,
+        );
+        message.badges = Badge::parse_tag("vip/1,glhf-pledge/1,premium/1")
+            .into_iter()
+            .map(|badge| (badge, None))
+            .collect();
+
+        assert!(message.badge_spans(BadgeStyle::Hidden).is_empty());
+        assert_eq!(
+            message.badge_spans(BadgeStyle::Unicode),
+            vec![
+                Span::raw(VIP_BADGE.to_string()),
+                Span::raw(PRIME_GAMING_BADGE.to_string()),
+            ]
+        );
+        // Badges without a loaded image fall back to their symbol
+        assert_eq!(
+            message.badge_spans(BadgeStyle::Images),
+            message.badge_spans(BadgeStyle::Unicode)
+        );
+    }
+
     const EMOTES_ID_PID: [(Color, Color); 3] = [
         (Color::Red, Color::Green),
         (Color::Black, Color::Cyan),
@@ -743,6 +1226,7 @@ mod tests {
             Style::default(),
             (search_highlight, STYLES[0]),
             (username_highlight, STYLES[1]),
+            &[],
         );
 
         assert_eq!(start_index, line_len + 1);
@@ -765,6 +1249,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn highlight_line_with_cheers() {
+        let line = Cow::Borrowed("hi Cheer1");
+
+        let mut start_index = 0;
+
+        let spans = MessageData::highlight(
+            line,
+            &mut start_index,
+            Style::default(),
+            NO_HIGHLIGHTS,
+            ([1].as_slice(), STYLES[1]),
+            &[(3..9, STYLES[2])],
+        );
+
+        assert_eq!(spans[0], Span::raw("h"));
+        assert_eq!(spans[1], Span::styled("i", STYLES[1]));
+        assert_eq!(spans[2], Span::raw(" "));
+        assert!(spans[3..].iter().all(|span| span.style == STYLES[2]));
+    }
+
     fn assert_build_line<'s>(
         line: &'s str,
         start_index: usize,
@@ -782,6 +1287,7 @@ mod tests {
                 Style::default(),
                 search_highlight,
                 username_highlight,
+                &[],
                 &mut emotes,
             );
 
@@ -798,6 +1304,7 @@ mod tests {
                 Style::default(),
                 search_highlight,
                 username_highlight,
+                &[],
                 &mut emotes,
             );
 
@@ -915,7 +1422,7 @@ mod tests {
             .flat_map(|(index, _)| index..(index + 2))
             .collect::<Vec<usize>>();
 
-        let search_highlight = FUZZY_FINDER
+        let search_highlight = SkimMatcherV2::default()
             .fuzzy_indices(&line, " b")
             .map_or(Vec::new(), |(_, indices)| {
                 MessageData::char_to_byte_indices(&line, indices.into_iter())
@@ -962,6 +1469,7 @@ mod tests {
             BTreeMap::new(),
             None,
             false,
+            None,
         );
 
         let data = MessageData::from_twitch_message(raw_message, &SharedEmotes::default(), false);
@@ -971,7 +1479,13 @@ mod tests {
             ..FrontendConfig::default()
         };
 
-        let lines = data.to_vec(&frontendconfig, 13, Some("bar"), None);
+        let lines = data.to_vec(
+            &frontendconfig,
+            13,
+            Some(&SearchQuery::parse("bar", false)),
+            None,
+            None,
+        );
 
         assert_eq!(
             lines,
@@ -1002,4 +1516,69 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_reply_from_tags() {
+        let tags = HashMap::from([
+            ("reply-parent-msg-id", "b"),
+            ("reply-thread-parent-msg-id", "a"),
+            ("reply-parent-display-name", "Human"),
+            ("reply-parent-user-login", "human"),
+            ("reply-parent-msg-body", "hello"),
+        ]);
+
+        assert_eq!(
+            Reply::from_tags(&tags),
+            Some(Reply {
+                parent_id: "b".to_string(),
+                thread_id: "a".to_string(),
+                parent_user: "Human".to_string(),
+                parent_body: "hello".to_string(),
+            })
+        );
+        assert_eq!(Reply::from_tags(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_mentions() {
+        let message = |author: &str, payload: &str| {
+            MessageData::new_user_message(
+                author.to_string(),
+                None,
+                false,
+                payload.to_string(),
+                None,
+                false,
+                &SharedEmotes::default(),
+                false,
+            )
+        };
+
+        assert!(message("human", "hi @Xithrius!").mentions("xithrius"));
+        assert!(message("human", "xithrius").mentions("xithrius"));
+        assert!(!message("human", "xithrius_bot is here").mentions("xithrius"));
+        assert!(!message("xithrius", "I am xithrius").mentions("xithrius"));
+        assert!(!message("human", "hello").mentions(""));
+    }
+
+    #[test]
+    fn test_delete_message() {
+        let mut message = MessageData::new_user_message(
+            "human".to_string(),
+            None,
+            false,
+            "hello".to_string(),
+            None,
+            false,
+            &SharedEmotes::default(),
+            false,
+        );
+
+        message.delete(true);
+        assert!(message.deleted);
+        assert_eq!(message.payload, "hello");
+
+        message.delete(false);
+        assert_eq!(message.payload, DELETED_MESSAGE);
+    }
 }
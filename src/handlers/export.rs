@@ -0,0 +1,246 @@
+use std::{
+    collections::VecDeque,
+    fs::{create_dir_all, write},
+    path::Path,
+};
+
+use chrono::Local;
+use color_eyre::{Result, eyre::WrapErr};
+use serde::Serialize;
+
+use crate::{handlers::data::MessageData, utils::pathing::config_path};
+
+const CSV_HEADER: [&str; 14] = [
+    "time",
+    "channel",
+    "author",
+    "user_id",
+    "message_id",
+    "message",
+    "badges",
+    "color",
+    "bits",
+    "reply_to",
+    "system",
+    "highlight",
+    "mentioned",
+    "deleted",
+];
+
+/// A message as it is written to an export.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct ExportedMessage {
+    /// When the message was received, in RFC 3339.
+    time: String,
+    channel: String,
+    author: String,
+    user_id: Option<String>,
+    message_id: Option<String>,
+    message: String,
+    /// Badges as they are in the `badges` tag, such as `moderator/1`.
+    badges: Vec<String>,
+    /// The color the author picked on Twitch, such as `#FF0000`.
+    color: Option<String>,
+    bits: Option<u64>,
+    /// The ID of the message this one replied to.
+    reply_to: Option<String>,
+    system: bool,
+    highlight: bool,
+    mentioned: bool,
+    deleted: bool,
+}
+
+impl ExportedMessage {
+    fn new(channel: &str, message: &MessageData) -> Self {
+        Self {
+            time: message.time_sent.to_rfc3339(),
+            channel: channel.to_string(),
+            author: message.author.clone(),
+            user_id: message.user_id.clone(),
+            message_id: message.message_id.clone(),
+            message: message.plain_payload(),
+            badges: message
+                .badges
+                .iter()
+                .map(|(badge, _)| format!("{}/{}", badge.name, badge.version))
+                .collect(),
+            color: message.user_color.map(|color| color.to_string()),
+            bits: message.bits,
+            reply_to: message.reply.as_ref().map(|reply| reply.parent_id.clone()),
+            system: message.system,
+            highlight: message.highlight,
+            mentioned: message.mentioned,
+            deleted: message.deleted,
+        }
+    }
+
+    fn csv_row(&self) -> String {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+
+        [
+            self.time.clone(),
+            self.channel.clone(),
+            self.author.clone(),
+            optional(&self.user_id),
+            optional(&self.message_id),
+            self.message.clone(),
+            self.badges.join(","),
+            optional(&self.color),
+            self.bits.map(|bits| bits.to_string()).unwrap_or_default(),
+            optional(&self.reply_to),
+            self.system.to_string(),
+            self.highlight.to_string(),
+            self.mentioned.to_string(),
+            self.deleted.to_string(),
+        ]
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<String>>()
+        .join(",")
+    }
+}
+
+/// A field of a CSV row, quoted if it has anything that would otherwise end it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    /// CSV for paths ending in `.csv`, and JSON for anything else.
+    fn from_path(path: &str) -> Self {
+        if Path::new(path)
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+        {
+            Self::Csv
+        } else {
+            Self::Json
+        }
+    }
+}
+
+/// Parse an `/export [path]` command into its path, returning `None` if the message is not one.
+/// The path is empty if none was given.
+pub fn parse_export_command(message: &str) -> Option<&str> {
+    let path = message.strip_prefix("/export")?;
+
+    if !path.is_empty() && !path.starts_with(' ') {
+        return None;
+    }
+
+    Some(path.trim())
+}
+
+fn serialize(messages: &[ExportedMessage], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(messages)?),
+        ExportFormat::Csv => Ok(std::iter::once(CSV_HEADER.join(","))
+            .chain(messages.iter().map(ExportedMessage::csv_row))
+            .map(|row| row + "\n")
+            .collect()),
+    }
+}
+
+/// Write the messages of a channel to a file, oldest first, returning where they were written.
+/// Without a path, they are written as JSON to the `exports` directory next to the config file.
+pub fn export_messages(
+    channel: &str,
+    messages: &VecDeque<MessageData>,
+    path: &str,
+) -> Result<String> {
+    let path = if path.is_empty() {
+        config_path(&format!(
+            "exports/{channel}-{}.json",
+            Local::now().format("%Y-%m-%d-%H%M%S")
+        ))
+    } else {
+        path.to_string()
+    };
+
+    let exported = messages
+        .iter()
+        .rev()
+        .map(|message| ExportedMessage::new(channel, message))
+        .collect::<Vec<ExportedMessage>>();
+
+    let content = serialize(&exported, ExportFormat::from_path(&path))?;
+
+    if let Some(directory) = Path::new(&path).parent() {
+        create_dir_all(directory)
+            .wrap_err_with(|| format!("Directory for {path} could not be made"))?;
+    }
+
+    write(&path, content).wrap_err_with(|| format!("Messages could not be exported to {path}"))?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exported(message: &str) -> ExportedMessage {
+        ExportedMessage {
+            time: "2024-05-01T09:03:07+00:00".to_string(),
+            channel: "xithrius".to_string(),
+            author: "human".to_string(),
+            user_id: Some("1".to_string()),
+            message_id: None,
+            message: message.to_string(),
+            badges: vec!["moderator/1".to_string(), "subscriber/12".to_string()],
+            color: Some("#FF0000".to_string()),
+            bits: None,
+            reply_to: None,
+            system: false,
+            highlight: false,
+            mentioned: true,
+            deleted: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_export_command() {
+        assert_eq!(parse_export_command("/export"), Some(""));
+        assert_eq!(parse_export_command("/export  chat.csv "), Some("chat.csv"));
+        assert_eq!(parse_export_command("/exports"), None);
+        assert_eq!(parse_export_command("hello"), None);
+    }
+
+    #[test]
+    fn test_export_format() {
+        assert_eq!(ExportFormat::from_path("chat.CSV"), ExportFormat::Csv);
+        assert_eq!(ExportFormat::from_path("chat.json"), ExportFormat::Json);
+        assert_eq!(ExportFormat::from_path("chat"), ExportFormat::Json);
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            serialize(&[exported("hi, \"you\"")], ExportFormat::Csv).unwrap(),
+            format!(
+                "{}\n2024-05-01T09:03:07+00:00,xithrius,human,1,,\"hi, \"\"you\"\"\",\"moderator/1,subscriber/12\",#FF0000,,,false,false,true,false\n",
+                CSV_HEADER.join(",")
+            )
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let json = serialize(&[exported("hi")], ExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value[0]["author"], "human");
+        assert_eq!(value[0]["badges"][1], "subscriber/12");
+        assert_eq!(value[0]["bits"], serde_json::Value::Null);
+    }
+}
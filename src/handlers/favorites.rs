@@ -0,0 +1,55 @@
+use crate::handlers::storage::SharedStorage;
+
+/// If a channel was marked as a favorite, ignoring case.
+pub fn is_favorite(favorites: &[String], channel: &str) -> bool {
+    favorites.iter().any(|f| f.eq_ignore_ascii_case(channel))
+}
+
+/// Mark a channel as a favorite, or unmark it if it already is one.
+pub fn toggle_favorite(storage: &SharedStorage, channel: &str) {
+    let mut storage = storage.borrow_mut();
+    let channel = channel.to_lowercase();
+
+    if storage.contains("favorites", &channel) {
+        storage.remove_inner_with("favorites", &channel);
+    } else {
+        storage.add("favorites", channel);
+    }
+}
+
+/// The favorites in the order they were marked, then the rest of the channels in their own order.
+pub fn pin_favorites(favorites: &[String], channels: &[String]) -> Vec<String> {
+    let mut pinned = favorites.to_vec();
+
+    pinned.extend(
+        channels
+            .iter()
+            .filter(|channel| !is_favorite(favorites, channel))
+            .cloned(),
+    );
+
+    pinned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_favorites() {
+        let favorites = ["xithrius".to_string(), "unvisited".to_string()];
+        let channels = [
+            "first".to_string(),
+            "Xithrius".to_string(),
+            "second".to_string(),
+        ];
+
+        assert_eq!(
+            pin_favorites(&favorites, &channels),
+            vec!["xithrius", "unvisited", "first", "second"]
+        );
+        assert_eq!(pin_favorites(&[], &channels), channels);
+        assert!(is_favorite(&favorites, "XITHRIUS"));
+        assert!(!is_favorite(&favorites, "first"));
+    }
+}
@@ -1,49 +1,371 @@
-use std::{cell::RefCell, fs::read_to_string, rc::Rc};
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    fs::{read_to_string, rename, write},
+    path::Path,
+    rc::Rc,
+};
 
+use color_eyre::{Result, eyre::WrapErr};
+use log::{error, warn};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
-use crate::{handlers::config::FiltersConfig, utils::pathing::config_path};
+use crate::{
+    handlers::{config::FiltersConfig, data::MessageData},
+    utils::pathing::config_path,
+};
 
 pub type SharedFilters = Rc<RefCell<Filters>>;
 
+/// The file filters are kept in, next to the config file.
+pub const FILTERS_FILE: &str = "filters.toml";
+
+/// Where filters were kept before they had targets, channels, and actions, with a pattern on each line.
+/// They are copied to the filters file the first time it is made, after which this file is no longer read.
+const LEGACY_FILTERS_FILE: &str = "filters.txt";
+
+/// What part of a message the pattern of a filter is matched against.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterTarget {
+    #[default]
+    Content,
+    Author,
+    /// The names of the badges of the author, such as `moderator` or `subscriber`.
+    Badge,
+}
+
+impl FilterTarget {
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Content => Self::Author,
+            Self::Author => Self::Badge,
+            Self::Badge => Self::Content,
+        }
+    }
+}
+
+impl Display for FilterTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Content => "content",
+                Self::Author => "author",
+                Self::Badge => "badge",
+            }
+        )
+    }
+}
+
+/// What happens to messages that a filter matches.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    #[default]
+    Hide,
+    Dim,
+    /// Give the message the background of mentions.
+    Highlight,
+}
+
+impl FilterAction {
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Hide => Self::Dim,
+            Self::Dim => Self::Highlight,
+            Self::Highlight => Self::Hide,
+        }
+    }
+}
+
+impl Display for FilterAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Hide => "hide",
+                Self::Dim => "dim",
+                Self::Highlight => "highlight",
+            }
+        )
+    }
+}
+
+/// A filter as it is written in the filters file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FilterRule {
+    /// A regex, matched against the target.
+    pub pattern: String,
+    #[serde(default)]
+    pub target: FilterTarget,
+    #[serde(default)]
+    pub action: FilterAction,
+    /// The channels the filter is used in, or every channel if there are none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+}
+
+impl FilterRule {
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            target: FilterTarget::default(),
+            action: FilterAction::default(),
+            channels: vec![],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct FiltersFile {
+    #[serde(default, rename = "filter")]
+    filters: Vec<FilterRule>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Filters {
-    captures: Vec<Regex>,
+    /// Filters with patterns that are not valid regexes are kept with their error,
+    /// so that they are saved as they were written, and match nothing.
+    rules: Vec<(FilterRule, Result<Regex, regex::Error>)>,
     enabled: bool,
     reversed: bool,
+    /// Where the filters are saved when they are changed, if anywhere.
+    file_path: Option<String>,
+    /// Why the filters file could not be used, if it could not be.
+    warning: Option<String>,
+}
+
+/// The filters in a filters file, or in the legacy filters file if there is none yet.
+fn load_rules(file_path: &str, legacy_path: &str) -> Result<Vec<FilterRule>> {
+    if Path::new(file_path).exists() {
+        let content = read_to_string(file_path)
+            .wrap_err_with(|| format!("Filters file {file_path} could not be read"))?;
+
+        return toml::from_str::<FiltersFile>(&content)
+            .map(|file| file.filters)
+            .wrap_err_with(|| format!("Filters file {file_path} is not valid"));
+    }
+
+    Ok(read_to_string(legacy_path)
+        .map(|content| {
+            content
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(FilterRule::new)
+                .collect()
+        })
+        .unwrap_or_default())
 }
 
 impl Filters {
-    pub fn new(file: &str, config: &FiltersConfig) -> Self {
-        let file_path = config_path(file);
+    pub fn new(config: &FiltersConfig) -> Self {
+        let file_path = config_path(FILTERS_FILE);
+        let migrating = !Path::new(&file_path).exists();
+
+        let (rules, warning, saved_to) = match load_rules(
+            &file_path,
+            &config_path(LEGACY_FILTERS_FILE),
+        ) {
+            Ok(rules) => (rules, None, Some(file_path)),
+            Err(err) => {
+                // The file is kept aside rather than written over by the first change to the filters
+                let backup = format!("{file_path}.bak");
+
+                match rename(&file_path, &backup) {
+                    Ok(()) => (
+                        vec![],
+                        Some(format!(
+                            "Filters could not be read, so they were moved to {backup}: {err:#}"
+                        )),
+                        Some(file_path),
+                    ),
+                    Err(rename_err) => (
+                        vec![],
+                        Some(format!(
+                            "Filters could not be read: {err:#}, nor moved to {backup}, so changes to them will not be saved: {rename_err}"
+                        )),
+                        None,
+                    ),
+                }
+            }
+        };
+
+        if let Some(warning) = &warning {
+            warn!("{warning}");
+        }
+
+        let mut filters = Self::from_rules(rules, config);
+        filters.file_path = saved_to;
+        filters.warning = warning;
 
+        if migrating && !filters.rules.is_empty() {
+            filters.save();
+        }
+
+        filters
+    }
+
+    /// Filters that are not saved anywhere.
+    pub fn from_rules(rules: Vec<FilterRule>, config: &FiltersConfig) -> Self {
         Self {
-            captures: read_to_string(file_path).map_or_else(
-                |_| vec![],
-                |f| {
-                    f.split('\n')
-                        .filter(|s| !s.is_empty())
-                        .flat_map(Regex::new)
-                        .collect::<Vec<Regex>>()
-                },
-            ),
+            rules: rules
+                .into_iter()
+                .map(|rule| {
+                    let regex = Regex::new(&rule.pattern);
+
+                    if let Err(err) = &regex {
+                        warn!(
+                            "Filter pattern {} is not a valid regex: {err}",
+                            rule.pattern
+                        );
+                    }
+
+                    (rule, regex)
+                })
+                .collect(),
             enabled: config.enabled,
             reversed: config.reversed,
+            file_path: None,
+            warning: None,
         }
     }
 
-    pub fn contaminated(&self, data: &str) -> bool {
-        if self.enabled {
-            for re in &self.captures {
-                if re.is_match(data) {
-                    return !self.reversed;
-                }
-            }
+    /// Why the filters file could not be used, if it could not be, which is only given once.
+    pub const fn take_warning(&mut self) -> Option<String> {
+        self.warning.take()
+    }
+
+    fn save(&self) {
+        let Some(file_path) = &self.file_path else {
+            return;
+        };
+
+        let file = FiltersFile {
+            filters: self.rules().cloned().collect(),
+        };
+
+        let result = toml::to_string(&file)
+            .wrap_err("Filters could not be written")
+            .and_then(|content| {
+                write(file_path, content)
+                    .wrap_err_with(|| format!("Filters could not be saved to {file_path}"))
+            });
+
+        if let Err(err) = result {
+            error!("{err:#}");
+        }
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &FilterRule> {
+        self.rules.iter().map(|(rule, _)| rule)
+    }
+
+    /// Why the pattern of a filter is not a valid regex, if it is not.
+    pub fn error(&self, index: usize) -> Option<String> {
+        let (_, Err(err)) = self.rules.get(index)? else {
+            return None;
+        };
+
+        // Errors with where in the pattern they are span lines, and end with what is wrong
+        err.to_string().lines().last().map(str::to_string)
+    }
+
+    /// Add a filter, saving it along with the others.
+    pub fn add(&mut self, rule: FilterRule) -> Result<()> {
+        let regex = Regex::new(&rule.pattern)?;
+
+        self.rules.push((rule, Ok(regex)));
+        self.save();
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.rules.len() {
+            let _ = self.rules.remove(index);
+            self.save();
+        }
+    }
+
+    /// Change the target, action, or channels of a filter, saving it along with the others.
+    pub fn update(&mut self, index: usize, change: impl FnOnce(&mut FilterRule)) {
+        if let Some((rule, _)) = self.rules.get_mut(index) {
+            change(rule);
+            self.save();
+        }
+    }
+
+    /// The action of the first filter that matches a message in a channel, whether the filters are enabled or not.
+    fn find(
+        &self,
+        channel: &str,
+        author: &str,
+        badges: &[&str],
+        content: &str,
+    ) -> Option<FilterAction> {
+        self.rules
+            .iter()
+            .find(|(rule, regex)| {
+                let Ok(regex) = regex else {
+                    return false;
+                };
+
+                let in_channel = rule.channels.is_empty()
+                    || rule
+                        .channels
+                        .iter()
+                        .any(|c| c.trim_start_matches('#').eq_ignore_ascii_case(channel));
+
+                in_channel
+                    && match rule.target {
+                        FilterTarget::Content => regex.is_match(content),
+                        FilterTarget::Author => regex.is_match(author),
+                        FilterTarget::Badge => badges.iter().any(|badge| regex.is_match(badge)),
+                    }
+            })
+            .map(|(rule, _)| rule.action)
+    }
+
+    fn hides(&self, channel: &str, author: &str, badges: &[&str], content: &str) -> bool {
+        if self.enabled && self.find(channel, author, badges, content) == Some(FilterAction::Hide) {
+            return !self.reversed;
         }
 
         self.reversed
     }
 
+    /// If a message in a channel is left out of chat, by matching a filter that hides messages.
+    /// Reversing the filters only shows the messages that match one.
+    pub fn hides_message(&self, channel: &str, message: &MessageData) -> bool {
+        let badges = message
+            .badges
+            .iter()
+            .map(|(badge, _)| badge.name.as_str())
+            .collect::<Vec<&str>>();
+
+        self.hides(channel, &message.author, &badges, &message.payload)
+    }
+
+    /// If a message in a channel is dimmed or highlighted by a filter.
+    pub fn style_of(&self, channel: &str, message: &MessageData) -> Option<FilterAction> {
+        if !self.enabled {
+            return None;
+        }
+
+        let badges = message
+            .badges
+            .iter()
+            .map(|(badge, _)| badge.name.as_str())
+            .collect::<Vec<&str>>();
+
+        self.find(channel, &message.author, &badges, &message.payload)
+            .filter(|action| *action != FilterAction::Hide)
+    }
+
     pub const fn enabled(&self) -> bool {
         self.enabled
     }
@@ -61,30 +383,60 @@ impl Filters {
     }
 }
 
+/// Add filters that hide messages with these patterns to the filters file, skipping the ones already in it.
+/// Returns how many were added, and where.
+pub fn add_to_filters_file(patterns: &[String]) -> Result<(usize, String)> {
+    let file_path = config_path(FILTERS_FILE);
+
+    // Filters that could not be read are not written over
+    let mut rules = load_rules(&file_path, &config_path(LEGACY_FILTERS_FILE))?;
+
+    let new = patterns
+        .iter()
+        .filter(|pattern| !rules.iter().any(|rule| &rule.pattern == *pattern))
+        .map(|pattern| FilterRule::new(pattern))
+        .collect::<Vec<FilterRule>>();
+    let added = new.len();
+
+    if added > 0 {
+        rules.extend(new);
+
+        write(
+            &file_path,
+            toml::to_string(&FiltersFile { filters: rules })?,
+        )
+        .wrap_err_with(|| format!("Filters could not be saved to {file_path}"))?;
+    }
+
+    Ok((added, file_path))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn setup() -> Filters {
-        Filters {
-            captures: vec![Regex::new("^bad.*$").unwrap()],
-            enabled: true,
-            reversed: false,
-        }
+        Filters::from_rules(
+            vec![FilterRule::new("^bad.*$")],
+            &FiltersConfig {
+                enabled: true,
+                ..FiltersConfig::default()
+            },
+        )
     }
 
     #[test]
     fn test_contaminated() {
         let filters = setup();
 
-        assert!(filters.contaminated("bad word"));
+        assert!(filters.hides("xithrius", "human", &[], "bad word"));
     }
 
     #[test]
     fn test_non_contaminated() {
         let filters = setup();
 
-        assert!(!filters.contaminated("not a bad word"));
+        assert!(!filters.hides("xithrius", "human", &[], "not a bad word"));
     }
 
     #[test]
@@ -93,7 +445,7 @@ mod tests {
 
         filters.reverse();
 
-        assert!(!filters.contaminated("bad word"));
+        assert!(!filters.hides("xithrius", "human", &[], "bad word"));
     }
 
     #[test]
@@ -102,6 +454,59 @@ mod tests {
 
         filters.reverse();
 
-        assert!(filters.contaminated("not a bad word"));
+        assert!(filters.hides("xithrius", "human", &[], "not a bad word"));
+    }
+
+    #[test]
+    fn test_filter_rules() {
+        let file = toml::from_str::<FiltersFile>(
+            r##"
+            [[filter]]
+            pattern = "(?i)^nightbot$"
+            target = "author"
+            action = "dim"
+
+            [[filter]]
+            pattern = "^moderator$"
+            target = "badge"
+            action = "highlight"
+            channels = ["#Xithrius"]
+
+            [[filter]]
+            pattern = "["
+            "##,
+        )
+        .unwrap();
+
+        let mut filters = Filters::from_rules(
+            file.filters,
+            &FiltersConfig {
+                enabled: true,
+                ..FiltersConfig::default()
+            },
+        );
+
+        // Filters with patterns that are not regexes are kept, to be saved, but match nothing
+        assert_eq!(filters.rules().count(), 3);
+        assert_eq!(filters.error(0), None);
+        assert!(filters.error(2).is_some());
+        assert_eq!(filters.find("xithrius", "human", &[], "["), None);
+
+        assert_eq!(
+            filters.find("xithrius", "Nightbot", &[], "hello"),
+            Some(FilterAction::Dim)
+        );
+        assert_eq!(
+            filters.find("xithrius", "human", &["moderator"], "hello"),
+            Some(FilterAction::Highlight)
+        );
+        assert_eq!(
+            filters.find("other", "human", &["moderator"], "hello"),
+            None
+        );
+        assert!(!filters.hides("xithrius", "Nightbot", &[], "hello"));
+
+        filters.update(0, |rule| rule.action = rule.action.next().next());
+        assert!(filters.hides("xithrius", "Nightbot", &[], "hello"));
     }
 }
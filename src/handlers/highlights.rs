@@ -0,0 +1,129 @@
+use std::{cell::RefCell, rc::Rc};
+
+use log::error;
+use regex::{Regex, RegexBuilder};
+use tui::style::Style;
+
+use crate::{
+    handlers::{
+        config::{HighlightRule, HighlightsConfig},
+        themes::theme,
+    },
+    utils::styles::NO_COLOR,
+};
+
+pub type SharedHighlights = Rc<RefCell<Highlights>>;
+
+/// Keywords that give the messages they are found in a background, and can notify the user about them.
+#[derive(Debug, Clone, Default)]
+pub struct Highlights {
+    rules: Vec<(HighlightRule, Regex)>,
+}
+
+impl Highlights {
+    /// The highlight rules of the config, leaving out those with patterns that are not valid regexes.
+    pub fn new(config: &HighlightsConfig) -> Self {
+        let rules = config
+            .rules
+            .iter()
+            .filter_map(|rule| {
+                match RegexBuilder::new(&rule.pattern)
+                    .case_insensitive(!rule.case_sensitive)
+                    .build()
+                {
+                    Ok(regex) => Some((rule.clone(), regex)),
+                    Err(err) => {
+                        error!("Highlight pattern {} is not valid: {err}", rule.pattern);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// The first rule with a pattern found in the text of a message.
+    pub fn find(&self, text: &str) -> Option<&HighlightRule> {
+        self.rules
+            .iter()
+            .find(|(_, regex)| regex.is_match(text))
+            .map(|(rule, _)| rule)
+    }
+
+    /// The style of messages matching a rule, with the color of the rule,
+    /// or the color of its name in the theme, or the background of mentions otherwise.
+    pub fn style(rule: &HighlightRule) -> Style {
+        if *NO_COLOR {
+            return Style::default();
+        }
+
+        let color = rule.color.unwrap_or_else(|| {
+            let theme = theme();
+
+            rule.name
+                .as_ref()
+                .and_then(|name| theme.highlights.get(name).copied())
+                .unwrap_or(theme.mention)
+        });
+
+        Style::default().bg(color)
+    }
+}
+
+/// What a highlight rule is called, for notifications about it.
+pub fn rule_name(rule: &HighlightRule) -> &str {
+    rule.name.as_deref().unwrap_or(&rule.pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use tui::style::Color;
+
+    use super::*;
+
+    fn rule(pattern: &str) -> HighlightRule {
+        HighlightRule {
+            pattern: pattern.to_string(),
+            ..HighlightRule::default()
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        let highlights = Highlights::new(&HighlightsConfig {
+            rules: vec![
+                rule("["),
+                HighlightRule {
+                    name: Some("game".to_string()),
+                    ..rule(r"\bfactorio\b")
+                },
+                HighlightRule {
+                    case_sensitive: true,
+                    ..rule("TWT")
+                },
+            ],
+        });
+
+        assert_eq!(
+            highlights.find("Playing Factorio later").map(rule_name),
+            Some("game")
+        );
+        assert_eq!(highlights.find("factorio2"), None);
+        assert_eq!(highlights.find("twt rocks"), None);
+        assert_eq!(highlights.find("TWT rocks").map(rule_name), Some("TWT"));
+    }
+
+    #[test]
+    fn test_style() {
+        let red = HighlightRule {
+            color: Some(Color::Red),
+            ..rule("red")
+        };
+
+        if !*NO_COLOR {
+            assert_eq!(Highlights::style(&red).bg, Some(Color::Red));
+            assert_eq!(Highlights::style(&rule("a")).bg, Some(theme().mention));
+        }
+    }
+}
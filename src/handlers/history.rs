@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+/// The most messages remembered for each channel.
+const HISTORY_LENGTH: usize = 100;
+
+/// The messages sent in each channel, the oldest first, to recall them with Up and Down.
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    channels: HashMap<String, Vec<String>>,
+}
+
+impl InputHistory {
+    pub fn from_storage(items: &[String]) -> Self {
+        let mut history = Self::default();
+
+        for item in items {
+            if let Some((channel, message)) = item.split_once('\t') {
+                history.push(channel, message);
+            }
+        }
+
+        history
+    }
+
+    /// The messages of every channel, in the format read by [`Self::from_storage`].
+    pub fn to_storage(&self) -> Vec<String> {
+        // Channels are kept in the same order every time, rather than in the order of the map
+        let mut channels = self.channels.iter().collect::<Vec<_>>();
+        channels.sort_by_key(|(channel, _)| *channel);
+
+        channels
+            .into_iter()
+            .flat_map(|(channel, messages)| {
+                messages
+                    .iter()
+                    .map(move |message| format!("{channel}\t{message}"))
+            })
+            .collect()
+    }
+
+    /// Remember a message sent in a channel, moving it to the end if it was sent before.
+    pub fn push(&mut self, channel: &str, message: &str) {
+        let messages = self.channels.entry(channel.to_string()).or_default();
+
+        messages.retain(|m| m != message);
+        messages.push(message.to_string());
+
+        if messages.len() > HISTORY_LENGTH {
+            messages.drain(..messages.len() - HISTORY_LENGTH);
+        }
+    }
+
+    /// The newest message sent in a channel before a position that starts with what was typed, along with its position.
+    /// Without a position, the search starts from the newest message.
+    pub fn older(
+        &self,
+        channel: &str,
+        typed: &str,
+        before: Option<usize>,
+    ) -> Option<(usize, &str)> {
+        let messages = self.channels.get(channel)?;
+        let before = before.unwrap_or(messages.len()).min(messages.len());
+
+        messages[..before]
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, message)| message.starts_with(typed))
+            .map(|(i, message)| (i, message.as_str()))
+    }
+
+    /// The oldest message sent in a channel after a position that starts with what was typed, along with its position.
+    pub fn newer(&self, channel: &str, typed: &str, after: usize) -> Option<(usize, &str)> {
+        let messages = self.channels.get(channel)?;
+
+        messages
+            .iter()
+            .enumerate()
+            .skip(after + 1)
+            .find(|(_, message)| message.starts_with(typed))
+            .map(|(i, message)| (i, message.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recall() {
+        let mut history = InputHistory::default();
+
+        history.push("channel", "hello");
+        history.push("channel", "!uptime");
+        history.push("channel", "!followage");
+        history.push("other", "bye");
+
+        assert_eq!(history.older("channel", "", None), Some((2, "!followage")));
+        assert_eq!(history.older("channel", "", Some(2)), Some((1, "!uptime")));
+        assert_eq!(history.older("channel", "h", None), Some((0, "hello")));
+        assert_eq!(history.older("channel", "h", Some(0)), None);
+        assert_eq!(history.newer("channel", "!", 1), Some((2, "!followage")));
+        assert_eq!(history.newer("channel", "!", 2), None);
+        assert_eq!(history.older("empty", "", None), None);
+
+        // Sending a message again makes it the newest
+        history.push("channel", "hello");
+
+        assert_eq!(history.older("channel", "", None), Some((2, "hello")));
+    }
+
+    #[test]
+    fn test_history_storage() {
+        let mut history = InputHistory::default();
+
+        for i in 0..=HISTORY_LENGTH {
+            history.push("channel", &i.to_string());
+        }
+
+        history.push("other", "hello\tthere");
+
+        let loaded = InputHistory::from_storage(&history.to_storage());
+
+        assert_eq!(loaded.older("channel", "", None), Some((99, "100")));
+        assert_eq!(loaded.older("channel", "", Some(0)), None);
+        assert_eq!(loaded.older("channel", "0", None), None);
+        assert_eq!(loaded.older("other", "", None), Some((0, "hello\tthere")));
+    }
+}
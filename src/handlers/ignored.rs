@@ -0,0 +1,125 @@
+use std::{cell::RefCell, collections::BTreeSet, rc::Rc};
+
+use color_eyre::{Result, eyre::eyre};
+
+pub type SharedIgnoredUsers = Rc<RefCell<IgnoredUsers>>;
+
+/// Users whose messages are left out of chat, either ignored with `/ignore` or blocked on Twitch.
+#[derive(Debug, Default)]
+pub struct IgnoredUsers {
+    /// The lowercase names of users ignored with `/ignore`, which are kept in storage.
+    ignored: BTreeSet<String>,
+    /// The lowercase names of users blocked on Twitch, fetched each time the terminal starts.
+    blocked: BTreeSet<String>,
+}
+
+impl IgnoredUsers {
+    pub fn from_storage(items: &[String]) -> Self {
+        Self {
+            ignored: items.iter().map(|user| user.to_lowercase()).collect(),
+            blocked: BTreeSet::new(),
+        }
+    }
+
+    pub fn to_storage(&self) -> Vec<String> {
+        self.ignored.iter().cloned().collect()
+    }
+
+    pub fn contains(&self, user: &str) -> bool {
+        let user = user.to_lowercase();
+
+        self.ignored.contains(&user) || self.blocked.contains(&user)
+    }
+
+    /// Every ignored user in alphabetical order, along with if they are blocked on Twitch.
+    pub fn users(&self) -> Vec<(&str, bool)> {
+        self.ignored
+            .union(&self.blocked)
+            .map(|user| (user.as_str(), self.blocked.contains(user)))
+            .collect()
+    }
+
+    /// Ignore a user, or stop ignoring them, whether they were ignored here or blocked on Twitch.
+    pub fn set(&mut self, user: &str, ignored: bool) {
+        let user = user.to_lowercase();
+
+        if ignored {
+            self.ignored.insert(user);
+        } else {
+            self.ignored.remove(&user);
+            self.blocked.remove(&user);
+        }
+    }
+
+    pub fn set_blocked(&mut self, users: &[String]) {
+        self.blocked = users.iter().map(|user| user.to_lowercase()).collect();
+    }
+}
+
+/// Parse an `/ignore <user>` or `/unignore <user>` command into the user and if they are ignored,
+/// returning `None` if the message is not one, or an error with how to use the command if it has no user.
+pub fn parse_ignore_command(message: &str) -> Option<Result<(String, bool)>> {
+    let (name, ignored, arguments) = message
+        .strip_prefix("/ignore")
+        .map(|arguments| ("ignore", true, arguments))
+        .or_else(|| {
+            message
+                .strip_prefix("/unignore")
+                .map(|arguments| ("unignore", false, arguments))
+        })?;
+
+    if !arguments.is_empty() && !arguments.starts_with(' ') {
+        return None;
+    }
+
+    let user = arguments.trim().trim_start_matches('@');
+
+    if user.is_empty() || user.contains(' ') {
+        return Some(Err(eyre!("Usage: /{name} <user>")));
+    }
+
+    Some(Ok((user.to_string(), ignored)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignored_users() {
+        let mut ignored = IgnoredUsers::from_storage(&["Xithrius".to_string()]);
+
+        ignored.set_blocked(&["Spammer".to_string(), "xithrius".to_string()]);
+
+        assert!(ignored.contains("XITHRIUS"));
+        assert!(ignored.contains("spammer"));
+        assert!(!ignored.contains("human"));
+        assert_eq!(ignored.users(), vec![("spammer", true), ("xithrius", true)]);
+        assert_eq!(ignored.users().len(), 2);
+
+        ignored.set("spammer", false);
+        ignored.set("Human", true);
+
+        assert!(!ignored.contains("spammer"));
+        assert_eq!(ignored.users(), vec![("human", false), ("xithrius", true)]);
+
+        // Only users ignored here are kept, as blocks are fetched again from Twitch
+        assert_eq!(ignored.to_storage(), vec!["human", "xithrius"]);
+    }
+
+    #[test]
+    fn test_parse_ignore_command() {
+        assert_eq!(
+            parse_ignore_command("/ignore @Xithrius").map(Result::unwrap),
+            Some(("Xithrius".to_string(), true))
+        );
+        assert_eq!(
+            parse_ignore_command("/unignore xithrius ").map(Result::unwrap),
+            Some(("xithrius".to_string(), false))
+        );
+        assert!(parse_ignore_command("/ignore").unwrap().is_err());
+        assert!(parse_ignore_command("/unignore a b").unwrap().is_err());
+        assert!(parse_ignore_command("/ignored xithrius").is_none());
+        assert!(parse_ignore_command("hello").is_none());
+    }
+}
@@ -0,0 +1,434 @@
+use std::{
+    collections::HashMap,
+    fs::{copy, create_dir_all, read_to_string},
+    path::Path,
+};
+
+use color_eyre::{Result, eyre::bail};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    handlers::{
+        config::{CompleteConfig, persist_config},
+        filters::add_to_filters_file,
+    },
+    utils::pathing::config_path,
+};
+
+/// Another chat client to take settings from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportSource {
+    /// The path to Chatterino's `settings.json`.
+    Chatterino(String),
+    /// The path to WeeChat's config directory, containing `irc.conf` and `weechat.conf`.
+    WeeChat(String),
+}
+
+/// Everything that could be taken from another client, and everything that could not.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Imported {
+    pub username: Option<String>,
+    pub token: Option<String>,
+    pub channels: Vec<String>,
+    /// Regexes to be added to the filters file.
+    pub filters: Vec<String>,
+    /// Settings that have no equivalent here.
+    pub untranslated: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChatterinoIgnore {
+    pattern: String,
+    #[serde(default)]
+    regex: bool,
+    #[serde(default = "default_true")]
+    is_block: bool,
+    #[serde(default)]
+    case_sensitive: bool,
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// Count the entries of a list setting, such as `highlighting.highlights`.
+fn list_len(settings: &Value, pointer: &str) -> usize {
+    settings
+        .pointer(pointer)
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len)
+}
+
+pub fn parse_chatterino(settings: &str) -> Result<Imported> {
+    let settings = serde_json::from_str::<Value>(settings)?;
+
+    let mut imported = Imported::default();
+
+    let ignores = settings
+        .pointer("/ignore/phrases")
+        .cloned()
+        .map(serde_json::from_value::<Vec<ChatterinoIgnore>>)
+        .transpose()?
+        .unwrap_or_default();
+
+    for ignore in ignores {
+        if !ignore.is_block {
+            imported.untranslated.push(format!(
+                "ignored phrase `{}` replaces text rather than hiding the message",
+                ignore.pattern
+            ));
+            continue;
+        }
+
+        let pattern = if ignore.regex {
+            ignore.pattern
+        } else {
+            regex::escape(&ignore.pattern)
+        };
+
+        imported.filters.push(if ignore.case_sensitive {
+            pattern
+        } else {
+            format!("(?i){pattern}")
+        });
+    }
+
+    for (pointer, name) in [
+        ("/highlighting/highlights", "highlighted phrases"),
+        ("/highlighting/users", "highlighted users"),
+        ("/highlighting/blacklist", "users excluded from highlights"),
+        ("/ignore/users", "ignored users"),
+        ("/nicknames", "nicknames"),
+    ] {
+        let count = list_len(&settings, pointer);
+
+        if count > 0 {
+            imported.untranslated.push(format!("{count} {name}"));
+        }
+    }
+
+    Ok(imported)
+}
+
+/// Read the options of an INI-like WeeChat config file, keyed by `section.option`.
+fn parse_weechat_options(file: &str) -> HashMap<String, String> {
+    let mut options = HashMap::new();
+    let mut section = String::new();
+
+    for line in file.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim();
+
+            options.insert(
+                format!("{section}.{}", key.trim()),
+                value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .unwrap_or(value)
+                    .to_string(),
+            );
+        }
+    }
+
+    options
+}
+
+pub fn parse_weechat(irc_conf: &str, weechat_conf: Option<&str>) -> Result<Imported> {
+    let options = parse_weechat_options(irc_conf);
+
+    // The server can be named anything, so find the one that connects to Twitch
+    let Some(server) = options.iter().find_map(|(key, value)| {
+        key.strip_prefix("server.")
+            .and_then(|k| k.strip_suffix(".addresses"))
+            .filter(|_| value.contains("twitch.tv"))
+    }) else {
+        bail!("No server connecting to Twitch was found in irc.conf.");
+    };
+
+    let server_option = |name: &str| {
+        options
+            .get(&format!("server.{server}.{name}"))
+            .filter(|value| !value.is_empty())
+    };
+
+    let mut imported = Imported {
+        username: server_option("nicks")
+            .and_then(|nicks| nicks.split(',').next())
+            .map(str::to_string),
+        ..Default::default()
+    };
+
+    if let Some(password) = server_option("password") {
+        if password.starts_with("${") {
+            imported
+                .untranslated
+                .push("the password, as it is kept in WeeChat's secured data".to_string());
+        } else {
+            imported.token = Some(password.clone());
+        }
+    }
+
+    imported.channels = server_option("autojoin")
+        .map(|autojoin| {
+            autojoin
+                // Channel keys come after a space, and Twitch has no use for them
+                .split(' ')
+                .next()
+                .unwrap_or_default()
+                .split(',')
+                .map(|channel| channel.trim_start_matches('#').to_lowercase())
+                .filter(|channel| !channel.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let ignores = options
+        .keys()
+        .filter(|key| key.starts_with("ignore."))
+        .count();
+
+    if ignores > 0 {
+        imported
+            .untranslated
+            .push(format!("{ignores} ignored users"));
+    }
+
+    if let Some(weechat_conf) = weechat_conf {
+        let options = parse_weechat_options(weechat_conf);
+
+        for (option, name) in [
+            ("look.highlight", "highlighted words"),
+            ("look.highlight_regex", "highlight regex"),
+        ] {
+            if let Some(value) = options.get(option).filter(|value| !value.is_empty()) {
+                imported.untranslated.push(format!("{name} `{value}`"));
+            }
+        }
+    }
+
+    Ok(imported)
+}
+
+/// Merge what was imported into the config, leaving what is already set alone.
+/// Returns a line for each thing that changed.
+fn merge_into_config(config: &mut CompleteConfig, imported: &Imported) -> Vec<String> {
+    let mut changes = vec![];
+
+    if let Some(username) = &imported.username {
+        if config.twitch.username.is_empty() {
+            config.twitch.username.clone_from(username);
+            changes.push(format!("username `{username}`"));
+        }
+    }
+
+    if let Some(token) = &imported.token {
+        if config.twitch.token.as_ref().is_none_or(String::is_empty) {
+            config.twitch.token = Some(token.clone());
+            changes.push("token".to_string());
+        }
+    }
+
+    if config.twitch.channel.is_empty() {
+        if let Some(channel) = imported.channels.first() {
+            config.twitch.channel.clone_from(channel);
+            changes.push(format!("channel `{channel}`"));
+        }
+    }
+
+    for channel in &imported.channels {
+        if !config.frontend.favorite_channels.contains(channel) {
+            config.frontend.favorite_channels.push(channel.clone());
+            changes.push(format!("favorite channel `{channel}`"));
+        }
+    }
+
+    if !imported.filters.is_empty() && !config.filters.enabled {
+        config.filters.enabled = true;
+        changes.push("filters enabled".to_string());
+    }
+
+    changes
+}
+
+/// Read the settings of another client, write what can be used into the config and filters,
+/// then report on everything that was and wasn't brought over.
+pub fn import_settings(source: &ImportSource) -> Result<()> {
+    let (client, imported) = match source {
+        ImportSource::Chatterino(path) => ("Chatterino", parse_chatterino(&read_to_string(path)?)?),
+        ImportSource::WeeChat(path) => {
+            let path = Path::new(path);
+
+            let irc_conf = read_to_string(path.join("irc.conf"))?;
+            let weechat_conf = read_to_string(path.join("weechat.conf")).ok();
+
+            (
+                "WeeChat",
+                parse_weechat(&irc_conf, weechat_conf.as_deref())?,
+            )
+        }
+    };
+
+    let config_path_str = config_path("config.toml");
+    let config_file = Path::new(&config_path_str);
+
+    let mut config = if config_file.exists() {
+        match toml::from_str::<CompleteConfig>(&read_to_string(config_file)?) {
+            Ok(config) => config,
+            Err(err) => bail!("Config could not be processed. Error: {:?}", err.message()),
+        }
+    } else {
+        create_dir_all(config_file.parent().unwrap())?;
+
+        CompleteConfig::default()
+    };
+
+    let mut changes = merge_into_config(&mut config, &imported);
+
+    let (added, filters_path) = add_to_filters_file(&imported.filters)?;
+
+    if added > 0 {
+        changes.push(format!("{added} filters, added to {filters_path}"));
+    }
+
+    if changes.is_empty() {
+        println!("Nothing new was found in the {client} settings.");
+    } else {
+        // Writing the config loses its comments, so keep the original around
+        if config_file.exists() {
+            let backup = format!("{config_path_str}.bak");
+            copy(config_file, &backup)?;
+            println!("The previous config was copied to {backup}.");
+        }
+
+        persist_config(config_file, &config)?;
+
+        println!("Imported from {client}:");
+
+        for change in changes {
+            println!("  - {change}");
+        }
+    }
+
+    if !imported.untranslated.is_empty() {
+        println!("Not imported, as there is nothing to translate them to:");
+
+        for untranslated in &imported.untranslated {
+            println!("  - {untranslated}");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chatterino() {
+        let settings = r#"{
+            "ignore": {
+                "phrases": [
+                    { "pattern": "a.b", "regex": false, "isBlock": true, "caseSensitive": false },
+                    { "pattern": "^!\\w+", "regex": true, "isBlock": true, "caseSensitive": true },
+                    { "pattern": "heck", "regex": false, "isBlock": false, "replaceWith": "***" }
+                ]
+            },
+            "highlighting": {
+                "highlights": [{ "pattern": "xithrius" }, { "pattern": "twt" }],
+                "users": []
+            },
+            "nicknames": [{ "name": "someone", "replace": "friend" }]
+        }"#;
+
+        let imported = parse_chatterino(settings).unwrap();
+
+        assert_eq!(imported.filters, vec![r"(?i)a\.b", r"^!\w+"]);
+        assert_eq!(imported.untranslated.len(), 3);
+        assert!(imported.untranslated[0].contains("heck"));
+        assert_eq!(imported.untranslated[1], "2 highlighted phrases");
+        assert_eq!(imported.untranslated[2], "1 nicknames");
+
+        assert_eq!(parse_chatterino("{}").unwrap(), Imported::default());
+        assert!(parse_chatterino("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_weechat() {
+        let irc_conf = r##"
+# irc.conf
+[server]
+libera.addresses = "irc.libera.chat/6697"
+libera.nicks = "someone"
+twitch.addresses = "irc.chat.twitch.tv/6697"
+twitch.password = "oauth:abc"
+twitch.nicks = "Xithrius,xithrius_"
+twitch.autojoin = "#Xithrius,#twitch key"
+
+[ignore]
+ignore = "^spammer$;twitch;*"
+"##;
+
+        let weechat_conf = r#"
+[look]
+highlight = "twt,tui"
+highlight_regex = ""
+"#;
+
+        let imported = parse_weechat(irc_conf, Some(weechat_conf)).unwrap();
+
+        assert_eq!(imported.username.as_deref(), Some("Xithrius"));
+        assert_eq!(imported.token.as_deref(), Some("oauth:abc"));
+        assert_eq!(imported.channels, vec!["xithrius", "twitch"]);
+        assert_eq!(
+            imported.untranslated,
+            vec!["1 ignored users", "highlighted words `twt,tui`"]
+        );
+
+        let secured = parse_weechat(
+            "[server]\ntwitch.addresses = \"irc.chat.twitch.tv\"\ntwitch.password = \"${sec.data.twitch}\"",
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(secured.token, None);
+        assert_eq!(secured.untranslated.len(), 1);
+
+        assert!(parse_weechat("[server]\nlibera.addresses = \"irc.libera.chat\"", None).is_err());
+    }
+
+    #[test]
+    fn test_merge_into_config() {
+        let mut config = CompleteConfig::default();
+        config.twitch.username = "existing".to_string();
+        config.frontend.favorite_channels = vec!["twitch".to_string()];
+
+        let imported = Imported {
+            username: Some("imported".to_string()),
+            token: Some("oauth:abc".to_string()),
+            channels: vec!["xithrius".to_string(), "twitch".to_string()],
+            filters: vec!["spam".to_string()],
+            ..Default::default()
+        };
+
+        let changes = merge_into_config(&mut config, &imported);
+
+        assert_eq!(config.twitch.username, "existing");
+        assert_eq!(config.twitch.token.as_deref(), Some("oauth:abc"));
+        assert_eq!(config.twitch.channel, "xithrius");
+        assert_eq!(
+            config.frontend.favorite_channels,
+            vec!["twitch", "xithrius"]
+        );
+        assert!(config.filters.enabled);
+        assert_eq!(changes.len(), 4);
+    }
+}
@@ -17,34 +17,56 @@ pub(super) fn interactive_config() -> Option<CompleteConfig> {
         return None;
     }
 
+    Some(CompleteConfig {
+        twitch: twitch_settings(&TwitchConfig::default()),
+        ..Default::default()
+    })
+}
+
+/// Ask for what is needed to connect to Twitch, starting from the current settings.
+pub(super) fn twitch_settings(current: &TwitchConfig) -> TwitchConfig {
     let username: String = Input::with_theme(&ColorfulTheme::default())
         .with_prompt("Username: ")
+        .with_initial_text(&current.username)
         .interact_text()
         .unwrap();
 
     let token: String = Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Token: ")
+        .with_prompt("Token (leave empty to log in through the browser): ")
+        .allow_empty(true)
         .interact_text()
         .unwrap();
 
+    let client_id = if token.is_empty() {
+        let client_id: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Client ID of your application: ")
+            .with_initial_text(current.client_id.clone().unwrap_or_default())
+            .interact_text()
+            .unwrap();
+
+        Some(client_id)
+    } else {
+        None
+    };
+
     let channel: String = Input::with_theme(&ColorfulTheme::default())
         .with_prompt("Channel: ")
+        .with_initial_text(&current.channel)
         .interact_text()
         .unwrap();
 
     let server: String = Input::with_theme(&ColorfulTheme::default())
         .with_prompt("IRC server: ")
-        .default("irc.chat.twitch.tv".to_string())
+        .default(current.server.clone())
         .interact_text()
         .unwrap();
 
-    Some(CompleteConfig {
-        twitch: TwitchConfig {
-            username,
-            channel,
-            server,
-            token: Some(token),
-        },
-        ..Default::default()
-    })
+    TwitchConfig {
+        username,
+        channel: channel.to_lowercase(),
+        server,
+        token: (!token.is_empty()).then_some(token),
+        client_id,
+        ..current.clone()
+    }
 }
@@ -0,0 +1,172 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use chrono::{DateTime, Local};
+
+pub type SharedJoinHistory = Rc<RefCell<JoinHistory>>;
+
+/// How many of the latest joins are remembered for each channel.
+const JOINS_PER_CHANNEL: usize = 10;
+
+/// How many days it takes for a join to count half as much.
+const HALF_LIFE_DAYS: f64 = 3.0;
+
+/// When each channel was joined, so that the channels joined the most recently and often are switched to first.
+#[derive(Debug, Default)]
+pub struct JoinHistory {
+    channels: HashMap<String, Vec<DateTime<Local>>>,
+}
+
+impl JoinHistory {
+    /// Load the history from storage, where each join is stored as `channel\ttime`.
+    pub fn from_storage(items: &[String]) -> Self {
+        let mut history = Self::default();
+
+        for item in items {
+            let Some((channel, time)) = item.split_once('\t') else {
+                continue;
+            };
+
+            if let Ok(time) = DateTime::parse_from_rfc3339(time) {
+                history.record(channel, time.with_timezone(&Local));
+            }
+        }
+
+        history
+    }
+
+    /// Every join, in the format read by [`Self::from_storage`].
+    pub fn to_storage(&self) -> Vec<String> {
+        // Channels are kept in the same order every time, rather than in the order of the map
+        let mut channels = self.channels.iter().collect::<Vec<_>>();
+        channels.sort_by_key(|(channel, _)| *channel);
+
+        channels
+            .into_iter()
+            .flat_map(|(channel, joins)| {
+                joins
+                    .iter()
+                    .map(move |time| format!("{channel}\t{}", time.to_rfc3339()))
+            })
+            .collect()
+    }
+
+    /// Remember that a channel was joined, forgetting its oldest join if there are too many.
+    pub fn record(&mut self, channel: &str, time: DateTime<Local>) {
+        let joins = self.channels.entry(channel.to_lowercase()).or_default();
+
+        joins.push(time);
+        joins.sort();
+
+        if joins.len() > JOINS_PER_CHANNEL {
+            joins.drain(..joins.len() - JOINS_PER_CHANNEL);
+        }
+    }
+
+    /// When a channel was last joined, if it has been.
+    pub fn last_joined(&self, channel: &str) -> Option<&DateTime<Local>> {
+        self.channels
+            .get(&channel.to_lowercase())
+            .and_then(|joins| joins.last())
+    }
+
+    /// How recently and often a channel was joined, with each join counting less the longer ago it was.
+    pub fn frecency(&self, channel: &str, now: &DateTime<Local>) -> f64 {
+        self.channels
+            .get(&channel.to_lowercase())
+            .into_iter()
+            .flatten()
+            .map(|time| {
+                let days = (*now - *time).num_seconds().max(0) as f64 / 86_400.0;
+
+                0.5_f64.powf(days / HALF_LIFE_DAYS)
+            })
+            .sum()
+    }
+
+    /// Every channel that was joined, the highest frecency first, and the most recently joined otherwise.
+    pub fn ranked(&self, now: &DateTime<Local>) -> Vec<String> {
+        let mut channels = self
+            .channels
+            .keys()
+            .map(|channel| {
+                (
+                    self.frecency(channel, now),
+                    self.last_joined(channel),
+                    channel,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        channels.sort_by(|(a_score, a_last, a), (b_score, b_last, b)| {
+            b_score
+                .total_cmp(a_score)
+                .then_with(|| b_last.cmp(a_last))
+                .then_with(|| a.cmp(b))
+        });
+
+        channels
+            .into_iter()
+            .map(|(_, _, channel)| channel.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    #[test]
+    fn test_ranked_by_frecency() {
+        let now = Local::now();
+        let mut history = JoinHistory::default();
+
+        for days in [20, 21, 22, 23] {
+            history.record("often", now - TimeDelta::days(days));
+        }
+        history.record("Recent", now - TimeDelta::hours(1));
+        history.record("daily", now - TimeDelta::days(1));
+        history.record("daily", now - TimeDelta::days(2));
+
+        assert_eq!(history.ranked(&now), vec!["daily", "recent", "often"]);
+
+        // Joining an old favourite a few times brings it back up
+        history.record("often", now);
+        history.record("often", now);
+
+        assert_eq!(history.ranked(&now), vec!["often", "daily", "recent"]);
+    }
+
+    #[test]
+    fn test_joins_per_channel() {
+        let now = Local::now();
+        let mut history = JoinHistory::default();
+
+        for minutes in 0..=i64::try_from(JOINS_PER_CHANNEL).unwrap() {
+            history.record("channel", now - TimeDelta::minutes(minutes));
+        }
+
+        assert_eq!(history.to_storage().len(), JOINS_PER_CHANNEL);
+        assert_eq!(history.last_joined("CHANNEL"), Some(&now));
+    }
+
+    #[test]
+    fn test_join_storage() {
+        let now = Local::now();
+        let mut history = JoinHistory::default();
+
+        history.record("xithrius", now);
+        history.record("other", now - TimeDelta::days(1));
+
+        let loaded = JoinHistory::from_storage(&history.to_storage());
+
+        assert_eq!(loaded.ranked(&now), vec!["xithrius", "other"]);
+        assert!((loaded.frecency("xithrius", &now) - 1.0).abs() < 0.01);
+        assert!(
+            JoinHistory::from_storage(&["invalid".to_string()])
+                .ranked(&now)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use std::{
+    fs::{File, read_dir},
+    io::{self, Read, Seek, SeekFrom},
+    mem,
+    path::{Path, PathBuf},
+    sync::mpsc::{Receiver, channel},
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::NaiveDate;
+use log::warn;
+use regex::Regex;
+
+use crate::{
+    handlers::config::{LogsConfig, Matching},
+    utils::{matching::matches_any, pathing::config_path},
+};
+
+/// The most messages a search finds, so that searching years of logs does not fill memory.
+const MAXIMUM_RESULTS: usize = 1_000;
+/// How many found messages are sent to be shown at a time.
+const BATCH_SIZE: usize = 50;
+/// The longest found messages wait to be sent while a batch fills up.
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+/// How much of a log is read at a time, from its end towards its start.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// A message read back from the chat logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedMessage {
+    pub channel: String,
+    pub date: NaiveDate,
+    /// The time as it was written, in the `datetime_format` of the logs.
+    pub time: String,
+    /// Empty if the line was written in a format other than the current one.
+    pub author: String,
+    pub message: String,
+}
+
+/// What to search the chat logs for, typed like `#channel from:user since:2024-01-01 until:2024-02-01 text`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LogQuery {
+    pub channel: Option<String>,
+    pub author: Option<String>,
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    /// The rest of what was typed, matched against the messages.
+    pub text: String,
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+impl LogQuery {
+    /// Words that are not a channel, author, or valid date are searched for in the messages.
+    pub fn parse(search: &str) -> Self {
+        let mut query = Self::default();
+        let mut text = vec![];
+
+        for word in search.split_whitespace() {
+            if let Some(channel) = word.strip_prefix('#').filter(|c| !c.is_empty()) {
+                query.channel = Some(channel.to_lowercase());
+            } else if let Some(author) = word.strip_prefix("from:").filter(|a| !a.is_empty()) {
+                query.author = Some(author.trim_start_matches('@').to_string());
+            } else if let Some(since) = word.strip_prefix("since:").and_then(parse_date) {
+                query.since = Some(since);
+            } else if let Some(until) = word.strip_prefix("until:").and_then(parse_date) {
+                query.until = Some(until);
+            } else {
+                text.push(word);
+            }
+        }
+
+        query.text = text.join(" ");
+
+        query
+    }
+
+    /// If the log of a channel from a day could have messages that are searched for.
+    fn covers(&self, channel: &str, date: NaiveDate) -> bool {
+        self.channel.as_ref().is_none_or(|c| c == channel)
+            && self.since.is_none_or(|since| date >= since)
+            && self.until.is_none_or(|until| date <= until)
+    }
+
+    fn matches(&self, message: &LoggedMessage, matching: Matching) -> bool {
+        self.author
+            .as_ref()
+            .is_none_or(|author| author.eq_ignore_ascii_case(&message.author))
+            && matches_any(matching, &[&message.message], &self.text)
+    }
+}
+
+/// The lines of the logs in the format of the config, to read the time, author, and message back.
+fn line_pattern(format: &str) -> Regex {
+    let pattern = regex::escape(format)
+        .replace(r"\{time\}", "(?P<time>.*?)")
+        .replace(r"\{author\}", r"(?P<author>\S+)")
+        .replace(r"\{message\}", "(?P<message>.*)");
+
+    Regex::new(&format!("^{pattern}$")).unwrap()
+}
+
+/// Lines in another format than the current one are kept whole as the message.
+fn parse_line(pattern: &Regex, channel: &str, date: NaiveDate, line: &str) -> LoggedMessage {
+    let captures = pattern.captures(line);
+    let field = |name: &str| {
+        captures
+            .as_ref()
+            .and_then(|c| c.name(name))
+            .map_or_else(String::new, |m| m.as_str().to_string())
+    };
+
+    LoggedMessage {
+        channel: channel.to_string(),
+        date,
+        time: field("time"),
+        author: field("author"),
+        message: captures
+            .as_ref()
+            .and_then(|c| c.name("message"))
+            .map_or_else(|| line.to_string(), |m| m.as_str().to_string()),
+    }
+}
+
+/// The lines of a file from the last to the first, read a block at a time from its end,
+/// so that the most recent messages of a log are found without reading all of it first.
+/// Lines that are not valid UTF-8 are read lossily rather than ending the search.
+struct ReverseLines<R> {
+    reader: R,
+    block_size: usize,
+    /// Where the part of the file that is yet to be read ends.
+    position: u64,
+    /// The start of the earliest line read so far, which continues into the previous block.
+    partial: Vec<u8>,
+    /// Whole lines that were read, in the order of the file.
+    lines: Vec<Vec<u8>>,
+}
+
+impl<R: Read + Seek> ReverseLines<R> {
+    fn new(mut reader: R, block_size: usize) -> io::Result<Self> {
+        let position = reader.seek(SeekFrom::End(0))?;
+
+        Ok(Self {
+            reader,
+            block_size,
+            position,
+            partial: vec![],
+            lines: vec![],
+        })
+    }
+
+    fn read_block(&mut self) -> io::Result<()> {
+        let size = self
+            .position
+            .min(u64::try_from(self.block_size).unwrap_or(u64::MAX));
+        self.position -= size;
+
+        let mut block = vec![0; usize::try_from(size).unwrap_or_default()];
+        self.reader.seek(SeekFrom::Start(self.position))?;
+        self.reader.read_exact(&mut block)?;
+        block.append(&mut self.partial);
+
+        let mut pieces = block.split(|byte| *byte == b'\n');
+        // Bytes before the first newline of the block may be the end of a line in the block before
+        self.partial = pieces.next().unwrap_or_default().to_vec();
+        self.lines = pieces.map(<[u8]>::to_vec).collect();
+
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> Iterator for ReverseLines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(line) = self.lines.pop() {
+                return Some(Ok(String::from_utf8_lossy(&line).into_owned()));
+            }
+
+            if self.position == 0 {
+                if self.partial.is_empty() {
+                    return None;
+                }
+
+                let line = mem::take(&mut self.partial);
+
+                return Some(Ok(String::from_utf8_lossy(&line).into_owned()));
+            }
+
+            if let Err(err) = self.read_block() {
+                // Stop rather than read the same block again
+                self.position = 0;
+                self.partial.clear();
+
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+/// The logs that could have messages that are searched for, the most recent day first.
+fn log_files(directory: &Path, query: &LogQuery) -> Vec<(String, NaiveDate, PathBuf)> {
+    let Ok(channels) = read_dir(directory) else {
+        return vec![];
+    };
+
+    let mut files = channels
+        .flatten()
+        .filter_map(|channel| {
+            let name = channel.file_name().to_string_lossy().to_string();
+
+            Some((name, read_dir(channel.path()).ok()?))
+        })
+        .flat_map(|(channel, logs)| {
+            logs.flatten().filter_map(move |log| {
+                let path = log.path();
+                let date = path
+                    .extension()
+                    .filter(|extension| *extension == "log")
+                    .and_then(|_| parse_date(&path.file_stem()?.to_string_lossy()))?;
+
+                Some((channel.clone(), date, path))
+            })
+        })
+        .filter(|(channel, date, _)| query.covers(channel, *date))
+        .collect::<Vec<_>>();
+
+    files.sort_by(|(a_channel, a_date, _), (b_channel, b_date, _)| {
+        b_date.cmp(a_date).then_with(|| a_channel.cmp(b_channel))
+    });
+
+    files
+}
+
+/// Search the chat logs on a thread of their own, sending what is found a few messages at a time
+/// as it is found, the most recent first. The search stops once the receiver is dropped, and the
+/// receiver is disconnected once every log has been searched.
+pub fn search_logs(
+    config: &LogsConfig,
+    query: LogQuery,
+    matching: Matching,
+) -> Receiver<Vec<LoggedMessage>> {
+    let (sender, receiver) = channel();
+    let pattern = line_pattern(&config.format);
+
+    thread::spawn(move || {
+        let mut found = 0;
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        let mut last_sent = Instant::now();
+
+        for (channel, date, path) in log_files(Path::new(&config_path("logs")), &query) {
+            let lines = match File::open(&path).and_then(|file| ReverseLines::new(file, BLOCK_SIZE))
+            {
+                Ok(lines) => lines,
+                Err(err) => {
+                    warn!("Chat log {} could not be read: {err}", path.display());
+                    continue;
+                }
+            };
+
+            let messages = lines
+                .map_while(Result::ok)
+                .map(|line| line.trim_end_matches('\r').to_string())
+                .filter(|line| !line.is_empty() && !line.starts_with("# Start logging"))
+                .map(|line| parse_line(&pattern, &channel, date, &line))
+                .filter(|message| query.matches(message, matching));
+
+            for message in messages {
+                batch.push(message);
+                found += 1;
+
+                if found >= MAXIMUM_RESULTS
+                    || batch.len() >= BATCH_SIZE
+                    || last_sent.elapsed() >= BATCH_INTERVAL
+                {
+                    if sender.send(mem::take(&mut batch)).is_err() || found >= MAXIMUM_RESULTS {
+                        return;
+                    }
+
+                    last_sent = Instant::now();
+                }
+            }
+        }
+
+        if !batch.is_empty() {
+            // Nothing is left to do if the search was closed in the meantime
+            let _ = sender.send(batch);
+        }
+    });
+
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, day).unwrap()
+    }
+
+    #[test]
+    fn test_reverse_lines() {
+        let log = b"# Start logging\nfirst\nsecond line\n\xffthird\n";
+        let expected = vec![
+            "",
+            "\u{fffd}third",
+            "second line",
+            "first",
+            "# Start logging",
+        ];
+
+        // Lines are put back together when they are split between blocks
+        for block_size in [1, 3, 7, 1024] {
+            let lines = ReverseLines::new(io::Cursor::new(log), block_size)
+                .unwrap()
+                .map(Result::unwrap)
+                .collect::<Vec<String>>();
+
+            assert_eq!(lines, expected);
+        }
+
+        assert_eq!(
+            ReverseLines::new(io::Cursor::new(b""), 4)
+                .unwrap()
+                .map(Result::unwrap)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            LogQuery::parse("#Xithrius from:@human since:2024-05-01 until:2024-05-03 hello there"),
+            LogQuery {
+                channel: Some("xithrius".to_string()),
+                author: Some("human".to_string()),
+                since: Some(date(1)),
+                until: Some(date(3)),
+                text: "hello there".to_string(),
+            }
+        );
+
+        // Dates that are not valid are searched for as text
+        assert_eq!(
+            LogQuery::parse("since:yesterday #"),
+            LogQuery {
+                text: "since:yesterday #".to_string(),
+                ..LogQuery::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_query_covers() {
+        let query = LogQuery::parse("#xithrius since:2024-05-02 until:2024-05-03");
+
+        assert!(query.covers("xithrius", date(2)));
+        assert!(query.covers("xithrius", date(3)));
+        assert!(!query.covers("xithrius", date(1)));
+        assert!(!query.covers("xithrius", date(4)));
+        assert!(!query.covers("other", date(2)));
+        assert!(LogQuery::default().covers("other", date(1)));
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let pattern = line_pattern(&LogsConfig::default().format);
+
+        let logged = parse_line(&pattern, "xithrius", date(1), "[09:03:07] human: hi: there");
+
+        assert_eq!(logged.time, "09:03:07");
+        assert_eq!(logged.author, "human");
+        assert_eq!(logged.message, "hi: there");
+
+        // Lines written in another format are still searched as a whole
+        let logged = parse_line(&pattern, "xithrius", date(1), "human said hi");
+
+        assert_eq!(logged.author, "");
+        assert_eq!(logged.message, "human said hi");
+
+        let pattern = line_pattern("{time} <{author}> {message} (*)");
+
+        let logged = parse_line(&pattern, "xithrius", date(1), "09:03 <human> hi (*)");
+
+        assert_eq!(logged.author, "human");
+        assert_eq!(logged.message, "hi");
+    }
+
+    #[test]
+    fn test_query_matches() {
+        let pattern = line_pattern(&LogsConfig::default().format);
+        let message = parse_line(
+            &pattern,
+            "xithrius",
+            date(1),
+            "[09:03:07] Human: hello there",
+        );
+
+        assert!(LogQuery::parse("from:human hello").matches(&message, Matching::Substring));
+        assert!(LogQuery::parse("").matches(&message, Matching::Substring));
+        assert!(!LogQuery::parse("from:other hello").matches(&message, Matching::Substring));
+        assert!(!LogQuery::parse("goodbye").matches(&message, Matching::Substring));
+    }
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Local};
+
+use crate::{
+    handlers::{config::Matching, data::MessageData},
+    utils::matching::matches_any,
+};
+
+/// A message that mentioned the current user, kept for the rest of the session.
+#[derive(Debug, Clone)]
+pub struct Mention {
+    pub time_sent: DateTime<Local>,
+    /// The channel the message was sent in, if known.
+    pub channel: Option<String>,
+    pub author: String,
+    pub message: String,
+}
+
+impl Mention {
+    pub fn new(channel: Option<String>, message: &MessageData) -> Self {
+        Self {
+            time_sent: message.time_sent,
+            channel,
+            author: message.author.clone(),
+            message: message.plain_payload(),
+        }
+    }
+
+    pub fn matches(&self, query: &str, matching: Matching) -> bool {
+        matches_any(
+            matching,
+            &[
+                &self.author,
+                &self.message,
+                self.channel.as_deref().unwrap_or_default(),
+            ],
+            query,
+        )
+    }
+}
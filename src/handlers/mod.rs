@@ -1,9 +1,25 @@
 pub mod app;
 pub mod args;
+pub mod chat_log;
+pub mod completion;
 pub mod config;
 pub mod data;
+pub mod export;
+pub mod favorites;
 pub mod filters;
+pub mod highlights;
+pub mod history;
+pub mod ignored;
+pub mod import;
 mod interactive;
+pub mod joins;
+pub mod log_search;
+pub mod mentions;
+pub mod nicknames;
+pub mod pacing;
+pub mod schema;
 pub mod state;
 pub mod storage;
+pub mod tabs;
+pub mod themes;
 pub mod user_input;
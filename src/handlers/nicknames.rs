@@ -0,0 +1,136 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use color_eyre::{Result, eyre::eyre};
+
+pub type SharedNicknames = Rc<RefCell<Nicknames>>;
+
+/// Names chosen by the user to show in place of the names of other users, such as to shorten long ones.
+#[derive(Debug, Default)]
+pub struct Nicknames {
+    /// Nicknames by the lowercase name of the user.
+    names: HashMap<String, String>,
+}
+
+impl Nicknames {
+    /// Load the nicknames from storage, where each one is stored as `user\tnickname`.
+    pub fn from_storage(items: &[String]) -> Self {
+        let names = items
+            .iter()
+            .filter_map(|item| item.split_once('\t'))
+            .map(|(user, nickname)| (user.to_lowercase(), nickname.to_string()))
+            .collect();
+
+        Self { names }
+    }
+
+    pub fn to_storage(&self) -> Vec<String> {
+        let mut items = self
+            .names
+            .iter()
+            .map(|(user, nickname)| format!("{user}\t{nickname}"))
+            .collect::<Vec<String>>();
+
+        items.sort();
+
+        items
+    }
+
+    pub fn get(&self, user: &str) -> Option<&str> {
+        self.names.get(&user.to_lowercase()).map(String::as_str)
+    }
+
+    /// Give a user a nickname, or go back to their own name if there is none.
+    pub fn set(&mut self, user: &str, nickname: Option<String>) {
+        let user = user.to_lowercase();
+
+        match nickname {
+            Some(nickname) => self.names.insert(user, nickname),
+            None => self.names.remove(&user),
+        };
+    }
+
+    /// The user with a nickname starting with some text, to complete mentions of them by their nickname.
+    pub fn find_user(&self, nickname: &str) -> Option<&str> {
+        let nickname = nickname.to_lowercase();
+
+        let mut matches = self
+            .names
+            .iter()
+            .filter(|(_, n)| n.to_lowercase().starts_with(&nickname))
+            .map(|(user, _)| user.as_str())
+            .collect::<Vec<&str>>();
+
+        // The same user should be suggested each time
+        matches.sort_unstable();
+
+        matches.first().copied()
+    }
+}
+
+/// Parse a `/nick <user> [nickname]` command, returning `None` if the message is not one,
+/// or an error with how to use the command if it has no user.
+/// Leaving out the nickname removes the nickname of the user.
+pub fn parse_nick_command(message: &str) -> Option<Result<(String, Option<String>)>> {
+    let arguments = message.strip_prefix("/nick")?;
+
+    if !arguments.is_empty() && !arguments.starts_with(' ') {
+        return None;
+    }
+
+    let arguments = arguments.trim();
+
+    if arguments.is_empty() {
+        return Some(Err(eyre!("Usage: /nick <user> [nickname]")));
+    }
+
+    let (user, nickname) = arguments.split_once(' ').unwrap_or((arguments, ""));
+    let nickname = nickname.trim();
+
+    Some(Ok((
+        user.trim_start_matches('@').to_string(),
+        (!nickname.is_empty()).then(|| nickname.to_string()),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nicknames() {
+        let mut nicknames = Nicknames::default();
+
+        nicknames.set("AVeryVeryLongName", Some("Long".to_string()));
+        nicknames.set("xithrius", Some("Xith".to_string()));
+
+        assert_eq!(nicknames.get("averyverylongname"), Some("Long"));
+        assert_eq!(nicknames.find_user("lo"), Some("averyverylongname"));
+        assert_eq!(nicknames.find_user("x"), Some("xithrius"));
+        assert_eq!(nicknames.find_user("z"), None);
+
+        let stored = Nicknames::from_storage(&nicknames.to_storage());
+
+        assert_eq!(stored.get("xithrius"), Some("Xith"));
+
+        nicknames.set("xithrius", None);
+
+        assert_eq!(nicknames.get("xithrius"), None);
+    }
+
+    #[test]
+    fn test_parse_nick_command() {
+        assert_eq!(
+            parse_nick_command("/nick @AVeryVeryLongName Long name").map(Result::unwrap),
+            Some((
+                "AVeryVeryLongName".to_string(),
+                Some("Long name".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_nick_command("/nick xithrius").map(Result::unwrap),
+            Some(("xithrius".to_string(), None))
+        );
+        assert!(parse_nick_command("/nick").unwrap().is_err());
+        assert!(parse_nick_command("/nickname a b").is_none());
+    }
+}
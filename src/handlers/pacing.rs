@@ -0,0 +1,79 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// How long to wait between sending the parts of a message that was split.
+/// Twitch allows users who are not moderators 20 messages every 30 seconds, and drops the rest.
+pub const PART_SPACING: Duration = Duration::from_millis(1_500);
+
+/// Things that are sent one at a time and spaced apart, such as the parts of a long message.
+#[derive(Debug)]
+pub struct Paced<T> {
+    queue: VecDeque<T>,
+    spacing: Duration,
+    /// When the last item was taken to be sent.
+    last_sent: Option<Instant>,
+}
+
+impl<T> Paced<T> {
+    pub const fn new(spacing: Duration) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            spacing,
+            last_sent: None,
+        }
+    }
+
+    pub fn push(&mut self, items: impl IntoIterator<Item = T>) {
+        self.queue.extend(items);
+    }
+
+    /// The next item, once enough time has passed since the one before it was taken.
+    pub fn next_due(&mut self, now: Instant) -> Option<T> {
+        if self
+            .last_sent
+            .is_some_and(|last| now.duration_since(last) < self.spacing)
+        {
+            return None;
+        }
+
+        let item = self.queue.pop_front()?;
+        self.last_sent = Some(now);
+
+        Some(item)
+    }
+
+    /// Drop everything that was not sent yet, returning how many items that was.
+    pub fn clear(&mut self) -> usize {
+        let dropped = self.queue.len();
+
+        self.queue.clear();
+
+        dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paced() {
+        let mut paced = Paced::new(Duration::from_secs(1));
+        let start = Instant::now();
+
+        assert_eq!(paced.next_due(start), None);
+
+        paced.push([1, 2, 3]);
+
+        // The first item goes out right away, and the rest wait for the spacing
+        assert_eq!(paced.next_due(start), Some(1));
+        assert_eq!(paced.next_due(start + Duration::from_millis(500)), None);
+        assert_eq!(paced.next_due(start + Duration::from_secs(1)), Some(2));
+        assert_eq!(paced.next_due(start + Duration::from_secs(1)), None);
+
+        assert_eq!(paced.clear(), 1);
+        assert_eq!(paced.next_due(start + Duration::from_secs(5)), None);
+    }
+}
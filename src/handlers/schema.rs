@@ -0,0 +1,215 @@
+use std::collections::BTreeSet;
+
+use once_cell::sync::Lazy;
+
+use crate::handlers::user_input::keybinds::Action;
+
+/// The default config, with every key that can be set, either with its default value or as a commented out example.
+pub const DEFAULT_CONFIG: &str = include_str!("../../default-config.toml");
+
+/// The paths of every key in the default config, such as `frontend.theme`, along with the sections they are in.
+static KNOWN_KEYS: Lazy<BTreeSet<String>> = Lazy::new(|| known_keys(DEFAULT_CONFIG));
+
+fn join(section: &str, key: &str) -> String {
+    if section.is_empty() {
+        key.to_string()
+    } else {
+        format!("{section}.{key}")
+    }
+}
+
+/// The paths of the keys and sections in a config, including the ones in comments,
+/// so that examples such as `# border_type = "rounded"` are known too.
+fn known_keys(content: &str) -> BTreeSet<String> {
+    let mut keys = BTreeSet::new();
+    let mut section = String::new();
+    // A section written in a comment, which the keys in comments after it are in
+    let mut example: Option<String> = None;
+
+    for line in content.lines() {
+        let commented = line.trim_start().starts_with('#');
+        let line = line.trim_start_matches('#').trim();
+
+        let header = line
+            .strip_prefix("[[")
+            .and_then(|l| l.strip_suffix("]]"))
+            .or_else(|| line.strip_prefix('[').and_then(|l| l.strip_suffix(']')))
+            // Lists written in comments are not sections
+            .filter(|header| {
+                header
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+            });
+
+        if let Some(header) = header {
+            let header = header.trim().to_string();
+
+            let mut path = String::new();
+
+            for part in header.split('.') {
+                path = join(&path, part);
+                keys.insert(path.clone());
+            }
+
+            if commented {
+                example = Some(header);
+            } else {
+                section = header;
+                example = None;
+            }
+        } else if let Ok(table) = toml::from_str::<toml::Table>(line) {
+            if !commented && !table.is_empty() {
+                example = None;
+            }
+
+            let section = if commented {
+                example.as_ref().unwrap_or(&section)
+            } else {
+                &section
+            };
+
+            keys.extend(table.keys().map(|key| join(section, key)));
+        }
+    }
+
+    keys
+}
+
+/// The option closest to what was written, if it is close enough to have been a typo of it.
+fn closest<'a>(written: &str, options: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    options
+        .map(|option| (strsim::levenshtein(written, option), option))
+        .filter(|(distance, _)| *distance <= (written.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, option)| option)
+}
+
+/// What is wrong with a key that is not known, along with what it most likely was meant to be.
+fn unknown(kind: &str, path: &str, suggestion: Option<&str>) -> String {
+    suggestion.map_or_else(
+        || format!("unknown {kind} {path}"),
+        |suggestion| format!("unknown {kind} {path}, did you mean {suggestion}?"),
+    )
+}
+
+fn find_unknown_keys(table: &toml::Table, section: &str, errors: &mut Vec<String>) {
+    for (key, value) in table {
+        let path = join(section, key);
+
+        if section == "keybinds" {
+            let actions = Action::all()
+                .map(|action| action.to_string())
+                .collect::<Vec<_>>();
+
+            if !actions.contains(key) {
+                let suggestion = closest(key, actions.iter().map(String::as_str));
+
+                errors.push(unknown("action", &path, suggestion));
+            }
+
+            continue;
+        }
+
+        if !KNOWN_KEYS.contains(&path) {
+            let siblings = KNOWN_KEYS.iter().filter_map(|known| {
+                let (parent, name) = known.rsplit_once('.').unwrap_or(("", known));
+
+                (parent == section).then_some(name)
+            });
+
+            errors.push(unknown("key", &path, closest(key, siblings)));
+
+            continue;
+        }
+
+        match value {
+            toml::Value::Table(table) => find_unknown_keys(table, &path, errors),
+            toml::Value::Array(items) => {
+                for item in items {
+                    if let toml::Value::Table(table) = item {
+                        find_unknown_keys(table, &path, errors);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Every key in a config that is not in the default config, such as
+/// `unknown key frontend.boarder_type, did you mean border_type?`.
+pub fn unknown_keys(table: &toml::Table) -> Vec<String> {
+    let mut errors = vec![];
+
+    find_unknown_keys(table, "", &mut errors);
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::config::CompleteConfig;
+
+    #[test]
+    fn test_known_keys() {
+        for key in [
+            "twitch",
+            "twitch.token",
+            "frontend.border_type",
+            "filters.enabled",
+            "filters.language.hide",
+            "widgets",
+            "widgets.chat.border_type",
+            "highlights.rule.pattern",
+            "sounds.player",
+        ] {
+            assert!(KNOWN_KEYS.contains(key), "{key} is not known");
+        }
+    }
+
+    #[test]
+    fn test_default_config_is_known() {
+        // Every setting has to be in the default config, or setting it would be an error
+        let table = toml::Table::try_from(CompleteConfig::default()).unwrap();
+
+        assert_eq!(unknown_keys(&table), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let table: toml::Table = toml::from_str(
+            r#"
+            volume = 3
+
+            [frontend]
+            boarder_type = "rounded"
+            theme = "dark"
+
+            [keybinds]
+            quit = ["q"]
+            qiut = ["Q"]
+
+            [widgets.chat]
+            title_alignment = "left"
+            colour = "red"
+
+            [[highlights.rule]]
+            pattern = "a"
+            case_sensitiv = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            unknown_keys(&table),
+            vec![
+                "unknown key frontend.boarder_type, did you mean border_type?",
+                "unknown key highlights.rule.case_sensitiv, did you mean case_sensitive?",
+                "unknown action keybinds.qiut, did you mean quit?",
+                "unknown key volume",
+                "unknown key widgets.chat.colour",
+            ]
+        );
+    }
+}
@@ -44,6 +44,7 @@ pub enum State {
     Dashboard,
     Normal,
     Help,
+    Projector,
 }
 
 impl Default for State {
@@ -61,6 +62,7 @@ impl Display for State {
                 Self::Dashboard => "dashboard",
                 Self::Normal => "normal",
                 Self::Help => "help",
+                Self::Projector => "projector",
             }
         )
     }
@@ -74,6 +76,7 @@ impl FromStr for State {
             "normal" | "default" | "chat" => Ok(Self::Normal),
             "dashboard" | "dash" | "start" => Ok(Self::Dashboard),
             "help" | "commands" => Ok(Self::Help),
+            "projector" | "overlay" => Ok(Self::Projector),
             _ => bail!("State '{}' cannot be deserialized", s),
         }
     }
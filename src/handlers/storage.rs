@@ -1,18 +1,37 @@
 use std::{
     cell::RefCell,
     collections::HashMap,
-    fs::{File, read_to_string},
+    fs::{File, read, rename},
     io::Write,
-    path::Path,
     rc::Rc,
 };
 
+use color_eyre::{Result, eyre::anyhow};
+use log::warn;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
-use crate::{handlers::config::StorageConfig, utils::pathing::config_path};
+use crate::{
+    handlers::config::StorageConfig,
+    utils::{
+        encryption::{Key, is_encrypted, load_key, open, seal},
+        pathing::config_path,
+    },
+};
 
-static ITEM_KEYS: Lazy<Vec<&str>> = Lazy::new(|| vec!["channels", "mentions"]);
+static ITEM_KEYS: Lazy<Vec<&str>> = Lazy::new(|| {
+    vec![
+        "channels",
+        "mentions",
+        "raids",
+        "completions",
+        "nicknames",
+        "ignored",
+        "history",
+        "joins",
+        "favorites",
+    ]
+});
 
 pub type SharedStorage = Rc<RefCell<Storage>>;
 type StorageMap = HashMap<String, StorageItem>;
@@ -21,6 +40,12 @@ type StorageMap = HashMap<String, StorageItem>;
 pub struct Storage {
     items: StorageMap,
     file_path: String,
+    /// If the file should be encrypted when written.
+    encrypted: bool,
+    /// The key the file is encrypted with, loaded once rather than every time the file is written.
+    key: Option<Key>,
+    /// Why the file could not be used, to tell the user once the terminal is shown.
+    warning: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -29,51 +54,128 @@ pub struct StorageItem {
     enabled: bool,
 }
 
+impl StorageItem {
+    fn new(key: &str, config: &StorageConfig) -> Self {
+        let enabled = match key {
+            // Joins are the channels switched to, along with when they were switched to
+            "channels" | "joins" => config.channels,
+            "mentions" => config.mentions,
+            "raids" => config.raids,
+            "completions" => config.completions,
+            "nicknames" => config.nicknames,
+            "ignored" => config.ignored,
+            "history" => config.history,
+            // Favorites are only ever marked on purpose, so they are always kept
+            "favorites" => true,
+            _ => panic!("Invalid storage key {key}."),
+        };
+
+        Self {
+            content: vec![],
+            enabled,
+        }
+    }
+}
+
+/// The items in the contents of the file, which is always decrypted if it is encrypted,
+/// so that turning encryption off keeps the data.
+fn read_items(content: &[u8], key: Option<&Key>) -> Result<StorageMap> {
+    if is_encrypted(content) {
+        let key = key.ok_or_else(|| anyhow!("the storage key could not be loaded"))?;
+
+        return Ok(serde_json::from_slice(&open(key, content)?)?);
+    }
+
+    Ok(serde_json::from_slice(content)?)
+}
+
 impl Storage {
     pub fn new(file: &str, config: &StorageConfig) -> Self {
         let file_path = config_path(file);
+        let content = read(&file_path).ok();
+        let mut warning = None;
+
+        let key = if config.encrypted || content.as_deref().is_some_and(is_encrypted) {
+            load_key()
+                .map_err(|err| {
+                    warning = Some(format!(
+                        "The storage key could not be loaded, so nothing will be stored: {err}"
+                    ));
+                })
+                .ok()
+        } else {
+            None
+        };
 
-        if !Path::new(&file_path).exists() {
-            let mut items = StorageMap::new();
-
-            for item_key in ITEM_KEYS.iter() {
-                let enabled = match *item_key {
-                    "channels" => config.channels,
-                    "mentions" => config.mentions,
-                    _ => panic!("Invalid storage key {item_key}."),
-                };
-
-                items.insert(
-                    (*item_key).to_string(),
-                    StorageItem {
-                        content: vec![],
-                        enabled,
-                    },
-                );
-            }
+        let items = match content.map(|content| read_items(&content, key.as_ref())) {
+            Some(Ok(items)) => Some(items),
+            Some(Err(err)) => {
+                // The file is kept aside, in case the key that it was encrypted with is found again
+                let backup = format!("{file_path}.bak");
 
-            let storage_str = serde_json::to_string(&items).unwrap();
+                warning = Some(match rename(&file_path, &backup) {
+                    Ok(()) => {
+                        format!("Storage could not be read, so it was moved to {backup}: {err}")
+                    }
+                    Err(rename_err) => format!(
+                        "Storage could not be read: {err}, nor moved to {backup}: {rename_err}"
+                    ),
+                });
 
-            let mut file = File::create(&file_path).unwrap();
+                None
+            }
+            None => None,
+        };
 
-            file.write_all(storage_str.as_bytes()).unwrap();
+        let is_new = items.is_none();
+        let mut items = items.unwrap_or_default();
 
-            return Self { items, file_path };
+        // Files written before a key existed are missing it.
+        for item_key in ITEM_KEYS.iter() {
+            items
+                .entry((*item_key).to_string())
+                .or_insert_with(|| StorageItem::new(item_key, config));
         }
 
-        let file_content = read_to_string(&file_path).unwrap();
+        if let Some(warning) = &warning {
+            warn!("{warning}");
+        }
 
-        let items: StorageMap = serde_json::from_str(&file_content).unwrap();
+        let storage = Self {
+            items,
+            file_path,
+            encrypted: config.encrypted,
+            key,
+            warning,
+        };
 
-        Self { items, file_path }
+        if is_new {
+            storage.dump_data();
+        }
+
+        storage
+    }
+
+    /// Why the file could not be used, if it could not be, which is only given once.
+    pub const fn take_warning(&mut self) -> Option<String> {
+        self.warning.take()
     }
 
     pub fn dump_data(&self) {
-        let storage_str = serde_json::to_string(&self.items).unwrap();
+        let mut storage_data = serde_json::to_vec(&self.items).unwrap();
+
+        if self.encrypted {
+            // Writing the file unencrypted instead would go against what was asked for
+            let Some(key) = &self.key else {
+                return;
+            };
+
+            storage_data = seal(key, &storage_data).unwrap();
+        }
 
         let mut file = File::create(&self.file_path).unwrap();
 
-        file.write_all(storage_str.as_bytes()).unwrap();
+        file.write_all(&storage_data).unwrap();
     }
 
     pub fn add(&mut self, key: &str, value: String) {
@@ -91,6 +193,19 @@ impl Storage {
         }
     }
 
+    /// Replace everything stored under a key, for items that are managed as a whole.
+    pub fn set(&mut self, key: &str, values: Vec<String>) {
+        if ITEM_KEYS.contains(&key) {
+            if let Some(item) = self.items.get_mut(key) {
+                if item.enabled {
+                    item.content = values;
+                }
+            }
+        } else {
+            panic!("Attempted to set value with key {key} in JSON storage.");
+        }
+    }
+
     pub fn get(&self, key: &str) -> Vec<String> {
         if ITEM_KEYS.contains(&key) {
             self.items
@@ -0,0 +1,356 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Local};
+
+use crate::handlers::data::MessageData;
+
+/// The message chat was scrolled to when a channel was switched away from,
+/// so the same position can be found again after more messages have arrived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ScrollAnchor {
+    Id(String),
+    /// Messages from the application itself have no ID.
+    Sent(DateTime<Local>, String),
+}
+
+impl ScrollAnchor {
+    fn new(message: &MessageData) -> Self {
+        message.message_id.as_ref().map_or_else(
+            || Self::Sent(message.time_sent, message.author.clone()),
+            |id| Self::Id(id.clone()),
+        )
+    }
+
+    fn matches(&self, message: &MessageData) -> bool {
+        match self {
+            Self::Id(id) => message.message_id.as_ref() == Some(id),
+            Self::Sent(time_sent, author) => {
+                message.message_id.is_none()
+                    && message.time_sent == *time_sent
+                    && message.author == *author
+            }
+        }
+    }
+}
+
+/// What was being typed in a channel when it was switched away from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Draft {
+    pub text: String,
+    /// The ID and author of the message being replied to, if any.
+    pub reply_to: Option<(String, String)>,
+}
+
+/// Everything that belongs to a single channel's chat, which is put aside while
+/// another channel is being shown.
+#[derive(Debug, Default)]
+pub struct ChannelBuffer {
+    pub messages: VecDeque<MessageData>,
+    pub scroll_offset: usize,
+    /// What was in the chat input when the channel was switched away from.
+    pub draft: Draft,
+    /// Messages received while the channel was not being shown.
+    pub unread: usize,
+    /// The message that was scrolled to, if chat was not at the bottom.
+    anchor: Option<ScrollAnchor>,
+}
+
+impl ChannelBuffer {
+    pub const fn new(messages: VecDeque<MessageData>, scroll_offset: usize, draft: Draft) -> Self {
+        Self {
+            messages,
+            scroll_offset,
+            draft,
+            unread: 0,
+            anchor: None,
+        }
+    }
+
+    /// Remember the message that is scrolled to, rather than how far from the bottom it is.
+    fn put_away(&mut self) {
+        self.anchor = (self.scroll_offset > 0)
+            .then(|| self.messages.get(self.scroll_offset).map(ScrollAnchor::new))
+            .flatten();
+    }
+
+    /// Scroll back to the remembered message, or to the bottom if it is gone.
+    fn bring_back(mut self) -> Self {
+        self.scroll_offset = self
+            .anchor
+            .take()
+            .and_then(|anchor| self.messages.iter().position(|m| anchor.matches(m)))
+            .unwrap_or(0);
+        self.unread = 0;
+
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ChannelTabs {
+    /// Joined channels, in the order they were opened.
+    channels: Vec<String>,
+    /// Index of the channel currently shown in chat.
+    active: usize,
+    /// Buffers of every channel except the active one, as that one is stored by the app.
+    buffers: HashMap<String, ChannelBuffer>,
+}
+
+/// Channels are looked up in lowercase, the same as they are in the messages received from Twitch.
+fn normalize(channel: &str) -> String {
+    channel.to_lowercase()
+}
+
+impl ChannelTabs {
+    pub fn new(channel: &str) -> Self {
+        Self {
+            channels: vec![normalize(channel)],
+            active: 0,
+            buffers: HashMap::new(),
+        }
+    }
+
+    pub fn channels(&self) -> &[String] {
+        &self.channels
+    }
+
+    pub fn active(&self) -> &str {
+        &self.channels[self.active]
+    }
+
+    pub const fn active_index(&self) -> usize {
+        self.active
+    }
+
+    pub fn unread(&self, channel: &str) -> usize {
+        self.buffers
+            .get(&normalize(channel))
+            .map_or(0, |b| b.unread)
+    }
+
+    /// Put away the buffer of the active channel, and hand back the buffer of the channel
+    /// being switched to. The channel is opened in a new tab if it was not open yet.
+    pub fn switch_to(&mut self, channel: &str, mut current: ChannelBuffer) -> ChannelBuffer {
+        let channel = normalize(channel);
+
+        if self.active() == channel {
+            return current;
+        }
+
+        current.put_away();
+
+        let previous = self.active().to_string();
+        self.buffers.insert(previous, current);
+
+        self.active = self
+            .channels
+            .iter()
+            .position(|c| *c == channel)
+            .unwrap_or_else(|| {
+                self.channels.push(channel.clone());
+                self.channels.len() - 1
+            });
+
+        self.buffers
+            .remove(&channel)
+            .unwrap_or_default()
+            .bring_back()
+    }
+
+    /// The channel in the tab after the active one, wrapping around.
+    pub fn next(&self) -> &str {
+        &self.channels[(self.active + 1) % self.channels.len()]
+    }
+
+    /// The channel in the tab before the active one, wrapping around.
+    pub fn previous(&self) -> &str {
+        &self.channels[(self.active + self.channels.len() - 1) % self.channels.len()]
+    }
+
+    /// Close the active tab, returning the name of the closed channel, the channel that is now active,
+    /// and the buffer of that channel. The last remaining tab cannot be closed.
+    pub fn close_active(&mut self) -> Option<(String, String, ChannelBuffer)> {
+        if self.channels.len() <= 1 {
+            return None;
+        }
+
+        let closed = self.channels.remove(self.active);
+        self.active = self.active.min(self.channels.len() - 1);
+
+        let next = self.active().to_string();
+        let buffer = self.buffers.remove(&next).unwrap_or_default().bring_back();
+
+        Some((closed, next, buffer))
+    }
+
+    /// Store a message for a channel that is open but not active.
+    /// If the channel is active or unknown, the message is given back so it can be shown.
+    pub fn push_message(
+        &mut self,
+        channel: Option<&str>,
+        message: MessageData,
+        maximum_messages: usize,
+    ) -> Option<MessageData> {
+        let Some(buffer) = channel.and_then(|c| self.buffers.get_mut(&normalize(c))) else {
+            return Some(message);
+        };
+
+        buffer.messages.push_front(message);
+        buffer.unread += 1;
+
+        if buffer.messages.len() > maximum_messages {
+            let evicted = buffer.messages.split_off(maximum_messages);
+
+            if buffer
+                .anchor
+                .as_ref()
+                .is_some_and(|anchor| evicted.iter().any(|m| anchor.matches(m)))
+            {
+                buffer.anchor = None;
+            }
+        }
+
+        None
+    }
+
+    /// Messages of a channel that is open but not active.
+    pub fn messages_mut(&mut self, channel: &str) -> Option<&mut VecDeque<MessageData>> {
+        self.buffers
+            .get_mut(&normalize(channel))
+            .map(|b| &mut b.messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+
+    fn message(payload: &str) -> MessageData {
+        MessageData {
+            time_sent: DateTime::default(),
+            author: "human".to_string(),
+            user_id: None,
+            system: false,
+            payload: payload.to_string(),
+            emotes: vec![],
+            message_id: Some(payload.to_string()),
+            highlight: false,
+            badges: vec![],
+            reply: None,
+            mentioned: false,
+            foreign_language: false,
+            deleted: false,
+            color: None,
+            bits: None,
+            user_color: None,
+        }
+    }
+
+    #[test]
+    fn test_switch_keeps_buffers() {
+        let mut tabs = ChannelTabs::new("first");
+
+        let messages = ["e", "d", "c", "b", "a"].map(message).into();
+        let draft = Draft {
+            text: "hello".to_string(),
+            reply_to: Some(("c".to_string(), "human".to_string())),
+        };
+        let current = ChannelBuffer::new(messages, 3, draft.clone());
+
+        let buffer = tabs.switch_to("second", current);
+
+        assert_eq!(tabs.active(), "second");
+        assert_eq!(buffer.draft, Draft::default());
+
+        let buffer = tabs.switch_to("first", buffer);
+
+        assert_eq!(tabs.channels(), ["first", "second"]);
+        assert_eq!(buffer.draft, draft);
+        assert_eq!(buffer.scroll_offset, 3);
+    }
+
+    #[test]
+    fn test_scroll_position_follows_anchor() {
+        let mut tabs = ChannelTabs::new("first");
+
+        let messages = ["c", "b", "a"].map(message).into();
+        tabs.switch_to("second", ChannelBuffer::new(messages, 1, Draft::default()));
+
+        tabs.push_message(Some("first"), message("d"), 4);
+        tabs.push_message(Some("first"), message("e"), 4);
+
+        let buffer = tabs.switch_to("first", ChannelBuffer::default());
+
+        // Still scrolled to "b", which now has two newer messages below it
+        assert_eq!(buffer.scroll_offset, 3);
+        assert_eq!(buffer.messages[3].payload, "b");
+
+        let buffer = tabs.switch_to("second", buffer);
+
+        tabs.push_message(Some("first"), message("f"), 4);
+
+        let buffer = tabs.switch_to("first", buffer);
+
+        // The anchored message was pushed out, so chat is back at the bottom
+        assert_eq!(buffer.scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_inactive_messages_are_unread() {
+        let mut tabs = ChannelTabs::new("first");
+        tabs.switch_to("second", ChannelBuffer::default());
+
+        assert!(tabs.push_message(Some("first"), message("a"), 10).is_none());
+        assert!(
+            tabs.push_message(Some("second"), message("b"), 10)
+                .is_some()
+        );
+        assert!(tabs.push_message(None, message("c"), 10).is_some());
+
+        assert_eq!(tabs.unread("first"), 1);
+
+        let buffer = tabs.switch_to("first", ChannelBuffer::default());
+
+        assert_eq!(buffer.messages.len(), 1);
+        assert_eq!(buffer.unread, 0);
+    }
+
+    #[test]
+    fn test_cycle_and_close() {
+        let mut tabs = ChannelTabs::new("first");
+        tabs.switch_to("second", ChannelBuffer::default());
+        tabs.switch_to("third", ChannelBuffer::default());
+
+        assert_eq!(tabs.next(), "first");
+        assert_eq!(tabs.previous(), "second");
+
+        let (closed, next, _) = tabs.close_active().unwrap();
+
+        assert_eq!(closed, "third");
+        assert_eq!(next, "second");
+
+        tabs.close_active().unwrap();
+
+        assert!(tabs.close_active().is_none());
+        assert_eq!(tabs.active(), "first");
+    }
+
+    #[test]
+    fn test_mixed_case_channels() {
+        let mut tabs = ChannelTabs::new("First");
+        tabs.switch_to("SomeChannel", ChannelBuffer::default());
+
+        assert_eq!(tabs.channels(), ["first", "somechannel"]);
+
+        // Messages from Twitch name channels in lowercase
+        assert!(tabs.push_message(Some("first"), message("a"), 10).is_none());
+        assert_eq!(tabs.unread("First"), 1);
+
+        let buffer = tabs.switch_to("FIRST", ChannelBuffer::default());
+
+        assert_eq!(tabs.active(), "first");
+        assert_eq!(buffer.messages.len(), 1);
+    }
+}
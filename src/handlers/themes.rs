@@ -0,0 +1,205 @@
+use std::{
+    collections::BTreeMap,
+    fs::{read_dir, read_to_string},
+    path::Path,
+    sync::{PoisonError, RwLock, RwLockReadGuard},
+};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, bail},
+};
+use serde::Deserialize;
+use tui::style::Color;
+
+use crate::{handlers::config::persist_frontend_setting, utils::pathing::config_path};
+
+/// The themes that are always available, as they are not loaded from files.
+const BUILT_IN_THEMES: [&str; 2] = ["dark", "light"];
+
+/// The theme that everything is drawn with, which is dark until a theme is chosen.
+static THEME: RwLock<Theme> = RwLock::new(Theme::dark());
+
+/// The colors of everything in the terminal that is not colored by Twitch or the user.
+/// Themes are either built in, or loaded from `themes/<name>.toml` in the config directory,
+/// where any color that is left out is taken from the dark theme.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// The background of the terminal, which is not drawn over,
+    /// but which usernames are made readable against.
+    pub background: Color,
+    /// The text of messages, and of the windows that list things.
+    pub text: Color,
+    /// The time and date in front of messages.
+    pub datetime: Color,
+    /// The colors that usernames are picked from, rather than from the palette.
+    pub usernames: Vec<Color>,
+    /// The borders of every window.
+    pub border: Color,
+    /// The titles along the top of windows.
+    pub title: Color,
+    /// What was found by searches.
+    pub search: Color,
+    /// The username of the user where it appears in messages, with the background behind it.
+    pub highlight: Color,
+    pub highlight_background: Color,
+    /// The background of messages that mention the user.
+    pub mention: Color,
+    /// The names of messages from the application itself, and from Twitch.
+    pub system: Color,
+    /// The backgrounds of messages matching highlight rules, by the names of the rules.
+    pub highlights: BTreeMap<String, Color>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    const fn dark() -> Self {
+        Self {
+            background: Color::Rgb(0, 0, 0),
+            text: Color::White,
+            datetime: Color::Rgb(173, 173, 184),
+            usernames: Vec::new(),
+            border: Color::Reset,
+            title: Color::Red,
+            search: Color::Red,
+            highlight: Color::Black,
+            highlight_background: Color::White,
+            mention: Color::Rgb(70, 45, 45),
+            system: Color::Red,
+            highlights: BTreeMap::new(),
+        }
+    }
+
+    fn light() -> Self {
+        Self {
+            background: Color::Rgb(255, 255, 255),
+            datetime: Color::Rgb(83, 83, 95),
+            highlight: Color::White,
+            highlight_background: Color::Black,
+            mention: Color::Rgb(255, 222, 222),
+            ..Self::dark()
+        }
+    }
+
+    fn from_toml(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Load a theme by its name, which is either `dark`, `light`, or the name of a file in the themes directory.
+    pub fn load(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "dark" => return Ok(Self::dark()),
+            "light" => return Ok(Self::light()),
+            _ => {}
+        }
+
+        let path = config_path(&format!("themes/{name}.toml"));
+
+        if !Path::new(&path).exists() {
+            bail!("Theme '{name}' is not built in, and there is no theme file at {path}");
+        }
+
+        let content = read_to_string(&path)
+            .wrap_err_with(|| format!("Theme file {path} could not be read"))?;
+
+        Self::from_toml(&content).wrap_err_with(|| format!("Theme file {path} is not valid"))
+    }
+
+    /// The color of a username, if the theme has colors for them, by a hash of the name.
+    pub fn username_color(&self, hash: usize) -> Option<Color> {
+        if self.usernames.is_empty() {
+            return None;
+        }
+
+        Some(self.usernames[hash % self.usernames.len()])
+    }
+}
+
+/// Draw everything with a theme from now on, which is seen the next time the terminal is drawn.
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(PoisonError::into_inner) = theme;
+}
+
+pub fn theme() -> RwLockReadGuard<'static, Theme> {
+    THEME.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The names of the built in themes, followed by the themes in the themes directory by name.
+pub fn available_themes() -> Vec<String> {
+    let mut files = read_dir(config_path("themes"))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == "toml")
+                })
+                .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+                .filter(|name| !BUILT_IN_THEMES.contains(&name.to_lowercase().as_str()))
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    BUILT_IN_THEMES
+        .iter()
+        .map(ToString::to_string)
+        .chain(files)
+        .collect()
+}
+
+/// The theme after the current one, going back to the first after the last.
+pub fn next_theme(current: &str) -> String {
+    let themes = available_themes();
+
+    let next = themes
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(current))
+        .map_or(0, |i| (i + 1) % themes.len());
+
+    themes[next].clone()
+}
+
+/// Save the theme to the config file, so that it is used the next time.
+pub fn persist_theme(name: &str) -> Result<()> {
+    persist_frontend_setting("theme", &toml::Value::String(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_file() {
+        let theme = Theme::from_toml(
+            r##"
+            text = "#839496"
+            usernames = ["red", "lightblue"]
+
+            [highlights]
+            game = "#3a3a00"
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(theme.text, Color::Rgb(131, 148, 150));
+        assert_eq!(theme.username_color(3), Some(Color::LightBlue));
+        assert_eq!(theme.title, Theme::dark().title);
+        assert_eq!(theme.highlights.get("game"), Some(&Color::Rgb(58, 58, 0)));
+
+        assert!(Theme::from_toml("txt = \"red\"").is_err());
+    }
+
+    #[test]
+    fn test_built_in_themes() {
+        assert_eq!(Theme::load("Light").unwrap(), Theme::light());
+        assert_eq!(Theme::dark().username_color(3), None);
+    }
+}
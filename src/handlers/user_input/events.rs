@@ -1,11 +1,15 @@
-use std::{fmt::Display, time::Duration};
+use std::{collections::VecDeque, fmt::Display, str::FromStr, time::Duration};
 
+use color_eyre::eyre::{Error, bail, eyre};
 use crossterm::event::{
-    self, Event as CEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEventKind,
+    self, Event as CEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton,
+    MouseEventKind,
 };
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 use tokio::{sync::mpsc, time::Instant};
+use tui::layout::Position;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(SerializeDisplay, DeserializeFromStr, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Key {
     // Keyboard controls
     Backspace,
@@ -19,6 +23,8 @@ pub enum Key {
     Delete,
     Insert,
     Tab,
+    /// Tab while holding shift.
+    BackTab,
     Enter,
     Char(char),
     Ctrl(char),
@@ -30,26 +36,114 @@ pub enum Key {
     ScrollDown,
 }
 
+/// Keys that have a name, rather than being written as the character they type.
+const NAMED_KEYS: [(&str, Key); 14] = [
+    ("Backspace", Key::Backspace),
+    ("Esc", Key::Esc),
+    ("Up", Key::Up),
+    ("Down", Key::Down),
+    ("Left", Key::Left),
+    ("Right", Key::Right),
+    ("Home", Key::Home),
+    ("End", Key::End),
+    ("Delete", Key::Delete),
+    ("Insert", Key::Insert),
+    ("Tab", Key::Tab),
+    ("BackTab", Key::BackTab),
+    ("Enter", Key::Enter),
+    ("Space", Key::Char(' ')),
+];
+
+impl FromStr for Key {
+    type Err = Error;
+
+    /// Parse keys as they are shown in the keybinds window, such as `q`, `Ctrl + f`, or `Esc`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let (modifier, key) = match s.split_once('+') {
+            Some((modifier, key)) if !modifier.trim().is_empty() && !key.trim().is_empty() => {
+                (Some(modifier.trim()), key.trim())
+            }
+            _ => (None, s),
+        };
+
+        let mut chars = key.chars();
+
+        let (Some(character), None) = (chars.next(), chars.next()) else {
+            return NAMED_KEYS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .filter(|_| modifier.is_none())
+                .map(|(_, key)| *key)
+                .ok_or_else(|| eyre!("Key '{}' cannot be deserialized", s));
+        };
+
+        match modifier.map(str::to_lowercase).as_deref() {
+            None => Ok(Self::Char(character)),
+            Some("ctrl") => Ok(Self::Ctrl(character)),
+            Some("alt") => Ok(Self::Alt(character)),
+            Some(_) => bail!("Key '{}' cannot be deserialized", s),
+        }
+    }
+}
+
 impl Display for Key {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Char(c) | Self::Ctrl(c) | Self::Alt(c) => c,
-                _ => unimplemented!(),
-            }
-        )
+        if let Some((name, _)) = NAMED_KEYS.iter().find(|(_, key)| key == self) {
+            return write!(f, "{name}");
+        }
+
+        match self {
+            Self::Char(c) => write!(f, "{c}"),
+            Self::Ctrl(c) => write!(f, "Ctrl + {c}"),
+            Self::Alt(c) => write!(f, "Alt + {c}"),
+            Self::ScrollUp => write!(f, "Scroll up"),
+            Self::ScrollDown => write!(f, "Scroll down"),
+            _ => write!(f, "Null"),
+        }
     }
 }
 
+/// The keys that type out pasted text, for inputs that only take a single line one key at a time.
+/// Line breaks and tabs become spaces, and other control characters are left out.
+pub fn paste_keys(text: &str) -> Vec<Key> {
+    text.replace("\r\n", "\n")
+        .chars()
+        .filter_map(|c| match c {
+            '\n' | '\r' | '\t' => Some(Key::Char(' ')),
+            c if c.is_control() => None,
+            c => Some(Key::Char(c)),
+        })
+        .collect()
+}
+
+/// What was done with the left mouse button, which is only reported when mouse support is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    Click,
+    /// The mouse moved while the button was held down.
+    Drag,
+    Release,
+}
+
 pub enum Event {
     Input(Key),
+    /// A mouse action, along with the cell that the mouse was over.
+    Mouse(MouseAction, Position),
+    /// The terminal window gained or lost focus, which is only reported when the unread marker is enabled.
+    Focus(bool),
+    /// Text pasted into the terminal all at once, rather than as keys that would send it line by line.
+    Paste(String),
     Tick,
 }
 
 pub struct Events {
     rx: mpsc::Receiver<Event>,
+    /// Keys that should be handled before any new terminal events, with if they replay a macro.
+    injected: VecDeque<(Key, bool)>,
+    /// If the last event came from replaying a macro.
+    replaying: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -92,6 +186,7 @@ impl Events {
                                 KeyCode::Delete => Key::Delete,
                                 KeyCode::Insert => Key::Insert,
                                 KeyCode::Tab => Key::Tab,
+                                KeyCode::BackTab => Key::BackTab,
                                 KeyCode::Enter => Key::Enter,
                                 KeyCode::Char(c) => match modifiers {
                                     KeyModifiers::NONE | KeyModifiers::SHIFT => Key::Char(c),
@@ -106,18 +201,43 @@ impl Events {
                                 return;
                             }
                         }
-                        Ok(CEvent::Mouse(key)) => {
-                            let key = match key.kind {
-                                MouseEventKind::ScrollDown => Key::ScrollDown,
-                                MouseEventKind::ScrollUp => Key::ScrollUp,
-                                _ => Key::Null,
+                        Ok(CEvent::Mouse(mouse)) => {
+                            let position = Position::new(mouse.column, mouse.row);
+
+                            let event = match mouse.kind {
+                                MouseEventKind::ScrollDown => Event::Input(Key::ScrollDown),
+                                MouseEventKind::ScrollUp => Event::Input(Key::ScrollUp),
+                                MouseEventKind::Down(MouseButton::Left) => {
+                                    Event::Mouse(MouseAction::Click, position)
+                                }
+                                MouseEventKind::Drag(MouseButton::Left) => {
+                                    Event::Mouse(MouseAction::Drag, position)
+                                }
+                                MouseEventKind::Up(MouseButton::Left) => {
+                                    Event::Mouse(MouseAction::Release, position)
+                                }
+                                _ => Event::Input(Key::Null),
                             };
 
-                            if let Err(err) = tx.send(Event::Input(key)).await {
+                            if let Err(err) = tx.send(event).await {
                                 eprintln!("Mouse input error: {err}");
                                 return;
                             }
                         }
+                        Ok(focus @ (CEvent::FocusGained | CEvent::FocusLost)) => {
+                            let focused = matches!(focus, CEvent::FocusGained);
+
+                            if let Err(err) = tx.send(Event::Focus(focused)).await {
+                                eprintln!("Focus event error: {err}");
+                                return;
+                            }
+                        }
+                        Ok(CEvent::Paste(text)) => {
+                            if let Err(err) = tx.send(Event::Paste(text)).await {
+                                eprintln!("Paste event error: {err}");
+                                return;
+                            }
+                        }
                         _ => (),
                     }
                 }
@@ -132,10 +252,56 @@ impl Events {
             }
         });
 
-        Self { rx }
+        Self {
+            rx,
+            injected: VecDeque::new(),
+            replaying: false,
+        }
+    }
+
+    pub fn inject(&mut self, keys: Vec<Key>) {
+        self.injected
+            .extend(keys.into_iter().map(|key| (key, false)));
+    }
+
+    /// Inject the keys of a macro, which are not recorded again if another macro is being recorded.
+    pub fn replay(&mut self, keys: Vec<Key>) {
+        self.injected
+            .extend(keys.into_iter().map(|key| (key, true)));
+    }
+
+    pub const fn is_replaying(&self) -> bool {
+        self.replaying
     }
 
     pub async fn next(&mut self) -> Option<Event> {
+        if let Some((key, replaying)) = self.injected.pop_front() {
+            self.replaying = replaying;
+
+            return Some(Event::Input(key));
+        }
+
+        self.replaying = false;
+
         self.rx.recv().await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paste_keys() {
+        assert_eq!(
+            paste_keys("a\r\nb\tc\x07"),
+            vec![
+                Key::Char('a'),
+                Key::Char(' '),
+                Key::Char('b'),
+                Key::Char(' '),
+                Key::Char('c'),
+            ]
+        );
+    }
+}
@@ -0,0 +1,471 @@
+use std::{fmt::Display, str::FromStr};
+
+use color_eyre::eyre::{Error, bail};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+use crate::handlers::{config::KeybindsConfig, user_input::events::Key};
+
+/// Something the user can do with a single key, outside of typing into a widget.
+#[derive(
+    SerializeDisplay, DeserializeFromStr, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum Action {
+    InsertMode,
+    Mention,
+    Command,
+    Emotes,
+    Help,
+    Quit,
+    ChannelSwitcher,
+    QuickSwitch,
+    Following,
+    Dashboard,
+    Projector,
+    SearchMessages,
+    ToggleFilter,
+    ReverseFilter,
+    Filters,
+    Ignored,
+    Notices,
+    Whispers,
+    Activity,
+    ModLog,
+    StreamInfo,
+    AcknowledgeAlert,
+    ModLogOlder,
+    ModLogNewer,
+    Raids,
+    FollowRaid,
+    GiftRecipients,
+    DoNotDisturb,
+    SelectMessage,
+    Rewards,
+    Mentions,
+    AutoMod,
+    LogSearch,
+    NextTab,
+    PreviousTab,
+    CloseTab,
+    RecordMacro,
+    ReplayMacro,
+    OpenInBrowser,
+    Links,
+    About,
+    RotateTheme,
+    ToggleTimestamps,
+    ScrollToNewest,
+    ScrollToOldest,
+}
+
+struct Binding {
+    action: Action,
+    /// What the action is called in the `[keybinds]` table of the config.
+    name: &'static str,
+    keys: &'static [Key],
+    description: &'static str,
+}
+
+/// Every action, in the order they are shown in the keybinds window, along with their default keys.
+const BINDINGS: [Binding; 45] = [
+    Binding {
+        action: Action::InsertMode,
+        name: "insert_mode",
+        keys: &[Key::Char('i'), Key::Char('c')],
+        description: "Enter message (chat) mode for sending messages",
+    },
+    Binding {
+        action: Action::Mention,
+        name: "mention",
+        keys: &[Key::Char('@')],
+        description: "Messaging mode with mention symbol",
+    },
+    Binding {
+        action: Action::Command,
+        name: "command",
+        keys: &[Key::Char('/')],
+        description: "Messaging mode with command symbol",
+    },
+    Binding {
+        action: Action::Emotes,
+        name: "emotes",
+        keys: &[Key::Char('E')],
+        description: "Pick an emote to put in a message",
+    },
+    Binding {
+        action: Action::Help,
+        name: "help",
+        keys: &[Key::Char('?'), Key::Char('h')],
+        description: "Have this keybinds window appear",
+    },
+    Binding {
+        action: Action::Quit,
+        name: "quit",
+        keys: &[Key::Char('q')],
+        description: "Quit the application",
+    },
+    Binding {
+        action: Action::ChannelSwitcher,
+        name: "channel_switcher",
+        keys: &[Key::Char('s')],
+        description: "Open the recent channel search widget",
+    },
+    Binding {
+        action: Action::QuickSwitch,
+        name: "quick_switch",
+        keys: &[Key::Char('b')],
+        description: "Switch back to a channel joined recently and often",
+    },
+    Binding {
+        action: Action::Following,
+        name: "following",
+        keys: &[Key::Char('f')],
+        description: "Open the followed channel search widget",
+    },
+    Binding {
+        action: Action::Dashboard,
+        name: "dashboard",
+        keys: &[Key::Char('S')],
+        description: "Go to the dashboard screen (start screen)",
+    },
+    Binding {
+        action: Action::Projector,
+        name: "projector",
+        keys: &[Key::Char('P')],
+        description: "Show chat only, for capturing on stream",
+    },
+    Binding {
+        action: Action::SearchMessages,
+        name: "search_messages",
+        keys: &[Key::Ctrl('f')],
+        description: "Search messages",
+    },
+    Binding {
+        action: Action::ToggleFilter,
+        name: "toggle_filter",
+        keys: &[Key::Ctrl('t')],
+        description: "Toggle the message filter",
+    },
+    Binding {
+        action: Action::ReverseFilter,
+        name: "reverse_filter",
+        keys: &[Key::Ctrl('r')],
+        description: "Reverse the message filter",
+    },
+    Binding {
+        action: Action::Filters,
+        name: "filters",
+        keys: &[Key::Char('X')],
+        description: "Add, remove, and change the filters",
+    },
+    Binding {
+        action: Action::Ignored,
+        name: "ignored",
+        keys: &[Key::Char('U')],
+        description: "Open the list of ignored users",
+    },
+    Binding {
+        action: Action::Notices,
+        name: "notices",
+        keys: &[Key::Ctrl('n')],
+        description: "Open the list of notices sent by Twitch",
+    },
+    Binding {
+        action: Action::Whispers,
+        name: "whispers",
+        keys: &[Key::Char('w')],
+        description: "Open the list of whispers sent and received",
+    },
+    Binding {
+        action: Action::Activity,
+        name: "activity",
+        keys: &[Key::Char('a')],
+        description: "Toggle the sidebar of users joining and leaving",
+    },
+    Binding {
+        action: Action::ModLog,
+        name: "mod_log",
+        keys: &[Key::Char('M')],
+        description: "Toggle the sidebar of moderator actions",
+    },
+    Binding {
+        action: Action::StreamInfo,
+        name: "stream_info",
+        keys: &[Key::Char('t')],
+        description: "Show the stream info on two lines, one line, or hide it",
+    },
+    Binding {
+        action: Action::AcknowledgeAlert,
+        name: "acknowledge_alert",
+        keys: &[Key::Char('x')],
+        description: "Acknowledge the critical alert shown over chat",
+    },
+    Binding {
+        action: Action::ModLogOlder,
+        name: "mod_log_older",
+        keys: &[Key::Char('J')],
+        description: "Scroll the moderator actions to older ones",
+    },
+    Binding {
+        action: Action::ModLogNewer,
+        name: "mod_log_newer",
+        keys: &[Key::Char('K')],
+        description: "Scroll the moderator actions to newer ones",
+    },
+    Binding {
+        action: Action::Raids,
+        name: "raids",
+        keys: &[Key::Char('R')],
+        description: "Open the history of raids",
+    },
+    Binding {
+        action: Action::FollowRaid,
+        name: "follow_raid",
+        keys: &[Key::Char('F')],
+        description: "Follow the raid of the current channel",
+    },
+    Binding {
+        action: Action::GiftRecipients,
+        name: "gift_recipients",
+        keys: &[Key::Char('e')],
+        description: "Show or hide who received gift subs",
+    },
+    Binding {
+        action: Action::DoNotDisturb,
+        name: "do_not_disturb",
+        keys: &[Key::Char('D')],
+        description: "Pause or resume desktop notifications and sounds",
+    },
+    Binding {
+        action: Action::SelectMessage,
+        name: "select_message",
+        keys: &[Key::Char('r')],
+        description: "Select a message to reply to",
+    },
+    Binding {
+        action: Action::Rewards,
+        name: "rewards",
+        keys: &[Key::Char('p')],
+        description: "Open the channel point rewards of your channel",
+    },
+    Binding {
+        action: Action::Mentions,
+        name: "mentions",
+        keys: &[Key::Char('m')],
+        description: "Open the list of messages that mentioned you",
+    },
+    Binding {
+        action: Action::AutoMod,
+        name: "automod",
+        keys: &[Key::Char('A')],
+        description: "Open the messages held by AutoMod",
+    },
+    Binding {
+        action: Action::LogSearch,
+        name: "log_search",
+        keys: &[Key::Char('H')],
+        description: "Search the chat logs of past sessions",
+    },
+    Binding {
+        action: Action::NextTab,
+        name: "next_tab",
+        keys: &[Key::Char(']')],
+        description: "Switch to the next channel tab",
+    },
+    Binding {
+        action: Action::PreviousTab,
+        name: "previous_tab",
+        keys: &[Key::Char('[')],
+        description: "Switch to the previous channel tab",
+    },
+    Binding {
+        action: Action::CloseTab,
+        name: "close_tab",
+        keys: &[Key::Char('W')],
+        description: "Close the current channel tab",
+    },
+    Binding {
+        action: Action::RecordMacro,
+        name: "record_macro",
+        keys: &[Key::Char('Q')],
+        description: "Start or stop recording a macro",
+    },
+    Binding {
+        action: Action::ReplayMacro,
+        name: "replay_macro",
+        keys: &[Key::Char('.')],
+        description: "Replay the last macro, prefix with a number to repeat it",
+    },
+    Binding {
+        action: Action::OpenInBrowser,
+        name: "open_in_browser",
+        keys: &[Key::Char('o')],
+        description: "Open the current channel in the browser",
+    },
+    Binding {
+        action: Action::Links,
+        name: "links",
+        keys: &[Key::Char('l')],
+        description: "List the links in recent messages, to open one in the browser",
+    },
+    Binding {
+        action: Action::About,
+        name: "about",
+        keys: &[Key::Char('I')],
+        description: "Show the version, build, and detected terminal features",
+    },
+    Binding {
+        action: Action::RotateTheme,
+        name: "rotate_theme",
+        keys: &[Key::Char('T')],
+        description: "Switch to the next theme, and save it to the config",
+    },
+    Binding {
+        action: Action::ToggleTimestamps,
+        name: "toggle_timestamps",
+        keys: &[Key::Char('C')],
+        description: "Show or hide the time and date of messages",
+    },
+    Binding {
+        action: Action::ScrollToNewest,
+        name: "scroll_to_newest",
+        keys: &[Key::Char('G')],
+        description: "Scroll to the newest messages",
+    },
+    Binding {
+        action: Action::ScrollToOldest,
+        name: "scroll_to_oldest",
+        keys: &[Key::Char('g')],
+        description: "Scroll to the oldest messages",
+    },
+];
+
+/// The actions that can be taken from the dashboard, with the rest only working in chat.
+pub const DASHBOARD_ACTIONS: [Action; 6] = [
+    Action::Help,
+    Action::Quit,
+    Action::ChannelSwitcher,
+    Action::Following,
+    Action::About,
+    Action::RotateTheme,
+];
+
+impl Action {
+    fn binding(self) -> &'static Binding {
+        BINDINGS
+            .iter()
+            .find(|binding| binding.action == self)
+            .expect("every action has a binding")
+    }
+
+    /// Every action, in the order they are shown in the keybinds window.
+    pub fn all() -> impl Iterator<Item = Self> {
+        BINDINGS.iter().map(|binding| binding.action)
+    }
+
+    pub fn description(self) -> &'static str {
+        self.binding().description
+    }
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match BINDINGS
+            .iter()
+            .find(|binding| binding.name.eq_ignore_ascii_case(s.trim()))
+        {
+            Some(binding) => Ok(binding.action),
+            None => bail!("Keybind action '{}' cannot be deserialized", s),
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.binding().name)
+    }
+}
+
+/// The keys that take an action, being the configured ones if it was rebound.
+/// Default keys that were given to another action in the config no longer take this one.
+pub fn keys(keybinds: &KeybindsConfig, action: Action) -> Vec<Key> {
+    if let Some(keys) = keybinds.get(&action) {
+        return keys.clone();
+    }
+
+    action
+        .binding()
+        .keys
+        .iter()
+        .filter(|key| !keybinds.values().any(|keys| keys.contains(key)))
+        .copied()
+        .collect()
+}
+
+/// The action that a key takes, if any.
+pub fn resolve(keybinds: &KeybindsConfig, key: Key) -> Option<Action> {
+    keybinds
+        .iter()
+        .find(|(_, keys)| keys.contains(&key))
+        .map(|(action, _)| *action)
+        .or_else(|| {
+            BINDINGS
+                .iter()
+                .find(|binding| {
+                    !keybinds.contains_key(&binding.action) && binding.keys.contains(&key)
+                })
+                .map(|binding| binding.action)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!("q".parse::<Key>().unwrap(), Key::Char('q'));
+        assert_eq!("Ctrl + f".parse::<Key>().unwrap(), Key::Ctrl('f'));
+        assert_eq!("alt+e".parse::<Key>().unwrap(), Key::Alt('e'));
+        assert_eq!("+".parse::<Key>().unwrap(), Key::Char('+'));
+        assert_eq!("esc".parse::<Key>().unwrap(), Key::Esc);
+        assert!("Shift + q".parse::<Key>().is_err());
+        assert!("Ctrl + Enter".parse::<Key>().is_err());
+
+        for key in [Key::Ctrl('f'), Key::Char(' '), Key::Enter, Key::Char('?')] {
+            assert_eq!(key.to_string().parse::<Key>().unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn test_default_keys_are_unique() {
+        for (i, binding) in BINDINGS.iter().enumerate() {
+            for key in binding.keys {
+                assert!(
+                    !BINDINGS[i + 1..]
+                        .iter()
+                        .any(|other| other.keys.contains(key)),
+                    "{key} is bound more than once"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rebound_keys() {
+        let mut keybinds = KeybindsConfig::default();
+
+        keybinds.insert(Action::Quit, vec![Key::Ctrl('q')]);
+        keybinds.insert(Action::Whispers, vec![Key::Char('i')]);
+
+        assert_eq!(resolve(&keybinds, Key::Ctrl('q')), Some(Action::Quit));
+        assert_eq!(resolve(&keybinds, Key::Char('q')), None);
+        assert_eq!(resolve(&keybinds, Key::Char('i')), Some(Action::Whispers));
+        assert_eq!(resolve(&keybinds, Key::Char('w')), None);
+        assert_eq!(resolve(&keybinds, Key::Char('c')), Some(Action::InsertMode));
+
+        assert_eq!(keys(&keybinds, Action::InsertMode), vec![Key::Char('c')]);
+        assert_eq!(keys(&keybinds, Action::Quit), vec![Key::Ctrl('q')]);
+    }
+}
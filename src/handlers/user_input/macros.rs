@@ -0,0 +1,209 @@
+use crate::handlers::{
+    config::KeybindsConfig,
+    user_input::{
+        events::Key,
+        keybinds::{Action, keys},
+    },
+};
+
+/// The highest amount of times a single macro can be replayed in one go.
+const MAXIMUM_REPLAY_COUNT: usize = 100;
+
+/// Something done while a macro was being recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recorded {
+    /// An action from normal mode, replayed with whichever key is bound to it at the time.
+    Action(Action),
+    /// A key typed into an input or popup, replayed as it was.
+    Key(Key),
+}
+
+#[derive(Debug, Default)]
+pub struct Macros {
+    /// If keys are currently being recorded.
+    recording: bool,
+    /// If the keys being handled come from replaying a macro, so they are not recorded again.
+    replaying: bool,
+    /// What has been recorded since recording started.
+    buffer: Vec<Recorded>,
+    /// The last macro that was fully recorded.
+    last: Vec<Recorded>,
+    /// Digits typed before a replay, to repeat the macro multiple times.
+    count: Option<usize>,
+}
+
+impl Macros {
+    pub const fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub const fn set_replaying(&mut self, replaying: bool) {
+        self.replaying = replaying;
+    }
+
+    pub fn toggle_recording(&mut self) {
+        if self.recording {
+            self.last = std::mem::take(&mut self.buffer);
+        } else {
+            self.buffer.clear();
+        }
+
+        self.count = None;
+        self.recording = !self.recording;
+    }
+
+    pub fn record(&mut self, recorded: Recorded) {
+        if self.recording && !self.replaying {
+            self.buffer.push(recorded);
+        }
+    }
+
+    pub fn push_count_digit(&mut self, digit: u32) {
+        let count = self.count.unwrap_or_default() * 10 + digit as usize;
+
+        self.count = Some(count.min(MAXIMUM_REPLAY_COUNT));
+    }
+
+    pub const fn clear_count(&mut self) {
+        self.count = None;
+    }
+
+    /// Get the keys of the last recorded macro, repeated by the count typed beforehand.
+    /// Actions that are no longer bound to any key are skipped.
+    pub fn replay(&mut self, keybinds: &KeybindsConfig) -> Option<Vec<Key>> {
+        let count = self.count.take().unwrap_or(1).max(1);
+
+        if self.last.is_empty() {
+            return None;
+        }
+
+        let recorded = self
+            .last
+            .iter()
+            .filter_map(|recorded| match recorded {
+                Recorded::Action(action) => keys(keybinds, *action).first().copied(),
+                Recorded::Key(key) => Some(*key),
+            })
+            .collect::<Vec<Key>>();
+
+        Some(std::iter::repeat_n(recorded, count).flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(keys: &[Key]) -> Macros {
+        let mut macros = Macros::default();
+
+        macros.toggle_recording();
+        for key in keys {
+            macros.record(Recorded::Key(*key));
+        }
+        macros.toggle_recording();
+
+        macros
+    }
+
+    #[test]
+    fn test_replay_nothing_recorded() {
+        let mut macros = Macros::default();
+
+        assert!(macros.replay(&KeybindsConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_keys_not_recorded_when_idle() {
+        let mut macros = Macros::default();
+
+        macros.record(Recorded::Key(Key::Char('a')));
+
+        assert!(macros.replay(&KeybindsConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_replay_with_count() {
+        let keybinds = KeybindsConfig::default();
+        let mut macros = recorded(&[Key::Char('a'), Key::Enter]);
+
+        macros.push_count_digit(1);
+        macros.push_count_digit(2);
+
+        let keys = macros.replay(&keybinds).unwrap();
+
+        assert_eq!(keys.len(), 24);
+
+        // The count is only used once
+        assert_eq!(macros.replay(&keybinds).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_cleared_count_not_used() {
+        let mut macros = recorded(&[Key::Char('a')]);
+
+        macros.push_count_digit(3);
+        macros.clear_count();
+
+        assert_eq!(macros.replay(&KeybindsConfig::default()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_replay_count_is_capped() {
+        let mut macros = recorded(&[Key::Char('a')]);
+
+        for _ in 0..5 {
+            macros.push_count_digit(9);
+        }
+
+        assert_eq!(
+            macros.replay(&KeybindsConfig::default()).unwrap().len(),
+            MAXIMUM_REPLAY_COUNT
+        );
+    }
+
+    #[test]
+    fn test_replay_actions_with_rebound_keys() {
+        let mut keybinds = KeybindsConfig::default();
+        let mut macros = Macros::default();
+
+        macros.toggle_recording();
+        macros.record(Recorded::Action(Action::Whispers));
+        macros.record(Recorded::Key(Key::Char('w')));
+        macros.toggle_recording();
+
+        assert_eq!(
+            macros.replay(&keybinds).unwrap(),
+            vec![Key::Char('w'), Key::Char('w')]
+        );
+
+        keybinds.insert(Action::Whispers, vec![Key::Char('i')]);
+
+        assert_eq!(
+            macros.replay(&keybinds).unwrap(),
+            vec![Key::Char('i'), Key::Char('w')]
+        );
+
+        keybinds.insert(Action::Whispers, vec![]);
+
+        assert_eq!(macros.replay(&keybinds).unwrap(), vec![Key::Char('w')]);
+    }
+
+    #[test]
+    fn test_replayed_keys_not_recorded() {
+        let mut macros = Macros::default();
+
+        macros.toggle_recording();
+        macros.record(Recorded::Key(Key::Char('a')));
+        macros.set_replaying(true);
+        macros.record(Recorded::Key(Key::Char('a')));
+        macros.set_replaying(false);
+        macros.record(Recorded::Key(Key::Char('b')));
+        macros.toggle_recording();
+
+        assert_eq!(
+            macros.replay(&KeybindsConfig::default()).unwrap(),
+            vec![Key::Char('a'), Key::Char('b')]
+        );
+    }
+}
@@ -1,2 +1,5 @@
 pub mod events;
+pub mod keybinds;
+pub mod macros;
+pub mod navigation;
 pub mod scrolling;
@@ -0,0 +1,130 @@
+use crate::handlers::user_input::events::Key;
+
+/// The highest count that can be typed before a motion.
+const MAXIMUM_COUNT: usize = 1000;
+
+/// A movement through messages, with the amount of times it is repeated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Older(usize),
+    Newer(usize),
+    /// Half of the messages shown, towards older ones.
+    HalfPageOlder(usize),
+    /// Half of the messages shown, towards newer ones.
+    HalfPageNewer(usize),
+    Oldest,
+    Newest,
+}
+
+/// What a key did, after following it along with the keys typed before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Navigated {
+    /// The key is part of a motion that is not complete yet, such as a count or the first `g` of `gg`.
+    Pending,
+    Moved(Motion),
+    /// The key is not a motion, with the count typed before it, if there was one.
+    Other(Option<usize>),
+}
+
+/// Follows vim style motions, such as `5k`, `gg`, `G`, and `Ctrl + d`.
+#[derive(Debug, Default)]
+pub struct Navigation {
+    /// Digits typed before a motion, to repeat it.
+    count: Option<usize>,
+    /// If `g` was typed, waiting for a second one.
+    pending_g: bool,
+}
+
+impl Navigation {
+    /// The count being typed, to show before the motion is complete.
+    pub const fn count(&self) -> Option<usize> {
+        self.count
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn key(&mut self, key: Key) -> Navigated {
+        let pending_g = std::mem::take(&mut self.pending_g);
+
+        // Zero only continues a count, rather than starting one
+        if let Key::Char(c @ '0'..='9') = key {
+            if c != '0' || self.count.is_some() {
+                let digit = c.to_digit(10).unwrap_or_default() as usize;
+                let count = self.count.unwrap_or_default() * 10 + digit;
+
+                self.count = Some(count.min(MAXIMUM_COUNT));
+
+                return Navigated::Pending;
+            }
+        }
+
+        if key == Key::Char('g') && !pending_g {
+            self.pending_g = true;
+
+            return Navigated::Pending;
+        }
+
+        let count = self.count.take();
+        let repeat = count.unwrap_or(1);
+
+        Navigated::Moved(match key {
+            Key::Char('g') => Motion::Oldest,
+            Key::Char('G') => Motion::Newest,
+            Key::Char('k') | Key::Up | Key::ScrollUp => Motion::Older(repeat),
+            Key::Char('j') | Key::Down | Key::ScrollDown => Motion::Newer(repeat),
+            Key::Ctrl('u') => Motion::HalfPageOlder(repeat),
+            Key::Ctrl('d') => Motion::HalfPageNewer(repeat),
+            _ => return Navigated::Other(count),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follow(navigation: &mut Navigation, keys: &str) -> Vec<Navigated> {
+        keys.chars().map(|c| navigation.key(Key::Char(c))).collect()
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut navigation = Navigation::default();
+
+        assert_eq!(
+            follow(&mut navigation, "12k").last(),
+            Some(&Navigated::Moved(Motion::Older(12)))
+        );
+        assert_eq!(
+            navigation.key(Key::Ctrl('d')),
+            Navigated::Moved(Motion::HalfPageNewer(1))
+        );
+
+        // Zero does not start a count
+        assert_eq!(navigation.key(Key::Char('0')), Navigated::Other(None));
+
+        assert_eq!(
+            follow(&mut navigation, "3b").last(),
+            Some(&Navigated::Other(Some(3)))
+        );
+        assert_eq!(navigation.count(), None);
+    }
+
+    #[test]
+    fn test_go_to_oldest() {
+        let mut navigation = Navigation::default();
+
+        assert_eq!(
+            follow(&mut navigation, "gg"),
+            vec![Navigated::Pending, Navigated::Moved(Motion::Oldest)]
+        );
+
+        // A single `g` followed by something else is dropped
+        assert_eq!(
+            follow(&mut navigation, "gj"),
+            vec![Navigated::Pending, Navigated::Moved(Motion::Newer(1))]
+        );
+    }
+}
@@ -1,15 +1,20 @@
+use std::cell::Cell;
+
 pub struct Scrolling {
     /// Offset of scroll
-    offset: usize,
+    offset: Cell<usize>,
     /// If the scrolling is currently inverted
     inverted: bool,
+    /// How many messages arrived while scrolled up, that have not been scrolled down to yet
+    unseen: Cell<usize>,
 }
 
 impl Scrolling {
     pub const fn new(inverted: bool) -> Self {
         Self {
-            offset: 0,
+            offset: Cell::new(0),
             inverted,
+            unseen: Cell::new(0),
         }
     }
 
@@ -17,20 +22,34 @@ impl Scrolling {
         self.inverted
     }
 
-    pub fn up(&mut self) {
-        self.offset = self.offset.saturating_add(1);
+    pub fn up(&self) {
+        self.offset.set(self.offset.get().saturating_add(1));
+    }
+
+    pub fn down(&self) {
+        self.jump_to(self.offset.get().saturating_sub(1));
     }
 
-    pub fn down(&mut self) {
-        self.offset = self.offset.saturating_sub(1);
+    pub fn jump_to(&self, index: usize) {
+        self.offset.set(index);
+        self.unseen.set(self.unseen.get().min(index));
     }
 
-    pub fn jump_to(&mut self, index: usize) {
-        self.offset = index;
+    pub fn get_offset(&self) -> usize {
+        self.offset.get()
     }
 
-    pub const fn get_offset(&self) -> usize {
-        self.offset
+    /// Keep the same messages shown when a new one arrives while scrolled up, counting it as unseen.
+    pub fn message_added(&self) {
+        if self.offset.get() > 0 {
+            self.up();
+            self.unseen.set(self.unseen.get() + 1);
+        }
+    }
+
+    /// The messages below the ones shown that arrived while scrolled up.
+    pub fn unseen(&self) -> usize {
+        self.unseen.get()
     }
 }
 
@@ -40,10 +59,32 @@ mod tests {
 
     #[test]
     fn test_no_scroll_overflow_not_inverted() {
-        let mut scroll = Scrolling::new(false);
+        let scroll = Scrolling::new(false);
         assert_eq!(scroll.get_offset(), 0);
 
         scroll.down();
         assert_eq!(scroll.get_offset(), 0);
     }
+
+    #[test]
+    fn test_unseen_messages() {
+        let scroll = Scrolling::new(false);
+
+        // Messages only stay in place when scrolled up
+        scroll.message_added();
+        assert_eq!((scroll.get_offset(), scroll.unseen()), (0, 0));
+
+        scroll.jump_to(5);
+        scroll.message_added();
+        scroll.message_added();
+        assert_eq!((scroll.get_offset(), scroll.unseen()), (7, 2));
+
+        // Scrolling down to the new messages sees them
+        scroll.jump_to(2);
+        scroll.down();
+        assert_eq!((scroll.get_offset(), scroll.unseen()), (1, 1));
+
+        scroll.jump_to(0);
+        assert_eq!(scroll.unseen(), 0);
+    }
 }
@@ -17,16 +17,24 @@
     clippy::too_many_arguments
 )]
 
-use std::thread;
+use std::{env, thread, time::Duration};
 
 use clap::Parser;
-use color_eyre::eyre::{Result, WrapErr};
+use color_eyre::eyre::{ContextCompat, Result, WrapErr};
 use log::{info, warn};
 use tokio::sync::{broadcast, mpsc};
 
 use crate::{
-    handlers::{app::App, args::Cli, config::CompleteConfig},
-    utils::emotes::emotes_enabled,
+    emotes::support_graphics_protocol,
+    handlers::{
+        app::App,
+        args::Cli,
+        config::{BadgeStyle, CompleteConfig, ConfigWatcher},
+        import::import_settings,
+        schema::DEFAULT_CONFIG,
+    },
+    ui::components::StartupChoice,
+    utils::{about, emotes::emotes_enabled, status_command::run_status_command, traffic},
 };
 
 mod commands;
@@ -66,25 +74,108 @@ fn initialize_logging(config: &CompleteConfig) {
     }
 }
 
+fn print_version(verbose: bool) {
+    println!("{}", about::version_line());
+
+    if verbose {
+        // The terminal is only asked about graphics when it is one that could support them
+        if let Err(err) = support_graphics_protocol() {
+            eprintln!("Unable to check for the graphics protocol: {err}");
+        }
+
+        for (name, value) in about::about() {
+            println!("{name}: {value}");
+        }
+    }
+}
+
+/// Make sure the token works, or log in through the browser for one if there is none.
+async fn log_in(config: &mut CompleteConfig) -> Result<()> {
+    if let Some(token) = &config.twitch.token {
+        return twitch::oauth::check_token(token)
+            .await
+            .wrap_err("Unable to log in to Twitch.");
+    }
+
+    let client_id = config
+        .twitch
+        .client_id
+        .clone()
+        .context("Either a token or a client ID has to be set.")?;
+
+    let token = terminal::log_in(config, &client_id)
+        .await
+        .wrap_err("Unable to log in to Twitch.")?;
+
+    config.twitch.token = Some(token);
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let startup_time = chrono::Local::now();
 
     color_eyre::install().unwrap();
 
-    let mut config = CompleteConfig::new(Cli::parse())
+    let cli = Cli::parse();
+
+    if cli.version {
+        print_version(cli.verbose);
+        return Ok(());
+    }
+
+    if cli.print_default_config {
+        print!("{DEFAULT_CONFIG}");
+        return Ok(());
+    }
+
+    if let Some(source) = cli.import_source() {
+        return import_settings(&source).wrap_err("Settings could not be imported.");
+    }
+
+    let mut config = CompleteConfig::new(&cli)
         .wrap_err("Configuration error.")
         .unwrap();
 
+    traffic::set_strict_privacy(config.terminal.strict_privacy);
+    twitch::cache::configure(&config.cache);
+
     initialize_logging(&config);
 
     info!("Logging system initialised");
 
+    // Tokens from logging in through the browser are kept encrypted, unlike the one in the config file
+    let plain_token =
+        config.twitch.token.is_some() && env::var("TWT_TOKEN").is_err() && config.storage.encrypted;
+
+    while let Err(err) = log_in(&mut config).await {
+        warn!("Unable to start: {err:#}");
+
+        match terminal::startup_error(&config, &err).await {
+            StartupChoice::Retry => {}
+            StartupChoice::Settings => config
+                .edit_twitch_settings()
+                .wrap_err("Settings could not be saved.")
+                .unwrap(),
+            StartupChoice::Quit => std::process::exit(1),
+        }
+    }
+
     let (twitch_tx, terminal_rx) = mpsc::channel(100);
     let (terminal_tx, twitch_rx) = broadcast::channel(100);
 
     let app = App::new(config.clone(), startup_time);
 
+    if plain_token {
+        app.add_system_message(
+            "Storage is encrypted, but the token in config.toml is not. Move it to the TWT_TOKEN environment variable, or log in through the browser, to keep it out of the file.".to_string(),
+        );
+    }
+
+    // Started after logging in, so that settings saved while logging in are not seen as changes
+    let watcher = ConfigWatcher::new(cli);
+
     info!("Started tokio communication channels.");
 
     let decoded_rx = if emotes_enabled(&config.frontend) {
@@ -99,6 +190,8 @@ async fn main() -> Result<()> {
                     )
                 });
 
+                emotes::ANIMATE_EMOTES.get_or_init(|| config.frontend.animated_emotes);
+
                 let (decoder_tx, decoder_rx) = mpsc::channel(100);
                 emotes::DECODE_EMOTE_SENDER.get_or_init(|| decoder_tx);
 
@@ -115,6 +208,9 @@ async fn main() -> Result<()> {
                 config.frontend.betterttv_emotes = false;
                 config.frontend.seventv_emotes = false;
                 config.frontend.frankerfacez_emotes = false;
+                if config.frontend.badges == BadgeStyle::Images {
+                    config.frontend.badges = BadgeStyle::Unicode;
+                }
                 warn!("Unable to query terminal for it's dimensions, disabling emotes. {e}");
                 None
             }
@@ -123,13 +219,50 @@ async fn main() -> Result<()> {
         None
     };
 
+    if let Some(command) = config.frontend.status_command.clone() {
+        let interval = Duration::from_secs(config.frontend.status_command_interval.max(1));
+
+        tokio::task::spawn(run_status_command(command, interval));
+    }
+
+    // The stream info can be shown with a key at any time, so the live status is always checked
+    tokio::task::spawn(twitch::streams::live_status(
+        config.clone(),
+        twitch_tx.clone(),
+        terminal_tx.subscribe(),
+    ));
+
+    if config.notifications.live {
+        tokio::task::spawn(twitch::streams::notify_followed_live(config.clone()));
+    }
+
+    tokio::task::spawn(twitch::oauth::keep_token_valid(
+        config.clone(),
+        twitch_tx.clone(),
+    ));
+
     let cloned_config = config.clone();
 
+    tokio::task::spawn(twitch::eventsub::eventsub(
+        config.clone(),
+        twitch_tx.clone(),
+        terminal_tx.subscribe(),
+    ));
+
     tokio::task::spawn(async move {
         twitch::twitch_irc(config, twitch_tx, twitch_rx).await;
     });
 
-    terminal::ui_driver(cloned_config, app, terminal_tx, terminal_rx, decoded_rx).await;
+    // The driver holds the whole app, which is too large to keep on the stack
+    Box::pin(terminal::ui_driver(
+        cloned_config,
+        app,
+        watcher,
+        terminal_tx,
+        terminal_rx,
+        decoded_rx,
+    ))
+    .await;
 
     std::process::exit(0)
 }
@@ -1,20 +1,51 @@
-use std::time::Duration;
+use std::{cell::RefCell, rc::Rc, time::Duration};
 
+use chrono::Local;
+use color_eyre::{Report, Result, eyre::eyre};
 use log::{debug, info, warn};
-use tokio::sync::{broadcast::Sender, mpsc::Receiver};
+use tokio::{
+    sync::{broadcast::Sender, mpsc::Receiver},
+    time::{Instant, interval, sleep_until},
+};
+use tui::{buffer::Buffer, layout::Rect};
 
 use crate::{
-    commands::{init_terminal, quit_terminal, reset_terminal},
+    commands::{draw_hyperlinks, init_terminal, quit_terminal, reset_terminal},
     emotes::{ApplyCommand, DecodedEmote, display_emote, query_emotes},
     handlers::{
         app::App,
-        config::CompleteConfig,
-        data::{MessageData, TwitchToTerminalAction},
+        config::{CompleteConfig, ConfigWatcher},
+        data::{MessageData, Reply, TwitchToTerminalAction},
+        export::{export_messages, parse_export_command},
+        highlights::rule_name,
+        ignored::parse_ignore_command,
+        mentions::Mention,
+        nicknames::parse_nick_command,
         state::State,
-        user_input::events::{Config, Events, Key},
+        user_input::events::{Config, Event, Events, Key},
+    },
+    twitch::{
+        TwitchAction,
+        channel_info::parse_channel_info_command,
+        moderation::{ModerationCommand, parse_moderation_command},
+        oauth::{get_twitch_client_id, poll_device_code, request_device_code, saved_token},
+        outbox::Outgoing,
+        polls::parse_poll_command,
+        raids::{Raid, parse_raid_command},
+        whispers::{Whisper, parse_whisper_command},
+    },
+    ui::{
+        components::{Component, DeviceCodeWidget, StartupChoice, StartupErrorWidget},
+        statics::TWITCH_MESSAGE_LIMIT,
+    },
+    utils::{
+        emotes::emotes_enabled,
+        language::is_foreign,
+        links::hyperlinks_supported,
+        notifications::{NotificationKind, notify, toggle_do_not_disturb},
+        sounds::{SoundEvent, play},
+        text::{split_message, split_outgoing},
     },
-    twitch::{TwitchAction, oauth::get_twitch_client_id},
-    utils::emotes::emotes_enabled,
 };
 
 pub enum TerminalAction {
@@ -22,12 +53,131 @@ pub enum TerminalAction {
     BackOneLayer,
     SwitchState(State),
     ClearMessages,
+    ReplayKeys(Vec<Key>),
+    ReplayMacro(Vec<Key>),
+    CloseTab,
+    ToggleGiftRecipients,
+    ToggleDoNotDisturb,
+    ToggleAbout,
+    RotateTheme,
     Enter(TwitchAction),
 }
 
+/// The longest chat is drawn again on its own, before everything else is drawn again to keep it up to date.
+const FULL_REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Toggles dry-run mode for moderation commands.
+const DRY_RUN_COMMAND: &str = "/dryrun";
+
+/// Run a moderation command, or only show what it would do if dry-run mode is enabled.
+fn send_moderation(app: &App, tx: &Sender<TwitchAction>, command: ModerationCommand) {
+    if app.config.borrow().twitch.dry_run {
+        app.add_system_message(format!("Would execute: {}", command.describe()));
+    } else {
+        tx.send(TwitchAction::Moderate(command)).unwrap();
+    }
+}
+
+/// Ignore a user or stop ignoring them, blocking or unblocking them on Twitch if blocks are synced.
+fn ignore_user(app: &App, tx: &Sender<TwitchAction>, user: String, ignored: bool) {
+    app.set_ignored(&user, ignored);
+
+    app.add_system_message(if ignored {
+        format!("Messages from {user} are now hidden.")
+    } else {
+        format!("Messages from {user} are shown again.")
+    });
+
+    if app.config.borrow().filters.twitch_blocks {
+        tx.send(TwitchAction::Ignore(user, ignored)).unwrap();
+    }
+}
+
+/// Log in by having the user enter a code on Twitch from their browser, showing the code until it is entered.
+/// The token saved from logging in before is used instead if there is one.
+pub async fn log_in(config: &CompleteConfig, client_id: &str) -> Result<String> {
+    if let Some(token) = saved_token(client_id).await {
+        return Ok(token);
+    }
+
+    let code = request_device_code(client_id).await?;
+
+    let mut events = Events::with_config(Config {
+        exit_key: Key::Null,
+        tick_rate: Duration::from_millis(config.terminal.delay),
+    });
+
+    let mut poll = interval(Duration::from_secs(code.interval.max(1)));
+    let expiry = Instant::now() + Duration::from_secs(code.expires_in);
+
+    let mut widget = DeviceCodeWidget::new(Rc::new(RefCell::new(config.clone())), code.clone());
+
+    let mut terminal = init_terminal(&config.frontend);
+
+    terminal.clear().unwrap();
+
+    let token = loop {
+        terminal.draw(|f| widget.draw(f, None)).unwrap();
+
+        tokio::select! {
+            Some(event) = events.next() => {
+                if matches!(event, Event::Input(Key::Esc | Key::Ctrl('c'))) {
+                    break Err(eyre!("Logging in was cancelled."));
+                }
+            }
+            _ = poll.tick() => {
+                match poll_device_code(client_id, &code).await {
+                    Ok(Some(token)) => break Ok(token),
+                    Ok(None) => {}
+                    Err(err) => break Err(err),
+                }
+            }
+            () = sleep_until(expiry) => {
+                break Err(eyre!("The code expired before it was entered."));
+            }
+        }
+    };
+
+    quit_terminal(terminal);
+
+    token
+}
+
+/// Show why starting up failed, until the user chooses to retry, change the settings, or quit.
+pub async fn startup_error(config: &CompleteConfig, err: &Report) -> StartupChoice {
+    let mut events = Events::with_config(Config {
+        exit_key: Key::Null,
+        tick_rate: Duration::from_millis(config.terminal.delay),
+    });
+
+    let mut widget = StartupErrorWidget::new(Rc::new(RefCell::new(config.clone())), err);
+
+    let mut terminal = init_terminal(&config.frontend);
+
+    terminal.clear().unwrap();
+
+    let choice = loop {
+        terminal.draw(|f| widget.draw(f, None)).unwrap();
+
+        match events.next().await {
+            Some(Event::Input(Key::Char('r'))) => break StartupChoice::Retry,
+            Some(Event::Input(Key::Char('s'))) => break StartupChoice::Settings,
+            Some(Event::Input(Key::Esc | Key::Char('q') | Key::Ctrl('c'))) | None => {
+                break StartupChoice::Quit;
+            }
+            _ => {}
+        }
+    };
+
+    quit_terminal(terminal);
+
+    choice
+}
+
 pub async fn ui_driver(
-    config: CompleteConfig,
+    mut config: CompleteConfig,
     mut app: App,
+    mut watcher: ConfigWatcher,
     tx: Sender<TwitchAction>,
     mut rx: Receiver<TwitchToTerminalAction>,
     mut drx: Option<Receiver<Result<DecodedEmote, String>>>,
@@ -56,12 +206,46 @@ pub async fn ui_driver(
 
     let is_emotes_enabled = emotes_enabled(&config.frontend);
 
+    let hyperlinks = config.frontend.hyperlinks && hyperlinks_supported();
+
+    let mut previous_frame = Buffer::empty(Rect::default());
+    let mut last_full_redraw = Instant::now();
+
     loop {
+        // Only chat is drawn again if nothing but new messages changed, which is most of the time in busy chats
+        let mut new_message = false;
+        let mut other_change = false;
+
+        // Compared against the config as it is now, since the channel and such change while running
+        let reloaded = watcher.reload(&app.config.borrow());
+
+        if let Some(reloaded) = reloaded {
+            other_change = true;
+
+            match reloaded.and_then(|reloaded| app.reload_config(reloaded)) {
+                Ok(()) => {
+                    info!("Reloaded the config file.");
+
+                    config = app.config.borrow().clone();
+                    app.add_system_message("Config reloaded.".to_string());
+                }
+                Err(e) => {
+                    warn!("Unable to reload the config file: {e:#}");
+
+                    app.add_system_message(format!("Config could not be reloaded: {e:#}"));
+                }
+            }
+        }
+
         if is_emotes_enabled {
             // Check if we have received any emotes
-            if let Ok((user_emotes, global_emotes)) = erx.try_recv() {
+            if let Ok((user_emotes, global_emotes, overriding_emotes, badges)) = erx.try_recv() {
+                other_change = true;
+
                 *app.emotes.user_emotes.borrow_mut() = user_emotes;
                 *app.emotes.global_emotes.borrow_mut() = global_emotes;
+                *app.emotes.overriding_emotes.borrow_mut() = overriding_emotes;
+                *app.emotes.badges.borrow_mut() = badges;
 
                 for message in &mut *app.messages.borrow_mut() {
                     message.reparse_emotes(&app.emotes, is_emotes_enabled);
@@ -71,6 +255,8 @@ pub async fn ui_driver(
             // Check if we need to load a decoded emote
             if let Some(rx) = &mut drx {
                 if let Ok(r) = rx.try_recv() {
+                    other_change = true;
+
                     match r {
                         Ok(d) => {
                             if let Err(e) = d.apply() {
@@ -91,35 +277,166 @@ pub async fn ui_driver(
         }
 
         if let Ok(msg) = rx.try_recv() {
+            new_message = matches!(msg, TwitchToTerminalAction::Message(_));
+            other_change = !new_message;
+
             match msg {
                 TwitchToTerminalAction::Message(m) => {
-                    app.messages
-                        .borrow_mut()
-                        .push_front(MessageData::from_twitch_message(
-                            m,
-                            &app.emotes,
-                            is_emotes_enabled,
-                        ));
+                    let channel = m.channel.clone();
+
+                    let mut message =
+                        MessageData::from_twitch_message(m, &app.emotes, is_emotes_enabled);
+
+                    message.mentioned = message.mentions(&config.twitch.username);
+                    message.foreign_language = is_foreign(
+                        &config.filters.language,
+                        channel.as_deref().unwrap_or(&config.twitch.channel),
+                        &message.payload,
+                    );
+
+                    let hidden = app.hides_message(
+                        channel.as_deref().unwrap_or(&config.twitch.channel),
+                        &message,
+                    );
+
+                    if hidden {
+                        // Hidden messages neither notify nor count as mentions
+                    } else if message.mentioned {
+                        notify(
+                            &config.notifications,
+                            NotificationKind::Mention,
+                            &format!("{} mentioned you", message.author),
+                            &message.payload,
+                        );
+                        play(&config.sounds, SoundEvent::Mention);
+
+                        app.add_mention(Mention::new(channel.clone(), &message));
+                    } else if let Some(rule) = app
+                        .highlights
+                        .borrow()
+                        .find(&message.payload)
+                        .filter(|rule| rule.notify)
+                    {
+                        notify(
+                            &config.notifications,
+                            NotificationKind::Highlight,
+                            &format!("{} mentioned {}", message.author, rule_name(rule)),
+                            &message.payload,
+                        );
+                    }
+
+                    let shown = app.add_message(channel.as_deref(), message);
 
                     // If scrolling is enabled, pad for more messages.
-                    if app.components.chat.scroll_offset.get_offset() > 0 {
+                    if shown && app.components.chat.scroll_offset.get_offset() > 0 {
                         app.components.chat.scroll_offset.up();
                     }
                 }
-                TwitchToTerminalAction::ClearChat(user_id) => {
+                TwitchToTerminalAction::ClearChat(channel, user_id) => {
                     if let Some(user) = user_id {
-                        app.purge_user_messages(user.as_str());
+                        app.purge_user_messages(channel.as_deref(), user.as_str());
                     } else {
-                        app.clear_messages();
+                        app.clear_channel_messages(channel.as_deref());
+                    }
+                }
+                TwitchToTerminalAction::DeleteMessage(channel, message_id) => {
+                    app.delete_message_with(channel.as_deref(), message_id.as_str());
+                }
+                TwitchToTerminalAction::Notice(notice) => {
+                    app.add_notice(notice);
+                }
+                TwitchToTerminalAction::Whisper(whisper) => {
+                    // Whispers from ignored users are kept in the list of whispers, without a word about them
+                    let ignored = app.ignored.borrow().contains(&whisper.user);
+
+                    if !ignored {
+                        notify(
+                            &config.notifications,
+                            NotificationKind::Whisper,
+                            &format!("Whisper from {}", whisper.user),
+                            &whisper.message,
+                        );
+                        play(&config.sounds, SoundEvent::Whisper);
+                    }
+
+                    // Let the user know about the whisper if they can't see it arrive
+                    if !ignored
+                        && (app.get_state() != State::Normal
+                            || !app.components.chat.is_whispers_focused())
+                    {
+                        app.add_system_message(format!(
+                            "Whisper from {}: {} (press w to see all whispers)",
+                            whisper.user, whisper.message
+                        ));
                     }
+
+                    app.add_whisper(whisper);
+                }
+                TwitchToTerminalAction::Membership(membership) => {
+                    app.add_membership(membership);
+                }
+                TwitchToTerminalAction::Raid(raid) => {
+                    if !raid.outgoing {
+                        play(&config.sounds, SoundEvent::Raid);
+                    }
+
+                    app.add_raid(raid);
+                }
+                TwitchToTerminalAction::GiftSub(gift) => {
+                    // Bursts of gift subs only play the sound once
+                    if app.add_gift_sub(gift) {
+                        play(&config.sounds, SoundEvent::Subscription);
+                    }
+                }
+                TwitchToTerminalAction::AutoModHeld(held) => {
+                    if let Some(alert) = app.alert_rules.message_held(&held.channel, held.held_at) {
+                        app.add_alert(alert);
+                    }
+
+                    app.add_held_message(held);
+                }
+                TwitchToTerminalAction::AutoModResolved(message_id) => {
+                    app.remove_held_message(&message_id);
                 }
-                TwitchToTerminalAction::DeleteMessage(message_id) => {
-                    app.remove_message_with(message_id.as_str());
+                TwitchToTerminalAction::ModAction(mod_action) => {
+                    if let Some(alert) = app.alert_rules.mod_action(&mod_action) {
+                        app.add_alert(alert);
+                    }
+
+                    app.add_mod_action(mod_action);
+                }
+                TwitchToTerminalAction::Alert(alert) => {
+                    app.add_alert(alert);
+                }
+                TwitchToTerminalAction::Poll(poll) => {
+                    app.update_poll(poll);
+                }
+                TwitchToTerminalAction::Redemption(redemption) => {
+                    if !config.filters.hide_redemptions {
+                        app.add_redemption(&redemption);
+                    }
+                }
+                TwitchToTerminalAction::Subscription(subscription) => {
+                    play(&config.sounds, SoundEvent::Subscription);
+                    app.add_subscription(&subscription);
+                }
+                TwitchToTerminalAction::LiveStatus(channel, live) => {
+                    app.set_live(channel, live);
+                }
+                TwitchToTerminalAction::Recovered(items) => {
+                    app.components.chat.recover(items);
+                }
+                TwitchToTerminalAction::BlockedUsers(users) => {
+                    app.ignored.borrow_mut().set_blocked(&users);
                 }
             }
         }
 
         if let Some(event) = events.next().await {
+            other_change |= !matches!(event, Event::Tick);
+
+            app.components.chat.set_replaying(events.is_replaying());
+
             if let Some(action) = app.event(&event).await {
                 match action {
                     TerminalAction::Quit => {
@@ -144,55 +461,296 @@ pub async fn ui_driver(
                         app.set_state(state);
                     }
                     TerminalAction::ClearMessages => {
-                        app.clear_messages();
+                        if !app.config.borrow().twitch.dry_run {
+                            app.clear_messages();
+                        }
 
-                        tx.send(TwitchAction::ClearMessages).unwrap();
+                        send_moderation(&app, &tx, ModerationCommand::Clear);
+                    }
+                    TerminalAction::ReplayKeys(keys) => {
+                        events.inject(keys);
+                    }
+                    TerminalAction::ReplayMacro(keys) => {
+                        events.replay(keys);
+                    }
+                    TerminalAction::CloseTab => {
+                        if let Some((closed, channel)) = app.close_channel() {
+                            tx.send(TwitchAction::Part(closed)).unwrap();
+                            tx.send(TwitchAction::Join(channel.clone())).unwrap();
+                            erx = query_emotes(&config, channel);
+                        }
+                    }
+                    TerminalAction::ToggleGiftRecipients => {
+                        app.toggle_gift_recipients();
+                    }
+                    TerminalAction::ToggleDoNotDisturb => {
+                        app.add_system_message(if toggle_do_not_disturb() {
+                            "Desktop notifications and sounds are paused.".to_string()
+                        } else {
+                            "Desktop notifications and sounds are resumed.".to_string()
+                        });
+                    }
+                    TerminalAction::ToggleAbout => {
+                        app.components.about.toggle_focus();
+                    }
+                    TerminalAction::RotateTheme => {
+                        app.rotate_theme();
                     }
                     TerminalAction::Enter(action) => match action {
-                        TwitchAction::Privmsg(message) => {
-                            const ME_COMMAND: &str = "/me ";
+                        TwitchAction::Privmsg(message)
+                            if parse_whisper_command(&message).is_some() =>
+                        {
+                            let (user, whisper) = parse_whisper_command(&message).unwrap();
+
+                            app.add_whisper(Whisper::new(
+                                user.to_string(),
+                                whisper.to_string(),
+                                true,
+                            ));
+
+                            tx.send(TwitchAction::Whisper(user.to_string(), whisper.to_string()))
+                                .unwrap();
+                        }
+                        TwitchAction::Privmsg(message) if message.trim() == DRY_RUN_COMMAND => {
+                            let dry_run = {
+                                let mut config = app.config.borrow_mut();
+                                config.twitch.dry_run = !config.twitch.dry_run;
+                                config.twitch.dry_run
+                            };
+
+                            app.add_system_message(if dry_run {
+                                "Dry run enabled, moderation commands will only be shown."
+                                    .to_string()
+                            } else {
+                                "Dry run disabled, moderation commands will be run.".to_string()
+                            });
+                        }
+                        TwitchAction::Privmsg(message)
+                            if parse_nick_command(&message).is_some() =>
+                        {
+                            match parse_nick_command(&message).unwrap() {
+                                Ok((user, nickname)) => {
+                                    app.add_system_message(nickname.as_ref().map_or_else(
+                                        || format!("{user} is shown by their own name again."),
+                                        |nickname| format!("{user} is now shown as {nickname}."),
+                                    ));
+
+                                    app.set_nickname(&user, nickname);
+                                }
+                                Err(usage) => app.add_system_message(usage.to_string()),
+                            }
+                        }
+                        TwitchAction::Privmsg(message)
+                            if parse_ignore_command(&message).is_some() =>
+                        {
+                            match parse_ignore_command(&message).unwrap() {
+                                Ok((user, ignored)) => ignore_user(&app, &tx, user, ignored),
+                                Err(usage) => app.add_system_message(usage.to_string()),
+                            }
+                        }
+                        TwitchAction::Privmsg(message)
+                            if parse_export_command(&message).is_some() =>
+                        {
+                            let path = parse_export_command(&message).unwrap();
 
-                            let (msg, highlight) = message.strip_prefix(ME_COMMAND).map_or_else(
-                                || (message.clone(), false),
-                                |msg| (msg.to_string(), true),
+                            let result = export_messages(
+                                &config.twitch.channel,
+                                &app.messages.borrow(),
+                                path,
                             );
 
+                            app.add_system_message(match result {
+                                Ok(path) => format!(
+                                    "Exported {} messages to {path}.",
+                                    app.messages.borrow().len()
+                                ),
+                                Err(report) => format!("{report:#}"),
+                            });
+                        }
+                        TwitchAction::Privmsg(message)
+                            if parse_moderation_command(&message).is_some() =>
+                        {
+                            match parse_moderation_command(&message).unwrap() {
+                                Ok(command) => send_moderation(&app, &tx, command),
+                                Err(usage) => app.add_system_message(usage.to_string()),
+                            }
+                        }
+                        TwitchAction::Privmsg(message)
+                            if parse_channel_info_command(&message).is_some() =>
+                        {
+                            match parse_channel_info_command(&message).unwrap() {
+                                Ok(command) => {
+                                    tx.send(TwitchAction::ChannelInfo(command)).unwrap();
+                                }
+                                Err(usage) => app.add_system_message(usage.to_string()),
+                            }
+                        }
+                        TwitchAction::Privmsg(message)
+                            if parse_poll_command(&message).is_some() =>
+                        {
+                            match parse_poll_command(&message).unwrap() {
+                                Ok(command) => {
+                                    tx.send(TwitchAction::Poll(command)).unwrap();
+                                }
+                                Err(usage) => app.add_system_message(usage.to_string()),
+                            }
+                        }
+                        TwitchAction::Privmsg(message) => {
+                            // Long messages and ones with more than a line are sent as more than one, spaced apart
+                            let (prefix, parts) = split_outgoing(&message, TWITCH_MESSAGE_LIMIT);
+
                             let user_id = get_twitch_client_id(config.twitch.token.as_deref())
                                 .await
                                 .map(|x| x.user_id.clone())
                                 .ok();
 
-                            let message_data = MessageData::new_user_message(
-                                config.twitch.username.to_string(),
-                                user_id,
-                                false,
-                                msg,
-                                None,
-                                highlight,
-                                &app.emotes,
-                                is_emotes_enabled,
-                            );
+                            let parts = parts
+                                .into_iter()
+                                .map(|msg| {
+                                    let message_data = MessageData::new_user_message(
+                                        config.twitch.username.to_string(),
+                                        user_id.clone(),
+                                        false,
+                                        msg.clone(),
+                                        None,
+                                        !prefix.is_empty(),
+                                        &app.emotes,
+                                        is_emotes_enabled,
+                                    );
+
+                                    (
+                                        message_data,
+                                        TwitchAction::Privmsg(format!("{prefix}{msg}")),
+                                    )
+                                })
+                                .collect::<Vec<_>>();
+
+                            app.outgoing.push(parts);
+                        }
+                        TwitchAction::Reply(parent_id, message) => {
+                            let user_id = get_twitch_client_id(config.twitch.token.as_deref())
+                                .await
+                                .map(|x| x.user_id.clone())
+                                .ok();
+
+                            let reply = app
+                                .messages
+                                .borrow()
+                                .iter()
+                                .find(|m| m.message_id.as_ref() == Some(&parent_id))
+                                .and_then(Reply::to_message);
+
+                            let parts = split_message(&message, TWITCH_MESSAGE_LIMIT)
+                                .into_iter()
+                                .map(|message| {
+                                    let mut message_data = MessageData::new_user_message(
+                                        config.twitch.username.to_string(),
+                                        user_id.clone(),
+                                        false,
+                                        message.clone(),
+                                        None,
+                                        false,
+                                        &app.emotes,
+                                        is_emotes_enabled,
+                                    );
 
-                            app.messages.borrow_mut().push_front(message_data);
+                                    message_data.reply.clone_from(&reply);
 
-                            tx.send(TwitchAction::Privmsg(message)).unwrap();
+                                    (
+                                        message_data,
+                                        TwitchAction::Reply(parent_id.clone(), message),
+                                    )
+                                })
+                                .collect::<Vec<_>>();
+
+                            app.outgoing.push(parts);
                         }
                         TwitchAction::Join(channel) => {
-                            app.clear_messages();
-                            app.emotes.unload();
+                            app.switch_channel(&channel);
 
                             tx.send(TwitchAction::Join(channel.clone())).unwrap();
                             erx = query_emotes(&config, channel);
 
                             app.set_state(State::Normal);
                         }
-                        TwitchAction::ClearMessages => {}
+                        TwitchAction::Moderate(command) => send_moderation(&app, &tx, command),
+                        TwitchAction::Ignore(user, ignored) => {
+                            ignore_user(&app, &tx, user, ignored);
+                        }
+                        TwitchAction::Recovered(items, send) => {
+                            if send {
+                                for item in &items {
+                                    if let Outgoing::Whisper { user, message } = item {
+                                        app.add_whisper(Whisper::new(
+                                            user.clone(),
+                                            message.clone(),
+                                            true,
+                                        ));
+                                    }
+                                }
+
+                                app.add_system_message(format!(
+                                    "Sending {} unsent message(s) from the previous session.",
+                                    items.len()
+                                ));
+                            }
+
+                            tx.send(TwitchAction::Recovered(items, send)).unwrap();
+                        }
+                        action @ (TwitchAction::ResolveAutoMod(..)
+                        | TwitchAction::Poll(_)
+                        | TwitchAction::ChannelInfo(_)) => {
+                            tx.send(action).unwrap();
+                        }
+                        TwitchAction::Part(_) | TwitchAction::Whisper(_, _) => {}
                     },
                 }
             }
         }
 
-        terminal.draw(|f| app.draw(f)).unwrap();
+        // Messages are shown in chat as they are sent, rather than when they were typed
+        if let Some((mut message_data, action)) = app.outgoing.next_due(Instant::now().into_std()) {
+            other_change = true;
+
+            if let TwitchAction::Privmsg(message) = &action {
+                if let Some(channel) = parse_raid_command(message) {
+                    app.add_raid(Raid::new(
+                        config.twitch.username.clone(),
+                        channel,
+                        None,
+                        true,
+                    ));
+                }
+            }
+
+            message_data.time_sent = Local::now();
+            app.messages.borrow_mut().push_front(message_data);
+
+            tx.send(action).unwrap();
+        }
+
+        app.update_window_title();
+
+        let chat_only =
+            new_message && !other_change && last_full_redraw.elapsed() < FULL_REDRAW_INTERVAL;
+
+        let frame = terminal
+            .draw(|f| {
+                if !(chat_only && app.draw_chat_viewport(f, &previous_frame)) {
+                    app.draw(f);
+                    last_full_redraw = Instant::now();
+                }
+            })
+            .unwrap();
+
+        // Cells that did not change were not drawn again, so they are still hyperlinks
+        let changed = *frame.buffer != previous_frame;
+
+        previous_frame.clone_from(frame.buffer);
+
+        if hyperlinks && changed {
+            draw_hyperlinks(&mut terminal, &previous_frame, &app.drawn_links());
+        }
     }
 
     app.cleanup();
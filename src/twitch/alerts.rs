@@ -0,0 +1,288 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use chrono::{DateTime, Local, TimeDelta};
+use color_eyre::Result;
+use serde::Deserialize;
+use serde_json::Value;
+use tui::style::Color;
+
+use crate::{
+    handlers::config::AlertsConfig, twitch::mod_actions::ModAction, utils::time::format_duration,
+};
+
+/// How urgent an alert is, with critical alerts staying on screen until they are acknowledged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertSeverity {
+    Info,
+    Warn,
+    Critical,
+}
+
+impl AlertSeverity {
+    pub const fn color(self) -> Color {
+        match self {
+            Self::Info => Color::LightCyan,
+            Self::Warn => Color::Yellow,
+            Self::Critical => Color::LightRed,
+        }
+    }
+}
+
+impl Display for AlertSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Info => write!(f, "Info"),
+            Self::Warn => write!(f, "Warning"),
+            Self::Critical => write!(f, "Critical"),
+        }
+    }
+}
+
+/// Something in the moderation of a channel that moderators should know about right away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub time_received: DateTime<Local>,
+    pub channel: String,
+    pub severity: AlertSeverity,
+    pub message: String,
+}
+
+impl Alert {
+    fn new(channel: &str, severity: AlertSeverity, message: String, now: DateTime<Local>) -> Self {
+        Self {
+            time_received: now,
+            channel: channel.to_string(),
+            severity,
+            message,
+        }
+    }
+}
+
+/// The event sent when shield mode is turned on or off.
+/// <https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/#channelshield_modebegin>
+#[derive(Deserialize)]
+struct ShieldModeEvent {
+    broadcaster_user_login: String,
+    moderator_user_name: String,
+}
+
+impl Alert {
+    /// Turning shield mode on is critical, as the channel is likely being attacked.
+    pub fn from_shield_mode_event(kind: &str, event: Value, now: DateTime<Local>) -> Result<Self> {
+        let event = serde_json::from_value::<ShieldModeEvent>(event)?;
+
+        Ok(if kind.ends_with("begin") {
+            Self::new(
+                &event.broadcaster_user_login,
+                AlertSeverity::Critical,
+                format!("{} turned on shield mode", event.moderator_user_name),
+                now,
+            )
+        } else {
+            Self::new(
+                &event.broadcaster_user_login,
+                AlertSeverity::Info,
+                format!("{} turned off shield mode", event.moderator_user_name),
+                now,
+            )
+        })
+    }
+}
+
+fn window(seconds: u64) -> TimeDelta {
+    TimeDelta::seconds(i64::try_from(seconds).unwrap_or(i64::MAX))
+}
+
+/// Counts events within a window of time, firing once the count reaches a threshold.
+#[derive(Debug, Default)]
+struct Window {
+    times: VecDeque<(String, DateTime<Local>)>,
+}
+
+impl Window {
+    /// Count an event, returning how many happened in its channel within the window if that reached the threshold.
+    /// The count starts over once it has fired, so a single spike only raises a single alert.
+    fn count(
+        &mut self,
+        channel: &str,
+        now: DateTime<Local>,
+        seconds: u64,
+        threshold: usize,
+    ) -> Option<usize> {
+        let window = window(seconds);
+
+        self.times.retain(|(_, time)| now - *time <= window);
+        self.times.push_back((channel.to_string(), now));
+
+        let count = self.times.iter().filter(|(c, _)| c == channel).count();
+
+        if threshold == 0 || count < threshold {
+            return None;
+        }
+
+        self.times.retain(|(c, _)| c != channel);
+
+        Some(count)
+    }
+}
+
+/// Decides which moderation events raise alerts, from the thresholds in the config.
+#[derive(Debug)]
+pub struct AlertRules {
+    config: AlertsConfig,
+    held: Window,
+    removals: Window,
+}
+
+impl AlertRules {
+    pub fn new(config: AlertsConfig) -> Self {
+        Self {
+            config,
+            held: Window::default(),
+            removals: Window::default(),
+        }
+    }
+
+    /// Warn when AutoMod holds many messages in a short time, such as during a raid of spam bots.
+    pub fn message_held(&mut self, channel: &str, now: DateTime<Local>) -> Option<Alert> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let count = self.held.count(
+            channel,
+            now,
+            self.config.automod_spike_window,
+            self.config.automod_spike,
+        )?;
+
+        Some(Alert::new(
+            channel,
+            AlertSeverity::Warn,
+            format!(
+                "AutoMod held {count} messages in the last {}",
+                format_duration(window(self.config.automod_spike_window))
+            ),
+            now,
+        ))
+    }
+
+    /// Raise a critical alert when many users are banned or timed out in a short time.
+    pub fn mod_action(&mut self, action: &ModAction) -> Option<Alert> {
+        if !self.config.enabled || !action.removal {
+            return None;
+        }
+
+        let count = self.removals.count(
+            &action.channel,
+            action.time_received,
+            self.config.mass_timeout_window,
+            self.config.mass_timeout,
+        )?;
+
+        Some(Alert::new(
+            &action.channel,
+            AlertSeverity::Critical,
+            format!(
+                "{count} users were banned or timed out in the last {}",
+                format_duration(window(self.config.mass_timeout_window))
+            ),
+            action.time_received,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    fn rules() -> AlertRules {
+        AlertRules::new(AlertsConfig {
+            enabled: true,
+            automod_spike: 3,
+            automod_spike_window: 60,
+            mass_timeout: 2,
+            mass_timeout_window: 30,
+        })
+    }
+
+    #[test]
+    fn test_automod_spike() {
+        let mut rules = rules();
+
+        assert_eq!(rules.message_held("xithrius", now()), None);
+        assert_eq!(rules.message_held("other", now()), None);
+        // Outside of the window of the first one
+        assert_eq!(
+            rules.message_held("xithrius", now() + TimeDelta::seconds(61)),
+            None
+        );
+        assert_eq!(
+            rules.message_held("xithrius", now() + TimeDelta::seconds(62)),
+            None
+        );
+
+        let alert = rules
+            .message_held("xithrius", now() + TimeDelta::seconds(63))
+            .unwrap();
+
+        assert_eq!(alert.severity, AlertSeverity::Warn);
+        assert_eq!(alert.message, "AutoMod held 3 messages in the last 1m");
+
+        // The count starts over after an alert
+        assert_eq!(
+            rules.message_held("xithrius", now() + TimeDelta::seconds(64)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_mass_timeout() {
+        let mut rules = rules();
+
+        let action = |removal: bool| ModAction {
+            time_received: now(),
+            channel: "xithrius".to_string(),
+            moderator: Some("Moderator".to_string()),
+            description: String::new(),
+            removal,
+        };
+
+        assert_eq!(rules.mod_action(&action(true)), None);
+        assert_eq!(rules.mod_action(&action(false)), None);
+
+        let alert = rules.mod_action(&action(true)).unwrap();
+
+        assert_eq!(alert.severity, AlertSeverity::Critical);
+        assert_eq!(
+            alert.message,
+            "2 users were banned or timed out in the last 30s"
+        );
+    }
+
+    #[test]
+    fn test_shield_mode_event() {
+        let event = json!({
+            "broadcaster_user_login": "xithrius",
+            "moderator_user_name": "Moderator",
+        });
+
+        let begin =
+            Alert::from_shield_mode_event("channel.shield_mode.begin", event.clone(), now())
+                .unwrap();
+
+        assert_eq!(begin.severity, AlertSeverity::Critical);
+        assert_eq!(begin.message, "Moderator turned on shield mode");
+
+        let end = Alert::from_shield_mode_event("channel.shield_mode.end", event, now()).unwrap();
+
+        assert_eq!(end.severity, AlertSeverity::Info);
+    }
+}
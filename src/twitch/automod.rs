@@ -0,0 +1,168 @@
+use chrono::{DateTime, Local};
+use color_eyre::{Result, eyre::bail};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    handlers::config::Matching,
+    twitch::{
+        budget::{Priority, send_helix},
+        helix::{ensure_requestable, track_request},
+        oauth::{get_twitch_client, get_twitch_client_id},
+    },
+    utils::matching::matches_any,
+};
+
+/// The scope needed to see and act on messages held by AutoMod.
+pub const AUTOMOD_SCOPE: &str = "moderator:manage:automod";
+
+#[derive(Deserialize)]
+struct HeldText {
+    text: String,
+}
+
+/// The event sent when AutoMod holds a message.
+/// <https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/#automodmessagehold>
+#[derive(Deserialize)]
+struct HoldEvent {
+    broadcaster_user_login: String,
+    user_id: String,
+    user_name: String,
+    message_id: String,
+    message: HeldText,
+    category: String,
+    level: u8,
+    held_at: String,
+}
+
+/// The event sent when a held message was approved, denied, or expired.
+/// <https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/#automodmessageupdate>
+#[derive(Deserialize)]
+struct UpdateEvent {
+    message_id: String,
+}
+
+/// A message held back by AutoMod, waiting for a moderator to approve or deny it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeldMessage {
+    pub message_id: String,
+    pub channel: String,
+    pub user_id: String,
+    pub user: String,
+    pub message: String,
+    /// Why the message was held, such as `swearing`.
+    pub category: String,
+    /// How severe AutoMod considers the message to be, from 1 to 4.
+    pub level: u8,
+    pub held_at: DateTime<Local>,
+}
+
+impl HeldMessage {
+    pub fn from_event(event: serde_json::Value) -> Result<Self> {
+        let event = serde_json::from_value::<HoldEvent>(event)?;
+
+        Ok(Self {
+            message_id: event.message_id,
+            channel: event.broadcaster_user_login,
+            user_id: event.user_id,
+            user: event.user_name,
+            message: event.message.text,
+            category: event.category,
+            level: event.level,
+            held_at: DateTime::parse_from_rfc3339(&event.held_at)?.with_timezone(&Local),
+        })
+    }
+
+    pub fn matches(&self, query: &str, matching: Matching) -> bool {
+        matches_any(
+            matching,
+            &[&self.user, &self.message, &self.category],
+            query,
+        )
+    }
+}
+
+/// The ID of the held message that an update event is about.
+pub fn resolved_message_id(event: serde_json::Value) -> Result<String> {
+    Ok(serde_json::from_value::<UpdateEvent>(event)?.message_id)
+}
+
+/// Let a held message through to chat, or deny it.
+/// <https://dev.twitch.tv/docs/api/reference/#manage-held-automod-messages>
+pub async fn resolve_held_message(
+    token: Option<&str>,
+    message_id: &str,
+    allow: bool,
+) -> Result<()> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let client_id = get_twitch_client_id(None).await?;
+
+    if !client_id.scopes.iter().any(|s| s == AUTOMOD_SCOPE) {
+        bail!("Handling AutoMod requires the `{AUTOMOD_SCOPE}` scope in your token.");
+    }
+
+    let response = track_request(
+        send_helix(
+            "helix/moderation/automod/message",
+            Priority::User,
+            client
+                .post("https://api.twitch.tv/helix/moderation/automod/message")
+                .json(&json!({
+                    "user_id": client_id.user_id,
+                    "msg_id": message_id,
+                    "action": if allow { "ALLOW" } else { "DENY" },
+                })),
+        )
+        .await,
+    )?;
+
+    if !response.status().is_success() {
+        bail!(
+            "Unable to {} the message: {}",
+            if allow { "approve" } else { "deny" },
+            response.status()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_held_message_from_event() {
+        let event = json!({
+            "broadcaster_user_id": "1",
+            "broadcaster_user_login": "xithrius",
+            "broadcaster_user_name": "Xithrius",
+            "user_id": "2",
+            "user_login": "human",
+            "user_name": "Human",
+            "message_id": "abc",
+            "message": { "text": "some words", "fragments": [] },
+            "category": "swearing",
+            "level": 3,
+            "held_at": "2024-01-01T12:00:00.000000000Z"
+        });
+
+        let held = HeldMessage::from_event(event).unwrap();
+
+        assert_eq!(held.message_id, "abc");
+        assert_eq!(held.channel, "xithrius");
+        assert_eq!(held.user, "Human");
+        assert_eq!(held.message, "some words");
+        assert_eq!(held.level, 3);
+        assert!(held.matches("SWEAR", Matching::Substring));
+        assert!(!held.matches("kappa", Matching::Substring));
+
+        assert!(HeldMessage::from_event(json!({ "message_id": "abc" })).is_err());
+        assert_eq!(
+            resolved_message_id(json!({ "message_id": "abc", "status": "Approved" })).unwrap(),
+            "abc"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use color_eyre::{Result, eyre::bail};
+use serde::Deserialize;
+
+use crate::twitch::{
+    budget::{Priority, send_helix},
+    helix::{ensure_requestable, track_request},
+    moderation::HelixError,
+    oauth::{get_channel_id, get_twitch_client, get_twitch_client_id},
+};
+
+const READ_BLOCKS_SCOPE: &str = "user:read:blocked_users";
+const MANAGE_BLOCKS_SCOPE: &str = "user:manage:blocked_users";
+
+#[derive(Deserialize)]
+struct BlockedUser {
+    user_login: String,
+}
+
+#[derive(Deserialize, Default)]
+struct Pagination {
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BlockList {
+    data: Vec<BlockedUser>,
+    #[serde(default)]
+    pagination: Pagination,
+}
+
+/// The logins of every user the user has blocked on Twitch.
+/// <https://dev.twitch.tv/docs/api/reference/#get-user-block-list>
+pub async fn get_blocked_users(token: Option<&str>) -> Result<Vec<String>> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let client_id = get_twitch_client_id(None).await?;
+
+    if !client_id.scopes.iter().any(|s| s == READ_BLOCKS_SCOPE) {
+        bail!("Blocked users require the `{READ_BLOCKS_SCOPE}` scope in your token.");
+    }
+
+    let mut users = vec![];
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let mut request = client
+            .get("https://api.twitch.tv/helix/users/blocks")
+            .query(&[
+                ("broadcaster_id", client_id.user_id.as_str()),
+                ("first", "100"),
+            ]);
+
+        if let Some(after) = &cursor {
+            request = request.query(&[("after", after)]);
+        }
+
+        let list =
+            track_request(send_helix("helix/users/blocks", Priority::Background, request).await)?
+                .error_for_status()?
+                .json::<BlockList>()
+                .await?;
+
+        users.extend(list.data.into_iter().map(|user| user.user_login));
+
+        cursor = list.pagination.cursor.filter(|cursor| !cursor.is_empty());
+
+        if cursor.is_none() {
+            return Ok(users);
+        }
+    }
+}
+
+/// Block a user on Twitch, or unblock them.
+/// <https://dev.twitch.tv/docs/api/reference/#block-user>
+/// <https://dev.twitch.tv/docs/api/reference/#unblock-user>
+pub async fn set_blocked(token: Option<&str>, user: &str, blocked: bool) -> Result<()> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let client_id = get_twitch_client_id(None).await?;
+
+    if !client_id.scopes.iter().any(|s| s == MANAGE_BLOCKS_SCOPE) {
+        bail!("Blocking users on Twitch requires the `{MANAGE_BLOCKS_SCOPE}` scope in your token.");
+    }
+
+    let user_id = track_request(get_channel_id(&client, user, Priority::User).await)?;
+    let url = format!("https://api.twitch.tv/helix/users/blocks?target_user_id={user_id}");
+
+    let request = if blocked {
+        client.put(url)
+    } else {
+        client.delete(url)
+    };
+
+    let response = track_request(send_helix("helix/users/blocks", Priority::User, request).await)?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+
+        let message = response
+            .json::<HelixError>()
+            .await
+            .map_or_else(|_| status.to_string(), |err| err.message);
+
+        bail!(
+            "Unable to {} {user} on Twitch: {message}",
+            if blocked { "block" } else { "unblock" }
+        );
+    }
+
+    Ok(())
+}
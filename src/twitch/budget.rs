@@ -0,0 +1,261 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{Result, eyre::anyhow};
+use futures::{
+    FutureExt,
+    future::{BoxFuture, Shared},
+};
+use log::warn;
+use reqwest::{RequestBuilder, Response, StatusCode, header::HeaderMap};
+
+use crate::utils::traffic::send_tracked;
+
+/// How many points Helix gives a token per window.
+/// <https://dev.twitch.tv/docs/api/guide/#twitch-rate-limits>
+const BUDGET_POINTS: u32 = 800;
+/// How long it takes for the points to be refilled, if Helix did not say otherwise.
+const BUDGET_WINDOW: Duration = Duration::from_secs(60);
+/// Points that background refreshes cannot use, so that user actions always go through.
+const USER_RESERVE: u32 = 200;
+/// The shortest amount of time between two background requests.
+const BACKGROUND_SPACING: Duration = Duration::from_millis(250);
+
+static BUDGET: Mutex<Budget> = Mutex::new(Budget::new());
+static IN_FLIGHT: Mutex<BTreeMap<String, SharedRequest>> = Mutex::new(BTreeMap::new());
+
+type SharedRequest = Shared<BoxFuture<'static, Result<Arc<dyn Any + Send + Sync>, String>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Something the user is waiting on, such as sending a whisper.
+    User,
+    /// Periodic or automatic refreshes, which can wait.
+    Background,
+}
+
+/// Keeps track of the Helix rate limit, shared by every feature that talks to Helix.
+#[derive(Debug)]
+struct Budget {
+    remaining: u32,
+    reset_at: Option<Instant>,
+    last_background: Option<Instant>,
+}
+
+impl Budget {
+    const fn new() -> Self {
+        Self {
+            remaining: BUDGET_POINTS,
+            reset_at: None,
+            last_background: None,
+        }
+    }
+
+    /// Take a point if the request can be sent now, otherwise return how long to wait.
+    fn take(&mut self, priority: Priority, now: Instant) -> Option<Duration> {
+        if self.reset_at.is_none_or(|reset_at| now >= reset_at) {
+            self.remaining = BUDGET_POINTS;
+            self.reset_at = Some(now + BUDGET_WINDOW);
+        }
+
+        let until_reset = self
+            .reset_at
+            .map_or(BUDGET_WINDOW, |reset_at| reset_at - now);
+
+        match priority {
+            Priority::User if self.remaining == 0 => Some(until_reset),
+            Priority::User => {
+                self.remaining -= 1;
+                None
+            }
+            Priority::Background if self.remaining <= USER_RESERVE => Some(until_reset),
+            Priority::Background => {
+                let until_spaced = self
+                    .last_background
+                    .map(|last| (last + BACKGROUND_SPACING).saturating_duration_since(now))
+                    .filter(|wait| !wait.is_zero());
+
+                if until_spaced.is_some() {
+                    return until_spaced;
+                }
+
+                self.remaining -= 1;
+                self.last_background = Some(now);
+                None
+            }
+        }
+    }
+
+    /// Use what Helix says about the rate limit over what was counted locally.
+    fn update(&mut self, remaining: u32, reset_in: Duration, now: Instant) {
+        self.remaining = remaining;
+        self.reset_at = Some(now + reset_in);
+    }
+}
+
+/// Read the rate limit headers of a Helix response, as the remaining points and the time until they are refilled.
+fn rate_limit(headers: &HeaderMap) -> Option<(u32, Duration)> {
+    let header = |name| headers.get(name)?.to_str().ok()?.parse::<u64>().ok();
+
+    let remaining = u32::try_from(header("ratelimit-remaining")?).ok()?;
+    let reset = Duration::from_secs(header("ratelimit-reset")?);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+
+    Some((remaining, reset.saturating_sub(now)))
+}
+
+async fn acquire(priority: Priority) {
+    loop {
+        let wait = BUDGET
+            .lock()
+            .ok()
+            .and_then(|mut budget| budget.take(priority, Instant::now()));
+
+        match wait {
+            Some(wait) => tokio::time::sleep(wait).await,
+            None => return,
+        }
+    }
+}
+
+/// Send a request to Helix once the rate limit allows it,
+/// with requests made by the user going ahead of background refreshes.
+pub async fn send_helix(
+    endpoint: &'static str,
+    priority: Priority,
+    request: RequestBuilder,
+) -> Result<Response> {
+    acquire(priority).await;
+
+    let response = send_tracked(endpoint, request).await?;
+
+    if let Ok(mut budget) = BUDGET.lock() {
+        if let Some((remaining, reset_in)) = rate_limit(response.headers()) {
+            budget.update(remaining, reset_in, Instant::now());
+        } else if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            budget.update(0, BUDGET_WINDOW, Instant::now());
+        }
+    }
+
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        warn!("Helix rate limit reached on {endpoint}, waiting for it to reset.");
+    }
+
+    Ok(response)
+}
+
+/// Run a request, unless an identical one is already in flight, in which case its result is shared.
+pub async fn deduplicate<T, F>(key: String, request: F) -> Result<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Future<Output = Result<T>> + Send + 'static,
+{
+    let shared = {
+        let mut in_flight = IN_FLIGHT
+            .lock()
+            .map_err(|_| anyhow!("In-flight requests are poisoned."))?;
+
+        in_flight
+            .entry(key.clone())
+            .or_insert_with(|| {
+                request
+                    .map(|result| {
+                        result
+                            .map(|value| Arc::new(value) as Arc<dyn Any + Send + Sync>)
+                            .map_err(|err| err.to_string())
+                    })
+                    .boxed()
+                    .shared()
+            })
+            .clone()
+    };
+
+    let result = shared.clone().await;
+
+    if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+        if in_flight.get(&key).is_some_and(|f| f.ptr_eq(&shared)) {
+            in_flight.remove(&key);
+        }
+    }
+
+    result
+        .map_err(|err| anyhow!(err))?
+        .downcast_ref::<T>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Request {key} was shared with a different type."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_background_keeps_user_reserve() {
+        let now = Instant::now();
+        let mut budget = Budget::new();
+        budget.update(USER_RESERVE + 1, BUDGET_WINDOW, now);
+
+        assert!(budget.take(Priority::Background, now).is_none());
+        assert!(
+            budget
+                .take(Priority::Background, now + BACKGROUND_SPACING)
+                .is_some()
+        );
+        assert!(budget.take(Priority::User, now).is_none());
+    }
+
+    #[test]
+    fn test_background_spacing() {
+        let now = Instant::now();
+        let mut budget = Budget::new();
+
+        assert!(budget.take(Priority::Background, now).is_none());
+        assert_eq!(
+            budget.take(Priority::Background, now),
+            Some(BACKGROUND_SPACING)
+        );
+        assert!(budget.take(Priority::User, now).is_none());
+        assert!(
+            budget
+                .take(Priority::Background, now + BACKGROUND_SPACING)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_budget_refills_after_reset() {
+        let now = Instant::now();
+        let mut budget = Budget::new();
+        budget.update(0, Duration::from_secs(5), now);
+
+        assert_eq!(
+            budget.take(Priority::User, now),
+            Some(Duration::from_secs(5))
+        );
+        assert!(
+            budget
+                .take(Priority::User, now + Duration::from_secs(5))
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deduplicate_shares_result() {
+        let (first, second) = futures::join!(
+            deduplicate("key".to_string(), async {
+                tokio::task::yield_now().await;
+                Ok(1)
+            }),
+            deduplicate("key".to_string(), async { Ok(2) })
+        );
+
+        assert_eq!(first.unwrap(), 1);
+        assert_eq!(second.unwrap(), 1);
+        assert!(IN_FLIGHT.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use color_eyre::Result;
+
+use crate::{handlers::config::CacheConfig, twitch::budget::deduplicate};
+
+static CACHE: Mutex<Cache> = Mutex::new(Cache::new());
+
+/// What is looked up from the Twitch API, each kept for as long as the config says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CacheKind {
+    Users,
+    Streams,
+    Games,
+    Badges,
+}
+
+impl CacheKind {
+    const ALL: [Self; 4] = [Self::Users, Self::Streams, Self::Games, Self::Badges];
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Display for CacheKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Users => write!(f, "Users"),
+            Self::Streams => write!(f, "Streams"),
+            Self::Games => write!(f, "Games"),
+            Self::Badges => write!(f, "Badges"),
+        }
+    }
+}
+
+struct Entry {
+    value: Arc<dyn Any + Send + Sync>,
+    stored_at: Instant,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Stats {
+    hits: u64,
+    misses: u64,
+}
+
+struct Cache {
+    /// How long each kind of answer is kept for.
+    ttls: [Duration; 4],
+    entries: BTreeMap<(CacheKind, String), Entry>,
+    stats: [Stats; 4],
+}
+
+impl Cache {
+    const fn new() -> Self {
+        Self {
+            ttls: [Duration::ZERO; 4],
+            entries: BTreeMap::new(),
+            stats: [Stats { hits: 0, misses: 0 }; 4],
+        }
+    }
+
+    fn configure(&mut self, config: &CacheConfig) {
+        self.ttls =
+            [config.users, config.streams, config.games, config.badges].map(Duration::from_secs);
+    }
+
+    fn is_fresh(&self, kind: CacheKind, entry: &Entry, now: Instant) -> bool {
+        now.duration_since(entry.stored_at) < self.ttls[kind.index()]
+    }
+
+    fn get<T: Clone + 'static>(&mut self, kind: CacheKind, key: &str, now: Instant) -> Option<T> {
+        let value = self
+            .entries
+            .get(&(kind, key.to_string()))
+            .filter(|entry| self.is_fresh(kind, entry, now))
+            .and_then(|entry| entry.value.downcast_ref::<T>())
+            .cloned();
+
+        let stats = &mut self.stats[kind.index()];
+
+        if value.is_some() {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+
+        value
+    }
+
+    fn insert<T: Send + Sync + 'static>(
+        &mut self,
+        kind: CacheKind,
+        key: &str,
+        value: T,
+        now: Instant,
+    ) {
+        // Expired answers are only cleared out as new ones come in, which is often enough to keep the cache small
+        let ttls = self.ttls;
+        self.entries
+            .retain(|(kind, _), entry| now.duration_since(entry.stored_at) < ttls[kind.index()]);
+
+        if self.ttls[kind.index()].is_zero() {
+            return;
+        }
+
+        self.entries.insert(
+            (kind, key.to_string()),
+            Entry {
+                value: Arc::new(value),
+                stored_at: now,
+            },
+        );
+    }
+}
+
+/// Set how long each kind of answer is kept for, from the config.
+pub fn configure(config: &CacheConfig) {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.configure(config);
+    }
+}
+
+/// Reuse an earlier answer to a request if it is recent enough, or send the request otherwise.
+/// The same request being sent by several features at once is only sent once.
+pub async fn cached<T, F>(kind: CacheKind, key: &str, request: F) -> Result<T>
+where
+    T: Clone + Send + Sync + 'static,
+    F: Future<Output = Result<T>> + Send + 'static,
+{
+    let key = key.to_lowercase();
+
+    if let Some(value) = CACHE
+        .lock()
+        .ok()
+        .and_then(|mut cache| cache.get::<T>(kind, &key, Instant::now()))
+    {
+        return Ok(value);
+    }
+
+    let value = deduplicate(format!("cache/{kind}/{key}"), request).await?;
+
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.insert(kind, &key, value.clone(), Instant::now());
+    }
+
+    Ok(value)
+}
+
+/// Rows of how much of each kind of answer is cached and reused, to be shown in the debug window.
+pub fn cache_stats() -> Vec<(String, String)> {
+    let Ok(cache) = CACHE.lock() else {
+        return vec![];
+    };
+
+    let now = Instant::now();
+
+    CacheKind::ALL
+        .iter()
+        .map(|&kind| {
+            let entries = cache
+                .entries
+                .iter()
+                .filter(|((k, _), entry)| *k == kind && cache.is_fresh(kind, entry, now))
+                .count();
+
+            let Stats { hits, misses } = cache.stats[kind.index()];
+
+            (
+                kind.to_string(),
+                format!("{entries} kept, {hits} reused, {misses} asked"),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache() -> Cache {
+        let mut cache = Cache::new();
+
+        cache.configure(&CacheConfig {
+            users: 60,
+            streams: 0,
+            ..CacheConfig::default()
+        });
+
+        cache
+    }
+
+    #[test]
+    fn test_cache_expiry() {
+        let mut cache = cache();
+        let now = Instant::now();
+
+        assert_eq!(cache.get::<i32>(CacheKind::Users, "xithrius", now), None);
+
+        cache.insert(CacheKind::Users, "xithrius", 1, now);
+
+        assert_eq!(
+            cache.get::<i32>(CacheKind::Users, "xithrius", now + Duration::from_secs(59)),
+            Some(1)
+        );
+        assert_eq!(
+            cache.get::<i32>(CacheKind::Users, "xithrius", now + Duration::from_secs(60)),
+            None
+        );
+        assert_eq!(
+            cache.stats[CacheKind::Users.index()],
+            Stats { hits: 1, misses: 2 }
+        );
+
+        // Nothing is kept for a kind without a time to keep it for
+        cache.insert(CacheKind::Streams, "xithrius", 1, now);
+
+        assert_eq!(cache.get::<i32>(CacheKind::Streams, "xithrius", now), None);
+    }
+
+    #[test]
+    fn test_cache_kinds_are_separate() {
+        let mut cache = cache();
+        let now = Instant::now();
+
+        cache.insert(CacheKind::Users, "xithrius", 1, now);
+        cache.insert(CacheKind::Games, "xithrius", "Factorio".to_string(), now);
+
+        assert_eq!(cache.get::<i32>(CacheKind::Users, "xithrius", now), Some(1));
+        assert_eq!(
+            cache.get::<String>(CacheKind::Games, "xithrius", now),
+            Some("Factorio".to_string())
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::twitch::{
+    budget::{Priority, send_helix},
+    cache::{CacheKind, cached},
+    helix::{ensure_requestable, track_request},
+    moderation::HelixError,
+    oauth::{get_channel_id, get_twitch_client, get_twitch_client_id},
+};
+
+const CHANNEL_INFO_SCOPE: &str = "channel:manage:broadcast";
+
+/// Commands that change the stream information of the user's own channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelInfoCommand {
+    Title(String),
+    /// The name of a category, which is searched for to find its ID.
+    Game(String),
+}
+
+/// Parse a `/title` or `/game` command, returning `None` if the message is not one,
+/// or an error with how to use the command if it has nothing after it.
+pub fn parse_channel_info_command(message: &str) -> Option<Result<ChannelInfoCommand>> {
+    let message = message.strip_prefix('/')?;
+    let (command, arguments) = message.split_once(' ').unwrap_or((message, ""));
+    let arguments = arguments.trim();
+
+    let parsed = match command {
+        "title" if arguments.is_empty() => Err(eyre!("Usage: /title <text>")),
+        "title" => Ok(ChannelInfoCommand::Title(arguments.to_string())),
+        "game" if arguments.is_empty() => Err(eyre!("Usage: /game <category name>")),
+        "game" => Ok(ChannelInfoCommand::Game(arguments.to_string())),
+        _ => return None,
+    };
+
+    Some(parsed)
+}
+
+/// A category that can be streamed under.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Category {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct CategoryList {
+    data: Vec<Category>,
+}
+
+/// Pick the category with the exact name that was searched for, or the closest one Twitch found otherwise.
+fn pick_category(name: &str, mut categories: Vec<Category>) -> Option<Category> {
+    let exact = categories
+        .iter()
+        .position(|category| category.name.eq_ignore_ascii_case(name))
+        .unwrap_or(0);
+
+    (exact < categories.len()).then(|| categories.swap_remove(exact))
+}
+
+/// <https://dev.twitch.tv/docs/api/reference/#search-categories>
+async fn search_category(client: &Client, name: &str) -> Result<Category> {
+    let request = client
+        .get("https://api.twitch.tv/helix/search/categories")
+        .query(&[("query", name), ("first", "20")]);
+
+    let categories = cached(CacheKind::Games, name, async move {
+        Ok(
+            track_request(send_helix("helix/search/categories", Priority::User, request).await)?
+                .error_for_status()?
+                .json::<CategoryList>()
+                .await?
+                .data,
+        )
+    })
+    .await?;
+
+    pick_category(name, categories).ok_or_else(|| eyre!("No category was found for {name}."))
+}
+
+/// Change the title or category of the user's own channel, returning what it was changed to.
+/// <https://dev.twitch.tv/docs/api/reference/#modify-channel-information>
+pub async fn update_channel_info(
+    token: Option<&str>,
+    channel: &str,
+    command: &ChannelInfoCommand,
+) -> Result<String> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let client_id = get_twitch_client_id(None).await?;
+
+    if !client_id.scopes.iter().any(|s| s == CHANNEL_INFO_SCOPE) {
+        bail!("/title and /game require the `{CHANNEL_INFO_SCOPE}` scope in your token.");
+    }
+
+    let broadcaster_id = track_request(get_channel_id(&client, channel, Priority::User).await)?;
+
+    if broadcaster_id.to_string() != client_id.user_id {
+        bail!("Only the broadcaster can change the title and category.");
+    }
+
+    let (body, confirmation) = match command {
+        ChannelInfoCommand::Title(title) => (
+            json!({ "title": title }),
+            format!("Title changed to: {title}"),
+        ),
+        ChannelInfoCommand::Game(name) => {
+            let category = search_category(&client, name).await?;
+
+            (
+                json!({ "game_id": category.id }),
+                format!("Category changed to {}", category.name),
+            )
+        }
+    };
+
+    let response = track_request(
+        send_helix(
+            "helix/channels",
+            Priority::User,
+            client
+                .patch(format!(
+                    "https://api.twitch.tv/helix/channels?broadcaster_id={broadcaster_id}"
+                ))
+                .json(&body),
+        )
+        .await,
+    )?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+
+        let message = response
+            .json::<HelixError>()
+            .await
+            .map_or_else(|_| status.to_string(), |err| err.message);
+
+        bail!("Unable to change the stream information: {message}");
+    }
+
+    Ok(confirmation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: &str, name: &str) -> Category {
+        Category {
+            id: id.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_channel_info_command() {
+        assert_eq!(
+            parse_channel_info_command("/title Building a factory ").map(Result::unwrap),
+            Some(ChannelInfoCommand::Title("Building a factory".to_string()))
+        );
+        assert_eq!(
+            parse_channel_info_command("/game Factorio").map(Result::unwrap),
+            Some(ChannelInfoCommand::Game("Factorio".to_string()))
+        );
+        assert!(parse_channel_info_command("/title").unwrap().is_err());
+        assert!(parse_channel_info_command("/titles a").is_none());
+        assert!(parse_channel_info_command("title a").is_none());
+    }
+
+    #[test]
+    fn test_pick_category() {
+        let categories = vec![
+            category("1", "Factorio: Space Age"),
+            category("2", "Factorio"),
+        ];
+
+        assert_eq!(
+            pick_category("factorio", categories.clone()),
+            Some(category("2", "Factorio"))
+        );
+        assert_eq!(
+            pick_category("factor", categories),
+            Some(category("1", "Factorio: Space Age"))
+        );
+        assert_eq!(pick_category("factorio", vec![]), None);
+    }
+}
@@ -1,17 +1,48 @@
 use std::{
+    cmp::Reverse,
+    collections::HashMap,
     fmt::Display,
+    fs::{read_to_string, write},
     string::{String, ToString},
+    time::{Duration, Instant},
     vec::Vec,
 };
 
+use chrono::Local;
 use color_eyre::Result;
+use log::{debug, warn};
 use reqwest::Client;
 use serde::Deserialize;
+use tokio::sync::oneshot::{self, Receiver, error::TryRecvError};
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
 
-use super::oauth::{get_twitch_client, get_twitch_client_id};
-use crate::{handlers::config::TwitchConfig, ui::components::utils::SearchItemGetter};
+use super::{
+    budget::{Priority, send_helix},
+    helix::{ensure_requestable, track_request},
+    oauth::{get_twitch_client, get_twitch_client_id},
+    streams::{LiveStream, get_followed_streams},
+};
+use crate::{
+    handlers::{
+        config::{FollowingSort, SharedCompleteConfig, TwitchConfig},
+        favorites::is_favorite,
+        storage::SharedStorage,
+    },
+    ui::components::utils::{SearchItemGetter, mark_favorite},
+    utils::{pathing::cache_path, styles::NO_COLOR, time::format_duration},
+};
 
 const FOLLOWER_COUNT: usize = 100;
+/// The width of the viewer counts of live channels, such as `12.3k`.
+const VIEWERS_WIDTH: usize = 6;
+/// How often the followed channels that are live are checked again while the list is open.
+const LIVE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+const FOLLOWING_CACHE_FILE: &str = "following.json";
+/// How many channels a search for a channel by name finds at most.
+const SEARCH_COUNT: usize = 10;
 
 #[derive(Deserialize, Debug, Clone, Default)]
 #[allow(dead_code)]
@@ -29,66 +60,395 @@ impl Display for FollowingUser {
 }
 
 #[derive(Deserialize, Debug, Clone, Default)]
-#[allow(dead_code)]
 struct Pagination {
     cursor: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone, Default)]
-#[allow(dead_code)]
 pub struct FollowingList {
     pub total: u64,
     pub data: Vec<FollowingUser>,
+    #[serde(default)]
     pagination: Pagination,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Deserialize, Debug, Clone)]
+struct SearchedChannel {
+    broadcaster_login: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct SearchedChannels {
+    data: Vec<SearchedChannel>,
+}
+
+#[derive(Debug)]
 #[allow(dead_code)]
 pub struct Following {
     // TODO: Don't re-create client on new requests
     // client: &Client,
-    twitch_config: TwitchConfig,
+    config: SharedCompleteConfig,
+    /// The channels switched to, to sort by the most recently watched.
+    storage: SharedStorage,
+    /// The pages of followed channels loaded so far, with the cursor of the next page if there are more.
     list: FollowingList,
+    /// If the items are from the cache, because Helix could not be reached.
+    stale: bool,
+    /// The followed channels that are live, by their login.
+    live: HashMap<String, LiveStream>,
+    /// When the live channels were last checked.
+    live_checked: Instant,
+    /// The live channels being checked again in the background, while the list is open.
+    live_refresh: Option<Receiver<Result<Vec<LiveStream>>>>,
 }
 
+/// A page of the channels followed by a user, starting after the cursor of the previous page if there is one.
 // https://dev.twitch.tv/docs/api/reference/#get-followed-channels
-pub async fn get_user_following(client: &Client, user_id: &str) -> Result<FollowingList> {
-    Ok(client
-        .get(format!(
-            "https://api.twitch.tv/helix/channels/followed?user_id={user_id}&first={FOLLOWER_COUNT}",
-        ))
-        .send()
+pub async fn get_user_following(
+    client: &Client,
+    user_id: &str,
+    after: Option<&str>,
+) -> Result<FollowingList> {
+    let mut request = client
+        .get("https://api.twitch.tv/helix/channels/followed")
+        .query(&[("user_id", user_id), ("first", &FOLLOWER_COUNT.to_string())]);
+
+    if let Some(after) = after {
+        request = request.query(&[("after", after)]);
+    }
+
+    Ok(
+        send_helix("helix/channels/followed", Priority::User, request)
+            .await?
+            .error_for_status()?
+            .json::<FollowingList>()
+            .await?,
+    )
+}
+
+/// The logins of the channels with names like a search, the closest matches first.
+// https://dev.twitch.tv/docs/api/reference/#search-channels
+pub async fn search_channels(twitch_config: &TwitchConfig, query: &str) -> Result<Vec<String>> {
+    let client = get_twitch_client(twitch_config.token.as_deref()).await?;
+
+    let request = client
+        .get("https://api.twitch.tv/helix/search/channels")
+        .query(&[("query", query), ("first", &SEARCH_COUNT.to_string())]);
+
+    Ok(send_helix("helix/search/channels", Priority::User, request)
         .await?
         .error_for_status()?
-        .json::<FollowingList>()
-        .await?)
+        .json::<SearchedChannels>()
+        .await?
+        .data
+        .into_iter()
+        .map(|channel| channel.broadcaster_login)
+        .collect())
+}
+
+pub async fn get_following(
+    twitch_config: &TwitchConfig,
+    after: Option<&str>,
+) -> Result<FollowingList> {
+    let client = get_twitch_client(twitch_config.token.as_deref()).await?;
+    let user_id = &get_twitch_client_id(None).await?.user_id;
+
+    get_user_following(&client, user_id, after).await
 }
 
-pub async fn get_following(twitch_config: &TwitchConfig) -> Result<FollowingList> {
+pub async fn get_live_following(twitch_config: &TwitchConfig) -> Result<Vec<LiveStream>> {
     let client = get_twitch_client(twitch_config.token.as_deref()).await?;
     let user_id = &get_twitch_client_id(None).await?.user_id;
 
-    get_user_following(&client, user_id).await
+    get_followed_streams(&client, user_id).await
+}
+
+/// A viewer count in at most five characters, such as `845`, `12.3k`, or `1.2M`.
+fn format_viewers(viewers: u64) -> String {
+    #[allow(clippy::cast_precision_loss)]
+    let compact = |divisor: u64, unit: &str| {
+        let amount = viewers as f64 / divisor as f64;
+
+        if amount < 100.0 {
+            format!("{amount:.1}{unit}")
+        } else {
+            format!("{amount:.0}{unit}")
+        }
+    };
+
+    match viewers {
+        0..1_000 => viewers.to_string(),
+        1_000..1_000_000 => compact(1_000, "k"),
+        _ => compact(1_000_000, "M"),
+    }
 }
 
 impl Following {
-    pub fn new(twitch_config: TwitchConfig) -> Self {
+    pub fn new(config: SharedCompleteConfig, storage: SharedStorage) -> Self {
         Self {
-            twitch_config,
+            config,
+            storage,
             list: FollowingList::default(),
+            stale: false,
+            live: HashMap::new(),
+            live_checked: Instant::now(),
+            live_refresh: None,
         }
     }
+
+    fn set_live(&mut self, streams: Vec<LiveStream>) {
+        self.live = streams
+            .into_iter()
+            .map(|stream| (stream.user_login.clone(), stream))
+            .collect();
+    }
+}
+
+/// The followed channels from the last time they were gotten, if they were.
+pub fn load_cached_following() -> Option<Vec<String>> {
+    read_to_string(cache_path(FOLLOWING_CACHE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn cache_following(following: &[String]) {
+    let result = serde_json::to_string(following)
+        .map_err(color_eyre::Report::from)
+        .and_then(|content| Ok(write(cache_path(FOLLOWING_CACHE_FILE), content)?));
+
+    if let Err(err) = result {
+        warn!("Unable to cache the following list: {err}");
+    }
 }
 
 impl SearchItemGetter<String> for Following {
     async fn get_items(&mut self) -> Result<Vec<String>> {
-        let following = get_following(&self.twitch_config).await;
+        let twitch_config = self.config.borrow().twitch.clone();
+
+        let following = match ensure_requestable() {
+            Ok(()) => track_request(get_following(&twitch_config, None).await),
+            Err(err) => Err(err),
+        };
+
+        match following {
+            Ok(v) => {
+                let following = v
+                    .data
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>();
+
+                // Channels on later pages are only cached once they are loaded
+                cache_following(&following);
+                self.list = v;
+                self.stale = false;
+
+                // Being able to search by what is streamed is a bonus, so the list is still shown without it
+                match track_request(get_live_following(&twitch_config).await) {
+                    Ok(streams) => self.set_live(streams),
+                    Err(err) => {
+                        debug!("Unable to get the followed channels that are live: {err}");
+                        self.live.clear();
+                    }
+                }
+
+                self.live_checked = Instant::now();
+                self.live_refresh = None;
+
+                Ok(following)
+            }
+            Err(err) => {
+                // Show the last known list of followed channels rather than nothing at all
+                let cached = load_cached_following().ok_or(err)?;
+                self.list = FollowingList::default();
+                self.stale = true;
+                self.live.clear();
+
+                Ok(cached)
+            }
+        }
+    }
 
-        following.map(|v| {
-            v.data
+    async fn more_items(&mut self) -> Result<Vec<String>> {
+        let Some(cursor) = self.list.pagination.cursor.take() else {
+            return Ok(vec![]);
+        };
+
+        let twitch_config = self.config.borrow().twitch.clone();
+
+        ensure_requestable()?;
+
+        let page = match track_request(get_following(&twitch_config, Some(&cursor)).await) {
+            Ok(page) => page,
+            Err(err) => {
+                // Scrolling down again tries the same page again
+                self.list.pagination.cursor = Some(cursor);
+
+                return Err(err);
+            }
+        };
+
+        let following = page
+            .data
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>();
+
+        self.list.data.extend(page.data);
+        self.list.pagination = page.pagination;
+        self.list.total = page.total;
+
+        cache_following(
+            &self
+                .list
+                .data
                 .iter()
                 .map(ToString::to_string)
-                .collect::<Vec<String>>()
+                .collect::<Vec<String>>(),
+        );
+
+        Ok(following)
+    }
+
+    fn total(&self) -> Option<usize> {
+        self.list
+            .pagination
+            .cursor
+            .is_some()
+            .then(|| usize::try_from(self.list.total).unwrap_or(usize::MAX))
+    }
+
+    fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    fn refresh(&mut self) {
+        if let Some(refresh) = &mut self.live_refresh {
+            match refresh.try_recv() {
+                Ok(Ok(streams)) => self.set_live(streams),
+                Ok(Err(err)) => debug!("Unable to get the followed channels that are live: {err}"),
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Closed) => {}
+            }
+
+            self.live_refresh = None;
+            self.live_checked = Instant::now();
+        } else if !self.stale && self.live_checked.elapsed() >= LIVE_REFRESH_INTERVAL {
+            let (tx, rx) = oneshot::channel();
+            let twitch_config = self.config.borrow().twitch.clone();
+
+            tokio::spawn(async move {
+                let streams = match ensure_requestable() {
+                    Ok(()) => track_request(get_live_following(&twitch_config).await),
+                    Err(err) => Err(err),
+                };
+
+                let _ = tx.send(streams);
+            });
+
+            self.live_refresh = Some(rx);
+        }
+    }
+
+    fn sort(&self, items: &mut [String]) {
+        match self.config.borrow().frontend.following_sort {
+            FollowingSort::Followed => {}
+            FollowingSort::Viewers => {
+                items.sort_by_key(|item| Reverse(self.live.get(item).map(|s| s.viewer_count)));
+            }
+            FollowingSort::Alphabetical => items.sort_by_cached_key(|item| item.to_lowercase()),
+            FollowingSort::Watched => {
+                // The most recently switched to channels are stored last
+                let watched = self.storage.borrow().get("channels");
+
+                items.sort_by_key(|item| {
+                    Reverse(watched.iter().rposition(|w| w.eq_ignore_ascii_case(item)))
+                });
+            }
+        }
+    }
+
+    fn is_sorted(&self) -> bool {
+        self.config.borrow().frontend.following_sort != FollowingSort::Followed
+    }
+
+    fn pinned(&self) -> Vec<String> {
+        self.storage.borrow().get("favorites")
+    }
+
+    fn title(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "Alt + s",
+                self.config.borrow().frontend.following_sort.to_string(),
+            ),
+            ("Alt + p", "favorite".to_string()),
+        ]
+    }
+
+    fn decorate(&self, item: &String, line: Line<'static>) -> Line<'static> {
+        let favorite = is_favorite(&self.storage.borrow().get("favorites"), item);
+
+        // Channels that are not live are lined up with the ones that are
+        let Some(stream) = self.live.get(item) else {
+            let line =
+                Line::from([vec![Span::raw(" ".repeat(VIEWERS_WIDTH + 4))], line.spans].concat());
+
+            return mark_favorite(line, favorite);
+        };
+
+        let live_style = if *NO_COLOR {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Red)
+        };
+
+        let details = [
+            Some(stream.game_name.clone()).filter(|game| !game.is_empty()),
+            stream.uptime(Local::now()).map(format_duration),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>();
+
+        let mut spans = vec![
+            Span::styled("● ", live_style),
+            Span::styled(
+                format!("{:>VIEWERS_WIDTH$}  ", format_viewers(stream.viewer_count)),
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ];
+        spans.extend(line.spans);
+
+        if !details.is_empty() {
+            spans.push(Span::styled(
+                format!("  {}", details.join(" · ")),
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+
+        mark_favorite(Line::from(spans), favorite)
+    }
+
+    fn details(&self, item: &String) -> Vec<(&'static str, String)> {
+        self.live.get(item).map_or_else(Vec::new, |stream| {
+            vec![
+                ("playing", stream.game_name.clone()),
+                ("title", stream.title.clone()),
+            ]
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_viewers() {
+        assert_eq!(format_viewers(845), "845");
+        assert_eq!(format_viewers(12_345), "12.3k");
+        assert_eq!(format_viewers(123_456), "123k");
+        assert_eq!(format_viewers(1_234_567), "1.2M");
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    hash::BuildHasher,
+    sync::Mutex,
+};
+
+static CONNECTION_STATE: Mutex<ConnectionState> = Mutex::new(ConnectionState::NotConnected);
+
+/// The modes of the chat of each joined channel, by the name of the channel.
+static ROOM_MODES: Mutex<BTreeMap<String, RoomModes>> = Mutex::new(BTreeMap::new());
+
+/// How the connection to Twitch chat is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// No channel has been chosen to connect for yet, such as on the dashboard.
+    NotConnected,
+    Connecting,
+    Connected,
+    /// The connection was lost, and is being made again.
+    Reconnecting,
+}
+
+impl Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::NotConnected => "Not connected",
+                Self::Connecting => "Connecting",
+                Self::Connected => "Connected",
+                Self::Reconnecting => "Reconnecting",
+            }
+        )
+    }
+}
+
+pub fn set_connection_state(state: ConnectionState) {
+    if let Ok(mut current) = CONNECTION_STATE.lock() {
+        *current = state;
+    }
+}
+
+pub fn connection_state() -> ConnectionState {
+    CONNECTION_STATE
+        .lock()
+        .map_or(ConnectionState::NotConnected, |state| *state)
+}
+
+/// The restrictions on who can chat in a channel, and how.
+/// <https://dev.twitch.tv/docs/irc/tags/#roomstate-tags>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoomModes {
+    pub emote_only: bool,
+    /// How many minutes users need to have followed for to chat, if only followers can.
+    pub followers_only: Option<u32>,
+    pub subscribers_only: bool,
+    /// How many seconds users need to wait between messages, if any.
+    pub slow: u32,
+    /// If messages need to be different from the last ones sent.
+    pub unique: bool,
+}
+
+impl RoomModes {
+    /// Update the modes from the tags of a `ROOMSTATE` message,
+    /// which only has the modes that changed after the first one sent when joining.
+    pub fn update<S: BuildHasher>(&mut self, tags: &HashMap<&str, &str, S>) {
+        for (name, value) in tags {
+            match *name {
+                "emote-only" => self.emote_only = *value == "1",
+                "followers-only" => self.followers_only = value.parse::<u32>().ok(),
+                "subs-only" => self.subscribers_only = *value == "1",
+                "slow" => self.slow = value.parse::<u32>().unwrap_or_default(),
+                "r9k" => self.unique = *value == "1",
+                _ => (),
+            }
+        }
+    }
+
+    /// The modes that are on, in short, such as `emote-only` and `slow 30s`.
+    pub fn enabled(&self) -> Vec<String> {
+        let mut modes = vec![];
+
+        if self.emote_only {
+            modes.push("emote-only".to_string());
+        }
+
+        match self.followers_only {
+            Some(0) => modes.push("followers-only".to_string()),
+            Some(minutes) => modes.push(format!("followers-only {minutes}m")),
+            None => (),
+        }
+
+        if self.subscribers_only {
+            modes.push("subs-only".to_string());
+        }
+
+        if self.slow > 0 {
+            modes.push(format!("slow {}s", self.slow));
+        }
+
+        if self.unique {
+            modes.push("unique".to_string());
+        }
+
+        modes
+    }
+}
+
+pub fn update_room_modes<S: BuildHasher>(channel: &str, tags: &HashMap<&str, &str, S>) {
+    if let Ok(mut rooms) = ROOM_MODES.lock() {
+        rooms
+            .entry(channel.to_lowercase())
+            .or_default()
+            .update(tags);
+    }
+}
+
+pub fn room_modes(channel: &str) -> RoomModes {
+    ROOM_MODES
+        .lock()
+        .ok()
+        .and_then(|rooms| rooms.get(&channel.to_lowercase()).cloned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_room_modes() {
+        let mut modes = RoomModes::default();
+
+        modes.update(&HashMap::from([
+            ("emote-only", "0"),
+            ("followers-only", "-1"),
+            ("subs-only", "0"),
+            ("slow", "0"),
+            ("r9k", "0"),
+        ]));
+        assert!(modes.enabled().is_empty());
+
+        modes.update(&HashMap::from([("slow", "30"), ("followers-only", "10")]));
+        modes.update(&HashMap::from([("emote-only", "1")]));
+        assert_eq!(
+            modes.enabled(),
+            vec!["emote-only", "followers-only 10m", "slow 30s"]
+        );
+
+        modes.update(&HashMap::from([("followers-only", "0"), ("slow", "0")]));
+        assert_eq!(modes.enabled(), vec!["emote-only", "followers-only"]);
+    }
+}
@@ -0,0 +1,86 @@
+use std::ops::Range;
+
+use tui::style::Color;
+
+/// The smallest amount of bits of each tier, from the highest tier to the lowest.
+/// <https://dev.twitch.tv/docs/api/reference/#get-cheermotes>
+const TIERS: [u64; 5] = [10_000, 5_000, 1_000, 100, 1];
+
+/// The tier a cheer of some amount of bits belongs to, being the smallest amount of bits of that tier.
+pub fn cheer_tier(amount: u64) -> u64 {
+    TIERS.into_iter().find(|&tier| amount >= tier).unwrap_or(1)
+}
+
+/// The color Twitch shows a cheer of some amount of bits in.
+pub const fn cheer_color(amount: u64) -> Color {
+    match amount {
+        10_000.. => Color::Rgb(244, 48, 33),
+        5_000.. => Color::Rgb(0, 153, 254),
+        1_000.. => Color::Rgb(29, 178, 165),
+        100.. => Color::Rgb(156, 62, 232),
+        _ => Color::Rgb(151, 151, 151),
+    }
+}
+
+/// Split a word such as `Cheer100` into its prefix and amount of bits, if it is a cheer.
+pub fn parse_cheer(word: &str) -> Option<(&str, u64)> {
+    let split = word.find(|c: char| c.is_ascii_digit())?;
+    let (prefix, amount) = word.split_at(split);
+
+    if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    amount
+        .parse()
+        .ok()
+        .filter(|&amount| amount > 0)
+        .map(|amount| (prefix, amount))
+}
+
+/// The byte ranges of the cheers in a message, along with their amount of bits.
+pub fn find_cheers(payload: &str) -> Vec<(Range<usize>, u64)> {
+    payload
+        .split(' ')
+        .scan(0, |start, word| {
+            let range = *start..*start + word.len();
+            *start = range.end + 1;
+
+            Some((range, word))
+        })
+        .filter_map(|(range, word)| parse_cheer(word).map(|(_, amount)| (range, amount)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cheer() {
+        assert_eq!(parse_cheer("Cheer100"), Some(("Cheer", 100)));
+        assert_eq!(parse_cheer("BibleThump5000"), Some(("BibleThump", 5000)));
+        assert_eq!(parse_cheer("Cheer0"), None);
+        assert_eq!(parse_cheer("100"), None);
+        assert_eq!(parse_cheer("Cheer"), None);
+        assert_eq!(parse_cheer("Cheer10x"), None);
+    }
+
+    #[test]
+    fn test_find_cheers() {
+        assert_eq!(
+            find_cheers("Cheer100 nice  Corgo1"),
+            vec![(0..8, 100), (15..21, 1)]
+        );
+        assert!(find_cheers("no cheers here").is_empty());
+    }
+
+    #[test]
+    fn test_cheer_tier() {
+        assert_eq!(cheer_tier(1), 1);
+        assert_eq!(cheer_tier(99), 1);
+        assert_eq!(cheer_tier(100), 100);
+        assert_eq!(cheer_tier(4999), 1000);
+        assert_eq!(cheer_tier(25_000), 10_000);
+    }
+}
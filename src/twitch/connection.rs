@@ -1,23 +1,99 @@
 use std::{cmp::min, time::Duration};
 
+use futures::{StreamExt, stream::BoxStream};
 use irc::{
-    client::{Client, ClientStream, prelude::Config},
+    client::{
+        Client,
+        prelude::{Capability, Command, Config, Message},
+    },
     error::Error::{self, PingTimeout},
+    proto::{CapSubCommand, message::Tag},
 };
 use tokio::{sync::mpsc::Sender, time::sleep};
 
-use crate::handlers::{
-    config::CompleteConfig,
-    data::{DataBuilder, TwitchToTerminalAction},
+use crate::{
+    handlers::{
+        config::{CompleteConfig, Transport},
+        data::{DataBuilder, TwitchToTerminalAction},
+    },
+    twitch::{
+        oauth::current_token,
+        websocket::{WebSocketSender, connect},
+    },
 };
 
+/// The host serving Twitch chat through secure web sockets.
+const WEBSOCKET_HOST: &str = "irc-ws.chat.twitch.tv";
+const WEBSOCKET_PORT: u16 = 443;
+
+pub type MessageStream = BoxStream<'static, Result<Message, Error>>;
+
+/// Sends IRC messages, regardless of the transport they go through.
+pub enum Connection {
+    Tcp(Client),
+    WebSocket(WebSocketSender),
+}
+
+impl Connection {
+    pub fn send<M: Into<Message>>(&self, message: M) -> Result<(), Error> {
+        match self {
+            Self::Tcp(client) => client.send(message),
+            Self::WebSocket(sender) => Ok(sender.send_text(message.into().to_string())?),
+        }
+    }
+
+    pub fn send_privmsg(&self, target: String, message: String) -> Result<(), Error> {
+        self.send(Command::PRIVMSG(target, message))
+    }
+
+    /// Send a message in reply to another one, which Twitch links through a client tag.
+    pub fn send_reply(
+        &self,
+        target: String,
+        parent_id: String,
+        message: String,
+    ) -> Result<(), Error> {
+        self.send(Message {
+            tags: Some(vec![Tag(
+                "reply-parent-msg-id".to_string(),
+                Some(parent_id),
+            )]),
+            prefix: None,
+            command: Command::PRIVMSG(target, message),
+        })
+    }
+
+    pub fn send_join(&self, channel: String) -> Result<(), Error> {
+        self.send(Command::JOIN(channel, None, None))
+    }
+
+    pub fn send_part(&self, channel: String) -> Result<(), Error> {
+        self.send(Command::PART(channel, None))
+    }
+
+    pub fn send_cap_req(&self, capabilities: &[Capability]) -> Result<(), Error> {
+        let capabilities = capabilities
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        self.send(Command::CAP(
+            None,
+            CapSubCommand::REQ,
+            None,
+            Some(capabilities),
+        ))
+    }
+}
+
 /// Initialize the config and send it to the client to connect to an IRC channel.
-async fn create_client_stream(config: CompleteConfig) -> Result<(Client, ClientStream), Error> {
+async fn create_tcp_stream(config: CompleteConfig) -> Result<(Connection, MessageStream), Error> {
     let irc_config = Config {
         nickname: Some(config.twitch.username.clone()),
         server: Some(config.twitch.server.clone()),
         channels: vec![format!("#{}", config.twitch.channel)],
-        password: config.twitch.token.clone(),
+        password: current_token(config.twitch.token.as_deref()),
         port: Some(6697),
         use_tls: Some(true),
         ping_timeout: Some(10),
@@ -31,14 +107,67 @@ async fn create_client_stream(config: CompleteConfig) -> Result<(Client, ClientS
 
     let stream = client.stream()?;
 
-    Ok((client, stream))
+    Ok((Connection::Tcp(client), stream.boxed()))
+}
+
+/// Connect through a WebSocket, where every message can contain several IRC lines.
+async fn create_websocket_stream(
+    config: CompleteConfig,
+) -> Result<(Connection, MessageStream), Error> {
+    let (sender, messages) = connect(WEBSOCKET_HOST, WEBSOCKET_PORT, "/").await?;
+
+    let connection = Connection::WebSocket(sender.clone());
+
+    // A token refreshed during the session is used when reconnecting
+    if let Some(token) = current_token(config.twitch.token.as_deref()) {
+        connection.send(Command::PASS(token))?;
+    }
+    connection.send(Command::NICK(config.twitch.username))?;
+    connection.send_join(format!("#{}", config.twitch.channel))?;
+
+    let stream = messages
+        .flat_map(|text| {
+            let lines = match text {
+                Ok(text) => text
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(|line| line.parse::<Message>().map_err(Error::from))
+                    .collect(),
+                Err(err) => vec![Err(Error::from(err))],
+            };
+
+            futures::stream::iter(lines)
+        })
+        .inspect(move |message| {
+            // The IRC client answers pings by itself, but here it has to be done manually
+            if let Ok(Message {
+                command: Command::PING(server, _),
+                ..
+            }) = message
+            {
+                let _ = sender
+                    .send_text(Message::from(Command::PONG(server.clone(), None)).to_string());
+            }
+        })
+        .boxed();
+
+    Ok((connection, stream))
+}
+
+async fn create_client_stream(
+    config: CompleteConfig,
+) -> Result<(Connection, MessageStream), Error> {
+    match config.twitch.transport {
+        Transport::Tcp => create_tcp_stream(config).await,
+        Transport::WebSocket => create_websocket_stream(config).await,
+    }
 }
 
 pub async fn wait_client_stream(
     tx: Sender<TwitchToTerminalAction>,
     data_builder: DataBuilder<'_>,
     config: CompleteConfig,
-) -> (Client, ClientStream) {
+) -> (Connection, MessageStream) {
     let mut timeout = 1;
 
     loop {
@@ -69,7 +198,7 @@ pub async fn client_stream_reconnect(
     tx: Sender<TwitchToTerminalAction>,
     data_builder: DataBuilder<'_>,
     config: &CompleteConfig,
-) -> (Client, ClientStream) {
+) -> (Connection, MessageStream) {
     match err {
         PingTimeout => {
             tx.send(data_builder.system("Ping to Twitch has timed out.".to_string()))
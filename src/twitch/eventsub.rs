@@ -0,0 +1,671 @@
+//! Events that are only sent through EventSub rather than IRC, received over a WebSocket.
+//! <https://dev.twitch.tv/docs/eventsub/handling-websocket-events/>
+
+use std::{collections::VecDeque, time::Duration};
+
+use chrono::Local;
+use color_eyre::{Result, eyre::bail};
+use futures::StreamExt;
+use log::{debug, info, warn};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::{
+    sync::{
+        broadcast::{Receiver, error::RecvError},
+        mpsc::Sender,
+    },
+    time::{Instant, sleep_until},
+};
+
+use crate::{
+    handlers::{config::CompleteConfig, data::TwitchToTerminalAction, state::State},
+    twitch::{
+        TwitchAction,
+        alerts::Alert,
+        automod::{AUTOMOD_SCOPE, HeldMessage, resolved_message_id},
+        budget::{Priority, send_helix},
+        helix::{ensure_requestable, track_request},
+        mod_actions::ModAction,
+        oauth::{get_channel_id, get_twitch_client, get_twitch_client_id},
+        polls::{POLLS_SCOPE, PREDICTIONS_SCOPE, Poll},
+        raids::Raid,
+        redemptions::Redemption,
+        websocket::connect,
+    },
+};
+
+const EVENTSUB_HOST: &str = "eventsub.wss.twitch.tv";
+const EVENTSUB_PATH: &str = "/ws";
+const EVENTSUB_PORT: u16 = 443;
+/// How long to wait before connecting again after the connection failed.
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+/// How long a session can be quiet for until its welcome message says otherwise.
+const DEFAULT_KEEPALIVE: Duration = Duration::from_secs(10);
+/// Extra time given to keepalive messages that are running late before the connection is considered lost.
+const KEEPALIVE_GRACE: Duration = Duration::from_secs(5);
+/// How many notification IDs are remembered, as Twitch can send the same notification more than once.
+const RECENT_NOTIFICATIONS: usize = 100;
+
+/// An event that can be subscribed to, if the token has one scope out of each group of scopes.
+struct SubscriptionType {
+    kind: &'static str,
+    version: &'static str,
+    scopes: &'static [&'static [&'static str]],
+    /// If the event is received as a moderator of the channel, rather than only by the broadcaster.
+    moderated: bool,
+    /// The field of the condition that the ID of the channel is given in.
+    condition: &'static str,
+    /// Turn an event into what is shown in the terminal, given the type of the subscription.
+    handle: fn(&str, Value) -> Result<TwitchToTerminalAction>,
+}
+
+impl SubscriptionType {
+    fn is_allowed(&self, scopes: &[String]) -> bool {
+        self.scopes
+            .iter()
+            .all(|group| group.iter().any(|scope| scopes.iter().any(|s| s == scope)))
+    }
+}
+
+/// The subscriptions made for the current channel.
+/// <https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/>
+const SUBSCRIPTIONS: [SubscriptionType; 15] = [
+    SubscriptionType {
+        kind: "automod.message.hold",
+        version: "1",
+        scopes: &[&[AUTOMOD_SCOPE]],
+        moderated: true,
+        condition: "broadcaster_user_id",
+        handle: |_, event| HeldMessage::from_event(event).map(TwitchToTerminalAction::AutoModHeld),
+    },
+    SubscriptionType {
+        kind: "automod.message.update",
+        version: "1",
+        scopes: &[&[AUTOMOD_SCOPE]],
+        moderated: true,
+        condition: "broadcaster_user_id",
+        handle: |_, event| resolved_message_id(event).map(TwitchToTerminalAction::AutoModResolved),
+    },
+    SubscriptionType {
+        kind: "channel.moderate",
+        version: "1",
+        scopes: &[
+            &[
+                "moderator:read:blocked_terms",
+                "moderator:manage:blocked_terms",
+            ],
+            &[
+                "moderator:read:chat_settings",
+                "moderator:manage:chat_settings",
+            ],
+            &[
+                "moderator:read:unban_requests",
+                "moderator:manage:unban_requests",
+            ],
+            &[
+                "moderator:read:banned_users",
+                "moderator:manage:banned_users",
+            ],
+            &[
+                "moderator:read:chat_messages",
+                "moderator:manage:chat_messages",
+            ],
+            &["moderator:read:moderators", "channel:manage:moderators"],
+            &["moderator:read:vips", "channel:manage:vips"],
+        ],
+        moderated: true,
+        condition: "broadcaster_user_id",
+        handle: |_, event| {
+            ModAction::from_moderate_event(event, Local::now())
+                .map(TwitchToTerminalAction::ModAction)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.unban_request.create",
+        version: "1",
+        scopes: &[&[
+            "moderator:read:unban_requests",
+            "moderator:manage:unban_requests",
+        ]],
+        moderated: true,
+        condition: "broadcaster_user_id",
+        handle: |_, event| {
+            ModAction::from_unban_request_event(event, Local::now())
+                .map(TwitchToTerminalAction::ModAction)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.channel_points_custom_reward_redemption.add",
+        version: "1",
+        scopes: &[&["channel:read:redemptions", "channel:manage:redemptions"]],
+        moderated: false,
+        condition: "broadcaster_user_id",
+        handle: |_, event| Redemption::from_event(event).map(TwitchToTerminalAction::Redemption),
+    },
+    SubscriptionType {
+        kind: "channel.poll.begin",
+        version: "1",
+        scopes: &[&["channel:read:polls", POLLS_SCOPE]],
+        moderated: false,
+        condition: "broadcaster_user_id",
+        handle: |kind, event| {
+            Poll::from_event(kind, event, Local::now()).map(TwitchToTerminalAction::Poll)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.poll.progress",
+        version: "1",
+        scopes: &[&["channel:read:polls", POLLS_SCOPE]],
+        moderated: false,
+        condition: "broadcaster_user_id",
+        handle: |kind, event| {
+            Poll::from_event(kind, event, Local::now()).map(TwitchToTerminalAction::Poll)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.poll.end",
+        version: "1",
+        scopes: &[&["channel:read:polls", POLLS_SCOPE]],
+        moderated: false,
+        condition: "broadcaster_user_id",
+        handle: |kind, event| {
+            Poll::from_event(kind, event, Local::now()).map(TwitchToTerminalAction::Poll)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.prediction.begin",
+        version: "1",
+        scopes: &[&["channel:read:predictions", PREDICTIONS_SCOPE]],
+        moderated: false,
+        condition: "broadcaster_user_id",
+        handle: |kind, event| {
+            Poll::from_event(kind, event, Local::now()).map(TwitchToTerminalAction::Poll)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.prediction.progress",
+        version: "1",
+        scopes: &[&["channel:read:predictions", PREDICTIONS_SCOPE]],
+        moderated: false,
+        condition: "broadcaster_user_id",
+        handle: |kind, event| {
+            Poll::from_event(kind, event, Local::now()).map(TwitchToTerminalAction::Poll)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.prediction.lock",
+        version: "1",
+        scopes: &[&["channel:read:predictions", PREDICTIONS_SCOPE]],
+        moderated: false,
+        condition: "broadcaster_user_id",
+        handle: |kind, event| {
+            Poll::from_event(kind, event, Local::now()).map(TwitchToTerminalAction::Poll)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.prediction.end",
+        version: "1",
+        scopes: &[&["channel:read:predictions", PREDICTIONS_SCOPE]],
+        moderated: false,
+        condition: "broadcaster_user_id",
+        handle: |kind, event| {
+            Poll::from_event(kind, event, Local::now()).map(TwitchToTerminalAction::Poll)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.shield_mode.begin",
+        version: "1",
+        scopes: &[&["moderator:read:shield_mode", "moderator:manage:shield_mode"]],
+        moderated: true,
+        condition: "broadcaster_user_id",
+        handle: |kind, event| {
+            Alert::from_shield_mode_event(kind, event, Local::now())
+                .map(TwitchToTerminalAction::Alert)
+        },
+    },
+    SubscriptionType {
+        kind: "channel.shield_mode.end",
+        version: "1",
+        scopes: &[&["moderator:read:shield_mode", "moderator:manage:shield_mode"]],
+        moderated: true,
+        condition: "broadcaster_user_id",
+        handle: |kind, event| {
+            Alert::from_shield_mode_event(kind, event, Local::now())
+                .map(TwitchToTerminalAction::Alert)
+        },
+    },
+    // Incoming raids are already sent over IRC, so only the channel raiding others is subscribed to
+    SubscriptionType {
+        kind: "channel.raid",
+        version: "1",
+        scopes: &[],
+        moderated: false,
+        condition: "from_broadcaster_user_id",
+        handle: |_, event| Raid::from_event(event).map(TwitchToTerminalAction::Raid),
+    },
+];
+
+/// The subscriptions the token has the scopes for.
+fn allowed_subscriptions(scopes: &[String]) -> Vec<&'static SubscriptionType> {
+    SUBSCRIPTIONS
+        .iter()
+        .filter(|subscription| subscription.is_allowed(scopes))
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    message_id: String,
+    message_type: String,
+}
+
+#[derive(Deserialize)]
+struct Session {
+    id: String,
+    reconnect_url: Option<String>,
+    keepalive_timeout_seconds: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct Subscription {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize, Default)]
+struct Payload {
+    session: Option<Session>,
+    subscription: Option<Subscription>,
+    event: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct RawMessage {
+    metadata: Metadata,
+    #[serde(default)]
+    payload: Payload,
+}
+
+#[derive(Debug, PartialEq)]
+enum EventSubMessage {
+    /// The first message of a session, with the ID subscriptions are made for,
+    /// and how many seconds can pass without a message before the connection is considered lost.
+    Welcome(String, Option<u64>),
+    /// An event of a subscription, with the ID of the message and the type of the subscription.
+    Notification(String, String, Value),
+    /// Twitch wants the connection moved to another URL, keeping the subscriptions.
+    Reconnect(String),
+    /// A subscription was revoked, such as when the user is no longer a moderator, with its type.
+    Revocation(String),
+    Keepalive,
+}
+
+fn parse_message(text: &str) -> Result<EventSubMessage> {
+    let message = serde_json::from_str::<RawMessage>(text)?;
+    let payload = message.payload;
+
+    Ok(match message.metadata.message_type.as_str() {
+        "session_welcome" => match payload.session {
+            Some(session) => {
+                EventSubMessage::Welcome(session.id, session.keepalive_timeout_seconds)
+            }
+            None => bail!("EventSub welcome message is missing its session."),
+        },
+        "notification" => match (payload.subscription, payload.event) {
+            (Some(subscription), Some(event)) => {
+                EventSubMessage::Notification(message.metadata.message_id, subscription.kind, event)
+            }
+            _ => bail!("EventSub notification is missing its event."),
+        },
+        "session_reconnect" => match payload.session.and_then(|s| s.reconnect_url) {
+            Some(url) => EventSubMessage::Reconnect(url),
+            None => bail!("EventSub reconnect message is missing its URL."),
+        },
+        "revocation" => match payload.subscription {
+            Some(subscription) => EventSubMessage::Revocation(subscription.kind),
+            None => bail!("EventSub revocation is missing its subscription."),
+        },
+        _ => EventSubMessage::Keepalive,
+    })
+}
+
+/// Why a session ended.
+enum SessionEnd {
+    /// The user switched to another channel, which needs new subscriptions.
+    SwitchChannel(String),
+    /// Twitch moved the session to another URL.
+    Reconnect(String),
+    /// Events cannot be received in this channel, so nothing is done until the channel changes.
+    Unavailable,
+    /// The application is shutting down.
+    Closed,
+}
+
+/// Subscribe to every allowed event in the channel, succeeding if at least one subscription was made,
+/// as moderators might not have access to all of them, and only the broadcaster receives redemptions and polls.
+async fn subscribe(token: Option<&str>, channel: &str, session_id: &str) -> Result<()> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let client_id = get_twitch_client_id(None).await?;
+    let broadcaster_id =
+        track_request(get_channel_id(&client, channel, Priority::Background).await)?;
+
+    let mut subscribed = false;
+
+    for subscription in allowed_subscriptions(&client_id.scopes) {
+        let mut condition = json!({ subscription.condition: broadcaster_id.to_string() });
+
+        if subscription.moderated {
+            condition["moderator_user_id"] = json!(client_id.user_id);
+        }
+
+        let response = track_request(
+            send_helix(
+                "helix/eventsub/subscriptions",
+                Priority::Background,
+                client
+                    .post("https://api.twitch.tv/helix/eventsub/subscriptions")
+                    .json(&json!({
+                        "type": subscription.kind,
+                        "version": subscription.version,
+                        "condition": condition,
+                        "transport": {
+                            "method": "websocket",
+                            "session_id": session_id,
+                        },
+                    })),
+            )
+            .await,
+        )?;
+
+        // Forbidden when the user does not moderate the channel
+        if response.status().is_success() {
+            subscribed = true;
+        } else {
+            debug!(
+                "Unable to subscribe to {} in {channel}: {}",
+                subscription.kind,
+                response.status()
+            );
+        }
+    }
+
+    if !subscribed {
+        bail!("No EventSub subscriptions could be made in {channel}.");
+    }
+
+    Ok(())
+}
+
+async fn handle_notification(kind: &str, event: Value, tx: &Sender<TwitchToTerminalAction>) {
+    let Some(subscription) = SUBSCRIPTIONS.iter().find(|s| s.kind == kind) else {
+        return;
+    };
+
+    match (subscription.handle)(kind, event) {
+        Ok(action) => tx.send(action).await.unwrap(),
+        Err(err) => warn!("Unable to read EventSub {kind} event: {err}"),
+    }
+}
+
+/// Run a session until it ends, subscribing to the events of the channel once it starts,
+/// unless the session was moved from another one which already made the subscriptions.
+async fn run_session(
+    config: &CompleteConfig,
+    channel: &str,
+    url: &Url,
+    subscribed: bool,
+    seen: &mut VecDeque<String>,
+    tx: &Sender<TwitchToTerminalAction>,
+    rx: &mut Receiver<TwitchAction>,
+) -> Result<SessionEnd> {
+    let host = url.host_str().unwrap_or(EVENTSUB_HOST);
+    let path = url.query().map_or_else(
+        || url.path().to_string(),
+        |query| format!("{}?{query}", url.path()),
+    );
+
+    let (_sender, mut messages) = connect(host, url.port().unwrap_or(EVENTSUB_PORT), &path).await?;
+
+    let mut keepalive = DEFAULT_KEEPALIVE;
+    let mut deadline = Instant::now() + keepalive + KEEPALIVE_GRACE;
+
+    loop {
+        tokio::select! {
+            () = sleep_until(deadline) => bail!("EventSub keepalive was missed."),
+            message = messages.next() => {
+                let Some(message) = message else {
+                    bail!("EventSub connection was closed.");
+                };
+
+                let message = parse_message(&message?)?;
+
+                if let EventSubMessage::Welcome(_, Some(seconds)) = message {
+                    keepalive = Duration::from_secs(seconds);
+                }
+
+                // Any message shows that the connection is still alive
+                deadline = Instant::now() + keepalive + KEEPALIVE_GRACE;
+
+                match message {
+                    EventSubMessage::Welcome(session_id, _) => {
+                        if subscribed {
+                            continue;
+                        }
+
+                        if let Err(err) = subscribe(config.twitch.token.as_deref(), channel, &session_id).await {
+                            debug!("Not receiving EventSub events in {channel}: {err}");
+
+                            return Ok(SessionEnd::Unavailable);
+                        }
+
+                        info!("Receiving EventSub events in {channel}.");
+                    }
+                    EventSubMessage::Notification(id, kind, event) => {
+                        if seen.contains(&id) {
+                            continue;
+                        }
+
+                        if seen.len() >= RECENT_NOTIFICATIONS {
+                            seen.pop_front();
+                        }
+
+                        seen.push_back(id);
+
+                        handle_notification(&kind, event, tx).await;
+                    }
+                    EventSubMessage::Reconnect(url) => return Ok(SessionEnd::Reconnect(url)),
+                    // The other subscriptions keep working, such as raids after losing moderator
+                    EventSubMessage::Revocation(kind) => {
+                        debug!("EventSub subscription to {kind} in {channel} was revoked.");
+                    }
+                    EventSubMessage::Keepalive => {}
+                }
+            }
+            action = rx.recv() => match action {
+                Ok(TwitchAction::Join(new_channel)) if new_channel != channel => {
+                    return Ok(SessionEnd::SwitchChannel(new_channel));
+                }
+                Err(RecvError::Closed) => return Ok(SessionEnd::Closed),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Wait for the user to switch to another channel.
+async fn wait_for_channel(rx: &mut Receiver<TwitchAction>) -> Option<String> {
+    loop {
+        match rx.recv().await {
+            Ok(TwitchAction::Join(channel)) => return Some(channel),
+            Err(RecvError::Closed) => return None,
+            _ => {}
+        }
+    }
+}
+
+/// Receive the events of the current channel that IRC does not send.
+///
+/// These are the raids the channel starts, the messages held by AutoMod and the actions of moderators
+/// for users who can moderate the channel, and channel point redemptions, polls, and predictions in the user's own channel.
+pub async fn eventsub(
+    config: CompleteConfig,
+    tx: Sender<TwitchToTerminalAction>,
+    mut rx: Receiver<TwitchAction>,
+) {
+    // Raids need no scopes, so there is always something to subscribe to once the token is valid
+    if get_twitch_client_id(config.twitch.token.as_deref())
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let default_url = Url::parse(&format!("wss://{EVENTSUB_HOST}{EVENTSUB_PATH}")).unwrap();
+
+    // The channel is picked from the dashboard first, if that is where the application starts
+    let mut channel = if config.terminal.first_state == State::Dashboard {
+        let Some(picked) = wait_for_channel(&mut rx).await else {
+            return;
+        };
+
+        picked
+    } else {
+        config.twitch.channel.clone()
+    };
+
+    let mut url = default_url.clone();
+    let mut subscribed = false;
+    let mut seen = VecDeque::with_capacity(RECENT_NOTIFICATIONS);
+
+    loop {
+        match run_session(&config, &channel, &url, subscribed, &mut seen, &tx, &mut rx).await {
+            Ok(SessionEnd::SwitchChannel(new_channel)) => {
+                channel = new_channel;
+                url = default_url.clone();
+                subscribed = false;
+            }
+            Ok(SessionEnd::Reconnect(reconnect_url)) => {
+                if let Ok(reconnect_url) = Url::parse(&reconnect_url) {
+                    url = reconnect_url;
+                    subscribed = true;
+                }
+            }
+            Ok(SessionEnd::Unavailable) => {
+                let Some(new_channel) = wait_for_channel(&mut rx).await else {
+                    return;
+                };
+
+                channel = new_channel;
+                url = default_url.clone();
+                subscribed = false;
+            }
+            Ok(SessionEnd::Closed) => return,
+            Err(err) => {
+                warn!("EventSub connection failed, retrying in {RETRY_DELAY:?}: {err}");
+
+                tokio::time::sleep(RETRY_DELAY).await;
+
+                url = default_url.clone();
+                subscribed = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twitch::oauth::DEVICE_CODE_SCOPES;
+
+    #[test]
+    fn test_allowed_subscriptions() {
+        let kinds = |scopes: &[&str]| {
+            allowed_subscriptions(
+                &scopes
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>(),
+            )
+            .iter()
+            .map(|subscription| subscription.kind)
+            .collect::<Vec<&str>>()
+        };
+
+        assert_eq!(kinds(&["chat:read"]), ["channel.raid"]);
+        assert_eq!(
+            kinds(&[AUTOMOD_SCOPE, "moderator:manage:unban_requests"]),
+            [
+                "automod.message.hold",
+                "automod.message.update",
+                "channel.unban_request.create",
+                "channel.raid"
+            ]
+        );
+        // Moderation events need every group of scopes
+        assert!(
+            !kinds(&[
+                "moderator:read:banned_users",
+                "moderator:read:chat_messages"
+            ])
+            .contains(&"channel.moderate")
+        );
+
+        // Logging in through the browser asks for the scopes of every event
+        assert_eq!(
+            kinds(&DEVICE_CODE_SCOPES.split(' ').collect::<Vec<&str>>()).len(),
+            SUBSCRIPTIONS.len()
+        );
+    }
+
+    #[test]
+    fn test_parse_message() {
+        assert_eq!(
+            parse_message(
+                r#"{"metadata":{"message_id":"1","message_type":"session_welcome"},"payload":{"session":{"id":"session","status":"connected","reconnect_url":null,"keepalive_timeout_seconds":10}}}"#
+            )
+            .unwrap(),
+            EventSubMessage::Welcome("session".to_string(), Some(10))
+        );
+        assert_eq!(
+            parse_message(
+                r#"{"metadata":{"message_id":"2","message_type":"notification"},"payload":{"subscription":{"type":"automod.message.update"},"event":{"message_id":"abc"}}}"#
+            )
+            .unwrap(),
+            EventSubMessage::Notification(
+                "2".to_string(),
+                "automod.message.update".to_string(),
+                json!({ "message_id": "abc" })
+            )
+        );
+        assert_eq!(
+            parse_message(
+                r#"{"metadata":{"message_id":"3","message_type":"session_reconnect"},"payload":{"session":{"id":"session","reconnect_url":"wss://example.com/ws?id=1"}}}"#
+            )
+            .unwrap(),
+            EventSubMessage::Reconnect("wss://example.com/ws?id=1".to_string())
+        );
+        assert_eq!(
+            parse_message(
+                r#"{"metadata":{"message_id":"4","message_type":"session_keepalive"},"payload":{}}"#
+            )
+            .unwrap(),
+            EventSubMessage::Keepalive
+        );
+        assert_eq!(
+            parse_message(
+                r#"{"metadata":{"message_id":"5","message_type":"revocation"},"payload":{"subscription":{"type":"channel.moderate","status":"authorization_revoked"}}}"#
+            )
+            .unwrap(),
+            EventSubMessage::Revocation("channel.moderate".to_string())
+        );
+        assert!(
+            parse_message(
+                r#"{"metadata":{"message_id":"6","message_type":"notification"},"payload":{}}"#
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Local};
+
+/// The login Twitch gives the sender of gift subs when they chose to stay anonymous.
+const ANONYMOUS_GIFTER_LOGIN: &str = "ananonymousgifter";
+/// How anonymous gifters are shown in chat.
+const ANONYMOUS_GIFTER: &str = "An anonymous gifter";
+
+/// A gifted subscription, or the announcement of several subscriptions about to be gifted at once.
+/// <https://dev.twitch.tv/docs/irc/tags/#usernotice-tags>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GiftSub {
+    /// The channel the subscriptions were gifted in, if any.
+    pub channel: Option<String>,
+    pub gifter: String,
+    /// Who received the subscription, only set for a single gift.
+    pub recipient: Option<String>,
+    /// The amount of subscriptions announced, only set for gifts to the community.
+    pub count: Option<u64>,
+}
+
+impl GiftSub {
+    /// Read a gift from the tags of a `USERNOTICE`, if it is one.
+    pub fn from_tags(channel: Option<String>, tags: &HashMap<&str, &str>) -> Option<Self> {
+        let msg_id = tags.get("msg-id").copied().unwrap_or_default();
+
+        // Anonymous gifts are either sent as their own kind, or as being from a placeholder user
+        let gifter =
+            if msg_id.starts_with("anon") || tags.get("login") == Some(&ANONYMOUS_GIFTER_LOGIN) {
+                ANONYMOUS_GIFTER.to_string()
+            } else {
+                tags.get("display-name")
+                    .or_else(|| tags.get("login"))
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            };
+
+        match msg_id.trim_start_matches("anon") {
+            "subgift" => Some(Self {
+                channel,
+                gifter,
+                recipient: tags
+                    .get("msg-param-recipient-display-name")
+                    .or_else(|| tags.get("msg-param-recipient-user-name"))
+                    .map(ToString::to_string),
+                count: None,
+            }),
+            "submysterygift" => Some(Self {
+                channel,
+                gifter,
+                recipient: None,
+                count: tags
+                    .get("msg-param-mass-gift-count")
+                    .and_then(|count| count.parse().ok()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Gift subs from one user in quick succession, shown as a single line that is updated as more gifts come in.
+#[derive(Debug, Clone)]
+pub struct GiftBurst {
+    pub channel: Option<String>,
+    pub gifter: String,
+    /// The ID of the message showing this burst.
+    pub message_id: String,
+    /// The amount of subscriptions announced, if the gifts were announced.
+    total: Option<u64>,
+    recipients: Vec<String>,
+    last_gift: DateTime<Local>,
+}
+
+impl GiftBurst {
+    pub fn new(gift: GiftSub, now: DateTime<Local>) -> Self {
+        let message_id = format!(
+            "gift-subs-{}-{}",
+            gift.gifter,
+            now.timestamp_nanos_opt().unwrap_or_default()
+        );
+
+        let mut burst = Self {
+            channel: gift.channel.clone(),
+            gifter: gift.gifter.clone(),
+            message_id,
+            total: None,
+            recipients: vec![],
+            last_gift: now,
+        };
+
+        burst.add(gift, now);
+
+        burst
+    }
+
+    /// If a gift belongs to this burst, being from the same user in the same channel, within the aggregation window.
+    pub fn accepts(&self, gift: &GiftSub, now: DateTime<Local>, window: Duration) -> bool {
+        self.channel == gift.channel && self.gifter == gift.gifter && now - self.last_gift <= window
+    }
+
+    pub fn add(&mut self, gift: GiftSub, now: DateTime<Local>) {
+        if let Some(count) = gift.count {
+            self.total = Some(self.total.unwrap_or_default() + count);
+        }
+
+        self.recipients.extend(gift.recipient);
+        self.last_gift = now;
+    }
+
+    /// The line shown in chat, listing every recipient when expanded.
+    pub fn summary(&self, expanded: bool) -> String {
+        let shown = self.recipients.len();
+
+        let summary = match (self.total, &self.recipients[..]) {
+            (Some(total), _) => format!(
+                "{} gifted {total} sub{} ({shown} shown)",
+                self.gifter,
+                if total == 1 { "" } else { "s" }
+            ),
+            // A single gift always names who received it.
+            (None, [recipient]) => return format!("{} gifted a sub to {recipient}", self.gifter),
+            (None, _) => format!("{} gifted {shown} subs", self.gifter),
+        };
+
+        if expanded && !self.recipients.is_empty() {
+            format!("{summary}: {}", self.recipients.join(", "))
+        } else {
+            summary
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gift(recipient: Option<&str>, count: Option<u64>) -> GiftSub {
+        GiftSub {
+            channel: Some("channel".to_string()),
+            gifter: "UserX".to_string(),
+            recipient: recipient.map(ToString::to_string),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_gift_sub_from_tags() {
+        let tags = HashMap::from([
+            ("msg-id", "subgift"),
+            ("display-name", "UserX"),
+            ("msg-param-recipient-display-name", "UserY"),
+        ]);
+
+        assert_eq!(
+            GiftSub::from_tags(Some("channel".to_string()), &tags),
+            Some(gift(Some("UserY"), None))
+        );
+
+        let tags = HashMap::from([
+            ("msg-id", "submysterygift"),
+            ("display-name", "UserX"),
+            ("msg-param-mass-gift-count", "50"),
+        ]);
+
+        assert_eq!(
+            GiftSub::from_tags(Some("channel".to_string()), &tags),
+            Some(gift(None, Some(50)))
+        );
+
+        let tags = HashMap::from([
+            ("msg-id", "subgift"),
+            ("login", "ananonymousgifter"),
+            ("display-name", "AnAnonymousGifter"),
+            ("msg-param-recipient-display-name", "UserY"),
+        ]);
+
+        assert_eq!(
+            GiftSub::from_tags(None, &tags).unwrap().gifter,
+            "An anonymous gifter"
+        );
+
+        let tags = HashMap::from([
+            ("msg-id", "anonsubmysterygift"),
+            ("msg-param-mass-gift-count", "5"),
+        ]);
+
+        assert_eq!(
+            GiftSub::from_tags(None, &tags).map(|gift| (gift.gifter, gift.count)),
+            Some(("An anonymous gifter".to_string(), Some(5)))
+        );
+
+        let tags = HashMap::from([("msg-id", "raid")]);
+
+        assert_eq!(GiftSub::from_tags(None, &tags), None);
+    }
+
+    #[test]
+    fn test_gift_burst_summary() {
+        let now = Local::now();
+        let mut burst = GiftBurst::new(gift(None, Some(3)), now);
+
+        assert_eq!(burst.summary(false), "UserX gifted 3 subs (0 shown)");
+
+        burst.add(gift(Some("a"), None), now);
+        burst.add(gift(Some("b"), None), now);
+
+        assert_eq!(burst.summary(false), "UserX gifted 3 subs (2 shown)");
+        assert_eq!(burst.summary(true), "UserX gifted 3 subs (2 shown): a, b");
+
+        let burst = GiftBurst::new(gift(Some("a"), None), now);
+
+        assert_eq!(burst.summary(false), "UserX gifted a sub to a");
+    }
+
+    #[test]
+    fn test_gift_burst_window() {
+        let now = Local::now();
+        let window = Duration::seconds(10);
+        let burst = GiftBurst::new(gift(Some("a"), None), now);
+
+        assert!(burst.accepts(&gift(Some("b"), None), now + Duration::seconds(10), window));
+        assert!(!burst.accepts(&gift(Some("b"), None), now + Duration::seconds(11), window));
+
+        let mut other_gifter = gift(Some("b"), None);
+        other_gifter.gifter = "UserZ".to_string();
+
+        assert!(!burst.accepts(&other_gifter, now, window));
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use color_eyre::{Result, eyre::anyhow};
+use log::warn;
+
+/// How many requests in a row need to fail before Helix is considered to be down.
+const FAILURE_THRESHOLD: u32 = 3;
+/// The shortest amount of time to wait before retrying requests once Helix is considered down.
+const MINIMUM_BACKOFF: Duration = Duration::from_secs(30);
+/// The longest amount of time to wait before retrying requests once Helix is considered down.
+const MAXIMUM_BACKOFF: Duration = Duration::from_secs(600);
+
+static HELIX_STATUS: Mutex<HelixStatus> = Mutex::new(HelixStatus::new());
+
+/// Keeps track of failing Helix requests, so that features depending on the API
+/// can fall back to cached data instead of hammering an API that is down.
+#[derive(Debug)]
+pub struct HelixStatus {
+    consecutive_failures: u32,
+    retry_after: Option<Instant>,
+}
+
+impl HelixStatus {
+    const fn new() -> Self {
+        Self {
+            consecutive_failures: 0,
+            retry_after: None,
+        }
+    }
+
+    const fn is_degraded(&self) -> bool {
+        self.consecutive_failures >= FAILURE_THRESHOLD
+    }
+
+    fn backoff(&self) -> Duration {
+        let exponent = self.consecutive_failures.saturating_sub(FAILURE_THRESHOLD);
+
+        MINIMUM_BACKOFF
+            .saturating_mul(2_u32.saturating_pow(exponent))
+            .min(MAXIMUM_BACKOFF)
+    }
+
+    fn success(&mut self) {
+        self.consecutive_failures = 0;
+        self.retry_after = None;
+    }
+
+    fn failure(&mut self) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+
+        if self.is_degraded() {
+            self.retry_after = Some(Instant::now() + self.backoff());
+        }
+    }
+
+    fn can_request(&self) -> bool {
+        self.retry_after.is_none_or(|time| Instant::now() >= time)
+    }
+}
+
+/// If Helix requests have failed enough times in a row for features to be using stale data.
+pub fn helix_degraded() -> bool {
+    HELIX_STATUS.lock().is_ok_and(|status| status.is_degraded())
+}
+
+/// Record the result of a request to Helix, passing it through.
+pub fn track_request<T>(result: Result<T>) -> Result<T> {
+    if let Ok(mut status) = HELIX_STATUS.lock() {
+        match &result {
+            Ok(_) => status.success(),
+            Err(err) => {
+                status.failure();
+
+                if status.is_degraded() {
+                    warn!(
+                        "Helix request failed {} times in a row, backing off for {:?}: {err}",
+                        status.consecutive_failures,
+                        status.backoff()
+                    );
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Fail early when Helix is backing off, so callers can use their cached data.
+pub fn ensure_requestable() -> Result<()> {
+    if HELIX_STATUS.lock().is_ok_and(|status| status.can_request()) {
+        Ok(())
+    } else {
+        Err(anyhow!("Helix is unavailable, retrying later."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degraded_after_threshold() {
+        let mut status = HelixStatus::new();
+
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            status.failure();
+        }
+
+        assert!(!status.is_degraded());
+        assert!(status.can_request());
+
+        status.failure();
+
+        assert!(status.is_degraded());
+        assert!(!status.can_request());
+
+        status.success();
+
+        assert!(!status.is_degraded());
+        assert!(status.can_request());
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let mut status = HelixStatus::new();
+
+        for _ in 0..50 {
+            status.failure();
+        }
+
+        assert_eq!(status.backoff(), MAXIMUM_BACKOFF);
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Local};
+
+/// A user joining or leaving a channel, only sent when the membership capability is requested.
+/// <https://dev.twitch.tv/docs/irc/capabilities/#membership-capability>
+#[derive(Debug, Clone)]
+pub struct Membership {
+    pub time_received: DateTime<Local>,
+    pub channel: String,
+    pub user: String,
+    /// If the user joined the channel, rather than leaving it.
+    pub joined: bool,
+}
+
+impl Membership {
+    pub fn new(channel: &str, user: String, joined: bool) -> Self {
+        Self {
+            time_received: Local::now(),
+            channel: channel.trim_start_matches('#').to_string(),
+            user,
+            joined,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ActivityState {
+    Shown,
+    /// The channel just went over the chatter limit, and its activity will no longer be shown.
+    Disabled(usize),
+    Hidden,
+}
+
+/// The users known to be in each channel, so that activity stops being shown in channels that are too busy to follow.
+#[derive(Debug, Default)]
+pub struct Chatters {
+    channels: HashMap<String, HashSet<String>>,
+    disabled: HashSet<String>,
+}
+
+impl Chatters {
+    /// Keep track of a user joining or leaving, and whether the activity of its channel should still be shown.
+    /// A limit of 0 never hides activity.
+    pub fn update(&mut self, membership: &Membership, limit: usize) -> ActivityState {
+        let users = self.channels.entry(membership.channel.clone()).or_default();
+
+        if membership.joined {
+            users.insert(membership.user.to_lowercase());
+        } else {
+            users.remove(&membership.user.to_lowercase());
+        }
+
+        if self.disabled.contains(&membership.channel) {
+            ActivityState::Hidden
+        } else if limit != 0 && users.len() > limit {
+            self.disabled.insert(membership.channel.clone());
+            ActivityState::Disabled(users.len())
+        } else {
+            ActivityState::Shown
+        }
+    }
+
+    /// Forget about a channel that has been left, so that its activity is shown again if it is rejoined.
+    pub fn remove_channel(&mut self, channel: &str) {
+        let channel = channel.trim_start_matches('#');
+
+        self.channels.remove(channel);
+        self.disabled.remove(channel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_disabled_above_limit() {
+        let mut chatters = Chatters::default();
+
+        assert_eq!(
+            chatters.update(&Membership::new("#channel", "a".to_string(), true), 2),
+            ActivityState::Shown
+        );
+        assert_eq!(
+            chatters.update(&Membership::new("#channel", "b".to_string(), true), 2),
+            ActivityState::Shown
+        );
+        assert_eq!(
+            chatters.update(&Membership::new("#channel", "c".to_string(), true), 2),
+            ActivityState::Disabled(3)
+        );
+        // Activity stays hidden, even once the channel is small again
+        assert_eq!(
+            chatters.update(&Membership::new("#channel", "c".to_string(), false), 2),
+            ActivityState::Hidden
+        );
+        assert_eq!(
+            chatters.update(&Membership::new("#other", "a".to_string(), true), 2),
+            ActivityState::Shown
+        );
+
+        chatters.remove_channel("#channel");
+
+        assert_eq!(
+            chatters.update(&Membership::new("#channel", "a".to_string(), true), 2),
+            ActivityState::Shown
+        );
+    }
+
+    #[test]
+    fn test_no_chatter_limit() {
+        let mut chatters = Chatters::default();
+
+        for user in ["a", "b", "c"] {
+            assert_eq!(
+                chatters.update(&Membership::new("#channel", user.to_string(), true), 0),
+                ActivityState::Shown
+            );
+        }
+    }
+}
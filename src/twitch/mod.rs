@@ -1,9 +1,38 @@
-mod badges;
+pub mod alerts;
+pub mod automod;
+pub mod blocks;
+pub mod budget;
+pub mod cache;
+pub mod channel_info;
 pub mod channels;
+pub mod chat_status;
+pub mod cheers;
 mod connection;
+pub mod eventsub;
+pub mod gifts;
+pub mod helix;
+pub mod membership;
+pub mod mod_actions;
+pub mod moderation;
+pub mod notices;
 pub mod oauth;
-
-use std::{collections::HashMap, hash::BuildHasher};
+pub mod outbox;
+pub mod polls;
+pub mod raids;
+pub mod redemptions;
+mod rejoin;
+pub mod rewards;
+pub mod streams;
+pub mod subscriptions;
+mod websocket;
+pub mod whispers;
+
+use std::{
+    collections::HashMap,
+    hash::BuildHasher,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use color_eyre::Result;
 use futures::StreamExt;
@@ -12,18 +41,36 @@ use irc::{
     proto::{Command, Message},
 };
 use log::{debug, info};
-use tokio::sync::{broadcast::Receiver, mpsc::Sender};
+use tokio::{
+    sync::{broadcast::Receiver, mpsc::Sender},
+    time::interval,
+};
+use tui::style::Color;
 
 use crate::{
-    emotes::{DownloadedEmotes, get_twitch_emote},
+    emotes::{DownloadedEmotes, get_cheermote, get_twitch_emote},
     handlers::{
-        config::CompleteConfig,
-        data::{DataBuilder, TwitchToTerminalAction},
+        config::{BadgeStyle, CompleteConfig},
+        data::{Badge, DataBuilder, Reply, TwitchToTerminalAction},
         state::State,
     },
     twitch::{
-        badges::retrieve_user_badges,
-        connection::{client_stream_reconnect, wait_client_stream},
+        automod::resolve_held_message,
+        blocks::{get_blocked_users, set_blocked},
+        channel_info::{ChannelInfoCommand, update_channel_info},
+        chat_status::{ConnectionState, set_connection_state, update_room_modes},
+        cheers::{cheer_tier, parse_cheer},
+        connection::{Connection, client_stream_reconnect, wait_client_stream},
+        gifts::GiftSub,
+        membership::{ActivityState, Chatters, Membership},
+        moderation::{ModerationCommand, moderate},
+        notices::Notice,
+        outbox::{Outbox, Outgoing},
+        polls::{PollCommand, run_poll_command},
+        raids::Raid,
+        rejoin::{JOIN_TIMEOUT, RejoinStep, Rejoins},
+        subscriptions::Subscription,
+        whispers::{Whisper, send_whisper},
     },
     utils::{
         emotes::emotes_enabled,
@@ -34,8 +81,26 @@ use crate::{
 #[derive(Debug, Clone)]
 pub enum TwitchAction {
     Privmsg(String),
+    /// Join a channel if it has not been joined yet, and make it the channel messages are sent to.
     Join(String),
-    ClearMessages,
+    /// Leave a channel that is open in another tab.
+    Part(String),
+    /// Reply to the message with an ID.
+    Reply(String, String),
+    /// Send a whisper with a message to a user.
+    Whisper(String, String),
+    /// Run a moderation command in the current channel through Helix.
+    Moderate(ModerationCommand),
+    /// Approve or deny the message with an ID that was held by AutoMod.
+    ResolveAutoMod(String, bool),
+    /// Start or end a poll or prediction in the current channel.
+    Poll(PollCommand),
+    /// Change the title or category of the current channel.
+    ChannelInfo(ChannelInfoCommand),
+    /// Send or drop messages that were not sent during the previous session.
+    Recovered(Vec<Outgoing>, bool),
+    /// Ignore a user or stop ignoring them, blocking or unblocking them on Twitch if blocks are synced.
+    Ignore(String, bool),
 }
 
 pub async fn twitch_irc(
@@ -45,6 +110,31 @@ pub async fn twitch_irc(
 ) {
     info!("Spawned Twitch IRC thread.");
 
+    // Shared with the tasks sending whispers, which remove them once they are sent
+    let outbox = Arc::new(Mutex::new(Outbox::load(&config.storage)));
+
+    let recovered = outbox.lock().unwrap().recovered();
+
+    if !recovered.is_empty() {
+        tx.send(TwitchToTerminalAction::Recovered(recovered))
+            .await
+            .unwrap();
+    }
+
+    if config.filters.twitch_blocks {
+        let tx = tx.clone();
+        let token = config.twitch.token.clone();
+
+        tokio::task::spawn(async move {
+            let message = match get_blocked_users(token.as_deref()).await {
+                Ok(users) => TwitchToTerminalAction::BlockedUsers(users),
+                Err(err) => DataBuilder::default().system(err.to_string()),
+            };
+
+            tx.send(message).await.unwrap();
+        });
+    }
+
     // If the dashboard is the start state, wait until the user has selected
     // a channel before connecting to Twitch's IRC.
     if config.terminal.first_state == State::Dashboard {
@@ -62,22 +152,38 @@ pub async fn twitch_irc(
 
     let enable_emotes = emotes_enabled(&config.frontend);
 
-    let data_builder = DataBuilder::new(&config.frontend.datetime_format);
+    let data_builder = DataBuilder::default();
     let mut room_state_startup = false;
 
-    let (mut client, mut stream) =
+    set_connection_state(ConnectionState::Connecting);
+
+    let (mut connection, mut stream) =
         wait_client_stream(tx.clone(), data_builder, config.clone()).await;
 
-    let sender = client.sender();
+    set_connection_state(ConnectionState::Connected);
+
+    // Every channel that has been joined, with one tab each.
+    let mut joined = vec![config.twitch.channel.clone()];
+
+    // Joins are checked for being confirmed by Twitch, to join again if they were lost
+    let mut rejoins = Rejoins::default();
+    let mut rejoin_interval = interval(JOIN_TIMEOUT);
+
+    rejoins.joining(&config.twitch.channel, Instant::now());
 
     // Request commands capabilities
-    if client
-        .send_cap_req(&[
-            Capability::Custom("twitch.tv/commands"),
-            Capability::Custom("twitch.tv/tags"),
-        ])
-        .is_err()
-    {
+    let mut capabilities = vec![Capability::Custom("twitch.tv/commands")];
+
+    // Tags make up most of the traffic in busy channels
+    if !config.twitch.low_bandwidth {
+        capabilities.push(Capability::Custom("twitch.tv/tags"));
+    }
+
+    if config.twitch.membership_enabled() {
+        capabilities.push(Capability::Custom("twitch.tv/membership"));
+    }
+
+    if connection.send_cap_req(&capabilities).is_err() {
         tx.send(
             data_builder.system(
                 "Unable to request commands/tags capability, certain features may be affected."
@@ -90,6 +196,9 @@ pub async fn twitch_irc(
 
     let mut connected = true;
 
+    // Users in each channel, to stop showing activity in busy channels.
+    let mut chatters = Chatters::default();
+
     loop {
         tokio::select! {
             biased;
@@ -101,30 +210,142 @@ pub async fn twitch_irc(
                     TwitchAction::Privmsg(message) => {
                         debug!("Sending message to Twitch: {}", message);
 
-                        client
-                            .send_privmsg(current_channel, message)
-                            .unwrap();
+                        if let Err(err) = connection.send_privmsg(current_channel, message.clone()) {
+                            hold_message(&outbox, &tx, data_builder, &config.twitch.channel, message, &err).await;
+                        }
+                    }
+                    TwitchAction::Reply(parent_id, message) => {
+                        debug!("Replying to message {parent_id} on Twitch: {message}");
+
+                        // Replies are held as plain messages, as the message replied to may be gone by the time they are sent
+                        if let Err(err) = connection.send_reply(current_channel, parent_id, message.clone()) {
+                            hold_message(&outbox, &tx, data_builder, &config.twitch.channel, message, &err).await;
+                        }
                     }
                     TwitchAction::Join(channel) => {
                         debug!("Switching to channel {channel}");
 
-                        let channel_list = format!("#{channel}");
+                        // Channels stay joined when switching away from them, so they only need to be joined once.
+                        if !joined.contains(&channel) {
+                            if let Err(err) = connection.send_join(format!("#{channel}")) {
+                                tx.send(data_builder.twitch(err.to_string())).await.unwrap();
+                            }
 
-                        // Leave previous channel
-                        if let Err(err) = sender.send_part(current_channel) {
-                            tx.send(data_builder.twitch(err.to_string())).await.unwrap();
+                            rejoins.joining(&channel, Instant::now());
+                            joined.push(channel.clone());
                         }
 
-                        // Join specified channel
-                        if let Err(err) = sender.send_join(&channel_list) {
+                        // Set old channel to new channel
+                        config.twitch.channel = channel;
+                    }
+                    TwitchAction::Part(channel) => {
+                        debug!("Leaving channel {channel}");
+
+                        if let Err(err) = connection.send_part(format!("#{channel}")) {
                             tx.send(data_builder.twitch(err.to_string())).await.unwrap();
                         }
 
-                        // Set old channel to new channel
-                        config.twitch.channel = channel;
+                        rejoins.parted(&channel);
+                        joined.retain(|c| !c.eq_ignore_ascii_case(&channel));
+                    }
+                    TwitchAction::Whisper(user, message) => {
+                        debug!("Sending whisper to {user}");
+
+                        spawn_whisper(outbox.clone(), tx.clone(), config.twitch.token.clone(), user, message);
+                    }
+                    TwitchAction::Moderate(command) => {
+                        debug!("Running moderation command {command:?}");
+
+                        let tx = tx.clone();
+                        let token = config.twitch.token.clone();
+                        let channel = config.twitch.channel.clone();
+
+                        tokio::task::spawn(async move {
+                            let message = match moderate(token.as_deref(), &channel, &command).await {
+                                Ok(()) => command.success_message(),
+                                Err(err) => Some(err.to_string()),
+                            };
+
+                            if let Some(message) = message {
+                                tx.send(DataBuilder::default().system(message)).await.unwrap();
+                            }
+                        });
+                    }
+                    TwitchAction::ResolveAutoMod(message_id, allow) => {
+                        debug!("Resolving AutoMod message {message_id}");
+
+                        let tx = tx.clone();
+                        let token = config.twitch.token.clone();
+
+                        // The message leaves the queue once EventSub reports it as resolved
+                        tokio::task::spawn(async move {
+                            if let Err(err) = resolve_held_message(token.as_deref(), &message_id, allow).await {
+                                tx.send(DataBuilder::default().system(err.to_string())).await.unwrap();
+                            }
+                        });
+                    }
+                    TwitchAction::Recovered(items, send) => {
+                        for item in items {
+                            let taken = outbox.lock().unwrap().take_recovered(&item);
+
+                            // Recovered items that are not sent are only dropped from the outbox
+                            match item {
+                                Outgoing::Message { channel, message } if taken && send => {
+                                    debug!("Sending recovered message to {channel}");
+
+                                    if let Err(err) = connection.send_privmsg(format!("#{channel}"), message.clone()) {
+                                        hold_message(&outbox, &tx, data_builder, &channel, message, &err).await;
+                                    }
+                                }
+                                Outgoing::Whisper { user, message } if taken && send => {
+                                    debug!("Sending recovered whisper to {user}");
+
+                                    spawn_whisper(outbox.clone(), tx.clone(), config.twitch.token.clone(), user, message);
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    TwitchAction::ChannelInfo(command) => {
+                        debug!("Changing channel information with {command:?}");
+
+                        let tx = tx.clone();
+                        let token = config.twitch.token.clone();
+                        let channel = config.twitch.channel.clone();
+
+                        tokio::task::spawn(async move {
+                            let message = update_channel_info(token.as_deref(), &channel, &command)
+                                .await
+                                .unwrap_or_else(|err| err.to_string());
+
+                            tx.send(DataBuilder::default().system(message)).await.unwrap();
+                        });
                     }
-                    TwitchAction::ClearMessages => {
-                        client.send(Command::Raw("CLEARCHAT".to_string(), vec![])).unwrap();
+                    TwitchAction::Poll(command) => {
+                        debug!("Running poll command {command:?}");
+
+                        let tx = tx.clone();
+                        let token = config.twitch.token.clone();
+                        let channel = config.twitch.channel.clone();
+
+                        // Progress is shown from EventSub, so only failures need to be told
+                        tokio::task::spawn(async move {
+                            if let Err(err) = run_poll_command(token.as_deref(), &channel, &command).await {
+                                tx.send(DataBuilder::default().system(err.to_string())).await.unwrap();
+                            }
+                        });
+                    }
+                    TwitchAction::Ignore(user, ignored) => {
+                        debug!("Setting {user} as blocked: {ignored}");
+
+                        let tx = tx.clone();
+                        let token = config.twitch.token.clone();
+
+                        tokio::task::spawn(async move {
+                            if let Err(err) = set_blocked(token.as_deref(), &user, ignored).await {
+                                tx.send(DataBuilder::default().system(err.to_string())).await.unwrap();
+                            }
+                        });
                     }
                 }
             }
@@ -134,27 +355,145 @@ pub async fn twitch_irc(
                         if !connected {
                             tx.send(data_builder.system("Reconnect succcessful.".to_string())).await.unwrap();
                             connected = true;
+
+                            set_connection_state(ConnectionState::Connected);
                         }
 
-                        if let Some(b) = handle_message_command(message, tx.clone(), data_builder, config.frontend.badges, room_state_startup, enable_emotes).await {
+                        if let Some(step) = rejoins.observe(&message, &config.twitch.username, &joined, Instant::now()) {
+                            rejoin(&connection, &tx, data_builder, &mut joined, step).await;
+                        }
+
+                        if let Some(b) = handle_message_command(message, tx.clone(), data_builder, &config, &mut chatters, room_state_startup, enable_emotes).await {
                             room_state_startup = b;
                         }
                     }
                     Err(err) => {
                         connected = false;
 
+                        set_connection_state(ConnectionState::Reconnecting);
+
                         debug!("Twitch connection error encountered: {}, attempting to reconnect.", err);
 
-                        (client, stream) = client_stream_reconnect(err, tx.clone(), data_builder, &config).await;
+                        (connection, stream) = client_stream_reconnect(err, tx.clone(), data_builder, &config).await;
+
+                        // Only the active channel is joined when connecting, so rejoin the other tabs.
+                        for channel in joined.iter().filter(|c| **c != config.twitch.channel) {
+                            if let Err(err) = connection.send_join(format!("#{channel}")) {
+                                tx.send(data_builder.twitch(err.to_string())).await.unwrap();
+                            }
+                        }
+
+                        for channel in &joined {
+                            rejoins.joining(channel, Instant::now());
+                        }
+
+                        let held = outbox.lock().unwrap().take_messages();
+
+                        for item in held {
+                            if let Outgoing::Message { channel, message } = item {
+                                if let Err(err) = connection.send_privmsg(format!("#{channel}"), message.clone()) {
+                                    hold_message(&outbox, &tx, data_builder, &channel, message, &err).await;
+                                }
+                            }
+                        }
 
                     }
                 }
             }
+            _ = rejoin_interval.tick() => {
+                for step in rejoins.due(Instant::now()) {
+                    rejoin(&connection, &tx, data_builder, &mut joined, step).await;
+                }
+            }
             else => {}
         };
     }
 }
 
+/// Join a channel again that Twitch dropped or did not confirm, or tell the user that it could not be joined.
+/// Channels that could not be joined are no longer counted as joined, so that switching to them joins them again.
+async fn rejoin(
+    connection: &Connection,
+    tx: &Sender<TwitchToTerminalAction>,
+    data_builder: DataBuilder<'_>,
+    joined: &mut Vec<String>,
+    step: RejoinStep,
+) {
+    match step {
+        RejoinStep::Join(channel) => {
+            debug!("Joining {channel} again, as Twitch did not confirm being in it");
+
+            if let Err(err) = connection.send_join(format!("#{channel}")) {
+                tx.send(data_builder.twitch(err.to_string())).await.unwrap();
+            }
+        }
+        RejoinStep::GiveUp(channel) => {
+            joined.retain(|c| !c.eq_ignore_ascii_case(&channel));
+
+            let target = format!("#{channel}");
+
+            tx.send(data_builder.in_channel(&target).system(format!(
+                "Unable to join {channel}, messages from it will not be shown. Switch to it again to retry."
+            )))
+            .await
+            .unwrap();
+        }
+    }
+}
+
+/// Keep a message that could not be sent, to send it again once the connection is back.
+async fn hold_message(
+    outbox: &Mutex<Outbox>,
+    tx: &Sender<TwitchToTerminalAction>,
+    data_builder: DataBuilder<'_>,
+    channel: &str,
+    message: String,
+    err: &irc::error::Error,
+) {
+    debug!("Unable to send message to {channel}, holding it until reconnected: {err}");
+
+    outbox.lock().unwrap().push(Outgoing::Message {
+        channel: channel.to_string(),
+        message,
+    });
+
+    tx.send(
+        data_builder
+            .system("Unable to send the message, it will be sent once reconnected.".to_string()),
+    )
+    .await
+    .unwrap();
+}
+
+/// Send a whisper through Helix without holding up IRC, keeping it in the outbox until it is sent.
+fn spawn_whisper(
+    outbox: Arc<Mutex<Outbox>>,
+    tx: Sender<TwitchToTerminalAction>,
+    token: Option<String>,
+    user: String,
+    message: String,
+) {
+    let item = Outgoing::Whisper {
+        user: user.clone(),
+        message: message.clone(),
+    };
+
+    outbox.lock().unwrap().push(item.clone());
+
+    tokio::task::spawn(async move {
+        let result = send_whisper(token.as_deref(), &user, &message).await;
+
+        // Failed whispers are not tried again, as Helix has already said why they failed
+        outbox.lock().unwrap().remove(&item);
+
+        if let Err(err) = result {
+            tx.send(DataBuilder::default().system(format!("Unable to whisper {user}: {err}")))
+                .await
+                .unwrap();
+        }
+    });
+}
+
 /// Emotes comming from twitch arrive in the `emote` tag.
 /// They have the format `<emote-id1>:<start>-<end>,.../<emote-id2>:...`
 /// This functions returns a list of emote name and id,
@@ -183,7 +522,8 @@ async fn handle_message_command(
     message: Message,
     tx: Sender<TwitchToTerminalAction>,
     data_builder: DataBuilder<'_>,
-    badges: bool,
+    config: &CompleteConfig,
+    chatters: &mut Chatters,
     room_state_startup: bool,
     enable_emotes: bool,
 ) -> Option<bool> {
@@ -198,7 +538,7 @@ async fn handle_message_command(
     }
 
     match message.command {
-        Command::PRIVMSG(ref _target, ref msg) => {
+        Command::PRIVMSG(ref target, ref msg) => {
             // Detects if the message contains an IRC CTCP Action, and return the message content.
             // WARNING: Emote parsing needs to be done *after* the message has been extracted from the action,
             // but *before* problematic unicode characters have been removed from it.
@@ -220,65 +560,208 @@ async fn handle_message_command(
                 .buffer_unordered(10)
                 .collect::<Vec<Result<(String, (String, bool))>>>();
 
-            // lowercase username from message
-            let mut name = message.source_nickname().unwrap().to_string();
+            let bits = tags.get("bits").and_then(|bits| bits.parse::<u64>().ok());
+
+            // Cheers are only images when the message actually cheered, as words like `Cheer100` can be sent without bits.
+            let cheers = if enable_emotes && config.frontend.cheermotes && bits.is_some() {
+                msg.split(' ')
+                    .filter_map(|word| {
+                        parse_cheer(word).map(|(prefix, amount)| {
+                            (word.to_string(), prefix.to_string(), cheer_tier(amount))
+                        })
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+
+            let cheermotes =
+                futures::stream::iter(cheers.into_iter().map(|(word, prefix, tier)| async move {
+                    let filename = get_cheermote(&prefix, tier).await?;
+                    Ok((word, (filename, false)))
+                }))
+                .buffer_unordered(10)
+                .collect::<Vec<Result<(String, (String, bool))>>>();
 
-            retrieve_user_badges(&mut name, &message, badges);
+            // Prefer the display name, falling back to the lowercase username from the message
+            let name = tags
+                .get("display-name")
+                .filter(|name| !name.is_empty())
+                .map_or_else(
+                    || message.source_nickname().unwrap().to_string(),
+                    |&name| name.to_string(),
+                );
+
+            let badges = if config.frontend.badges == BadgeStyle::Hidden {
+                vec![]
+            } else {
+                tags.get("badges")
+                    .map(|&badges| Badge::parse_tag(badges))
+                    .unwrap_or_default()
+            };
 
             // Remove invalid unicode characters from the message.
             let cleaned_message = clean_message(msg);
 
             let message_id = tags.get("id").map(|&s| s.to_string());
             let user_id = tags.get("user-id").map(|&s| s.to_string());
+            // Users that never picked a color have an empty tag
+            let user_color = tags
+                .get("color")
+                .and_then(|color| color.parse::<Color>().ok());
 
             debug!("Message received from twitch: {name} - {cleaned_message:?}");
 
-            let emotes = emotes.await.into_iter().flatten().collect();
+            let emotes = emotes
+                .await
+                .into_iter()
+                .chain(cheermotes.await)
+                .flatten()
+                .collect();
 
-            tx.send(DataBuilder::user(
+            tx.send(data_builder.in_channel(target).user(
                 name,
                 user_id,
                 cleaned_message,
                 emotes,
                 message_id,
                 highlight,
+                badges,
+                Reply::from_tags(&tags),
+                bits,
+                user_color,
             ))
             .await
             .unwrap();
         }
-        Command::NOTICE(ref _target, ref msg) => {
-            tx.send(data_builder.twitch(msg.to_string())).await.unwrap();
-        }
-        Command::JOIN(ref channel, _, _) => {
-            tx.send(data_builder.twitch(format!("Joined {}", *channel)))
+        Command::NOTICE(ref target, ref msg) => {
+            tx.send(data_builder.in_channel(target).twitch(msg.to_string()))
                 .await
                 .unwrap();
+
+            let msg_id = tags.get("msg-id").map(|&s| s.to_string());
+
+            tx.send(TwitchToTerminalAction::Notice(Notice::new(
+                target,
+                msg_id,
+                msg.to_string(),
+            )))
+            .await
+            .unwrap();
         }
-        Command::Raw(ref cmd, ref _items) => {
+        Command::JOIN(ref channel, _, _) | Command::PART(ref channel, _) => {
+            let joined = matches!(message.command, Command::JOIN(..));
+            let user = message.source_nickname().unwrap_or_default();
+
+            if user.eq_ignore_ascii_case(&config.twitch.username) {
+                if !joined {
+                    chatters.remove_channel(channel);
+                }
+
+                if joined {
+                    tx.send(
+                        data_builder
+                            .in_channel(channel)
+                            .twitch(format!("Joined {}", *channel)),
+                    )
+                    .await
+                    .unwrap();
+                }
+            } else {
+                // Other users only show up with the membership capability
+                let membership = Membership::new(channel, user.to_string(), joined);
+
+                match chatters.update(&membership, config.twitch.membership_chatter_limit) {
+                    ActivityState::Shown => {
+                        tx.send(TwitchToTerminalAction::Membership(membership))
+                            .await
+                            .unwrap();
+                    }
+                    ActivityState::Disabled(count) => {
+                        tx.send(data_builder.in_channel(channel).system(format!(
+                            "{count} chatters are in {channel}, join/part activity will no longer be shown."
+                        )))
+                        .await
+                        .unwrap();
+                    }
+                    ActivityState::Hidden => {}
+                }
+            }
+        }
+        Command::Raw(ref cmd, ref items) => {
+            // The first parameter of these commands is the channel they were sent in.
+            let data_builder = items
+                .first()
+                .map_or(data_builder, |target| data_builder.in_channel(target));
+            let channel = data_builder.channel.map(ToString::to_string);
+
             match cmd.as_ref() {
                 // https://dev.twitch.tv/docs/irc/tags/#roomstate-tags
                 "ROOMSTATE" => {
+                    if let Some(channel) = &channel {
+                        update_room_modes(channel, &tags);
+                    }
+
                     // Only display roomstate on startup, since twitch
                     // sends a NOTICE whenever roomstate changes.
                     if !room_state_startup {
-                        handle_roomstate(&tx, &tags).await;
+                        handle_roomstate(&tx, data_builder, &tags).await;
                     }
 
                     return Some(true);
                 }
                 // https://dev.twitch.tv/docs/irc/tags/#usernotice-tags
                 "USERNOTICE" => {
-                    if let Some(value) = tags.get("system-msg") {
+                    // Gift subs are combined by the terminal, rather than shown one by one
+                    let gift = (config.frontend.gift_sub_window > 0)
+                        .then(|| GiftSub::from_tags(channel.clone(), &tags))
+                        .flatten();
+
+                    if let Some(gift) = gift {
+                        tx.send(TwitchToTerminalAction::GiftSub(gift))
+                            .await
+                            .unwrap();
+                    } else if let Some(subscription) = Subscription::from_tags(
+                        channel.clone(),
+                        &tags,
+                        items.get(1).map(String::as_str),
+                    ) {
+                        tx.send(TwitchToTerminalAction::Subscription(subscription))
+                            .await
+                            .unwrap();
+                    } else if let Some(value) = tags.get("system-msg") {
                         tx.send(data_builder.twitch((*value).to_string()))
                             .await
                             .unwrap();
                     }
+
+                    // Raids are also kept in a history, so that raiders can be thanked later
+                    if tags.get("msg-id") == Some(&"raid") {
+                        let login = tags.get("msg-param-login").copied().unwrap_or_default();
+                        let raider = tags
+                            .get("msg-param-displayName")
+                            .copied()
+                            .unwrap_or(login)
+                            .to_string();
+                        let viewers = tags
+                            .get("msg-param-viewerCount")
+                            .and_then(|count| count.parse().ok());
+
+                        tx.send(TwitchToTerminalAction::Raid(Raid::new(
+                            raider,
+                            channel.as_deref().unwrap_or_default(),
+                            viewers,
+                            login.eq_ignore_ascii_case(&config.twitch.username),
+                        )))
+                        .await
+                        .unwrap();
+                    }
                 }
                 // https://dev.twitch.tv/docs/irc/tags/#clearchat-tags
                 "CLEARCHAT" => {
                     let user_id = tags.get("target-user-id").map(|&s| s.to_string());
 
-                    tx.send(TwitchToTerminalAction::ClearChat(user_id.clone()))
+                    tx.send(TwitchToTerminalAction::ClearChat(channel, user_id.clone()))
                         .await
                         .unwrap();
 
@@ -309,12 +792,32 @@ async fn handle_message_command(
                             .unwrap();
                     }
                 }
+                // https://dev.twitch.tv/docs/chat/irc/#receiving-whispers
+                "WHISPER" => {
+                    let name = tags
+                        .get("display-name")
+                        .copied()
+                        .or_else(|| message.source_nickname());
+
+                    if let (Some(name), Some(msg)) = (name, items.get(1)) {
+                        tx.send(TwitchToTerminalAction::Whisper(Whisper::new(
+                            name.to_string(),
+                            clean_message(msg),
+                            false,
+                        )))
+                        .await
+                        .unwrap();
+                    }
+                }
                 // https://dev.twitch.tv/docs/irc/tags/#clearmsg-tags
                 "CLEARMSG" => {
                     if let Some(id) = tags.get("target-msg-id") {
-                        tx.send(TwitchToTerminalAction::DeleteMessage((*id).to_string()))
-                            .await
-                            .unwrap();
+                        tx.send(TwitchToTerminalAction::DeleteMessage(
+                            channel,
+                            (*id).to_string(),
+                        ))
+                        .await
+                        .unwrap();
                     }
                 }
                 _ => (),
@@ -328,6 +831,7 @@ async fn handle_message_command(
 
 pub async fn handle_roomstate<S: BuildHasher>(
     tx: &Sender<TwitchToTerminalAction>,
+    data_builder: DataBuilder<'_>,
     tags: &HashMap<&str, &str, S>,
 ) {
     let mut room_state = String::new();
@@ -361,13 +865,17 @@ pub async fn handle_roomstate<S: BuildHasher>(
 
     let message_id = tags.get("target-msg-id").map(|&s| s.to_string());
 
-    tx.send(DataBuilder::user(
+    tx.send(data_builder.user(
         String::from("Info"),
         None,
         room_state,
         DownloadedEmotes::default(),
         message_id,
         false,
+        vec![],
+        None,
+        None,
+        None,
     ))
     .await
     .unwrap();
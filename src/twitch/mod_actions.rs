@@ -0,0 +1,240 @@
+use chrono::{DateTime, Local};
+use color_eyre::Result;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::utils::time::format_duration;
+
+#[derive(Deserialize, Default)]
+struct Target {
+    user_name: String,
+    reason: Option<String>,
+    /// When a timeout ends.
+    expires_at: Option<String>,
+    /// The deleted message.
+    message_body: Option<String>,
+    /// If an unban request was approved, rather than denied.
+    is_approved: Option<bool>,
+}
+
+/// The event sent when a moderator takes an action in a channel.
+/// <https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/#channelmoderate>
+#[derive(Deserialize)]
+struct ModerateEvent {
+    broadcaster_user_login: String,
+    moderator_user_name: String,
+    action: String,
+    ban: Option<Target>,
+    unban: Option<Target>,
+    timeout: Option<Target>,
+    untimeout: Option<Target>,
+    delete: Option<Target>,
+    unban_request: Option<Target>,
+}
+
+/// The event sent when a banned user asks to be unbanned.
+/// <https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/#channelunban_requestcreate>
+#[derive(Deserialize)]
+struct UnbanRequestEvent {
+    broadcaster_user_login: String,
+    user_name: String,
+    text: String,
+}
+
+/// Something that happened in the moderation of a channel, such as a ban or a deleted message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModAction {
+    pub time_received: DateTime<Local>,
+    pub channel: String,
+    /// The moderator who took the action, if it was taken by one.
+    pub moderator: Option<String>,
+    /// What happened, such as `banned human: spam`.
+    pub description: String,
+    /// If a user was banned or timed out.
+    pub removal: bool,
+}
+
+/// Add what a moderator gave as the reason, if anything.
+fn with_reason(description: String, reason: Option<&str>) -> String {
+    match reason.filter(|r| !r.is_empty()) {
+        Some(reason) => format!("{description}: {reason}"),
+        None => description,
+    }
+}
+
+impl ModAction {
+    pub fn from_moderate_event(event: Value, now: DateTime<Local>) -> Result<Self> {
+        let event = serde_json::from_value::<ModerateEvent>(event)?;
+
+        let removal = matches!(event.action.as_str(), "ban" | "timeout");
+
+        let description = match event.action.as_str() {
+            "ban" => {
+                let target = event.ban.unwrap_or_default();
+
+                with_reason(
+                    format!("banned {}", target.user_name),
+                    target.reason.as_deref(),
+                )
+            }
+            "timeout" => {
+                let target = event.timeout.unwrap_or_default();
+
+                let duration = target
+                    .expires_at
+                    .as_deref()
+                    .and_then(|expires_at| DateTime::parse_from_rfc3339(expires_at).ok())
+                    .map(|expires_at| {
+                        format!(
+                            " for {}",
+                            format_duration(expires_at.with_timezone(&Local) - now)
+                        )
+                    })
+                    .unwrap_or_default();
+
+                with_reason(
+                    format!("timed out {}{duration}", target.user_name),
+                    target.reason.as_deref(),
+                )
+            }
+            "unban" => format!("unbanned {}", event.unban.unwrap_or_default().user_name),
+            "untimeout" => format!(
+                "removed the timeout of {}",
+                event.untimeout.unwrap_or_default().user_name
+            ),
+            "delete" => {
+                let target = event.delete.unwrap_or_default();
+
+                with_reason(
+                    format!("deleted a message of {}", target.user_name),
+                    target.message_body.as_deref(),
+                )
+            }
+            "clear" => "cleared chat".to_string(),
+            "approve_unban_request" | "deny_unban_request" => {
+                let target = event.unban_request.unwrap_or_default();
+
+                format!(
+                    "{} the unban request of {}",
+                    if target
+                        .is_approved
+                        .unwrap_or_else(|| event.action.starts_with("approve"))
+                    {
+                        "approved"
+                    } else {
+                        "denied"
+                    },
+                    target.user_name
+                )
+            }
+            // Chat settings and other actions are named well enough to be shown as they are
+            action => action.replace('_', " "),
+        };
+
+        Ok(Self {
+            time_received: now,
+            channel: event.broadcaster_user_login,
+            moderator: Some(event.moderator_user_name),
+            description,
+            removal,
+        })
+    }
+
+    pub fn from_unban_request_event(event: Value, now: DateTime<Local>) -> Result<Self> {
+        let event = serde_json::from_value::<UnbanRequestEvent>(event)?;
+
+        Ok(Self {
+            time_received: now,
+            channel: event.broadcaster_user_login,
+            moderator: None,
+            description: with_reason(
+                format!("{} asked to be unbanned", event.user_name),
+                Some(&event.text),
+            ),
+            removal: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+
+    fn moderate(action: &str, target: &Value) -> ModAction {
+        let mut event = json!({
+            "broadcaster_user_login": "xithrius",
+            "moderator_user_name": "Moderator",
+            "action": action,
+        });
+
+        // Both kinds of unban request actions share the same field
+        let field = action
+            .strip_prefix("approve_")
+            .or_else(|| action.strip_prefix("deny_"))
+            .unwrap_or(action);
+
+        event[field] = target.clone();
+
+        ModAction::from_moderate_event(event, Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_moderate_event_descriptions() {
+        let ban = moderate("ban", &json!({ "user_name": "Human", "reason": "spam" }));
+
+        assert_eq!(ban.channel, "xithrius");
+        assert_eq!(ban.moderator.as_deref(), Some("Moderator"));
+        assert_eq!(ban.description, "banned Human: spam");
+        assert!(ban.removal);
+
+        let expires_at = Local
+            .with_ymd_and_hms(2024, 1, 1, 12, 10, 0)
+            .unwrap()
+            .to_rfc3339();
+
+        assert_eq!(
+            moderate(
+                "timeout",
+                &json!({ "user_name": "Human", "reason": "", "expires_at": expires_at })
+            )
+            .description,
+            "timed out Human for 10m"
+        );
+        assert_eq!(
+            moderate(
+                "delete",
+                &json!({ "user_name": "Human", "message_body": "hello" })
+            )
+            .description,
+            "deleted a message of Human: hello"
+        );
+        assert_eq!(
+            moderate("deny_unban_request", &json!({ "user_name": "Human" })).description,
+            "denied the unban request of Human"
+        );
+        assert_eq!(
+            moderate("emoteonlyoff", &Value::Null).description,
+            "emoteonlyoff"
+        );
+    }
+
+    #[test]
+    fn test_unban_request_event() {
+        let request = ModAction::from_unban_request_event(
+            json!({
+                "broadcaster_user_login": "xithrius",
+                "user_name": "Human",
+                "text": "sorry",
+            }),
+            Local::now(),
+        )
+        .unwrap();
+
+        assert_eq!(request.moderator, None);
+        assert_eq!(request.description, "Human asked to be unbanned: sorry");
+    }
+}
@@ -0,0 +1,534 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::TimeDelta;
+use color_eyre::{
+    Result,
+    eyre::{Error, bail, eyre},
+};
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+use crate::{
+    twitch::{
+        budget::{Priority, send_helix},
+        helix::{ensure_requestable, track_request},
+        oauth::{get_channel_id, get_twitch_client, get_twitch_client_id},
+    },
+    utils::time::{format_duration, parse_duration},
+};
+
+/// Moderation commands that go through Helix, as chat commands over IRC are no longer supported.
+/// <https://dev.twitch.tv/docs/api/reference/#ban-user>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModerationCommand {
+    Ban {
+        user: String,
+        reason: Option<String>,
+    },
+    Unban {
+        user: String,
+    },
+    Timeout {
+        user: String,
+        seconds: u32,
+        reason: Option<String>,
+    },
+    /// Delete the message with an ID.
+    Delete {
+        message_id: String,
+    },
+    /// Delete every message in the channel.
+    Clear,
+    Mod {
+        user: String,
+    },
+    Vip {
+        user: String,
+    },
+}
+
+/// The commands that take a username as their first argument.
+pub const USER_COMMANDS: [&str; 5] = ["ban", "unban", "timeout", "mod", "vip"];
+
+/// The longest timeout Twitch allows, being two weeks.
+const MAXIMUM_TIMEOUT: u32 = 1_209_600;
+/// How long a timeout lasts if no duration is given, which is the same as in Twitch's own chat.
+const DEFAULT_TIMEOUT: u32 = 600;
+
+/// Why Helix refused a request.
+#[derive(Deserialize)]
+pub struct HelixError {
+    pub message: String,
+}
+
+impl ModerationCommand {
+    /// The scope the token needs for this command.
+    pub const fn scope(&self) -> &'static str {
+        match self {
+            Self::Ban { .. } | Self::Unban { .. } | Self::Timeout { .. } => {
+                "moderator:manage:banned_users"
+            }
+            Self::Delete { .. } | Self::Clear => "moderator:manage:chat_messages",
+            Self::Mod { .. } => "channel:manage:moderators",
+            Self::Vip { .. } => "channel:manage:vips",
+        }
+    }
+
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Ban { .. } => "ban",
+            Self::Unban { .. } => "unban",
+            Self::Timeout { .. } => "timeout",
+            Self::Delete { .. } => "delete",
+            Self::Clear => "clear",
+            Self::Mod { .. } => "mod",
+            Self::Vip { .. } => "vip",
+        }
+    }
+
+    /// What the command would do, such as `timeout human for 10m (spam)`, for dry runs.
+    pub fn describe(&self) -> String {
+        let with_reason = |description: String, reason: Option<&str>| match reason {
+            Some(reason) => format!("{description} ({reason})"),
+            None => description,
+        };
+
+        match self {
+            Self::Ban { user, reason } => with_reason(format!("ban {user}"), reason.as_deref()),
+            Self::Timeout {
+                user,
+                seconds,
+                reason,
+            } => with_reason(
+                format!(
+                    "timeout {user} for {}",
+                    format_duration(TimeDelta::seconds(i64::from(*seconds)))
+                ),
+                reason.as_deref(),
+            ),
+            Self::Delete { message_id } => format!("delete message {message_id}"),
+            Self::Clear => "clear chat".to_string(),
+            Self::Unban { user } | Self::Mod { user } | Self::Vip { user } => {
+                format!("{} {user}", self.name())
+            }
+        }
+    }
+
+    /// What to tell the user once the command went through, if it is not visible in chat already.
+    pub fn success_message(&self) -> Option<String> {
+        match self {
+            Self::Unban { user } => Some(format!("{user} was unbanned.")),
+            Self::Mod { user } => Some(format!("{user} is now a moderator.")),
+            Self::Vip { user } => Some(format!("{user} is now a VIP.")),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a moderation command, returning `None` if the message is not one,
+/// or an error with how to use the command if its arguments are wrong.
+pub fn parse_moderation_command(message: &str) -> Option<Result<ModerationCommand>> {
+    let message = message.strip_prefix('/')?;
+    let (command, arguments) = message.split_once(' ').unwrap_or((message, ""));
+
+    let mut arguments = arguments.split_whitespace();
+    let mut user = || {
+        arguments
+            .next()
+            .map(|u| u.trim_start_matches('@').to_string())
+    };
+
+    let parsed = match command {
+        "ban" => user().map(|user| ModerationCommand::Ban {
+            user,
+            reason: rest(message, 2),
+        }),
+        "unban" => user().map(|user| ModerationCommand::Unban { user }),
+        "timeout" => match user() {
+            Some(user) => {
+                // Anything starting with a number is meant as the duration, rather than the reason
+                let duration = message
+                    .split_whitespace()
+                    .nth(2)
+                    .filter(|d| d.starts_with(|c: char| c.is_ascii_digit()));
+
+                let seconds = match duration.map(parse_timeout).transpose() {
+                    Ok(seconds) => seconds,
+                    Err(err) => return Some(Err(err)),
+                };
+
+                Some(ModerationCommand::Timeout {
+                    user,
+                    seconds: seconds.unwrap_or(DEFAULT_TIMEOUT),
+                    reason: rest(message, if duration.is_some() { 3 } else { 2 }),
+                })
+            }
+            None => None,
+        },
+        "delete" => message
+            .split_whitespace()
+            .nth(1)
+            .map(|id| ModerationCommand::Delete {
+                message_id: id.to_string(),
+            }),
+        "clear" => Some(ModerationCommand::Clear),
+        "mod" => user().map(|user| ModerationCommand::Mod { user }),
+        "vip" => user().map(|user| ModerationCommand::Vip { user }),
+        _ => return None,
+    };
+
+    Some(parsed.ok_or_else(|| {
+        let usage = match command {
+            "ban" => "/ban <user> [reason]",
+            "timeout" => "/timeout <user> [duration, such as 10m or 1h30m] [reason]",
+            "delete" => "/delete <message id>",
+            _ => return eyre!("Usage: /{command} <user>"),
+        };
+
+        eyre!("Usage: {usage}")
+    }))
+}
+
+/// The seconds of a timeout duration, which Twitch only allows from a second up to two weeks.
+fn parse_timeout(duration: &str) -> Result<u32> {
+    let seconds = parse_duration(duration)?.as_secs();
+
+    match u32::try_from(seconds) {
+        Ok(0) => bail!("Timeouts need to last at least a second."),
+        Ok(seconds) if seconds <= MAXIMUM_TIMEOUT => Ok(seconds),
+        _ => bail!("Timeouts can last at most two weeks, being 2w or {MAXIMUM_TIMEOUT} seconds."),
+    }
+}
+
+/// An action on the author of a selected message, which are chosen from with a count before `b` in the order they are configured.
+#[derive(SerializeDisplay, DeserializeFromStr, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LadderStep {
+    Timeout(u32),
+    Ban,
+}
+
+impl FromStr for LadderStep {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("ban") {
+            Ok(Self::Ban)
+        } else {
+            parse_timeout(s).map(Self::Timeout)
+        }
+    }
+}
+
+impl Display for LadderStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout(seconds) => write!(
+                f,
+                "{}",
+                format_duration(TimeDelta::seconds(i64::from(*seconds))).replace(' ', "")
+            ),
+            Self::Ban => write!(f, "ban"),
+        }
+    }
+}
+
+impl LadderStep {
+    /// The command for this step, with the reason filled in from a template.
+    /// `{user}`, `{action}`, and `{message}` in the template are replaced with
+    /// the author, this step, and the selected message.
+    pub fn command(self, author: &str, payload: &str, template: &str) -> ModerationCommand {
+        let reason = (!template.is_empty()).then(|| {
+            template
+                .replace("{user}", author)
+                .replace("{action}", &self.to_string())
+                .replace("{message}", payload)
+        });
+
+        let user = author.to_lowercase();
+
+        match self {
+            Self::Timeout(seconds) => ModerationCommand::Timeout {
+                user,
+                seconds,
+                reason,
+            },
+            Self::Ban => ModerationCommand::Ban { user, reason },
+        }
+    }
+}
+
+/// Everything in a message after a number of words, if there is anything.
+fn rest(message: &str, words: usize) -> Option<String> {
+    let rest = message
+        .split_whitespace()
+        .skip(words)
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    (!rest.is_empty()).then_some(rest)
+}
+
+/// Run a moderation command in a channel, as the user the token belongs to.
+pub async fn moderate(
+    token: Option<&str>,
+    channel: &str,
+    command: &ModerationCommand,
+) -> Result<()> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let client_id = get_twitch_client_id(None).await?;
+
+    // Missing scopes are the most common reason for these to fail, so say so clearly
+    if !client_id.scopes.iter().any(|s| s == command.scope()) {
+        bail!(
+            "/{} requires the `{}` scope in your token.",
+            command.name(),
+            command.scope()
+        );
+    }
+
+    let moderator_id = &client_id.user_id;
+    let broadcaster_id = track_request(get_channel_id(&client, channel, Priority::User).await)?;
+
+    let user_id = |user: &str| {
+        let client = client.clone();
+        let user = user.to_string();
+
+        async move { track_request(get_channel_id(&client, &user, Priority::User).await) }
+    };
+
+    let moderation = format!("broadcaster_id={broadcaster_id}&moderator_id={moderator_id}");
+
+    let (endpoint, request) = match command {
+        ModerationCommand::Ban { user, reason } => {
+            let user_id = user_id(user).await?;
+
+            ban(&client, &moderation, user_id, None, reason.as_deref())
+        }
+        ModerationCommand::Timeout {
+            user,
+            seconds,
+            reason,
+        } => {
+            let user_id = user_id(user).await?;
+
+            ban(
+                &client,
+                &moderation,
+                user_id,
+                Some(*seconds),
+                reason.as_deref(),
+            )
+        }
+        ModerationCommand::Unban { user } => {
+            let user_id = user_id(user).await?;
+
+            (
+                "helix/moderation/bans",
+                client.delete(format!(
+                    "https://api.twitch.tv/helix/moderation/bans?{moderation}&user_id={user_id}"
+                )),
+            )
+        }
+        ModerationCommand::Delete { message_id } => (
+            "helix/moderation/chat",
+            client.delete(format!(
+                "https://api.twitch.tv/helix/moderation/chat?{moderation}&message_id={message_id}"
+            )),
+        ),
+        ModerationCommand::Clear => (
+            "helix/moderation/chat",
+            client.delete(format!(
+                "https://api.twitch.tv/helix/moderation/chat?{moderation}"
+            )),
+        ),
+        ModerationCommand::Mod { user } => {
+            let user_id = user_id(user).await?;
+
+            (
+                "helix/moderation/moderators",
+                client.post(format!(
+                    "https://api.twitch.tv/helix/moderation/moderators?broadcaster_id={broadcaster_id}&user_id={user_id}"
+                )),
+            )
+        }
+        ModerationCommand::Vip { user } => {
+            let user_id = user_id(user).await?;
+
+            (
+                "helix/channels/vips",
+                client.post(format!(
+                    "https://api.twitch.tv/helix/channels/vips?broadcaster_id={broadcaster_id}&user_id={user_id}"
+                )),
+            )
+        }
+    };
+
+    let response = track_request(send_helix(endpoint, Priority::User, request).await)?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+
+        // Helix explains why a command was refused, such as the user already being banned
+        let message = response
+            .json::<HelixError>()
+            .await
+            .map_or_else(|_| status.to_string(), |err| err.message);
+
+        bail!("/{} failed: {message}", command.name());
+    }
+
+    Ok(())
+}
+
+fn ban(
+    client: &Client,
+    moderation: &str,
+    user_id: i32,
+    duration: Option<u32>,
+    reason: Option<&str>,
+) -> (&'static str, RequestBuilder) {
+    let mut data = json!({ "user_id": user_id.to_string() });
+
+    if let Some(duration) = duration {
+        data["duration"] = Value::from(duration);
+    }
+
+    if let Some(reason) = reason {
+        data["reason"] = Value::from(reason);
+    }
+
+    (
+        "helix/moderation/bans",
+        client
+            .post(format!(
+                "https://api.twitch.tv/helix/moderation/bans?{moderation}"
+            ))
+            .json(&json!({ "data": data })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(message: &str) -> Option<ModerationCommand> {
+        parse_moderation_command(message).map(Result::unwrap)
+    }
+
+    #[test]
+    fn test_parse_moderation_command() {
+        assert_eq!(
+            parse("/ban @human being rude"),
+            Some(ModerationCommand::Ban {
+                user: "human".to_string(),
+                reason: Some("being rude".to_string()),
+            })
+        );
+        assert_eq!(
+            parse("/timeout human 60"),
+            Some(ModerationCommand::Timeout {
+                user: "human".to_string(),
+                seconds: 60,
+                reason: None,
+            })
+        );
+        assert_eq!(
+            parse("/timeout human spam"),
+            Some(ModerationCommand::Timeout {
+                user: "human".to_string(),
+                seconds: 600,
+                reason: Some("spam".to_string()),
+            })
+        );
+        assert_eq!(
+            parse("/timeout @human 1h30m spam"),
+            Some(ModerationCommand::Timeout {
+                user: "human".to_string(),
+                seconds: 5400,
+                reason: Some("spam".to_string()),
+            })
+        );
+        assert_eq!(parse("/clear"), Some(ModerationCommand::Clear));
+        assert_eq!(parse("/me waves"), None);
+        assert_eq!(parse("hello"), None);
+    }
+
+    #[test]
+    fn test_parse_moderation_command_usage() {
+        assert_eq!(
+            parse_moderation_command("/ban")
+                .unwrap()
+                .unwrap_err()
+                .to_string(),
+            "Usage: /ban <user> [reason]"
+        );
+        assert_eq!(
+            parse_moderation_command("/vip")
+                .unwrap()
+                .unwrap_err()
+                .to_string(),
+            "Usage: /vip <user>"
+        );
+        assert_eq!(
+            parse_moderation_command("/timeout human 10x")
+                .unwrap()
+                .unwrap_err()
+                .to_string(),
+            "Duration `10x` has an unknown unit `x`, use s, m, h, d, or w."
+        );
+        assert_eq!(
+            parse_moderation_command("/timeout human 3w")
+                .unwrap()
+                .unwrap_err()
+                .to_string(),
+            "Timeouts can last at most two weeks, being 2w or 1209600 seconds."
+        );
+        assert!(
+            parse_moderation_command("/timeout human 0")
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_describe_moderation_command() {
+        assert_eq!(
+            parse("/timeout @human 1h30m spam").unwrap().describe(),
+            "timeout human for 1h 30m (spam)"
+        );
+        assert_eq!(parse("/ban human").unwrap().describe(), "ban human");
+        assert_eq!(parse("/vip human").unwrap().describe(), "vip human");
+        assert_eq!(parse("/clear").unwrap().describe(), "clear chat");
+    }
+
+    #[test]
+    fn test_ladder_step() {
+        assert_eq!(
+            "10m".parse::<LadderStep>().unwrap(),
+            LadderStep::Timeout(600)
+        );
+        assert_eq!("Ban".parse::<LadderStep>().unwrap(), LadderStep::Ban);
+        assert!("3w".parse::<LadderStep>().is_err());
+        assert_eq!(LadderStep::Timeout(5400).to_string(), "1h30m");
+
+        assert_eq!(
+            LadderStep::Timeout(60).command("Human", "spam", "{action} for: {message}"),
+            ModerationCommand::Timeout {
+                user: "human".to_string(),
+                seconds: 60,
+                reason: Some("1m for: spam".to_string()),
+            }
+        );
+        assert_eq!(
+            LadderStep::Ban.command("human", "spam", ""),
+            ModerationCommand::Ban {
+                user: "human".to_string(),
+                reason: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Local};
+
+use crate::{handlers::config::Matching, utils::matching::matches_any};
+
+/// A NOTICE sent by the Twitch IRC server.
+/// <https://dev.twitch.tv/docs/irc/msg-id/>
+#[derive(Debug, Clone)]
+pub struct Notice {
+    pub time_received: DateTime<Local>,
+    /// The channel the notice was sent to, if it was not a global notice.
+    pub channel: Option<String>,
+    /// The `msg-id` tag, which identifies what kind of notice this is.
+    pub msg_id: Option<String>,
+    pub message: String,
+}
+
+impl Notice {
+    pub fn new(channel: &str, msg_id: Option<String>, message: String) -> Self {
+        let channel = channel.strip_prefix('#').map(ToString::to_string);
+
+        Self {
+            time_received: Local::now(),
+            channel,
+            msg_id,
+            message,
+        }
+    }
+
+    pub fn explanation(&self) -> &'static str {
+        self.msg_id
+            .as_deref()
+            .map_or("Notice sent by the server without a code.", explain_msg_id)
+    }
+
+    pub fn matches(&self, query: &str, matching: Matching) -> bool {
+        matches_any(
+            matching,
+            &[
+                &self.message,
+                self.msg_id.as_deref().unwrap_or_default(),
+                self.explanation(),
+            ],
+            query,
+        )
+    }
+}
+
+/// Human readable explanations of the most common `msg-id` codes.
+pub fn explain_msg_id(msg_id: &str) -> &'static str {
+    match msg_id {
+        "msg_banned" => "You are permanently banned from talking in this channel.",
+        "msg_timedout" => "You are timed out and cannot talk in this channel yet.",
+        "msg_channel_suspended" => "This channel has been suspended.",
+        "msg_suspended" => "Your account has been suspended, you cannot send messages.",
+        "msg_duplicate" => "Your message was identical to your previous one within 30 seconds.",
+        "msg_emoteonly" => "This channel is in emote-only mode, only emotes can be sent.",
+        "msg_followersonly" | "msg_followersonly_followed" | "msg_followersonly_zero" => {
+            "This channel is in followers-only mode."
+        }
+        "msg_subsonly" => "This channel is in subscribers-only mode.",
+        "msg_slowmode" => "This channel is in slow mode, wait before sending another message.",
+        "msg_r9k" => "This channel is in unique-chat mode, your message was not unique.",
+        "msg_ratelimit" => "You are sending messages too quickly.",
+        "msg_rejected" | "msg_rejected_mandatory" => {
+            "Your message was held or rejected by AutoMod or the channel's blocked terms."
+        }
+        "msg_requires_verified_phone_number" => {
+            "This channel requires a verified phone number to chat."
+        }
+        "msg_verified_email" => "This channel requires a verified email address to chat.",
+        "msg_channel_blocked" => "Your account is blocked from chatting in this channel.",
+        "slow_on" => "Slow mode has been enabled.",
+        "slow_off" => "Slow mode has been disabled.",
+        "emote_only_on" => "Emote-only mode has been enabled.",
+        "emote_only_off" => "Emote-only mode has been disabled.",
+        "followers_on" | "followers_on_zero" => "Followers-only mode has been enabled.",
+        "followers_off" => "Followers-only mode has been disabled.",
+        "subs_on" => "Subscribers-only mode has been enabled.",
+        "subs_off" => "Subscribers-only mode has been disabled.",
+        "r9k_on" => "Unique-chat mode has been enabled.",
+        "r9k_off" => "Unique-chat mode has been disabled.",
+        "host_on" | "host_target_went_offline" => "The channel is hosting another channel.",
+        "host_off" => "The channel has stopped hosting.",
+        "unrecognized_cmd" => "The command you sent is not recognised by Twitch.",
+        "no_permission" => "You do not have permission to perform this action.",
+        "tos_ban" => "The target account has been banned from Twitch.",
+        "already_banned" | "bad_ban_self" | "bad_ban_broadcaster" | "bad_ban_mod" => {
+            "The user could not be banned."
+        }
+        "bad_timeout_self" | "bad_timeout_broadcaster" | "bad_timeout_mod" => {
+            "The user could not be timed out."
+        }
+        "invalid_user" => "The user specified does not exist.",
+        "bad_auth" => "Authentication failed, verify your token.",
+        _ => "Unknown notice code.",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notice_channel_strip() {
+        let notice = Notice::new("#xithrius", None, "Hello".to_string());
+
+        assert_eq!(notice.channel, Some("xithrius".to_string()));
+    }
+
+    #[test]
+    fn test_notice_global() {
+        let notice = Notice::new("*", None, "Login authentication failed".to_string());
+
+        assert_eq!(notice.channel, None);
+    }
+
+    #[test]
+    fn test_notice_matches_explanation() {
+        let notice = Notice::new(
+            "#xithrius",
+            Some("slow_on".to_string()),
+            "This room is now in slow mode.".to_string(),
+        );
+
+        assert!(notice.matches("SLOW_ON", Matching::Substring));
+        assert!(notice.matches("enabled", Matching::Substring));
+        assert!(!notice.matches("subscribers", Matching::Substring));
+    }
+}
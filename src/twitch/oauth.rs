@@ -1,22 +1,110 @@
-use std::sync::OnceLock;
+use std::{
+    fs::{OpenOptions, read},
+    io::Write,
+    sync::{Mutex, OnceLock, RwLock},
+    time::Duration,
+};
 
-use color_eyre::{Result, eyre::ContextCompat};
+use chrono::{DateTime, Local, TimeDelta};
+use color_eyre::{
+    Result,
+    eyre::{ContextCompat, WrapErr, bail},
+};
+use log::{debug, info, warn};
 use reqwest::{
-    Client,
+    Client, StatusCode,
     header::{AUTHORIZATION, HeaderMap, HeaderValue},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use tokio::{sync::mpsc::Sender, time::interval};
 
-#[derive(Deserialize)]
-#[allow(dead_code)]
+use crate::{
+    handlers::{
+        config::CompleteConfig,
+        data::{DataBuilder, TwitchToTerminalAction},
+    },
+    twitch::{
+        budget::{Priority, send_helix},
+        cache::{CacheKind, cached},
+    },
+    utils::{
+        encryption::{decrypt, encrypt},
+        pathing::config_path,
+        time::format_duration,
+        traffic::send_tracked,
+    },
+};
+
+const VALIDATE_URL: &str = "https://id.twitch.tv/oauth2/validate";
+const TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
+/// The scopes asked for when logging in through the browser, which are every scope used by a feature,
+/// the same as the ones listed for a token in the default config.
+pub const DEVICE_CODE_SCOPES: &str = concat!(
+    "chat:read chat:edit channel:moderate user:read:follows user:read:emotes user:manage:whispers ",
+    "user:read:blocked_users user:manage:blocked_users ",
+    "channel:manage:redemptions channel:manage:polls channel:manage:predictions channel:manage:broadcast ",
+    "channel:manage:moderators channel:manage:vips ",
+    "moderator:manage:banned_users moderator:manage:chat_messages moderator:manage:automod ",
+    "moderator:read:unban_requests moderator:read:banned_users moderator:read:chat_messages ",
+    "moderator:read:chat_settings moderator:read:blocked_terms moderator:read:moderators ",
+    "moderator:read:vips moderator:read:shield_mode",
+);
+/// The file the token from logging in through the browser is kept in, encrypted.
+const SAVED_TOKEN_FILE: &str = "token";
+/// How often the token is validated, which Twitch requires at least once an hour.
+const VALIDATE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The token that replaced the one in the config, after it was refreshed during this session.
+static REFRESHED_TOKEN: RwLock<Option<String>> = RwLock::new(None);
+/// When the token was last validated, and what Twitch said about it.
+static TOKEN_STATUS: Mutex<Option<(DateTime<Local>, ClientId)>> = Mutex::new(None);
+
+#[derive(Deserialize, Debug, Clone)]
 pub struct ClientId {
     pub client_id: String,
     pub login: String,
     pub scopes: Vec<String>,
     pub user_id: String,
+    /// Seconds until the token expires, or 0 if it does not.
     pub expires_in: i32,
 }
 
+/// The token to use, which is the refreshed one if the one from the config was refreshed.
+pub fn current_token(token: Option<&str>) -> Option<String> {
+    REFRESHED_TOKEN
+        .read()
+        .ok()
+        .and_then(|refreshed| refreshed.clone())
+        .or_else(|| token.map(ToString::to_string))
+}
+
+/// Ask Twitch about a token, returning `None` if it is invalid or has expired.
+/// <https://dev.twitch.tv/docs/authentication/validate-tokens/>
+async fn validate(token: &str) -> Result<Option<ClientId>> {
+    // Strips the `oauth:` prefix if it exists
+    let token = token.strip_prefix("oauth:").unwrap_or(token);
+
+    let response = send_tracked(
+        "oauth2/validate",
+        Client::new()
+            .get(VALIDATE_URL)
+            .header(AUTHORIZATION, &format!("OAuth {token}")),
+    )
+    .await?;
+
+    if response.status() == StatusCode::UNAUTHORIZED {
+        return Ok(None);
+    }
+
+    let client_id = response.error_for_status()?.json::<ClientId>().await?;
+
+    if let Ok(mut status) = TOKEN_STATUS.lock() {
+        *status = Some((Local::now(), client_id.clone()));
+    }
+
+    Ok(Some(client_id))
+}
+
 pub async fn get_twitch_client_id(token: Option<&str>) -> Result<&ClientId> {
     static TWITCH_CLIENT_ID: OnceLock<ClientId> = OnceLock::new();
 
@@ -24,29 +112,18 @@ pub async fn get_twitch_client_id(token: Option<&str>) -> Result<&ClientId> {
         return Ok(id);
     }
 
-    let token = token.context("Twitch token is empty")?;
-
-    // Strips the `oauth:` prefix if it exists
-    let token = token.strip_prefix("oauth:").unwrap_or(token);
-
-    let client = Client::new();
+    let token = current_token(token).context("Twitch token is empty")?;
 
-    let data = client
-        .get("https://id.twitch.tv/oauth2/validate")
-        .header(AUTHORIZATION, &format!("OAuth {token}"))
-        .send()
+    let client_id = validate(&token)
         .await?
-        .error_for_status()
-        .unwrap();
-
-    let client_id = data.json::<ClientId>().await?;
+        .context("The Twitch token is invalid or has expired.")?;
 
     Ok(TWITCH_CLIENT_ID.get_or_init(|| client_id))
 }
 
 pub async fn get_twitch_client(oauth_token: Option<&str>) -> Result<Client> {
-    let token = oauth_token
-        .context("Twitch token is empty")?
+    let token = current_token(oauth_token).context("Twitch token is empty")?;
+    let token = token
         .strip_prefix("oauth:")
         .context("token does not start with `oauth:`")?;
 
@@ -72,17 +149,343 @@ pub struct ChannelList {
     data: Vec<Channel>,
 }
 
-pub async fn get_channel_id(client: &Client, channel: &str) -> Result<i32> {
-    Ok(client
-        .get(format!("https://api.twitch.tv/helix/users?login={channel}",))
-        .send()
-        .await?
-        .error_for_status()?
-        .json::<ChannelList>()
-        .await?
-        .data
-        .first()
-        .context("Could not get channel id.")?
-        .id
-        .parse()?)
+pub async fn get_channel_id(client: &Client, channel: &str, priority: Priority) -> Result<i32> {
+    let url = format!("https://api.twitch.tv/helix/users?login={channel}");
+    let request = client.get(&url);
+
+    // Emotes and whispers can look up the same channel at the same time
+    cached(CacheKind::Users, channel, async move {
+        Ok(send_helix("helix/users", priority, request)
+            .await?
+            .error_for_status()?
+            .json::<ChannelList>()
+            .await?
+            .data
+            .first()
+            .context("Could not get channel id.")?
+            .id
+            .parse()?)
+    })
+    .await
+}
+
+/// A code for the user to enter on Twitch, to log in without pasting a token into the config.
+/// <https://dev.twitch.tv/docs/authentication/getting-tokens-oauth/#device-code-grant-flow>
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeviceCode {
+    device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    /// Seconds until the code can no longer be entered.
+    pub expires_in: u64,
+    /// Seconds to wait between checking if the code was entered.
+    pub interval: u64,
+}
+
+/// A token from logging in through the browser, which can be refreshed once it expires.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct SavedToken {
+    access_token: String,
+    refresh_token: String,
+}
+
+impl SavedToken {
+    /// The token in the form used in the config.
+    fn irc_token(&self) -> String {
+        format!("oauth:{}", self.access_token)
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenError {
+    message: String,
+}
+
+/// The token from a response to a token request, or `None` if the user has not entered the code yet.
+fn parse_token_response(success: bool, body: &str) -> Result<Option<SavedToken>> {
+    if success {
+        return Ok(Some(serde_json::from_str(body)?));
+    }
+
+    let message = serde_json::from_str::<TokenError>(body)
+        .map_or_else(|_| body.to_string(), |err| err.message);
+
+    match message.as_str() {
+        "authorization_pending" | "slow_down" => Ok(None),
+        _ => bail!("Twitch refused the login: {message}"),
+    }
+}
+
+async fn request_token(
+    endpoint: &'static str,
+    form: &[(&str, &str)],
+) -> Result<Option<SavedToken>> {
+    let response = send_tracked(endpoint, Client::new().post(TOKEN_URL).form(form)).await?;
+    let success = response.status().is_success();
+
+    parse_token_response(success, &response.text().await?)
+}
+
+fn save_token(token: &SavedToken) -> Result<()> {
+    let content = encrypt(serde_json::to_string(token)?.as_bytes())?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    // Nobody but the current user should be able to read the token.
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options
+        .open(config_path(SAVED_TOKEN_FILE))?
+        .write_all(&content)?;
+
+    Ok(())
+}
+
+fn load_token() -> Option<SavedToken> {
+    let content = read(config_path(SAVED_TOKEN_FILE)).ok()?;
+
+    let content = decrypt(&content)
+        .map_err(|err| warn!("Unable to decrypt the saved token: {err}"))
+        .ok()?;
+
+    serde_json::from_slice(&content).ok()
+}
+
+/// Ask Twitch for a code for the user to enter, to log in from their browser.
+pub async fn request_device_code(client_id: &str) -> Result<DeviceCode> {
+    Ok(send_tracked(
+        "oauth2/device",
+        Client::new()
+            .post("https://id.twitch.tv/oauth2/device")
+            .form(&[("client_id", client_id), ("scopes", DEVICE_CODE_SCOPES)]),
+    )
+    .await?
+    .error_for_status()?
+    .json::<DeviceCode>()
+    .await?)
+}
+
+/// Check if the user has entered the code yet, returning the token and saving it for next time once they have.
+pub async fn poll_device_code(client_id: &str, code: &DeviceCode) -> Result<Option<String>> {
+    let Some(token) = request_token(
+        "oauth2/token",
+        &[
+            ("client_id", client_id),
+            ("scopes", DEVICE_CODE_SCOPES),
+            ("device_code", &code.device_code),
+            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+        ],
+    )
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    // Not being able to save the token only means having to log in again next time
+    if let Err(err) = save_token(&token) {
+        warn!("Unable to save the token: {err}");
+    }
+
+    Ok(Some(token.irc_token()))
+}
+
+/// Refresh the token saved from logging in through the browser, saving the new one in its place.
+async fn refresh_saved_token(client_id: &str, token: &SavedToken) -> Result<String> {
+    let refreshed = request_token(
+        "oauth2/token",
+        &[
+            ("client_id", client_id),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &token.refresh_token),
+        ],
+    )
+    .await?
+    .context("Twitch did not refresh the token.")?;
+
+    if let Err(err) = save_token(&refreshed) {
+        warn!("Unable to save the refreshed token: {err}");
+    }
+
+    Ok(refreshed.irc_token())
+}
+
+/// The token saved from logging in through the browser before, refreshed if it has expired.
+pub async fn saved_token(client_id: &str) -> Option<String> {
+    let token = load_token()?;
+
+    if matches!(validate(&token.access_token).await, Ok(Some(_))) {
+        return Some(token.irc_token());
+    }
+
+    refresh_saved_token(client_id, &token)
+        .await
+        .map_err(|err| warn!("Unable to refresh the saved token: {err}"))
+        .ok()
+}
+
+/// Make sure the token works before connecting, rather than failing with an error from the IRC server.
+pub async fn check_token(token: &str) -> Result<()> {
+    if validate(token)
+        .await
+        .wrap_err("Twitch could not be reached to check the token.")?
+        .is_none()
+    {
+        bail!(
+            "The Twitch token is invalid or has expired. Generate a new one, or leave it empty and set client_id to log in through the browser."
+        );
+    }
+
+    Ok(())
+}
+
+/// If a token should be refreshed before it is validated again.
+fn expires_soon(client_id: &ClientId) -> bool {
+    u64::try_from(client_id.expires_in)
+        .is_ok_and(|seconds| seconds > 0 && Duration::from_secs(seconds) < VALIDATE_INTERVAL)
+}
+
+/// Validate the token every hour, refreshing it if it was gotten by logging in through the browser.
+/// Tokens that cannot be refreshed are pointed out before they stop working.
+pub async fn keep_token_valid(config: CompleteConfig, tx: Sender<TwitchToTerminalAction>) {
+    let mut validate_interval = interval(VALIDATE_INTERVAL);
+
+    // The token was already validated on startup
+    validate_interval.tick().await;
+
+    loop {
+        validate_interval.tick().await;
+
+        let Some(token) = current_token(config.twitch.token.as_deref()) else {
+            return;
+        };
+
+        let expiring = match validate(&token).await {
+            Ok(client_id) => client_id.as_ref().is_none_or(expires_soon),
+            Err(err) => {
+                debug!("Unable to validate the Twitch token: {err}");
+                continue;
+            }
+        };
+
+        if !expiring {
+            continue;
+        }
+
+        // Only the token from logging in through the browser has a refresh token
+        let saved = load_token().filter(|saved| saved.irc_token() == token);
+
+        let (Some(client_id), Some(saved)) = (config.twitch.client_id.as_deref(), saved) else {
+            tx.send(DataBuilder::default().system(
+                "The Twitch token has expired or is about to. Generate a new one and restart to keep chatting.".to_string(),
+            ))
+            .await
+            .unwrap();
+
+            continue;
+        };
+
+        match refresh_saved_token(client_id, &saved).await {
+            Ok(token) => {
+                info!("Refreshed the Twitch token.");
+
+                if let Ok(mut refreshed) = REFRESHED_TOKEN.write() {
+                    *refreshed = Some(token.clone());
+                }
+
+                if let Err(err) = validate(&token).await {
+                    debug!("Unable to validate the refreshed token: {err}");
+                }
+            }
+            Err(err) => {
+                tx.send(DataBuilder::default().system(format!(
+                    "Unable to refresh the Twitch token, restart to log in again: {err}"
+                )))
+                .await
+                .unwrap();
+            }
+        }
+    }
+}
+
+/// How long until a token expires, from when it was validated.
+fn remaining(validated: DateTime<Local>, expires_in: i32, now: DateTime<Local>) -> TimeDelta {
+    (validated + TimeDelta::seconds(i64::from(expires_in)) - now).max(TimeDelta::zero())
+}
+
+/// Rows about the token from when it was last validated, to be shown in the debug window.
+pub fn token_stats() -> Vec<(String, String)> {
+    let Some((validated, client_id)) = TOKEN_STATUS.lock().ok().and_then(|status| status.clone())
+    else {
+        return vec![("Status".to_string(), "Not validated".to_string())];
+    };
+
+    let now = Local::now();
+
+    let expires = if client_id.expires_in == 0 {
+        "Never".to_string()
+    } else {
+        format_duration(remaining(validated, client_id.expires_in, now))
+    };
+
+    let mut stats = vec![
+        ("Login".to_string(), client_id.login),
+        ("Expires in".to_string(), expires),
+        (
+            "Validated".to_string(),
+            format!("{} ago", format_duration(now - validated)),
+        ),
+    ];
+
+    stats.extend(
+        client_id
+            .scopes
+            .into_iter()
+            .map(|scope| ("Scope".to_string(), scope)),
+    );
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining() {
+        let validated = Local::now();
+
+        assert_eq!(
+            remaining(validated, 3600, validated + TimeDelta::minutes(20)),
+            TimeDelta::minutes(40)
+        );
+        assert_eq!(
+            remaining(validated, 60, validated + TimeDelta::minutes(20)),
+            TimeDelta::zero()
+        );
+    }
+
+    #[test]
+    fn test_parse_token_response() {
+        let token = parse_token_response(
+            true,
+            r#"{"access_token":"abc","refresh_token":"def","expires_in":14400,"scope":["chat:read"],"token_type":"bearer"}"#,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(token.irc_token(), "oauth:abc");
+        assert_eq!(token.refresh_token, "def");
+
+        assert!(
+            parse_token_response(false, r#"{"status":400,"message":"authorization_pending"}"#)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            parse_token_response(false, r#"{"status":400,"message":"invalid device code"}"#)
+                .is_err()
+        );
+    }
 }
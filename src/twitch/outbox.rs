@@ -0,0 +1,196 @@
+use std::fs::{read, write};
+
+use color_eyre::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    handlers::config::StorageConfig,
+    utils::{
+        encryption::{decrypt, encrypt, is_encrypted},
+        pathing::config_path,
+    },
+};
+
+const OUTBOX_FILE: &str = "outbox.json";
+
+/// A message or whisper that has not been sent yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Outgoing {
+    Message { channel: String, message: String },
+    Whisper { user: String, message: String },
+}
+
+impl Outgoing {
+    /// The text to put in the chat input to change it before sending it.
+    pub fn as_input(&self) -> String {
+        match self {
+            Self::Message { message, .. } => message.clone(),
+            Self::Whisper { user, message } => format!("/w {user} {message}"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct OutboxItems {
+    /// Items left over from a previous session, waiting for the user to decide what happens to them.
+    recovered: Vec<Outgoing>,
+    /// Items that are being sent, or are waiting for the connection to come back.
+    pending: Vec<Outgoing>,
+}
+
+/// Messages and whispers that have not been sent yet,
+/// kept in a file if enabled so that they can be sent after a crash or restart.
+#[derive(Debug)]
+pub struct Outbox {
+    items: OutboxItems,
+    /// Where the items are kept, if they should be kept at all.
+    file_path: Option<String>,
+    encrypted: bool,
+}
+
+fn read_items(file_path: &str) -> Result<OutboxItems> {
+    let mut content = read(file_path)?;
+
+    if is_encrypted(&content) {
+        content = decrypt(&content)?;
+    }
+
+    Ok(serde_json::from_slice(&content)?)
+}
+
+impl Outbox {
+    /// Load what was not sent during the previous session, which all counts as recovered.
+    pub fn load(config: &StorageConfig) -> Self {
+        if !config.outbox {
+            return Self {
+                items: OutboxItems::default(),
+                file_path: None,
+                encrypted: false,
+            };
+        }
+
+        let file_path = config_path(OUTBOX_FILE);
+
+        let items = read_items(&file_path).unwrap_or_default();
+
+        Self {
+            items: OutboxItems {
+                recovered: [items.recovered, items.pending].concat(),
+                pending: vec![],
+            },
+            file_path: Some(file_path),
+            encrypted: config.encrypted,
+        }
+    }
+
+    fn save(&self) {
+        let Some(file_path) = &self.file_path else {
+            return;
+        };
+
+        let result = serde_json::to_vec(&self.items)
+            .map_err(color_eyre::Report::from)
+            .and_then(|content| {
+                if self.encrypted {
+                    encrypt(&content)
+                } else {
+                    Ok(content)
+                }
+            })
+            .and_then(|content| Ok(write(file_path, content)?));
+
+        if let Err(err) = result {
+            warn!("Unable to save the unsent messages: {err}");
+        }
+    }
+
+    pub fn recovered(&self) -> Vec<Outgoing> {
+        self.items.recovered.clone()
+    }
+
+    /// Stop waiting for the user to decide about a recovered item.
+    pub fn take_recovered(&mut self, item: &Outgoing) -> bool {
+        let Some(index) = self.items.recovered.iter().position(|i| i == item) else {
+            return false;
+        };
+
+        self.items.recovered.remove(index);
+        self.save();
+
+        true
+    }
+
+    pub fn push(&mut self, item: Outgoing) {
+        self.items.pending.push(item);
+        self.save();
+    }
+
+    /// Forget an item once it has been sent, or has failed in a way that retrying will not fix.
+    pub fn remove(&mut self, item: &Outgoing) {
+        if let Some(index) = self.items.pending.iter().position(|i| i == item) {
+            self.items.pending.remove(index);
+            self.save();
+        }
+    }
+
+    /// Take the messages waiting for the connection to come back, to try sending them again.
+    pub fn take_messages(&mut self) -> Vec<Outgoing> {
+        let (messages, whispers): (Vec<Outgoing>, Vec<Outgoing>) = self
+            .items
+            .pending
+            .drain(..)
+            .partition(|item| matches!(item, Outgoing::Message { .. }));
+
+        self.items.pending = whispers;
+        self.save();
+
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outbox() -> Outbox {
+        Outbox {
+            items: OutboxItems::default(),
+            file_path: None,
+            encrypted: false,
+        }
+    }
+
+    #[test]
+    fn test_take_messages_keeps_whispers() {
+        let mut outbox = outbox();
+
+        let message = Outgoing::Message {
+            channel: "xithrius".to_string(),
+            message: "hello".to_string(),
+        };
+        let whisper = Outgoing::Whisper {
+            user: "human".to_string(),
+            message: "hi".to_string(),
+        };
+
+        outbox.push(message.clone());
+        outbox.push(whisper.clone());
+
+        assert_eq!(outbox.take_messages(), vec![message]);
+
+        outbox.remove(&whisper);
+
+        assert!(outbox.items.pending.is_empty());
+    }
+
+    #[test]
+    fn test_whisper_as_input() {
+        let whisper = Outgoing::Whisper {
+            user: "human".to_string(),
+            message: "hi there".to_string(),
+        };
+
+        assert_eq!(whisper.as_input(), "/w human hi there");
+    }
+}
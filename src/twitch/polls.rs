@@ -0,0 +1,555 @@
+use chrono::{DateTime, Local, TimeDelta};
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::{
+    twitch::{
+        budget::{Priority, send_helix},
+        helix::{ensure_requestable, track_request},
+        moderation::HelixError,
+        oauth::{get_channel_id, get_twitch_client, get_twitch_client_id},
+    },
+    utils::time::parse_duration,
+};
+
+pub const POLLS_SCOPE: &str = "channel:manage:polls";
+pub const PREDICTIONS_SCOPE: &str = "channel:manage:predictions";
+
+/// How long the results of a poll stay on screen after it ended.
+const RESULTS_SHOWN_FOR: TimeDelta = TimeDelta::seconds(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollKind {
+    Poll,
+    /// Viewers bet channel points on the outcome, rather than voting.
+    Prediction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollStatus {
+    Active,
+    /// A prediction that stopped taking bets, waiting for its outcome.
+    Locked,
+    Ended,
+    /// A prediction that was called off, refunding every bet.
+    Canceled,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PollChoice {
+    pub title: String,
+    /// The votes on a poll choice, or the channel points bet on a prediction outcome.
+    pub votes: u64,
+    /// If this is the outcome a prediction was resolved to.
+    pub won: bool,
+}
+
+#[derive(Deserialize)]
+struct RawChoice {
+    id: String,
+    title: String,
+    #[serde(default)]
+    votes: u64,
+    #[serde(default)]
+    channel_points: u64,
+}
+
+/// The events of both polls and predictions, which differ in a few field names.
+/// <https://dev.twitch.tv/docs/eventsub/eventsub-reference/#poll-choice>
+#[derive(Deserialize)]
+struct PollEvent {
+    id: String,
+    broadcaster_user_login: String,
+    title: String,
+    #[serde(alias = "outcomes")]
+    choices: Vec<RawChoice>,
+    /// When a poll ends.
+    ends_at: Option<String>,
+    /// When a prediction stops taking bets.
+    locks_at: Option<String>,
+    status: Option<String>,
+    winning_outcome_id: Option<String>,
+}
+
+/// A poll or prediction in a channel, replaced as its progress comes in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Poll {
+    pub id: String,
+    pub kind: PollKind,
+    pub channel: String,
+    pub title: String,
+    pub choices: Vec<PollChoice>,
+    /// When voting closes, if it is still open.
+    pub closes_at: Option<DateTime<Local>>,
+    pub status: PollStatus,
+    pub received_at: DateTime<Local>,
+}
+
+impl Poll {
+    /// Read a poll or prediction event, such as `channel.poll.progress`.
+    pub fn from_event(kind: &str, event: Value, now: DateTime<Local>) -> Result<Self> {
+        let event = serde_json::from_value::<PollEvent>(event)?;
+
+        let (poll_kind, stage) = if let Some(stage) = kind.strip_prefix("channel.poll.") {
+            (PollKind::Poll, stage)
+        } else if let Some(stage) = kind.strip_prefix("channel.prediction.") {
+            (PollKind::Prediction, stage)
+        } else {
+            bail!("{kind} is not a poll or prediction event.");
+        };
+
+        let status = match (stage, event.status.as_deref()) {
+            ("lock", _) => PollStatus::Locked,
+            ("end", Some("canceled")) => PollStatus::Canceled,
+            ("end", _) => PollStatus::Ended,
+            _ => PollStatus::Active,
+        };
+
+        let closes_at = if status == PollStatus::Active {
+            event
+                .ends_at
+                .or(event.locks_at)
+                .and_then(|time| DateTime::parse_from_rfc3339(&time).ok())
+                .map(|time| time.with_timezone(&Local))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            id: event.id,
+            kind: poll_kind,
+            channel: event.broadcaster_user_login,
+            title: event.title,
+            choices: event
+                .choices
+                .into_iter()
+                .map(|choice| PollChoice {
+                    won: event.winning_outcome_id.as_ref() == Some(&choice.id),
+                    title: choice.title,
+                    votes: match poll_kind {
+                        PollKind::Poll => choice.votes,
+                        PollKind::Prediction => choice.channel_points,
+                    },
+                })
+                .collect(),
+            closes_at,
+            status,
+            received_at: now,
+        })
+    }
+
+    /// The share of the votes each choice has, in whole percentages.
+    pub fn percentages(&self) -> Vec<u64> {
+        let total = self.choices.iter().map(|choice| choice.votes).sum::<u64>();
+
+        self.choices
+            .iter()
+            .map(|choice| {
+                if total == 0 {
+                    0
+                } else {
+                    choice.votes * 100 / total
+                }
+            })
+            .collect()
+    }
+
+    /// If the poll is still running, or ended recently enough for its results to be shown.
+    pub fn is_shown(&self, now: DateTime<Local>) -> bool {
+        match self.status {
+            PollStatus::Active | PollStatus::Locked => true,
+            PollStatus::Ended | PollStatus::Canceled => now - self.received_at < RESULTS_SHOWN_FOR,
+        }
+    }
+}
+
+/// Starting and ending polls and predictions in the user's own channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PollCommand {
+    StartPoll {
+        title: String,
+        choices: Vec<String>,
+        seconds: u64,
+    },
+    EndPoll,
+    StartPrediction {
+        title: String,
+        outcomes: Vec<String>,
+        seconds: u64,
+    },
+    /// Stop taking bets on the prediction.
+    LockPrediction,
+    /// Pay out the bets on an outcome, counting from 1.
+    ResolvePrediction {
+        outcome: usize,
+    },
+    CancelPrediction,
+}
+
+const POLL_USAGE: &str =
+    "Usage: /poll <duration> <title> | <choice> | <choice> [| ...], or /poll end";
+const PREDICTION_USAGE: &str = "Usage: /prediction <duration> <title> | <outcome> | <outcome> [| ...], or /prediction lock, resolve <outcome number>, or cancel";
+
+impl PollCommand {
+    const fn kind(&self) -> PollKind {
+        match self {
+            Self::StartPoll { .. } | Self::EndPoll => PollKind::Poll,
+            _ => PollKind::Prediction,
+        }
+    }
+
+    /// The scope the token needs for this command.
+    pub const fn scope(&self) -> &'static str {
+        match self.kind() {
+            PollKind::Poll => POLLS_SCOPE,
+            PollKind::Prediction => PREDICTIONS_SCOPE,
+        }
+    }
+}
+
+/// Read the duration, title, and choices of a new poll or prediction,
+/// checking them against the limits Twitch has for each.
+fn parse_start(
+    arguments: &str,
+    seconds_range: (u64, u64),
+    choices_range: (usize, usize),
+    usage: &str,
+) -> Result<(u64, String, Vec<String>)> {
+    let (duration, rest) = arguments
+        .trim()
+        .split_once(' ')
+        .ok_or_else(|| eyre!("{usage}"))?;
+
+    let seconds = parse_duration(duration)?.as_secs();
+
+    if !(seconds_range.0..=seconds_range.1).contains(&seconds) {
+        bail!(
+            "The duration needs to be from {} to {} seconds.",
+            seconds_range.0,
+            seconds_range.1
+        );
+    }
+
+    let mut parts = rest
+        .split('|')
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty());
+
+    let title = parts.next().ok_or_else(|| eyre!("{usage}"))?;
+    let choices = parts.collect::<Vec<String>>();
+
+    if !(choices_range.0..=choices_range.1).contains(&choices.len()) {
+        bail!(
+            "There need to be from {} to {} choices, separated by |.",
+            choices_range.0,
+            choices_range.1
+        );
+    }
+
+    Ok((seconds, title, choices))
+}
+
+/// Parse a poll or prediction command, returning `None` if the message is not one,
+/// or an error with how to use the command if its arguments are wrong.
+pub fn parse_poll_command(message: &str) -> Option<Result<PollCommand>> {
+    let message = message.strip_prefix('/')?;
+    let (command, arguments) = message.split_once(' ').unwrap_or((message, ""));
+
+    let parsed = match (command, arguments.trim()) {
+        ("poll", "end") => Ok(PollCommand::EndPoll),
+        ("poll", "") => Err(eyre!("{POLL_USAGE}")),
+        ("poll", arguments) => parse_start(arguments, (15, 1800), (2, 5), POLL_USAGE).map(
+            |(seconds, title, choices)| PollCommand::StartPoll {
+                title,
+                choices,
+                seconds,
+            },
+        ),
+        ("prediction", "lock") => Ok(PollCommand::LockPrediction),
+        ("prediction", "cancel") => Ok(PollCommand::CancelPrediction),
+        ("prediction", arguments) if arguments.starts_with("resolve") => arguments
+            .strip_prefix("resolve")
+            .and_then(|outcome| outcome.trim().parse::<usize>().ok())
+            .filter(|outcome| *outcome > 0)
+            .map(|outcome| PollCommand::ResolvePrediction { outcome })
+            .ok_or_else(|| eyre!("Usage: /prediction resolve <outcome number, starting at 1>")),
+        ("prediction", "") => Err(eyre!("{PREDICTION_USAGE}")),
+        ("prediction", arguments) => parse_start(arguments, (30, 1800), (2, 10), PREDICTION_USAGE)
+            .map(|(seconds, title, outcomes)| PollCommand::StartPrediction {
+                title,
+                outcomes,
+                seconds,
+            }),
+        _ => return None,
+    };
+
+    Some(parsed)
+}
+
+#[derive(Deserialize)]
+struct CurrentOutcome {
+    id: String,
+}
+
+/// The most recent poll or prediction, as listed by Helix.
+#[derive(Deserialize)]
+struct Current {
+    id: String,
+    status: String,
+    #[serde(default)]
+    outcomes: Vec<CurrentOutcome>,
+}
+
+#[derive(Deserialize)]
+struct CurrentList {
+    data: Vec<Current>,
+}
+
+/// Run a poll or prediction command in the user's own channel.
+/// <https://dev.twitch.tv/docs/api/reference/#create-poll>
+/// <https://dev.twitch.tv/docs/api/reference/#create-prediction>
+pub async fn run_poll_command(
+    token: Option<&str>,
+    channel: &str,
+    command: &PollCommand,
+) -> Result<()> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let client_id = get_twitch_client_id(None).await?;
+
+    let name = match command.kind() {
+        PollKind::Poll => "poll",
+        PollKind::Prediction => "prediction",
+    };
+
+    if !client_id.scopes.iter().any(|s| s == command.scope()) {
+        bail!(
+            "/{name} requires the `{}` scope in your token.",
+            command.scope()
+        );
+    }
+
+    let broadcaster_id = track_request(get_channel_id(&client, channel, Priority::User).await)?;
+
+    if broadcaster_id.to_string() != client_id.user_id {
+        bail!("Only the broadcaster can start and end a {name}.");
+    }
+
+    let endpoint = match command.kind() {
+        PollKind::Poll => "helix/polls",
+        PollKind::Prediction => "helix/predictions",
+    };
+    let url = format!("https://api.twitch.tv/{endpoint}");
+
+    // Ending something needs its ID, which is always the most recent one
+    let current = || async {
+        let response = track_request(
+            send_helix(
+                endpoint,
+                Priority::User,
+                client.get(format!("{url}?broadcaster_id={broadcaster_id}&first=1")),
+            )
+            .await,
+        )?;
+
+        response
+            .error_for_status()?
+            .json::<CurrentList>()
+            .await?
+            .data
+            .into_iter()
+            .find(|current| current.status == "ACTIVE" || current.status == "LOCKED")
+            .ok_or_else(|| eyre!("There is no {name} running."))
+    };
+
+    let request = match command {
+        PollCommand::StartPoll {
+            title,
+            choices,
+            seconds,
+        } => client.post(&url).json(&json!({
+            "broadcaster_id": broadcaster_id.to_string(),
+            "title": title,
+            "choices": choices.iter().map(|title| json!({ "title": title })).collect::<Vec<Value>>(),
+            "duration": seconds,
+        })),
+        PollCommand::StartPrediction {
+            title,
+            outcomes,
+            seconds,
+        } => client.post(&url).json(&json!({
+            "broadcaster_id": broadcaster_id.to_string(),
+            "title": title,
+            "outcomes": outcomes.iter().map(|title| json!({ "title": title })).collect::<Vec<Value>>(),
+            "prediction_window": seconds,
+        })),
+        PollCommand::EndPoll => {
+            let current = current().await?;
+
+            client.patch(&url).json(&json!({
+                "broadcaster_id": broadcaster_id.to_string(),
+                "id": current.id,
+                "status": "TERMINATED",
+            }))
+        }
+        PollCommand::LockPrediction | PollCommand::CancelPrediction => {
+            let current = current().await?;
+
+            client.patch(&url).json(&json!({
+                "broadcaster_id": broadcaster_id.to_string(),
+                "id": current.id,
+                "status": if *command == PollCommand::LockPrediction { "LOCKED" } else { "CANCELED" },
+            }))
+        }
+        PollCommand::ResolvePrediction { outcome } => {
+            let current = current().await?;
+
+            let Some(winner) = current.outcomes.get(outcome - 1) else {
+                bail!("The prediction only has {} outcomes.", current.outcomes.len());
+            };
+
+            client.patch(&url).json(&json!({
+                "broadcaster_id": broadcaster_id.to_string(),
+                "id": current.id,
+                "status": "RESOLVED",
+                "winning_outcome_id": winner.id,
+            }))
+        }
+    };
+
+    let response = track_request(send_helix(endpoint, Priority::User, request).await)?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+
+        let message = response
+            .json::<HelixError>()
+            .await
+            .map_or_else(|_| status.to_string(), |err| err.message);
+
+        bail!("/{name} failed: {message}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_poll_from_event() {
+        let ends_at = (now() + TimeDelta::minutes(1)).to_rfc3339();
+
+        let poll = Poll::from_event(
+            "channel.poll.progress",
+            json!({
+                "id": "1",
+                "broadcaster_user_login": "xithrius",
+                "title": "Best editor",
+                "choices": [
+                    { "id": "a", "title": "vim", "votes": 3 },
+                    { "id": "b", "title": "emacs", "votes": 1 }
+                ],
+                "started_at": now().to_rfc3339(),
+                "ends_at": ends_at,
+            }),
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(poll.kind, PollKind::Poll);
+        assert_eq!(poll.status, PollStatus::Active);
+        assert_eq!(poll.closes_at, Some(now() + TimeDelta::minutes(1)));
+        assert_eq!(poll.percentages(), vec![75, 25]);
+        assert!(poll.is_shown(now() + TimeDelta::hours(1)));
+    }
+
+    #[test]
+    fn test_prediction_from_event() {
+        let prediction = Poll::from_event(
+            "channel.prediction.end",
+            json!({
+                "id": "1",
+                "broadcaster_user_login": "xithrius",
+                "title": "Win?",
+                "outcomes": [
+                    { "id": "a", "title": "yes", "users": 2, "channel_points": 100 },
+                    { "id": "b", "title": "no", "users": 1, "channel_points": 300 }
+                ],
+                "status": "resolved",
+                "winning_outcome_id": "b",
+            }),
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(prediction.kind, PollKind::Prediction);
+        assert_eq!(prediction.status, PollStatus::Ended);
+        assert_eq!(prediction.closes_at, None);
+        assert_eq!(prediction.percentages(), vec![25, 75]);
+        assert!(prediction.choices[1].won);
+        assert!(prediction.is_shown(now() + TimeDelta::seconds(10)));
+        assert!(!prediction.is_shown(now() + TimeDelta::minutes(1)));
+
+        let empty = Poll::from_event(
+            "channel.prediction.begin",
+            json!({ "id": "1", "broadcaster_user_login": "x", "title": "t", "outcomes": [{ "id": "a", "title": "yes" }] }),
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(empty.percentages(), vec![0]);
+    }
+
+    #[test]
+    fn test_parse_poll_command() {
+        assert_eq!(
+            parse_poll_command("/poll 2m Best editor | vim | emacs ").map(Result::unwrap),
+            Some(PollCommand::StartPoll {
+                title: "Best editor".to_string(),
+                choices: vec!["vim".to_string(), "emacs".to_string()],
+                seconds: 120,
+            })
+        );
+        assert_eq!(
+            parse_poll_command("/poll end").map(Result::unwrap),
+            Some(PollCommand::EndPoll)
+        );
+        assert_eq!(
+            parse_poll_command("/prediction resolve 2").map(Result::unwrap),
+            Some(PollCommand::ResolvePrediction { outcome: 2 })
+        );
+        assert!(parse_poll_command("/polls").is_none());
+        assert!(parse_poll_command("poll end").is_none());
+
+        let error = |message: &str| {
+            parse_poll_command(message)
+                .unwrap()
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error("/poll").starts_with("Usage: /poll"));
+        assert_eq!(
+            error("/poll 5s Title | a | b"),
+            "The duration needs to be from 15 to 1800 seconds."
+        );
+        assert_eq!(
+            error("/poll 1m Title | a"),
+            "There need to be from 2 to 5 choices, separated by |."
+        );
+        assert!(error("/prediction resolve 0").starts_with("Usage: /prediction resolve"));
+    }
+}
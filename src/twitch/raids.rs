@@ -0,0 +1,189 @@
+use chrono::{DateTime, Local, TimeDelta};
+use color_eyre::Result;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{handlers::config::Matching, utils::matching::matches_any};
+
+/// How long the banner of a raid is shown over chat, which is about how long the raid countdown lasts.
+const BANNER_SECONDS: i64 = 90;
+
+/// The event sent when a channel raids another.
+/// <https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/#channelraid>
+#[derive(Deserialize)]
+struct RaidEvent {
+    from_broadcaster_user_login: String,
+    to_broadcaster_user_login: String,
+    viewers: u64,
+}
+
+/// A raid into a joined channel, or out of a joined channel or started by the current user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raid {
+    pub time_received: DateTime<Local>,
+    /// The channel the viewers came from.
+    pub raider: String,
+    /// The channel the viewers were sent to.
+    pub channel: String,
+    /// The size of the raiding party, which is unknown for raids started from the chat input.
+    pub viewers: Option<u64>,
+    /// If the raid left a joined channel or was started by the current user,
+    /// rather than arriving in a joined channel.
+    pub outgoing: bool,
+}
+
+impl Raid {
+    pub fn new(raider: String, channel: &str, viewers: Option<u64>, outgoing: bool) -> Self {
+        Self {
+            time_received: Local::now(),
+            raider,
+            channel: channel.trim_start_matches('#').to_string(),
+            viewers,
+            outgoing,
+        }
+    }
+
+    /// A joined channel raiding another, as sent by EventSub.
+    pub fn from_event(event: Value) -> Result<Self> {
+        let event = serde_json::from_value::<RaidEvent>(event)?;
+
+        Ok(Self::new(
+            event.from_broadcaster_user_login,
+            &event.to_broadcaster_user_login,
+            Some(event.viewers),
+            true,
+        ))
+    }
+
+    /// If the banner of this raid should be shown over the chat of a channel.
+    pub fn is_banner_shown(&self, channel: &str, now: DateTime<Local>) -> bool {
+        let involved = if self.outgoing {
+            self.raider.eq_ignore_ascii_case(channel)
+        } else {
+            self.channel.eq_ignore_ascii_case(channel)
+        };
+
+        involved && now - self.time_received < TimeDelta::seconds(BANNER_SECONDS)
+    }
+
+    /// The text of the banner, such as `Raiding human with 42 viewers`.
+    pub fn banner(&self) -> String {
+        let viewers = self
+            .viewers
+            .map(|viewers| format!(" with {viewers} viewers"))
+            .unwrap_or_default();
+
+        if self.outgoing {
+            format!("Raiding {}{viewers}", self.channel)
+        } else {
+            format!("{} is raiding{viewers}", self.raider)
+        }
+    }
+
+    /// The channel on the other side of the raid.
+    pub fn other_channel(&self) -> &str {
+        if self.outgoing {
+            &self.channel
+        } else {
+            &self.raider
+        }
+    }
+
+    pub fn matches(&self, query: &str, matching: Matching) -> bool {
+        matches_any(matching, &[&self.raider, &self.channel], query)
+    }
+
+    /// Raids are persisted as a single line each, with their fields separated by tabs.
+    pub fn to_storage(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}",
+            self.time_received.to_rfc3339(),
+            self.raider,
+            self.channel,
+            self.viewers.map(|v| v.to_string()).unwrap_or_default(),
+            self.outgoing
+        )
+    }
+
+    pub fn from_storage(value: &str) -> Option<Self> {
+        let mut fields = value.split('\t');
+
+        let time_received = DateTime::parse_from_rfc3339(fields.next()?)
+            .ok()?
+            .with_timezone(&Local);
+        let raider = fields.next()?.to_string();
+        let channel = fields.next()?.to_string();
+        let viewers = fields.next()?.parse().ok();
+        let outgoing = fields.next()?.parse().ok()?;
+
+        Some(Self {
+            time_received,
+            raider,
+            channel,
+            viewers,
+            outgoing,
+        })
+    }
+}
+
+/// The channel being raided by a `/raid <channel>` command.
+pub fn parse_raid_command(message: &str) -> Option<&str> {
+    let channel = message
+        .strip_prefix("/raid ")?
+        .trim()
+        .trim_start_matches('@');
+
+    (!channel.is_empty() && !channel.contains(' ')).then_some(channel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raid_storage_round_trip() {
+        let raid = Raid::new("raider".to_string(), "#channel", Some(42), false);
+
+        assert_eq!(Raid::from_storage(&raid.to_storage()), Some(raid));
+
+        let raid = Raid::new("me".to_string(), "channel", None, true);
+
+        assert_eq!(Raid::from_storage(&raid.to_storage()), Some(raid));
+        assert_eq!(Raid::from_storage("not a raid"), None);
+    }
+
+    #[test]
+    fn test_raid_banner() {
+        let raid = Raid::from_event(serde_json::json!({
+            "from_broadcaster_user_id": "1",
+            "from_broadcaster_user_login": "xithrius",
+            "from_broadcaster_user_name": "Xithrius",
+            "to_broadcaster_user_id": "2",
+            "to_broadcaster_user_login": "human",
+            "to_broadcaster_user_name": "Human",
+            "viewers": 42
+        }))
+        .unwrap();
+
+        let now = raid.time_received;
+
+        assert_eq!(raid.banner(), "Raiding human with 42 viewers");
+        assert!(raid.is_banner_shown("Xithrius", now));
+        assert!(!raid.is_banner_shown("human", now));
+        assert!(!raid.is_banner_shown("xithrius", now + TimeDelta::minutes(2)));
+
+        let raid = Raid::new("Raider".to_string(), "#xithrius", None, false);
+
+        assert_eq!(raid.banner(), "Raider is raiding");
+        assert!(raid.is_banner_shown("xithrius", raid.time_received));
+    }
+
+    #[test]
+    fn test_parse_raid_command() {
+        assert_eq!(parse_raid_command("/raid channel"), Some("channel"));
+        assert_eq!(parse_raid_command("/raid @channel "), Some("channel"));
+        assert_eq!(parse_raid_command("/raid "), None);
+        assert_eq!(parse_raid_command("/unraid"), None);
+        assert_eq!(parse_raid_command("/raid a b"), None);
+    }
+}
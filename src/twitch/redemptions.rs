@@ -0,0 +1,91 @@
+use color_eyre::Result;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Deserialize)]
+struct RedeemedReward {
+    title: String,
+    cost: u64,
+}
+
+/// The event sent when a viewer redeems a custom reward.
+/// <https://dev.twitch.tv/docs/eventsub/eventsub-subscription-types/#channelchannel_points_custom_reward_redemptionadd>
+#[derive(Deserialize)]
+struct RedemptionEvent {
+    broadcaster_user_login: String,
+    user_name: String,
+    #[serde(default)]
+    user_input: String,
+    reward: RedeemedReward,
+}
+
+/// A custom channel point reward redeemed by a viewer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redemption {
+    pub channel: String,
+    pub user: String,
+    pub reward: String,
+    pub cost: u64,
+    /// What the viewer wrote, for rewards that ask for text.
+    pub input: Option<String>,
+}
+
+impl Redemption {
+    pub fn from_event(event: Value) -> Result<Self> {
+        let event = serde_json::from_value::<RedemptionEvent>(event)?;
+
+        Ok(Self {
+            channel: event.broadcaster_user_login,
+            user: event.user_name,
+            reward: event.reward.title,
+            cost: event.reward.cost,
+            input: Some(event.user_input).filter(|input| !input.is_empty()),
+        })
+    }
+
+    /// The line shown in chat, such as `Human redeemed Hydrate (500): drink water`.
+    pub fn summary(&self) -> String {
+        let summary = format!("{} redeemed {} ({})", self.user, self.reward, self.cost);
+
+        match &self.input {
+            Some(input) => format!("{summary}: {input}"),
+            None => summary,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_redemption_from_event() {
+        let event = json!({
+            "id": "1",
+            "broadcaster_user_login": "xithrius",
+            "user_login": "human",
+            "user_name": "Human",
+            "user_input": "",
+            "status": "unfulfilled",
+            "reward": { "id": "2", "title": "Hydrate", "cost": 500, "prompt": "" },
+            "redeemed_at": "2024-01-01T12:00:00Z"
+        });
+
+        let redemption = Redemption::from_event(event.clone()).unwrap();
+
+        assert_eq!(redemption.channel, "xithrius");
+        assert_eq!(redemption.input, None);
+        assert_eq!(redemption.summary(), "Human redeemed Hydrate (500)");
+
+        let mut with_input = event;
+        with_input["user_input"] = json!("drink water");
+
+        assert_eq!(
+            Redemption::from_event(with_input).unwrap().summary(),
+            "Human redeemed Hydrate (500): drink water"
+        );
+        assert!(Redemption::from_event(json!({ "user_name": "Human" })).is_err());
+    }
+}
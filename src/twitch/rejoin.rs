@@ -0,0 +1,259 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use irc::proto::{Command, Message};
+
+/// How long Twitch has to confirm a JOIN before it is sent again.
+pub const JOIN_TIMEOUT: Duration = Duration::from_secs(10);
+/// How many times a channel is joined before the user is told that it could not be.
+const MAXIMUM_JOIN_ATTEMPTS: u32 = 3;
+
+/// NOTICE codes meaning that a channel cannot be joined, so joining it again would not help.
+/// <https://dev.twitch.tv/docs/irc/msg-id/>
+const JOIN_REFUSED: [&str; 1] = ["msg_channel_suspended"];
+
+/// What has to be done to get back into a channel.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RejoinStep {
+    /// Send a JOIN for the channel again.
+    Join(String),
+    /// Stop trying to join the channel, after it was tried too many times or refused.
+    GiveUp(String),
+}
+
+/// Channels are kept in lowercase without the leading `#`, the same as Twitch confirms them.
+fn normalize(channel: &str) -> String {
+    channel.trim_start_matches('#').to_lowercase()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PendingJoin {
+    sent_at: Instant,
+    attempts: u32,
+}
+
+/// Checks that joined channels are confirmed by Twitch, joining them again if they are not,
+/// or if Twitch parts the user from them without being asked to.
+#[derive(Debug, Default)]
+pub struct Rejoins {
+    /// Channels with a JOIN that Twitch has not confirmed yet.
+    pending: HashMap<String, PendingJoin>,
+}
+
+impl Rejoins {
+    /// A JOIN was sent for a channel, which will be sent again if Twitch does not confirm it.
+    pub fn joining(&mut self, channel: &str, now: Instant) {
+        self.pending.insert(
+            normalize(channel),
+            PendingJoin {
+                sent_at: now,
+                attempts: 1,
+            },
+        );
+    }
+
+    /// The user left a channel, so it is no longer joined again.
+    pub fn parted(&mut self, channel: &str) {
+        self.pending.remove(&normalize(channel));
+    }
+
+    /// Follow what Twitch says about the channels of the user, being the joined ones,
+    /// returning what has to be done if it dropped the user from one of them or refused to let them in.
+    pub fn observe(
+        &mut self,
+        message: &Message,
+        username: &str,
+        joined: &[String],
+        now: Instant,
+    ) -> Option<RejoinStep> {
+        let own = message
+            .source_nickname()
+            .is_some_and(|nickname| nickname.eq_ignore_ascii_case(username));
+
+        match &message.command {
+            Command::JOIN(channel, _, _) if own => {
+                self.pending.remove(&normalize(channel));
+
+                None
+            }
+            Command::PART(channel, _) if own => {
+                let channel = normalize(channel);
+
+                // Channels the user left themselves are no longer in the joined ones
+                joined
+                    .iter()
+                    .any(|c| c.eq_ignore_ascii_case(&channel))
+                    .then(|| {
+                        self.joining(&channel, now);
+
+                        RejoinStep::Join(channel)
+                    })
+            }
+            Command::NOTICE(channel, _) => {
+                let refused = message.tags.as_ref().is_some_and(|tags| {
+                    tags.iter().any(|tag| {
+                        tag.0 == "msg-id"
+                            && tag
+                                .1
+                                .as_deref()
+                                .is_some_and(|id| JOIN_REFUSED.contains(&id))
+                    })
+                });
+
+                let channel = normalize(channel);
+
+                (refused && self.pending.remove(&channel).is_some())
+                    .then_some(RejoinStep::GiveUp(channel))
+            }
+            _ => None,
+        }
+    }
+
+    /// Join the channels again that Twitch did not confirm in time,
+    /// and give up on those that were tried too many times.
+    pub fn due(&mut self, now: Instant) -> Vec<RejoinStep> {
+        let mut steps = vec![];
+
+        self.pending.retain(|channel, pending| {
+            if now.duration_since(pending.sent_at) < JOIN_TIMEOUT {
+                return true;
+            }
+
+            if pending.attempts >= MAXIMUM_JOIN_ATTEMPTS {
+                steps.push(RejoinStep::GiveUp(channel.clone()));
+
+                return false;
+            }
+
+            pending.sent_at = now;
+            pending.attempts += 1;
+
+            steps.push(RejoinStep::Join(channel.clone()));
+
+            true
+        });
+
+        steps.sort();
+
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(raw: &str) -> Message {
+        raw.parse().unwrap()
+    }
+
+    #[test]
+    fn test_rejoin_after_unexpected_part() {
+        let mut rejoins = Rejoins::default();
+        let now = Instant::now();
+        let joined = vec!["xithrius".to_string()];
+
+        assert_eq!(
+            rejoins.observe(
+                &message(":human!human@human.tmi.twitch.tv PART #xithrius"),
+                "human",
+                &joined,
+                now
+            ),
+            Some(RejoinStep::Join("xithrius".to_string()))
+        );
+
+        assert_eq!(
+            rejoins.observe(
+                &message(":human!human@human.tmi.twitch.tv JOIN #xithrius"),
+                "human",
+                &joined,
+                now
+            ),
+            None
+        );
+        assert!(rejoins.due(now + JOIN_TIMEOUT).is_empty());
+
+        // Leaving a channel on purpose removes it from the joined ones first
+        assert_eq!(
+            rejoins.observe(
+                &message(":human!human@human.tmi.twitch.tv PART #twitch"),
+                "human",
+                &joined,
+                now
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_give_up_after_attempts() {
+        let mut rejoins = Rejoins::default();
+        let mut now = Instant::now();
+
+        rejoins.joining("xithrius", now);
+
+        for _ in 1..MAXIMUM_JOIN_ATTEMPTS {
+            now += JOIN_TIMEOUT;
+
+            assert_eq!(
+                rejoins.due(now),
+                vec![RejoinStep::Join("xithrius".to_string())]
+            );
+        }
+
+        now += JOIN_TIMEOUT;
+
+        assert_eq!(
+            rejoins.due(now),
+            vec![RejoinStep::GiveUp("xithrius".to_string())]
+        );
+        assert!(rejoins.due(now + JOIN_TIMEOUT).is_empty());
+    }
+
+    #[test]
+    fn test_give_up_when_refused() {
+        let mut rejoins = Rejoins::default();
+        let now = Instant::now();
+
+        rejoins.joining("xithrius", now);
+
+        assert_eq!(
+            rejoins.observe(
+                &message(
+                    "@msg-id=msg_channel_suspended :tmi.twitch.tv NOTICE #xithrius :This channel has been suspended."
+                ),
+                "human",
+                &[],
+                now
+            ),
+            Some(RejoinStep::GiveUp("xithrius".to_string()))
+        );
+        assert!(rejoins.due(now + JOIN_TIMEOUT).is_empty());
+    }
+
+    #[test]
+    fn test_confirm_mixed_case_join() {
+        let mut rejoins = Rejoins::default();
+        let now = Instant::now();
+
+        rejoins.joining("#Xithrius", now);
+
+        // Twitch confirms joins with the channel in lowercase
+        rejoins.observe(
+            &message(":human!human@human.tmi.twitch.tv JOIN #xithrius"),
+            "human",
+            &[],
+            now,
+        );
+
+        assert!(rejoins.due(now + JOIN_TIMEOUT).is_empty());
+
+        rejoins.joining("Xithrius", now);
+        rejoins.parted("#XITHRIUS");
+
+        assert!(rejoins.due(now + JOIN_TIMEOUT).is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+use color_eyre::{Result, eyre::bail};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    handlers::config::Matching,
+    twitch::{
+        budget::{Priority, send_helix},
+        helix::{ensure_requestable, track_request},
+        oauth::{get_channel_id, get_twitch_client, get_twitch_client_id},
+    },
+    utils::matching::matches_any,
+};
+
+/// A custom channel point reward.
+/// <https://dev.twitch.tv/docs/api/reference/#get-custom-reward>
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Reward {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub prompt: String,
+    pub cost: u64,
+    /// If the reward is visible to viewers at all.
+    pub is_enabled: bool,
+    /// If the reward is visible, but cannot be redeemed.
+    pub is_paused: bool,
+}
+
+impl Reward {
+    pub fn matches(&self, query: &str, matching: Matching) -> bool {
+        matches_any(matching, &[&self.title, &self.prompt], query)
+    }
+
+    pub const fn status(&self) -> &'static str {
+        if !self.is_enabled {
+            "disabled"
+        } else if self.is_paused {
+            "paused"
+        } else {
+            "enabled"
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RewardList {
+    data: Vec<Reward>,
+}
+
+/// The rewards of a channel, from the cheapest to the most expensive.
+///
+/// Helix only lists the rewards of the channel that the token belongs to,
+/// so the rewards of other channels cannot be retrieved.
+/// Requires the `channel:read:redemptions` or `channel:manage:redemptions` scope.
+pub async fn get_rewards(token: Option<&str>, channel: &str) -> Result<Vec<Reward>> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let user_id = &get_twitch_client_id(None).await?.user_id;
+    let broadcaster_id = track_request(get_channel_id(&client, channel, Priority::User).await)?;
+
+    if broadcaster_id.to_string() != *user_id {
+        bail!("Twitch only lists the channel point rewards of your own channel.");
+    }
+
+    let response = send_helix(
+        "helix/channel_points/custom_rewards",
+        Priority::User,
+        client.get(format!(
+            "https://api.twitch.tv/helix/channel_points/custom_rewards?broadcaster_id={broadcaster_id}"
+        )),
+    )
+    .await;
+
+    let mut rewards = track_request(response)?
+        .error_for_status()?
+        .json::<RewardList>()
+        .await?
+        .data;
+
+    rewards.sort_by_key(|r| r.cost);
+
+    Ok(rewards)
+}
+
+/// Pause or resume a reward of the current user's channel.
+///
+/// Twitch only allows this for rewards created with the same client ID as the token.
+/// Requires the `channel:manage:redemptions` scope.
+/// <https://dev.twitch.tv/docs/api/reference/#update-custom-reward>
+pub async fn set_reward_paused(token: Option<&str>, reward_id: &str, paused: bool) -> Result<()> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let broadcaster_id = &get_twitch_client_id(None).await?.user_id;
+
+    let response = send_helix(
+        "helix/channel_points/custom_rewards",
+        Priority::User,
+        client
+            .patch(format!(
+                "https://api.twitch.tv/helix/channel_points/custom_rewards?broadcaster_id={broadcaster_id}&id={reward_id}"
+            ))
+            .json(&json!({ "is_paused": paused })),
+    )
+    .await;
+
+    track_request(response)?.error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reward_list() {
+        let rewards = serde_json::from_str::<RewardList>(
+            r#"{"data": [{"id": "a", "title": "Hydrate", "prompt": "Drink water", "cost": 100,
+                "is_enabled": true, "is_paused": true, "is_in_stock": true}]}"#,
+        )
+        .unwrap()
+        .data;
+
+        assert_eq!(rewards.len(), 1);
+        assert_eq!(rewards[0].status(), "paused");
+        assert!(rewards[0].matches("water", Matching::Substring));
+        assert!(!rewards[0].matches("song", Matching::Substring));
+    }
+}
@@ -0,0 +1,233 @@
+use std::{collections::HashSet, time::Duration};
+
+use chrono::{DateTime, Local, TimeDelta};
+use color_eyre::Result;
+use log::debug;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::{
+    sync::{
+        broadcast::{Receiver, error::RecvError},
+        mpsc::Sender,
+    },
+    time::interval,
+};
+
+use crate::{
+    handlers::{config::CompleteConfig, data::TwitchToTerminalAction},
+    twitch::{
+        TwitchAction,
+        budget::{Priority, send_helix},
+        cache::{CacheKind, cached},
+        channels::get_live_following,
+        helix::{ensure_requestable, track_request},
+        oauth::get_twitch_client,
+    },
+    utils::notifications::{NotificationKind, notify},
+};
+
+/// How often the live status of the current channel is checked.
+const LIVE_STATUS_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+struct StreamList {
+    data: Vec<LiveStream>,
+}
+
+/// A channel that is currently live.
+/// <https://dev.twitch.tv/docs/api/reference/#get-streams>
+#[derive(Deserialize, Debug, Clone)]
+pub struct LiveStream {
+    pub user_login: String,
+    pub game_name: String,
+    pub title: String,
+    pub viewer_count: u64,
+    /// When the stream started, in RFC3339 format.
+    pub started_at: String,
+}
+
+impl LiveStream {
+    /// How long the stream has been live for.
+    pub fn uptime(&self, now: DateTime<Local>) -> Option<TimeDelta> {
+        DateTime::parse_from_rfc3339(&self.started_at)
+            .ok()
+            .map(|started_at| now.fixed_offset() - started_at)
+    }
+}
+
+/// The stream of a channel, if the channel is live.
+pub async fn get_live_stream(client: &Client, channel: &str) -> Result<Option<LiveStream>> {
+    let request = client.get(format!(
+        "https://api.twitch.tv/helix/streams?user_login={channel}"
+    ));
+
+    cached(CacheKind::Streams, channel, async move {
+        Ok(send_helix("helix/streams", Priority::Background, request)
+            .await?
+            .error_for_status()?
+            .json::<StreamList>()
+            .await?
+            .data
+            .into_iter()
+            .next())
+    })
+    .await
+}
+
+/// The channels followed by a user that are currently live.
+/// <https://dev.twitch.tv/docs/api/reference/#get-followed-streams>
+pub async fn get_followed_streams(client: &Client, user_id: &str) -> Result<Vec<LiveStream>> {
+    Ok(send_helix(
+        "helix/streams/followed",
+        Priority::User,
+        client.get(format!(
+            "https://api.twitch.tv/helix/streams/followed?user_id={user_id}&first=100"
+        )),
+    )
+    .await?
+    .error_for_status()?
+    .json::<StreamList>()
+    .await?
+    .data)
+}
+
+async fn send_live_status(
+    token: Option<&str>,
+    channel: &str,
+    tx: &Sender<TwitchToTerminalAction>,
+) -> Result<()> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let stream = track_request(get_live_stream(&client, channel).await)?;
+
+    tx.send(TwitchToTerminalAction::LiveStatus(
+        channel.to_string(),
+        stream,
+    ))
+    .await
+    .unwrap();
+
+    Ok(())
+}
+
+/// Keep checking if the current channel is live, checking again right away when the channel changes.
+pub async fn live_status(
+    config: CompleteConfig,
+    tx: Sender<TwitchToTerminalAction>,
+    mut rx: Receiver<TwitchAction>,
+) {
+    let mut channel = config.twitch.channel.clone();
+
+    loop {
+        if !channel.is_empty() {
+            if let Err(err) = send_live_status(config.twitch.token.as_deref(), &channel, &tx).await
+            {
+                debug!("Unable to check if {channel} is live: {err}");
+            }
+        }
+
+        tokio::select! {
+            () = tokio::time::sleep(LIVE_STATUS_INTERVAL) => {}
+            action = rx.recv() => match action {
+                Ok(TwitchAction::Join(new_channel)) => channel = new_channel,
+                Err(RecvError::Closed) => return,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The streams of channels that were not live when they were last checked.
+fn newly_live<'a>(
+    previous: &'a HashSet<String>,
+    streams: &'a [LiveStream],
+) -> impl Iterator<Item = &'a LiveStream> {
+    streams
+        .iter()
+        .filter(|stream| !previous.contains(&stream.user_login))
+}
+
+/// Notify the user when followed channels go live.
+/// Channels that were already live when this started are not notified about.
+pub async fn notify_followed_live(config: CompleteConfig) {
+    let mut check_interval = interval(Duration::from_secs(
+        config.notifications.live_interval.max(1),
+    ));
+
+    let mut live: Option<HashSet<String>> = None;
+
+    loop {
+        check_interval.tick().await;
+
+        let streams = match ensure_requestable() {
+            Ok(()) => track_request(get_live_following(&config.twitch).await),
+            Err(err) => Err(err),
+        };
+
+        let streams = match streams {
+            Ok(streams) => streams,
+            Err(err) => {
+                debug!("Unable to check if followed channels went live: {err}");
+                continue;
+            }
+        };
+
+        if let Some(previous) = &live {
+            for stream in newly_live(previous, &streams) {
+                notify(
+                    &config.notifications,
+                    NotificationKind::Live,
+                    &format!("{} is live", stream.user_login),
+                    &format!("{}: {}", stream.game_name, stream.title),
+                );
+            }
+        }
+
+        live = Some(
+            streams
+                .into_iter()
+                .map(|stream| stream.user_login)
+                .collect(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(user_login: &str) -> LiveStream {
+        LiveStream {
+            user_login: user_login.to_string(),
+            game_name: "Factorio".to_string(),
+            title: "Building".to_string(),
+            viewer_count: 10,
+            started_at: "2024-03-09T10:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_uptime() {
+        let stream = stream("xithrius");
+
+        let now = DateTime::parse_from_rfc3339("2024-03-09T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Local);
+
+        assert_eq!(stream.uptime(now), Some(TimeDelta::minutes(150)));
+    }
+
+    #[test]
+    fn test_newly_live() {
+        let previous = HashSet::from(["xithrius".to_string()]);
+        let streams = [stream("xithrius"), stream("twitch")];
+
+        assert_eq!(
+            newly_live(&previous, &streams)
+                .map(|stream| stream.user_login.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["twitch"]
+        );
+    }
+}
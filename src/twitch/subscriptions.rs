@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+/// How a subscription is paid for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubPlan {
+    Prime,
+    Tier(u8),
+}
+
+impl SubPlan {
+    /// Read the `msg-param-sub-plan` tag, such as `Prime` or `2000` for tier 2.
+    fn from_tag(value: &str) -> Self {
+        match value {
+            "Prime" => Self::Prime,
+            "2000" => Self::Tier(2),
+            "3000" => Self::Tier(3),
+            _ => Self::Tier(1),
+        }
+    }
+}
+
+/// A new subscription, or a subscription being shared again with the months it has lasted.
+/// <https://dev.twitch.tv/docs/irc/tags/#usernotice-tags>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subscription {
+    /// The channel that was subscribed to, if any.
+    pub channel: Option<String>,
+    pub user: String,
+    pub plan: SubPlan,
+    /// The total months subscribed, only set for resubscriptions.
+    pub months: Option<u64>,
+    /// The months subscribed in a row, if the user chose to share them.
+    pub streak: Option<u64>,
+    /// What the user wrote along with their resubscription.
+    pub message: Option<String>,
+}
+
+impl Subscription {
+    /// Read a subscription from the tags and text of a `USERNOTICE`, if it is one.
+    pub fn from_tags(
+        channel: Option<String>,
+        tags: &HashMap<&str, &str>,
+        message: Option<&str>,
+    ) -> Option<Self> {
+        let resub = match tags.get("msg-id").copied() {
+            Some("sub") => false,
+            Some("resub") => true,
+            _ => return None,
+        };
+
+        let number = |tag: &str| tags.get(tag).and_then(|value| value.parse::<u64>().ok());
+
+        let shares_streak = tags.get("msg-param-should-share-streak") == Some(&"1");
+
+        Some(Self {
+            channel,
+            user: tags
+                .get("display-name")
+                .or_else(|| tags.get("login"))
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            plan: SubPlan::from_tag(tags.get("msg-param-sub-plan").copied().unwrap_or_default()),
+            months: number("msg-param-cumulative-months").filter(|_| resub),
+            streak: number("msg-param-streak-months").filter(|_| shares_streak),
+            message: message
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .map(ToString::to_string),
+        })
+    }
+
+    /// The line shown in chat, such as `Human resubscribed at tier 1 for 12 months, 5 in a row: hi`.
+    pub fn summary(&self) -> String {
+        let plan = match self.plan {
+            SubPlan::Prime => "with Prime".to_string(),
+            SubPlan::Tier(tier) => format!("at tier {tier}"),
+        };
+
+        let mut summary = self.months.map_or_else(
+            || format!("{} subscribed {plan}", self.user),
+            |months| format!("{} resubscribed {plan} for {months} months", self.user),
+        );
+
+        if let Some(streak) = self.streak {
+            summary.push_str(&format!(", {streak} in a row"));
+        }
+
+        match &self.message {
+            Some(message) => format!("{summary}: {message}"),
+            None => summary,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_from_tags() {
+        let tags = HashMap::from([
+            ("msg-id", "sub"),
+            ("display-name", "Human"),
+            ("msg-param-sub-plan", "Prime"),
+            ("msg-param-cumulative-months", "1"),
+        ]);
+
+        let sub = Subscription::from_tags(None, &tags, None).unwrap();
+
+        assert_eq!(sub.months, None);
+        assert_eq!(sub.summary(), "Human subscribed with Prime");
+
+        let tags = HashMap::from([
+            ("msg-id", "resub"),
+            ("display-name", "Human"),
+            ("msg-param-sub-plan", "2000"),
+            ("msg-param-cumulative-months", "12"),
+            ("msg-param-should-share-streak", "1"),
+            ("msg-param-streak-months", "5"),
+        ]);
+
+        assert_eq!(
+            Subscription::from_tags(None, &tags, Some("hello there "))
+                .unwrap()
+                .summary(),
+            "Human resubscribed at tier 2 for 12 months, 5 in a row: hello there"
+        );
+
+        let tags = HashMap::from([("msg-id", "raid")]);
+
+        assert_eq!(Subscription::from_tags(None, &tags, None), None);
+    }
+}
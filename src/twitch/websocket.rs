@@ -0,0 +1,577 @@
+//! A small WebSocket client, only supporting what is needed to talk to Twitch chat over port 443,
+//! and to receive events from EventSub.
+//! <https://datatracker.ietf.org/doc/html/rfc6455>
+
+use std::{
+    io::{Error, ErrorKind, Result},
+    mem,
+    time::Duration,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use futures::{StreamExt, stream::BoxStream};
+use ring::{
+    digest::{SHA1_FOR_LEGACY_USE_ONLY, digest},
+    rand::{SecureRandom, SystemRandom},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf, split},
+    net::TcpStream,
+    sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+    time::timeout,
+};
+use tokio_native_tls::{TlsConnector, TlsStream, native_tls};
+
+/// Appended to the handshake key to prove that the server understood the handshake.
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// Twitch sends a PING every few minutes, so a connection this quiet is considered dead.
+const READ_TIMEOUT: Duration = Duration::from_secs(6 * 60);
+/// Frames larger than this are not expected from Twitch, and are treated as a broken connection.
+const MAXIMUM_FRAME_LENGTH: u64 = 1024 * 1024;
+/// The same goes for messages split over many frames, which are limited as a whole.
+const MAXIMUM_MESSAGE_LENGTH: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Opcode {
+    Continuation,
+    Text,
+    Binary,
+    Close,
+    Ping,
+    Pong,
+}
+
+impl Opcode {
+    const fn from_u8(byte: u8) -> Option<Self> {
+        match byte {
+            0x0 => Some(Self::Continuation),
+            0x1 => Some(Self::Text),
+            0x2 => Some(Self::Binary),
+            0x8 => Some(Self::Close),
+            0x9 => Some(Self::Ping),
+            0xA => Some(Self::Pong),
+            _ => None,
+        }
+    }
+
+    const fn as_u8(self) -> u8 {
+        match self {
+            Self::Continuation => 0x0,
+            Self::Text => 0x1,
+            Self::Binary => 0x2,
+            Self::Close => 0x8,
+            Self::Ping => 0x9,
+            Self::Pong => 0xA,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Frame {
+    fin: bool,
+    opcode: Opcode,
+    payload: Vec<u8>,
+}
+
+type Stream = TlsStream<TcpStream>;
+
+fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0; N];
+
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| Error::other("Unable to generate random bytes."))?;
+
+    Ok(bytes)
+}
+
+/// The value the server has to respond with in `Sec-WebSocket-Accept`.
+fn accept_key(key: &str) -> String {
+    STANDARD.encode(digest(
+        &SHA1_FOR_LEGACY_USE_ONLY,
+        format!("{key}{HANDSHAKE_GUID}").as_bytes(),
+    ))
+}
+
+/// Frames sent by a client always have to be masked.
+fn encode_frame(opcode: Opcode, payload: &[u8], mask: [u8; 4]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 14);
+
+    frame.push(0x80 | opcode.as_u8());
+
+    match payload.len() {
+        length @ 0..=125 => frame.push(0x80 | length as u8),
+        length @ 126..=0xFFFF => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(length as u16).to_be_bytes());
+        }
+        length => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+    }
+
+    frame.extend_from_slice(&mask);
+    frame.extend(
+        payload
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| byte ^ mask[i % 4]),
+    );
+
+    frame
+}
+
+/// Decode the first frame in the buffer, returning it with its length in bytes,
+/// or `None` if the buffer does not contain a whole frame yet.
+fn decode_frame(buffer: &[u8]) -> Result<Option<(Frame, usize)>> {
+    let [first, second, ..] = *buffer else {
+        return Ok(None);
+    };
+
+    let opcode = Opcode::from_u8(first & 0x0F)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Unknown WebSocket opcode."))?;
+    let masked = second & 0x80 != 0;
+
+    let (length, mut offset) = match second & 0x7F {
+        126 => match buffer.get(2..4) {
+            Some(bytes) => (u64::from(u16::from_be_bytes([bytes[0], bytes[1]])), 4),
+            None => return Ok(None),
+        },
+        127 => match buffer.get(2..10).and_then(|bytes| bytes.try_into().ok()) {
+            Some(bytes) => (u64::from_be_bytes(bytes), 10),
+            None => return Ok(None),
+        },
+        length => (u64::from(length), 2),
+    };
+
+    if length > MAXIMUM_FRAME_LENGTH {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "WebSocket frame is too large.",
+        ));
+    }
+
+    let mask = if masked {
+        let Some(mask) = buffer.get(offset..offset + 4) else {
+            return Ok(None);
+        };
+        offset += 4;
+
+        Some([mask[0], mask[1], mask[2], mask[3]])
+    } else {
+        None
+    };
+
+    // The length is capped above, so it fits in a usize
+    let end = offset + length as usize;
+
+    let Some(payload) = buffer.get(offset..end) else {
+        return Ok(None);
+    };
+
+    let payload = mask.map_or_else(
+        || payload.to_vec(),
+        |mask| {
+            payload
+                .iter()
+                .enumerate()
+                .map(|(i, byte)| byte ^ mask[i % 4])
+                .collect()
+        },
+    );
+
+    Ok(Some((
+        Frame {
+            fin: first & 0x80 != 0,
+            opcode,
+            payload,
+        },
+        end,
+    )))
+}
+
+/// Puts text messages back together from the frames they were split into, skipping binary messages.
+#[derive(Default)]
+struct Message {
+    payload: Vec<u8>,
+    /// The kind of message that continuation frames are part of, if one was started but not finished.
+    continuing: Option<Opcode>,
+}
+
+impl Message {
+    /// Add a text, binary, or continuation frame, giving the text message once all of it has arrived.
+    fn push(&mut self, frame: Frame) -> Result<Option<String>> {
+        let opcode = if frame.opcode == Opcode::Continuation {
+            self.continuing.ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    "WebSocket continuation frame without a message to continue.",
+                )
+            })?
+        } else {
+            self.payload.clear();
+            frame.opcode
+        };
+
+        self.continuing = (!frame.fin).then_some(opcode);
+
+        if opcode != Opcode::Text {
+            return Ok(None);
+        }
+
+        if self.payload.len() + frame.payload.len() > MAXIMUM_MESSAGE_LENGTH {
+            self.payload.clear();
+            self.continuing = None;
+
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "WebSocket message is too long.",
+            ));
+        }
+
+        self.payload.extend(frame.payload);
+
+        if !frame.fin {
+            return Ok(None);
+        }
+
+        String::from_utf8(mem::take(&mut self.payload))
+            .map(Some)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+}
+
+struct Reader {
+    stream: ReadHalf<Stream>,
+    buffer: Vec<u8>,
+}
+
+impl Reader {
+    async fn fill(&mut self) -> Result<()> {
+        let mut chunk = [0; 4096];
+
+        let read = timeout(READ_TIMEOUT, self.stream.read(&mut chunk))
+            .await
+            .map_err(|_| Error::new(ErrorKind::TimedOut, "WebSocket connection timed out."))??;
+
+        if read == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "WebSocket connection was closed.",
+            ));
+        }
+
+        self.buffer.extend_from_slice(&chunk[..read]);
+
+        Ok(())
+    }
+
+    async fn read_frame(&mut self) -> Result<Frame> {
+        loop {
+            if let Some((frame, length)) = decode_frame(&self.buffer)? {
+                self.buffer.drain(..length);
+                return Ok(frame);
+            }
+
+            self.fill().await?;
+        }
+    }
+
+    /// Read the next text message, answering pings along the way.
+    async fn read_text(&mut self, pongs: &UnboundedSender<Frame>) -> Result<String> {
+        let mut message = Message::default();
+
+        loop {
+            let frame = self.read_frame().await?;
+
+            match frame.opcode {
+                Opcode::Text | Opcode::Binary | Opcode::Continuation => {
+                    if let Some(text) = message.push(frame)? {
+                        return Ok(text);
+                    }
+                }
+                Opcode::Ping => {
+                    let _ = pongs.send(Frame {
+                        fin: true,
+                        opcode: Opcode::Pong,
+                        payload: frame.payload,
+                    });
+                }
+                Opcode::Close => {
+                    return Err(Error::new(
+                        ErrorKind::ConnectionAborted,
+                        "WebSocket connection was closed by the server.",
+                    ));
+                }
+                Opcode::Pong => {}
+            }
+        }
+    }
+}
+
+async fn write_frames(mut stream: WriteHalf<Stream>, mut frames: UnboundedReceiver<Frame>) {
+    while let Some(frame) = frames.recv().await {
+        let Ok(mask) = random_bytes() else {
+            break;
+        };
+
+        let bytes = encode_frame(frame.opcode, &frame.payload, mask);
+
+        if stream.write_all(&bytes).await.is_err() {
+            break;
+        }
+    }
+
+    let _ = stream.shutdown().await;
+}
+
+async fn handshake(stream: &mut Stream, host: &str, path: &str) -> Result<Vec<u8>> {
+    let key = STANDARD.encode(random_bytes::<16>()?);
+
+    let request = format!(
+        "GET {path} HTTP/1.1\r\n\
+         Host: {host}\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Key: {key}\r\n\
+         Sec-WebSocket-Version: 13\r\n\r\n"
+    );
+
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = Vec::new();
+    let mut chunk = [0; 1024];
+
+    let header_end = loop {
+        if let Some(position) = response.windows(4).position(|w| w == b"\r\n\r\n") {
+            break position + 4;
+        }
+
+        if response.len() > 16 * 1024 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "WebSocket handshake response is too large.",
+            ));
+        }
+
+        let read = stream.read(&mut chunk).await?;
+
+        if read == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "Connection closed during the WebSocket handshake.",
+            ));
+        }
+
+        response.extend_from_slice(&chunk[..read]);
+    };
+
+    let headers = String::from_utf8_lossy(&response[..header_end]);
+    let mut lines = headers.lines();
+
+    let status = lines.next().unwrap_or_default();
+
+    if status.split(' ').nth(1) != Some("101") {
+        return Err(Error::new(
+            ErrorKind::ConnectionRefused,
+            format!("WebSocket handshake was refused: {status}"),
+        ));
+    }
+
+    let accepted = lines
+        .filter_map(|line| line.split_once(':'))
+        .any(|(name, value)| {
+            name.eq_ignore_ascii_case("sec-websocket-accept") && value.trim() == accept_key(&key)
+        });
+
+    if !accepted {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "WebSocket handshake was not accepted.",
+        ));
+    }
+
+    // Anything after the headers is already part of the first frames
+    Ok(response.split_off(header_end))
+}
+
+/// Sends text messages through the connection, which is closed once every sender and the stream are dropped.
+#[derive(Clone)]
+pub struct WebSocketSender(UnboundedSender<Frame>);
+
+impl WebSocketSender {
+    pub fn send_text(&self, text: String) -> Result<()> {
+        self.0
+            .send(Frame {
+                fin: true,
+                opcode: Opcode::Text,
+                payload: text.into_bytes(),
+            })
+            .map_err(|_| Error::new(ErrorKind::BrokenPipe, "WebSocket connection is closed."))
+    }
+}
+
+/// Connect to a secure WebSocket server, returning a sender for text messages,
+/// and a stream of the text messages received.
+pub async fn connect(
+    host: &str,
+    port: u16,
+    path: &str,
+) -> Result<(WebSocketSender, BoxStream<'static, Result<String>>)> {
+    let tcp = TcpStream::connect((host, port)).await?;
+
+    let connector = TlsConnector::from(native_tls::TlsConnector::new().map_err(Error::other)?);
+    let mut stream = connector.connect(host, tcp).await.map_err(Error::other)?;
+
+    let buffer = handshake(&mut stream, host, path).await?;
+
+    let (read_half, write_half) = split(stream);
+
+    let (frame_tx, frame_rx) = unbounded_channel::<Frame>();
+    tokio::spawn(write_frames(write_half, frame_rx));
+
+    let reader = Reader {
+        stream: read_half,
+        buffer,
+    };
+
+    let pong_tx = frame_tx.clone();
+
+    let messages = futures::stream::unfold(Some((reader, pong_tx)), |state| async move {
+        let (mut reader, pong_tx) = state?;
+
+        match reader.read_text(&pong_tx).await {
+            Ok(text) => Some((Ok(text), Some((reader, pong_tx)))),
+            // The error ends the stream
+            Err(err) => Some((Err(err), None)),
+        }
+    })
+    .boxed();
+
+    Ok((WebSocketSender(frame_tx), messages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_key() {
+        // Example from the RFC
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn test_frame_round_trip() {
+        for length in [0, 5, 125, 126, 300, 70_000] {
+            let payload = vec![b'a'; length];
+            let encoded = encode_frame(Opcode::Text, &payload, [1, 2, 3, 4]);
+
+            let (frame, decoded_length) = decode_frame(&encoded).unwrap().unwrap();
+
+            assert_eq!(decoded_length, encoded.len());
+            assert_eq!(
+                frame,
+                Frame {
+                    fin: true,
+                    opcode: Opcode::Text,
+                    payload
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_partial_frame() {
+        let encoded = encode_frame(Opcode::Text, b"PING :tmi.twitch.tv", [9, 9, 9, 9]);
+
+        assert!(decode_frame(&encoded[..1]).unwrap().is_none());
+        assert!(
+            decode_frame(&encoded[..encoded.len() - 1])
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_unmasked_server_frame() {
+        let (frame, length) = decode_frame(b"\x81\x02hi\x89\x00").unwrap().unwrap();
+
+        assert_eq!(length, 4);
+        assert_eq!(frame.opcode, Opcode::Text);
+        assert_eq!(frame.payload, b"hi");
+    }
+
+    #[test]
+    fn test_fragmented_messages() {
+        let frame = |fin, opcode, payload: &[u8]| Frame {
+            fin,
+            opcode,
+            payload: payload.to_vec(),
+        };
+
+        let mut message = Message::default();
+
+        assert_eq!(
+            message.push(frame(false, Opcode::Text, b"PING ")).unwrap(),
+            None
+        );
+        assert_eq!(
+            message
+                .push(frame(true, Opcode::Continuation, b":tmi.twitch.tv"))
+                .unwrap(),
+            Some("PING :tmi.twitch.tv".to_string())
+        );
+
+        // Binary messages are skipped along with their continuation frames
+        assert_eq!(
+            message.push(frame(false, Opcode::Binary, b"\x00")).unwrap(),
+            None
+        );
+        assert_eq!(
+            message
+                .push(frame(true, Opcode::Continuation, b"\x01"))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            message.push(frame(true, Opcode::Text, b"hi")).unwrap(),
+            Some("hi".to_string())
+        );
+
+        assert!(
+            message
+                .push(frame(true, Opcode::Continuation, b"hi"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_message_length() {
+        let frame = |fin, opcode, length| Frame {
+            fin,
+            opcode,
+            payload: vec![b'a'; length],
+        };
+
+        let mut message = Message::default();
+
+        message
+            .push(frame(false, Opcode::Text, MAXIMUM_MESSAGE_LENGTH / 2))
+            .unwrap();
+        message
+            .push(frame(
+                false,
+                Opcode::Continuation,
+                MAXIMUM_MESSAGE_LENGTH / 2,
+            ))
+            .unwrap();
+
+        // Each frame is short, but the message they make is too long
+        assert!(message.push(frame(true, Opcode::Continuation, 1)).is_err());
+    }
+}
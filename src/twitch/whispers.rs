@@ -0,0 +1,98 @@
+use chrono::{DateTime, Local};
+use color_eyre::Result;
+use serde_json::json;
+
+use crate::{
+    handlers::config::Matching,
+    twitch::{
+        budget::{Priority, send_helix},
+        helix::{ensure_requestable, track_request},
+        oauth::{get_channel_id, get_twitch_client, get_twitch_client_id},
+    },
+    utils::matching::matches_any,
+};
+
+/// A direct message, either received from or sent to another user.
+#[derive(Debug, Clone)]
+pub struct Whisper {
+    pub time_sent: DateTime<Local>,
+    /// The other user in the conversation.
+    pub user: String,
+    pub message: String,
+    /// If the whisper was sent by the current user.
+    pub outgoing: bool,
+}
+
+impl Whisper {
+    pub fn new(user: String, message: String, outgoing: bool) -> Self {
+        Self {
+            time_sent: Local::now(),
+            user,
+            message,
+            outgoing,
+        }
+    }
+
+    pub fn matches(&self, query: &str, matching: Matching) -> bool {
+        matches_any(matching, &[&self.user, &self.message], query)
+    }
+}
+
+/// Split the arguments of a `/w <user> <message>` command.
+pub fn parse_whisper_command(message: &str) -> Option<(&str, &str)> {
+    let arguments = message
+        .strip_prefix("/w ")
+        .or_else(|| message.strip_prefix("/whisper "))?;
+
+    let (user, message) = arguments.trim_start().split_once(' ')?;
+    let user = user.trim_start_matches('@');
+    let message = message.trim();
+
+    (!user.is_empty() && !message.is_empty()).then_some((user, message))
+}
+
+/// Send a whisper through Helix, as whispers cannot be sent through IRC anymore.
+/// Requires the `user:manage:whispers` scope.
+/// <https://dev.twitch.tv/docs/api/reference/#send-whisper>
+pub async fn send_whisper(token: Option<&str>, user: &str, message: &str) -> Result<()> {
+    ensure_requestable()?;
+
+    let client = get_twitch_client(token).await?;
+    let from_user_id = &get_twitch_client_id(None).await?.user_id;
+    let to_user_id = track_request(get_channel_id(&client, user, Priority::User).await)?;
+
+    let response = send_helix(
+        "helix/whispers",
+        Priority::User,
+        client
+            .post(format!(
+                "https://api.twitch.tv/helix/whispers?from_user_id={from_user_id}&to_user_id={to_user_id}"
+            ))
+            .json(&json!({ "message": message })),
+    )
+    .await;
+
+    track_request(response)?.error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_whisper_command() {
+        assert_eq!(
+            parse_whisper_command("/w @xithrius hello there"),
+            Some(("xithrius", "hello there"))
+        );
+        assert_eq!(
+            parse_whisper_command("/whisper xithrius hi"),
+            Some(("xithrius", "hi"))
+        );
+        assert_eq!(parse_whisper_command("/w xithrius"), None);
+        assert_eq!(parse_whisper_command("/w xithrius   "), None);
+        assert_eq!(parse_whisper_command("hello"), None);
+    }
+}
@@ -0,0 +1,91 @@
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    widgets::{Cell, Clear, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    ui::components::{Component, utils::centered_rect},
+    utils::{
+        about::about,
+        styles::{BOLD_STYLE, title_style},
+        text::{TitleStyle, title_line},
+    },
+};
+
+/// The version and build of the application, along with what was detected about the terminal, for bug reports.
+#[derive(Debug, Clone)]
+pub struct AboutWidget {
+    config: SharedCompleteConfig,
+    focused: bool,
+}
+
+impl AboutWidget {
+    pub const fn new(config: SharedCompleteConfig) -> Self {
+        Self {
+            config,
+            focused: false,
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+    }
+}
+
+impl Component for AboutWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let rows = about();
+
+        let r = area.map_or_else(
+            || centered_rect(60, 40, rows.len() as u16 + 2, f.area()),
+            |a| a,
+        );
+
+        let rows = rows
+            .into_iter()
+            .map(|(name, value)| {
+                Row::new(vec![Cell::from(name).style(*BOLD_STYLE), Cell::from(value)])
+            })
+            .collect::<Vec<Row>>();
+
+        let title_binding = [
+            TitleStyle::Single("About"),
+            TitleStyle::Combined("Esc", "close"),
+        ];
+
+        let table = Table::new(rows, [Constraint::Length(18), Constraint::Min(20)])
+            .column_spacing(2)
+            .block(
+                self.config
+                    .borrow()
+                    .block(Widget::Popup)
+                    .title(title_line(&title_binding, title_style())),
+            );
+
+        f.render_widget(Clear, r);
+        f.render_widget(table, r);
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => self.toggle_focus(),
+                Key::Char('q') => return Some(TerminalAction::Quit),
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,95 @@
+use tui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem},
+};
+
+use crate::{
+    handlers::{
+        app::SharedActivity,
+        config::{SharedCompleteConfig, Widget},
+    },
+    utils::{
+        styles::{NO_COLOR, title_style},
+        text::{TitleStyle, title_line},
+        time::format_time,
+    },
+};
+
+/// The width of the activity sidebar, including its borders.
+pub const ACTIVITY_WIDTH: u16 = 32;
+
+/// A sidebar of users joining and leaving the current channel, kept out of the chat itself.
+pub struct ActivityWidget {
+    config: SharedCompleteConfig,
+    activity: SharedActivity,
+    visible: bool,
+}
+
+impl ActivityWidget {
+    pub const fn new(config: SharedCompleteConfig, activity: SharedActivity) -> Self {
+        Self {
+            config,
+            activity,
+            visible: false,
+        }
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle_visibility(&mut self) {
+        // Without the membership capability, there is nothing to show
+        self.visible = !self.visible && self.config.borrow().twitch.membership_enabled();
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect) {
+        let config = self.config.borrow();
+        let activity = self.activity.borrow();
+
+        let height = area.height.saturating_sub(2) as usize;
+
+        // Most recent events at the top
+        let items = activity
+            .iter()
+            .rev()
+            .filter(|event| event.channel == config.twitch.channel)
+            .take(height)
+            .map(|event| {
+                let (symbol, color) = if event.joined {
+                    ("+", Color::Green)
+                } else {
+                    ("-", Color::Red)
+                };
+
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", format_time(&event.time_received, &config.frontend)),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(
+                        symbol,
+                        if *NO_COLOR {
+                            Style::default()
+                        } else {
+                            Style::default().fg(color)
+                        },
+                    ),
+                    Span::raw(format!(" {}", event.user)),
+                ]))
+            });
+
+        let title_binding = [TitleStyle::Single("Activity")];
+
+        let list = List::new(items).block(
+            config
+                .block(Widget::Chat)
+                .title(title_line(&title_binding, title_style())),
+        );
+
+        f.render_widget(list, area);
+    }
+}
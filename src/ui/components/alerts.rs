@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+
+use tui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+
+use crate::{
+    handlers::config::{SharedCompleteConfig, Widget},
+    twitch::alerts::Alert,
+    utils::{
+        styles::{NO_COLOR, title_style},
+        text::{TitleStyle, title_line},
+        time::format_time,
+    },
+};
+
+/// Critical alerts, shown in a banner along the top of chat until they are acknowledged.
+pub struct AlertsWidget {
+    config: SharedCompleteConfig,
+    /// Alerts that have not been acknowledged yet, the oldest first.
+    critical: VecDeque<Alert>,
+}
+
+impl AlertsWidget {
+    pub const fn new(config: SharedCompleteConfig) -> Self {
+        Self {
+            config,
+            critical: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, alert: Alert) {
+        self.critical.push_back(alert);
+    }
+
+    pub fn is_shown(&self) -> bool {
+        !self.critical.is_empty()
+    }
+
+    /// Acknowledge the oldest alert, showing the next one if there are more.
+    pub fn acknowledge(&mut self) {
+        self.critical.pop_front();
+    }
+
+    pub fn draw_banner(&self, f: &mut Frame, area: Rect) {
+        let Some(alert) = self.critical.front() else {
+            return;
+        };
+
+        if area.width < 10 || area.height < 5 {
+            return;
+        }
+
+        let config = self.config.borrow();
+
+        let style = if *NO_COLOR {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+                .fg(alert.severity.color())
+                .add_modifier(Modifier::BOLD)
+        };
+
+        let text = format!(
+            "{} #{}: {}",
+            format_time(&alert.time_received, &config.frontend),
+            alert.channel,
+            alert.message
+        );
+
+        let count = format!("1 / {}", self.critical.len());
+
+        let title_binding = [
+            TitleStyle::Single("Alert"),
+            TitleStyle::Single(&count),
+            TitleStyle::Combined("x", "acknowledge"),
+        ];
+
+        let paragraph = Paragraph::new(Line::from(Span::styled(text, style)))
+            .alignment(Alignment::Center)
+            .block(
+                config
+                    .block(Widget::Popup)
+                    .title(title_line(&title_binding, title_style())),
+            );
+
+        let rect = Rect::new(area.x + 1, area.y + 1, area.width - 2, 3);
+
+        f.render_widget(Clear, rect);
+        f.render_widget(paragraph, rect);
+    }
+}
@@ -0,0 +1,158 @@
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    widgets::{Cell, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        app::SharedAutoMod,
+        config::{SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    twitch::{TwitchAction, automod::HeldMessage},
+    ui::components::{
+        Component,
+        utils::{InputWidget, SearchableList, centered_rect, split_input},
+    },
+    utils::{text::TitleStyle, time::format_time},
+};
+
+pub struct AutoModWidget {
+    config: SharedCompleteConfig,
+    automod: SharedAutoMod,
+    focused: bool,
+    search_input: InputWidget<()>,
+    list: SearchableList<HeldMessage>,
+}
+
+impl AutoModWidget {
+    pub fn new(config: SharedCompleteConfig, automod: SharedAutoMod) -> Self {
+        let search_input = InputWidget::new(config.clone(), "Filter", None, None, None);
+        let list = SearchableList::new(config.clone(), Widget::Popup);
+
+        Self {
+            config,
+            automod,
+            focused: false,
+            search_input,
+            list,
+        }
+    }
+
+    /// Approve or deny the selected message, which is removed once Twitch confirms it.
+    fn resolve_selected(&self, allow: bool) -> Option<TerminalAction> {
+        let held = self.list.selected()?.clone();
+
+        Some(TerminalAction::Enter(TwitchAction::ResolveAutoMod(
+            held.message_id,
+            allow,
+        )))
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+
+        if !self.focused {
+            self.search_input.clear();
+            self.list.clear();
+        }
+    }
+}
+
+impl Component for AutoModWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let [r, input_rect] =
+            split_input(area.map_or_else(|| centered_rect(80, 60, 23, f.area()), |a| a));
+
+        let query = self.search_input.to_string();
+        let matching = self.list.matching();
+
+        // The oldest first, as they expire first
+        self.list.set_rows(
+            self.automod
+                .borrow()
+                .iter()
+                .filter(|held| held.matches(&query, matching))
+                .cloned()
+                .collect(),
+            None,
+        );
+
+        let config = self.config.borrow();
+
+        let rows = self
+            .list
+            .rows()
+            .iter()
+            .map(|held| {
+                Row::new(vec![
+                    Cell::from(format_time(&held.held_at, &config.frontend)),
+                    Cell::from(format!("#{}", held.channel)),
+                    Cell::from(format!("{} {}", held.category, held.level)),
+                    Cell::from(held.user.clone()),
+                    Cell::from(held.message.clone()),
+                ])
+            })
+            .collect::<Vec<Row>>();
+
+        drop(config);
+
+        let title = [
+            TitleStyle::Single("AutoMod"),
+            TitleStyle::Combined("y", "approve"),
+            TitleStyle::Combined("n", "deny"),
+        ];
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Length(20),
+                Constraint::Length(14),
+                Constraint::Length(26),
+                Constraint::Min(20),
+            ],
+        );
+
+        self.list.draw_table(f, r, table, &title);
+
+        self.search_input.draw(f, Some(input_rect));
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => {
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
+                    } else {
+                        self.toggle_focus();
+                    }
+                }
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
+                }
+                // Typing goes to the filter until a message is selected
+                Key::Char(c @ ('y' | 'n')) if self.list.selected_index().is_some() => {
+                    return self.resolve_selected(*c == 'y');
+                }
+                _ => {
+                    self.search_input.event(event).await;
+                    self.list.select_first();
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -1,52 +1,89 @@
-use std::fmt::Display;
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use chrono::Local;
+use color_eyre::Result;
 use log::debug;
-use once_cell::sync::Lazy;
 use regex::Regex;
-use tui::{
-    Frame,
-    layout::Rect,
-    prelude::{Alignment, Margin},
-    style::{Color, Modifier, Style},
-    symbols::scrollbar,
-    text::{Line, Span},
-    widgets::{
-        Block, Borders, Clear, List, ListItem, ListState, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, block::Position,
-    },
-};
+use tokio::sync::oneshot::{self, Receiver, error::TryRecvError};
+use tui::{Frame, layout::Rect};
 
-use super::utils::centered_rect;
+use super::utils::{FavoriteMatcher, SearchableList, centered_rect};
 use crate::{
     handlers::{
-        config::SharedCompleteConfig,
+        config::{SharedCompleteConfig, Widget},
+        favorites::{pin_favorites, toggle_favorite},
+        joins::JoinHistory,
         storage::SharedStorage,
         user_input::events::{Event, Key},
     },
     terminal::TerminalAction,
-    twitch::TwitchAction,
+    twitch::{
+        TwitchAction,
+        channels::{get_following, load_cached_following, search_channels},
+        helix::{ensure_requestable, track_request},
+    },
     ui::{
         components::{Component, utils::InputWidget},
         statics::{NAME_MAX_CHARACTERS, NAME_RESTRICTION_REGEX},
     },
-    utils::{
-        styles::TITLE_STYLE,
-        text::{TitleStyle, first_similarity, title_line},
-    },
+    utils::text::{TitleStyle, first_similarity},
 };
 
-static FUZZY_FINDER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
+/// How long typing has to stop for before Twitch is searched for what was typed.
+const SEARCH_DELAY: Duration = Duration::from_millis(500);
+
+/// The channels that what is typed is completed to.
+type SharedCandidates = Rc<RefCell<Vec<String>>>;
+
+/// What was searched for, along with the channels found.
+type Searched = (String, Vec<String>);
+
+/// Every channel in the first list, then the ones in the next lists that were not in an earlier one.
+fn merge_channels(lists: &[&[String]]) -> Vec<String> {
+    let mut merged: Vec<String> = vec![];
+
+    for channel in lists.iter().flat_map(|list| list.iter()) {
+        if !merged.iter().any(|c| c.eq_ignore_ascii_case(channel)) {
+            merged.push(channel.clone());
+        }
+    }
+
+    merged
+}
+
+/// What a request in the background sent back once it is done, which is only taken once.
+fn take_response<T>(request: &mut Option<Receiver<Result<T>>>) -> Option<Result<T>> {
+    let response = match request.as_mut()?.try_recv() {
+        Ok(response) => Some(response),
+        Err(TryRecvError::Empty) => return None,
+        Err(TryRecvError::Closed) => None,
+    };
+
+    *request = None;
+
+    response
+}
 
 pub struct ChannelSwitcherWidget {
     config: SharedCompleteConfig,
     focused: bool,
     storage: SharedStorage,
-    search_input: InputWidget<SharedStorage>,
-    list_state: ListState,
-    filtered_channels: Option<Vec<String>>,
-    vertical_scroll_state: ScrollbarState,
-    vertical_scroll: usize,
+    search_input: InputWidget<SharedCandidates>,
+    list: SearchableList<String>,
+    candidates: SharedCandidates,
+    /// The followed channels, from the cache until they are gotten from Twitch.
+    following: Vec<String>,
+    following_request: Option<Receiver<Result<Vec<String>>>>,
+    /// When something was last typed, to search Twitch once typing stops.
+    typed_at: Instant,
+    /// The channels found by searching Twitch, along with what was searched for.
+    searched: Option<Searched>,
+    search_request: Option<Receiver<Result<Searched>>>,
 }
 
 impl ChannelSwitcherWidget {
@@ -61,80 +98,136 @@ impl ChannelSwitcherWidget {
         let visual_indicator =
             Box::new(|s: String| -> String { format!("{} / {}", s.len(), NAME_MAX_CHARACTERS) });
 
-        let input_suggester = Box::new(|storage: SharedStorage, s: String| -> Option<String> {
-            first_similarity(
-                &storage
-                    .borrow()
-                    .get("channels")
-                    .iter()
-                    .map(ToString::to_string)
-                    .collect::<Vec<String>>(),
-                &s,
-            )
-        });
+        let input_suggester = Box::new(
+            |candidates: SharedCandidates, s: String| -> Option<String> {
+                first_similarity(&candidates.borrow(), &s)
+            },
+        );
+
+        let candidates = SharedCandidates::default();
 
         let search_input = InputWidget::new(
             config.clone(),
             "Channel switcher",
-            Some((storage.clone(), input_validator)),
+            Some((candidates.clone(), input_validator)),
             Some(visual_indicator),
-            Some((storage.clone(), input_suggester)),
+            Some((candidates.clone(), input_suggester)),
         );
 
+        let list = SearchableList::new(config.clone(), Widget::Popup);
+
         Self {
             config,
             focused: false,
             storage,
             search_input,
-            list_state: ListState::default(),
-            filtered_channels: None,
-            vertical_scroll_state: ScrollbarState::default(),
-            vertical_scroll: 0,
+            list,
+            candidates,
+            following: vec![],
+            following_request: None,
+            typed_at: Instant::now(),
+            searched: None,
+            search_request: None,
         }
     }
 
-    fn next(&mut self) {
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                let items = self.storage.borrow().get("channels");
-
-                if i >= items.len() - 1 {
-                    items.len() - 1
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
-        };
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
 
-        self.list_state.select(Some(i));
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
 
-        self.vertical_scroll = self.vertical_scroll.saturating_add(1);
-        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+        if self.focused {
+            self.load_following();
+        }
     }
 
-    fn previous(&mut self) {
-        let i = self
-            .list_state
-            .selected()
-            .map_or(0, |i| if i == 0 { 0 } else { i - 1 });
+    /// Get the followed channels from the cache, or from Twitch if they were never cached.
+    fn load_following(&mut self) {
+        if !self.following.is_empty() || self.following_request.is_some() {
+            return;
+        }
 
-        self.list_state.select(Some(i));
+        if let Some(following) = load_cached_following() {
+            self.following = following;
 
-        self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
-        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+            return;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let twitch_config = self.config.borrow().twitch.clone();
+
+        tokio::spawn(async move {
+            let following = match ensure_requestable() {
+                Ok(()) => track_request(get_following(&twitch_config, None).await),
+                Err(err) => Err(err),
+            };
+
+            let _ = tx.send(following.map(|list| {
+                list.data
+                    .into_iter()
+                    .map(|user| user.broadcaster_login)
+                    .collect()
+            }));
+        });
+
+        self.following_request = Some(rx);
     }
 
-    fn unselect(&mut self) {
-        self.list_state.select(None);
+    /// Take the followed channels and search results that arrived since the last time they were checked.
+    fn receive(&mut self) {
+        match take_response(&mut self.following_request) {
+            Some(Ok(following)) => self.following = following,
+            Some(Err(err)) => debug!("Unable to get the followed channels: {err}"),
+            None => {}
+        }
+
+        match take_response(&mut self.search_request) {
+            Some(Ok(searched)) => self.searched = Some(searched),
+            Some(Err(err)) => debug!("Unable to search for channels: {err}"),
+            None => {}
+        }
     }
 
-    pub const fn is_focused(&self) -> bool {
-        self.focused
+    /// Search Twitch for what was typed, once typing has stopped and nothing else was found.
+    fn search(&mut self, query: &str) {
+        let already_searched = self
+            .searched
+            .as_ref()
+            .is_some_and(|(searched, _)| searched == query);
+
+        if already_searched
+            || self.search_request.is_some()
+            || !self.search_input.is_valid()
+            || self.typed_at.elapsed() < SEARCH_DELAY
+        {
+            return;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let twitch_config = self.config.borrow().twitch.clone();
+        let search = query.to_string();
+
+        tokio::spawn(async move {
+            let channels = match ensure_requestable() {
+                Ok(()) => track_request(search_channels(&twitch_config, &search).await),
+                Err(err) => Err(err),
+            };
+
+            let _ = tx.send(channels.map(|channels| (search, channels)));
+        });
+
+        self.search_request = Some(rx);
     }
 
-    pub fn toggle_focus(&mut self) {
-        self.focused = !self.focused;
+    /// The channels switched to before, then the ones joined in other ways, then the followed ones.
+    fn local_channels(&self) -> Vec<String> {
+        let storage = self.storage.borrow();
+
+        let joined = JoinHistory::from_storage(&storage.get("joins")).ranked(&Local::now());
+
+        merge_channels(&[&storage.get("channels"), &joined, &self.following])
     }
 }
 
@@ -150,107 +243,42 @@ impl Component for ChannelSwitcherWidget {
         // Make sure we have space for the input widget, which has a height of 3.
         r.height -= 3;
 
-        let channels = self.storage.borrow().get("channels");
-
-        let mut items = vec![];
-        let current_input = self.search_input.to_string();
-
-        if current_input.is_empty() {
-            for channel in channels.clone() {
-                items.push(ListItem::new(channel.clone()));
-            }
+        self.receive();
 
-            self.filtered_channels = None;
-        } else {
-            let channel_filter = |c: String| -> Vec<usize> {
-                FUZZY_FINDER
-                    .fuzzy_indices(&c, &current_input)
-                    .map(|(_, indices)| indices)
-                    .unwrap_or_default()
-            };
+        let favorites = self.storage.borrow().get("favorites");
+        let channels = pin_favorites(&favorites, &self.local_channels());
+        let search = self.search_input.to_string();
 
-            let mut matched = vec![];
+        let mut items = self
+            .list
+            .filter(&channels, &search, &FavoriteMatcher(&favorites));
 
-            for channel in channels.clone() {
-                let matched_indices = channel_filter(channel.clone());
+        let mut title = TitleStyle::Single("Channel switcher");
 
-                if matched_indices.is_empty() {
-                    continue;
-                }
+        // Channels that were never switched to or followed are searched for on Twitch
+        if items.is_empty() && !search.is_empty() {
+            self.search(&search);
 
-                let line = channel
-                    .chars()
-                    .enumerate()
-                    .map(|(i, c)| {
-                        if matched_indices.contains(&i) {
-                            Span::styled(c.to_string(), *TITLE_STYLE)
-                        } else {
-                            Span::raw(c.to_string())
-                        }
-                    })
-                    .collect::<Vec<Span>>();
-
-                items.push(ListItem::new(vec![Line::from(line)]));
-                matched.push(channel);
+            if let Some((_, found)) = self.searched.as_ref().filter(|(s, _)| *s == search) {
+                items = self.list.filter(found, "", &FavoriteMatcher(&favorites));
+                title = TitleStyle::Single("Found on Twitch");
+            } else if self.search_request.is_some() {
+                title = TitleStyle::Single("Searching Twitch");
             }
-
-            self.filtered_channels = Some(matched);
         }
 
-        let title_binding = [TitleStyle::Single("Channel switcher")];
-
-        let list = List::new(items.clone())
-            .block(
-                Block::default()
-                    .title(title_line(&title_binding, *TITLE_STYLE))
-                    .borders(Borders::ALL)
-                    .border_type(self.config.borrow().frontend.border_type.clone().into()),
-            )
-            .highlight_style(
-                Style::default()
-                    .bg(Color::LightGreen)
-                    .add_modifier(Modifier::BOLD),
-            );
-
-        f.render_widget(Clear, r);
-        f.render_stateful_widget(list, r, &mut self.list_state);
-
-        self.vertical_scroll_state = self.vertical_scroll_state.content_length(items.len());
-
-        f.render_stateful_widget(
-            Scrollbar::default()
-                .orientation(ScrollbarOrientation::VerticalRight)
-                .symbols(scrollbar::VERTICAL)
-                .begin_symbol(None)
-                .end_symbol(None),
-            r.inner(Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut self.vertical_scroll_state,
-        );
+        *self.candidates.borrow_mut() = match &self.searched {
+            Some((_, found)) => merge_channels(&[&channels, found]),
+            None => channels,
+        };
 
-        let title_binding = format!(
-            "{} / {}",
-            self.list_state.selected().map_or(1, |i| i + 1),
-            self.filtered_channels
-                .as_ref()
-                .map_or(channels.len(), Vec::len)
+        self.list.draw(
+            f,
+            r,
+            items,
+            &[title, TitleStyle::Combined("Alt + p", "favorite")],
         );
 
-        let title = [TitleStyle::Single(&title_binding)];
-
-        let bottom_block = Block::default()
-            .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
-            .border_type(self.config.borrow().frontend.border_type.clone().into())
-            .title(title_line(&title, Style::default()))
-            .title_position(Position::Bottom)
-            .title_alignment(Alignment::Right);
-
-        let rect = Rect::new(r.x, r.bottom() - 1, r.width, 1);
-
-        f.render_widget(bottom_block, rect);
-
         let input_rect = Rect::new(r.x, r.bottom(), r.width, 3);
 
         self.search_input.draw(f, Some(input_rect));
@@ -260,127 +288,73 @@ impl Component for ChannelSwitcherWidget {
         if let Event::Input(key) = event {
             match key {
                 Key::Esc => {
-                    if self.list_state.selected().is_some() {
-                        self.unselect();
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
                     } else {
                         self.toggle_focus();
                         self.search_input.clear();
                     }
                 }
                 Key::Ctrl('p') => panic!("Manual panic triggered by user."),
-                Key::ScrollDown | Key::Down => self.next(),
-                Key::ScrollUp | Key::Up => self.previous(),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
+                }
                 Key::Ctrl('d') => {
-                    if let Some(index) = self.list_state.selected() {
-                        // TODO: Make this just two if lets
-                        if let Some(filtered) = self.filtered_channels.clone() {
-                            if let Some(value) = filtered.get(index) {
-                                self.storage
-                                    .borrow_mut()
-                                    .remove_inner_with("channels", value);
-                            }
-                        } else if let Some(value) = self.storage.borrow().get("channels").get(index)
-                        {
-                            self.storage
-                                .borrow_mut()
-                                .remove_inner_with("channels", value);
-                        }
+                    // Favorites that were never switched to are only removed by unmarking them
+                    if let Some(channel) = self
+                        .list
+                        .selected()
+                        .filter(|channel| self.storage.borrow().contains("channels", channel))
+                    {
+                        self.storage
+                            .borrow_mut()
+                            .remove_inner_with("channels", channel);
+                    }
+                }
+                Key::Alt('p') => {
+                    if let Some(channel) = self.list.selected() {
+                        toggle_favorite(&self.storage, channel);
                     }
                 }
                 Key::Enter => {
-                    // TODO: Reduce code duplication
-                    if let Some(i) = self.list_state.selected() {
-                        self.toggle_focus();
-                        self.unselect();
-
-                        let channels = self.storage.borrow().get("channels");
-
-                        let selected_channel = if let Some(v) = &self.filtered_channels {
-                            if v.is_empty() {
-                                let selected_channel = self.search_input.to_string();
-
-                                if !selected_channel.is_empty() {
-                                    if self.config.borrow().storage.channels {
-                                        self.storage
-                                            .borrow_mut()
-                                            .add("channels", selected_channel.clone());
-                                    }
-
-                                    self.search_input.clear();
-
-                                    self.config
-                                        .borrow_mut()
-                                        .twitch
-                                        .channel
-                                        .clone_from(&selected_channel);
-
-                                    return Some(TerminalAction::Enter(TwitchAction::Join(
-                                        selected_channel,
-                                    )));
-                                }
-                            }
-
-                            v.get(i).unwrap()
-                        } else {
-                            channels.get(i).unwrap()
-                        };
-
-                        if self.config.borrow().storage.channels {
-                            self.storage
-                                .borrow_mut()
-                                .add("channels", selected_channel.clone());
-                        }
-
-                        self.search_input.clear();
-
-                        self.config
+                    // Join the selected channel, or the one typed in if none of them were found
+                    let selected_channel = match self.list.selected() {
+                        Some(channel) => channel.clone(),
+                        None if self.search_input.is_valid() => self.search_input.to_string(),
+                        None => return None,
+                    };
+
+                    self.toggle_focus();
+                    self.list.unselect();
+
+                    if self.config.borrow().storage.channels {
+                        self.storage
                             .borrow_mut()
-                            .twitch
-                            .channel
-                            .clone_from(selected_channel);
-
-                        debug!(
-                            "Joining previously joined channel {:?}",
-                            selected_channel.to_string()
-                        );
-
-                        return Some(TerminalAction::Enter(TwitchAction::Join(
-                            selected_channel.to_string(),
-                        )));
-                    } else if self.search_input.is_valid() {
-                        self.toggle_focus();
-                        self.unselect();
-
-                        let selected_channel = self.search_input.to_string();
-
-                        if self.config.borrow().storage.channels {
-                            self.storage
-                                .borrow_mut()
-                                .add("channels", selected_channel.clone());
-                        }
+                            .add("channels", selected_channel.clone());
+                    }
 
-                        self.search_input.clear();
+                    self.search_input.clear();
+                    self.searched = None;
 
-                        self.config
-                            .borrow_mut()
-                            .twitch
-                            .channel
-                            .clone_from(&selected_channel);
+                    self.config
+                        .borrow_mut()
+                        .twitch
+                        .channel
+                        .clone_from(&selected_channel);
 
-                        debug!("Joining new channel {:?}", selected_channel);
+                    debug!("Joining channel {:?}", selected_channel);
 
-                        return Some(TerminalAction::Enter(TwitchAction::Join(selected_channel)));
-                    }
+                    return Some(TerminalAction::Enter(TwitchAction::Join(selected_channel)));
                 }
                 _ => {
                     self.search_input.event(event).await;
+                    self.typed_at = Instant::now();
 
                     // Assuming that the user inputted something that modified the input
-                    if let Some(v) = &self.filtered_channels {
-                        if !v.is_empty() {
-                            self.list_state.select(Some(0));
-                        }
-                    }
+                    self.list.select_first();
                 }
             }
         }
@@ -1,48 +1,173 @@
-use std::{collections::VecDeque, slice::Iter};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet, VecDeque},
+    mem,
+    ops::Range,
+    slice::Iter,
+};
 
 use chrono::Local;
 use tui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
     style::{Color, Modifier, Style},
+    symbols::DOT,
     text::{Line, Span, Text},
-    widgets::{Block, Borders, List, ListItem, block::Position},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Tabs, block,
+    },
 };
 
 use crate::{
+    commands::copy_to_clipboard,
     emotes::SharedEmotes,
     handlers::{
-        app::SharedMessages,
-        config::SharedCompleteConfig,
+        app::{
+            SharedActivity, SharedAutoMod, SharedChannelTabs, SharedMentions, SharedMessages,
+            SharedModActions, SharedNotices, SharedPolls, SharedRaids, SharedWhispers,
+        },
+        completion::SharedRecentChatters,
+        config::{CompleteConfig, SharedCompleteConfig, Widget},
         data::MessageData,
-        filters::SharedFilters,
+        filters::{FilterAction, SharedFilters},
+        highlights::{Highlights, SharedHighlights},
+        ignored::SharedIgnoredUsers,
+        joins::SharedJoinHistory,
+        nicknames::SharedNicknames,
         state::State,
         storage::SharedStorage,
+        tabs::Draft,
         user_input::{
-            events::{Event, Key},
+            events::{Event, Key, MouseAction, paste_keys},
+            keybinds::{Action, keys, resolve},
+            macros::{Macros, Recorded},
+            navigation::{Motion, Navigated, Navigation},
             scrolling::Scrolling,
         },
     },
     terminal::TerminalAction,
+    twitch::{
+        TwitchAction, alerts::Alert, helix::helix_degraded, moderation::ModerationCommand,
+        outbox::Outgoing, streams::LiveStream,
+    },
     ui::components::{
-        ChannelSwitcherWidget, ChatInputWidget, Component, MessageSearchWidget,
+        ActivityWidget, AutoModWidget, ChannelSwitcherWidget, ChatInputWidget, Component,
+        LogSearchWidget, MentionsWidget, MessageSearchWidget, NoticesWidget, RaidsWidget,
+        RecoveredWidget, RewardsWidget, WhispersWidget,
+        activity::ACTIVITY_WIDTH,
+        alerts::AlertsWidget,
+        filters::FiltersWidget,
         following::FollowingWidget,
+        ignored::IgnoredWidget,
+        links::LinksWidget,
+        mod_log::{MOD_LOG_WIDTH, ModLogWidget},
+        polls::PollWidget,
+        quick_switch::QuickSwitchWidget,
+        stream_info::StreamInfoWidget,
     },
     utils::{
-        styles::{NO_COLOR, TEXT_DARK_STYLE, TITLE_STYLE},
-        text::{TitleStyle, title_line},
+        links::{DrawnLink, find_urls, link_columns},
+        status_command::status_output,
+        styles::{
+            NO_COLOR, STATE_TABS_STYLE, border_style, mention_style, system_chat_style, text_style,
+            title_style,
+        },
+        text::{TitleStyle, centered_rule, collapse_lines, text_between, title_line},
+        time::format_datetime,
     },
 };
 
+/// A row of chat as it was last drawn, to find what was clicked on and to copy its text.
+#[derive(Default)]
+struct DrawnRow {
+    /// The ID of the message that the row is a part of, if it can be selected.
+    message_id: Option<String>,
+    author: String,
+    /// The columns taken up by the name of the author, on the row that shows it.
+    username: Option<Range<usize>>,
+    text: String,
+    /// The links in the message, which can be partly on this row.
+    urls: Vec<String>,
+}
+
+impl DrawnRow {
+    fn new(message: &MessageData, shown_author: &str, urls: &[String], line: &Line) -> Self {
+        let mut column = 0;
+        let mut username = None;
+
+        // The name is followed by a colon, which tells it apart from the same word in the message
+        for (span, next) in line.spans.iter().zip(line.spans.iter().skip(1)) {
+            if username.is_none() && span.content == shown_author && next.content == ": " {
+                username = Some(column..column + span.width());
+            }
+
+            column += span.width();
+        }
+
+        Self {
+            message_id: message.message_id.clone().filter(|_| !message.system),
+            author: message.author.clone(),
+            username: username.filter(|_| !message.system),
+            text: line.to_string(),
+            urls: urls.to_vec(),
+        }
+    }
+}
+
 pub struct ChatWidget {
     config: SharedCompleteConfig,
     messages: SharedMessages,
+    tabs: SharedChannelTabs,
     chat_input: ChatInputWidget,
     channel_input: ChannelSwitcherWidget,
     search_input: MessageSearchWidget,
     following: FollowingWidget,
+    links: LinksWidget,
+    quick_switch: QuickSwitchWidget,
+    notices: NoticesWidget,
+    whispers: WhispersWidget,
+    activity: ActivityWidget,
+    mod_log: ModLogWidget,
+    raids: RaidsWidget,
+    rewards: RewardsWidget,
+    mentions: MentionsWidget,
+    automod: AutoModWidget,
+    log_search: LogSearchWidget,
+    recovered: RecoveredWidget,
+    filters_list: FiltersWidget,
+    ignored_list: IgnoredWidget,
+    stream_info: StreamInfoWidget,
+    alerts: AlertsWidget,
+    poll: PollWidget,
     filters: SharedFilters,
+    highlights: SharedHighlights,
+    ignored: SharedIgnoredUsers,
+    nicknames: SharedNicknames,
     pub scroll_offset: Scrolling,
+    /// The ID of the message selected to be replied to, if a message is being selected.
+    selected: Option<String>,
+    /// Motions moving the selection, along with the counts typed before them.
+    navigation: Navigation,
+    /// If the timeout ladder is shown for the selected message, for a step of it to be picked with a digit.
+    ladder_shown: bool,
+    /// Threads whose replies are hidden, by the ID of the message that started them.
+    collapsed_threads: HashSet<String>,
+    /// Long messages that are shown in full, by their ID.
+    expanded_messages: HashSet<String>,
+    /// The amount of messages that fit on screen the last time chat was drawn.
+    shown_messages: Cell<usize>,
+    /// Where messages were drawn the last time chat was drawn, if nothing was drawn over them.
+    viewport: Option<Rect>,
+    /// The rows of messages from the top, as they were last drawn.
+    drawn_rows: RefCell<Vec<DrawnRow>>,
+    /// Where the rows of messages were last drawn, inside of the border.
+    rows_area: Cell<Rect>,
+    /// Where the mouse was pressed down in chat, and where it has been dragged to since.
+    drag: Option<(Position, Option<Position>)>,
+    macros: Macros,
+    /// How many messages have arrived since the terminal last lost focus, that the unread marker is drawn above.
+    unread: Cell<Option<usize>>,
     // theme: Theme,
 }
 
@@ -50,27 +175,272 @@ impl ChatWidget {
     pub fn new(
         config: SharedCompleteConfig,
         messages: SharedMessages,
+        notices: SharedNotices,
+        whispers: SharedWhispers,
+        activity: SharedActivity,
+        raids: SharedRaids,
+        mentions: SharedMentions,
+        automod: SharedAutoMod,
+        mod_actions: SharedModActions,
+        polls: SharedPolls,
+        tabs: SharedChannelTabs,
         storage: &SharedStorage,
         emotes: &SharedEmotes,
         filters: SharedFilters,
+        highlights: SharedHighlights,
+        ignored: SharedIgnoredUsers,
+        nicknames: SharedNicknames,
+        chatters: SharedRecentChatters,
+        joins: SharedJoinHistory,
     ) -> Self {
-        let chat_input = ChatInputWidget::new(config.clone(), storage.clone(), emotes.clone());
+        let chat_input = ChatInputWidget::new(
+            config.clone(),
+            storage.clone(),
+            emotes.clone(),
+            chatters,
+            nicknames.clone(),
+        );
         let channel_input = ChannelSwitcherWidget::new(config.clone(), storage.clone());
         let search_input = MessageSearchWidget::new(config.clone());
-        let following = FollowingWidget::new(config.clone());
+        let following = FollowingWidget::new(config.clone(), storage.clone());
+        let links = LinksWidget::new(config.clone());
+        let quick_switch = QuickSwitchWidget::new(config.clone(), joins);
+        let notices = NoticesWidget::new(config.clone(), notices);
+        let whispers = WhispersWidget::new(config.clone(), whispers);
+        let activity = ActivityWidget::new(config.clone(), activity);
+        let mod_log = ModLogWidget::new(config.clone(), mod_actions);
+        let raids = RaidsWidget::new(config.clone(), raids);
+        let rewards = RewardsWidget::new(config.clone());
+        let mentions = MentionsWidget::new(config.clone(), mentions);
+        let automod = AutoModWidget::new(config.clone(), automod);
+        let log_search = LogSearchWidget::new(config.clone());
+        let recovered = RecoveredWidget::new(config.clone());
+        let filters_list = FiltersWidget::new(config.clone(), filters.clone());
+        let ignored_list = IgnoredWidget::new(config.clone(), ignored.clone());
+        let stream_info = StreamInfoWidget::new(&config);
+        let alerts = AlertsWidget::new(config.clone());
+        let poll = PollWidget::new(config.clone(), polls);
 
         let scroll_offset = Scrolling::new(config.borrow().frontend.inverted_scrolling);
 
         Self {
             config,
             messages,
+            tabs,
             chat_input,
             channel_input,
             search_input,
             following,
+            links,
+            quick_switch,
+            notices,
+            whispers,
+            activity,
+            mod_log,
+            raids,
+            rewards,
+            mentions,
+            automod,
+            log_search,
+            recovered,
+            filters_list,
+            ignored_list,
+            stream_info,
+            alerts,
+            poll,
             filters,
+            highlights,
+            ignored,
+            nicknames,
             scroll_offset,
+            selected: None,
+            navigation: Navigation::default(),
+            ladder_shown: false,
+            collapsed_threads: HashSet::new(),
+            expanded_messages: HashSet::new(),
+            shown_messages: Cell::new(0),
+            viewport: None,
+            drawn_rows: RefCell::new(vec![]),
+            rows_area: Cell::new(Rect::default()),
+            drag: None,
+            macros: Macros::default(),
+            unread: Cell::new(None),
+        }
+    }
+
+    /// If a message is being selected, with motions such as `Ctrl + d` moving the selection.
+    pub const fn is_selecting(&self) -> bool {
+        self.selected.is_some()
+    }
+
+    /// What is being done in chat, for the status bar.
+    pub const fn mode(&self) -> &'static str {
+        if self.chat_input.is_focused() {
+            "Insert"
+        } else if self.search_input.is_focused() {
+            "Search"
+        } else if self.selected.is_some() {
+            "Select"
+        } else {
+            "Normal"
+        }
+    }
+
+    pub const fn is_whispers_focused(&self) -> bool {
+        self.whispers.is_focused()
+    }
+
+    /// Whether the next keys come from replaying a macro, which should not be recorded again.
+    pub const fn set_replaying(&mut self, replaying: bool) {
+        self.macros.set_replaying(replaying);
+    }
+
+    pub const fn is_mentions_focused(&self) -> bool {
+        self.mentions.is_focused()
+    }
+
+    /// Ask what should happen to the messages left unsent by the previous session.
+    pub fn recover(&mut self, items: Vec<Outgoing>) {
+        self.recovered.recover(items);
+    }
+
+    /// Keep a critical alert on screen until it is acknowledged.
+    pub fn push_alert(&mut self, alert: Alert) {
+        self.alerts.push(alert);
+    }
+
+    pub fn set_stream(&mut self, channel: String, stream: Option<LiveStream>) {
+        self.stream_info.set_stream(channel, stream);
+    }
+
+    pub fn take_draft(&mut self) -> Draft {
+        self.chat_input.take_draft()
+    }
+
+    pub fn restore_draft(&mut self, draft: Draft) {
+        self.chat_input.restore_draft(draft);
+    }
+
+    fn draw_channel_tabs(&self, f: &mut Frame, area: Rect) {
+        let tabs = self.tabs.borrow();
+
+        let titles = tabs
+            .channels()
+            .iter()
+            .map(|channel| {
+                let unread = tabs.unread(channel);
+
+                if unread > 0 {
+                    Line::from(format!("{channel} ({unread})"))
+                } else {
+                    Line::from(channel.as_str())
+                }
+            })
+            .collect::<Vec<Line>>();
+
+        let tab_bar = Tabs::new(titles)
+            .style(*STATE_TABS_STYLE)
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .remove_modifier(Modifier::DIM)
+                    .add_modifier(Modifier::UNDERLINED),
+            )
+            .divider(DOT)
+            .select(tabs.active_index());
+
+        f.render_widget(tab_bar, area);
+    }
+
+    /// Switch to the channel in another tab, if there are other tabs.
+    fn switch_tab(&self, forward: bool) -> Option<TerminalAction> {
+        let tabs = self.tabs.borrow();
+
+        if tabs.channels().len() <= 1 {
+            return None;
+        }
+
+        let channel = if forward {
+            tabs.next()
+        } else {
+            tabs.previous()
+        };
+
+        Some(TerminalAction::Enter(TwitchAction::Join(
+            channel.to_string(),
+        )))
+    }
+
+    /// Take an action bound to a key while no widget over chat is focused.
+    async fn normal_action(&mut self, action: Action) -> Option<TerminalAction> {
+        match action {
+            Action::InsertMode => self.chat_input.toggle_focus(),
+            Action::Mention => self.chat_input.toggle_focus_with("@"),
+            Action::Command => self.chat_input.toggle_focus_with("/"),
+            Action::Emotes => self.chat_input.open_emote_picker(),
+            Action::ChannelSwitcher => self.channel_input.toggle_focus(),
+            Action::QuickSwitch => self.quick_switch.open(),
+            Action::SearchMessages => self.search_input.toggle_focus(),
+            Action::Following => self.following.toggle_focus().await,
+            Action::ToggleFilter => self.filters.borrow_mut().toggle(),
+            Action::ReverseFilter => self.filters.borrow_mut().reverse(),
+            Action::Filters => self.filters_list.toggle_focus(),
+            Action::Ignored => self.ignored_list.toggle_focus(),
+            Action::Notices => self.notices.toggle_focus(),
+            Action::Whispers => self.whispers.toggle_focus(),
+            Action::Activity => self.activity.toggle_visibility(),
+            Action::ModLog => self.mod_log.toggle_visibility(),
+            Action::StreamInfo => self.stream_info.cycle_style(),
+            Action::AcknowledgeAlert if self.alerts.is_shown() => self.alerts.acknowledge(),
+            Action::ModLogOlder if self.mod_log.is_visible() => self.mod_log.scroll_older(),
+            Action::ModLogNewer if self.mod_log.is_visible() => self.mod_log.scroll_newer(),
+            Action::Raids => self.raids.toggle_focus(),
+            Action::FollowRaid => {
+                if let Some(raid) = self.raids.banner_raid().filter(|r| r.outgoing) {
+                    return Some(TerminalAction::Enter(TwitchAction::Join(raid.channel)));
+                }
+            }
+            Action::Rewards => self.rewards.toggle_focus().await,
+            Action::Mentions => self.mentions.toggle_focus(),
+            Action::AutoMod => self.automod.toggle_focus(),
+            Action::LogSearch => self.log_search.toggle_focus(),
+            Action::GiftRecipients => return Some(TerminalAction::ToggleGiftRecipients),
+            Action::DoNotDisturb => return Some(TerminalAction::ToggleDoNotDisturb),
+            Action::SelectMessage => {
+                self.navigation.reset();
+                self.ladder_shown = false;
+                self.move_selection(Motion::Newest);
+            }
+            Action::NextTab => return self.switch_tab(true),
+            Action::PreviousTab => return self.switch_tab(false),
+            Action::CloseTab => return Some(TerminalAction::CloseTab),
+            Action::Dashboard => return Some(TerminalAction::SwitchState(State::Dashboard)),
+            Action::Projector => return Some(TerminalAction::SwitchState(State::Projector)),
+            Action::Help => return Some(TerminalAction::SwitchState(State::Help)),
+            Action::Quit => return Some(TerminalAction::Quit),
+            Action::OpenInBrowser => self.open_in_browser(),
+            Action::Links => self.links.open(self.recent_links()),
+            Action::About => return Some(TerminalAction::ToggleAbout),
+            Action::RotateTheme => return Some(TerminalAction::RotateTheme),
+            Action::ToggleTimestamps => {
+                let mut config = self.config.borrow_mut();
+                config.frontend.show_datetimes = !config.frontend.show_datetimes;
+            }
+            Action::ScrollToNewest => self.scroll_offset.jump_to(0),
+            Action::ScrollToOldest => {
+                // TODO: Make this not jump to nothingness
+                self.scroll_offset.jump_to(self.messages.borrow().len());
+            }
+            // Alerts and moderator actions are only handled while shown,
+            // and macros are controlled before keys reach the rest of chat
+            Action::AcknowledgeAlert
+            | Action::ModLogOlder
+            | Action::ModLogNewer
+            | Action::RecordMacro
+            | Action::ReplayMacro => {}
         }
+
+        None
     }
 
     pub fn open_in_browser(&self) {
@@ -79,9 +449,358 @@ impl ChatWidget {
             self.config.borrow().twitch.channel).as_str()).unwrap();
     }
 
+    /// The links in the messages that are shown, newest first, without repeating any of them.
+    fn recent_links(&self) -> Vec<String> {
+        let mut links = vec![];
+
+        for message in self.messages.borrow().iter().filter(|m| !self.is_hidden(m)) {
+            for link in find_urls(&message.payload) {
+                if !links.contains(&link) {
+                    links.push(link);
+                }
+            }
+        }
+
+        links
+    }
+
+    /// The parts of links on the rows of chat as they were last drawn, to make them clickable in the terminal.
+    pub fn drawn_links(&self) -> Vec<DrawnLink> {
+        let area = self.rows_area.get();
+
+        let mut links = vec![];
+
+        for (row, y) in self
+            .drawn_rows
+            .borrow()
+            .iter()
+            .zip(area.top()..area.bottom())
+        {
+            for (columns, text, url) in link_columns(&row.text, &row.urls) {
+                // Both are within the area, which is measured in `u16`
+                let x = area.x + columns.start as u16;
+                let width = columns.len() as u16;
+
+                links.push(DrawnLink {
+                    area: Rect::new(x, y, width, 1).intersection(area),
+                    text: text.to_string(),
+                    url: url.to_string(),
+                });
+            }
+        }
+
+        links
+    }
+
+    /// If a message is left out of chat, either by the filters or by its thread being collapsed.
+    /// Keep the messages shown in place when a message arrives in chat while it is scrolled up.
+    pub fn message_added(&self, message: &MessageData) {
+        if !self.is_hidden(message) {
+            self.scroll_offset.message_added();
+
+            if let Some(unread) = self.unread.get() {
+                self.unread.set(Some(unread + 1));
+            }
+        }
+    }
+
+    /// Start counting the messages that arrive once the terminal loses focus, to draw the unread marker above them.
+    /// The marker stays where it is after focus is gained again, until focus is lost the next time.
+    pub fn focus_changed(&self, focused: bool) {
+        if !focused {
+            self.unread.set(Some(0));
+        }
+    }
+
+    pub fn clear_unread_marker(&self) {
+        self.unread.set(None);
+    }
+
+    /// Dim or give a background to the lines of a message, for the filters and highlight rules it matches.
+    fn style_lines(&self, channel: &str, data: &MessageData, lines: &mut [Line]) {
+        match self.filters.borrow().style_of(channel, data) {
+            Some(FilterAction::Dim) => {
+                for line in lines.iter_mut() {
+                    line.style = line.style.add_modifier(Modifier::DIM);
+                }
+            }
+            Some(FilterAction::Highlight) => {
+                for line in lines.iter_mut() {
+                    line.style = line.style.patch(mention_style());
+                }
+            }
+            Some(FilterAction::Hide) | None => {}
+        }
+
+        // Mentions already have a background of their own
+        if data.system || data.mentioned {
+            return;
+        }
+
+        if let Some(rule) = self.highlights.borrow().find(&data.payload) {
+            let style = Highlights::style(rule);
+
+            for line in lines {
+                line.style = line.style.patch(style);
+            }
+        }
+    }
+
+    fn is_hidden(&self, message: &MessageData) -> bool {
+        self.filters
+            .borrow()
+            .hides_message(&self.config.borrow().twitch.channel, message)
+            || self.ignored.borrow().contains(&message.author)
+            || (message.foreign_language && self.config.borrow().filters.language.hide)
+            || message
+                .reply
+                .as_ref()
+                .is_some_and(|reply| self.collapsed_threads.contains(&reply.thread_id))
+    }
+
+    /// Move the selection to another message that can be replied to, selecting the newest one to start.
+    fn move_selection(&mut self, motion: Motion) {
+        let messages = self.messages.borrow();
+
+        let current = self.selected.as_ref().and_then(|id| {
+            messages
+                .iter()
+                .position(|m| m.message_id.as_ref() == Some(id))
+        });
+
+        let selectable = |i: &usize| {
+            let message = &messages[*i];
+            message.message_id.is_some() && !message.system && !self.is_hidden(message)
+        };
+
+        // Newer messages come first
+        let step = |from: Option<usize>, older: bool| match from {
+            None => (0..messages.len()).find(selectable),
+            Some(i) if older => (i + 1..messages.len()).find(selectable),
+            Some(i) => (0..i).rev().find(selectable),
+        };
+
+        let half_page = (self.shown_messages.get() / 2).max(1);
+
+        let (older, steps) = match motion {
+            Motion::Older(count) => (true, count),
+            Motion::Newer(count) => (false, count),
+            Motion::HalfPageOlder(count) => (true, count * half_page),
+            Motion::HalfPageNewer(count) => (false, count * half_page),
+            Motion::Oldest => (true, messages.len()),
+            Motion::Newest => (false, messages.len()),
+        };
+
+        let mut next = None;
+        let mut from = current;
+
+        for _ in 0..steps {
+            let Some(i) = step(from, older) else {
+                break;
+            };
+
+            next = Some(i);
+            from = Some(i);
+        }
+
+        drop(messages);
+
+        if let Some(next) = next {
+            self.select_index(next);
+        }
+    }
+
+    /// Jump to the next message that matches the message search, either older or newer than the one selected,
+    /// starting from the newest message shown if none are selected.
+    fn jump_to_match(&mut self, older: bool) {
+        let Some(query) = self.search_input.query() else {
+            return;
+        };
+
+        let messages = self.messages.borrow();
+
+        let current = self.selected.as_ref().and_then(|id| {
+            messages
+                .iter()
+                .position(|m| m.message_id.as_ref() == Some(id))
+        });
+
+        let matching = |i: &usize| {
+            let message = &messages[*i];
+            message.message_id.is_some()
+                && !message.system
+                && !self.is_hidden(message)
+                && query.matches(&message.author, &message.payload)
+        };
+
+        // Newer messages come first
+        let next = match current {
+            None => {
+                let offset = self.scroll_offset.get_offset();
+                let start = messages
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, m)| !self.is_hidden(m))
+                    .nth(offset)
+                    .map_or(messages.len(), |(i, _)| i);
+
+                if older {
+                    (start..messages.len()).find(matching)
+                } else {
+                    (0..start).rev().find(matching)
+                }
+            }
+            Some(i) if older => (i + 1..messages.len()).find(matching),
+            Some(i) => (0..i).rev().find(matching),
+        };
+
+        drop(messages);
+
+        if let Some(next) = next {
+            self.navigation.reset();
+            self.ladder_shown = false;
+            self.select_index(next);
+        }
+    }
+
+    /// Select a message by where it is in chat, scrolling to it if it is not shown.
+    fn select_index(&mut self, index: usize) {
+        let messages = self.messages.borrow();
+
+        self.selected.clone_from(&messages[index].message_id);
+
+        // The scroll offset only counts the messages that are shown
+        let position = messages
+            .iter()
+            .take(index)
+            .filter(|m| !self.is_hidden(m))
+            .count();
+
+        drop(messages);
+
+        let offset = self.scroll_offset.get_offset();
+        let shown = self.shown_messages.get().max(1);
+
+        if position < offset {
+            self.scroll_offset.jump_to(position);
+        } else if position >= offset + shown {
+            self.scroll_offset.jump_to(position + 1 - shown);
+        }
+    }
+
+    fn selected_message(&self) -> Option<MessageData> {
+        let id = self.selected.as_ref()?;
+
+        self.messages
+            .borrow()
+            .iter()
+            .find(|m| m.message_id.as_ref() == Some(id))
+            .cloned()
+    }
+
+    /// Apply the step of the timeout ladder picked with a digit to the author of the selected message.
+    /// Any other key hides the ladder without doing anything.
+    fn ladder_event(&mut self, key: Key) -> Option<TerminalAction> {
+        let Key::Char(c) = key else {
+            return None;
+        };
+
+        let config = self.config.borrow();
+
+        let step = c
+            .to_digit(10)
+            .and_then(|digit| (digit as usize).checked_sub(1))
+            .and_then(|index| config.moderation.ladder.get(index))?;
+
+        let message = self.selected_message()?;
+
+        let command = step.command(&message.author, &message.payload, &config.moderation.reason);
+
+        drop(config);
+        self.selected = None;
+
+        Some(TerminalAction::Enter(TwitchAction::Moderate(command)))
+    }
+
+    fn selection_event(&mut self, key: Key) -> Option<TerminalAction> {
+        if mem::take(&mut self.ladder_shown) {
+            return self.ladder_event(key);
+        }
+
+        match self.navigation.key(key) {
+            Navigated::Pending => return None,
+            Navigated::Moved(motion) => {
+                self.move_selection(motion);
+                return None;
+            }
+            Navigated::Other(_) => {}
+        }
+
+        match key {
+            Key::Char('n') => self.jump_to_match(true),
+            Key::Char('N') => self.jump_to_match(false),
+            Key::Enter | Key::Char('r') => {
+                if let Some(message) = self.selected_message() {
+                    if let Some(id) = message.message_id {
+                        self.chat_input.reply_to(id, &message.author);
+                    }
+                }
+
+                self.selected = None;
+            }
+            Key::Char('t') => {
+                if let Some(message) = self.selected_message() {
+                    let thread_id = message
+                        .reply
+                        .map(|reply| reply.thread_id)
+                        .or(message.message_id);
+
+                    if let Some(thread_id) = thread_id {
+                        if !self.collapsed_threads.remove(&thread_id) {
+                            self.collapsed_threads.insert(thread_id.clone());
+                        }
+
+                        // The selected reply might have just been hidden
+                        self.selected = Some(thread_id);
+                    }
+                }
+            }
+            Key::Char('l') => {
+                if let Some(message) = self.selected_message() {
+                    self.links.open(find_urls(&message.payload));
+                }
+            }
+            Key::Char('e') => {
+                if let Some(id) = self.selected_message().and_then(|m| m.message_id) {
+                    if !self.expanded_messages.remove(&id) {
+                        self.expanded_messages.insert(id);
+                    }
+                }
+            }
+            Key::Char('d') => {
+                let message_id = self.selected_message().and_then(|m| m.message_id);
+
+                self.selected = None;
+
+                if let Some(message_id) = message_id {
+                    return Some(TerminalAction::Enter(TwitchAction::Moderate(
+                        ModerationCommand::Delete { message_id },
+                    )));
+                }
+            }
+            Key::Char('b') => {
+                self.ladder_shown = !self.config.borrow().moderation.ladder.is_empty();
+            }
+            Key::Esc => self.selected = None,
+            Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+            _ => {}
+        }
+
+        None
+    }
+
     pub fn get_messages<'a>(
         &self,
-        frame: &Frame,
         area: Rect,
         messages_data: &'a VecDeque<MessageData>,
     ) -> VecDeque<Line<'a>> {
@@ -90,11 +809,12 @@ impl ChatWidget {
         let mut total_row_height: usize = 0;
 
         let mut messages = VecDeque::new();
+        let mut rows = VecDeque::new();
 
-        let mut general_chunk_height = area.height as usize;
-        if !self.config.borrow().frontend.hide_chat_border {
-            general_chunk_height -= 2;
-        }
+        let general_chunk_height = area
+            .height
+            .saturating_sub(self.config.borrow().frontend.chat_frame_height())
+            as usize;
 
         let mut scroll = self.scroll_offset.get_offset();
 
@@ -102,21 +822,56 @@ impl ChatWidget {
         let h_chunk = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Min(1)])
-            .split(frame.area());
-
-        let message_chunk_width = h_chunk[0].width as usize;
+            .split(area);
 
         let config = self.config.borrow();
 
+        // Padding only exists inside of the border
+        let padding = if config.frontend.hide_chat_border {
+            0
+        } else {
+            config.padding(Widget::Chat)
+        };
+
+        let message_chunk_width = h_chunk[0].width.saturating_sub(padding * 2) as usize;
+
+        // How many replies are hidden in each collapsed thread
+        let mut hidden_replies: HashMap<&str, usize> = HashMap::new();
+
+        if !self.collapsed_threads.is_empty() {
+            for reply in messages_data.iter().filter_map(|m| m.reply.as_ref()) {
+                if self.collapsed_threads.contains(&reply.thread_id) {
+                    *hidden_replies.entry(&reply.thread_id).or_default() += 1;
+                }
+            }
+        }
+
+        let selected_style = if *NO_COLOR {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().bg(Color::DarkGray)
+        };
+
+        let nicknames = self.nicknames.borrow();
+
+        let search = self.search_input.query();
+
+        let mut shown_messages = 0;
+
+        // The marker is only drawn if messages arrived after focus was lost
+        let unread = self
+            .unread
+            .get()
+            .filter(|&unread| unread > 0 && config.frontend.unread_marker);
+        let mut visible_index = 0;
+
         'outer: for data in messages_data {
-            if self
-                .filters
-                .borrow()
-                .contaminated(data.payload.clone().as_str())
-            {
+            if self.is_hidden(data) {
                 continue;
             }
 
+            visible_index += 1;
+
             // Offsetting of messages for scrolling through said messages
             if scroll > 0 {
                 scroll -= 1;
@@ -130,21 +885,72 @@ impl ChatWidget {
                 None
             };
 
-            let search = self.search_input.to_string();
+            let selected = self.selected.is_some() && data.message_id == self.selected;
 
-            let lines = data.to_vec(
+            // The selected message shows the real name of its author
+            let nickname = if selected {
+                None
+            } else {
+                nicknames.get(&data.author)
+            };
+
+            let mut lines = data.to_vec(
                 &self.config.borrow().frontend,
                 message_chunk_width,
-                if self.search_input.is_focused() {
-                    Some(&search)
-                } else {
-                    None
-                },
+                search.as_ref(),
                 username_highlight,
+                nickname,
             );
 
+            // Only messages that can be selected can be expanded again
+            let collapsed = data
+                .message_id
+                .as_ref()
+                .is_some_and(|id| !self.expanded_messages.contains(id));
+
+            if collapsed && config.frontend.collapse_lines > 0 {
+                collapse_lines(&mut lines, config.frontend.collapse_lines);
+            }
+
+            if let Some(hidden) = data
+                .message_id
+                .as_deref()
+                .and_then(|id| hidden_replies.get(id))
+            {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  [{hidden} {} hidden]",
+                        if *hidden == 1 { "reply" } else { "replies" }
+                    ),
+                    Style::default().add_modifier(Modifier::DIM),
+                )));
+            }
+
+            self.style_lines(&config.twitch.channel, data, &mut lines);
+
+            if selected {
+                lines = lines
+                    .into_iter()
+                    .map(|line| line.patch_style(selected_style))
+                    .collect();
+            }
+
+            shown_messages += 1;
+
+            // Messages are added from the bottom up, so the marker goes below the newest message that was read
+            if unread == Some(visible_index - 1) {
+                lines.push(Line::from(Span::styled(
+                    centered_rule("new messages", message_chunk_width),
+                    system_chat_style(),
+                )));
+            }
+
+            let shown_author = nickname.unwrap_or(&data.author);
+            let urls = find_urls(&data.payload);
+
             for span in lines.into_iter().rev() {
                 if total_row_height < general_chunk_height {
+                    rows.push_front(DrawnRow::new(data, shown_author, &urls, &span));
                     messages.push_front(span);
                     total_row_height += 1;
                 } else {
@@ -153,54 +959,136 @@ impl ChatWidget {
             }
         }
 
+        self.shown_messages.set(shown_messages);
+
         // Padding with empty rows so chat can go from bottom to top.
         if general_chunk_height > total_row_height {
             for _ in 0..(general_chunk_height - total_row_height) {
                 messages.push_front(Line::from(vec![Span::raw("")]));
+                rows.push_front(DrawnRow::default());
             }
         }
 
+        *self.drawn_rows.borrow_mut() = rows.into();
+
         messages
     }
-}
-
-impl Component for ChatWidget {
-    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
-        let r = area.map_or_else(|| f.area(), |a| a);
 
+    /// Draw whichever input or popup currently has focus, with inputs being placed in the given area.
+    /// The list of messages, in a border with the title unless it is hidden.
+    /// In compact mode, the title is a single line above the messages instead.
+    fn chat_list<'a>(&self, messages: Vec<ListItem<'a>>, title: Line<'a>) -> List<'a> {
         let config = self.config.borrow();
 
-        let mut v_constraints = vec![Constraint::Min(1)];
+        let list = List::new(messages).style(text_style());
 
-        if self.chat_input.is_focused() || self.search_input.is_focused() {
-            v_constraints.push(Constraint::Length(3));
+        if config.frontend.compact && config.frontend.title_shown {
+            list.block(Block::default().title(title))
+        } else if config.frontend.compact || config.frontend.hide_chat_border {
+            list
+        } else {
+            list.block(config.block(Widget::Chat).title(title))
         }
+    }
 
-        let v_chunks_binding = Layout::default()
-            .direction(Direction::Vertical)
-            .margin(self.config.borrow().frontend.margin)
-            .constraints(v_constraints)
-            .split(r);
+    /// Show the scroll offset on the bottom border of chat.
+    fn draw_scroll_offset(&self, f: &mut Frame, area: Rect, scroll_offset: &str) {
+        let title = [TitleStyle::Single(scroll_offset)];
 
-        let mut v_chunks: Iter<Rect> = v_chunks_binding.iter();
+        let bottom_block = Block::default()
+            .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
+            .border_type(self.config.borrow().border_type(Widget::Chat))
+            .border_style(border_style())
+            .title(title_line(&title, Style::default()))
+            .title_position(block::Position::Bottom)
+            .title_alignment(Alignment::Right);
 
-        let first_v_chunk = v_chunks.next().unwrap();
+        let rect = Rect::new(area.x, area.bottom() - 1, area.width, 1);
 
-        if self.messages.borrow().len() > self.config.borrow().terminal.maximum_messages {
-            self.messages
-                .borrow_mut()
-                .truncate(self.config.borrow().terminal.maximum_messages);
+        f.render_widget(bottom_block, rect);
+    }
+
+    /// Show how far back chat is scrolled on its right border, with the oldest messages at the top.
+    fn draw_scrollbar(&self, f: &mut Frame, area: Rect, messages_data: &VecDeque<MessageData>) {
+        let total = messages_data.iter().filter(|m| !self.is_hidden(m)).count();
+
+        let position = total
+            .saturating_sub(1)
+            .saturating_sub(self.scroll_offset.get_offset());
+
+        let mut state = ScrollbarState::new(total).position(position);
+
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_symbol(None)
+            .thumb_style(border_style());
+
+        f.render_stateful_widget(
+            scrollbar,
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut state,
+        );
+    }
+
+    /// Count the messages that arrived while scrolled up, on the last row of chat.
+    fn draw_unseen(&self, f: &mut Frame, config: &CompleteConfig) {
+        let unseen = self.scroll_offset.unseen();
+        let area = self.rows_area.get();
+
+        if unseen == 0 || area.height == 0 {
+            return;
         }
 
+        let key = keys(&config.keybinds, Action::ScrollToNewest)
+            .first()
+            .map_or_else(String::new, |key| format!(", press {key}"));
+
+        let text = format!(
+            " {unseen} new {} below{key} ",
+            if unseen == 1 { "message" } else { "messages" }
+        );
+
+        let width = (text.len() as u16).min(area.width);
+
+        let rect = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.bottom() - 1,
+            width,
+            1,
+        );
+
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Paragraph::new(text)
+                .style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED)),
+            rect,
+        );
+    }
+
+    /// Draw the messages, with what is shown on top of them.
+    fn draw_messages(&self, f: &mut Frame, area: Rect) {
+        let config = self.config.borrow();
+
         let messages_data = self.messages.borrow();
 
-        let messages = self.get_messages(f, *first_v_chunk, &messages_data);
+        let messages = self.get_messages(area, &messages_data);
 
-        let current_time = Local::now()
-            .format(&config.frontend.datetime_format)
-            .to_string();
+        let current_time = format_datetime(&Local::now(), &config.frontend);
 
-        let spans = [
+        // Cannot scroll past the first message
+        let scroll_offset = config.frontend.show_scroll_offset.then(|| {
+            format!(
+                "{} / {}",
+                self.scroll_offset.get_offset(),
+                messages_data.len().saturating_sub(1)
+            )
+        });
+
+        let mut spans = vec![
             TitleStyle::Combined("Time", &current_time),
             TitleStyle::Combined("Channel", config.twitch.channel.as_str()),
             TitleStyle::Custom(Span::styled(
@@ -223,8 +1111,72 @@ impl Component for ChatWidget {
             )),
         ];
 
+        if helix_degraded() {
+            spans.push(TitleStyle::Custom(Span::styled(
+                "Helix degraded",
+                if *NO_COLOR {
+                    Style::default()
+                } else {
+                    Style::default()
+                        .add_modifier(Modifier::BOLD)
+                        .fg(Color::Yellow)
+                },
+            )));
+        }
+
+        let status = status_output();
+
+        if let Some(status) = &status {
+            spans.push(TitleStyle::Custom(Span::raw(status.as_str())));
+        }
+
+        // There is no bottom border to show the scroll offset on in compact mode
+        if let Some(scroll_offset) = scroll_offset.as_ref().filter(|_| config.frontend.compact) {
+            spans.push(TitleStyle::Single(scroll_offset));
+        }
+
+        // The actions that can be taken on the author of the selected message, by the digit pressed after `b`
+        let ladder = config
+            .moderation
+            .ladder
+            .iter()
+            .take(9)
+            .enumerate()
+            .map(|(i, step)| ((i + 1).to_string(), step.to_string()))
+            .collect::<Vec<(String, String)>>();
+
+        // The count typed so far, before the motion or action it repeats
+        let count = self.navigation.count().map(|count| count.to_string());
+
+        if self.ladder_shown {
+            spans.extend(
+                ladder
+                    .iter()
+                    .map(|(key, step)| TitleStyle::Combined(key, step)),
+            );
+        } else if self.selected.is_some() {
+            if !ladder.is_empty() {
+                spans.push(TitleStyle::Combined("b", "Timeout ladder"));
+            }
+
+            if let Some(count) = &count {
+                spans.push(TitleStyle::Combined("Count", count));
+            }
+        }
+
+        if self.macros.is_recording() {
+            spans.push(TitleStyle::Custom(Span::styled(
+                "Recording",
+                if *NO_COLOR {
+                    Style::default()
+                } else {
+                    Style::default().add_modifier(Modifier::BOLD).fg(Color::Red)
+                },
+            )));
+        }
+
         let chat_title = if self.config.borrow().frontend.title_shown {
-            Line::from(title_line(&spans, *TITLE_STYLE))
+            Line::from(title_line(&spans, title_style()))
         } else {
             Line::default()
         };
@@ -235,65 +1187,412 @@ impl Component for ChatWidget {
             final_messages.push(ListItem::new(Text::from(item)));
         }
 
-        let list = if self.config.borrow().frontend.hide_chat_border {
-            List::new(final_messages)
+        let list = self.chat_list(final_messages, chat_title);
+
+        f.render_widget(list, area);
+
+        let frame_height = config.frontend.chat_frame_height();
+
+        // Rows are inside of the border and its padding, or under the title in compact mode
+        let left = if frame_height == 2 {
+            1 + config.padding(Widget::Chat)
         } else {
-            List::new(final_messages).block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(self.config.borrow().frontend.border_type.clone().into())
-                    .title(chat_title),
-            )
+            0
+        };
+
+        self.rows_area.set(Rect::new(
+            area.x + left,
+            area.y + frame_height.min(1),
+            area.width.saturating_sub(left * 2),
+            area.height.saturating_sub(frame_height),
+        ));
+
+        if let Some((start, Some(end))) = self.drag {
+            self.highlight_drag(f, start, end);
         }
-        .style(*TEXT_DARK_STYLE);
 
-        f.render_widget(list, *first_v_chunk);
+        if let Some(scroll_offset) = scroll_offset.filter(|_| !config.frontend.compact) {
+            self.draw_scroll_offset(f, area, &scroll_offset);
+        }
 
-        if self.config.borrow().frontend.show_scroll_offset {
-            // Cannot scroll past the first message
-            let message_amount = messages_data.len().saturating_sub(1);
+        if self.scroll_offset.get_offset() > 0 {
+            if config.frontend.scrollbar && frame_height == 2 {
+                self.draw_scrollbar(f, area, &messages_data);
+            }
 
-            let title_binding = format!(
-                "{} / {}",
-                self.scroll_offset.get_offset(),
-                message_amount.to_string().as_str()
-            );
+            self.draw_unseen(f, &config);
+        }
+
+        self.raids.draw_banner(f, area);
+        self.poll.draw(f, area);
+        self.alerts.draw_banner(f, area);
+    }
 
-            let title = [TitleStyle::Single(&title_binding)];
+    /// The column and row of the drawn rows under the mouse, or the closest one to it.
+    fn drawn_cell(&self, position: Position) -> (usize, usize) {
+        let area = self.rows_area.get();
 
-            let bottom_block = Block::default()
-                .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
-                .border_type(self.config.borrow().frontend.border_type.clone().into())
-                .title(title_line(&title, Style::default()))
-                .title_position(Position::Bottom)
-                .title_alignment(Alignment::Right);
+        let x = position
+            .x
+            .clamp(area.left(), area.right().saturating_sub(1).max(area.left()));
+        let y = position
+            .y
+            .clamp(area.top(), area.bottom().saturating_sub(1).max(area.top()));
 
-            let rect = Rect::new(
-                first_v_chunk.x,
-                first_v_chunk.bottom() - 1,
-                first_v_chunk.width,
-                1,
-            );
+        (usize::from(x - area.x), usize::from(y - area.y))
+    }
+
+    /// Show the text that the mouse was dragged over, which is copied when the mouse is let go.
+    fn highlight_drag(&self, f: &mut Frame, start: Position, end: Position) {
+        let area = self.rows_area.get();
+
+        let (start, end) = (self.drawn_cell(start), self.drawn_cell(end));
+        let (start, end) = if (start.1, start.0) <= (end.1, end.0) {
+            (start, end)
+        } else {
+            (end, start)
+        };
+
+        let buffer = f.buffer_mut();
+
+        for row in start.1..=end.1 {
+            let from = if row == start.1 { start.0 } else { 0 };
+            let to = if row == end.1 {
+                end.0
+            } else {
+                usize::from(area.width.saturating_sub(1))
+            };
+
+            for column in from..=to {
+                // Both are within the area, which is measured in `u16`
+                let position = (area.x + column as u16, area.y + row as u16);
+
+                if let Some(cell) = buffer.cell_mut(position) {
+                    cell.modifier.insert(Modifier::REVERSED);
+                }
+            }
+        }
+    }
+
+    /// Copy the text that the mouse was dragged over, or act on what was clicked if it was not dragged.
+    fn mouse_event(&mut self, action: MouseAction, position: Position) {
+        match action {
+            MouseAction::Click => {
+                self.drag = self
+                    .rows_area
+                    .get()
+                    .contains(position)
+                    .then_some((position, None));
+            }
+            MouseAction::Drag => {
+                if let Some((_, end)) = &mut self.drag {
+                    *end = Some(position);
+                }
+            }
+            MouseAction::Release => match self.drag.take() {
+                Some((start, Some(end))) if start != end => {
+                    let rows = self
+                        .drawn_rows
+                        .borrow()
+                        .iter()
+                        .map(|row| row.text.clone())
+                        .collect::<Vec<String>>();
+
+                    copy_to_clipboard(&text_between(
+                        &rows,
+                        self.drawn_cell(start),
+                        self.drawn_cell(end),
+                    ));
+                }
+                Some((start, _)) => self.click(start),
+                None => {}
+            },
+        }
+    }
+
+    /// Clicking on the name of an author mentions them, and clicking anywhere else on a message selects it.
+    fn click(&mut self, position: Position) {
+        let (column, row) = self.drawn_cell(position);
+
+        let (message_id, mention) = match self.drawn_rows.borrow().get(row) {
+            Some(drawn) if drawn.username.as_ref().is_some_and(|u| u.contains(&column)) => {
+                (None, Some(format!("@{} ", drawn.author)))
+            }
+            Some(drawn) => (drawn.message_id.clone(), None),
+            None => (None, None),
+        };
 
-            f.render_widget(bottom_block, rect);
+        self.navigation.reset();
+        self.ladder_shown = false;
+        self.selected = message_id;
+
+        if let Some(mention) = mention {
+            self.chat_input.toggle_focus_with(&mention);
         }
+    }
+
+    /// If a popup is drawn over chat, rather than an input below it.
+    const fn is_popup_focused(&self) -> bool {
+        self.chat_input.is_picker_focused()
+            || self.channel_input.is_focused()
+            || self.following.is_focused()
+            || self.links.is_focused()
+            || self.quick_switch.is_focused()
+            || self.notices.is_focused()
+            || self.whispers.is_focused()
+            || self.raids.is_focused()
+            || self.rewards.is_focused()
+            || self.mentions.is_focused()
+            || self.automod.is_focused()
+            || self.log_search.is_focused()
+            || self.recovered.is_focused()
+            || self.filters_list.is_focused()
+            || self.ignored_list.is_focused()
+    }
+
+    /// Where messages were last drawn, if they can be drawn there again on their own.
+    /// They cannot be if something was drawn over them, or if chat is scrolled up.
+    pub fn viewport(&self) -> Option<Rect> {
+        self.viewport
+            .filter(|_| self.scroll_offset.get_offset() == 0)
+    }
+
+    /// Draw only the messages, for when nothing but new messages changed since the last frame.
+    pub fn draw_viewport(&self, f: &mut Frame, viewport: Rect) {
+        f.render_widget(Clear, viewport);
+
+        self.draw_messages(f, viewport);
+    }
 
+    fn draw_focused(&mut self, f: &mut Frame, input_area: Option<Rect>) {
         if self.chat_input.is_focused() {
-            self.chat_input.draw(f, v_chunks.next().copied());
+            self.chat_input.draw(f, input_area);
         } else if self.channel_input.is_focused() {
             self.channel_input.draw(f, None);
         } else if self.search_input.is_focused() {
-            self.search_input.draw(f, v_chunks.next().copied());
+            self.search_input.draw(f, input_area);
         } else if self.following.is_focused() {
             self.following.draw(f, None);
+        } else if self.links.is_focused() {
+            self.links.draw(f, None);
+        } else if self.quick_switch.is_focused() {
+            self.quick_switch.draw(f, None);
+        } else if self.notices.is_focused() {
+            self.notices.draw(f, None);
+        } else if self.whispers.is_focused() {
+            self.whispers.draw(f, None);
+        } else if self.raids.is_focused() {
+            self.raids.draw(f, None);
+        } else if self.rewards.is_focused() {
+            self.rewards.draw(f, None);
+        } else if self.mentions.is_focused() {
+            self.mentions.draw(f, None);
+        } else if self.automod.is_focused() {
+            self.automod.draw(f, None);
+        } else if self.log_search.is_focused() {
+            self.log_search.draw(f, None);
+        } else if self.recovered.is_focused() {
+            self.recovered.draw(f, None);
+        } else if self.filters_list.is_focused() {
+            self.filters_list.draw(f, None);
+        } else if self.ignored_list.is_focused() {
+            self.ignored_list.draw(f, None);
+        }
+    }
+}
+
+impl Component for ChatWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let r = area.map_or_else(|| f.area(), |a| a);
+
+        let config = self.config.borrow();
+
+        // Only show the tab bar once more than one channel has been opened
+        let show_tabs = self.tabs.borrow().channels().len() > 1;
+
+        let mut v_constraints = vec![];
+
+        if show_tabs {
+            v_constraints.push(Constraint::Length(1));
+        }
+
+        let stream_info_height = self.stream_info.height();
+
+        if stream_info_height > 0 {
+            v_constraints.push(Constraint::Length(stream_info_height));
+        }
+
+        v_constraints.push(Constraint::Min(1));
+
+        if self.chat_input.is_focused() || self.search_input.is_focused() {
+            let lines = if self.chat_input.is_focused() {
+                self.chat_input.height()
+            } else {
+                1
+            };
+
+            // Compact input boxes only have their title above them
+            v_constraints.push(Constraint::Length(if config.frontend.compact {
+                lines + 1
+            } else {
+                lines + 2
+            }));
+        }
+
+        let v_chunks_binding = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(self.config.borrow().frontend.margin())
+            .constraints(v_constraints)
+            .split(r);
+
+        let mut v_chunks: Iter<Rect> = v_chunks_binding.iter();
+
+        if show_tabs {
+            self.draw_channel_tabs(f, *v_chunks.next().unwrap());
+        }
+
+        if stream_info_height > 0 {
+            self.stream_info
+                .draw(f, *v_chunks.next().unwrap(), &config.twitch.channel);
+        }
+
+        let mut first_v_chunk = v_chunks.next().unwrap();
+
+        // Sidebars take space away from the chat, rather than covering it
+        let h_chunks_binding;
+
+        if self.activity.is_visible() || self.mod_log.is_visible() {
+            let mut h_constraints = vec![Constraint::Min(1)];
+
+            if self.mod_log.is_visible() {
+                h_constraints.push(Constraint::Length(MOD_LOG_WIDTH));
+            }
+
+            if self.activity.is_visible() {
+                h_constraints.push(Constraint::Length(ACTIVITY_WIDTH));
+            }
+
+            h_chunks_binding = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(h_constraints)
+                .split(*first_v_chunk);
+
+            let mut sidebars = h_chunks_binding.iter();
+
+            first_v_chunk = sidebars.next().unwrap();
+
+            if self.mod_log.is_visible() {
+                self.mod_log.draw(f, *sidebars.next().unwrap());
+            }
+
+            if self.activity.is_visible() {
+                self.activity.draw(f, *sidebars.next().unwrap());
+            }
+        }
+
+        if self.messages.borrow().len() > self.config.borrow().terminal.maximum_messages {
+            self.messages
+                .borrow_mut()
+                .truncate(self.config.borrow().terminal.maximum_messages);
         }
+
+        drop(config);
+
+        self.draw_messages(f, *first_v_chunk);
+
+        // Popups are drawn over chat, so chat cannot be drawn again without them
+        self.viewport = (!self.is_popup_focused()).then_some(*first_v_chunk);
+
+        let input_area = v_chunks.next().copied();
+
+        self.draw_focused(f, input_area);
     }
 
     async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Mouse(action, position) = event {
+            if self.following.is_focused() {
+                return self.following.event(event).await;
+            } else if self.links.is_focused() {
+                return self.links.event(event).await;
+            } else if self.quick_switch.is_focused() {
+                return self.quick_switch.event(event).await;
+            }
+
+            if !(self.is_popup_focused()
+                || self.chat_input.is_focused()
+                || self.search_input.is_focused())
+            {
+                self.mouse_event(*action, *position);
+            }
+
+            return None;
+        }
+
+        // Only the chat input takes pasted text as a whole, the other inputs have it typed into them
+        if let Event::Paste(text) = event {
+            return if self.chat_input.is_focused() && !self.chat_input.is_picker_focused() {
+                self.chat_input.event(event).await
+            } else if self.search_input.is_focused() || self.is_popup_focused() {
+                Some(TerminalAction::ReplayKeys(paste_keys(text)))
+            } else {
+                None
+            };
+        }
+
         if let Event::Input(key) = event {
             let limit =
                 self.scroll_offset.get_offset() < self.messages.borrow().len().saturating_sub(1);
 
+            let is_normal_mode = !(self.chat_input.is_focused()
+                || self.channel_input.is_focused()
+                || self.search_input.is_focused()
+                || self.following.is_focused()
+                || self.links.is_focused()
+                || self.quick_switch.is_focused()
+                || self.notices.is_focused()
+                || self.whispers.is_focused()
+                || self.raids.is_focused()
+                || self.rewards.is_focused()
+                || self.mentions.is_focused()
+                || self.automod.is_focused()
+                || self.log_search.is_focused()
+                || self.recovered.is_focused()
+                || self.filters_list.is_focused()
+                || self.ignored_list.is_focused()
+                || self.selected.is_some());
+
+            let action = resolve(&self.config.borrow().keybinds, *key);
+
+            // Keys that control macros are never recorded themselves
+            if is_normal_mode {
+                match (action, key) {
+                    (Some(Action::RecordMacro), _) => {
+                        self.macros.toggle_recording();
+                        return None;
+                    }
+                    (Some(Action::ReplayMacro), _) => {
+                        return self
+                            .macros
+                            .replay(&self.config.borrow().keybinds)
+                            .map(TerminalAction::ReplayMacro);
+                    }
+                    (None, Key::Char(c)) if c.is_ascii_digit() => {
+                        self.macros.push_count_digit(c.to_digit(10).unwrap());
+                        return None;
+                    }
+                    _ => {}
+                }
+            }
+
+            // A count only applies to the replay typed straight after it
+            self.macros.clear_count();
+
+            // Actions are recorded rather than their keys, so macros still work after rebinding
+            self.macros.record(match action {
+                Some(action) if is_normal_mode => Recorded::Action(action),
+                _ => Recorded::Key(*key),
+            });
+
             if self.chat_input.is_focused() {
                 self.chat_input.event(event).await
             } else if self.channel_input.is_focused() {
@@ -302,27 +1601,48 @@ impl Component for ChatWidget {
                 self.search_input.event(event).await
             } else if self.following.is_focused() {
                 self.following.event(event).await
+            } else if self.links.is_focused() {
+                self.links.event(event).await
+            } else if self.quick_switch.is_focused() {
+                self.quick_switch.event(event).await
+            } else if self.notices.is_focused() {
+                self.notices.event(event).await
+            } else if self.whispers.is_focused() {
+                self.whispers.event(event).await
+            } else if self.raids.is_focused() {
+                self.raids.event(event).await
+            } else if self.rewards.is_focused() {
+                self.rewards.event(event).await
+            } else if self.mentions.is_focused() {
+                self.mentions.event(event).await
+            } else if self.automod.is_focused() {
+                self.automod.event(event).await
+            } else if self.log_search.is_focused() {
+                self.log_search.event(event).await
+            } else if self.filters_list.is_focused() {
+                self.filters_list.event(event).await
+            } else if self.ignored_list.is_focused() {
+                self.ignored_list.event(event).await
+            } else if self.recovered.is_focused() {
+                let action = self.recovered.event(event).await;
+
+                if let Some(edited) = self.recovered.take_edited() {
+                    self.chat_input.set_draft(&edited);
+                    self.chat_input.toggle_focus();
+                }
+
+                action
+            } else if self.selected.is_some() {
+                self.selection_event(*key)
             } else {
+                if let Some(action) = action {
+                    return self.normal_action(action).await;
+                }
+
                 match key {
-                    Key::Char('i' | 'c') => self.chat_input.toggle_focus(),
-                    Key::Char('@') => self.chat_input.toggle_focus_with("@"),
-                    Key::Char('/') => self.chat_input.toggle_focus_with("/"),
-                    Key::Char('s') => self.channel_input.toggle_focus(),
-                    Key::Ctrl('f') => self.search_input.toggle_focus(),
-                    Key::Char('f') => self.following.toggle_focus().await,
-                    Key::Ctrl('t') => self.filters.borrow_mut().toggle(),
-                    Key::Ctrl('r') => self.filters.borrow_mut().reverse(),
-                    Key::Char('S') => return Some(TerminalAction::SwitchState(State::Dashboard)),
-                    Key::Char('?' | 'h') => return Some(TerminalAction::SwitchState(State::Help)),
-                    Key::Char('q') => return Some(TerminalAction::Quit),
-                    Key::Char('o') => self.open_in_browser(),
-                    Key::Char('G') => {
-                        self.scroll_offset.jump_to(0);
-                    }
-                    Key::Char('g') => {
-                        // TODO: Make this not jump to nothingness
-                        self.scroll_offset.jump_to(self.messages.borrow().len());
-                    }
+                    Key::Char('n') if self.search_input.is_active() => self.jump_to_match(true),
+                    Key::Char('N') if self.search_input.is_active() => self.jump_to_match(false),
+                    Key::Esc if self.search_input.is_active() => self.search_input.deactivate(),
                     Key::Esc => {
                         if self.scroll_offset.get_offset() == 0 {
                             return Some(TerminalAction::BackOneLayer);
@@ -1,83 +1,241 @@
-use std::fmt::Display;
+use std::{cell::RefCell, fmt::Display, rc::Rc};
 
-use tui::{Frame, layout::Rect};
+use chrono::Local;
+use tui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, ListState},
+};
 
 use crate::{
     emotes::SharedEmotes,
     handlers::{
-        config::SharedCompleteConfig,
+        completion::{CompletionCycle, CompletionRanking, SharedRecentChatters},
+        config::{SharedCompleteConfig, Widget},
+        history::InputHistory,
+        nicknames::SharedNicknames,
         storage::SharedStorage,
+        tabs::Draft,
         user_input::events::{Event, Key},
     },
     terminal::TerminalAction,
-    twitch::TwitchAction,
+    twitch::{TwitchAction, moderation::USER_COMMANDS},
     ui::{
-        components::{Component, emote_picker::EmotePickerWidget, utils::InputWidget},
-        statics::{COMMANDS, TWITCH_MESSAGE_LIMIT},
+        components::{
+            Component, emoji_picker::EmojiPickerWidget, emote_picker::EmotePickerWidget,
+            utils::InputWidget,
+        },
+        statics::{COMMANDS, Command, TWITCH_MESSAGE_LIMIT, find_command},
+    },
+    utils::{
+        emoji::{expand_shortcodes, trailing_shortcode},
+        emotes::emotes_enabled,
+        styles::{HINT_STYLE, NO_COLOR},
+        text::{first_similarity, split_outgoing},
     },
-    utils::{emotes::emotes_enabled, text::first_similarity},
 };
 
+/// The most completions shown above the input at once.
+const SHOWN_COMPLETIONS: usize = 8;
+/// The most messages a message is split into before Enter has to be pressed again to send it.
+const MAXIMUM_UNCONFIRMED_PARTS: usize = 3;
+
+/// How a command is used, followed by what it does.
+fn command_line(command: &Command) -> Line<'static> {
+    Line::from(vec![
+        Span::raw(command.usage()),
+        Span::raw("  "),
+        Span::styled(command.description, *HINT_STYLE),
+    ])
+}
+
+/// Everything that completions are suggested from.
+#[derive(Clone)]
+struct CompletionSources {
+    config: SharedCompleteConfig,
+    storage: SharedStorage,
+    emotes: SharedEmotes,
+    chatters: SharedRecentChatters,
+    nicknames: SharedNicknames,
+    ranking: Rc<RefCell<CompletionRanking>>,
+}
+
+impl CompletionSources {
+    /// The lowercase names of the users who chatted in a channel, the most recent first.
+    fn recent_chatters(&self, channel: &str) -> Vec<String> {
+        self.chatters
+            .borrow()
+            .recent(channel)
+            .map(|user| user.to_lowercase())
+            .collect()
+    }
+}
+
 pub struct ChatInputWidget {
     config: SharedCompleteConfig,
     storage: SharedStorage,
-    input: InputWidget<SharedStorage>,
+    input: InputWidget<CompletionSources>,
+    /// Usage of the words sent in each channel, to rank completions.
+    ranking: Rc<RefCell<CompletionRanking>>,
     emote_picker: EmotePickerWidget,
+    emoji_picker: EmojiPickerWidget,
+    emotes: SharedEmotes,
+    /// The ID and author of the message being replied to, if any.
+    reply_to: Option<(String, String)>,
+    /// The users who chatted in each channel, to complete mentions of them.
+    chatters: SharedRecentChatters,
+    /// The words that the last word is being completed to with Tab, if any.
+    completion: Option<CompletionCycle>,
+    /// The messages sent in each channel, to recall them with Up and Down.
+    history: InputHistory,
+    /// What was typed before recalling sent messages that start with it, and the position of the one recalled.
+    recall: Option<(String, usize)>,
+    /// If Enter was pressed once on a message that is split into too many messages, to send it with Enter again.
+    confirming_split: bool,
 }
 
 impl ChatInputWidget {
-    pub fn new(config: SharedCompleteConfig, storage: SharedStorage, emotes: SharedEmotes) -> Self {
-        let input_validator =
-            Box::new(|_, s: String| -> bool { !s.is_empty() && s.len() < TWITCH_MESSAGE_LIMIT });
+    pub fn new(
+        config: SharedCompleteConfig,
+        storage: SharedStorage,
+        emotes: SharedEmotes,
+        chatters: SharedRecentChatters,
+        nicknames: SharedNicknames,
+    ) -> Self {
+        // Messages that are too long are sent as more than one
+        let input_validator = Box::new(|_, s: String| -> bool { !s.trim().is_empty() });
 
         // User should be known of how close they are to the message length limit.
-        let visual_indicator =
-            Box::new(|s: String| -> String { format!("{} / {}", s.len(), TWITCH_MESSAGE_LIMIT) });
-
-        let input_suggester = Box::new(|storage: SharedStorage, s: String| -> Option<String> {
-            s.chars()
-                .next()
-                .and_then(|start_character| match start_character {
-                    '/' => {
-                        let possible_suggestion = first_similarity(
-                            &COMMANDS
-                                .iter()
-                                .map(ToString::to_string)
-                                .collect::<Vec<String>>(),
-                            &s[1..],
-                        );
-
-                        let default_suggestion = possible_suggestion.clone();
-
-                        possible_suggestion.map_or(default_suggestion, |s| Some(format!("/{s}")))
+        let visual_indicator = Box::new(|s: String| -> String {
+            let length = s.chars().count();
+
+            match split_outgoing(&s, TWITCH_MESSAGE_LIMIT).1.len() {
+                0 | 1 => format!("{length} / {TWITCH_MESSAGE_LIMIT}"),
+                messages => {
+                    format!("{length} / {TWITCH_MESSAGE_LIMIT}, sent as {messages} messages")
+                }
+            }
+        });
+
+        let input_suggester = Box::new(|sources: CompletionSources, s: String| -> Option<String> {
+            let channel = sources.config.borrow().twitch.channel.clone();
+            let ranking = sources.ranking.borrow();
+            let now = Local::now();
+
+            match s.chars().next()? {
+                '/' => match s[1..].split_once(' ') {
+                    // Complete the user being moderated with someone who chatted recently
+                    Some((command, user))
+                        if USER_COMMANDS.contains(&command) && !user.contains(' ') =>
+                    {
+                        let user = user.trim_start_matches('@');
+                        let chatters = sources.recent_chatters(&channel);
+
+                        ranking
+                            .best(&channel, chatters.iter(), user, &now)
+                            .map(|u| format!("/{command} {u}"))
+                    }
+                    Some(_) => None,
+                    None => {
+                        let commands = COMMANDS
+                            .iter()
+                            .map(|command| command.name.to_string())
+                            .collect::<Vec<String>>();
+
+                        ranking
+                            .best(&channel, commands.iter(), &s[1..], &now)
+                            .map(|c| format!("/{c}"))
                     }
-                    '@' => {
-                        let possible_suggestion =
-                            first_similarity(&storage.borrow().get("mentions"), &s[1..]);
+                },
+                start_character => {
+                    // Complete the last word with the emote or username used the most in this channel
+                    let (before, word) = s.rsplit_once(' ').unwrap_or(("", &s));
 
-                        let default_suggestion = possible_suggestion.clone();
+                    let emotes = sources.emotes.user_emotes.borrow();
+                    let global_emotes = sources.emotes.global_emotes.borrow();
 
-                        possible_suggestion.map_or(default_suggestion, |s| Some(format!("@{s}")))
+                    let suggestion = if word.starts_with('@') {
+                        ranking.best(
+                            &channel,
+                            ranking.words(&channel).filter(|w| w.starts_with('@')),
+                            word,
+                            &now,
+                        )
+                    } else {
+                        ranking.best(
+                            &channel,
+                            emotes.keys().chain(global_emotes.keys()),
+                            word,
+                            &now,
+                        )
+                    };
+
+                    // Users can be mentioned by their nickname, which is completed to their name
+                    let suggestion = suggestion.cloned().or_else(|| {
+                        let nickname = word.strip_prefix('@')?;
+
+                        sources
+                            .nicknames
+                            .borrow()
+                            .find_user(nickname)
+                            .map(|user| format!("@{user}"))
+                    });
+
+                    match suggestion {
+                        Some(suggestion) if before.is_empty() => Some(suggestion),
+                        Some(suggestion) => Some(format!("{before} {suggestion}")),
+                        None if start_character == '@' => {
+                            first_similarity(&sources.storage.borrow().get("mentions"), &s[1..])
+                                .map(|s| format!("@{s}"))
+                        }
+                        None => None,
                     }
-                    _ => None,
-                })
+                }
+            }
         });
 
-        let input = InputWidget::new(
+        let sources = CompletionSources {
+            config: config.clone(),
+            storage: storage.clone(),
+            emotes: emotes.clone(),
+            chatters: chatters.clone(),
+            nicknames,
+            ranking: Rc::new(RefCell::new(CompletionRanking::from_storage(
+                &storage.borrow().get("completions"),
+            ))),
+        };
+
+        let mut input = InputWidget::new(
             config.clone(),
             "Chat",
-            Some((storage.clone(), input_validator)),
+            Some((sources.clone(), input_validator)),
             Some(visual_indicator),
-            Some((storage.clone(), input_suggester)),
+            Some((sources.clone(), input_suggester)),
         );
 
-        let emote_picker = EmotePickerWidget::new(config.clone(), emotes);
+        input.set_multiline(true);
+
+        let emote_picker = EmotePickerWidget::new(config.clone(), emotes.clone());
+        let emoji_picker = EmojiPickerWidget::new(config.clone());
+
+        let history = InputHistory::from_storage(&storage.borrow().get("history"));
 
         Self {
             config,
             storage,
+            ranking: sources.ranking,
             input,
             emote_picker,
+            emoji_picker,
+            emotes,
+            reply_to: None,
+            chatters,
+            completion: None,
+            history,
+            recall: None,
+            confirming_split: false,
         }
     }
 
@@ -85,13 +243,363 @@ impl ChatInputWidget {
         self.input.is_focused()
     }
 
+    /// If the emote or emoji picker is open over chat.
+    pub const fn is_picker_focused(&self) -> bool {
+        self.emote_picker.is_focused() || self.emoji_picker.is_focused()
+    }
+
     pub fn toggle_focus(&mut self) {
         self.input.toggle_focus();
     }
 
+    pub fn height(&self) -> u16 {
+        self.input.height()
+    }
+
     pub fn toggle_focus_with(&mut self, s: &str) {
         self.input.toggle_focus_with(s);
     }
+
+    /// Start typing a message with the emote picker open, if emotes are shown.
+    pub fn open_emote_picker(&mut self) {
+        if !emotes_enabled(&self.config.borrow().frontend) {
+            return;
+        }
+
+        if !self.is_focused() {
+            self.input.toggle_focus();
+        }
+
+        if !self.emote_picker.is_focused() {
+            self.emote_picker.toggle_focus();
+        }
+    }
+
+    /// Start typing a reply to a message.
+    pub fn reply_to(&mut self, parent_id: String, user: &str) {
+        self.input.set_title(&format!("Reply to @{user}"));
+        self.reply_to = Some((parent_id, user.to_string()));
+
+        if !self.is_focused() {
+            self.input.toggle_focus();
+        }
+    }
+
+    fn stop_replying(&mut self) {
+        self.reply_to = None;
+        self.input.set_title("Chat");
+    }
+
+    /// Stop asking to send a message as many messages, since what was typed changed.
+    fn stop_confirming(&mut self) {
+        if !self.confirming_split {
+            return;
+        }
+
+        self.confirming_split = false;
+
+        match &self.reply_to {
+            Some((_, user)) => self.input.set_title(&format!("Reply to @{user}")),
+            None => self.input.set_title("Chat"),
+        }
+    }
+
+    /// Count the commands, emotes, and mentions in a sent message, so they are suggested first next time.
+    fn record_usage(&self, message: &str) {
+        let channel = self.config.borrow().twitch.channel.clone();
+        let now = Local::now();
+        let mut ranking = self.ranking.borrow_mut();
+
+        if let Some(command) = message.strip_prefix('/') {
+            if let Some(command) = command.split_whitespace().next() {
+                ranking.record(&channel, command, now);
+            }
+        } else {
+            let emotes = self.emotes.user_emotes.borrow();
+            let global_emotes = self.emotes.global_emotes.borrow();
+
+            for word in message.split_whitespace() {
+                let word = if word.starts_with('@') {
+                    word.trim_end_matches(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                } else {
+                    word
+                };
+
+                let is_mention = word.len() > 1 && word.starts_with('@');
+
+                if is_mention || emotes.contains_key(word) || global_emotes.contains_key(word) {
+                    ranking.record(&channel, word, now);
+                }
+            }
+        }
+
+        if self.config.borrow().storage.completions {
+            self.storage
+                .borrow_mut()
+                .set("completions", ranking.to_storage(&now));
+        }
+    }
+
+    /// Replace a `:shortcode:` just typed before the cursor with its emoji.
+    fn expand_shortcode(&mut self) {
+        if !self.config.borrow().frontend.emoji_shortcodes {
+            return;
+        }
+
+        if let Some((length, emoji)) = trailing_shortcode(self.input.before_cursor()) {
+            self.input.replace_before_cursor(length, emoji);
+        }
+    }
+
+    /// Remember a sent message, to recall it later.
+    fn record_history(&mut self, message: &str) {
+        let channel = self.config.borrow().twitch.channel.clone();
+
+        self.history.push(&channel, message);
+        self.recall = None;
+
+        if self.config.borrow().storage.history {
+            self.storage
+                .borrow_mut()
+                .set("history", self.history.to_storage());
+        }
+    }
+
+    /// Replace the input with the next older sent message that starts with what was typed,
+    /// or the next newer one, going back to what was typed after the newest.
+    fn recall_history(&mut self, older: bool) {
+        let channel = self.config.borrow().twitch.channel.clone();
+
+        let (typed, position) = self.recall.take().map_or_else(
+            || (self.input.to_string(), None),
+            |(typed, i)| (typed, Some(i)),
+        );
+
+        let recalled = if older {
+            self.history.older(&channel, &typed, position)
+        } else {
+            position.and_then(|i| self.history.newer(&channel, &typed, i))
+        };
+
+        match (recalled, position) {
+            (Some((i, message)), _) => {
+                self.input.update(message);
+                self.recall = Some((typed, i));
+            }
+            // The oldest message stays recalled
+            (None, Some(i)) if older => self.recall = Some((typed, i)),
+            // Going past the newest message brings back what was typed
+            (None, Some(_)) => self.input.update(&typed),
+            (None, None) => {}
+        }
+    }
+
+    /// Start completing the last word with the emotes it is the start of, the ones used the most in this channel first,
+    /// or with the users who chatted the most recently in this channel if it starts with `@`,
+    /// or the name of the command being typed.
+    fn start_completion(&self) -> Option<CompletionCycle> {
+        let input = self.input.to_string();
+        let channel = self.config.borrow().twitch.channel.clone();
+
+        if let Some(name) = input.strip_prefix('/') {
+            // The arguments of commands are completed by the suggestions of the input instead
+            if name.contains(' ') {
+                return None;
+            }
+
+            let candidates = if name.is_empty() {
+                COMMANDS
+                    .iter()
+                    .map(|command| command.name.to_string())
+                    .collect::<Vec<String>>()
+            } else {
+                let names = COMMANDS
+                    .iter()
+                    .map(|command| command.name.to_string())
+                    .collect::<Vec<String>>();
+
+                self.ranking
+                    .borrow()
+                    .ranked(&channel, names.iter(), name, &Local::now())
+            };
+
+            return CompletionCycle::new(
+                &input,
+                candidates
+                    .into_iter()
+                    .map(|name| format!("/{name}"))
+                    .collect(),
+            );
+        }
+
+        let word = input
+            .rsplit_once(' ')
+            .map_or(input.as_str(), |(_, word)| word);
+
+        if let Some(user) = word.strip_prefix('@') {
+            let candidates = self
+                .chatters
+                .borrow()
+                .matching(&channel, user)
+                .into_iter()
+                .map(|user| format!("@{user}"))
+                .collect();
+
+            return CompletionCycle::new(&input, candidates);
+        }
+        let emotes = self.emotes.user_emotes.borrow();
+        let global_emotes = self.emotes.global_emotes.borrow();
+
+        let candidates = self.ranking.borrow().ranked(
+            &channel,
+            emotes.keys().chain(global_emotes.keys()),
+            word,
+            &Local::now(),
+        );
+
+        CompletionCycle::new(&input, candidates)
+    }
+
+    /// Go to the next or previous completion, starting to complete the last word if it is not being completed yet.
+    /// Returns if there was anything to complete.
+    fn cycle_completion(&mut self, forward: bool) -> bool {
+        if let Some(cycle) = &mut self.completion {
+            if forward {
+                cycle.next();
+            } else {
+                cycle.previous();
+            }
+        } else {
+            self.completion = self.start_completion();
+        }
+
+        let Some(cycle) = &self.completion else {
+            return false;
+        };
+
+        self.input.update(&cycle.input());
+
+        true
+    }
+
+    /// The commands that what is being typed could be, or the command it is if its arguments are being typed.
+    fn command_hints(&self) -> Vec<&'static Command> {
+        let input = self.input.to_string();
+
+        let Some(typed) = input.strip_prefix('/') else {
+            return vec![];
+        };
+
+        match typed.split_once(' ') {
+            Some((name, _)) => find_command(name).into_iter().collect(),
+            None => COMMANDS
+                .iter()
+                .filter(|command| command.name.starts_with(typed))
+                .collect(),
+        }
+    }
+
+    /// List the completions above the input with the selected one highlighted,
+    /// or the commands matching what is being typed along with how to use them.
+    fn draw_completions(&self, f: &mut Frame, input_area: Rect) {
+        let (items, selected) = self.completion.as_ref().map_or_else(
+            || {
+                let items = self
+                    .command_hints()
+                    .into_iter()
+                    .map(command_line)
+                    .collect::<Vec<Line>>();
+
+                (items, None)
+            },
+            |cycle| {
+                let items = cycle
+                    .candidates()
+                    .iter()
+                    .map(|candidate| {
+                        candidate
+                            .strip_prefix('/')
+                            .and_then(find_command)
+                            .map_or_else(|| Line::from(candidate.clone()), command_line)
+                    })
+                    .collect::<Vec<Line>>();
+
+                (items, Some(cycle.selected()))
+            },
+        );
+
+        if items.is_empty() {
+            return;
+        }
+
+        let shown = items.len().min(SHOWN_COMPLETIONS);
+
+        // The list scrolls along with the selection once it goes past what is shown
+        let offset = selected.map_or(0, |selected| (selected + 1).saturating_sub(shown));
+
+        let width = items.iter().map(Line::width).max().unwrap_or_default() as u16 + 4;
+        let height = shown as u16 + 2;
+
+        let area = Rect::new(
+            input_area.x,
+            input_area.y.saturating_sub(height),
+            width.max(20).min(input_area.width),
+            height.min(input_area.y),
+        );
+
+        let list = List::new(
+            items
+                .into_iter()
+                .skip(offset)
+                .take(shown)
+                .map(ListItem::new),
+        )
+        .block(self.config.borrow().block(Widget::Popup))
+        .highlight_style(if *NO_COLOR {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+                .bg(Color::LightGreen)
+                .add_modifier(Modifier::BOLD)
+        });
+
+        let mut state = ListState::default().with_selected(selected.map(|i| i - offset));
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut state);
+    }
+
+    /// Take out what has been typed so far and the message it replies to, leaving the input empty.
+    pub fn take_draft(&mut self) -> Draft {
+        let draft = Draft {
+            text: self.input.to_string(),
+            reply_to: self.reply_to.clone(),
+        };
+
+        self.input.clear();
+        self.stop_replying();
+        self.completion = None;
+        self.recall = None;
+
+        draft
+    }
+
+    /// Bring back what was typed in a channel, along with the message it replies to.
+    pub fn restore_draft(&mut self, draft: Draft) {
+        self.input.update(&draft.text);
+
+        match draft.reply_to {
+            Some((parent_id, user)) => {
+                self.input.set_title(&format!("Reply to @{user}"));
+                self.reply_to = Some((parent_id, user));
+            }
+            None => self.stop_replying(),
+        }
+    }
+
+    pub fn set_draft(&mut self, draft: &str) {
+        self.input.update(draft);
+    }
 }
 
 impl Display for ChatInputWidget {
@@ -104,9 +612,17 @@ impl Component for ChatInputWidget {
     fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
         self.input.draw(f, area);
 
+        if let Some(area) = area {
+            self.draw_completions(f, area);
+        }
+
         if self.emote_picker.is_focused() {
             self.emote_picker.draw(f, None);
         }
+
+        if self.emoji_picker.is_focused() {
+            self.emoji_picker.draw(f, None);
+        }
     }
 
     async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
@@ -117,16 +633,87 @@ impl Component for ChatInputWidget {
                 self.input.insert(&emote);
                 self.input.insert(" ");
             }
+        } else if self.emoji_picker.is_focused() {
+            if let Some(TerminalAction::Enter(TwitchAction::Privmsg(emoji))) =
+                self.emoji_picker.event(event).await
+            {
+                self.input.insert(&emoji);
+            }
+        } else if let Event::Paste(_) = event {
+            self.completion = None;
+            self.recall = None;
+            self.stop_confirming();
+
+            self.input.event(event).await;
         } else if let Event::Input(key) = event {
+            if !matches!(key, Key::Up | Key::Down) {
+                self.recall = None;
+            }
+
+            if *key != Key::Enter {
+                self.stop_confirming();
+            }
+
+            // Any other key keeps the completion as it is
+            let completing = if matches!(key, Key::Tab | Key::BackTab | Key::Esc) {
+                self.completion.is_some()
+            } else {
+                self.completion = None;
+                false
+            };
+
             match key {
+                Key::Tab | Key::BackTab if completing => {
+                    self.cycle_completion(*key == Key::Tab);
+                }
+                Key::Tab if self.cycle_completion(true) => {}
+                // Esc goes back to what was typed before completing, rather than leaving the input
+                Key::Esc if completing => {
+                    if let Some(cycle) = self.completion.take() {
+                        self.input.update(cycle.original());
+                    }
+                }
                 Key::Enter => {
                     if self.input.is_valid() {
-                        let current_input = self.input.to_string();
+                        let mut current_input = self.input.to_string();
+
+                        // Shortcodes that were pasted rather than typed are expanded when sending
+                        if self.config.borrow().frontend.emoji_shortcodes
+                            && !current_input.starts_with('/')
+                        {
+                            current_input = expand_shortcodes(&current_input).into_owned();
+                        }
+
+                        // Pastes that would flood chat are only sent once Enter is pressed again
+                        let parts = split_outgoing(&current_input, TWITCH_MESSAGE_LIMIT).1.len();
+
+                        if parts > MAXIMUM_UNCONFIRMED_PARTS && !self.confirming_split {
+                            self.confirming_split = true;
+                            self.input
+                                .set_title(&format!("Send as {parts} messages? Press Enter again"));
+
+                            return None;
+                        }
 
-                        let action =
-                            TerminalAction::Enter(TwitchAction::Privmsg(current_input.clone()));
+                        self.confirming_split = false;
+
+                        // Commands cannot be replies
+                        let action = match self.reply_to.take() {
+                            Some((parent_id, _)) if !current_input.starts_with('/') => {
+                                TerminalAction::Enter(TwitchAction::Reply(
+                                    parent_id,
+                                    current_input.clone(),
+                                ))
+                            }
+                            _ => {
+                                TerminalAction::Enter(TwitchAction::Privmsg(current_input.clone()))
+                            }
+                        };
 
                         self.input.clear();
+                        self.stop_replying();
+                        self.record_usage(&current_input);
+                        self.record_history(&current_input);
 
                         if let Some(message) = current_input.strip_prefix('@') {
                             if self.config.borrow().storage.mentions {
@@ -143,16 +730,24 @@ impl Component for ChatInputWidget {
                         return Some(action);
                     }
                 }
+                Key::Up => self.recall_history(true),
+                Key::Down => self.recall_history(false),
                 Key::Alt('e') => {
                     if emotes_enabled(&self.config.borrow().frontend) {
                         self.emote_picker.toggle_focus();
                     }
                 }
+                Key::Alt('u') => self.emoji_picker.toggle_focus(),
                 Key::Esc => {
+                    self.stop_replying();
                     self.input.toggle_focus();
                 }
                 _ => {
                     self.input.event(event).await;
+
+                    if *key == Key::Char(':') {
+                        self.expand_shortcode();
+                    }
                 }
             }
         }
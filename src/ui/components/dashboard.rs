@@ -14,12 +14,15 @@ use crate::{
         config::SharedCompleteConfig,
         state::State,
         storage::SharedStorage,
-        user_input::events::{Event, Key},
+        user_input::{
+            events::{Event, Key, paste_keys},
+            keybinds::{Action, resolve},
+        },
     },
     terminal::TerminalAction,
     twitch::TwitchAction,
     ui::components::{ChannelSwitcherWidget, Component},
-    utils::styles::{DASHBOARD_SECTION_STYLE, DASHBOARD_TITLE_COLOR_STYLE, TEXT_DARK_STYLE},
+    utils::styles::{DASHBOARD_SECTION_STYLE, DASHBOARD_TITLE_COLOR_STYLE, text_style},
 };
 
 const DASHBOARD_TITLE: [&str; 5] = [
@@ -40,7 +43,7 @@ pub struct DashboardWidget {
 impl DashboardWidget {
     pub fn new(config: SharedCompleteConfig, storage: SharedStorage) -> Self {
         let channel_input = ChannelSwitcherWidget::new(config.clone(), storage.clone());
-        let following = FollowingWidget::new(config.clone());
+        let following = FollowingWidget::new(config.clone(), storage.clone());
 
         Self {
             config,
@@ -66,7 +69,7 @@ impl DashboardWidget {
                 Span::raw(s),
             ]))
         }))
-        .style(*TEXT_DARK_STYLE)
+        .style(text_style())
         .highlight_style(Style::default().add_modifier(Modifier::ITALIC))
     }
 
@@ -212,6 +215,15 @@ impl Component for DashboardWidget {
     }
 
     async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if matches!(event, Event::Mouse(..)) && self.following.is_focused() {
+            return self.following.event(event).await;
+        }
+
+        if let Event::Paste(text) = event {
+            return (self.channel_input.is_focused() || self.following.is_focused())
+                .then(|| TerminalAction::ReplayKeys(paste_keys(text)));
+        }
+
         if let Event::Input(key) = event {
             if self.channel_input.is_focused() {
                 return self.channel_input.event(event).await;
@@ -219,11 +231,26 @@ impl Component for DashboardWidget {
                 return self.following.event(event).await;
             }
 
+            let action = resolve(&self.config.borrow().keybinds, *key);
+
+            match action {
+                Some(Action::Quit) => return Some(TerminalAction::Quit),
+                Some(Action::ChannelSwitcher) => {
+                    self.channel_input.toggle_focus();
+                    return None;
+                }
+                Some(Action::Following) => {
+                    self.following.toggle_focus().await;
+                    return None;
+                }
+                Some(Action::Help) => return Some(TerminalAction::SwitchState(State::Help)),
+                Some(Action::About) => return Some(TerminalAction::ToggleAbout),
+                Some(Action::RotateTheme) => return Some(TerminalAction::RotateTheme),
+                _ => {}
+            }
+
             match key {
                 Key::Ctrl('p') => panic!("Manual panic triggered by user."),
-                Key::Char('q') => return Some(TerminalAction::Quit),
-                Key::Char('s') => self.channel_input.toggle_focus(),
-                Key::Char('f') => self.following.toggle_focus().await,
                 Key::Enter => {
                     let action = TerminalAction::Enter(TwitchAction::Join(
                         self.config.borrow().twitch.channel.clone(),
@@ -231,7 +258,6 @@ impl Component for DashboardWidget {
 
                     return Some(action);
                 }
-                Key::Char('?' | 'h') => return Some(TerminalAction::SwitchState(State::Help)),
                 Key::Char(c) => {
                     if let Some(selection) = c.to_digit(10) {
                         let mut channels = self.config.borrow().frontend.favorite_channels.clone();
@@ -8,14 +8,17 @@ use tui::{
 
 use crate::{
     handlers::{
-        config::{SharedCompleteConfig, ToVec},
+        config::{SharedCompleteConfig, ToVec, Widget},
         user_input::events::{Event, Key},
     },
     terminal::TerminalAction,
+    twitch::{cache::cache_stats, oauth::token_stats},
     ui::components::Component,
     utils::{
-        styles::{BOLD_STYLE, TITLE_STYLE},
+        styles::{BOLD_STYLE, border_style, title_style},
         text::{TitleStyle, title_line},
+        time::{format_datetime, format_duration},
+        traffic::traffic_stats,
     },
 };
 
@@ -52,6 +55,18 @@ impl DebugWidget {
             ("Storage Config".to_string(), c.storage.to_vec()),
             ("Filter Config".to_string(), c.filters.to_vec()),
             ("Frontend Config".to_string(), c.frontend.to_vec()),
+            ("Emotes Config".to_string(), c.emotes.to_vec()),
+            ("Alerts Config".to_string(), c.alerts.to_vec()),
+            ("Notifications Config".to_string(), c.notifications.to_vec()),
+            ("Moderation Config".to_string(), c.moderation.to_vec()),
+            ("Sounds Config".to_string(), c.sounds.to_vec()),
+            ("Cache Config".to_string(), c.cache.to_vec()),
+            ("Logs Config".to_string(), c.logs.to_vec()),
+            ("Highlights Config".to_string(), c.highlights.to_vec()),
+            ("Keybinds Config".to_string(), c.keybinds.to_vec()),
+            ("Token".to_string(), token_stats()),
+            ("Cache".to_string(), cache_stats()),
+            ("Network".to_string(), traffic_stats()),
         ]
     }
 }
@@ -86,26 +101,28 @@ impl Component for DebugWidget {
         let title_binding = [TitleStyle::Single("Debug")];
 
         let table = Table::new(rows, &[Constraint::Length(25), Constraint::Length(25)]).block(
-            Block::default()
-                .title(title_line(&title_binding, *TITLE_STYLE))
-                .borders(Borders::ALL)
-                .border_type(self.config.borrow().frontend.border_type.clone().into()),
+            self.config
+                .borrow()
+                .block(Widget::Popup)
+                .title(title_line(&title_binding, title_style())),
         );
 
         f.render_widget(Clear, r);
         f.render_widget(table, r);
 
-        let title_binding = self
-            .startup_time
-            .format(&self.config.borrow().frontend.datetime_format)
-            .to_string();
+        let title_binding = format_datetime(&self.startup_time, &self.config.borrow().frontend);
+        let uptime_binding = format_duration(Local::now() - self.startup_time);
 
-        let title = [TitleStyle::Combined("Startup time", &title_binding)];
+        let title = [
+            TitleStyle::Combined("Startup time", &title_binding),
+            TitleStyle::Combined("Uptime", &uptime_binding),
+        ];
 
         let bottom_block = Block::default()
             .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
-            .border_type(self.config.borrow().frontend.border_type.clone().into())
-            .title(title_line(&title, *TITLE_STYLE))
+            .border_type(self.config.borrow().border_type(Widget::Popup))
+            .border_style(border_style())
+            .title(title_line(&title, title_style()))
             .title_position(Position::Bottom)
             .title_alignment(Alignment::Left);
 
@@ -0,0 +1,64 @@
+use tui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+
+use crate::{
+    handlers::config::{SharedCompleteConfig, Widget},
+    twitch::oauth::DeviceCode,
+    ui::components::{Component, utils::centered_rect},
+    utils::{
+        styles::title_style,
+        text::{TitleStyle, title_line},
+    },
+};
+
+/// Shows where to log in and the code to enter there, while waiting for it to be entered.
+pub struct DeviceCodeWidget {
+    config: SharedCompleteConfig,
+    code: DeviceCode,
+}
+
+impl DeviceCodeWidget {
+    pub const fn new(config: SharedCompleteConfig, code: DeviceCode) -> Self {
+        Self { config, code }
+    }
+}
+
+impl Component for DeviceCodeWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let r = area.map_or_else(|| centered_rect(60, 20, 9, f.area()), |a| a);
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+
+        let lines = vec![
+            Line::from("Go to"),
+            Line::from(Span::styled(self.code.verification_uri.clone(), bold)),
+            Line::from("and enter the code"),
+            Line::from(Span::styled(self.code.user_code.clone(), bold)),
+            Line::default(),
+            Line::from(Span::styled(
+                "Waiting for the code to be entered...",
+                Style::default().add_modifier(Modifier::DIM),
+            )),
+        ];
+
+        let title_binding = [
+            TitleStyle::Single("Log in to Twitch"),
+            TitleStyle::Combined("Esc", "quit"),
+        ];
+
+        let paragraph = Paragraph::new(lines).alignment(Alignment::Center).block(
+            self.config
+                .borrow()
+                .block(Widget::Popup)
+                .title(title_line(&title_binding, title_style())),
+        );
+
+        f.render_widget(Clear, r);
+        f.render_widget(paragraph, r);
+    }
+}
@@ -0,0 +1,89 @@
+use tui::{Frame, layout::Rect};
+
+use super::utils::{NameMatcher, SearchableList, centered_rect, split_input};
+use crate::{
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    twitch::TwitchAction,
+    ui::components::{Component, utils::InputWidget},
+    utils::{
+        emoji::{EMOJI, Emoji},
+        text::TitleStyle,
+    },
+};
+
+/// Unicode emoji searched by their shortcodes, to put them in a message.
+pub struct EmojiPickerWidget {
+    input: InputWidget<()>,
+    list: SearchableList<Emoji>,
+}
+
+impl EmojiPickerWidget {
+    pub fn new(config: SharedCompleteConfig) -> Self {
+        let input = InputWidget::new(config.clone(), "Emoji", None, None, None);
+
+        let list = SearchableList::new(config, Widget::Popup);
+
+        Self { input, list }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.input.is_focused()
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.input.toggle_focus();
+    }
+}
+
+impl Component for EmojiPickerWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let [r, input_rect] =
+            split_input(area.map_or_else(|| centered_rect(60, 60, 23, f.area()), |a| a));
+
+        let items = self
+            .list
+            .filter(EMOJI, &self.input.to_string(), &NameMatcher);
+
+        self.list.draw(f, r, items, &[TitleStyle::Single("Emoji")]);
+
+        self.input.draw(f, Some(input_rect));
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => self.toggle_focus(),
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
+                }
+                Key::Enter => {
+                    if let Some(emoji) = self.list.selected().copied() {
+                        self.toggle_focus();
+                        self.input.clear();
+                        self.list.clear();
+
+                        return Some(TerminalAction::Enter(TwitchAction::Privmsg(
+                            emoji.emoji.to_string(),
+                        )));
+                    }
+                }
+                _ => {
+                    self.input.event(event).await;
+
+                    // Assuming that the user inputted something that modified the input
+                    self.list.select_first();
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -1,20 +1,16 @@
-use std::cmp::max;
-
-use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
 use log::warn;
-use once_cell::sync::Lazy;
 use tui::{
     Frame,
-    layout::Rect,
+    layout::{Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    widgets::{Clear, Paragraph},
 };
 
 use crate::{
     emotes::{SharedEmotes, load_picker_emote},
     handlers::{
-        config::SharedCompleteConfig,
+        config::{SharedCompleteConfig, Widget},
         user_input::events::{Event, Key},
     },
     terminal::TerminalAction,
@@ -22,27 +18,36 @@ use crate::{
     ui::{
         components::{
             Component,
-            utils::{InputWidget, centered_rect},
+            utils::{InputWidget, centered_rect, highlight_matches},
         },
         statics::TWITCH_MESSAGE_LIMIT,
     },
     utils::{
         colors::u32_to_color,
         emotes::UnicodePlaceholder,
-        styles::{NO_COLOR, SEARCH_STYLE, TITLE_STYLE},
+        matching::match_indices,
+        styles::{NO_COLOR, title_style},
         text::{TitleStyle, first_similarity_iter, title_line},
     },
 };
 
-static FUZZY_FINDER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
+/// How many columns each emote takes in the grid, for its picture and the start of its name.
+const CELL_WIDTH: u16 = 14;
+/// Each emote has its picture on one line, and its name on the line under it.
+const CELL_HEIGHT: u16 = 2;
 
+/// A grid of the emotes that can be used in the current channel, searched by name.
 pub struct EmotePickerWidget {
     config: SharedCompleteConfig,
     emotes: SharedEmotes,
     input: InputWidget<SharedEmotes>,
-    search_theme: Style,
-    list_state: ListState,
-    filtered_emotes: Vec<String>,
+    /// The emotes matching the search, the best matches first.
+    matches: Vec<String>,
+    selected: usize,
+    /// How many emotes fit in a row of the grid, as of when it was last drawn.
+    columns: usize,
+    /// The first row of the grid that is shown.
+    scroll: usize,
 }
 
 impl EmotePickerWidget {
@@ -77,44 +82,141 @@ impl EmotePickerWidget {
             config,
             emotes,
             input,
-            search_theme: *SEARCH_STYLE,
-            list_state: ListState::default(),
-            filtered_emotes: vec![],
+            matches: vec![],
+            selected: 0,
+            columns: 1,
+            scroll: 0,
         }
     }
-    fn next(&mut self) {
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i >= self.filtered_emotes.len() - 1 {
-                    self.filtered_emotes.len() - 1
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
-        };
 
-        self.list_state.select(Some(i));
+    pub const fn is_focused(&self) -> bool {
+        self.input.is_focused()
     }
 
-    fn previous(&mut self) {
-        let i = self
-            .list_state
-            .selected()
-            .map_or(0, |i| if i == 0 { 0 } else { i - 1 });
-
-        self.list_state.select(Some(i));
+    pub fn toggle_focus(&mut self) {
+        self.input.toggle_focus();
     }
 
-    fn unselect(&mut self) {
-        self.list_state.select(None);
+    /// Move the selection forward or back by a number of emotes, staying within the grid.
+    fn move_selection(&mut self, forward: bool, by: usize) {
+        let last = self.matches.len().saturating_sub(1);
+
+        self.selected = if forward {
+            self.selected.saturating_add(by).min(last)
+        } else {
+            self.selected.saturating_sub(by)
+        };
     }
-    pub const fn is_focused(&self) -> bool {
-        self.input.is_focused()
+
+    /// Find the emotes matching the search, along with the characters of their names that matched.
+    fn find_matches(&self) -> Vec<(String, Vec<usize>)> {
+        let search = self.input.to_string();
+        let matching = self.config.borrow().frontend.matching;
+
+        let user_emotes = self.emotes.user_emotes.borrow();
+        let global_emotes = self.emotes.global_emotes.borrow();
+
+        let mut matches = user_emotes
+            .keys()
+            .chain(global_emotes.keys())
+            .filter_map(|name| {
+                let (score, indices) =
+                    match_indices(matching, &name.to_ascii_lowercase(), &search)?;
+
+                Some((score, name.clone(), indices))
+            })
+            .collect::<Vec<_>>();
+
+        // The best matches first, and alphabetically otherwise so the grid does not move around
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        matches.dedup_by(|a, b| a.1 == b.1);
+
+        matches
+            .into_iter()
+            .map(|(_, name, indices)| (name, indices))
+            .collect()
     }
 
-    pub fn toggle_focus(&mut self) {
-        self.input.toggle_focus();
+    /// The picture of an emote and its name, each padded to the width of a cell.
+    /// Returns `None` if the emote could not be loaded.
+    fn cell(
+        &self,
+        name: &str,
+        indices: &[usize],
+        selected: bool,
+    ) -> Option<(Vec<Span<'static>>, Vec<Span<'static>>)> {
+        let (filename, zero_width) = {
+            let user_emotes = self.emotes.user_emotes.borrow();
+            let global_emotes = self.emotes.global_emotes.borrow();
+
+            user_emotes
+                .get(name)
+                .or_else(|| global_emotes.get(name))
+                .cloned()?
+        };
+
+        let cell_size = *self
+            .emotes
+            .cell_size
+            .get()
+            .expect("Terminal cell size should be set when emotes are enabled.");
+
+        let loaded_emote = load_picker_emote(
+            name,
+            &filename,
+            zero_width,
+            &mut self.emotes.info.borrow_mut(),
+            cell_size,
+        )
+        .map_err(|e| warn!("{e}"))
+        .ok()?;
+
+        // Emotes wider than a cell only have the start of their picture shown
+        let cols =
+            ((loaded_emote.width as f32 / cell_size.0).ceil() as u16).clamp(1, CELL_WIDTH - 1);
+
+        #[cfg(not(target_os = "windows"))]
+        let underline_style = Style::default()
+            .fg(u32_to_color(loaded_emote.hash))
+            .underline_color(u32_to_color(1));
+
+        #[cfg(target_os = "windows")]
+        let underline_style = { Style::default().fg(u32_to_color(loaded_emote.hash)) };
+
+        let picture = vec![
+            Span::styled(
+                UnicodePlaceholder::new(cols as usize).string(),
+                underline_style,
+            ),
+            Span::raw(" ".repeat((CELL_WIDTH - cols) as usize)),
+        ];
+
+        let shown = name
+            .chars()
+            .take(CELL_WIDTH as usize - 1)
+            .collect::<String>();
+
+        let mut label = highlight_matches(&shown, indices);
+        label.push(Span::raw(
+            " ".repeat(CELL_WIDTH as usize - shown.chars().count()),
+        ));
+
+        if selected {
+            let style = if *NO_COLOR {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+                    .bg(Color::LightGreen)
+                    .add_modifier(Modifier::BOLD)
+            };
+
+            label = label
+                .into_iter()
+                .map(|span| span.patch_style(style))
+                .collect();
+        }
+
+        Some((picture, label))
     }
 }
 
@@ -124,93 +226,59 @@ impl Component for EmotePickerWidget {
         // Make sure we have space for the input widget, which has a height of 3.
         r.height -= 3;
 
+        let inner = r.inner(Margin::new(1, 1));
+
+        self.columns = (inner.width / CELL_WIDTH).max(1) as usize;
+        let visible_rows = (inner.height / CELL_HEIGHT).max(1) as usize;
+
+        let matches = self.find_matches();
+
+        self.matches = matches.iter().map(|(name, _)| name.clone()).collect();
+        self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+
+        // Scroll just enough to keep the selected emote in the grid
+        let selected_row = self.selected / self.columns;
+
+        if selected_row < self.scroll {
+            self.scroll = selected_row;
+        } else if selected_row >= self.scroll + visible_rows {
+            self.scroll = selected_row + 1 - visible_rows;
+        }
+
         // Only load the emotes that are actually being displayed, as loading every emote is not really possible.
         // Some channels can have multiple thousands emotes and decoding all of them takes a while.
-        let max_len = max(
-            self.list_state.selected().unwrap_or_default(),
-            self.list_state.offset(),
-        ) + r.height as usize;
-        let mut items = Vec::with_capacity(max_len);
+        let mut lines = vec![];
         let mut bad_emotes = vec![];
 
-        let current_input = self.input.to_string();
+        let first = self.scroll * self.columns;
+        let shown = matches
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(visible_rows * self.columns);
 
-        let cell_size = *self
-            .emotes
-            .cell_size
-            .get()
-            .expect("Terminal cell size should be set when emotes are enabled.");
-
-        // Enter a new scope to drop the user/global emotes borrow when we don't need them anymore.
-        {
-            let user_emotes = self.emotes.user_emotes.borrow();
-            let global_emotes = self.emotes.global_emotes.borrow();
+        let mut pictures = vec![];
+        let mut labels = vec![];
 
-            // First find all the emotes that match the input
-            let mut matched_emotes = user_emotes
-                .iter()
-                .chain(global_emotes.iter())
-                .filter_map(|(name, data)| {
-                    Some((
-                        name,
-                        data,
-                        FUZZY_FINDER.fuzzy_indices(&name.to_ascii_lowercase(), &current_input)?,
-                    ))
-                })
-                .collect::<Vec<_>>();
-
-            // Sort them by match score
-            matched_emotes.sort_by(|a, b| b.2.0.cmp(&a.2.0));
-
-            for (name, (filename, zero_width), (_, matched_indices)) in matched_emotes {
-                if items.len() >= max_len {
-                    break;
-                }
+        for (column, (i, (name, indices))) in shown.enumerate() {
+            if column > 0 && column % self.columns == 0 {
+                lines.push(Line::from(std::mem::take(&mut pictures)));
+                lines.push(Line::from(std::mem::take(&mut labels)));
+            }
 
-                let Ok(loaded_emote) = load_picker_emote(
-                    name,
-                    filename,
-                    *zero_width,
-                    &mut self.emotes.info.borrow_mut(),
-                    cell_size,
-                )
-                .map_err(|e| warn!("{e}")) else {
-                    bad_emotes.push(name.clone());
-                    continue;
-                };
-
-                let cols = (loaded_emote.width as f32 / cell_size.0).ceil() as u16;
-
-                #[cfg(not(target_os = "windows"))]
-                let underline_style = Style::default()
-                    .fg(u32_to_color(loaded_emote.hash))
-                    .underline_color(u32_to_color(1));
-
-                #[cfg(target_os = "windows")]
-                let underline_style = { Style::default().fg(u32_to_color(loaded_emote.hash)) };
-
-                let mut row = name
-                    .chars()
-                    .enumerate()
-                    .map(|(i, c)| {
-                        if matched_indices.contains(&i) {
-                            Span::styled(c.to_string(), self.search_theme)
-                        } else {
-                            Span::raw(c.to_string())
-                        }
-                    })
-                    .collect::<Vec<Span>>();
-
-                row.push(Span::raw(" - "));
-                row.push(Span::styled(
-                    UnicodePlaceholder::new(cols as usize).string(),
-                    underline_style,
-                ));
-
-                items.push((name.clone(), ListItem::new(vec![Line::from(row)])));
+            if let Some((picture, label)) = self.cell(name, indices, i == self.selected) {
+                pictures.extend(picture);
+                labels.extend(label);
+            } else {
+                bad_emotes.push(name.clone());
+                pictures.push(Span::raw(" ".repeat(CELL_WIDTH as usize)));
+                labels.push(Span::raw(" ".repeat(CELL_WIDTH as usize)));
             }
         }
 
+        lines.push(Line::from(pictures));
+        lines.push(Line::from(labels));
+
         // Remove emotes that could not be loaded from list of emotes
         for emote in bad_emotes {
             self.emotes.info.borrow_mut().remove(&emote);
@@ -218,36 +286,35 @@ impl Component for EmotePickerWidget {
             self.emotes.global_emotes.borrow_mut().remove(&emote);
         }
 
-        let (names, list_items) = items.into_iter().unzip();
-        self.filtered_emotes = names;
+        let config = self.config.borrow();
 
-        let title_binding = [TitleStyle::Single("Emotes")];
+        let matching = config.frontend.matching.to_string();
+        let position = format!(
+            "{} / {}",
+            (self.selected + 1).min(self.matches.len()),
+            self.matches.len()
+        );
 
-        let list = List::new::<Vec<ListItem>>(list_items)
-            .block(
-                Block::default()
-                    .title(title_line(&title_binding, *TITLE_STYLE))
-                    .borders(Borders::ALL)
-                    .border_type(self.config.borrow().frontend.border_type.clone().into()),
-            )
-            .highlight_style(if *NO_COLOR {
-                Style::default()
-            } else {
-                Style::default()
-                    .bg(Color::LightGreen)
-                    .add_modifier(Modifier::BOLD)
-            });
+        let mut title = vec![
+            TitleStyle::Single("Emotes"),
+            TitleStyle::Combined("Alt + m", &matching),
+            TitleStyle::Single(&position),
+        ];
 
-        f.render_widget(Clear, r);
-        f.render_stateful_widget(list, r, &mut self.list_state);
+        if let Some(selected) = self.matches.get(self.selected) {
+            title.push(TitleStyle::Single(selected));
+        }
 
-        let bottom_block = Block::default()
-            .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
-            .border_type(self.config.borrow().frontend.border_type.clone().into());
+        let grid = Paragraph::new(lines).block(
+            config
+                .block(Widget::Popup)
+                .title(title_line(&title, title_style())),
+        );
 
-        let rect = Rect::new(r.x, r.bottom() - 1, r.width, 1);
+        f.render_widget(Clear, r);
+        f.render_widget(grid, r);
 
-        f.render_widget(bottom_block, rect);
+        drop(config);
 
         let input_rect = Rect::new(r.x, r.bottom(), r.width, 3);
 
@@ -259,16 +326,22 @@ impl Component for EmotePickerWidget {
             match key {
                 Key::Esc => self.toggle_focus(),
                 Key::Ctrl('p') => panic!("Manual panic triggered by user."),
-                Key::ScrollDown | Key::Down => self.next(),
-                Key::ScrollUp | Key::Up => self.previous(),
+                Key::Right | Key::Tab => self.move_selection(true, 1),
+                Key::Left | Key::BackTab => self.move_selection(false, 1),
+                Key::ScrollDown | Key::Down => self.move_selection(true, self.columns),
+                Key::ScrollUp | Key::Up => self.move_selection(false, self.columns),
+                Key::Alt('m') => {
+                    let mut config = self.config.borrow_mut();
+                    config.frontend.matching = config.frontend.matching.next();
+
+                    self.selected = 0;
+                }
                 Key::Enter => {
-                    if let Some(idx) = self.list_state.selected() {
-                        let emote = self.filtered_emotes[idx].clone();
-
+                    if let Some(emote) = self.matches.get(self.selected).cloned() {
                         self.toggle_focus();
                         self.input.clear();
-                        self.unselect();
-                        self.filtered_emotes.clear();
+                        self.matches.clear();
+                        self.selected = 0;
 
                         return Some(TerminalAction::Enter(TwitchAction::Privmsg(emote)));
                     }
@@ -277,10 +350,7 @@ impl Component for EmotePickerWidget {
                     self.input.event(event).await;
 
                     // Assuming that the user inputted something that modified the input
-                    match self.filtered_emotes.len() {
-                        0 => self.unselect(),
-                        _ => self.list_state.select(Some(0)),
-                    }
+                    self.selected = 0;
                 }
             }
         }
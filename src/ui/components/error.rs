@@ -8,7 +8,7 @@ use tui::{
 
 use crate::{
     ui::components::Component,
-    utils::styles::{NO_COLOR, TEXT_DARK_STYLE},
+    utils::styles::{NO_COLOR, text_style},
 };
 
 #[derive(Debug, Clone)]
@@ -54,7 +54,7 @@ impl Component for ErrorWidget {
                 })
                 .title_top(Line::from("[ ERROR ]").centered()),
         )
-        .style(*TEXT_DARK_STYLE)
+        .style(text_style())
         .alignment(Alignment::Center);
 
         f.render_widget(Clear, r);
@@ -0,0 +1,225 @@
+use regex::Regex;
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Cell, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        filters::{FilterRule, SharedFilters},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    ui::components::{
+        Component,
+        utils::{InputWidget, SearchableList, centered_rect, split_input},
+    },
+    utils::text::TitleStyle,
+};
+
+/// The filters, to add, remove, and change them while chatting.
+pub struct FiltersWidget {
+    config: SharedCompleteConfig,
+    filters: SharedFilters,
+    focused: bool,
+    list: SearchableList<FilterRule>,
+    /// The pattern of a filter being added.
+    input: InputWidget<()>,
+}
+
+impl FiltersWidget {
+    pub fn new(config: SharedCompleteConfig, filters: SharedFilters) -> Self {
+        let input_validator =
+            Box::new(|(), s: String| -> bool { !s.is_empty() && Regex::new(&s).is_ok() });
+
+        let input = InputWidget::new(
+            config.clone(),
+            "Pattern",
+            Some(((), input_validator)),
+            None,
+            None,
+        );
+
+        let list = SearchableList::new(config.clone(), Widget::Popup).without_search();
+
+        Self {
+            config,
+            filters,
+            focused: false,
+            list,
+            input,
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+
+        self.refresh();
+
+        if self.focused && self.list.selected_index().is_none() {
+            self.list.select_first();
+        }
+    }
+
+    /// Show the filters as they are now, after they were changed.
+    fn refresh(&mut self) {
+        let rules = self.filters.borrow().rules().cloned().collect();
+
+        self.list.set_rows(rules, None);
+    }
+
+    fn update_selected(&mut self, change: impl FnOnce(&mut FilterRule)) {
+        if let Some(i) = self.list.selected_index() {
+            self.filters.borrow_mut().update(i, change);
+        }
+
+        self.refresh();
+    }
+
+    /// Scope the selected filter to the current channel, or to every channel if it already was scoped.
+    fn toggle_channel(&mut self) {
+        let channel = self.config.borrow().twitch.channel.clone();
+
+        self.update_selected(|rule| {
+            if rule.channels.is_empty() {
+                rule.channels = vec![channel];
+            } else {
+                rule.channels.clear();
+            }
+        });
+    }
+
+    fn remove_selected(&mut self) {
+        if let Some(i) = self.list.selected_index() {
+            self.filters.borrow_mut().remove(i);
+        }
+
+        self.refresh();
+    }
+}
+
+impl Component for FiltersWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let r = area.map_or_else(|| centered_rect(70, 50, 12, f.area()), |a| a);
+
+        self.refresh();
+
+        let rows = self
+            .list
+            .rows()
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                let error = self.filters.borrow().error(i);
+
+                Row::new(vec![
+                    Cell::from(error.map_or_else(
+                        || rule.pattern.clone(),
+                        |err| format!("{} (not a valid regex, {err})", rule.pattern),
+                    )),
+                    Cell::from(rule.target.to_string()),
+                    Cell::from(rule.action.to_string()),
+                    Cell::from(if rule.channels.is_empty() {
+                        "every channel".to_string()
+                    } else {
+                        rule.channels
+                            .iter()
+                            .map(|channel| format!("#{}", channel.trim_start_matches('#')))
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    }),
+                ])
+            })
+            .collect::<Vec<Row>>();
+
+        let title = [
+            TitleStyle::Single("Filters"),
+            TitleStyle::Combined("a", "add"),
+            TitleStyle::Combined("d", "delete"),
+            TitleStyle::Combined("t", "target"),
+            TitleStyle::Combined("h", "action"),
+            TitleStyle::Combined("c", "channel"),
+        ];
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(8),
+                Constraint::Length(10),
+                Constraint::Length(20),
+            ],
+        )
+        .header(
+            Row::new(vec!["Pattern", "Target", "Action", "Channels"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        );
+
+        self.list.draw_table(f, r, table, &title);
+
+        if self.input.is_focused() {
+            let [_, input_area] = split_input(r);
+
+            self.input.draw(f, Some(input_area));
+        }
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            if self.input.is_focused() {
+                match key {
+                    Key::Esc => {
+                        self.input.clear();
+                        self.input.toggle_focus();
+                    }
+                    Key::Enter => {
+                        if self.input.is_valid() {
+                            let pattern = self.input.to_string();
+
+                            // The pattern was checked to be a valid regex
+                            if self
+                                .filters
+                                .borrow_mut()
+                                .add(FilterRule::new(&pattern))
+                                .is_ok()
+                            {
+                                self.refresh();
+                                self.list.select_last();
+                            }
+
+                            self.input.clear();
+                            self.input.toggle_focus();
+                        }
+                    }
+                    _ => {
+                        self.input.event(event).await;
+                    }
+                }
+
+                return None;
+            }
+
+            match key {
+                Key::Esc => self.toggle_focus(),
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down | Key::Char('j') => self.list.next(),
+                Key::ScrollUp | Key::Up | Key::Char('k') => self.list.previous(),
+                Key::Char('a') => self.input.toggle_focus(),
+                Key::Char('d') => self.remove_selected(),
+                Key::Char('t') => self.update_selected(|rule| rule.target = rule.target.next()),
+                Key::Char('h') => self.update_selected(|rule| rule.action = rule.action.next()),
+                Key::Char('c') => self.toggle_channel(),
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
@@ -1,9 +1,15 @@
+use log::warn;
 use once_cell::sync::Lazy;
 use tui::{Frame, layout::Rect};
 
 use super::utils::SearchWidget;
 use crate::{
-    handlers::{config::SharedCompleteConfig, user_input::events::Event},
+    handlers::{
+        config::{SharedCompleteConfig, persist_frontend_setting},
+        favorites::toggle_favorite,
+        storage::SharedStorage,
+        user_input::events::{Event, Key},
+    },
     terminal::TerminalAction,
     twitch::{TwitchAction, channels::Following},
     ui::components::Component,
@@ -21,14 +27,14 @@ static INCORRECT_SCOPES_ERROR_MESSAGE: Lazy<Vec<&'static str>> = Lazy::new(|| {
 });
 
 pub struct FollowingWidget {
-    #[allow(dead_code)]
     config: SharedCompleteConfig,
+    storage: SharedStorage,
     pub search_widget: SearchWidget<String, Following>,
 }
 
 impl FollowingWidget {
-    pub fn new(config: SharedCompleteConfig) -> Self {
-        let item_getter = Following::new(config.borrow().twitch.clone());
+    pub fn new(config: SharedCompleteConfig, storage: SharedStorage) -> Self {
+        let item_getter = Following::new(config.clone(), storage.clone());
 
         let search_widget = SearchWidget::new(
             config.clone(),
@@ -38,6 +44,7 @@ impl FollowingWidget {
 
         Self {
             config,
+            storage,
             search_widget,
         }
     }
@@ -49,6 +56,22 @@ impl FollowingWidget {
     pub async fn toggle_focus(&mut self) {
         self.search_widget.toggle_focus().await;
     }
+
+    /// Sort the channels the next way, saving it to the config so that they are sorted that way the next time.
+    fn cycle_sort(&self) {
+        let sort = {
+            let mut config = self.config.borrow_mut();
+
+            config.frontend.following_sort = config.frontend.following_sort.next();
+            config.frontend.following_sort
+        };
+
+        if let Err(err) =
+            persist_frontend_setting("following_sort", &toml::Value::String(sort.to_string()))
+        {
+            warn!("Unable to save the order of followed channels: {err:#}");
+        }
+    }
 }
 
 impl Component for FollowingWidget {
@@ -57,6 +80,20 @@ impl Component for FollowingWidget {
     }
 
     async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if matches!(event, Event::Input(Key::Alt('s'))) {
+            self.cycle_sort();
+
+            return None;
+        }
+
+        if matches!(event, Event::Input(Key::Alt('p'))) {
+            if let Some(channel) = self.search_widget.selected() {
+                toggle_favorite(&self.storage, channel);
+            }
+
+            return None;
+        }
+
         let action = self.search_widget.event(event).await;
 
         if let Some(TerminalAction::Enter(TwitchAction::Join(channel))) = &action {
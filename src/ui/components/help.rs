@@ -1,14 +1,17 @@
 use tui::{
     Frame,
     layout::{Constraint, Rect},
-    widgets::{Block, Borders, Cell, Row, Table},
+    widgets::{Cell, Row, Table},
 };
 
 use crate::{
-    handlers::config::SharedCompleteConfig,
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        user_input::keybinds::{Action, DASHBOARD_ACTIONS, keys},
+    },
     ui::{
         components::Component,
-        statics::{HELP_COLUMN_TITLES, HELP_KEYBINDS},
+        statics::{COMMANDS, HELP_COLUMN_TITLES, HELP_KEYBINDS},
     },
     utils::styles::{BOLD_STYLE, COLUMN_TITLE_STYLE},
 };
@@ -17,6 +20,15 @@ use crate::{
 const TABLE_CONSTRAINTS: [Constraint; 3] =
     [Constraint::Min(11), Constraint::Min(8), Constraint::Min(38)];
 
+/// The actions shown in a section of the keybinds window, before the keys that cannot be rebound.
+fn section_actions(section: &str) -> Vec<Action> {
+    match section {
+        "Dashboard" => DASHBOARD_ACTIONS.to_vec(),
+        "Normal mode" => Action::all().collect(),
+        _ => vec![],
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HelpWidget {
     config: SharedCompleteConfig,
@@ -35,7 +47,25 @@ impl Component for HelpWidget {
         let mut rows = vec![];
 
         for (s, v) in HELP_KEYBINDS.iter() {
-            for (i, (key, desc)) in v.iter().enumerate() {
+            let bound = section_actions(s).into_iter().map(|action| {
+                let keys = keys(&self.config.borrow().keybinds, action)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>();
+
+                (
+                    if keys.is_empty() {
+                        "Unbound".to_string()
+                    } else {
+                        keys.join(" or ")
+                    },
+                    action.description(),
+                )
+            });
+
+            let fixed = v.iter().map(|(key, desc)| ((*key).to_string(), *desc));
+
+            for (i, (key, desc)) in bound.chain(fixed).enumerate() {
                 rows.push(Row::new(vec![
                     if i == 0 {
                         Cell::from((*s).to_string())
@@ -43,21 +73,34 @@ impl Component for HelpWidget {
                         Cell::from("")
                     }
                     .style(*BOLD_STYLE),
-                    Cell::from((*key).to_string()),
-                    Cell::from((*desc).to_string()),
+                    Cell::from(key),
+                    Cell::from(desc.to_string()),
                 ]));
             }
 
             rows.push(Row::new(vec![Cell::from("")]));
         }
 
+        for (i, command) in COMMANDS.iter().enumerate() {
+            rows.push(Row::new(vec![
+                if i == 0 {
+                    Cell::from("Commands")
+                } else {
+                    Cell::from("")
+                }
+                .style(*BOLD_STYLE),
+                Cell::from(command.usage()),
+                Cell::from(command.description),
+            ]));
+        }
+
         let help_table = Table::new(rows, TABLE_CONSTRAINTS)
             .header(Row::new(HELP_COLUMN_TITLES.iter().copied()).style(*COLUMN_TITLE_STYLE))
             .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("[ Keybinds ]")
-                    .border_type(self.config.borrow().frontend.border_type.clone().into()),
+                self.config
+                    .borrow()
+                    .block(Widget::Popup)
+                    .title("[ Keybinds ]"),
             )
             .column_spacing(2);
 
@@ -0,0 +1,165 @@
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    widgets::{Cell, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        ignored::SharedIgnoredUsers,
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    twitch::TwitchAction,
+    ui::components::{
+        Component,
+        utils::{InputWidget, SearchableList, centered_rect, split_input},
+    },
+    utils::text::TitleStyle,
+};
+
+/// The users whose messages are left out of chat, to ignore more of them or stop ignoring them.
+pub struct IgnoredWidget {
+    ignored: SharedIgnoredUsers,
+    focused: bool,
+    /// Each user along with if they were blocked on Twitch.
+    list: SearchableList<(String, bool)>,
+    /// The name of a user being ignored.
+    input: InputWidget<()>,
+}
+
+impl IgnoredWidget {
+    pub fn new(config: SharedCompleteConfig, ignored: SharedIgnoredUsers) -> Self {
+        let input_validator =
+            Box::new(|(), s: String| -> bool { !s.is_empty() && !s.contains(' ') });
+
+        let input = InputWidget::new(
+            config.clone(),
+            "User",
+            Some(((), input_validator)),
+            None,
+            None,
+        );
+
+        Self {
+            ignored,
+            focused: false,
+            list: SearchableList::new(config, Widget::Popup).without_search(),
+            input,
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+
+        self.refresh();
+
+        if self.focused && self.list.selected_index().is_none() {
+            self.list.select_first();
+        }
+    }
+
+    /// Show the users as they are now, after they were changed.
+    fn refresh(&mut self) {
+        let users = self
+            .ignored
+            .borrow()
+            .users()
+            .into_iter()
+            .map(|(user, blocked)| (user.to_string(), blocked))
+            .collect();
+
+        self.list.set_rows(users, None);
+    }
+
+    /// Stop ignoring the selected user, with the selection moving to the user that takes their place.
+    fn unignore_selected(&self) -> Option<TerminalAction> {
+        let (user, _) = self.list.selected()?.clone();
+
+        Some(TerminalAction::Enter(TwitchAction::Ignore(user, false)))
+    }
+}
+
+impl Component for IgnoredWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let r = area.map_or_else(|| centered_rect(50, 50, 12, f.area()), |a| a);
+
+        self.refresh();
+
+        let rows = self
+            .list
+            .rows()
+            .iter()
+            .map(|(user, blocked)| {
+                Row::new(vec![
+                    Cell::from(user.clone()),
+                    Cell::from(if *blocked { "blocked on Twitch" } else { "" }),
+                ])
+            })
+            .collect::<Vec<Row>>();
+
+        let title = [
+            TitleStyle::Single("Ignored users"),
+            TitleStyle::Combined("a", "ignore"),
+            TitleStyle::Combined("d", "unignore"),
+        ];
+
+        let table = Table::new(rows, [Constraint::Min(20), Constraint::Length(18)]);
+
+        self.list.draw_table(f, r, table, &title);
+
+        if self.input.is_focused() {
+            let [_, input_area] = split_input(r);
+
+            self.input.draw(f, Some(input_area));
+        }
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            if self.input.is_focused() {
+                match key {
+                    Key::Esc => {
+                        self.input.clear();
+                        self.input.toggle_focus();
+                    }
+                    Key::Enter => {
+                        if self.input.is_valid() {
+                            let user = self.input.to_string();
+
+                            self.input.clear();
+                            self.input.toggle_focus();
+
+                            return Some(TerminalAction::Enter(TwitchAction::Ignore(
+                                user.trim_start_matches('@').to_string(),
+                                true,
+                            )));
+                        }
+                    }
+                    _ => {
+                        self.input.event(event).await;
+                    }
+                }
+
+                return None;
+            }
+
+            match key {
+                Key::Esc => self.toggle_focus(),
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down | Key::Char('j') => self.list.next(),
+                Key::ScrollUp | Key::Up | Key::Char('k') => self.list.previous(),
+                Key::Char('a') => self.input.toggle_focus(),
+                Key::Char('d') => return self.unignore_selected(),
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
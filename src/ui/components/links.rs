@@ -0,0 +1,124 @@
+use log::warn;
+use tui::{Frame, layout::Rect};
+
+use super::utils::{NameMatcher, SearchableList, centered_rect, split_input};
+use crate::{
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key, MouseAction},
+    },
+    terminal::TerminalAction,
+    ui::components::{Component, utils::InputWidget},
+    utils::text::TitleStyle,
+};
+
+/// The links of some messages, one of which can be opened in the browser.
+pub struct LinksWidget {
+    focused: bool,
+    search_input: InputWidget<()>,
+    list: SearchableList<String>,
+    links: Vec<String>,
+}
+
+impl LinksWidget {
+    pub fn new(config: SharedCompleteConfig) -> Self {
+        let search_input = InputWidget::new(config.clone(), "Filter", None, None, None);
+        let list = SearchableList::new(config, Widget::Popup);
+
+        Self {
+            focused: false,
+            search_input,
+            list,
+            links: vec![],
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Show a list of links, with the first one selected.
+    pub fn open(&mut self, links: Vec<String>) {
+        self.list.set_rows(links.clone(), None);
+        self.list.select_first();
+
+        self.links = links;
+        self.focused = true;
+
+        self.search_input.clear();
+    }
+
+    fn close(&mut self) {
+        self.focused = false;
+        self.links.clear();
+        self.list.clear();
+    }
+
+    fn open_selected(&mut self) {
+        if let Some(link) = self.list.selected() {
+            if let Err(err) = webbrowser::open(link) {
+                warn!("Unable to open {link} in the browser. {err}");
+            }
+
+            self.close();
+        }
+    }
+}
+
+impl Component for LinksWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let [r, input_rect] =
+            split_input(area.map_or_else(|| centered_rect(70, 60, 23, f.area()), |a| a));
+
+        let items = self
+            .list
+            .filter(&self.links, &self.search_input.to_string(), &NameMatcher);
+
+        let title = if self.links.is_empty() {
+            TitleStyle::Single("No links found")
+        } else {
+            TitleStyle::Single("Links")
+        };
+
+        self.list
+            .draw(f, r, items, &[title, TitleStyle::Combined("Enter", "open")]);
+
+        self.search_input.draw(f, Some(input_rect));
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Mouse(MouseAction::Click, position) = event {
+            if self.list.click(*position) {
+                self.open_selected();
+            }
+        }
+
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => {
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
+                    } else {
+                        self.close();
+                    }
+                }
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
+                }
+                Key::Enter => self.open_selected(),
+                _ => {
+                    self.search_input.event(event).await;
+
+                    // Assuming that the user inputted something that modified the input
+                    self.list.select_first();
+                }
+            }
+        }
+
+        None
+    }
+}
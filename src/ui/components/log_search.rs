@@ -0,0 +1,173 @@
+use std::sync::mpsc::{Receiver, TryRecvError};
+
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Cell, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        log_search::{LogQuery, LoggedMessage, search_logs},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    ui::components::{
+        Component,
+        utils::{InputWidget, SearchableList, centered_rect, split_input},
+    },
+    utils::text::TitleStyle,
+};
+
+pub struct LogSearchWidget {
+    config: SharedCompleteConfig,
+    focused: bool,
+    search_input: InputWidget<()>,
+    list: SearchableList<LoggedMessage>,
+    /// The messages found so far, the most recent first.
+    found: Vec<LoggedMessage>,
+    /// The messages still being found in the background, if a search is running.
+    search: Option<Receiver<Vec<LoggedMessage>>>,
+}
+
+impl LogSearchWidget {
+    pub fn new(config: SharedCompleteConfig) -> Self {
+        let search_input = InputWidget::new(config.clone(), "Search logs", None, None, None);
+        let list = SearchableList::new(config.clone(), Widget::Popup);
+
+        Self {
+            config,
+            focused: false,
+            search_input,
+            list,
+            found: vec![],
+            search: None,
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+
+        if !self.focused {
+            self.search_input.clear();
+            self.list.clear();
+            self.found.clear();
+            // Dropping the receiver stops the search
+            self.search = None;
+        }
+    }
+
+    fn start_search(&mut self) {
+        let query = LogQuery::parse(&self.search_input.to_string());
+
+        self.found.clear();
+        self.list.unselect();
+        self.search = Some(search_logs(
+            &self.config.borrow().logs,
+            query,
+            self.list.matching(),
+        ));
+    }
+
+    /// Take the messages found since the last time this was checked.
+    fn receive(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        loop {
+            match search.try_recv() {
+                Ok(messages) => self.found.extend(messages),
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+
+        self.search = None;
+    }
+}
+
+impl Component for LogSearchWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let [r, input_rect] =
+            split_input(area.map_or_else(|| centered_rect(80, 60, 23, f.area()), |a| a));
+
+        self.receive();
+
+        self.list.set_rows(self.found.clone(), None);
+
+        let rows = self
+            .list
+            .rows()
+            .iter()
+            .map(|m| {
+                Row::new(vec![
+                    Cell::from(Text::from(vec![
+                        Line::from(m.date.format("%Y-%m-%d").to_string()),
+                        Line::from(Span::styled(
+                            m.time.clone(),
+                            Style::default().add_modifier(Modifier::DIM),
+                        )),
+                    ])),
+                    Cell::from(format!("#{}", m.channel)),
+                    Cell::from(m.author.clone()),
+                    Cell::from(m.message.clone()),
+                ])
+                .height(2)
+            })
+            .collect::<Vec<Row>>();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Length(20),
+                Constraint::Length(26),
+                Constraint::Min(20),
+            ],
+        );
+
+        let mut title = vec![TitleStyle::Single("Chat logs")];
+
+        if self.search.is_some() {
+            title.push(TitleStyle::Single("Searching"));
+        }
+
+        title.push(TitleStyle::Combined("Enter", "search"));
+
+        self.list.draw_table(f, r, table, &title);
+
+        self.search_input.draw(f, Some(input_rect));
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => {
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
+                    } else {
+                        self.toggle_focus();
+                    }
+                }
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => self.list.cycle_matching(),
+                Key::Enter => self.start_search(),
+                _ => {
+                    self.search_input.event(event).await;
+                }
+            }
+        }
+
+        None
+    }
+}
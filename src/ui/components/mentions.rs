@@ -0,0 +1,166 @@
+use chrono::Local;
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    widgets::{Cell, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        app::SharedMentions,
+        config::{SharedCompleteConfig, Widget},
+        mentions::Mention,
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    ui::components::{
+        Component,
+        utils::{InputWidget, SearchableList, centered_rect, split_input},
+    },
+    utils::{
+        text::TitleStyle,
+        time::{format_relative, format_time},
+    },
+};
+
+pub struct MentionsWidget {
+    config: SharedCompleteConfig,
+    mentions: SharedMentions,
+    focused: bool,
+    search_input: InputWidget<()>,
+    list: SearchableList<Mention>,
+    /// If how long ago mentions were is shown, rather than the time they were at.
+    relative_times: bool,
+}
+
+impl MentionsWidget {
+    pub fn new(config: SharedCompleteConfig, mentions: SharedMentions) -> Self {
+        let search_input = InputWidget::new(config.clone(), "Filter", None, None, None);
+        let list = SearchableList::new(config.clone(), Widget::Popup);
+
+        Self {
+            config,
+            mentions,
+            focused: false,
+            search_input,
+            list,
+            relative_times: false,
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+
+        if !self.focused {
+            self.search_input.clear();
+            self.list.clear();
+        }
+    }
+}
+
+impl Component for MentionsWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let [r, input_rect] =
+            split_input(area.map_or_else(|| centered_rect(80, 60, 23, f.area()), |a| a));
+
+        let query = self.search_input.to_string();
+        let matching = self.list.matching();
+
+        // Most recent mentions first
+        self.list.set_rows(
+            self.mentions
+                .borrow()
+                .iter()
+                .rev()
+                .filter(|m| m.matches(&query, matching))
+                .cloned()
+                .collect(),
+            None,
+        );
+
+        let config = self.config.borrow();
+        let now = Local::now();
+
+        let rows = self
+            .list
+            .rows()
+            .iter()
+            .map(|m| {
+                Row::new(vec![
+                    Cell::from(if self.relative_times {
+                        format_relative(&m.time_sent, &now)
+                    } else {
+                        format_time(&m.time_sent, &config.frontend)
+                    }),
+                    Cell::from(
+                        m.channel
+                            .as_deref()
+                            .map_or_else(String::new, |c| format!("#{c}")),
+                    ),
+                    Cell::from(m.author.clone()),
+                    Cell::from(m.message.clone()),
+                ])
+            })
+            .collect::<Vec<Row>>();
+
+        drop(config);
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Length(20),
+                Constraint::Length(26),
+                Constraint::Min(20),
+            ],
+        );
+
+        let title = [
+            TitleStyle::Single("Mentions"),
+            TitleStyle::Combined(
+                "Alt + r",
+                if self.relative_times {
+                    "clock times"
+                } else {
+                    "relative times"
+                },
+            ),
+        ];
+
+        self.list.draw_table(f, r, table, &title);
+
+        self.search_input.draw(f, Some(input_rect));
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => {
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
+                    } else {
+                        self.toggle_focus();
+                    }
+                }
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('r') => self.relative_times = !self.relative_times,
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
+                }
+                _ => {
+                    self.search_input.event(event).await;
+                    self.list.select_first();
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -12,11 +12,17 @@ use crate::{
         components::{Component, utils::InputWidget},
         statics::TWITCH_MESSAGE_LIMIT,
     },
+    utils::search_query::SearchQuery,
 };
 
+const TITLE: &str = "Message search";
+
 pub struct MessageSearchWidget {
     _config: SharedCompleteConfig,
     input: InputWidget<()>,
+    case_sensitive: bool,
+    /// If the search was entered, so that its matches stay highlighted after the search is closed.
+    active: bool,
 }
 
 impl MessageSearchWidget {
@@ -31,7 +37,7 @@ impl MessageSearchWidget {
 
         let input = InputWidget::new(
             config.clone(),
-            "Message search",
+            TITLE,
             Some(((), input_validator)),
             Some(visual_indicator),
             None,
@@ -40,6 +46,8 @@ impl MessageSearchWidget {
         Self {
             _config: config,
             input,
+            case_sensitive: false,
+            active: false,
         }
     }
 
@@ -50,6 +58,33 @@ impl MessageSearchWidget {
     pub fn toggle_focus(&mut self) {
         self.input.toggle_focus();
     }
+
+    /// If the matches of an entered search are still highlighted, and can be jumped between.
+    pub const fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub const fn deactivate(&mut self) {
+        self.active = false;
+    }
+
+    /// The search to highlight messages with, while it is being typed or after it was entered.
+    pub fn query(&self) -> Option<SearchQuery> {
+        let input = self.input.to_string();
+
+        ((self.is_focused() || self.active) && !input.trim().is_empty())
+            .then(|| SearchQuery::parse(&input, self.case_sensitive))
+    }
+
+    fn toggle_case_sensitivity(&mut self) {
+        self.case_sensitive = !self.case_sensitive;
+
+        if self.case_sensitive {
+            self.input.set_title(&format!("{TITLE} (case sensitive)"));
+        } else {
+            self.input.set_title(TITLE);
+        }
+    }
 }
 
 impl Display for MessageSearchWidget {
@@ -68,7 +103,15 @@ impl Component for MessageSearchWidget {
             match key {
                 Key::Esc => {
                     self.input.toggle_focus();
+                    self.active = false;
+                }
+                Key::Enter => {
+                    if self.input.is_valid() {
+                        self.input.toggle_focus();
+                        self.active = true;
+                    }
                 }
+                Key::Alt('c') => self.toggle_case_sensitivity(),
                 _ => {
                     self.input.event(event).await;
                 }
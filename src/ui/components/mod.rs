@@ -1,36 +1,81 @@
+mod about;
+mod activity;
+mod alerts;
+mod automod;
 mod channel_switcher;
 mod chat;
 mod chat_input;
 mod dashboard;
 mod debug;
+mod device_code;
 mod error;
+mod filters;
 mod following;
 mod help;
+mod ignored;
+mod links;
+mod log_search;
+mod mentions;
 mod message_search;
+mod mod_log;
+mod notices;
+mod polls;
+mod projector;
+mod quick_switch;
+mod raids;
+mod recovered;
+mod rewards;
+mod startup_error;
 mod state_tabs;
+mod status_bar;
+mod stream_info;
+mod whispers;
 
+mod emoji_picker;
 mod emote_picker;
 pub mod utils;
 
+pub use about::AboutWidget;
+pub use activity::ActivityWidget;
+pub use automod::AutoModWidget;
 pub use channel_switcher::ChannelSwitcherWidget;
 pub use chat::ChatWidget;
 pub use chat_input::ChatInputWidget;
 use chrono::{DateTime, Local};
 pub use dashboard::DashboardWidget;
 pub use debug::DebugWidget;
+pub use device_code::DeviceCodeWidget;
 pub use error::ErrorWidget;
 pub use help::HelpWidget;
+pub use log_search::LogSearchWidget;
+pub use mentions::MentionsWidget;
 pub use message_search::MessageSearchWidget;
+pub use notices::NoticesWidget;
 use once_cell::sync::Lazy;
+pub use projector::ProjectorWidget;
+pub use raids::RaidsWidget;
+pub use recovered::RecoveredWidget;
+pub use rewards::RewardsWidget;
+pub use startup_error::{StartupChoice, StartupErrorWidget};
 pub use state_tabs::StateTabsWidget;
+pub use status_bar::StatusBarWidget;
 use tui::{Frame, layout::Rect};
+pub use whispers::WhispersWidget;
 
 use crate::{
     emotes::SharedEmotes,
     handlers::{
-        app::SharedMessages,
+        app::{
+            SharedActivity, SharedAutoMod, SharedChannelTabs, SharedMentions, SharedMessages,
+            SharedModActions, SharedNotices, SharedPolls, SharedRaids, SharedWhispers,
+        },
+        completion::SharedRecentChatters,
         config::SharedCompleteConfig,
         filters::SharedFilters,
+        highlights::SharedHighlights,
+        ignored::SharedIgnoredUsers,
+        joins::SharedJoinHistory,
+        nicknames::SharedNicknames,
         storage::SharedStorage,
         user_input::events::{Event, Key},
     },
@@ -69,12 +114,15 @@ pub trait Component {
 pub struct Components {
     // Partial window widgets
     pub tabs: StateTabsWidget,
+    pub status_bar: StatusBarWidget,
     pub debug: DebugWidget,
+    pub about: AboutWidget,
 
     // Full window widgets
     pub chat: ChatWidget,
     pub dashboard: DashboardWidget,
     pub help: HelpWidget,
+    pub projector: ProjectorWidget,
 
     // Errors
     pub window_size_error: ErrorWidget,
@@ -85,17 +133,59 @@ impl Components {
         config: &SharedCompleteConfig,
         storage: SharedStorage,
         filters: SharedFilters,
+        highlights: SharedHighlights,
+        ignored: SharedIgnoredUsers,
         messages: SharedMessages,
+        notices: SharedNotices,
+        whispers: SharedWhispers,
+        activity: SharedActivity,
+        raids: SharedRaids,
+        mentions: SharedMentions,
+        automod: SharedAutoMod,
+        mod_actions: SharedModActions,
+        polls: SharedPolls,
+        tabs: SharedChannelTabs,
         emotes: &SharedEmotes,
+        nicknames: SharedNicknames,
+        chatters: SharedRecentChatters,
+        joins: SharedJoinHistory,
         startup_time: DateTime<Local>,
     ) -> Self {
         let window_size_error = ErrorWidget::new(WINDOW_SIZE_TOO_SMALL_ERROR.to_vec());
 
         Self {
             tabs: StateTabsWidget::new(config.clone()),
+            status_bar: StatusBarWidget::new(config.clone()),
             debug: DebugWidget::new(config.clone(), startup_time),
+            about: AboutWidget::new(config.clone()),
 
-            chat: ChatWidget::new(config.clone(), messages, &storage, emotes, filters),
+            projector: ProjectorWidget::new(
+                config.clone(),
+                messages.clone(),
+                filters.clone(),
+                ignored.clone(),
+            ),
+            chat: ChatWidget::new(
+                config.clone(),
+                messages,
+                notices,
+                whispers,
+                activity,
+                raids,
+                mentions,
+                automod,
+                mod_actions,
+                polls,
+                tabs,
+                &storage,
+                emotes,
+                filters,
+                highlights,
+                ignored,
+                nicknames,
+                chatters,
+                joins,
+            ),
             dashboard: DashboardWidget::new(config.clone(), storage),
             help: HelpWidget::new(config.clone()),
             window_size_error,
@@ -0,0 +1,138 @@
+use tui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{List, ListItem},
+};
+
+use crate::{
+    handlers::{
+        app::SharedModActions,
+        config::{SharedCompleteConfig, Widget},
+    },
+    utils::{
+        styles::title_style,
+        text::{TitleStyle, title_line},
+        time::format_time,
+    },
+};
+
+/// The width of the moderation log sidebar, including its borders.
+pub const MOD_LOG_WIDTH: u16 = 40;
+
+/// A sidebar of the actions moderators took in the current channel, to be kept open next to chat.
+pub struct ModLogWidget {
+    config: SharedCompleteConfig,
+    mod_actions: SharedModActions,
+    visible: bool,
+    /// How many of the most recent actions are scrolled past.
+    offset: usize,
+}
+
+impl ModLogWidget {
+    pub const fn new(config: SharedCompleteConfig, mod_actions: SharedModActions) -> Self {
+        Self {
+            config,
+            mod_actions,
+            visible: false,
+            offset: 0,
+        }
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle_visibility(&mut self) {
+        self.visible = !self.visible;
+        self.offset = 0;
+    }
+
+    /// Scroll towards older actions.
+    pub fn scroll_older(&mut self) {
+        let channel = self.config.borrow().twitch.channel.clone();
+
+        let total = self
+            .mod_actions
+            .borrow()
+            .iter()
+            .filter(|action| action.channel == channel)
+            .count();
+
+        self.offset = (self.offset + 1).min(total.saturating_sub(1));
+    }
+
+    /// Scroll towards newer actions.
+    pub const fn scroll_newer(&mut self) {
+        self.offset = self.offset.saturating_sub(1);
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect) {
+        let config = self.config.borrow();
+        let mod_actions = self.mod_actions.borrow();
+
+        let width = area
+            .width
+            .saturating_sub(2 + config.padding(Widget::Chat) * 2) as usize;
+
+        // Most recent actions at the top, each wrapped to fit the sidebar
+        let items = mod_actions
+            .iter()
+            .rev()
+            .filter(|action| action.channel == config.twitch.channel)
+            .skip(self.offset)
+            .map(|action| {
+                let mut spans = vec![Span::styled(
+                    format!("{} ", format_time(&action.time_received, &config.frontend)),
+                    Style::default().fg(Color::DarkGray),
+                )];
+
+                if let Some(moderator) = &action.moderator {
+                    spans.push(Span::styled(
+                        format!("{moderator} "),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ));
+                }
+
+                let line = Line::from(spans);
+                let prefix_width = line.width();
+
+                let mut lines = vec![];
+                let mut first = Some(line);
+
+                for wrapped in textwrap::wrap(
+                    &action.description,
+                    textwrap::Options::new(width.max(1))
+                        .initial_indent(&" ".repeat(prefix_width.min(width)))
+                        .subsequent_indent("  "),
+                ) {
+                    match first.take() {
+                        Some(mut line) => {
+                            line.spans.push(Span::raw(wrapped.trim_start().to_string()));
+                            lines.push(line);
+                        }
+                        None => lines.push(Line::raw(wrapped.to_string())),
+                    }
+                }
+
+                ListItem::new(Text::from(lines))
+            });
+
+        let title = if self.offset > 0 {
+            format!("Mod log (+{})", self.offset)
+        } else {
+            "Mod log".to_string()
+        };
+
+        let title_binding = [TitleStyle::Single(&title)];
+
+        let list = List::new(items).block(
+            config
+                .block(Widget::Chat)
+                .title(title_line(&title_binding, title_style())),
+        );
+
+        f.render_widget(list, area);
+    }
+}
@@ -0,0 +1,156 @@
+use chrono::Local;
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Cell, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        app::SharedNotices,
+        config::{SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    twitch::notices::Notice,
+    ui::components::{
+        Component,
+        utils::{InputWidget, SearchableList, centered_rect, split_input},
+    },
+    utils::{
+        text::TitleStyle,
+        time::{format_relative, format_time},
+    },
+};
+
+pub struct NoticesWidget {
+    config: SharedCompleteConfig,
+    notices: SharedNotices,
+    focused: bool,
+    search_input: InputWidget<()>,
+    list: SearchableList<Notice>,
+}
+
+impl NoticesWidget {
+    pub fn new(config: SharedCompleteConfig, notices: SharedNotices) -> Self {
+        let search_input = InputWidget::new(config.clone(), "Filter", None, None, None);
+        let list = SearchableList::new(config.clone(), Widget::Popup);
+
+        Self {
+            config,
+            notices,
+            focused: false,
+            search_input,
+            list,
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+
+        if !self.focused {
+            self.search_input.clear();
+            self.list.clear();
+        }
+    }
+}
+
+impl Component for NoticesWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let [r, input_rect] =
+            split_input(area.map_or_else(|| centered_rect(80, 60, 23, f.area()), |a| a));
+
+        let query = self.search_input.to_string();
+        let matching = self.list.matching();
+
+        // Most recent notices first
+        self.list.set_rows(
+            self.notices
+                .borrow()
+                .iter()
+                .rev()
+                .filter(|n| n.matches(&query, matching))
+                .cloned()
+                .collect(),
+            None,
+        );
+
+        let config = self.config.borrow();
+        let now = Local::now();
+
+        let rows = self
+            .list
+            .rows()
+            .iter()
+            .map(|n| {
+                Row::new(vec![
+                    Cell::from(Text::from(vec![
+                        Line::from(format_time(&n.time_received, &config.frontend)),
+                        Line::from(Span::styled(
+                            format_relative(&n.time_received, &now),
+                            Style::default().add_modifier(Modifier::DIM),
+                        )),
+                    ])),
+                    Cell::from(n.msg_id.clone().unwrap_or_else(|| "-".to_string())),
+                    Cell::from(Text::from(vec![
+                        Line::from(n.message.clone()),
+                        Line::from(Span::styled(
+                            n.explanation(),
+                            Style::default().add_modifier(Modifier::DIM),
+                        )),
+                    ])),
+                ])
+                .height(2)
+            })
+            .collect::<Vec<Row>>();
+
+        drop(config);
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Length(28),
+                Constraint::Min(20),
+            ],
+        );
+
+        self.list
+            .draw_table(f, r, table, &[TitleStyle::Single("Notices")]);
+
+        self.search_input.draw(f, Some(input_rect));
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => {
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
+                    } else {
+                        self.toggle_focus();
+                    }
+                }
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
+                }
+                _ => {
+                    self.search_input.event(event).await;
+                    self.list.select_first();
+                }
+            }
+        }
+
+        None
+    }
+}
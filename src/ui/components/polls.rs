@@ -0,0 +1,146 @@
+use chrono::Local;
+use tui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, block::Position},
+};
+
+use crate::{
+    handlers::{
+        app::SharedPolls,
+        config::{SharedCompleteConfig, Widget},
+    },
+    twitch::polls::{PollKind, PollStatus},
+    utils::{
+        styles::{NO_COLOR, border_style, title_style},
+        text::{TitleStyle, title_line, truncate_to_width},
+        time::format_duration,
+    },
+};
+
+/// The width of the poll overlay, including its borders.
+const POLL_WIDTH: u16 = 44;
+/// The width of the bars showing the share of votes.
+const BAR_WIDTH: usize = 10;
+
+/// An overlay in the corner of chat showing the poll or prediction running in the current channel,
+/// along with its results for a while after it ended.
+pub struct PollWidget {
+    config: SharedCompleteConfig,
+    polls: SharedPolls,
+}
+
+impl PollWidget {
+    pub const fn new(config: SharedCompleteConfig, polls: SharedPolls) -> Self {
+        Self { config, polls }
+    }
+
+    /// Draw the overlay in the top right corner of the chat area, if there is something to show.
+    pub fn draw(&self, f: &mut Frame, area: Rect) {
+        let config = self.config.borrow();
+        let polls = self.polls.borrow();
+        let now = Local::now();
+
+        let Some(poll) = polls
+            .iter()
+            .rev()
+            .find(|poll| poll.channel == config.twitch.channel && poll.is_shown(now))
+        else {
+            return;
+        };
+
+        let width = POLL_WIDTH.min(area.width.saturating_sub(2));
+        let height = (poll.choices.len() as u16 + 2).min(area.height.saturating_sub(2));
+
+        if width < 10 || height < 3 {
+            return;
+        }
+
+        let rect = Rect::new(area.right() - width - 1, area.y + 1, width, height);
+
+        // Room for the percentage and the bar, the rest goes to the title of the choice
+        let title_width = (width as usize).saturating_sub(2 + BAR_WIDTH + 6);
+
+        let lines = poll
+            .choices
+            .iter()
+            .zip(poll.percentages())
+            .map(|(choice, percentage)| {
+                let filled = (percentage as usize * BAR_WIDTH).div_ceil(100);
+
+                let style = if choice.won {
+                    Style::default().add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+
+                Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "{:<title_width$}",
+                            truncate_to_width(&choice.title, title_width)
+                        ),
+                        style,
+                    ),
+                    Span::styled(
+                        "█".repeat(filled),
+                        if *NO_COLOR {
+                            Style::default()
+                        } else {
+                            Style::default().fg(if choice.won {
+                                Color::Green
+                            } else {
+                                Color::LightBlue
+                            })
+                        },
+                    ),
+                    Span::raw(" ".repeat(BAR_WIDTH - filled)),
+                    Span::styled(format!(" {percentage:>3}%"), style),
+                ])
+            })
+            .collect::<Vec<Line>>();
+
+        let kind = match poll.kind {
+            PollKind::Poll => "Poll",
+            PollKind::Prediction => "Prediction",
+        };
+
+        let title_binding = [TitleStyle::Combined(kind, &poll.title)];
+
+        let status = match poll.status {
+            PollStatus::Active => poll.closes_at.map_or_else(
+                || "Open".to_string(),
+                |closes_at| format!("{} left", format_duration(closes_at - now)),
+            ),
+            PollStatus::Locked => "Locked".to_string(),
+            PollStatus::Ended => "Ended".to_string(),
+            PollStatus::Canceled => "Canceled".to_string(),
+        };
+
+        let status_binding = [TitleStyle::Single(&status)];
+
+        let paragraph = Paragraph::new(lines).block(
+            config
+                .block(Widget::Popup)
+                .title(title_line(&title_binding, title_style())),
+        );
+
+        f.render_widget(Clear, rect);
+        f.render_widget(paragraph, rect);
+
+        let bottom_block = Block::default()
+            .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
+            .border_type(config.border_type(Widget::Popup))
+            .border_style(border_style())
+            .title(title_line(&status_binding, Style::default()))
+            .title_position(Position::Bottom)
+            .title_alignment(Alignment::Right);
+
+        f.render_widget(
+            bottom_block,
+            Rect::new(rect.x, rect.bottom() - 1, rect.width, 1),
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::VecDeque;
+
+use tui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Text},
+    widgets::{Clear, List, ListItem},
+};
+
+use crate::{
+    handlers::{
+        app::SharedMessages,
+        config::SharedCompleteConfig,
+        data::MessageData,
+        filters::SharedFilters,
+        ignored::SharedIgnoredUsers,
+        user_input::{
+            events::{Event, Key},
+            scrolling::Scrolling,
+        },
+    },
+    terminal::TerminalAction,
+    ui::components::Component,
+    utils::styles::NO_COLOR,
+};
+
+/// Empty rows in between each message, so chat is easier to read on stream.
+const MESSAGE_SPACING: usize = 1;
+
+/// A read-only view of chat without any borders or inputs, meant to be captured as an overlay on stream.
+pub struct ProjectorWidget {
+    config: SharedCompleteConfig,
+    messages: SharedMessages,
+    filters: SharedFilters,
+    ignored: SharedIgnoredUsers,
+    scroll_offset: Scrolling,
+}
+
+impl ProjectorWidget {
+    pub fn new(
+        config: SharedCompleteConfig,
+        messages: SharedMessages,
+        filters: SharedFilters,
+        ignored: SharedIgnoredUsers,
+    ) -> Self {
+        let scroll_offset = Scrolling::new(config.borrow().frontend.inverted_scrolling);
+
+        Self {
+            config,
+            messages,
+            filters,
+            ignored,
+            scroll_offset,
+        }
+    }
+
+    fn get_lines<'a>(
+        &self,
+        area: Rect,
+        messages_data: &'a VecDeque<MessageData>,
+    ) -> VecDeque<Line<'a>> {
+        let config = self.config.borrow();
+        let filters = self.filters.borrow();
+        let ignored = self.ignored.borrow();
+
+        let height = area.height as usize;
+
+        let username_highlight = config
+            .frontend
+            .username_highlight
+            .then_some(config.twitch.username.as_str());
+
+        let mut lines = VecDeque::new();
+
+        for data in messages_data
+            .iter()
+            .filter(|m| {
+                !filters.hides_message(&config.twitch.channel, m) && !ignored.contains(&m.author)
+            })
+            .skip(self.scroll_offset.get_offset())
+        {
+            // Viewers of the stream see the names users chose themselves, rather than nicknames
+            let mut message_lines = data.to_vec(
+                &config.frontend,
+                area.width as usize,
+                None,
+                username_highlight,
+                None,
+            );
+
+            message_lines.extend((0..MESSAGE_SPACING).map(|_| Line::default()));
+
+            for mut line in message_lines.into_iter().rev() {
+                if lines.len() >= height {
+                    break;
+                }
+
+                // Dimmed text does not survive stream encoding well.
+                for span in &mut line.spans {
+                    span.style = span.style.remove_modifier(Modifier::DIM);
+                }
+
+                lines.push_front(line);
+            }
+
+            if lines.len() >= height {
+                break;
+            }
+        }
+
+        // Anchor chat to the bottom of the screen.
+        while lines.len() < height {
+            lines.push_front(Line::default());
+        }
+
+        lines
+    }
+}
+
+impl Component for ProjectorWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let r = area.map_or_else(|| f.area(), |a| a);
+
+        let messages_data = self.messages.borrow();
+
+        let lines = self.get_lines(r, &messages_data);
+
+        let list = List::new(lines.into_iter().map(|l| ListItem::new(Text::from(l)))).style(
+            if *NO_COLOR {
+                Style::default()
+            } else {
+                // A solid background is easier to key out when capturing the terminal.
+                Style::default().bg(Color::Black).fg(Color::White)
+            },
+        );
+
+        f.render_widget(Clear, r);
+        f.render_widget(list, r);
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            let limit =
+                self.scroll_offset.get_offset() < self.messages.borrow().len().saturating_sub(1);
+
+            match key {
+                Key::Char('q') => return Some(TerminalAction::Quit),
+                Key::Esc => {
+                    if self.scroll_offset.get_offset() == 0 {
+                        return Some(TerminalAction::BackOneLayer);
+                    }
+
+                    self.scroll_offset.jump_to(0);
+                }
+                Key::Char('G') => self.scroll_offset.jump_to(0),
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollUp | Key::Up => {
+                    if limit {
+                        self.scroll_offset.up();
+                    } else if self.scroll_offset.is_inverted() {
+                        self.scroll_offset.down();
+                    }
+                }
+                Key::ScrollDown | Key::Down => {
+                    if self.scroll_offset.is_inverted() {
+                        if limit {
+                            self.scroll_offset.up();
+                        }
+                    } else {
+                        self.scroll_offset.down();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,164 @@
+use chrono::Local;
+use tui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::ListItem,
+};
+
+use super::utils::{SearchableList, centered_rect};
+use crate::{
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        joins::SharedJoinHistory,
+        user_input::events::{Event, Key, MouseAction},
+    },
+    terminal::TerminalAction,
+    twitch::TwitchAction,
+    ui::components::Component,
+    utils::{text::TitleStyle, time::format_relative},
+};
+
+/// How many of the channels can be switched to with a number key.
+const NUMBERED_CHANNELS: usize = 9;
+
+/// The channels joined the most recently and often, to switch back to one of them with a single key.
+pub struct QuickSwitchWidget {
+    config: SharedCompleteConfig,
+    focused: bool,
+    joins: SharedJoinHistory,
+    list: SearchableList<String>,
+}
+
+impl QuickSwitchWidget {
+    pub fn new(config: SharedCompleteConfig, joins: SharedJoinHistory) -> Self {
+        let list = SearchableList::new(config.clone(), Widget::Popup).without_search();
+
+        Self {
+            config,
+            focused: false,
+            joins,
+            list,
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Show the channels other than the current one, with the one most likely to be switched to selected.
+    pub fn open(&mut self) {
+        let current = self.config.borrow().twitch.channel.to_lowercase();
+
+        let channels = self
+            .joins
+            .borrow()
+            .ranked(&Local::now())
+            .into_iter()
+            .filter(|channel| *channel != current)
+            .collect::<Vec<String>>();
+
+        self.list.set_rows(channels, None);
+        self.list.select_first();
+
+        self.focused = true;
+    }
+
+    fn close(&mut self) {
+        self.focused = false;
+        self.list.clear();
+    }
+
+    fn join(&mut self, channel: String) -> TerminalAction {
+        self.close();
+
+        TerminalAction::Enter(TwitchAction::Join(channel))
+    }
+}
+
+impl Component for QuickSwitchWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let r = area.map_or_else(|| centered_rect(50, 50, 15, f.area()), |a| a);
+
+        let now = Local::now();
+        let joins = self.joins.borrow();
+
+        let items = self
+            .list
+            .rows()
+            .iter()
+            .enumerate()
+            .map(|(i, channel)| {
+                let number = if i < NUMBERED_CHANNELS {
+                    format!("{}  ", i + 1)
+                } else {
+                    "   ".to_string()
+                };
+
+                let joined = joins
+                    .last_joined(channel)
+                    .map(|time| format!("  {}", format_relative(time, &now)))
+                    .unwrap_or_default();
+
+                ListItem::new(Line::from(vec![
+                    Span::styled(number, Style::default().add_modifier(Modifier::DIM)),
+                    Span::raw(channel.clone()),
+                    Span::styled(joined, Style::default().add_modifier(Modifier::DIM)),
+                ]))
+            })
+            .collect::<Vec<ListItem>>();
+
+        let title = if items.is_empty() {
+            TitleStyle::Single("No other channels joined")
+        } else {
+            TitleStyle::Single("Quick switch")
+        };
+
+        drop(joins);
+
+        self.list.draw(
+            f,
+            r,
+            items,
+            &[
+                title,
+                TitleStyle::Combined("1-9", "join"),
+                TitleStyle::Combined("Enter", "join selected"),
+            ],
+        );
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Mouse(MouseAction::Click, position) = event {
+            if self.list.click(*position) {
+                let channel = self.list.selected().cloned()?;
+
+                return Some(self.join(channel));
+            }
+        }
+
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => self.close(),
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down | Key::Char('j') => self.list.next(),
+                Key::ScrollUp | Key::Up | Key::Char('k') => self.list.previous(),
+                Key::Char(c @ '1'..='9') => {
+                    let i = c.to_digit(10).unwrap() as usize - 1;
+                    let channel = self.list.rows().get(i).cloned()?;
+
+                    return Some(self.join(channel));
+                }
+                Key::Enter => {
+                    let channel = self.list.selected().cloned()?;
+
+                    return Some(self.join(channel));
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
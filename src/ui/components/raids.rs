@@ -0,0 +1,212 @@
+use chrono::Local;
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    prelude::Alignment,
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Cell, Clear, Paragraph, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        app::SharedRaids,
+        config::{SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    twitch::raids::Raid,
+    ui::components::{
+        Component,
+        utils::{InputWidget, SearchableList, centered_rect, split_input},
+    },
+    utils::{
+        styles::{NO_COLOR, title_style},
+        text::{TitleStyle, title_line},
+        time::{format_datetime, format_relative},
+    },
+};
+
+pub struct RaidsWidget {
+    config: SharedCompleteConfig,
+    raids: SharedRaids,
+    focused: bool,
+    search_input: InputWidget<()>,
+    list: SearchableList<Raid>,
+}
+
+impl RaidsWidget {
+    pub fn new(config: SharedCompleteConfig, raids: SharedRaids) -> Self {
+        let search_input = InputWidget::new(config.clone(), "Filter", None, None, None);
+        let list = SearchableList::new(config.clone(), Widget::Popup);
+
+        Self {
+            config,
+            raids,
+            focused: false,
+            search_input,
+            list,
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The latest raid into or out of the current channel, while its banner is shown.
+    pub fn banner_raid(&self) -> Option<Raid> {
+        let channel = &self.config.borrow().twitch.channel;
+        let now = Local::now();
+
+        self.raids
+            .borrow()
+            .iter()
+            .rev()
+            .find(|raid| raid.is_banner_shown(channel, now))
+            .cloned()
+    }
+
+    /// Draw a banner along the bottom of the chat area while the current channel is raiding or being raided.
+    pub fn draw_banner(&self, f: &mut Frame, area: Rect) {
+        let Some(raid) = self.banner_raid() else {
+            return;
+        };
+
+        if area.width < 10 || area.height < 5 {
+            return;
+        }
+
+        let config = self.config.borrow();
+
+        let mut text = raid.banner();
+
+        if raid.outgoing {
+            text.push_str(", press F to follow");
+        }
+
+        let style = if *NO_COLOR {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+                .fg(Color::LightMagenta)
+                .add_modifier(Modifier::BOLD)
+        };
+
+        let title_binding = [TitleStyle::Single("Raid")];
+
+        let paragraph = Paragraph::new(Line::from(Span::styled(text, style)))
+            .alignment(Alignment::Center)
+            .block(
+                config
+                    .block(Widget::Popup)
+                    .title(title_line(&title_binding, title_style())),
+            );
+
+        let rect = Rect::new(area.x + 1, area.bottom() - 4, area.width - 2, 3);
+
+        f.render_widget(Clear, rect);
+        f.render_widget(paragraph, rect);
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+
+        if !self.focused {
+            self.search_input.clear();
+            self.list.clear();
+        }
+    }
+}
+
+impl Component for RaidsWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let [r, input_rect] =
+            split_input(area.map_or_else(|| centered_rect(80, 60, 23, f.area()), |a| a));
+
+        let query = self.search_input.to_string();
+        let matching = self.list.matching();
+
+        // Most recent raids first
+        self.list.set_rows(
+            self.raids
+                .borrow()
+                .iter()
+                .rev()
+                .filter(|r| r.matches(&query, matching))
+                .cloned()
+                .collect(),
+            None,
+        );
+
+        let config = self.config.borrow();
+        let now = Local::now();
+
+        let rows = self
+            .list
+            .rows()
+            .iter()
+            .map(|r| {
+                let direction = if r.outgoing { "to" } else { "from" };
+
+                Row::new(vec![
+                    Cell::from(Text::from(vec![
+                        Line::from(format_datetime(&r.time_received, &config.frontend)),
+                        Line::from(Span::styled(
+                            format_relative(&r.time_received, &now),
+                            Style::default().add_modifier(Modifier::DIM),
+                        )),
+                    ])),
+                    Cell::from(format!("{direction} {}", r.other_channel())),
+                    Cell::from(r.viewers.map_or_else(
+                        || "unknown".to_string(),
+                        |viewers| format!("{viewers} viewers"),
+                    )),
+                ])
+                .height(2)
+            })
+            .collect::<Vec<Row>>();
+
+        drop(config);
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(22),
+                Constraint::Length(30),
+                Constraint::Min(14),
+            ],
+        );
+
+        self.list
+            .draw_table(f, r, table, &[TitleStyle::Single("Raids")]);
+
+        self.search_input.draw(f, Some(input_rect));
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => {
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
+                    } else {
+                        self.toggle_focus();
+                    }
+                }
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
+                }
+                _ => {
+                    self.search_input.event(event).await;
+                    self.list.select_first();
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,150 @@
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    widgets::{Cell, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    twitch::{TwitchAction, outbox::Outgoing},
+    ui::components::{
+        Component,
+        utils::{SearchableList, centered_rect},
+    },
+    utils::text::TitleStyle,
+};
+
+/// Messages and whispers left unsent by the previous session, to be sent, edited, or discarded.
+pub struct RecoveredWidget {
+    items: Vec<Outgoing>,
+    focused: bool,
+    list: SearchableList<Outgoing>,
+    /// The text of the item chosen to be edited, to be put in the chat input.
+    edited: Option<String>,
+}
+
+impl RecoveredWidget {
+    pub fn new(config: SharedCompleteConfig) -> Self {
+        Self {
+            items: vec![],
+            focused: false,
+            list: SearchableList::new(config, Widget::Popup).without_search(),
+            edited: None,
+        }
+    }
+
+    /// Show the recovered items, asking what should happen to them.
+    pub fn recover(&mut self, items: Vec<Outgoing>) {
+        self.items.extend(items);
+
+        self.list.set_rows(self.items.clone(), None);
+
+        if !self.items.is_empty() {
+            self.focused = true;
+            self.list.select_first();
+        }
+    }
+
+    pub fn take_edited(&mut self) -> Option<String> {
+        self.edited.take()
+    }
+
+    /// Take the items that a decision was made about, closing the popup once none are left.
+    fn take(&mut self, all: bool) -> Vec<Outgoing> {
+        let taken = if all {
+            self.items.drain(..).collect()
+        } else {
+            self.list
+                .selected_index()
+                .filter(|&i| i < self.items.len())
+                .map(|i| vec![self.items.remove(i)])
+                .unwrap_or_default()
+        };
+
+        self.list.set_rows(self.items.clone(), None);
+
+        if self.items.is_empty() {
+            self.focused = false;
+        }
+
+        taken
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Hide the popup, leaving the items to be asked about again on the next start.
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused && !self.items.is_empty();
+    }
+}
+
+impl Component for RecoveredWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let r = area.map_or_else(|| centered_rect(70, 40, 10, f.area()), |a| a);
+
+        let rows = self
+            .items
+            .iter()
+            .map(|item| match item {
+                Outgoing::Message { channel, message } => Row::new(vec![
+                    Cell::from(format!("#{channel}")),
+                    Cell::from(message.clone()),
+                ]),
+                Outgoing::Whisper { user, message } => Row::new(vec![
+                    Cell::from(format!("whisper to {user}")),
+                    Cell::from(message.clone()),
+                ]),
+            })
+            .collect::<Vec<Row>>();
+
+        let title = [
+            TitleStyle::Single("Unsent messages"),
+            TitleStyle::Combined("s/S", "send/all"),
+            TitleStyle::Combined("e", "edit"),
+            TitleStyle::Combined("d/D", "discard/all"),
+        ];
+
+        let table = Table::new(rows, [Constraint::Length(30), Constraint::Min(20)]);
+
+        self.list.draw_table(f, r, table, &title);
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => self.toggle_focus(),
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Char(c @ ('s' | 'S')) => {
+                    let items = self.take(*c == 'S');
+
+                    return Some(TerminalAction::Enter(TwitchAction::Recovered(items, true)));
+                }
+                Key::Char(c @ ('d' | 'D')) => {
+                    let items = self.take(*c == 'D');
+
+                    return Some(TerminalAction::Enter(TwitchAction::Recovered(items, false)));
+                }
+                Key::Char('e') => {
+                    let items = self.take(false);
+
+                    // The edited message is sent like any other once it leaves the chat input
+                    self.edited = items.first().map(Outgoing::as_input);
+                    self.focused = false;
+
+                    return Some(TerminalAction::Enter(TwitchAction::Recovered(items, false)));
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
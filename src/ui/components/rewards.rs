@@ -0,0 +1,200 @@
+use tui::{
+    Frame,
+    layout::{Constraint, Margin, Rect},
+    prelude::Alignment,
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Cell, Paragraph, Row, Table, Wrap},
+};
+
+use crate::{
+    handlers::{
+        config::{SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    twitch::rewards::{Reward, get_rewards, set_reward_paused},
+    ui::components::{
+        Component,
+        utils::{InputWidget, SearchableList, centered_rect, split_input},
+    },
+    utils::text::TitleStyle,
+};
+
+pub struct RewardsWidget {
+    config: SharedCompleteConfig,
+    rewards: Vec<Reward>,
+    /// Why the rewards could not be retrieved, or the last reward could not be changed.
+    error: Option<String>,
+    focused: bool,
+    search_input: InputWidget<()>,
+    list: SearchableList<Reward>,
+}
+
+impl RewardsWidget {
+    pub fn new(config: SharedCompleteConfig) -> Self {
+        let search_input = InputWidget::new(config.clone(), "Filter", None, None, None);
+        let list = SearchableList::new(config.clone(), Widget::Popup);
+
+        Self {
+            config,
+            rewards: vec![],
+            error: None,
+            focused: false,
+            search_input,
+            list,
+        }
+    }
+
+    /// Pause the selected reward if it can be redeemed, or resume it otherwise.
+    async fn toggle_selected(&mut self) {
+        let Some(reward) = self.list.selected().cloned() else {
+            return;
+        };
+
+        let token = self.config.borrow().twitch.token.clone();
+        let paused = !reward.is_paused;
+
+        match set_reward_paused(token.as_deref(), &reward.id, paused).await {
+            Ok(()) => {
+                if let Some(r) = self.rewards.iter_mut().find(|r| r.id == reward.id) {
+                    r.is_paused = paused;
+                }
+
+                self.error = None;
+            }
+            Err(err) => {
+                self.error = Some(format!("Unable to change {}: {err}", reward.title));
+            }
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub async fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+
+        if self.focused {
+            let (token, channel) = {
+                let config = self.config.borrow();
+
+                (config.twitch.token.clone(), config.twitch.channel.clone())
+            };
+
+            match get_rewards(token.as_deref(), &channel).await {
+                Ok(rewards) => {
+                    self.rewards = rewards;
+                    self.error = None;
+                }
+                Err(err) => {
+                    self.rewards.clear();
+                    self.error = Some(err.to_string());
+                }
+            }
+        } else {
+            self.search_input.clear();
+            self.list.clear();
+        }
+    }
+}
+
+impl Component for RewardsWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let [r, input_rect] =
+            split_input(area.map_or_else(|| centered_rect(80, 60, 23, f.area()), |a| a));
+
+        let query = self.search_input.to_string();
+        let matching = self.list.matching();
+
+        self.list.set_rows(
+            self.rewards
+                .iter()
+                .filter(|r| r.matches(&query, matching))
+                .cloned()
+                .collect(),
+            None,
+        );
+
+        let rows = self
+            .list
+            .rows()
+            .iter()
+            .map(|r| {
+                let status_style = if !r.is_enabled || r.is_paused {
+                    Style::default().add_modifier(Modifier::DIM)
+                } else {
+                    Style::default()
+                };
+
+                Row::new(vec![
+                    Cell::from(Text::from(vec![
+                        Line::from(r.title.clone()),
+                        Line::from(Span::styled(
+                            r.prompt.clone(),
+                            Style::default().add_modifier(Modifier::DIM),
+                        )),
+                    ])),
+                    Cell::from(format!("{} points", r.cost)),
+                    Cell::from(Span::styled(r.status(), status_style)),
+                ])
+                .height(2)
+            })
+            .collect::<Vec<Row>>();
+
+        let title = [
+            TitleStyle::Single("Rewards"),
+            TitleStyle::Combined("Enter", "pause/resume"),
+        ];
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(30),
+                Constraint::Length(16),
+                Constraint::Length(10),
+            ],
+        );
+
+        self.list.draw_table(f, r, table, &title);
+
+        if let Some(error) = &self.error {
+            let paragraph = Paragraph::new(error.as_str())
+                .wrap(Wrap { trim: true })
+                .alignment(Alignment::Center);
+
+            f.render_widget(paragraph, r.inner(Margin::new(1, 1)));
+        }
+
+        self.search_input.draw(f, Some(input_rect));
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => {
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
+                    } else {
+                        self.toggle_focus().await;
+                    }
+                }
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
+                }
+                Key::Enter => self.toggle_selected().await,
+                _ => {
+                    self.search_input.event(event).await;
+                    self.list.select_first();
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,112 @@
+use color_eyre::Report;
+use tui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph, Wrap},
+};
+
+use crate::{
+    handlers::config::{SharedCompleteConfig, Widget},
+    ui::components::{Component, utils::centered_rect},
+    utils::{
+        styles::{NO_COLOR, title_style},
+        text::{TitleStyle, title_line},
+    },
+};
+
+/// What the user chose to do after starting up failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupChoice {
+    Retry,
+    Settings,
+    Quit,
+}
+
+/// What can be done about an error, depending on if Twitch could be reached at all.
+fn remediation(err: &Report) -> &'static str {
+    let unreachable = err
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(|cause| cause.is_connect() || cause.is_timeout() || cause.is_request());
+
+    if unreachable {
+        "Check your internet connection, then retry."
+    } else {
+        "Check the token and client ID in the settings, or log in again."
+    }
+}
+
+/// Shows why starting up failed, with what can be done about it.
+pub struct StartupErrorWidget {
+    config: SharedCompleteConfig,
+    /// The error, followed by each of its causes.
+    causes: Vec<String>,
+    remediation: &'static str,
+}
+
+impl StartupErrorWidget {
+    pub fn new(config: SharedCompleteConfig, err: &Report) -> Self {
+        Self {
+            config,
+            causes: err.chain().map(ToString::to_string).collect(),
+            remediation: remediation(err),
+        }
+    }
+}
+
+impl Component for StartupErrorWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let height = self.causes.len() as u16 + 5;
+
+        let r = area.map_or_else(|| centered_rect(70, 30, height, f.area()), |a| a);
+
+        let mut lines = self
+            .causes
+            .iter()
+            .enumerate()
+            .map(|(i, cause)| {
+                if i == 0 {
+                    Line::from(Span::styled(
+                        cause.clone(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ))
+                } else {
+                    Line::from(cause.clone())
+                }
+            })
+            .collect::<Vec<Line>>();
+
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(
+            self.remediation,
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+
+        let title_binding = [
+            TitleStyle::Single("Unable to start"),
+            TitleStyle::Combined("r", "retry"),
+            TitleStyle::Combined("s", "settings"),
+            TitleStyle::Combined("Esc", "quit"),
+        ];
+
+        let paragraph = Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(
+                self.config
+                    .borrow()
+                    .block(Widget::Popup)
+                    .border_style(if *NO_COLOR {
+                        Style::default()
+                    } else {
+                        Style::default().fg(Color::Red)
+                    })
+                    .title(title_line(&title_binding, title_style())),
+            );
+
+        f.render_widget(Clear, r);
+        f.render_widget(paragraph, r);
+    }
+}
@@ -0,0 +1,80 @@
+use tui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::{
+    handlers::config::SharedCompleteConfig,
+    twitch::chat_status::{ConnectionState, connection_state, room_modes},
+    utils::styles::{NO_COLOR, STATE_TABS_STYLE},
+};
+
+const SEPARATOR: &str = " │ ";
+
+/// A line along the bottom of the terminal with the state of the connection to Twitch,
+/// the current channel, what is being done, unread mentions, and the modes of the channel.
+#[derive(Debug, Clone)]
+pub struct StatusBarWidget {
+    config: SharedCompleteConfig,
+}
+
+impl StatusBarWidget {
+    pub const fn new(config: SharedCompleteConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect, mode: &str, unread_mentions: usize) {
+        let channel = self.config.borrow().twitch.channel.clone();
+        let state = connection_state();
+
+        let connection_style = if *NO_COLOR {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(match state {
+                    ConnectionState::Connected => Color::Green,
+                    ConnectionState::Connecting | ConnectionState::Reconnecting => Color::Yellow,
+                    ConnectionState::NotConnected => Color::Gray,
+                })
+        };
+
+        let mut spans = vec![
+            Span::styled(format!("● {state}"), connection_style),
+            Span::raw(SEPARATOR),
+            Span::raw(format!("#{channel}")),
+            Span::raw(SEPARATOR),
+            Span::raw(mode.to_string()),
+        ];
+
+        if unread_mentions > 0 {
+            spans.push(Span::raw(SEPARATOR));
+            spans.push(Span::styled(
+                format!(
+                    "{unread_mentions} {}",
+                    if unread_mentions == 1 {
+                        "mention"
+                    } else {
+                        "mentions"
+                    }
+                ),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+        }
+
+        let modes = room_modes(&channel).enabled();
+
+        if !modes.is_empty() {
+            spans.push(Span::raw(SEPARATOR));
+            spans.push(Span::raw(modes.join(", ")));
+        }
+
+        f.render_widget(
+            Paragraph::new(Line::from(spans)).style(*STATE_TABS_STYLE),
+            area,
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use chrono::Local;
+use tui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::{
+    handlers::config::{SharedCompleteConfig, StreamInfoStyle},
+    twitch::streams::LiveStream,
+    utils::{
+        styles::{BOLD_STYLE, NO_COLOR, STATE_TABS_STYLE},
+        time::format_duration,
+    },
+};
+
+/// A header above chat with the title, category, uptime, and viewers of the current stream.
+pub struct StreamInfoWidget {
+    style: StreamInfoStyle,
+    /// The streams of the channels that have been checked, being `None` while offline.
+    streams: HashMap<String, Option<LiveStream>>,
+}
+
+impl StreamInfoWidget {
+    pub fn new(config: &SharedCompleteConfig) -> Self {
+        Self {
+            style: config.borrow().frontend.stream_info,
+            streams: HashMap::new(),
+        }
+    }
+
+    pub fn set_stream(&mut self, channel: String, stream: Option<LiveStream>) {
+        self.streams.insert(channel, stream);
+    }
+
+    /// Go to the next way of showing the header, ending with it being hidden.
+    pub const fn cycle_style(&mut self) {
+        self.style = self.style.next();
+    }
+
+    /// The amount of lines the header takes up.
+    pub const fn height(&self) -> u16 {
+        match self.style {
+            StreamInfoStyle::Hidden => 0,
+            StreamInfoStyle::Line => 1,
+            StreamInfoStyle::Full => 2,
+        }
+    }
+
+    fn lines(&self, channel: &str) -> Vec<Line<'_>> {
+        let Some(checked) = self.streams.get(channel) else {
+            return vec![Line::styled(
+                format!("Checking if {channel} is live..."),
+                *STATE_TABS_STYLE,
+            )];
+        };
+
+        let Some(stream) = checked else {
+            return vec![Line::styled(
+                format!("{channel} is offline"),
+                *STATE_TABS_STYLE,
+            )];
+        };
+
+        let live = Span::styled(
+            "LIVE ",
+            if *NO_COLOR {
+                *BOLD_STYLE
+            } else {
+                BOLD_STYLE.fg(Color::Red)
+            },
+        );
+
+        let uptime = stream
+            .uptime(Local::now())
+            .map_or_else(String::new, |uptime| {
+                format!(" · up {}", format_duration(uptime))
+            });
+
+        let details = Span::styled(
+            format!(
+                "{}{uptime} · {} viewers",
+                stream.game_name, stream.viewer_count
+            ),
+            *STATE_TABS_STYLE,
+        );
+
+        let title = Span::styled(stream.title.clone(), Style::default());
+
+        match self.style {
+            StreamInfoStyle::Full => vec![Line::from(vec![live, title]), Line::from(vec![details])],
+            _ => vec![Line::from(vec![
+                live,
+                title,
+                Span::styled(" · ", *STATE_TABS_STYLE),
+                details,
+            ])],
+        }
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect, channel: &str) {
+        f.render_widget(Paragraph::new(self.lines(channel)), area);
+    }
+}
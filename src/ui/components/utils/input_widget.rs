@@ -15,14 +15,14 @@ use tui::{
 use super::centered_rect;
 use crate::{
     handlers::{
-        config::SharedCompleteConfig,
+        config::{SharedCompleteConfig, Widget},
         user_input::events::{Event, Key},
     },
     terminal::TerminalAction,
     ui::{components::Component, statics::LINE_BUFFER_CAPACITY},
     utils::{
         styles::NO_COLOR,
-        text::{TitleStyle, get_cursor_position, title_line},
+        text::{TitleStyle, get_cursor_line, get_cursor_position, title_line},
     },
 };
 
@@ -57,8 +57,13 @@ pub struct InputWidget<T: Clone> {
     visual_indicator: Option<VisualValidator>,
     input_suggester: Option<(T, InputSuggester<T>)>,
     suggestion: Option<String>,
+    /// If line breaks can be typed and pasted, rather than pasted line breaks becoming spaces.
+    multiline: bool,
 }
 
+/// The most lines an input grows to, before scrolling through them.
+const MAXIMUM_INPUT_LINES: usize = 5;
+
 impl<T: Clone> InputWidget<T> {
     pub fn new(
         config: SharedCompleteConfig,
@@ -77,18 +82,59 @@ impl<T: Clone> InputWidget<T> {
             visual_indicator,
             input_suggester,
             suggestion: None,
+            multiline: false,
         }
     }
 
-    #[allow(dead_code)]
     pub fn update(&mut self, s: &str) {
-        self.input.update(s, 0, &mut self.input_listener);
+        self.input.update(s, s.len(), &mut self.input_listener);
     }
 
     pub fn clear(&mut self) {
         self.input.update("", 0, &mut self.input_listener);
     }
 
+    pub fn set_title(&mut self, title: &str) {
+        title.clone_into(&mut self.title);
+    }
+
+    pub const fn set_multiline(&mut self, multiline: bool) {
+        self.multiline = multiline;
+    }
+
+    /// How many lines of text are shown, which grows with the lines typed up to a limit.
+    pub fn height(&self) -> u16 {
+        self.input
+            .as_str()
+            .split('\n')
+            .count()
+            .min(MAXIMUM_INPUT_LINES) as u16
+    }
+
+    /// Insert pasted text at the cursor, leaving out what does not fit in the input.
+    fn paste(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+
+        let mut text = if self.multiline {
+            text
+        } else {
+            text.replace('\n', " ")
+        };
+
+        let available = LINE_BUFFER_CAPACITY.saturating_sub(self.input.len());
+
+        if text.len() > available {
+            let end = (0..=available)
+                .rev()
+                .find(|i| text.is_char_boundary(*i))
+                .unwrap_or_default();
+
+            text.truncate(end);
+        }
+
+        self.insert(&text);
+    }
+
     pub const fn is_focused(&self) -> bool {
         self.focused
     }
@@ -119,6 +165,19 @@ impl<T: Clone> InputWidget<T> {
             .insert_str(self.input.pos(), s, &mut self.input_listener);
         self.input.set_pos(self.input.pos() + s.len());
     }
+
+    /// The text typed before the cursor.
+    pub fn before_cursor(&self) -> &str {
+        &self.input.as_str()[..self.input.pos()]
+    }
+
+    /// Replace the last bytes typed before the cursor with some other text.
+    pub fn replace_before_cursor(&mut self, length: usize, s: &str) {
+        let end = self.input.pos();
+
+        self.input
+            .replace(end.saturating_sub(length)..end, s, &mut self.input_listener);
+    }
 }
 
 impl<T: Clone> Display for InputWidget<T> {
@@ -131,16 +190,37 @@ impl<T: Clone> Component for InputWidget<T> {
     fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
         let r = area.map_or_else(|| centered_rect(60, 60, 20, f.area()), |a| a);
 
+        // Input boxes shown as popups keep their borders in compact mode
+        let compact = area.is_some() && self.config.borrow().frontend.compact;
+
         let cursor_pos = get_cursor_position(&self.input);
+        let padding = self.config.borrow().padding(Widget::Input);
+        let border = u16::from(!compact);
+
+        // The lines shown follow the cursor once there are more of them than fit
+        let cursor_line = get_cursor_line(&self.input) as u16;
+        let line_scroll = (cursor_line + 1).saturating_sub(self.height());
 
         f.set_cursor_position(LayoutPosition::new(
-            (r.x + cursor_pos as u16 + 1).min(r.x + r.width.saturating_sub(2)),
-            r.y + 1,
+            (r.x + padding + cursor_pos as u16 + border)
+                .min(r.x + r.width.saturating_sub(border * 2 + padding)),
+            r.y + 1 + cursor_line - line_scroll,
         ));
 
         let current_input = self.input.as_str();
 
-        let binding = [TitleStyle::Single(&self.title)];
+        // Without a bottom border, what the input would become is shown in the title
+        let visual = self
+            .visual_indicator
+            .as_ref()
+            .filter(|_| compact)
+            .map(|visual| visual(self.input.to_string()));
+
+        let mut binding = vec![TitleStyle::Single(&self.title)];
+
+        if let Some(visual) = &visual {
+            binding.push(TitleStyle::Single(visual));
+        }
 
         let status_color = if self.is_valid() {
             Color::Green
@@ -160,45 +240,52 @@ impl<T: Clone> Component for InputWidget<T> {
             })
             .flatten();
 
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .border_type(self.config.borrow().frontend.border_type.clone().into())
-            .border_style(Style::default().fg(status_color))
-            .title(title_line(
-                &binding,
-                if *NO_COLOR {
-                    Style::default()
-                } else {
-                    Style::default()
-                        .fg(status_color)
-                        .add_modifier(Modifier::BOLD)
-                },
-            ));
-
-        let paragraph_lines = Line::from(vec![
-            Span::raw(current_input),
-            Span::styled(
-                self.suggestion
-                    .as_ref()
-                    .map_or_else(String::new, |suggestion_buffer| {
-                        if suggestion_buffer.len() > current_input.len() {
-                            suggestion_buffer[current_input.len()..].to_string()
-                        } else {
-                            String::new()
-                        }
-                    }),
-                Style::default().add_modifier(Modifier::DIM),
-            ),
-        ]);
+        let block = if compact {
+            Block::default()
+        } else {
+            self.config.borrow().block(Widget::Input)
+        }
+        .border_style(Style::default().fg(status_color))
+        .title(title_line(
+            &binding,
+            if *NO_COLOR {
+                Style::default()
+            } else {
+                Style::default()
+                    .fg(status_color)
+                    .add_modifier(Modifier::BOLD)
+            },
+        ));
+
+        let paragraph_lines = if current_input.contains('\n') {
+            current_input.split('\n').map(Line::raw).collect()
+        } else {
+            vec![Line::from(vec![
+                Span::raw(current_input),
+                Span::styled(
+                    self.suggestion
+                        .as_ref()
+                        .map_or_else(String::new, |suggestion_buffer| {
+                            if suggestion_buffer.len() > current_input.len() {
+                                suggestion_buffer[current_input.len()..].to_string()
+                            } else {
+                                String::new()
+                            }
+                        }),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ])]
+        };
 
-        let paragraph = Paragraph::new(paragraph_lines)
-            .block(block)
-            .scroll((0, ((cursor_pos + 3) as u16).saturating_sub(r.width)));
+        let paragraph = Paragraph::new(paragraph_lines).block(block).scroll((
+            line_scroll,
+            ((cursor_pos + 1) as u16 + border * 2 + padding * 2).saturating_sub(r.width),
+        ));
 
         f.render_widget(Clear, r);
         f.render_widget(paragraph, r);
 
-        if let Some(visual) = &self.visual_indicator {
+        if let Some(visual) = self.visual_indicator.as_ref().filter(|_| !compact) {
             let contents = visual(self.input.to_string());
 
             let title = [TitleStyle::Single(&contents)];
@@ -216,7 +303,7 @@ impl<T: Clone> Component for InputWidget<T> {
                 ))
                 .title_position(BlockPosition::Bottom)
                 .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
-                .border_type(self.config.borrow().frontend.border_type.clone().into());
+                .border_type(self.config.borrow().border_type(Widget::Input));
 
             // This is only supposed to render on the very bottom line of the area.
             // If some rendering breaks for input boxes, this is a possible source.
@@ -226,6 +313,10 @@ impl<T: Clone> Component for InputWidget<T> {
     }
 
     async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Paste(text) = event {
+            self.paste(text);
+        }
+
         if let Event::Input(key) = event {
             match key {
                 Key::Ctrl('f') | Key::Right => {
@@ -284,6 +375,9 @@ impl<T: Clone> Component for InputWidget<T> {
                         }
                     }
                 }
+                Key::Ctrl('j') if self.multiline => {
+                    self.input.insert('\n', 1, &mut self.input_listener);
+                }
                 Key::Ctrl('p') => panic!("Manual panic triggered by user."),
                 Key::Ctrl('q') => return Some(TerminalAction::Quit),
                 Key::Char(c) => {
@@ -1,11 +1,15 @@
 mod input_widget;
 mod popups;
 mod search_widget;
+mod searchable_list;
 
 #[allow(unused_imports)]
 pub use input_widget::{
     InputListener, // This is used in a test within src/utils/text.rs
     InputWidget,
 };
-pub use popups::centered_rect;
+pub use popups::{centered_rect, split_input};
 pub use search_widget::{SearchItemGetter, SearchWidget};
+pub use searchable_list::{
+    FavoriteMatcher, NameMatcher, SearchableList, highlight_matches, mark_favorite,
+};
@@ -25,3 +25,8 @@ pub fn centered_rect(percent_x: u16, percent_y: u16, height: u16, r: Rect) -> Re
         )
         .split(popup_layout[1])[1]
 }
+
+/// The area of a popup split into its list, and the search input of height 3 under it.
+pub fn split_input(r: Rect) -> [Rect; 2] {
+    Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).areas(r)
+}
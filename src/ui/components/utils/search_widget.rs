@@ -1,43 +1,126 @@
-use std::{clone::Clone, convert::From, iter::Iterator, vec::Vec};
+use std::{clone::Clone, collections::HashSet, iter::Iterator, vec::Vec};
 
 use color_eyre::Result;
-use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
-use once_cell::sync::Lazy;
+use log::debug;
 use tui::{
     Frame,
     layout::Rect,
-    prelude::{Alignment, Margin},
-    style::{Color, Modifier, Style},
-    symbols::scrollbar,
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{
-        Block, Borders, Clear, List, ListItem, ListState, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, block::Position,
-    },
 };
 
-use super::{InputWidget, centered_rect};
+use super::{
+    InputWidget, centered_rect,
+    searchable_list::{NameMatcher, RowMatcher, SearchableList, highlight_matches},
+};
 use crate::{
     handlers::{
-        config::SharedCompleteConfig,
-        user_input::events::{Event, Key},
+        config::{Matching, SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key, MouseAction},
     },
     terminal::TerminalAction,
     twitch::TwitchAction,
     ui::components::{Component, ErrorWidget},
-    utils::{
-        styles::{NO_COLOR, SEARCH_STYLE, TITLE_STYLE},
-        text::{TitleStyle, title_line},
-    },
+    utils::{matching::match_indices, text::TitleStyle},
 };
 
-static FUZZY_FINDER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
+/// How many items from the last one shown the selection gets before more are gotten.
+const LOAD_AHEAD: usize = 10;
 
 pub trait SearchItemGetter<T>
 where
     T: ToString,
 {
     async fn get_items(&mut self) -> Result<Vec<T>>;
+
+    /// The items after the ones gotten so far, for items that are gotten a page at a time.
+    /// Empty once every item has been gotten.
+    #[allow(clippy::unused_async)]
+    async fn more_items(&mut self) -> Result<Vec<T>> {
+        Ok(vec![])
+    }
+
+    /// How many items there are, if only some of them have been gotten.
+    fn total(&self) -> Option<usize> {
+        None
+    }
+
+    /// If the items are cached and possibly outdated.
+    fn is_stale(&self) -> bool {
+        false
+    }
+
+    /// Other text an item can be searched by, such as what a channel is streaming, named by what it is.
+    fn details(&self, _item: &T) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+
+    /// Put the items in the order they are shown in.
+    fn sort(&self, _items: &mut [T]) {}
+
+    /// If the items are put in an order other than the one they are gotten in,
+    /// which is only complete once every item has been gotten.
+    fn is_sorted(&self) -> bool {
+        false
+    }
+
+    /// Items that are always shown first, whether they were gotten or not, such as favorite channels.
+    fn pinned(&self) -> Vec<T> {
+        vec![]
+    }
+
+    /// What is shown in the title along with the name of the list, such as keys and what they change.
+    fn title(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+
+    /// Keep what is shown next to the items up to date, which is done each time they are drawn.
+    fn refresh(&mut self) {}
+
+    /// The row of an item, with anything that is shown along with it, such as if a channel is live.
+    fn decorate(&self, _item: &T, line: Line<'static>) -> Line<'static> {
+        line
+    }
+}
+
+/// Matches items by their name, then by their details, which show what was matched.
+struct DetailsMatcher<'a, U>(&'a U);
+
+impl<T, U> RowMatcher<T> for DetailsMatcher<'_, U>
+where
+    T: ToString,
+    U: SearchItemGetter<T>,
+{
+    fn matches(&self, item: &T, search: &str, matching: Matching) -> Option<Line<'static>> {
+        if let Some(line) = NameMatcher.matches(item, search, matching) {
+            return Some(self.0.decorate(item, line));
+        }
+
+        let (field, value, matched_indices) =
+            self.0
+                .details(item)
+                .into_iter()
+                .find_map(|(field, value)| {
+                    let (_, matched_indices) = match_indices(matching, &value, search)?;
+
+                    Some((field, value, matched_indices))
+                })?;
+
+        let mut line = vec![
+            Span::raw(item.to_string()),
+            Span::styled(
+                format!(" ({field}: "),
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ];
+        line.extend(highlight_matches(&value, &matched_indices));
+        line.push(Span::styled(
+            ")",
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+
+        Some(self.0.decorate(item, Line::from(line)))
+    }
 }
 
 pub struct SearchWidget<T, U>
@@ -45,17 +128,13 @@ where
     T: ToString + Clone,
     U: SearchItemGetter<T>,
 {
-    config: SharedCompleteConfig,
     focused: bool,
 
     item_getter: U,
     items: Result<Vec<T>>,
-    filtered_items: Option<Vec<T>>,
 
-    list_state: ListState,
+    list: SearchableList<T>,
     search_input: InputWidget<()>,
-    vertical_scroll_state: ScrollbarState,
-    vertical_scroll: usize,
 
     error_widget: ErrorWidget,
 }
@@ -74,58 +153,15 @@ where
         let error_widget = ErrorWidget::new(error_message);
 
         Self {
-            config,
             focused: false,
             item_getter,
             items: Ok(vec![]),
-            filtered_items: None,
-            list_state: ListState::default(),
+            list: SearchableList::new(config, Widget::Following),
             search_input,
-            vertical_scroll_state: ScrollbarState::default(),
-            vertical_scroll: 0,
             error_widget,
         }
     }
 
-    fn next(&mut self) {
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if let Some(filtered) = &self.filtered_items {
-                    if i >= filtered.len().saturating_sub(1) {
-                        filtered.len().saturating_sub(1)
-                    } else {
-                        i + 1
-                    }
-                } else if i >= self.items.as_ref().unwrap().len().saturating_sub(1) {
-                    self.items.as_ref().unwrap().len().saturating_sub(1)
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
-        };
-
-        self.list_state.select(Some(i));
-
-        self.vertical_scroll = self.vertical_scroll.saturating_add(1);
-        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
-    }
-
-    fn previous(&mut self) {
-        let i = self
-            .list_state
-            .selected()
-            .map_or(0, |i| if i == 0 { 0 } else { i - 1 });
-        self.list_state.select(Some(i));
-
-        self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
-        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
-    }
-
-    fn unselect(&mut self) {
-        self.list_state.select(None);
-    }
-
     pub const fn is_focused(&self) -> bool {
         self.focused
     }
@@ -141,6 +177,47 @@ where
 
         self.focused = !self.focused;
     }
+
+    /// Get the next page of items when the selection gets close to the last item shown,
+    /// which is checked after anything that could have moved the selection or filtered the items.
+    async fn load_more(&mut self) {
+        if self.items.is_err() || self.item_getter.total().is_none() {
+            return;
+        }
+
+        // A search that found nothing might find something in the items not gotten yet
+        let near_end = self.list.selected_index().map_or_else(
+            || !self.search_input.to_string().is_empty(),
+            |i| i + LOAD_AHEAD >= self.list.rows().len(),
+        );
+
+        if !near_end {
+            return;
+        }
+
+        match self.item_getter.more_items().await {
+            Ok(more) => {
+                if let Ok(items) = &mut self.items {
+                    items.extend(more);
+                }
+            }
+            Err(err) => debug!("Unable to get more items: {err}"),
+        }
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        self.list.selected()
+    }
+
+    async fn join_selected(&mut self) -> Option<TerminalAction> {
+        let selected_channel = self.list.selected()?.to_string().to_lowercase();
+
+        self.toggle_focus().await;
+
+        self.list.unselect();
+
+        Some(TerminalAction::Enter(TwitchAction::Join(selected_channel)))
+    }
 }
 
 impl<T, U> Component for SearchWidget<T, U>
@@ -157,110 +234,61 @@ where
             return;
         }
 
-        let mut items = vec![];
-        let current_items = &self.items.as_ref().map_or(vec![], Clone::clone);
-        let current_input = self.search_input.to_string();
-
-        if current_input.is_empty() {
-            for item in current_items {
-                items.push(ListItem::new(item.to_string()));
-            }
+        self.item_getter.refresh();
 
-            self.filtered_items = None;
-        } else {
-            let item_filter = |c: String| -> Vec<usize> {
-                FUZZY_FINDER
-                    .fuzzy_indices(&c, &current_input)
-                    .map(|(_, indices)| indices)
-                    .unwrap_or_default()
-            };
+        let pinned = self.item_getter.pinned();
+        let pinned_names = pinned
+            .iter()
+            .map(|item| item.to_string().to_lowercase())
+            .collect::<HashSet<String>>();
 
-            let mut matched = vec![];
+        let mut items = self
+            .items
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter(|item| !pinned_names.contains(&item.to_string().to_lowercase()))
+            .cloned()
+            .collect::<Vec<T>>();
+        self.item_getter.sort(&mut items);
 
-            for item in current_items.clone() {
-                let matched_indices = item_filter(item.to_string());
+        let items = [pinned, items].concat();
 
-                if matched_indices.is_empty() {
-                    continue;
-                }
+        let search = self.search_input.to_string();
 
-                let line = item
-                    .to_string()
-                    .chars()
-                    .enumerate()
-                    .map(|(i, c)| {
-                        if matched_indices.contains(&i) {
-                            Span::styled(c.to_string(), *SEARCH_STYLE)
-                        } else {
-                            Span::raw(c.to_string())
-                        }
-                    })
-                    .collect::<Vec<Span>>();
-
-                items.push(ListItem::new(vec![Line::from(line)]));
-                matched.push(item);
-            }
+        let items = self
+            .list
+            .filter(&items, &search, &DetailsMatcher(&self.item_getter));
 
-            self.filtered_items = Some(matched);
+        // Without a search, every item is counted, including the ones that have not been gotten yet
+        if let Some(total) = self.item_getter.total().filter(|_| search.is_empty()) {
+            self.list.set_total(total);
         }
 
-        let title_binding = [TitleStyle::Single("Following")];
-
-        let list = List::new(items.clone())
-            .block(
-                Block::default()
-                    .title(title_line(&title_binding, *TITLE_STYLE))
-                    .borders(Borders::ALL)
-                    .border_type(self.config.borrow().frontend.border_type.clone().into()),
-            )
-            .highlight_style(if *NO_COLOR {
-                Style::default()
-            } else {
-                Style::default()
-                    .bg(Color::LightGreen)
-                    .add_modifier(Modifier::BOLD)
-            });
-
-        f.render_widget(Clear, r);
-        f.render_stateful_widget(list, r, &mut self.list_state);
-
-        self.vertical_scroll_state = self.vertical_scroll_state.content_length(items.len());
-
-        f.render_stateful_widget(
-            Scrollbar::default()
-                .orientation(ScrollbarOrientation::VerticalRight)
-                .symbols(scrollbar::VERTICAL)
-                .begin_symbol(None)
-                .end_symbol(None),
-            r.inner(Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut self.vertical_scroll_state,
-        );
+        let title_details = self.item_getter.title();
 
-        let title_binding = format!(
-            "{} / {}",
-            self.list_state.selected().map_or(1, |i| i + 1),
-            self.filtered_items
-                .as_ref()
-                .map_or(current_items.len(), Vec::len)
-        );
+        let mut title_binding = vec![TitleStyle::Single("Following")];
 
-        let title = [TitleStyle::Single(&title_binding)];
+        if self.item_getter.is_stale() {
+            title_binding.push(TitleStyle::Single("Stale"));
+        }
 
-        let bottom_block = Block::default()
-            .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
-            .border_type(self.config.borrow().frontend.border_type.clone().into())
-            .title(title_line(&title, Style::default()))
-            .title_position(Position::Bottom)
-            .title_alignment(Alignment::Right);
+        // Searches and orders only cover the items gotten so far
+        if self.item_getter.total().is_some()
+            && (!search.is_empty() || self.item_getter.is_sorted())
+        {
+            title_binding.push(TitleStyle::Single("Partial"));
+        }
 
-        let rect = Rect::new(r.x, r.bottom() - 1, r.width, 1);
+        title_binding.extend(
+            title_details
+                .iter()
+                .map(|(key, value)| TitleStyle::Combined(key, value)),
+        );
 
-        f.render_widget(bottom_block, rect);
+        self.list.draw(f, r, items, &title_binding);
 
-        let input_rect = Rect::new(rect.x, rect.bottom(), rect.width, 3);
+        let input_rect = Rect::new(r.x, r.bottom(), r.width, 3);
 
         self.search_input.draw(f, Some(input_rect));
     }
@@ -273,50 +301,42 @@ where
             return None;
         }
 
+        // Clicking on a channel joins it, like selecting it and pressing enter
+        if let Event::Mouse(MouseAction::Click, position) = event {
+            if self.list.click(*position) {
+                return self.join_selected().await;
+            }
+        }
+
         if let Event::Input(key) = event {
             match key {
                 Key::Esc => {
-                    if self.list_state.selected().is_some() {
-                        self.unselect();
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
                     } else {
                         self.toggle_focus().await;
                     }
                 }
-                Key::ScrollDown | Key::Down => self.next(),
-                Key::ScrollUp | Key::Up => self.previous(),
-                Key::Enter => {
-                    if let Some(i) = self.list_state.selected() {
-                        let selected_channel = if let Some(v) = self.filtered_items.clone() {
-                            if v.is_empty() {
-                                return None;
-                            }
-
-                            v.get(i).unwrap().to_string()
-                        } else {
-                            self.items.as_ref().unwrap().get(i).unwrap().to_string()
-                        }
-                        .to_lowercase();
-
-                        self.toggle_focus().await;
-
-                        self.unselect();
-
-                        return Some(TerminalAction::Enter(TwitchAction::Join(selected_channel)));
-                    }
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
                 }
+                Key::Enter => return self.join_selected().await,
                 _ => {
                     self.search_input.event(event).await;
 
                     // Assuming that the user inputted something that modified the input
-                    if let Some(v) = &self.filtered_items {
-                        if !v.is_empty() {
-                            self.list_state.select(Some(0));
-                        }
-                    }
+                    self.list.select_first();
                 }
             }
         }
 
+        if self.focused {
+            self.load_more().await;
+        }
+
         None
     }
 }
@@ -0,0 +1,372 @@
+use tui::{
+    Frame,
+    layout::{Position, Rect},
+    prelude::{Alignment, Margin},
+    style::{Color, Modifier, Style},
+    symbols::scrollbar,
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Table, TableState, block,
+    },
+};
+
+use crate::{
+    handlers::{
+        config::{Matching, SharedCompleteConfig, Widget},
+        favorites::is_favorite,
+    },
+    utils::{
+        matching::match_indices,
+        styles::{FAVORITE_STYLE, NO_COLOR, border_style, search_style, title_style},
+        text::{TitleStyle, title_line},
+    },
+};
+
+/// Style the characters of some text that were matched by a search.
+pub fn highlight_matches(text: &str, matched_indices: &[usize]) -> Vec<Span<'static>> {
+    text.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if matched_indices.contains(&i) {
+                Span::styled(c.to_string(), search_style())
+            } else {
+                Span::raw(c.to_string())
+            }
+        })
+        .collect()
+}
+
+/// Decides which rows of a list are found by a search, and how they are shown when they are.
+pub trait RowMatcher<T> {
+    /// The row with what was matched highlighted, or `None` if the search does not match it.
+    /// Every row is matched by an empty search.
+    fn matches(&self, row: &T, search: &str, matching: Matching) -> Option<Line<'static>>;
+}
+
+/// Matches rows by their name.
+pub struct NameMatcher;
+
+impl<T: ToString> RowMatcher<T> for NameMatcher {
+    fn matches(&self, row: &T, search: &str, matching: Matching) -> Option<Line<'static>> {
+        let name = row.to_string();
+
+        if search.is_empty() {
+            return Some(Line::from(name));
+        }
+
+        let (_, matched_indices) = match_indices(matching, &name, search)?;
+
+        Some(Line::from(highlight_matches(&name, &matched_indices)))
+    }
+}
+
+/// A row with a star in front of it if it is a favorite channel,
+/// or with space for the star otherwise so that the rows line up.
+pub fn mark_favorite(line: Line<'static>, favorite: bool) -> Line<'static> {
+    if favorite {
+        Line::from([vec![Span::styled("★ ", *FAVORITE_STYLE)], line.spans].concat())
+            .patch_style(*FAVORITE_STYLE)
+    } else {
+        Line::from([vec![Span::raw("  ")], line.spans].concat())
+    }
+}
+
+/// Matches channels by their name, marking the ones that are favorites.
+pub struct FavoriteMatcher<'a>(pub &'a [String]);
+
+impl RowMatcher<String> for FavoriteMatcher<'_> {
+    fn matches(&self, row: &String, search: &str, matching: Matching) -> Option<Line<'static>> {
+        let line = NameMatcher.matches(row, search, matching)?;
+
+        Some(mark_favorite(line, is_favorite(self.0, row)))
+    }
+}
+
+/// A scrollable list with a selection, of rows that can be filtered by a search.
+/// Pickers own their search input and rows, and leave the rest of the list to this.
+/// Rows are drawn as a list of names, or as a table for rows with more to them.
+pub struct SearchableList<T> {
+    config: SharedCompleteConfig,
+    widget: Widget,
+    /// If the rows can be searched, to show how they are matched.
+    searchable: bool,
+    /// The rows being shown, in the order they are shown.
+    rows: Vec<T>,
+    /// How many rows could be shown, which can be more than are loaded.
+    total: usize,
+    list_state: ListState,
+    vertical_scroll_state: ScrollbarState,
+    vertical_scroll: usize,
+    /// Where the rows were last drawn, to find which one was clicked.
+    rows_area: Rect,
+}
+
+impl<T: Clone> SearchableList<T> {
+    pub fn new(config: SharedCompleteConfig, widget: Widget) -> Self {
+        Self {
+            config,
+            widget,
+            searchable: true,
+            rows: vec![],
+            total: 0,
+            list_state: ListState::default(),
+            vertical_scroll_state: ScrollbarState::default(),
+            vertical_scroll: 0,
+            rows_area: Rect::default(),
+        }
+    }
+
+    /// For lists that show every row, without a search.
+    pub const fn without_search(mut self) -> Self {
+        self.searchable = false;
+
+        self
+    }
+
+    /// Show the rows matched by a search, or all of them if there is nothing being searched for.
+    pub fn filter<M: RowMatcher<T>>(
+        &mut self,
+        rows: &[T],
+        search: &str,
+        matcher: &M,
+    ) -> Vec<ListItem<'static>> {
+        let matching = self.matching();
+
+        let (rows, items): (Vec<T>, Vec<ListItem>) = rows
+            .iter()
+            .filter_map(|row| {
+                matcher
+                    .matches(row, search, matching)
+                    .map(|line| (row.clone(), ListItem::new(line)))
+            })
+            .unzip();
+
+        self.set_rows(rows, None);
+
+        items
+    }
+
+    /// Show rows that were filtered by the picker itself,
+    /// with the total of them if only some are loaded at a time.
+    /// The selection is kept within the rows, as rows can leave the list while it is shown.
+    pub fn set_rows(&mut self, rows: Vec<T>, total: Option<usize>) {
+        self.total = total.unwrap_or(rows.len());
+        self.rows = rows;
+
+        if let Some(i) = self.list_state.selected() {
+            if self.rows.is_empty() {
+                self.unselect();
+            } else if i >= self.rows.len() {
+                self.list_state.select(Some(self.rows.len() - 1));
+            }
+        }
+    }
+
+    /// Count more rows than the ones shown, for rows that are loaded a page at a time.
+    pub const fn set_total(&mut self, total: usize) {
+        self.total = total;
+    }
+
+    /// How rows are matched by what is typed, which is shared by every list.
+    pub fn matching(&self) -> Matching {
+        self.config.borrow().frontend.matching
+    }
+
+    /// Switch to the next way of matching rows, for every list.
+    pub fn cycle_matching(&self) {
+        let mut config = self.config.borrow_mut();
+
+        config.frontend.matching = config.frontend.matching.next();
+    }
+
+    /// The rows being shown, in the order they are shown.
+    pub fn rows(&self) -> &[T] {
+        &self.rows
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        self.rows.get(self.list_state.selected()?)
+    }
+
+    pub const fn selected_index(&self) -> Option<usize> {
+        self.list_state.selected()
+    }
+
+    pub fn next(&mut self) {
+        let i = self
+            .list_state
+            .selected()
+            .map_or(0, |i| (i + 1).min(self.rows.len().saturating_sub(1)));
+
+        self.list_state.select(Some(i));
+
+        self.vertical_scroll = self.vertical_scroll.saturating_add(1);
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+    }
+
+    pub fn previous(&mut self) {
+        let i = self
+            .list_state
+            .selected()
+            .map_or(0, |i| i.saturating_sub(1));
+
+        self.list_state.select(Some(i));
+
+        self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
+        self.vertical_scroll_state = self.vertical_scroll_state.position(self.vertical_scroll);
+    }
+
+    /// Select the row that was clicked, if there is one under the mouse.
+    pub fn click(&mut self, position: Position) -> bool {
+        if !self.rows_area.contains(position) {
+            return false;
+        }
+
+        let i = self.list_state.offset() + usize::from(position.y - self.rows_area.y);
+
+        if i >= self.rows.len() {
+            return false;
+        }
+
+        self.list_state.select(Some(i));
+
+        true
+    }
+
+    pub fn unselect(&mut self) {
+        self.list_state.select(None);
+    }
+
+    /// Select the best match after the search changed, if there is one.
+    pub fn select_first(&mut self) {
+        if self.rows.is_empty() {
+            self.unselect();
+        } else {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    /// Select the last row, such as one that was just added.
+    pub fn select_last(&mut self) {
+        self.list_state.select(self.rows.len().checked_sub(1));
+    }
+
+    pub fn clear(&mut self) {
+        self.unselect();
+        self.rows.clear();
+        self.total = 0;
+    }
+
+    /// The title of the list, along with how rows are matched if they can be searched.
+    fn title<'a>(&self, title: &[TitleStyle<'a>], matching: &'a str) -> Vec<TitleStyle<'a>> {
+        let mut title = title.to_vec();
+
+        if self.searchable {
+            title.push(TitleStyle::Combined("Alt + m", matching));
+        }
+
+        title
+    }
+
+    fn highlight_style() -> Style {
+        if *NO_COLOR {
+            Style::default()
+        } else {
+            Style::default()
+                .bg(Color::LightGreen)
+                .add_modifier(Modifier::BOLD)
+        }
+    }
+
+    /// Draw the list with a scrollbar, and the position of the selection along the bottom.
+    /// The row at the bottom of the area is left for the search input to be drawn under.
+    pub fn draw(
+        &mut self,
+        f: &mut Frame,
+        r: Rect,
+        items: Vec<ListItem<'static>>,
+        title: &[TitleStyle],
+    ) {
+        let matching = self.matching().to_string();
+        let title = self.title(title, &matching);
+
+        let list = List::new(items)
+            .block(
+                self.config
+                    .borrow()
+                    .block(self.widget)
+                    .title(title_line(&title, title_style())),
+            )
+            .highlight_style(Self::highlight_style());
+
+        f.render_widget(Clear, r);
+        f.render_stateful_widget(list, r, &mut self.list_state);
+
+        self.draw_position(f, r);
+    }
+
+    /// Draw the rows as a table, for rows with more than a name to them, which are given in the same order.
+    pub fn draw_table(&mut self, f: &mut Frame, r: Rect, table: Table, title: &[TitleStyle]) {
+        let matching = self.matching().to_string();
+        let title = self.title(title, &matching);
+
+        let table = table
+            .block(
+                self.config
+                    .borrow()
+                    .block(self.widget)
+                    .title(title_line(&title, title_style())),
+            )
+            .row_highlight_style(Self::highlight_style());
+
+        let mut table_state = TableState::default()
+            .with_selected(self.list_state.selected())
+            .with_offset(self.list_state.offset());
+
+        f.render_widget(Clear, r);
+        f.render_stateful_widget(table, r, &mut table_state);
+
+        *self.list_state.offset_mut() = table_state.offset();
+
+        self.draw_position(f, r);
+    }
+
+    /// Draw the scrollbar, and the position of the selection along the bottom.
+    fn draw_position(&mut self, f: &mut Frame, r: Rect) {
+        self.rows_area = r.inner(Margin::new(1, 1));
+
+        self.vertical_scroll_state = self.vertical_scroll_state.content_length(self.total);
+
+        f.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .symbols(scrollbar::VERTICAL)
+                .begin_symbol(None)
+                .end_symbol(None),
+            r.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut self.vertical_scroll_state,
+        );
+
+        let position = format!(
+            "{} / {}",
+            self.list_state.selected().map_or(1, |i| i + 1),
+            self.total
+        );
+
+        let position = [TitleStyle::Single(&position)];
+
+        let bottom_block = Block::default()
+            .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
+            .border_type(self.config.borrow().border_type(self.widget))
+            .border_style(border_style())
+            .title(title_line(&position, Style::default()))
+            .title_position(block::Position::Bottom)
+            .title_alignment(Alignment::Right);
+
+        f.render_widget(bottom_block, Rect::new(r.x, r.bottom() - 1, r.width, 1));
+    }
+}
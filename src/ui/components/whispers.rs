@@ -0,0 +1,138 @@
+use tui::{
+    Frame,
+    layout::{Constraint, Rect},
+    widgets::{Cell, Row, Table},
+};
+
+use crate::{
+    handlers::{
+        app::SharedWhispers,
+        config::{SharedCompleteConfig, Widget},
+        user_input::events::{Event, Key},
+    },
+    terminal::TerminalAction,
+    twitch::whispers::Whisper,
+    ui::components::{
+        Component,
+        utils::{InputWidget, SearchableList, centered_rect, split_input},
+    },
+    utils::{text::TitleStyle, time::format_time},
+};
+
+pub struct WhispersWidget {
+    config: SharedCompleteConfig,
+    whispers: SharedWhispers,
+    focused: bool,
+    search_input: InputWidget<()>,
+    list: SearchableList<Whisper>,
+}
+
+impl WhispersWidget {
+    pub fn new(config: SharedCompleteConfig, whispers: SharedWhispers) -> Self {
+        let search_input = InputWidget::new(config.clone(), "Filter", None, None, None);
+        let list = SearchableList::new(config.clone(), Widget::Popup);
+
+        Self {
+            config,
+            whispers,
+            focused: false,
+            search_input,
+            list,
+        }
+    }
+
+    pub const fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focused = !self.focused;
+
+        if !self.focused {
+            self.search_input.clear();
+            self.list.clear();
+        }
+    }
+}
+
+impl Component for WhispersWidget {
+    fn draw(&mut self, f: &mut Frame, area: Option<Rect>) {
+        let [r, input_rect] =
+            split_input(area.map_or_else(|| centered_rect(80, 60, 23, f.area()), |a| a));
+
+        let query = self.search_input.to_string();
+        let matching = self.list.matching();
+
+        // Most recent whispers first
+        self.list.set_rows(
+            self.whispers
+                .borrow()
+                .iter()
+                .rev()
+                .filter(|w| w.matches(&query, matching))
+                .cloned()
+                .collect(),
+            None,
+        );
+
+        let config = self.config.borrow();
+
+        let rows = self
+            .list
+            .rows()
+            .iter()
+            .map(|w| {
+                let direction = if w.outgoing { "to" } else { "from" };
+
+                Row::new(vec![
+                    Cell::from(format_time(&w.time_sent, &config.frontend)),
+                    Cell::from(format!("{direction} {}", w.user)),
+                    Cell::from(w.message.clone()),
+                ])
+            })
+            .collect::<Vec<Row>>();
+
+        drop(config);
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Length(30),
+                Constraint::Min(20),
+            ],
+        );
+
+        self.list
+            .draw_table(f, r, table, &[TitleStyle::Single("Whispers")]);
+
+        self.search_input.draw(f, Some(input_rect));
+    }
+
+    async fn event(&mut self, event: &Event) -> Option<TerminalAction> {
+        if let Event::Input(key) = event {
+            match key {
+                Key::Esc => {
+                    if self.list.selected_index().is_some() {
+                        self.list.unselect();
+                    } else {
+                        self.toggle_focus();
+                    }
+                }
+                Key::Ctrl('p') => panic!("Manual panic triggered by user."),
+                Key::ScrollDown | Key::Down => self.list.next(),
+                Key::ScrollUp | Key::Up => self.list.previous(),
+                Key::Alt('m') => {
+                    self.list.cycle_matching();
+                    self.list.select_first();
+                }
+                _ => {
+                    self.search_input.event(event).await;
+                    self.list.select_first();
+                }
+            }
+        }
+
+        None
+    }
+}
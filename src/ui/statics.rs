@@ -4,6 +4,7 @@ pub static HELP_COLUMN_TITLES: Lazy<Vec<&str>> =
     Lazy::new(|| vec!["State", "Keybind", "Description"]);
 
 // TODO: Make this type have less complexity
+/// Keys that cannot be rebound, shown after the actions of each section that can be.
 #[allow(clippy::type_complexity)]
 pub static HELP_KEYBINDS: Lazy<Vec<(&str, Vec<(&str, &str)>)>> = Lazy::new(|| {
     vec![
@@ -14,35 +15,54 @@ pub static HELP_KEYBINDS: Lazy<Vec<(&str, Vec<(&str, &str)>)>> = Lazy::new(|| {
                     "Enter",
                     "Enter the channel that you've configured in the config",
                 ),
-                ("? or h", "Have the keybinds popup window appear"),
-                ("q", "Quit the application"),
-                ("s", "Open the recent channel search popup"),
-                ("f", "Open the followed channel search popup"),
                 ("Ctrl + p", "Manually crash the application"),
             ],
         ),
         (
             "Normal mode",
             vec![
-                ("i or c", "Enter message (chat) mode for sending messages"),
-                ("@", "Messaging mode with mention symbol"),
-                ("/", "Messaging mode with command symbol"),
-                ("? or h", "* You are here!"),
-                ("q", "Quit the application"),
-                ("s", "Open the recent channel search widget"),
-                ("f", "Open the followed channel search widget"),
-                ("S", "Go to the dashboard screen (start screen)"),
-                ("Ctrl + f", "Search messages"),
-                ("Ctrl + t", "Toggle the message filter"),
-                ("Ctrl + r", "Reverse the message filter"),
                 ("Ctrl + p", "Manually crash the application"),
                 ("Esc", "Go back to the previous window"),
+                ("n or N", "Select the next older or newer search match"),
+            ],
+        ),
+        (
+            "Selection mode",
+            vec![
+                ("k or Up", "Select an older message"),
+                ("j or Down", "Select a newer message"),
+                (
+                    "Ctrl + u/d",
+                    "Move the selection by half of the messages shown",
+                ),
+                ("gg or G", "Select the oldest or newest message"),
+                ("n or N", "Select the next older or newer search match"),
+                ("1-9", "Type a count to repeat the next motion or action"),
+                ("Enter or r", "Reply to the selected message"),
+                ("t", "Collapse or expand the reply thread"),
+                ("e", "Expand or collapse a long message"),
+                ("l", "List the links in the message, to open one"),
+                ("d", "Delete the message, as a moderator"),
+                ("b", "Time out or ban the author, by the count typed before"),
+                ("Esc", "Stop selecting messages"),
             ],
         ),
         (
             "Insert modes",
             vec![
-                ("Tab", "Fill in suggestion, if available"),
+                (
+                    "Tab",
+                    "Fill in suggestion, or cycle through matching emotes, chatters, and commands",
+                ),
+                (
+                    "Shift + Tab",
+                    "Cycle back through matching emotes, chatters, and commands",
+                ),
+                (
+                    "Up or Down",
+                    "Recall sent messages starting with what was typed",
+                ),
+                ("Ctrl + j", "Start a new line, sent as its own message"),
                 ("Enter", "Confirm the input text to go through"),
                 ("Esc", "Go back to the previous window"),
                 ("Ctrl + f", "Move cursor to the right"),
@@ -60,49 +80,262 @@ pub static HELP_KEYBINDS: Lazy<Vec<(&str, Vec<(&str, &str)>)>> = Lazy::new(|| {
                 ("Alt + b", "Move to the start of the previous word"),
                 ("Alt + t", "Swap previous word with current word"),
                 ("Alt + e", "Toggle emote picker"),
+                ("Alt + u", "Toggle emoji picker"),
+                ("Alt + c", "Toggle case sensitivity of message search"),
+                (
+                    "Alt + m",
+                    "Search lists by fuzzy, substring, prefix, or regex matching",
+                ),
+                (
+                    "Alt + s",
+                    "Sort followed channels by follows, viewers, name, or recently watched",
+                ),
+                (
+                    "Alt + p",
+                    "Mark or unmark the selected channel as a favorite",
+                ),
             ],
         ),
     ]
 });
 
+/// A chat command, shown when completing commands and in the keybinds window.
+pub struct Command {
+    pub name: &'static str,
+    /// What comes after the name of the command, with optional arguments in brackets.
+    pub arguments: &'static str,
+    pub description: &'static str,
+}
+
+impl Command {
+    /// How the command is used, such as `/ban <user> [reason]`.
+    pub fn usage(&self) -> String {
+        if self.arguments.is_empty() {
+            format!("/{}", self.name)
+        } else {
+            format!("/{} {}", self.name, self.arguments)
+        }
+    }
+}
+
 // https://help.twitch.tv/s/article/chat-commands?language=en_US
-pub static COMMANDS: Lazy<Vec<&str>> = Lazy::new(|| {
-    vec![
-        "ban",
-        "unban",
-        "clear",
-        "color",
-        "commercial",
-        "delete",
-        "disconnect",
-        "emoteonly",
-        "emoteonlyoff",
-        "followers",
-        "followersoff",
-        "help",
-        "host",
-        "unhost",
-        "marker",
-        "me",
-        "mod",
-        "unmod",
-        "mods",
-        "r9kbeta",
-        "r9kbetaoff",
-        "raid",
-        "unraid",
-        "slow",
-        "slowoff",
-        "subscribers",
-        "subscribersoff",
-        "timeout",
-        "untimeout",
-        "vip",
-        "unvip",
-        "vips",
-        "w",
-    ]
-});
+pub static COMMANDS: &[Command] = &[
+    Command {
+        name: "ban",
+        arguments: "<user> [reason]",
+        description: "Permanently ban a user from chat",
+    },
+    Command {
+        name: "unban",
+        arguments: "<user>",
+        description: "Remove a ban or timeout from a user",
+    },
+    Command {
+        name: "clear",
+        arguments: "",
+        description: "Clear the chat for everyone, as a moderator",
+    },
+    Command {
+        name: "color",
+        arguments: "<color>",
+        description: "Change the color of your name",
+    },
+    Command {
+        name: "commercial",
+        arguments: "[length]",
+        description: "Run a commercial on your channel",
+    },
+    Command {
+        name: "delete",
+        arguments: "<message id>",
+        description: "Delete a message, as a moderator",
+    },
+    Command {
+        name: "disconnect",
+        arguments: "",
+        description: "Disconnect from chat",
+    },
+    Command {
+        name: "dryrun",
+        arguments: "",
+        description: "Toggle only showing what moderation commands would do",
+    },
+    Command {
+        name: "emoteonly",
+        arguments: "",
+        description: "Only allow emotes in chat",
+    },
+    Command {
+        name: "emoteonlyoff",
+        arguments: "",
+        description: "Allow more than emotes in chat",
+    },
+    Command {
+        name: "export",
+        arguments: "[path]",
+        description: "Export the messages of this channel to a file",
+    },
+    Command {
+        name: "followers",
+        arguments: "[duration]",
+        description: "Only allow followers to chat, optionally followed for a while",
+    },
+    Command {
+        name: "followersoff",
+        arguments: "",
+        description: "Allow everyone to chat",
+    },
+    Command {
+        name: "game",
+        arguments: "<category name>",
+        description: "Change the category of your channel",
+    },
+    Command {
+        name: "help",
+        arguments: "",
+        description: "List the commands Twitch knows about",
+    },
+    Command {
+        name: "ignore",
+        arguments: "<user>",
+        description: "Leave the messages of a user out of chat",
+    },
+    Command {
+        name: "unignore",
+        arguments: "<user>",
+        description: "Show the messages of an ignored user again",
+    },
+    Command {
+        name: "host",
+        arguments: "<channel>",
+        description: "Host a channel",
+    },
+    Command {
+        name: "unhost",
+        arguments: "",
+        description: "Stop hosting a channel",
+    },
+    Command {
+        name: "marker",
+        arguments: "[description]",
+        description: "Add a stream marker",
+    },
+    Command {
+        name: "me",
+        arguments: "<message>",
+        description: "Send a message in the color of your name",
+    },
+    Command {
+        name: "mod",
+        arguments: "<user>",
+        description: "Make a user a moderator",
+    },
+    Command {
+        name: "unmod",
+        arguments: "<user>",
+        description: "Remove a user as a moderator",
+    },
+    Command {
+        name: "mods",
+        arguments: "",
+        description: "List the moderators of this channel",
+    },
+    Command {
+        name: "nick",
+        arguments: "<user> [nickname]",
+        description: "Give a user a nickname, or remove it without one",
+    },
+    Command {
+        name: "poll",
+        arguments: "<duration> <title> | <choice> | <choice>",
+        description: "Start a poll, or end it with /poll end",
+    },
+    Command {
+        name: "prediction",
+        arguments: "<duration> <title> | <outcome> | <outcome>",
+        description: "Start a prediction, or lock, resolve, or cancel it",
+    },
+    Command {
+        name: "r9kbeta",
+        arguments: "",
+        description: "Only allow unique messages in chat",
+    },
+    Command {
+        name: "r9kbetaoff",
+        arguments: "",
+        description: "Allow repeated messages in chat",
+    },
+    Command {
+        name: "raid",
+        arguments: "<channel>",
+        description: "Raid a channel",
+    },
+    Command {
+        name: "unraid",
+        arguments: "",
+        description: "Cancel a raid",
+    },
+    Command {
+        name: "slow",
+        arguments: "[seconds]",
+        description: "Limit how often users can chat",
+    },
+    Command {
+        name: "slowoff",
+        arguments: "",
+        description: "Stop limiting how often users can chat",
+    },
+    Command {
+        name: "subscribers",
+        arguments: "",
+        description: "Only allow subscribers to chat",
+    },
+    Command {
+        name: "subscribersoff",
+        arguments: "",
+        description: "Allow everyone to chat",
+    },
+    Command {
+        name: "timeout",
+        arguments: "<user> [duration] [reason]",
+        description: "Temporarily ban a user from chat",
+    },
+    Command {
+        name: "title",
+        arguments: "<text>",
+        description: "Change the title of your stream",
+    },
+    Command {
+        name: "untimeout",
+        arguments: "<user>",
+        description: "Remove a timeout from a user",
+    },
+    Command {
+        name: "vip",
+        arguments: "<user>",
+        description: "Make a user a VIP",
+    },
+    Command {
+        name: "unvip",
+        arguments: "<user>",
+        description: "Remove a user as a VIP",
+    },
+    Command {
+        name: "vips",
+        arguments: "",
+        description: "List the VIPs of this channel",
+    },
+    Command {
+        name: "w",
+        arguments: "<user> <message>",
+        description: "Whisper a user",
+    },
+];
+
+/// The command with a name, if there is one.
+pub fn find_command(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|command| command.name == name)
+}
 
 pub static LINE_BUFFER_CAPACITY: usize = 4096;
 
@@ -0,0 +1,98 @@
+use std::env;
+
+use chrono::DateTime;
+
+use crate::{
+    emotes::graphics_protocol_detected,
+    utils::{
+        links::hyperlinks_supported,
+        pathing::{cache_path, config_path},
+        styles::NO_COLOR,
+    },
+};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The commit the binary was built from, as described by `git describe`.
+const COMMIT: &str = env!("TWT_GIT_DESCRIBE");
+/// When the binary was built, in seconds since the Unix epoch.
+const BUILD_TIMESTAMP: &str = env!("TWT_BUILD_TIMESTAMP");
+/// The Cargo features the binary was built with, or `default` if there were none.
+const FEATURES: &str = env!("TWT_FEATURES");
+
+fn build_date() -> String {
+    BUILD_TIMESTAMP
+        .parse::<i64>()
+        .ok()
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map_or_else(
+            || "unknown".to_string(),
+            |date| date.format("%Y-%m-%d %H:%M UTC").to_string(),
+        )
+}
+
+fn graphics_protocol() -> String {
+    match graphics_protocol_detected() {
+        Some(true) => "supported".to_string(),
+        Some(false) => "not supported".to_string(),
+        None => "not checked, as emotes are disabled".to_string(),
+    }
+}
+
+fn colors() -> String {
+    if *NO_COLOR {
+        return "disabled by NO_COLOR".to_string();
+    }
+
+    match env::var("COLORTERM") {
+        Ok(colorterm) if colorterm == "truecolor" || colorterm == "24bit" => {
+            "true color".to_string()
+        }
+        _ => "256 colors or fewer".to_string(),
+    }
+}
+
+/// The first line of `--version`.
+pub fn version_line() -> String {
+    format!("{} {VERSION} ({COMMIT})", env!("CARGO_PKG_NAME"))
+}
+
+/// What is useful to know about this build and the terminal it runs in, for bug reports.
+pub fn about() -> Vec<(&'static str, String)> {
+    vec![
+        ("Version", VERSION.to_string()),
+        ("Commit", COMMIT.to_string()),
+        ("Build date", build_date()),
+        (
+            "Build",
+            format!(
+                "{} {} ({})",
+                env::consts::OS,
+                env::consts::ARCH,
+                if cfg!(debug_assertions) {
+                    "debug"
+                } else {
+                    "release"
+                }
+            ),
+        ),
+        ("Features", FEATURES.to_string()),
+        (
+            "Terminal",
+            env::var("TERM_PROGRAM")
+                .or_else(|_| env::var("TERM"))
+                .unwrap_or_else(|_| "unknown".to_string()),
+        ),
+        ("Colors", colors()),
+        ("Graphics protocol", graphics_protocol()),
+        (
+            "Hyperlinks",
+            if hyperlinks_supported() {
+                "supported".to_string()
+            } else {
+                "not detected".to_string()
+            },
+        ),
+        ("Config", config_path("config.toml")),
+        ("Cache", cache_path("")),
+    ]
+}
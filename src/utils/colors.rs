@@ -37,6 +37,70 @@ pub const fn u32_to_color(value: u32) -> Color {
     Rgb((value >> 16) as u8, (value >> 8) as u8, value as u8)
 }
 
+/// How many steps a color is mixed towards white or black at most, to be readable.
+const CONTRAST_STEPS: u8 = 20;
+
+/// <https://www.w3.org/TR/WCAG21/#dfn-relative-luminance>
+fn relative_luminance([red, green, blue]: [u8; 3]) -> f64 {
+    let linear = |channel: u8| {
+        let channel = f64::from(channel) / 255.;
+
+        if channel <= 0.039_28 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    0.0722f64.mul_add(
+        linear(blue),
+        0.2126f64.mul_add(linear(red), 0.7152 * linear(green)),
+    )
+}
+
+/// <https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio>, from 1 for the same colors to 21 for black and white.
+pub fn contrast_ratio(first: [u8; 3], second: [u8; 3]) -> f64 {
+    let first = relative_luminance(first);
+    let second = relative_luminance(second);
+
+    (first.max(second) + 0.05) / (first.min(second) + 0.05)
+}
+
+fn mix(from: [u8; 3], to: [u8; 3], amount: f64) -> [u8; 3] {
+    let channel = |i: usize| {
+        (f64::from(to[i]) - f64::from(from[i]))
+            .mul_add(amount, f64::from(from[i]))
+            .round() as u8
+    };
+
+    [channel(0), channel(1), channel(2)]
+}
+
+/// Make a color readable against a background, by mixing it with white on dark backgrounds
+/// and with black on light ones, only as much as is needed to reach the minimum contrast ratio.
+/// Colors that are not RGB are left as they are, as the terminal decides what they look like.
+pub fn readable_color(color: Color, background: Color, min_contrast: f64) -> Color {
+    let (Rgb(red, green, blue), Rgb(bg_red, bg_green, bg_blue)) = (color, background) else {
+        return color;
+    };
+
+    let color = [red, green, blue];
+    let background = [bg_red, bg_green, bg_blue];
+
+    let target = if relative_luminance(background) < 0.5 {
+        [255, 255, 255]
+    } else {
+        [0, 0, 0]
+    };
+
+    let [red, green, blue] = (0..=CONTRAST_STEPS)
+        .map(|step| mix(color, target, f64::from(step) / f64::from(CONTRAST_STEPS)))
+        .find(|&mixed| contrast_ratio(mixed, background) >= min_contrast)
+        .unwrap_or(target);
+
+    Rgb(red, green, blue)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,6 +126,38 @@ mod tests {
         assert_eq!(Rgb(0, 0, 0), black_rgb);
     }
 
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((contrast_ratio([0, 0, 0], [255, 255, 255]) - 21.).abs() < 0.01);
+        assert!((contrast_ratio([120, 30, 200], [120, 30, 200]) - 1.).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_readable_color() {
+        let black = Rgb(0, 0, 0);
+        let white = Rgb(255, 255, 255);
+
+        // Dark blue is made lighter on black, and stays as it is on white
+        let Rgb(red, green, blue) = readable_color(Rgb(0, 0, 139), black, 4.5) else {
+            unreachable!();
+        };
+        assert!(contrast_ratio([red, green, blue], [0, 0, 0]) >= 4.5);
+        assert!(blue > 139);
+        assert_eq!(readable_color(Rgb(0, 0, 139), white, 4.5), Rgb(0, 0, 139));
+
+        // Yellow is made darker on white
+        let Rgb(red, green, blue) = readable_color(Rgb(255, 255, 0), white, 3.) else {
+            unreachable!();
+        };
+        assert!(contrast_ratio([red, green, blue], [255, 255, 255]) >= 3.);
+
+        assert_eq!(readable_color(Color::Blue, black, 4.5), Color::Blue);
+        assert_eq!(
+            readable_color(Rgb(0, 0, 139), Color::Reset, 4.5),
+            Rgb(0, 0, 139)
+        );
+    }
+
     #[test]
     fn test_u32_to_color() {
         let rgb = u32_to_color(126_496_416);
@@ -0,0 +1,347 @@
+use std::{borrow::Cow, fmt::Display};
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+/// `:shortcode:`, with the characters that shortcodes are made of.
+static SHORTCODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r":([a-z0-9_+-]+):").unwrap());
+
+/// A unicode emoji, along with the shortcode it is typed as between colons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Emoji {
+    pub shortcode: &'static str,
+    pub emoji: &'static str,
+}
+
+const fn emoji(shortcode: &'static str, emoji: &'static str) -> Emoji {
+    Emoji { shortcode, emoji }
+}
+
+impl Display for Emoji {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} :{}:", self.emoji, self.shortcode)
+    }
+}
+
+/// The emoji that can be typed with shortcodes, using the same names as GitHub and Slack.
+/// Twitch chat shows unicode emoji as they are, so these are sent as the emoji themselves.
+pub static EMOJI: &[Emoji] = &[
+    emoji("grinning", "😀"),
+    emoji("smiley", "😃"),
+    emoji("smile", "😄"),
+    emoji("grin", "😁"),
+    emoji("laughing", "😆"),
+    emoji("sweat_smile", "😅"),
+    emoji("rofl", "🤣"),
+    emoji("joy", "😂"),
+    emoji("slightly_smiling_face", "🙂"),
+    emoji("upside_down_face", "🙃"),
+    emoji("wink", "😉"),
+    emoji("blush", "😊"),
+    emoji("innocent", "😇"),
+    emoji("smiling_face_with_three_hearts", "🥰"),
+    emoji("heart_eyes", "😍"),
+    emoji("star_struck", "🤩"),
+    emoji("kissing_heart", "😘"),
+    emoji("yum", "😋"),
+    emoji("stuck_out_tongue", "😛"),
+    emoji("stuck_out_tongue_winking_eye", "😜"),
+    emoji("zany_face", "🤪"),
+    emoji("money_mouth_face", "🤑"),
+    emoji("hugs", "🤗"),
+    emoji("hand_over_mouth", "🤭"),
+    emoji("shushing_face", "🤫"),
+    emoji("thinking", "🤔"),
+    emoji("zipper_mouth_face", "🤐"),
+    emoji("raised_eyebrow", "🤨"),
+    emoji("neutral_face", "😐"),
+    emoji("expressionless", "😑"),
+    emoji("no_mouth", "😶"),
+    emoji("smirk", "😏"),
+    emoji("unamused", "😒"),
+    emoji("roll_eyes", "🙄"),
+    emoji("grimacing", "😬"),
+    emoji("lying_face", "🤥"),
+    emoji("relieved", "😌"),
+    emoji("pensive", "😔"),
+    emoji("sleepy", "😪"),
+    emoji("drooling_face", "🤤"),
+    emoji("sleeping", "😴"),
+    emoji("mask", "😷"),
+    emoji("face_with_thermometer", "🤒"),
+    emoji("nauseated_face", "🤢"),
+    emoji("vomiting_face", "🤮"),
+    emoji("sneezing_face", "🤧"),
+    emoji("hot_face", "🥵"),
+    emoji("cold_face", "🥶"),
+    emoji("woozy_face", "🥴"),
+    emoji("dizzy_face", "😵"),
+    emoji("exploding_head", "🤯"),
+    emoji("cowboy_hat_face", "🤠"),
+    emoji("partying_face", "🥳"),
+    emoji("sunglasses", "😎"),
+    emoji("nerd_face", "🤓"),
+    emoji("monocle_face", "🧐"),
+    emoji("confused", "😕"),
+    emoji("worried", "😟"),
+    emoji("slightly_frowning_face", "🙁"),
+    emoji("open_mouth", "😮"),
+    emoji("hushed", "😯"),
+    emoji("astonished", "😲"),
+    emoji("flushed", "😳"),
+    emoji("pleading_face", "🥺"),
+    emoji("frowning", "😦"),
+    emoji("anguished", "😧"),
+    emoji("fearful", "😨"),
+    emoji("cold_sweat", "😰"),
+    emoji("disappointed_relieved", "😥"),
+    emoji("cry", "😢"),
+    emoji("sob", "😭"),
+    emoji("scream", "😱"),
+    emoji("confounded", "😖"),
+    emoji("persevere", "😣"),
+    emoji("disappointed", "😞"),
+    emoji("sweat", "😓"),
+    emoji("weary", "😩"),
+    emoji("tired_face", "😫"),
+    emoji("yawning_face", "🥱"),
+    emoji("triumph", "😤"),
+    emoji("rage", "😡"),
+    emoji("angry", "😠"),
+    emoji("cursing_face", "🤬"),
+    emoji("smiling_imp", "😈"),
+    emoji("imp", "👿"),
+    emoji("skull", "💀"),
+    emoji("poop", "💩"),
+    emoji("clown_face", "🤡"),
+    emoji("ghost", "👻"),
+    emoji("alien", "👽"),
+    emoji("robot", "🤖"),
+    emoji("see_no_evil", "🙈"),
+    emoji("hear_no_evil", "🙉"),
+    emoji("speak_no_evil", "🙊"),
+    emoji("heart", "❤️"),
+    emoji("orange_heart", "🧡"),
+    emoji("yellow_heart", "💛"),
+    emoji("green_heart", "💚"),
+    emoji("blue_heart", "💙"),
+    emoji("purple_heart", "💜"),
+    emoji("black_heart", "🖤"),
+    emoji("white_heart", "🤍"),
+    emoji("broken_heart", "💔"),
+    emoji("sparkling_heart", "💖"),
+    emoji("two_hearts", "💕"),
+    emoji("100", "💯"),
+    emoji("anger", "💢"),
+    emoji("boom", "💥"),
+    emoji("dizzy", "💫"),
+    emoji("sweat_drops", "💦"),
+    emoji("zzz", "💤"),
+    emoji("wave", "👋"),
+    emoji("raised_hand", "✋"),
+    emoji("ok_hand", "👌"),
+    emoji("pinched_fingers", "🤌"),
+    emoji("v", "✌️"),
+    emoji("crossed_fingers", "🤞"),
+    emoji("love_you_gesture", "🤟"),
+    emoji("metal", "🤘"),
+    emoji("call_me_hand", "🤙"),
+    emoji("point_left", "👈"),
+    emoji("point_right", "👉"),
+    emoji("point_up_2", "👆"),
+    emoji("point_down", "👇"),
+    emoji("point_up", "☝️"),
+    emoji("+1", "👍"),
+    emoji("thumbsup", "👍"),
+    emoji("-1", "👎"),
+    emoji("thumbsdown", "👎"),
+    emoji("fist", "👊"),
+    emoji("clap", "👏"),
+    emoji("raised_hands", "🙌"),
+    emoji("open_hands", "👐"),
+    emoji("handshake", "🤝"),
+    emoji("pray", "🙏"),
+    emoji("muscle", "💪"),
+    emoji("eyes", "👀"),
+    emoji("brain", "🧠"),
+    emoji("salute", "🫡"),
+    emoji("facepalm", "🤦"),
+    emoji("shrug", "🤷"),
+    emoji("dog", "🐶"),
+    emoji("cat", "🐱"),
+    emoji("fox_face", "🦊"),
+    emoji("bear", "🐻"),
+    emoji("panda_face", "🐼"),
+    emoji("frog", "🐸"),
+    emoji("monkey_face", "🐵"),
+    emoji("chicken", "🐔"),
+    emoji("penguin", "🐧"),
+    emoji("owl", "🦉"),
+    emoji("unicorn", "🦄"),
+    emoji("bee", "🐝"),
+    emoji("snake", "🐍"),
+    emoji("turtle", "🐢"),
+    emoji("crab", "🦀"),
+    emoji("fish", "🐟"),
+    emoji("whale", "🐳"),
+    emoji("goat", "🐐"),
+    emoji("rose", "🌹"),
+    emoji("sunflower", "🌻"),
+    emoji("seedling", "🌱"),
+    emoji("four_leaf_clover", "🍀"),
+    emoji("sun_with_face", "🌞"),
+    emoji("star", "⭐"),
+    emoji("star2", "🌟"),
+    emoji("sparkles", "✨"),
+    emoji("zap", "⚡"),
+    emoji("fire", "🔥"),
+    emoji("rainbow", "🌈"),
+    emoji("snowflake", "❄️"),
+    emoji("droplet", "💧"),
+    emoji("ocean", "🌊"),
+    emoji("apple", "🍎"),
+    emoji("banana", "🍌"),
+    emoji("strawberry", "🍓"),
+    emoji("avocado", "🥑"),
+    emoji("pizza", "🍕"),
+    emoji("hamburger", "🍔"),
+    emoji("fries", "🍟"),
+    emoji("taco", "🌮"),
+    emoji("popcorn", "🍿"),
+    emoji("cake", "🍰"),
+    emoji("birthday", "🎂"),
+    emoji("cookie", "🍪"),
+    emoji("coffee", "☕"),
+    emoji("tea", "🍵"),
+    emoji("beer", "🍺"),
+    emoji("beers", "🍻"),
+    emoji("clinking_glasses", "🥂"),
+    emoji("wine_glass", "🍷"),
+    emoji("soccer", "⚽"),
+    emoji("basketball", "🏀"),
+    emoji("trophy", "🏆"),
+    emoji("1st_place_medal", "🥇"),
+    emoji("video_game", "🎮"),
+    emoji("joystick", "🕹️"),
+    emoji("game_die", "🎲"),
+    emoji("dart", "🎯"),
+    emoji("jigsaw", "🧩"),
+    emoji("tada", "🎉"),
+    emoji("confetti_ball", "🎊"),
+    emoji("balloon", "🎈"),
+    emoji("gift", "🎁"),
+    emoji("crown", "👑"),
+    emoji("gem", "💎"),
+    emoji("moneybag", "💰"),
+    emoji("microphone", "🎤"),
+    emoji("headphones", "🎧"),
+    emoji("musical_note", "🎵"),
+    emoji("notes", "🎶"),
+    emoji("guitar", "🎸"),
+    emoji("computer", "💻"),
+    emoji("keyboard", "⌨️"),
+    emoji("desktop_computer", "🖥️"),
+    emoji("movie_camera", "🎥"),
+    emoji("tv", "📺"),
+    emoji("camera", "📷"),
+    emoji("bulb", "💡"),
+    emoji("books", "📚"),
+    emoji("memo", "📝"),
+    emoji("calendar", "📆"),
+    emoji("pushpin", "📌"),
+    emoji("paperclip", "📎"),
+    emoji("lock", "🔒"),
+    emoji("key", "🔑"),
+    emoji("hammer", "🔨"),
+    emoji("wrench", "🔧"),
+    emoji("gear", "⚙️"),
+    emoji("rocket", "🚀"),
+    emoji("airplane", "✈️"),
+    emoji("car", "🚗"),
+    emoji("hourglass", "⌛"),
+    emoji("alarm_clock", "⏰"),
+    emoji("stopwatch", "⏱️"),
+    emoji("bell", "🔔"),
+    emoji("loudspeaker", "📢"),
+    emoji("warning", "⚠️"),
+    emoji("no_entry", "⛔"),
+    emoji("x", "❌"),
+    emoji("heavy_check_mark", "✔️"),
+    emoji("white_check_mark", "✅"),
+    emoji("question", "❓"),
+    emoji("exclamation", "❗"),
+    emoji("interrobang", "⁉️"),
+    emoji("heavy_plus_sign", "➕"),
+    emoji("heavy_minus_sign", "➖"),
+    emoji("arrow_up", "⬆️"),
+    emoji("arrow_down", "⬇️"),
+    emoji("arrow_left", "⬅️"),
+    emoji("arrow_right", "➡️"),
+    emoji("recycle", "♻️"),
+    emoji("copyright", "©️"),
+    emoji("registered", "®️"),
+    emoji("tm", "™️"),
+    emoji("red_circle", "🔴"),
+    emoji("green_circle", "🟢"),
+    emoji("large_blue_circle", "🔵"),
+    emoji("checkered_flag", "🏁"),
+    emoji("triangular_flag_on_post", "🚩"),
+    emoji("rainbow_flag", "🏳️‍🌈"),
+    emoji("pirate_flag", "🏴‍☠️"),
+];
+
+pub fn find_emoji(shortcode: &str) -> Option<&'static str> {
+    EMOJI
+        .iter()
+        .find(|emoji| emoji.shortcode == shortcode)
+        .map(|emoji| emoji.emoji)
+}
+
+/// Replace every known `:shortcode:` in some text with its emoji, leaving unknown ones as they are.
+pub fn expand_shortcodes(text: &str) -> Cow<'_, str> {
+    SHORTCODE_REGEX.replace_all(text, |captures: &Captures| {
+        find_emoji(&captures[1]).map_or_else(|| captures[0].to_string(), ToString::to_string)
+    })
+}
+
+/// If some text ends with a known `:shortcode:`, how many bytes it takes up along with its colons, and its emoji.
+pub fn trailing_shortcode(text: &str) -> Option<(usize, &'static str)> {
+    let start = text.strip_suffix(':')?.rfind(':')?;
+
+    let emoji = find_emoji(&text[start + 1..text.len() - 1])?;
+
+    Some((text.len() - start, emoji))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_shortcodes() {
+        assert_eq!(expand_shortcodes("hmm :thinking:"), "hmm 🤔");
+        assert_eq!(expand_shortcodes(":+1::fire: :nothing:"), "👍🔥 :nothing:");
+        assert_eq!(expand_shortcodes("12:30:45"), "12:30:45");
+    }
+
+    #[test]
+    fn test_trailing_shortcode() {
+        assert_eq!(trailing_shortcode("so :fire:"), Some((6, "🔥")));
+        assert_eq!(trailing_shortcode(":fire"), None);
+        assert_eq!(trailing_shortcode("a :b: :nothing:"), None);
+        assert_eq!(trailing_shortcode(":"), None);
+    }
+
+    #[test]
+    fn test_shortcodes_are_unique() {
+        for (i, emoji) in EMOJI.iter().enumerate() {
+            assert!(
+                EMOJI[i + 1..]
+                    .iter()
+                    .all(|other| other.shortcode != emoji.shortcode),
+                "{} is used more than once",
+                emoji.shortcode
+            );
+        }
+    }
+}
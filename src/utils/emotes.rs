@@ -1,6 +1,6 @@
 use std::iter;
 
-use crate::handlers::config::FrontendConfig;
+use crate::handlers::config::{BadgeStyle, FrontendConfig};
 
 pub const PRIVATE_USE_UNICODE: char = '\u{10EEEE}';
 pub const ZERO_WIDTH_SPACE: char = '\u{200B}';
@@ -11,6 +11,7 @@ pub const fn emotes_enabled(frontend: &FrontendConfig) -> bool {
         || frontend.betterttv_emotes
         || frontend.seventv_emotes
         || frontend.frankerfacez_emotes
+        || matches!(frontend.badges, BadgeStyle::Images)
 }
 
 pub const fn get_emote_offset(width: u16, cell_width: u16, cols: u16) -> (u16, u16) {
@@ -60,7 +61,6 @@ impl UnicodePlaceholder {
         })
     }
 
-    #[allow(unused)]
     pub fn string(&self) -> String {
         let mut s = String::with_capacity(self.len());
 
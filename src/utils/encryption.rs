@@ -0,0 +1,180 @@
+use std::{
+    env,
+    fs::{OpenOptions, read_to_string},
+    io::Write,
+    path::Path,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use color_eyre::{Result, eyre::anyhow};
+use once_cell::sync::OnceCell;
+use ring::{
+    aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey},
+    rand::{SecureRandom, SystemRandom},
+};
+
+use crate::utils::pathing::config_path;
+
+/// Written at the start of every encrypted file, so that plain files can still be read.
+const MAGIC: &[u8] = b"twt-encrypted-v1\n";
+/// Base64 encoded key, used instead of the key file if it is set.
+const KEY_ENVIRONMENT_VARIABLE: &str = "TWT_STORAGE_KEY";
+/// File containing the base64 encoded key, which is generated the first time it is needed.
+/// This is not the keyring of the system: the file is next to the files it encrypts, with the same owner.
+const KEY_FILE: &str = "storage.key";
+const KEY_LEN: usize = 32;
+
+/// The key once it has been loaded, as the storage, outbox, and token are encrypted many times a session.
+static LOADED_KEY: OnceCell<Key> = OnceCell::new();
+
+/// A key to encrypt and decrypt with, for when the same one is used many times.
+pub type Key = [u8; KEY_LEN];
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+fn decode_key(encoded: &str) -> Result<Key> {
+    STANDARD
+        .decode(encoded.trim())?
+        .try_into()
+        .map_err(|_| anyhow!("Storage key must be {KEY_LEN} bytes long."))
+}
+
+fn generate_key(path: &str) -> Result<Key> {
+    let mut key = [0; KEY_LEN];
+
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| anyhow!("Unable to generate a storage key."))?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+
+    // Nobody but the current user should be able to read the key.
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options
+        .open(path)?
+        .write_all(STANDARD.encode(key).as_bytes())?;
+
+    Ok(key)
+}
+
+fn read_key() -> Result<Key> {
+    if let Ok(encoded) = env::var(KEY_ENVIRONMENT_VARIABLE) {
+        return decode_key(&encoded);
+    }
+
+    let path = config_path(KEY_FILE);
+
+    if Path::new(&path).exists() {
+        decode_key(&read_to_string(path)?)
+    } else {
+        generate_key(&path)
+    }
+}
+
+/// The key from the environment or the key file, which is generated if neither exists.
+/// It is only read once, and is tried again if it could not be.
+pub fn load_key() -> Result<Key> {
+    LOADED_KEY.get_or_try_init(read_key).copied()
+}
+
+/// Encrypt data with ChaCha20-Poly1305.
+pub fn seal(key: &Key, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let key = LessSafeKey::new(
+        UnboundKey::new(&CHACHA20_POLY1305, key).map_err(|_| anyhow!("Invalid storage key."))?,
+    );
+
+    let mut nonce = [0; NONCE_LEN];
+
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| anyhow!("Unable to generate a nonce."))?;
+
+    let mut in_out = plaintext.to_vec();
+
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut in_out,
+    )
+    .map_err(|_| anyhow!("Unable to encrypt data."))?;
+
+    Ok([MAGIC, &nonce, &in_out].concat())
+}
+
+pub fn open(key: &Key, data: &[u8]) -> Result<Vec<u8>> {
+    let key = LessSafeKey::new(
+        UnboundKey::new(&CHACHA20_POLY1305, key).map_err(|_| anyhow!("Invalid storage key."))?,
+    );
+
+    let data = data
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| anyhow!("Data is not encrypted."))?;
+
+    if data.len() < NONCE_LEN {
+        return Err(anyhow!("Encrypted data is too short."));
+    }
+
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| anyhow!("Invalid nonce in encrypted data."))?;
+
+    let mut in_out = ciphertext.to_vec();
+
+    let plaintext = key
+        .open_in_place(nonce, Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow!("Unable to decrypt data, the storage key may have changed."))?;
+
+    Ok(plaintext.to_vec())
+}
+
+/// Encrypt data with ChaCha20-Poly1305, using the key loaded from the environment or the key file.
+pub fn encrypt(plaintext: &[u8]) -> Result<Vec<u8>> {
+    seal(&load_key()?, plaintext)
+}
+
+pub fn decrypt(data: &[u8]) -> Result<Vec<u8>> {
+    open(&load_key()?, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: Key = [7; KEY_LEN];
+
+    #[test]
+    fn test_round_trip() {
+        let data = seal(&KEY, b"{\"channels\": []}").unwrap();
+
+        assert!(is_encrypted(&data));
+        assert_eq!(open(&KEY, &data).unwrap(), b"{\"channels\": []}");
+    }
+
+    #[test]
+    fn test_wrong_key() {
+        let data = seal(&KEY, b"secret").unwrap();
+
+        assert!(open(&[8; KEY_LEN], &data).is_err());
+    }
+
+    #[test]
+    fn test_tampered_data() {
+        let mut data = seal(&KEY, b"secret").unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 1;
+
+        assert!(open(&KEY, &data).is_err());
+        assert!(!is_encrypted(b"{}"));
+    }
+
+    #[test]
+    fn test_decode_key_length() {
+        assert!(decode_key(&STANDARD.encode([1; KEY_LEN])).is_ok());
+        assert!(decode_key(&STANDARD.encode([1; 16])).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::handlers::config::LanguageFilterConfig;
+
+/// The most common words of languages written in the Latin alphabet, which tell them apart.
+const COMMON_WORDS: [(&str, &[&str]); 9] = [
+    (
+        "en",
+        &[
+            "the", "and", "is", "you", "to", "of", "it", "that", "this", "what", "for", "are",
+            "was", "with", "have", "not", "my", "just", "your", "he", "she", "they", "how",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "que", "y", "es", "los", "las", "un", "una", "por", "con", "para", "pero",
+            "como", "muy", "lo", "se", "del", "esta", "yo", "mi",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "que", "e", "é", "não", "um", "uma", "com", "para", "mas", "você", "eu", "isso",
+            "muito", "os", "do", "da", "tá", "tem", "no", "na",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "du", "ein", "eine", "zu", "mit",
+            "auf", "aber", "was", "wie", "sie", "den", "auch", "ja", "mal",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "je", "tu", "pas", "un", "une", "des", "que", "qui",
+            "ce", "il", "mais", "pour", "c'est", "du", "au", "oui",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "che", "di", "e", "è", "non", "un", "una", "per", "sono", "ma", "come", "mi",
+            "ti", "questo", "anche", "con", "del", "della", "io", "ciao",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "niet", "ik", "je", "dat", "van", "wat", "op", "met",
+            "maar", "ook", "zijn", "voor", "er", "dit", "hij", "jij",
+        ],
+    ),
+    (
+        "pl",
+        &[
+            "nie", "to", "jest", "się", "w", "i", "na", "że", "co", "z", "jak", "ale", "tak", "ja",
+            "ty", "do", "mi", "już", "po", "czy",
+        ],
+    ),
+    (
+        "tr",
+        &[
+            "ve", "bir", "bu", "da", "de", "ne", "çok", "ben", "sen", "için", "ama", "var", "yok",
+            "mi", "gibi", "değil", "daha", "evet", "hayır",
+        ],
+    ),
+];
+
+/// The language of the letters of a script that is only used by one language, or mostly by one.
+const fn script_language(c: char) -> Option<&'static str> {
+    match c {
+        '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' => Some("ko"),
+        '\u{3040}'..='\u{30FF}' => Some("ja"),
+        '\u{4E00}'..='\u{9FFF}' => Some("zh"),
+        '\u{0400}'..='\u{04FF}' => Some("ru"),
+        '\u{0600}'..='\u{06FF}' => Some("ar"),
+        '\u{0590}'..='\u{05FF}' => Some("he"),
+        '\u{0E00}'..='\u{0E7F}' => Some("th"),
+        '\u{0370}'..='\u{03FF}' => Some("el"),
+        '\u{0900}'..='\u{097F}' => Some("hi"),
+        _ => None,
+    }
+}
+
+/// Detect the language of a script that most of the letters are in, other than the Latin alphabet.
+fn detect_script(text: &str) -> Option<(&'static str, f32)> {
+    let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+
+    let mut counts: Vec<(&str, usize)> = vec![];
+
+    for language in text.chars().filter_map(script_language) {
+        match counts.iter_mut().find(|(l, _)| *l == language) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((language, 1)),
+        }
+    }
+
+    // Japanese is written with Chinese characters along with its own
+    let japanese = counts.iter().any(|(l, _)| *l == "ja");
+
+    if japanese {
+        let chinese = counts
+            .iter()
+            .find(|(l, _)| *l == "zh")
+            .map_or(0, |(_, count)| *count);
+
+        counts.retain(|(l, _)| *l != "zh");
+
+        if let Some((_, count)) = counts.iter_mut().find(|(l, _)| *l == "ja") {
+            *count += chinese;
+        }
+    }
+
+    let (language, count) = counts.into_iter().max_by_key(|(_, count)| *count)?;
+
+    (count * 2 > letters).then(|| (language, count as f32 / letters as f32))
+}
+
+/// Detect the language of text in the Latin alphabet by its most common words.
+/// A single common word is not enough to be sure, as it can be borrowed from another language.
+fn detect_words(text: &str) -> Option<(&'static str, f32)> {
+    let words = text
+        .split(|c: char| !c.is_alphabetic() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>();
+
+    let mut scores = COMMON_WORDS
+        .iter()
+        .map(|(language, common)| {
+            let hits = words
+                .iter()
+                .filter(|word| common.contains(&word.as_str()))
+                .count();
+
+            (*language, hits)
+        })
+        .filter(|(_, hits)| *hits > 0)
+        .collect::<Vec<(&str, usize)>>();
+
+    let total = scores.iter().map(|(_, hits)| hits).sum::<usize>();
+
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+
+    let (language, hits) = scores.first().copied()?;
+
+    let share = hits as f32 / total as f32;
+    let certainty = (hits as f32 / 2.0).min(1.0);
+
+    Some((language, share * certainty))
+}
+
+/// Detect the language of a message, as a two letter code along with how sure it is from 0 to 1.
+/// Messages without enough words to tell, such as those with only emotes, have no language.
+pub fn detect_language(text: &str) -> Option<(&'static str, f32)> {
+    detect_script(text).or_else(|| detect_words(text))
+}
+
+/// If a message in a channel is in a language other than the expected ones, going by the language filter.
+pub fn is_foreign(config: &LanguageFilterConfig, channel: &str, text: &str) -> bool {
+    if config.languages.is_empty()
+        || !config
+            .channels
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(channel))
+    {
+        return false;
+    }
+
+    detect_language(text).is_some_and(|(language, confidence)| {
+        confidence >= config.confidence
+            && !config
+                .languages
+                .iter()
+                .any(|l| l.eq_ignore_ascii_case(language))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_script() {
+        assert_eq!(detect_language("안녕하세요").map(|(l, _)| l), Some("ko"));
+        assert_eq!(
+            detect_language("こんにちは世界").map(|(l, _)| l),
+            Some("ja")
+        );
+        assert_eq!(detect_language("привет всем").map(|(l, _)| l), Some("ru"));
+    }
+
+    #[test]
+    fn test_detect_words() {
+        assert_eq!(
+            detect_language("what is the plan for this run").map(|(l, _)| l),
+            Some("en")
+        );
+        assert_eq!(
+            detect_language("das ist nicht gut").map(|(l, _)| l),
+            Some("de")
+        );
+        assert_eq!(detect_language("KEKW PogChamp"), None);
+    }
+
+    #[test]
+    fn test_is_foreign() {
+        let config = LanguageFilterConfig {
+            languages: vec!["en".to_string()],
+            channels: vec!["xithrius".to_string()],
+            confidence: 0.5,
+            hide: false,
+        };
+
+        assert!(is_foreign(&config, "xithrius", "das ist nicht gut"));
+        assert!(!is_foreign(&config, "xithrius", "this is not good"));
+        assert!(!is_foreign(&config, "xithrius", "KEKW"));
+        assert!(!is_foreign(&config, "twitch", "das ist nicht gut"));
+    }
+}
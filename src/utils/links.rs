@@ -0,0 +1,145 @@
+use std::{env, ops::Range};
+
+use once_cell::sync::Lazy;
+use tui::layout::Rect;
+use unicode_width::UnicodeWidthStr;
+
+/// Characters that end a sentence rather than a link, when they are at the end of one.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', ')', '\'', '"'];
+
+/// Terminals that set `TERM_PROGRAM` and show OSC 8 hyperlinks.
+const HYPERLINK_PROGRAMS: [&str; 6] = [
+    "iTerm.app",
+    "WezTerm",
+    "vscode",
+    "ghostty",
+    "Hyper",
+    "Tabby",
+];
+
+/// Terminals that can be told apart by `TERM` and show OSC 8 hyperlinks.
+const HYPERLINK_TERMS: [&str; 5] = ["kitty", "alacritty", "foot", "ghostty", "wezterm"];
+
+/// If the terminal is known to show OSC 8 hyperlinks, rather than printing them as text.
+static HYPERLINKS_SUPPORTED: Lazy<bool> = Lazy::new(|| {
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    let term = env::var("TERM").unwrap_or_default();
+
+    // GNOME Terminal and others built on VTE show hyperlinks since version 0.50
+    let vte = env::var("VTE_VERSION")
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok())
+        .is_some_and(|version| version >= 5000);
+
+    HYPERLINK_PROGRAMS.contains(&program.as_str())
+        || HYPERLINK_TERMS.iter().any(|t| term.contains(t))
+        || vte
+        || env::var("WT_SESSION").is_ok()
+        || env::var("KONSOLE_VERSION").is_ok()
+});
+
+pub fn hyperlinks_supported() -> bool {
+    *HYPERLINKS_SUPPORTED
+}
+
+/// Part of a link that was drawn on a single row of chat, as links can be wrapped over multiple rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrawnLink {
+    pub area: Rect,
+    /// The text that was drawn, which is only the part of the link on this row.
+    pub text: String,
+    pub url: String,
+}
+
+/// The web links in some text, in the order they appear.
+/// Links end at control characters, so that chat cannot put escape sequences of its own in the hyperlinks written out.
+pub fn find_urls(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter_map(|word| word.split(char::is_control).next())
+        .filter(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(|word| word.trim_end_matches(TRAILING_PUNCTUATION).to_string())
+        .collect()
+}
+
+/// Where the links of a message are on one of the rows it was wrapped to, by column,
+/// along with the text of the row there and the link it is a part of.
+/// Links wrapped over rows end the first row, and start the next one.
+pub fn link_columns<'a>(row: &'a str, urls: &'a [String]) -> Vec<(Range<usize>, &'a str, &'a str)> {
+    let mut column = 0;
+
+    let words = row
+        .split(' ')
+        .filter_map(|word| {
+            let start = column;
+            column += word.width() + 1;
+
+            (!word.is_empty()).then_some((start, word))
+        })
+        .collect::<Vec<(usize, &str)>>();
+
+    let last = words.len().saturating_sub(1);
+
+    words
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(start, word))| {
+            urls.iter().find_map(|url| {
+                let (offset, text) = if let Some(offset) = word.find(url.as_str()) {
+                    (offset, &word[offset..offset + url.len()])
+                } else if (i == last && url.starts_with(word))
+                    || (i == 0 && url.ends_with(word))
+                    || (i == 0 && i == last && url.contains(word))
+                {
+                    (0, word)
+                } else {
+                    return None;
+                };
+
+                let start = start + word[..offset].width();
+
+                Some((start..start + text.width(), text, url.as_str()))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_urls() {
+        assert_eq!(
+            find_urls("see https://twitch.tv/xithrius, and http://example.com/a?b=c"),
+            vec![
+                "https://twitch.tv/xithrius".to_string(),
+                "http://example.com/a?b=c".to_string()
+            ]
+        );
+        assert!(find_urls("no links here, just twitch.tv").is_empty());
+        assert_eq!(
+            find_urls("https://a.com/\x1b\\\x1b]52;c;aGk=\x07 \x1bhttps://b.com"),
+            vec!["https://a.com/".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_link_columns() {
+        let urls = vec!["https://example.com/a/long/path".to_string()];
+
+        assert_eq!(
+            link_columns("12:00 human: look (https://example.com/a/long/path)", &urls),
+            vec![(19..50, "https://example.com/a/long/path", urls[0].as_str())]
+        );
+
+        // A link wrapped over two rows, with the text around it left alone
+        assert_eq!(
+            link_columns("12:00 human: look https://example.com/a", &urls),
+            vec![(18..39, "https://example.com/a", urls[0].as_str())]
+        );
+        assert_eq!(
+            link_columns("             /long/path is long", &urls),
+            vec![(13..23, "/long/path", urls[0].as_str())]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Mutex;
+
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+
+use crate::handlers::config::Matching;
+
+static FUZZY_FINDER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
+
+/// A search along with the regular expression it compiled to, if it is a valid one.
+type CompiledSearch = (String, Option<Regex>);
+
+/// The last search compiled as a regular expression, as lists are searched once for every row.
+static LAST_REGEX: Lazy<Mutex<Option<CompiledSearch>>> = Lazy::new(|| Mutex::new(None));
+
+fn search_regex(search: &str) -> Option<Regex> {
+    let mut last = LAST_REGEX.lock().unwrap();
+
+    if last.as_ref().is_none_or(|(pattern, _)| pattern != search) {
+        let regex = RegexBuilder::new(search)
+            .case_insensitive(true)
+            .build()
+            .ok();
+
+        *last = Some((search.to_string(), regex));
+    }
+
+    last.as_ref().and_then(|(_, regex)| regex.clone())
+}
+
+/// Where the typed text first appears in some text as characters, ignoring case.
+fn find_ignoring_case(text: &[char], search: &[char]) -> Option<usize> {
+    if search.is_empty() {
+        return Some(0);
+    }
+
+    text.windows(search.len()).position(|window| {
+        window
+            .iter()
+            .zip(search)
+            .all(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+    })
+}
+
+/// The indices of the characters of some text that were matched by a search, if it matches,
+/// along with a score to order the matches by, the highest being the best.
+/// Searches that are not valid regular expressions match nothing.
+pub fn match_indices(matching: Matching, text: &str, search: &str) -> Option<(i64, Vec<usize>)> {
+    let text_chars = text.chars().collect::<Vec<char>>();
+    let search_chars = search.chars().collect::<Vec<char>>();
+
+    let (start, end) = match matching {
+        Matching::Fuzzy => return FUZZY_FINDER.fuzzy_indices(text, search),
+        Matching::Substring => {
+            let start = find_ignoring_case(&text_chars, &search_chars)?;
+
+            (start, start + search_chars.len())
+        }
+        Matching::Prefix => {
+            let start = find_ignoring_case(
+                &text_chars[..search_chars.len().min(text_chars.len())],
+                &search_chars,
+            )?;
+
+            (start, start + search_chars.len())
+        }
+        Matching::Regex => {
+            let found = search_regex(search)?.find(text)?;
+
+            (
+                text[..found.start()].chars().count(),
+                text[..found.end()].chars().count(),
+            )
+        }
+    };
+
+    // Matches closer to the start of the text come first
+    let score = i64::try_from(start).map_or(i64::MIN, |start| -start);
+
+    Some((score, (start..end).collect()))
+}
+
+/// If a search matches any of the fields of a row, for rows that are searched by more than their name.
+pub fn matches_any(matching: Matching, fields: &[&str], search: &str) -> bool {
+    search.is_empty()
+        || fields
+            .iter()
+            .any(|field| match_indices(matching, field, search).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substring_and_prefix() {
+        assert_eq!(
+            match_indices(Matching::Substring, "Xithrius", "THR"),
+            Some((-2, vec![2, 3, 4]))
+        );
+        assert_eq!(match_indices(Matching::Substring, "Xithrius", "xtr"), None);
+
+        assert_eq!(
+            match_indices(Matching::Prefix, "Xithrius", "xi"),
+            Some((0, vec![0, 1]))
+        );
+        assert_eq!(match_indices(Matching::Prefix, "Xithrius", "thr"), None);
+        assert_eq!(match_indices(Matching::Prefix, "Xi", "xith"), None);
+    }
+
+    #[test]
+    fn test_regex() {
+        assert_eq!(
+            match_indices(Matching::Regex, "émote_42", r"\d+$"),
+            Some((-6, vec![6, 7]))
+        );
+        assert_eq!(match_indices(Matching::Regex, "Xithrius", "("), None);
+    }
+
+    #[test]
+    fn test_fuzzy() {
+        assert!(match_indices(Matching::Fuzzy, "Xithrius", "xtr").is_some());
+    }
+
+    #[test]
+    fn test_matches_any() {
+        assert!(matches_any(Matching::Substring, &["human", "hello"], "ELL"));
+        assert!(matches_any(Matching::Fuzzy, &["human", "hello"], "hlo"));
+        assert!(!matches_any(
+            Matching::Substring,
+            &["human", "hello"],
+            "hlo"
+        ));
+        assert!(matches_any(Matching::Prefix, &[], ""));
+    }
+}
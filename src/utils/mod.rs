@@ -1,5 +1,18 @@
+pub mod about;
 pub mod colors;
+pub mod emoji;
 pub mod emotes;
+pub mod encryption;
+pub mod language;
+pub mod links;
+pub mod matching;
+pub mod notifications;
 pub mod pathing;
+pub mod search_query;
+pub mod sounds;
+pub mod status_command;
 pub mod styles;
 pub mod text;
+pub mod time;
+pub mod traffic;
+pub mod window_title;
@@ -0,0 +1,134 @@
+use std::{
+    process::Stdio,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use log::{debug, warn};
+use tokio::process::Command;
+
+use crate::handlers::config::NotificationsConfig;
+
+/// If notifications and sounds are paused by the user.
+static DO_NOT_DISTURB: AtomicBool = AtomicBool::new(false);
+
+/// What happened that the user can be notified about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Mention,
+    Whisper,
+    Live,
+    /// A message matching a highlight rule that notifies.
+    Highlight,
+}
+
+impl NotificationKind {
+    const fn is_enabled(self, config: &NotificationsConfig) -> bool {
+        match self {
+            Self::Mention => config.mentions,
+            Self::Whisper => config.whispers,
+            Self::Live => config.live,
+            // Each highlight rule decides for itself
+            Self::Highlight => true,
+        }
+    }
+}
+
+/// If notifications and sounds are paused by the user.
+pub fn do_not_disturb() -> bool {
+    DO_NOT_DISTURB.load(Ordering::Relaxed)
+}
+
+/// Pause or resume notifications and sounds, returning if they are now paused.
+pub fn toggle_do_not_disturb() -> bool {
+    !DO_NOT_DISTURB.fetch_xor(true, Ordering::Relaxed)
+}
+
+/// Put text in a string for `osascript`, so that quotes in messages cannot end it early.
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn notification_command(summary: &str, body: &str) -> Option<Command> {
+    if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.args([
+            "-e",
+            &format!(
+                "display notification {} with title {}",
+                applescript_string(body),
+                applescript_string(summary)
+            ),
+        ]);
+
+        Some(command)
+    } else if cfg!(unix) {
+        let mut command = Command::new("notify-send");
+        // Chat starting with a dash is not taken as an option
+        command.args(["--app-name", "twitch-tui", "--", summary, body]);
+
+        Some(command)
+    } else {
+        None
+    }
+}
+
+/// Show a desktop notification, if notifications of its kind are enabled and not paused.
+/// The notification is shown in the background, so that the terminal is not held up by it.
+pub fn notify(config: &NotificationsConfig, kind: NotificationKind, summary: &str, body: &str) {
+    if !kind.is_enabled(config) || do_not_disturb() {
+        return;
+    }
+
+    let Some(mut command) = notification_command(summary, body) else {
+        debug!("Desktop notifications are not supported on this platform");
+        return;
+    };
+
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .status();
+
+    tokio::task::spawn(async move {
+        if let Err(err) = child.await {
+            warn!("Unable to show a desktop notification: {err}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_applescript_string() {
+        assert_eq!(
+            applescript_string(r#"say "hi" \o/"#),
+            r#""say \"hi\" \\o/""#
+        );
+    }
+
+    #[test]
+    fn test_enabled_kinds() {
+        let config = NotificationsConfig {
+            whispers: true,
+            ..NotificationsConfig::default()
+        };
+
+        assert!(NotificationKind::Whisper.is_enabled(&config));
+        assert!(!NotificationKind::Mention.is_enabled(&config));
+    }
+
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn test_dashes_are_not_options() {
+        let command = notification_command("-u critical", "--wait").unwrap();
+
+        assert_eq!(
+            command.as_std().get_args().collect::<Vec<_>>(),
+            ["--app-name", "twitch-tui", "--", "-u critical", "--wait"]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+
+static FUZZY_FINDER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
+static CASE_SENSITIVE_FUZZY_FINDER: Lazy<SkimMatcherV2> =
+    Lazy::new(|| SkimMatcherV2::default().respect_case());
+
+/// What the text of messages is searched with.
+#[derive(Debug)]
+enum Pattern {
+    /// Every message matches, such as when only searching by author.
+    Any,
+    Fuzzy(String),
+    /// A regular expression, which matches nothing if it is not a valid one.
+    Regex(Option<Regex>),
+}
+
+/// A search through messages, parsed from what was typed into message search.
+/// `from:user` and `@user` only match messages from those users, and everything after `re:`
+/// is a regular expression. The rest of the words are searched for fuzzily.
+#[derive(Debug)]
+pub struct SearchQuery {
+    /// The lowercase names of the authors that messages need to be from, if any.
+    authors: Vec<String>,
+    pattern: Pattern,
+    case_sensitive: bool,
+}
+
+impl SearchQuery {
+    pub fn parse(query: &str, case_sensitive: bool) -> Self {
+        let (words, regex) = query
+            .find("re:")
+            .filter(|&i| i == 0 || query[..i].ends_with(' '))
+            .map_or((query, None), |i| (&query[..i], Some(&query[i + 3..])));
+
+        let mut authors = vec![];
+        let mut text = vec![];
+
+        for word in words.split_whitespace() {
+            let author = word
+                .strip_prefix("from:")
+                .or_else(|| word.strip_prefix('@'))
+                .filter(|author| !author.is_empty());
+
+            if let Some(author) = author {
+                authors.push(author.to_lowercase());
+            } else {
+                text.push(word);
+            }
+        }
+
+        let pattern = match regex {
+            Some(regex) => Pattern::Regex(
+                RegexBuilder::new(regex)
+                    .case_insensitive(!case_sensitive)
+                    .build()
+                    .ok(),
+            ),
+            None if text.is_empty() => Pattern::Any,
+            None => Pattern::Fuzzy(text.join(" ")),
+        };
+
+        Self {
+            authors,
+            pattern,
+            case_sensitive,
+        }
+    }
+
+    /// If a message matches the search, with the indices of the characters of its text that matched.
+    pub fn find(&self, author: &str, text: &str) -> Option<Vec<usize>> {
+        if !self.authors.is_empty() && !self.authors.contains(&author.to_lowercase()) {
+            return None;
+        }
+
+        match &self.pattern {
+            Pattern::Any => Some(vec![]),
+            Pattern::Fuzzy(search) => {
+                let finder = if self.case_sensitive {
+                    &CASE_SENSITIVE_FUZZY_FINDER
+                } else {
+                    &FUZZY_FINDER
+                };
+
+                finder
+                    .fuzzy_indices(text, search)
+                    .map(|(_, indices)| indices)
+            }
+            Pattern::Regex(regex) => {
+                let mut found = regex.as_ref()?.find_iter(text).peekable();
+
+                found.peek()?;
+
+                let ranges = found
+                    .map(|m| m.range())
+                    .filter(|range| !range.is_empty())
+                    .collect::<Vec<_>>();
+
+                Some(
+                    text.char_indices()
+                        .enumerate()
+                        .filter(|(_, (byte, _))| ranges.iter().any(|range| range.contains(byte)))
+                        .map(|(index, _)| index)
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    pub fn matches(&self, author: &str, text: &str) -> bool {
+        self.find(author, text).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_search() {
+        let query = SearchQuery::parse("hlo", false);
+
+        assert_eq!(query.find("human", "Hello"), Some(vec![0, 3, 4]));
+        assert_eq!(query.find("human", "goodbye"), None);
+
+        assert!(!SearchQuery::parse("HLO", true).matches("human", "hello"));
+    }
+
+    #[test]
+    fn test_author_search() {
+        let query = SearchQuery::parse("from:Xithrius", false);
+
+        assert_eq!(query.find("xithrius", "anything"), Some(vec![]));
+        assert!(!query.matches("human", "anything"));
+
+        let query = SearchQuery::parse("@human @xithrius hi", false);
+
+        assert!(query.matches("Human", "oh hi"));
+        assert!(query.matches("xithrius", "hi"));
+        assert!(!query.matches("xithrius", "hello"));
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let query = SearchQuery::parse("from:human re:b.r|\\d+", false);
+
+        assert_eq!(query.find("human", "éo BAR 42"), Some(vec![3, 4, 5, 7, 8]));
+        assert!(!query.matches("human", "foo"));
+        assert!(!query.matches("xithrius", "bar"));
+
+        assert!(!SearchQuery::parse("re:BAR", true).matches("human", "bar"));
+        assert!(!SearchQuery::parse("re:(", false).matches("human", "("));
+
+        // Only a word starting with `re:` makes the search a regular expression
+        assert!(SearchQuery::parse("more:", false).matches("human", "more: things"));
+    }
+}
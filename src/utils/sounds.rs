@@ -0,0 +1,146 @@
+use std::{
+    convert::Infallible,
+    fmt::Display,
+    io::{Write, stdout},
+    process::Stdio,
+    str::FromStr,
+};
+
+use log::warn;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use tokio::process::Command;
+
+use crate::{handlers::config::SoundsConfig, utils::notifications::do_not_disturb};
+
+/// What is played when an event happens.
+#[derive(SerializeDisplay, DeserializeFromStr, Debug, Clone, Default, PartialEq, Eq)]
+pub enum Sound {
+    #[default]
+    Silent,
+    /// The bell of the terminal.
+    Bell,
+    /// A sound file, played by the sound player.
+    File(String),
+}
+
+impl FromStr for Sound {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "" => Self::Silent,
+            s if s.eq_ignore_ascii_case("bell") => Self::Bell,
+            path => Self::File(path.to_string()),
+        })
+    }
+}
+
+impl Display for Sound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Silent => Ok(()),
+            Self::Bell => write!(f, "bell"),
+            Self::File(path) => write!(f, "{path}"),
+        }
+    }
+}
+
+/// What happened that a sound can be played for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEvent {
+    Mention,
+    Whisper,
+    Subscription,
+    Raid,
+}
+
+impl SoundEvent {
+    const fn sound(self, config: &SoundsConfig) -> &Sound {
+        match self {
+            Self::Mention => &config.mention,
+            Self::Whisper => &config.whisper,
+            Self::Subscription => &config.subscription,
+            Self::Raid => &config.raid,
+        }
+    }
+}
+
+/// The command playing sound files, with the file given as its last argument.
+fn player_command(config: &SoundsConfig) -> Vec<String> {
+    config.player.as_ref().map_or_else(
+        || {
+            vec![if cfg!(target_os = "macos") {
+                "afplay".to_string()
+            } else {
+                "paplay".to_string()
+            }]
+        },
+        |player| player.split_whitespace().map(ToString::to_string).collect(),
+    )
+}
+
+/// Play the sound for an event, unless notifications are paused.
+/// Sound files are played in the background, so that the terminal is not held up by them.
+pub fn play(config: &SoundsConfig, event: SoundEvent) {
+    if do_not_disturb() {
+        return;
+    }
+
+    match event.sound(config) {
+        Sound::Silent => {}
+        Sound::Bell => {
+            let mut stdout = stdout();
+
+            if let Err(err) = stdout.write_all(b"\x07").and_then(|()| stdout.flush()) {
+                warn!("Unable to ring the terminal bell: {err}");
+            }
+        }
+        Sound::File(path) => {
+            let player = player_command(config);
+
+            let Some((program, arguments)) = player.split_first() else {
+                return;
+            };
+
+            let child = Command::new(program)
+                .args(arguments)
+                .arg(path)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .kill_on_drop(true)
+                .status();
+
+            tokio::task::spawn(async move {
+                if let Err(err) = child.await {
+                    warn!("Unable to play a sound: {err}");
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sound() {
+        assert_eq!("".parse::<Sound>().unwrap(), Sound::Silent);
+        assert_eq!("Bell".parse::<Sound>().unwrap(), Sound::Bell);
+        assert_eq!(
+            "/home/human/ping.wav".parse::<Sound>().unwrap(),
+            Sound::File("/home/human/ping.wav".to_string())
+        );
+    }
+
+    #[test]
+    fn test_player_command() {
+        let config = SoundsConfig {
+            player: Some("mpv --no-video".to_string()),
+            ..SoundsConfig::default()
+        };
+
+        assert_eq!(player_command(&config), vec!["mpv", "--no-video"]);
+    }
+}
@@ -0,0 +1,112 @@
+use std::{process::Stdio, sync::Mutex, time::Duration};
+
+use log::{debug, warn};
+use tokio::{process::Command, time::timeout};
+
+/// The longest a status command can run for before it is killed.
+const STATUS_COMMAND_TIMEOUT: Duration = Duration::from_secs(2);
+/// The most characters of the command output that will be shown.
+const MAXIMUM_STATUS_LENGTH: usize = 48;
+
+/// The latest output of the user's status command, if any.
+static STATUS_OUTPUT: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn status_output() -> Option<String> {
+    STATUS_OUTPUT.lock().ok().and_then(|output| output.clone())
+}
+
+/// Only keep the first line of the output, without control characters or escape sequences,
+/// so that the output cannot mess with the terminal.
+fn sanitize_output(output: &str) -> Option<String> {
+    let line = output.lines().map(str::trim).find(|l| !l.is_empty())?;
+
+    let mut sanitized = String::new();
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip over the escape sequence, which ends at the first character from `@` to `~` after the `[`
+            if chars.next() == Some('[') {
+                chars.find(|c| ('@'..='~').contains(c));
+            }
+        } else if !c.is_control() {
+            sanitized.push(c);
+        }
+    }
+
+    Some(sanitized.chars().take(MAXIMUM_STATUS_LENGTH).collect())
+}
+
+async fn run_once(command: &str) -> Option<String> {
+    let mut process = if cfg!(target_os = "windows") {
+        let mut process = Command::new("cmd");
+        process.args(["/C", command]);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.args(["-c", command]);
+        process
+    };
+
+    let child = process
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+
+    match timeout(STATUS_COMMAND_TIMEOUT, child).await {
+        Ok(Ok(output)) => sanitize_output(&String::from_utf8_lossy(&output.stdout)),
+        Ok(Err(err)) => {
+            warn!("Unable to run status command: {err}");
+            None
+        }
+        Err(_) => {
+            debug!("Status command timed out after {STATUS_COMMAND_TIMEOUT:?}");
+            None
+        }
+    }
+}
+
+/// Run the status command forever on an interval, keeping its latest output.
+pub async fn run_status_command(command: String, interval: Duration) {
+    loop {
+        let output = run_once(&command).await;
+
+        if let Ok(mut status) = STATUS_OUTPUT.lock() {
+            *status = output;
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_first_line() {
+        assert_eq!(
+            sanitize_output("\n  Artist - Song  \nsecond line"),
+            Some("Artist - Song".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sanitize_escape_sequences() {
+        assert_eq!(
+            sanitize_output("\u{1b}[31mred\u{1b}[0m\t!"),
+            Some("red!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sanitize_empty_and_long() {
+        assert_eq!(sanitize_output("   \n\n"), None);
+        assert_eq!(
+            sanitize_output(&"a".repeat(100)).unwrap().len(),
+            MAXIMUM_STATUS_LENGTH
+        );
+    }
+}
@@ -3,6 +3,8 @@ use std::env;
 use once_cell::sync::Lazy;
 use tui::style::{Color, Modifier, Style};
 
+use crate::handlers::themes::theme;
+
 pub static NO_COLOR: Lazy<bool> = Lazy::new(|| env::var("NO_COLOR").is_ok());
 pub static BOLD: Lazy<Modifier> = Lazy::new(|| {
     if *NO_COLOR {
@@ -18,6 +20,20 @@ macro_rules! color {
     };
 }
 
+/// Styles that follow the theme are made again each time, as the theme can be changed while running.
+macro_rules! define_themed_style {
+    ($name:ident, $($key:ident: $value:expr),*) => {
+        pub fn $name() -> Style {
+            Style {
+                $(
+                    $key: $value,
+                )*
+                ..Style::default()
+            }
+        }
+    };
+}
+
 macro_rules! define_style {
     ($name:ident, $($key:ident: $value:expr),*) => {
         pub static $name: Lazy<Style> = Lazy::new(|| Style {
@@ -43,31 +59,25 @@ define_style!(STATE_TABS_STYLE,
     }
 );
 
-define_style!(TEXT_DARK_STYLE,
-    fg: color!(Color::White)
-);
-
-define_style!(DASHBOARD_SECTION_STYLE,
-    fg: color!(Color::LightRed)
+define_themed_style!(text_style,
+    fg: color!(theme().text)
 );
 
-define_style!(DATETIME_DARK_STYLE,
-    fg: color!(Color::Rgb(173, 173, 184))
+define_themed_style!(border_style,
+    fg: color!(theme().border)
 );
 
-define_style!(DATETIME_LIGHT_STYLE,
-    fg: color!(Color::Rgb(83, 83, 95))
+define_style!(DASHBOARD_SECTION_STYLE,
+    fg: color!(Color::LightRed)
 );
 
-define_style!(HIGHLIGHT_NAME_DARK_STYLE,
-    fg: color!(Color::Black),
-    bg: color!(Color::White),
-    add_modifier: *BOLD
+define_themed_style!(datetime_style,
+    fg: color!(theme().datetime)
 );
 
-define_style!(HIGHLIGHT_NAME_LIGHT_STYLE,
-    fg: color!(Color::White),
-    bg: color!(Color::Black),
+define_themed_style!(highlight_name_style,
+    fg: color!(theme().highlight),
+    bg: color!(theme().highlight_background),
     add_modifier: *BOLD
 );
 
@@ -76,8 +86,8 @@ define_style!(COLUMN_TITLE_STYLE,
     add_modifier: *BOLD
 );
 
-define_style!(SYSTEM_CHAT_STYLE,
-    fg: color!(Color::Red),
+define_themed_style!(system_chat_style,
+    fg: color!(theme().system),
     add_modifier: *BOLD
 );
 
@@ -85,12 +95,26 @@ define_style!(DASHBOARD_TITLE_COLOR_STYLE,
     fg: color!(Color::Rgb(135, 120, 165))
 );
 
-define_style!(SEARCH_STYLE,
-    fg: color!(Color::Red),
+define_themed_style!(search_style,
+    fg: color!(theme().search),
     add_modifier: *BOLD
 );
 
-define_style!(TITLE_STYLE,
-    fg: color!(Color::Red),
+define_themed_style!(title_style,
+    fg: color!(theme().title),
     add_modifier: *BOLD
 );
+
+define_themed_style!(mention_style,
+    bg: color!(theme().mention)
+);
+
+define_style!(HINT_STYLE,
+    fg: color!(Color::DarkGray)
+);
+
+// Stays bold without colors, to tell favorites apart
+define_style!(FAVORITE_STYLE,
+    fg: color!(Color::Yellow),
+    add_modifier: Modifier::BOLD
+);
@@ -3,20 +3,86 @@ use std::borrow::Cow;
 use memchr::memmem::Finder;
 use once_cell::sync::Lazy;
 use rustyline::line_buffer::LineBuffer;
-use tui::{style::Style, text::Span};
+use tui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
-/// Acquiring the horizontal position of the cursor so it can be rendered visually.
+/// Acquiring the horizontal position of the cursor in the line it is on, so it can be rendered visually.
 pub fn get_cursor_position(line_buffer: &LineBuffer) -> usize {
-    line_buffer
-        .as_str()
-        .grapheme_indices(true)
-        .take_while(|(offset, _)| *offset != line_buffer.pos())
-        .map(|(_, cluster)| cluster.width())
+    let before = &line_buffer.as_str()[..line_buffer.pos()];
+
+    before[before.rfind('\n').map_or(0, |i| i + 1)..]
+        .graphemes(true)
+        .map(UnicodeWidthStr::width)
         .sum()
 }
 
+/// The line that the cursor is on, for inputs with more than one line.
+pub fn get_cursor_line(line_buffer: &LineBuffer) -> usize {
+    line_buffer.as_str()[..line_buffer.pos()]
+        .matches('\n')
+        .count()
+}
+
+/// Split a message into messages no longer than the limit in characters, one or more for each of its lines.
+/// Lines that are too long are split between words, and words that are too long wherever the limit is reached.
+pub fn split_message(message: &str, limit: usize) -> Vec<String> {
+    let mut messages = vec![];
+
+    for line in message.lines().filter(|line| !line.trim().is_empty()) {
+        if line.chars().count() <= limit {
+            messages.push(line.to_string());
+            continue;
+        }
+
+        let mut current = String::new();
+        let mut length = 0;
+
+        for word in line.split_whitespace() {
+            if length > 0 && length + 1 + word.chars().count() > limit {
+                messages.push(std::mem::take(&mut current));
+                length = 0;
+            }
+
+            if length > 0 {
+                current.push(' ');
+                length += 1;
+            }
+
+            for c in word.chars() {
+                if length == limit {
+                    messages.push(std::mem::take(&mut current));
+                    length = 0;
+                }
+
+                current.push(c);
+                length += 1;
+            }
+        }
+
+        if !current.is_empty() {
+            messages.push(current);
+        }
+    }
+
+    messages
+}
+
+/// The command that makes a message an action, which has to start every part the message is sent as.
+const ME_COMMAND: &str = "/me ";
+
+/// Split a message that is about to be sent into the parts it is sent as, along with the prefix of each part.
+/// The prefix counts towards the limit, so the parts are shorter for actions.
+pub fn split_outgoing(message: &str, limit: usize) -> (&'static str, Vec<String>) {
+    message.strip_prefix(ME_COMMAND).map_or_else(
+        || ("", split_message(message, limit)),
+        |text| (ME_COMMAND, split_message(text, limit - ME_COMMAND.len())),
+    )
+}
+
 pub fn split_cow_in_place<'a>(cow: &mut Cow<'a, str>, mid: usize) -> Cow<'a, str> {
     match *cow {
         Cow::Owned(ref mut s) => {
@@ -32,6 +98,30 @@ pub fn split_cow_in_place<'a>(cow: &mut Cow<'a, str>, mid: usize) -> Cow<'a, str
     }
 }
 
+/// Cut a string down to a maximum width on screen, ending with an ellipsis if anything was removed.
+pub fn truncate_to_width(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+
+    let mut truncated = String::new();
+    let mut truncated_width = 0;
+
+    for grapheme in s.graphemes(true) {
+        // Leave room for the ellipsis
+        if truncated_width + grapheme.width() + 1 > width {
+            break;
+        }
+
+        truncated_width += grapheme.width();
+        truncated.push_str(grapheme);
+    }
+
+    truncated.push('…');
+    truncated
+}
+
+#[derive(Clone)]
 pub enum TitleStyle<'a> {
     Combined(&'a str, &'a str),
     Single(&'a str),
@@ -62,6 +152,65 @@ pub fn title_line<'a>(contents: &'a [TitleStyle<'a>], style: Style) -> Vec<Span<
     complete
 }
 
+/// Cut the lines of a long message down to the first `keep`, followed by how many were hidden.
+/// Messages that are only a line over are left alone, as the note would take up that line anyway.
+pub fn collapse_lines(lines: &mut Vec<Line<'_>>, keep: usize) {
+    if lines.len() <= keep + 1 {
+        return;
+    }
+
+    let hidden = lines.len() - keep;
+
+    lines.truncate(keep);
+    lines.push(Line::from(Span::styled(
+        format!("  (+{hidden} lines)"),
+        Style::default().add_modifier(Modifier::DIM),
+    )));
+}
+
+/// Text in the middle of a horizontal line as wide as the given width, such as `─── new messages ───`.
+pub fn centered_rule(text: &str, width: usize) -> String {
+    let padded = format!(" {text} ");
+    let fill = width.saturating_sub(padded.width());
+    let left = fill / 2;
+
+    format!("{}{padded}{}", "─".repeat(left), "─".repeat(fill - left))
+}
+
+/// The text of some rows from one cell to another, given as a column and row, the way a terminal selects text.
+/// Spaces at the end of each row are left out.
+pub fn text_between(rows: &[String], start: (usize, usize), end: (usize, usize)) -> String {
+    let (start, end) = if (start.1, start.0) <= (end.1, end.0) {
+        (start, end)
+    } else {
+        (end, start)
+    };
+
+    rows.iter()
+        .enumerate()
+        .skip(start.1)
+        .take(end.1 - start.1 + 1)
+        .map(|(row, text)| {
+            let from = if row == start.1 { start.0 } else { 0 };
+            let to = if row == end.1 { end.0 + 1 } else { usize::MAX };
+
+            let mut column = 0;
+
+            text.graphemes(true)
+                .filter(|grapheme| {
+                    let current = column;
+                    column += grapheme.width();
+
+                    (from..to).contains(&current)
+                })
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 /// Within an array of strings, find the first partial or full match, if any.
 pub fn first_similarity(possibilities: &[String], search: &str) -> Option<String> {
     first_similarity_iter(possibilities.iter(), search)
@@ -149,10 +298,7 @@ pub fn clean_message(msg: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use tui::{
-        style::{Color, Modifier},
-        text::Line,
-    };
+    use tui::style::Color;
 
     use super::*;
     use crate::ui::components::utils::InputListener;
@@ -185,6 +331,51 @@ mod tests {
         assert_eq!(get_cursor_position(&line_buffer), 6);
     }
 
+    #[test]
+    fn test_get_cursor_position_with_multiple_lines() {
+        let text = "never gonna\ngive you up";
+        let mut line_buffer = LineBuffer::with_capacity(25);
+        let mut input_listener = InputListener;
+        line_buffer.insert_str(0, text, &mut input_listener);
+
+        line_buffer.move_forward(11);
+        assert_eq!(get_cursor_position(&line_buffer), 11);
+        assert_eq!(get_cursor_line(&line_buffer), 0);
+        line_buffer.move_forward(3);
+        assert_eq!(get_cursor_position(&line_buffer), 2);
+        assert_eq!(get_cursor_line(&line_buffer), 1);
+    }
+
+    #[test]
+    fn test_split_message() {
+        assert_eq!(split_message("hello", 5), vec!["hello"]);
+        assert_eq!(
+            split_message("never gonna\r\n\n  give you up", 11),
+            vec!["never gonna", "give you up"]
+        );
+        assert_eq!(
+            split_message("never gonna give you up", 10),
+            vec!["never", "gonna give", "you up"]
+        );
+        assert_eq!(
+            split_message("a 123456789", 4),
+            vec!["a", "1234", "5678", "9"]
+        );
+        assert!(split_message(" \n ", 10).is_empty());
+    }
+
+    #[test]
+    fn test_split_outgoing() {
+        assert_eq!(
+            split_outgoing("hello there", 11),
+            ("", vec!["hello there".to_string()])
+        );
+        assert_eq!(
+            split_outgoing("/me hello there", 11),
+            ("/me ", vec!["hello".to_string(), "there".to_string()])
+        );
+    }
+
     #[test]
     fn test_2_dimensional_vector_to_line() {
         let s = Line::from(title_line(
@@ -229,6 +420,13 @@ mod tests {
         assert_eq!(output, None);
     }
 
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("hello", 5), "hello");
+        assert_eq!(truncate_to_width("hello world", 6), "hello…");
+        assert_eq!(truncate_to_width("日本語", 4), "日…");
+    }
+
     #[test]
     fn clean_message_end() {
         let output = clean_message("foo \u{e0000}");
@@ -250,6 +448,49 @@ mod tests {
         assert_eq!(output, "foobar  baz");
     }
 
+    #[test]
+    fn test_collapse_lines() {
+        let mut lines = (0..10)
+            .map(|i| Line::from(i.to_string()))
+            .collect::<Vec<_>>();
+
+        collapse_lines(&mut lines, 3);
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2], Line::from("2"));
+        assert_eq!(lines[3].to_string(), "  (+7 lines)");
+
+        let mut lines = (0..4)
+            .map(|i| Line::from(i.to_string()))
+            .collect::<Vec<_>>();
+
+        collapse_lines(&mut lines, 3);
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[3], Line::from("3"));
+    }
+
+    #[test]
+    fn test_text_between() {
+        let rows = vec![
+            "12:00 human: hello there".to_string(),
+            "             general 绝对".to_string(),
+        ];
+
+        assert_eq!(text_between(&rows, (13, 0), (17, 0)), "hello");
+        assert_eq!(
+            text_between(&rows, (15, 1), (13, 0)),
+            "hello there\n             gen"
+        );
+        assert_eq!(text_between(&rows, (23, 1), (24, 1)), "对");
+    }
+
+    #[test]
+    fn test_centered_rule() {
+        assert_eq!(centered_rule("new", 12), "─── new ────");
+        assert_eq!(centered_rule("new messages", 4), " new messages ");
+    }
+
     #[test]
     fn test_parse_message_action() {
         let (output, highlight) = parse_message_action("\u{1}ACTION foo\u{1}");
@@ -0,0 +1,259 @@
+use std::{borrow::Cow, time::Duration};
+
+use chrono::{DateTime, Local, TimeDelta};
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+
+use crate::handlers::config::{DateOrder, FrontendConfig};
+
+/// The strftime pattern used for full timestamps, either the one configured by the user,
+/// or one built from the configured clock and date order.
+/// <https://docs.rs/chrono/latest/chrono/format/strftime/index.html>
+pub fn datetime_pattern(frontend: &FrontendConfig) -> Cow<'_, str> {
+    frontend.datetime_format.as_deref().map_or_else(
+        || {
+            Cow::Owned(format!(
+                "{} {}",
+                date_pattern(frontend),
+                time_pattern(frontend)
+            ))
+        },
+        Cow::Borrowed,
+    )
+}
+
+const fn date_pattern(frontend: &FrontendConfig) -> &'static str {
+    match frontend.date_order {
+        DateOrder::YearMonthDay => "%Y-%m-%d",
+        DateOrder::DayMonthYear => "%d/%m/%Y",
+        DateOrder::MonthDayYear => "%m/%d/%Y",
+    }
+}
+
+const fn time_pattern(frontend: &FrontendConfig) -> &'static str {
+    match (frontend.twelve_hour_clock, frontend.show_seconds) {
+        (true, true) => "%I:%M:%S %p",
+        (true, false) => "%I:%M %p",
+        (false, true) => "%H:%M:%S",
+        (false, false) => "%H:%M",
+    }
+}
+
+/// A full timestamp, such as the time a message was sent.
+pub fn format_datetime(time: &DateTime<Local>, frontend: &FrontendConfig) -> String {
+    time.format(&datetime_pattern(frontend)).to_string()
+}
+
+/// Only the time of day, for when the date is not relevant.
+pub fn format_time(time: &DateTime<Local>, frontend: &FrontendConfig) -> String {
+    time.format(time_pattern(frontend)).to_string()
+}
+
+/// A length of time in its two largest units, such as `3h 25m` for uptimes, or `2y 4mo` for follow ages.
+pub fn format_duration(duration: TimeDelta) -> String {
+    const UNITS: [(i64, &str); 6] = [
+        (365 * 24 * 60 * 60, "y"),
+        (30 * 24 * 60 * 60, "mo"),
+        (24 * 60 * 60, "d"),
+        (60 * 60, "h"),
+        (60, "m"),
+        (1, "s"),
+    ];
+
+    let mut seconds = duration.num_seconds().max(0);
+
+    let parts = UNITS
+        .iter()
+        .filter_map(|(size, unit)| {
+            let amount = seconds / size;
+            seconds %= size;
+
+            (amount > 0).then(|| format!("{amount}{unit}"))
+        })
+        .take(2)
+        .collect::<Vec<String>>();
+
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// How long ago something happened, such as `2m ago`.
+pub fn format_relative(time: &DateTime<Local>, now: &DateTime<Local>) -> String {
+    let elapsed = *now - *time;
+
+    if elapsed.num_seconds() < 10 {
+        return "just now".to_string();
+    }
+
+    let duration = format_duration(elapsed);
+
+    // Only the largest unit is needed to get an idea of how long ago it was
+    let largest = duration.split(' ').next().unwrap_or(&duration);
+
+    format!("{largest} ago")
+}
+
+/// Parse a length of time written like `90`, `10m`, or `1h30m`, where a number without a unit is in seconds.
+pub fn parse_duration(duration: &str) -> Result<Duration> {
+    let duration = duration.trim();
+
+    if duration.is_empty() {
+        bail!("Duration is empty, use a number of seconds or a duration such as 1h30m.");
+    }
+
+    if let Ok(seconds) = duration.parse::<u64>() {
+        return Ok(Duration::from_secs(seconds));
+    }
+
+    let mut total: u64 = 0;
+    let mut number = String::new();
+
+    for c in duration.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let size = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => bail!("Duration `{duration}` has an unknown unit `{c}`, use s, m, h, d, or w."),
+        };
+
+        if number.is_empty() {
+            bail!("Duration `{duration}` is missing a number before `{c}`.");
+        }
+
+        total = number
+            .parse::<u64>()
+            .ok()
+            .and_then(|amount| amount.checked_mul(size))
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(|| eyre!("Duration `{duration}` is too long."))?;
+
+        number.clear();
+    }
+
+    if !number.is_empty() {
+        bail!("Duration `{duration}` is missing a unit after {number}.");
+    }
+
+    Ok(Duration::from_secs(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn time() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 7).unwrap()
+    }
+
+    #[test]
+    fn test_datetime_pattern_from_config() {
+        let mut frontend = FrontendConfig {
+            datetime_format: None,
+            ..Default::default()
+        };
+
+        assert_eq!(format_datetime(&time(), &frontend), "2024-03-09 14:05:07");
+
+        frontend.twelve_hour_clock = true;
+        frontend.date_order = DateOrder::DayMonthYear;
+
+        assert_eq!(
+            format_datetime(&time(), &frontend),
+            "09/03/2024 02:05:07 PM"
+        );
+        assert_eq!(format_time(&time(), &frontend), "02:05:07 PM");
+
+        frontend.show_seconds = false;
+
+        assert_eq!(format_datetime(&time(), &frontend), "09/03/2024 02:05 PM");
+    }
+
+    #[test]
+    fn test_custom_datetime_format_overrides() {
+        let frontend = FrontendConfig {
+            datetime_format: Some("%H:%M".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(format_datetime(&time(), &frontend), "14:05");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(TimeDelta::zero()), "0s");
+        assert_eq!(format_duration(TimeDelta::seconds(59)), "59s");
+        assert_eq!(
+            format_duration(TimeDelta::seconds(3 * 3600 + 25 * 60 + 4)),
+            "3h 25m"
+        );
+        assert_eq!(format_duration(TimeDelta::days(400)), "1y 1mo");
+        assert_eq!(format_duration(TimeDelta::seconds(-5)), "0s");
+    }
+
+    #[test]
+    fn test_format_relative() {
+        let now = time();
+
+        assert_eq!(format_relative(&now, &now), "just now");
+        assert_eq!(
+            format_relative(&(now - TimeDelta::seconds(150)), &now),
+            "2m ago"
+        );
+        assert_eq!(format_relative(&(now - TimeDelta::days(3)), &now), "3d ago");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let seconds = |s: &str| parse_duration(s).unwrap().as_secs();
+
+        assert_eq!(seconds("90"), 90);
+        assert_eq!(seconds("0"), 0);
+        assert_eq!(seconds("10m"), 600);
+        assert_eq!(seconds("1h30m"), 5400);
+        assert_eq!(seconds("1d"), 86_400);
+        assert_eq!(seconds("2w"), 1_209_600);
+        assert_eq!(seconds(" 45s "), 45);
+        // Units can repeat and come in any order
+        assert_eq!(seconds("30m1h"), 5400);
+        assert_eq!(seconds("1m1m"), 120);
+    }
+
+    #[test]
+    fn test_parse_duration_errors() {
+        let error = |s: &str| parse_duration(s).unwrap_err().to_string();
+
+        assert_eq!(
+            error(""),
+            "Duration is empty, use a number of seconds or a duration such as 1h30m."
+        );
+        assert_eq!(
+            error("10x"),
+            "Duration `10x` has an unknown unit `x`, use s, m, h, d, or w."
+        );
+        assert_eq!(error("m"), "Duration `m` is missing a number before `m`.");
+        assert_eq!(error("1h30"), "Duration `1h30` is missing a unit after 30.");
+        assert_eq!(
+            error("-5m"),
+            "Duration `-5m` has an unknown unit `-`, use s, m, h, d, or w."
+        );
+        assert_eq!(
+            error("99999999999999999999w"),
+            "Duration `99999999999999999999w` is too long."
+        );
+        assert!(parse_duration("1h 30m").is_err());
+    }
+}
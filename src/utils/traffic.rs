@@ -0,0 +1,180 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use color_eyre::{Result, eyre::bail};
+use reqwest::{RequestBuilder, Response, ResponseBuilderExt, Url};
+
+/// The hosts that can still be reached in strict privacy mode, being Twitch's own API and authentication.
+const ESSENTIAL_HOSTS: [&str; 2] = ["api.twitch.tv", "id.twitch.tv"];
+
+/// If requests to anything other than [`ESSENTIAL_HOSTS`] are refused, and traffic is not recorded.
+static STRICT_PRIVACY: AtomicBool = AtomicBool::new(false);
+
+/// Total amount of bytes downloaded for emotes, badges, and other images.
+static DOWNLOADED_BYTES: AtomicU64 = AtomicU64::new(0);
+/// Request count, total bytes, and total time spent per API endpoint.
+static ENDPOINTS: Mutex<BTreeMap<&'static str, EndpointStats>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EndpointStats {
+    pub requests: u64,
+    pub bytes: u64,
+    pub elapsed: Duration,
+}
+
+pub fn set_strict_privacy(enabled: bool) {
+    STRICT_PRIVACY.store(enabled, Ordering::Relaxed);
+}
+
+pub fn strict_privacy() -> bool {
+    STRICT_PRIVACY.load(Ordering::Relaxed)
+}
+
+fn is_essential(url: &Url) -> bool {
+    url.host_str()
+        .is_some_and(|host| ESSENTIAL_HOSTS.contains(&host))
+}
+
+pub fn record_download(bytes: usize) {
+    if strict_privacy() {
+        return;
+    }
+
+    DOWNLOADED_BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+fn record_request(endpoint: &'static str, bytes: u64, elapsed: Duration) {
+    if strict_privacy() {
+        return;
+    }
+
+    if let Ok(mut endpoints) = ENDPOINTS.lock() {
+        let stats = endpoints.entry(endpoint).or_default();
+
+        stats.requests += 1;
+        stats.bytes += bytes;
+        stats.elapsed += elapsed;
+    }
+}
+
+/// Send a request, keeping track of how many requests were made to the endpoint,
+/// and how long they took.
+///
+/// In strict privacy mode, requests to anything but Twitch itself are refused here,
+/// so that every feature respects the mode without checking for it.
+pub async fn send_tracked(endpoint: &'static str, request: RequestBuilder) -> Result<Response> {
+    if strict_privacy() {
+        let essential = request
+            .try_clone()
+            .and_then(|r| r.build().ok())
+            .is_some_and(|r| is_essential(r.url()));
+
+        if !essential {
+            bail!("Request to {endpoint} was blocked by strict privacy mode.");
+        }
+    }
+
+    let start = Instant::now();
+
+    let response = read_body(request).await;
+
+    let bytes = response.as_ref().map_or(0, |(_, bytes)| *bytes);
+
+    record_request(endpoint, bytes, start.elapsed());
+
+    Ok(response?.0)
+}
+
+/// Send a request and read its whole body, counting the bytes actually received,
+/// as chunked and compressed responses do not say their length up front.
+async fn read_body(request: RequestBuilder) -> Result<(Response, u64)> {
+    let response = request.send().await?;
+
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version())
+        .url(response.url().clone());
+
+    if let Some(headers) = builder.headers_mut() {
+        headers.clone_from(response.headers());
+    }
+
+    let body = response.bytes().await?;
+    let bytes = body.len() as u64;
+
+    Ok((builder.body(body)?.into(), bytes))
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+
+    while value >= 1024. && unit < UNITS.len() - 1 {
+        value /= 1024.;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Rows of network usage, to be shown in the debug window.
+pub fn traffic_stats() -> Vec<(String, String)> {
+    let mut stats = vec![(
+        "Image downloads".to_string(),
+        format_bytes(DOWNLOADED_BYTES.load(Ordering::Relaxed)),
+    )];
+
+    if let Ok(endpoints) = ENDPOINTS.lock() {
+        stats.extend(endpoints.iter().map(|(endpoint, s)| {
+            (
+                (*endpoint).to_string(),
+                format!(
+                    "{}x, {}, {}ms",
+                    s.requests,
+                    format_bytes(s.bytes),
+                    s.elapsed.as_millis()
+                ),
+            )
+        }));
+    }
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn test_is_essential() {
+        let url = |s: &str| Url::parse(s).unwrap();
+
+        assert!(is_essential(&url("https://api.twitch.tv/helix/users")));
+        assert!(is_essential(&url("https://id.twitch.tv/oauth2/validate")));
+        assert!(!is_essential(&url(
+            "https://static-cdn.jtvnw.net/emoticons/v2/25/static/light/1.0"
+        )));
+        assert!(!is_essential(&url("https://7tv.io/v3/emote-sets/global")));
+    }
+}
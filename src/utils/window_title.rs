@@ -0,0 +1,85 @@
+use std::{
+    env,
+    io::{Write, stdout},
+};
+
+use crossterm::{execute, terminal::SetTitle};
+use log::warn;
+
+/// What the window title is made from.
+pub struct TitleState<'a> {
+    pub channel: &'a str,
+    /// Mentions of the user that have not been looked at yet.
+    pub unread_mentions: usize,
+    /// If the channel is live, which is unknown until it has been checked.
+    pub live: Option<bool>,
+}
+
+/// Fill in a window title format, such as `twt: {channel}{mentions}{live}`.
+///
+/// `{mentions}` becomes ` (2)` with unread mentions and nothing without,
+/// and `{live}` becomes ` [live]` while the channel is live.
+pub fn format_window_title(format: &str, state: &TitleState) -> String {
+    let unread = if state.unread_mentions > 0 {
+        format!(" ({})", state.unread_mentions)
+    } else {
+        String::new()
+    };
+
+    let streaming = if state.live == Some(true) {
+        " [live]"
+    } else {
+        ""
+    };
+
+    format
+        .replace("{channel}", state.channel)
+        .replace("{mentions}", &unread)
+        .replace("{live}", streaming)
+}
+
+/// Set the title of the terminal window, and the name of the tmux window if running inside of tmux.
+pub fn set_window_title(title: &str, tmux: bool) {
+    // Titles cannot contain control characters, as those would end the escape sequence early
+    let title = title.replace(char::is_control, "");
+
+    if let Err(err) = execute!(stdout(), SetTitle(&title)) {
+        warn!("Unable to set the window title: {err}");
+    }
+
+    if tmux && env::var_os("TMUX").is_some() {
+        let mut stdout = stdout();
+
+        if let Err(err) = write!(stdout, "\x1bk{title}\x1b\\").and_then(|()| stdout.flush()) {
+            warn!("Unable to set the tmux window name: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_window_title() {
+        let mut state = TitleState {
+            channel: "xithrius",
+            unread_mentions: 0,
+            live: None,
+        };
+
+        assert_eq!(
+            format_window_title("twt: {channel}{mentions}{live}", &state),
+            "twt: xithrius"
+        );
+
+        state.unread_mentions = 2;
+        state.live = Some(true);
+
+        assert_eq!(
+            format_window_title("twt: {channel}{mentions}{live}", &state),
+            "twt: xithrius (2) [live]"
+        );
+        assert_eq!(format_window_title("{channel}", &state), "xithrius");
+    }
+}